@@ -0,0 +1,95 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rss_fuse::feed::{Article, ParsedArticle};
+use rss_fuse::fuse::InodeManager;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+const FEED: &str = "bench-feed";
+const READERS: usize = 4;
+
+fn create_articles(count: usize) -> Vec<(usize, Arc<Article>)> {
+    (0..count)
+        .map(|i| {
+            let parsed = ParsedArticle {
+                title: format!("Article {}", i),
+                link: format!("https://example.com/articles/{}", i),
+                description: Some(format!("Description of article {}", i)),
+                content: None,
+                author: None,
+                published: None,
+                guid: Some(format!("article-{}", i)),
+                categories: Vec::new(),
+                enclosures: Vec::new(),
+            };
+            (i + 1, Arc::new(Article::new(parsed, FEED)))
+        })
+        .collect()
+}
+
+/// Rebuild the feed's directory the way a refresh does
+fn refresh(manager: &InodeManager, articles: &[(usize, Arc<Article>)]) {
+    manager.stage_feed_directory(FEED).unwrap();
+    manager.create_feed_listing(FEED, articles.to_vec());
+    manager.commit_feed_directory(FEED).unwrap();
+    manager.release_retired(&HashSet::new());
+}
+
+/// List the feed directory and look up every entry by name
+fn read_listing(manager: &InodeManager) -> usize {
+    let Some(feed_dir) = manager.get_feed_directory(FEED) else {
+        return 0;
+    };
+    manager.list_children(feed_dir.ino)
+        .iter()
+        .filter(|child| manager.get_node_by_name(feed_dir.ino, &child.name).is_some())
+        .count()
+}
+
+fn bench_lookups_during_refresh(c: &mut Criterion) {
+    let mut group = c.benchmark_group("inode_lookups");
+    group.sample_size(10);
+
+    for count in [1000, 5000] {
+        let articles = create_articles(count);
+        let manager = Arc::new(InodeManager::new());
+        manager.create_feed_directory(FEED).unwrap();
+        refresh(&manager, &articles);
+
+        group.bench_with_input(BenchmarkId::new("idle", count), &manager, |b, manager| {
+            b.iter(|| black_box(read_listing(manager)));
+        });
+
+        // Refreshes run back to back while the readers list and look up
+        let refreshing = Arc::new(AtomicBool::new(true));
+        let refresher = {
+            let manager = Arc::clone(&manager);
+            let refreshing = Arc::clone(&refreshing);
+            let articles = articles.clone();
+            thread::spawn(move || {
+                while refreshing.load(Ordering::Relaxed) {
+                    refresh(&manager, &articles);
+                }
+            })
+        };
+        group.bench_with_input(BenchmarkId::new("refreshing", count), &manager, |b, manager| {
+            b.iter(|| {
+                let readers: Vec<_> = (0..READERS)
+                    .map(|_| {
+                        let manager = Arc::clone(manager);
+                        thread::spawn(move || read_listing(&manager))
+                    })
+                    .collect();
+                black_box(readers.into_iter().map(|reader| reader.join().unwrap()).sum::<usize>())
+            });
+        });
+        refreshing.store(false, Ordering::Relaxed);
+        refresher.join().unwrap();
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_lookups_during_refresh);
+criterion_main!(benches);
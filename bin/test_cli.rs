@@ -90,8 +90,9 @@ async fn test_add_feed_command() -> Result<()> {
     // Test adding a real RSS feed (using a mock URL for testing)
     let cli = Cli {
         command: Commands::AddFeed {
-            name: "test-feed".to_string(),
-            url: "https://hnrss.org/frontpage".to_string(), // Real RSS feed for testing
+            name: Some("test-feed".to_string()),
+            url: Some("https://hnrss.org/frontpage".to_string()), // Real RSS feed for testing
+            stdin: false,
         },
         config: None,
         verbose: false,
@@ -111,8 +112,9 @@ async fn test_add_feed_command() -> Result<()> {
     // Test adding with invalid URL
     let cli_invalid = Cli {
         command: Commands::AddFeed {
-            name: "invalid-feed".to_string(),
-            url: "not-a-valid-url".to_string(),
+            name: Some("invalid-feed".to_string()),
+            url: Some("not-a-valid-url".to_string()),
+            stdin: false,
         },
         config: None,
         verbose: false,
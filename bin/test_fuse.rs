@@ -17,6 +17,7 @@ fn create_test_feed(name: &str, article_count: usize) -> Feed {
             published: Some(Utc::now()),
             guid: Some(format!("{}-article-{}", name, i + 1)),
             categories: vec![name.to_string(), "test".to_string()],
+            enclosures: Vec::new(),
         };
         
         let article = Article::new(parsed_article, name);
@@ -93,7 +94,7 @@ fn test_filesystem_operations() -> Result<()> {
                     let preview = if content.len() > 200 {
                         format!("{}...", &content[..200])
                     } else {
-                        content
+                        content.to_string()
                     };
                     println!("   {}", preview.replace('\n', "\n   "));
                     println!();
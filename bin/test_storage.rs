@@ -165,6 +165,7 @@ fn create_test_article(id: &str, feed_name: &str) -> std::sync::Arc<Article> {
         published: Some(Utc::now()),
         guid: Some(id.to_string()),
         categories: vec!["test".to_string(), "storage".to_string()],
+        enclosures: Vec::new(),
     };
     std::sync::Arc::new(Article::new(parsed, feed_name))
 }
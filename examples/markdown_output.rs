@@ -38,6 +38,7 @@ async fn example() -> impl Future&lt;Output = ()&gt; {
         published: Some(Utc::now()),
         guid: Some("rust-1-75-0".to_string()),
         categories: vec!["rust".to_string(), "programming".to_string(), "release".to_string()],
+        enclosures: Vec::new(),
     };
 
     // Convert to Article
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rss_fuse::feed::parser::FeedParser;
+
+// Feed bodies come from arbitrary servers: parsing may fail, but must not
+// panic, whichever parser the body or its content type selects
+fuzz_target!(|data: &[u8]| {
+    let parser = FeedParser::new();
+    let _ = parser.parse_feed(data);
+    let _ = parser.parse_feed_as(data, Some("application/feed+json"));
+});
@@ -1,17 +1,35 @@
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use clap_complete::{generate, Shell};
 use clap::CommandFactory;
 use tracing::{info, warn, error, debug};
 use tokio;
 
-use crate::cli::Cli;
-use crate::config::Config;
-use crate::storage::{Repository, RepositoryFactory, FeedRepository, ArticleRepository};
+use crate::cli::{ArticleSort, CacheCommand, Cli, ConfigCommand, ExportArgs, ExportFormat, SearchArgs, StateCommand};
+use crate::cli::control::{self, RefreshResult, Request, Response};
+use crate::cli::doctor;
+use crate::cli::reload::load_order;
+use crate::config::{Config, TextHtmlPolicy};
+use crate::content::{summarizer, ContentExtractor};
+use crate::config_check::{self, Level};
+use crate::storage::{
+    RepositoryFactory, FeedRepository, ArticleRepository, ArticleQuery,
+    PersistentCache, PersistentCacheConfig, ReadStateStore, StarredStore, CacheConfig, CleanupStats, StateFile,
+};
 use crate::fuse::FuseOperations;
-use crate::feed::{Feed, FeedStatus};
+use crate::fuse::filesystem::ListedArticle;
+use crate::fuse::naming;
+use crate::feed::{Article, Feed, FeedResult, FeedStatus};
+use crate::feed::discovery::{discover_feeds_at, DiscoveredFeed};
+use crate::feed::fetcher::FeedFetcher;
+use crate::feed::rate_limit::RateLimiter;
+use crate::feed::parser::FeedParser;
 use crate::error::{Error, Result};
+use crate::limits::{self, ResourceUsage};
+use crate::lock::{self, InstanceLock};
+use crate::profile;
+use crate::sync::{self, LocalArticle, SyncReport};
 
 /// Initialize RSS-FUSE configuration and directory structure
 pub async fn init(mount_point: PathBuf) -> Result<()> {
@@ -60,19 +78,22 @@ pub async fn init(mount_point: PathBuf) -> Result<()> {
     }
     
     println!("✅ RSS-FUSE initialized successfully!");
+    if let Some(name) = profile::current() {
+        println!("   Profile: {}", name);
+    }
     println!("   Mount point: {}", mount_point.display());
     println!("   Config file: {}", config_file.display());
     println!("   Cache directory: {}", cache_dir.display());
     println!("");
     println!("Next steps:");
-    println!("   1. Add RSS feeds: rss-fuse add-feed <name> <url>");
-    println!("   2. Mount filesystem: rss-fuse mount {}", mount_point.display());
+    println!("   1. Add RSS feeds: rss-fuse{} add-feed <name> <url>", profile::cli_flag());
+    println!("   2. Mount filesystem: rss-fuse{} mount {}", profile::cli_flag(), mount_point.display());
     
     Ok(())
 }
 
 /// Add a new RSS feed to the configuration
-pub async fn add_feed(name: String, url: String, config_path: Option<PathBuf>) -> Result<()> {
+pub async fn add_feed(name: String, url: String, auto: bool, config_path: Option<PathBuf>) -> Result<()> {
     info!("Adding feed: {} -> {}", name, url);
     
     // Validate URL format
@@ -88,42 +109,257 @@ pub async fn add_feed(name: String, url: String, config_path: Option<PathBuf>) -
         return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
     };
     
-    // Check if feed already exists
-    if config.feeds.contains_key(&name) {
-        return Err(Error::AlreadyExists(format!("Feed '{}' already exists", name)));
-    }
+    // Check the name is free and fits the feed group hierarchy
+    config.check_new_feed_name(&name)?;
+    
+    // Enforce hard resource limits before touching the network
+    let usage = ResourceUsage::measure(&config);
+    limits::ensure_can_add_feeds(&config.limits.hard, &usage, 1)?;
     
     // Create repository for validation
-    let repo = RepositoryFactory::memory();
+    let network = config.network.options()?;
+    let rate_limiter = RateLimiter::new(config.network.rate_limits());
+    let repo = RepositoryFactory::memory()
+        .with_rate_limiter(rate_limiter.clone())
+        .with_network(network.clone())?;
     
     // Test feed URL by fetching it
     println!("📡 Testing feed URL...");
-    match repo.refresh_feed(&name, &url).await {
-        Ok(feed) => {
-            println!("✅ Feed validated successfully!");
-            println!("   Title: {}", feed.title.as_deref().unwrap_or("Unknown"));
-            println!("   Description: {}", feed.description.as_deref().unwrap_or("No description"));
-            println!("   Articles: {}", feed.articles.len());
-            
-            // Add to configuration
-            config.feeds.insert(name.clone(), url.clone());
-            
-            // Save configuration
-            let config_content = toml::to_string_pretty(&config)
-                .map_err(|e| Error::Config(format!("Failed to serialize config: {}", e)))?;
-            fs::write(&config_file, config_content)
-                .map_err(|e| Error::Io(e))?;
-            
-            println!("✅ Feed '{}' added successfully!", name);
-            
-            // Store the feed in repository for immediate availability
-            repo.save_feed(feed).await?;
-        },
+    let (url, feed) = match repo.refresh_feed(&name, &url).await {
+        Ok((feed, _)) => (url, feed),
         Err(e) => {
-            return Err(Error::FeedParse(format!("Failed to validate feed URL: {}", e)));
+            // Maybe a website that advertises its feeds
+            let discovered = match discover_feeds_at(&FeedFetcher::new().with_rate_limiter(rate_limiter).with_network(network)?, &url).await {
+                Ok(discovered) => discovered,
+                Err(_) => return Err(Error::FeedParse(format!("Failed to validate feed URL: {}", e))),
+            };
+            let chosen = choose_discovered_feed(&discovered, auto)?.url.clone();
+            
+            println!("📡 Testing feed URL {}...", chosen);
+            let (feed, _) = repo.refresh_feed(&name, &chosen).await
+                .map_err(|e| Error::FeedParse(format!("Failed to validate feed URL: {}", e)))?;
+            (chosen, feed)
+        }
+    };
+    
+    println!("✅ Feed validated successfully!");
+    println!("   Title: {}", feed.title.as_deref().unwrap_or("Unknown"));
+    println!("   Description: {}", feed.description.as_deref().unwrap_or("No description"));
+    println!("   Articles: {}", feed.articles.len());
+    
+    // Add to configuration
+    config.feeds.insert(name.clone(), url.into());
+    
+    // Save configuration
+    let config_content = toml::to_string_pretty(&config)
+        .map_err(|e| Error::Config(format!("Failed to serialize config: {}", e)))?;
+    fs::write(&config_file, config_content)
+        .map_err(|e| Error::Io(e))?;
+    
+    println!("✅ Feed '{}' added successfully!", name);
+    
+    let usage = ResourceUsage { feeds: usage.feeds + 1, ..usage };
+    limits::print_soft_limit_warnings(&config, &usage);
+    notify_mount().await;
+    
+    // Store the feed in repository for immediate availability
+    repo.save_feed(feed).await?;
+    
+    Ok(())
+}
+
+/// Pick one of the feeds a page advertises: the only or first one with
+/// `auto`, otherwise ask on stdin
+fn choose_discovered_feed(feeds: &[DiscoveredFeed], auto: bool) -> Result<&DiscoveredFeed> {
+    println!("🔎 Not a feed, but the page advertises {} feed(s):", feeds.len());
+    for (i, feed) in feeds.iter().enumerate() {
+        match &feed.title {
+            Some(title) => println!("   {}. {} ({})", i + 1, title, feed.url),
+            None => println!("   {}. {}", i + 1, feed.url),
         }
     }
     
+    if auto || feeds.len() == 1 {
+        return Ok(&feeds[0]);
+    }
+    
+    print!("Choose a feed [1-{}]: ", feeds.len());
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    parse_feed_choice(&input, feeds.len())
+        .map(|index| &feeds[index])
+        .ok_or_else(|| Error::Invalid("No feed selected; pass a feed URL or use --auto".to_string()))
+}
+
+/// Parse a 1-based menu choice into an index
+fn parse_feed_choice(input: &str, count: usize) -> Option<usize> {
+    match input.trim().parse::<usize>() {
+        Ok(choice) if (1..=count).contains(&choice) => Some(choice - 1),
+        _ => None,
+    }
+}
+
+/// A feed entry parsed from `add-feed --stdin` input
+#[derive(Debug, Clone, PartialEq)]
+struct BatchFeedEntry {
+    name: Option<String>,
+    url: String,
+}
+
+/// Parse one line of batch input: `name url`, a bare `url`, or a blank/`#` comment line
+fn parse_batch_line(line: &str) -> Option<std::result::Result<BatchFeedEntry, String>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let entry = match parts.as_slice() {
+        [url] => BatchFeedEntry { name: None, url: url.to_string() },
+        [name, url] => BatchFeedEntry { name: Some(name.to_string()), url: url.to_string() },
+        _ => return Some(Err(format!("expected `name url` or `url`, got: {}", line))),
+    };
+    
+    if !entry.url.starts_with("http://") && !entry.url.starts_with("https://") {
+        return Some(Err(format!("URL must start with http:// or https://: {}", entry.url)));
+    }
+    
+    Some(Ok(entry))
+}
+
+/// Derive a feed name from a URL's host, e.g. `https://blog.rust-lang.org/feed.xml` → `blog-rust-lang-org`
+fn feed_name_from_url(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").replace('.', "-")))
+        .unwrap_or_else(|| "feed".to_string())
+}
+
+/// Add many feeds at once from stdin, validating concurrently and saving the config once
+pub async fn add_feeds_from_stdin(config_path: Option<PathBuf>) -> Result<()> {
+    use futures::stream::{self, StreamExt};
+    use std::io::BufRead;
+    
+    let config_file = get_config_file(config_path)?;
+    let mut config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+    
+    // Parse input and assign unique names up front
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut failures = 0usize;
+    let existing_urls: std::collections::HashSet<String> = config.feeds.values().map(|entry| entry.url().to_string()).collect();
+    for (line_no, line) in io::stdin().lock().lines().enumerate() {
+        let line = line.map_err(Error::Io)?;
+        let entry = match parse_batch_line(&line) {
+            None => continue,
+            Some(Ok(entry)) => entry,
+            Some(Err(e)) => {
+                println!("❌ line {}: {}", line_no + 1, e);
+                failures += 1;
+                continue;
+            }
+        };
+        
+        if existing_urls.contains(&entry.url) || entries.iter().any(|(_, u)| u == &entry.url) {
+            println!("⏭️  Skipping already subscribed URL: {}", entry.url);
+            continue;
+        }
+        
+        let base = entry.name.clone().unwrap_or_else(|| feed_name_from_url(&entry.url));
+        if entry.name.is_some() {
+            let check = if entries.iter().any(|(existing, _)| config.feed_dir_path(existing) == config.feed_dir_path(&base)) {
+                Err(Error::AlreadyExists(format!("Feed '{}' already exists", base)))
+            } else {
+                config.check_new_feed_name(&base)
+            };
+            if let Err(e) = check {
+                println!("❌ line {}: {}", line_no + 1, e);
+                failures += 1;
+                continue;
+            }
+        }
+        // Names taken from URLs are made unique, including their directories
+        let mut name = base.clone();
+        let mut n = 2;
+        let dir_taken = |name: &str| {
+            matches!(config.check_new_feed_name(name), Err(Error::AlreadyExists(_)))
+                || entries.iter().any(|(existing, _)| config.feed_dir_path(existing) == config.feed_dir_path(name))
+        };
+        while dir_taken(&name) {
+            name = format!("{}-{}", base, n);
+            n += 1;
+        }
+        entries.push((name, entry.url));
+    }
+    
+    let usage = ResourceUsage::measure(&config);
+    if !entries.is_empty() {
+        limits::ensure_can_add_feeds(&config.limits.hard, &usage, entries.len())?;
+    }
+    
+    if entries.is_empty() {
+        println!("No new feeds to add.");
+        return if failures > 0 {
+            Err(Error::Invalid(format!("{} invalid input line(s)", failures)))
+        } else {
+            Ok(())
+        };
+    }
+    
+    println!("📡 Validating {} feeds...", entries.len());
+    let repo = RepositoryFactory::memory()
+        .with_rate_limiter(RateLimiter::new(config.network.rate_limits()))
+        .with_network(config.network.options()?)?;
+    let concurrency = config.settings.concurrent_fetches.max(1);
+    let results: Vec<_> = stream::iter(entries)
+        .map(|(name, url)| {
+            let repo = &repo;
+            async move {
+                let result = repo.refresh_feed(&name, &url).await;
+                (name, url, result)
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    
+    let mut added = 0usize;
+    for (name, url, result) in results {
+        match result {
+            Ok((feed, _)) => {
+                println!("✅ {} ({} articles)", name, feed.articles.len());
+                config.feeds.insert(name, url.into());
+                added += 1;
+            }
+            Err(e) => {
+                println!("❌ {}: {}", url, e);
+                failures += 1;
+            }
+        }
+    }
+    
+    if added > 0 {
+        let config_content = toml::to_string_pretty(&config)
+            .map_err(|e| Error::Config(format!("Failed to serialize config: {}", e)))?;
+        fs::write(&config_file, config_content)
+            .map_err(Error::Io)?;
+    }
+    
+    println!();
+    println!("📊 Added {} feed(s), {} failed", added, failures);
+    limits::print_soft_limit_warnings(&config, &ResourceUsage { feeds: usage.feeds + added, ..usage });
+    if added > 0 {
+        notify_mount().await;
+    }
+    
+    if added == 0 && failures > 0 {
+        return Err(Error::FeedParse("No feeds could be added".to_string()));
+    }
+    
     Ok(())
 }
 
@@ -145,7 +381,8 @@ pub async fn remove_feed(name: String, config_path: Option<PathBuf>) -> Result<(
     }
     
     // Remove from configuration
-    let url = config.feeds.remove(&name).unwrap();
+    let entry = config.feeds.remove(&name).unwrap();
+    config.retarget_virtual_feeds(&name, None);
     
     // Save configuration
     let config_content = toml::to_string_pretty(&config)
@@ -153,21 +390,901 @@ pub async fn remove_feed(name: String, config_path: Option<PathBuf>) -> Result<(
     fs::write(&config_file, config_content)
         .map_err(|e| Error::Io(e))?;
     
-    // Also remove from repository if it exists
-    let repo = RepositoryFactory::memory();
-    let _ = repo.delete_feed(&name).await; // Ignore errors since it might not be in storage
+    // Also remove from repository if it exists
+    let repo = RepositoryFactory::memory();
+    let _ = repo.delete_feed(&name).await; // Ignore errors since it might not be in storage
+    
+    println!("✅ Feed '{}' removed successfully!", name);
+    println!("   Removed URL: {}", entry.url());
+    notify_mount().await;
+    
+    Ok(())
+}
+
+/// Have a running mount apply a config change now instead of at its next
+/// poll of the config file
+async fn notify_mount() {
+    match control::send(&Request::Reload).await {
+        Ok(Some(_)) => println!("   Applied to the running mount"),
+        Ok(None) => {}
+        Err(e) => println!("⚠️  The running mount did not apply the change: {}", e),
+    }
+}
+
+/// Rename a feed in the configuration and move its cached articles, read
+/// and starred state to the new name. Refused while a mount is using the
+/// cache, as its next save would bring the old name back.
+pub async fn rename_feed(old: String, new: String, config_path: Option<PathBuf>) -> Result<()> {
+    info!("Renaming feed {} to {}", old, new);
+    
+    let config_file = get_config_file(config_path)?;
+    let mut config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+    
+    let entry = config.feeds.remove(&old)
+        .ok_or_else(|| Error::NotFound(format!("Feed '{}' not found", old)))?;
+    config.check_new_feed_name(&new)?;
+    let id_strategy = entry.id_strategy();
+    config.feeds.insert(new.clone(), entry);
+    config.retarget_virtual_feeds(&old, Some(&new));
+    config.validate()?;
+    
+    let cache_config = PersistentCacheConfig {
+        compression: config.cache.compression()?,
+        ..PersistentCacheConfig::default()
+    };
+    let _lock = lock_cache(&cache_config.cache_dir, "rename-feed")?;
+    config.save(&config_file)?;
+    println!("✅ Feed '{}' renamed to '{}'", old, new);
+    
+    // Article IDs derived from the feed name change with it
+    let renamed_ids = PersistentCache::new(cache_config.clone())?.rename_feed(&old, &new, id_strategy)?;
+    ReadStateStore::open(cache_config.cache_dir.join("read_state.json"))?.rename_ids(&renamed_ids)?;
+    StarredStore::open(cache_config.cache_dir.join("starred.json"))?.rename_ids(&renamed_ids)?;
+    
+    Ok(())
+}
+
+/// Point a feed at a new URL, keeping its name, cached articles and read state
+pub async fn set_feed_url(name: String, url: String, config_path: Option<PathBuf>) -> Result<()> {
+    info!("Setting URL of feed {} to {}", name, url);
+    
+    FeedParser::new().validate_feed_url(&url)?;
+    
+    let config_file = get_config_file(config_path)?;
+    let mut config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+    
+    let entry = config.feeds.get_mut(&name)
+        .ok_or_else(|| Error::NotFound(format!("Feed '{}' not found", name)))?;
+    let old_url = entry.url().to_string();
+    entry.set_url(url.clone());
+    config.save(&config_file)?;
+    
+    // A running mount owns the cache and moves the feed itself
+    let request = Request::SetFeedUrl { feed: name.clone(), url: url.clone() };
+    let applied = matches!(control::send(&request).await?, Some(Response::FeedUrlSet));
+    if !applied {
+        let cache_config = PersistentCacheConfig {
+            compression: config.cache.compression()?,
+            ..PersistentCacheConfig::default()
+        };
+        let _lock = lock_cache(&cache_config.cache_dir, "set-feed-url")?;
+        PersistentCache::new(cache_config)?.set_feed_url(&name, &url)?;
+    }
+    
+    println!("✅ Feed '{}' now fetches from {}", name, url);
+    println!("   Previous URL: {}", old_url);
+    if applied {
+        println!("   Applied to the running mount");
+    }
+    
+    Ok(())
+}
+
+/// Stop or resume fetching a feed. A disabled feed stays in the
+/// configuration and its cached articles stay in the mount.
+pub async fn set_feed_enabled(name: String, enabled: bool, config_path: Option<PathBuf>) -> Result<()> {
+    info!("{} feed {}", if enabled { "Enabling" } else { "Disabling" }, name);
+    
+    let config_file = get_config_file(config_path)?;
+    let mut config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+    
+    let entry = config.feeds.get_mut(&name)
+        .ok_or_else(|| Error::NotFound(format!("Feed '{}' not found", name)))?;
+    if entry.enabled() == enabled {
+        println!("Feed '{}' is already {}", name, if enabled { "enabled" } else { "disabled" });
+        return Ok(());
+    }
+    entry.set_enabled(enabled);
+    config.save(&config_file)?;
+    
+    if enabled {
+        println!("✅ Feed '{}' enabled; it is fetched again", name);
+    } else {
+        println!("⏸️  Feed '{}' disabled; its cached articles stay available", name);
+        println!("   Resume with: rss-fuse enable-feed {}", name);
+    }
+    notify_mount().await;
+    
+    Ok(())
+}
+
+/// List all configured RSS feeds
+pub async fn list_feeds(config_path: Option<PathBuf>) -> Result<()> {
+    info!("Listing feeds");
+    
+    // Load configuration
+    let config_file = get_config_file(config_path)?;
+    let config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+    
+    if config.feeds.is_empty() {
+        println!("📋 No feeds configured yet.");
+        println!("   Add feeds with: rss-fuse add-feed <name> <url>");
+        return Ok(());
+    }
+    
+    println!("📋 Configured RSS Feeds:");
+    println!("========================");
+    
+    // Create repository to get additional information
+    let repo = RepositoryFactory::memory();
+    
+    for (name, entry) in &config.feeds {
+        println!("\n📰 {}", name);
+        println!("   URL: {}", entry.url());
+        if !entry.enabled() {
+            println!("   Disabled (rss-fuse enable-feed {} to resume)", name);
+        }
+        
+        // Try to get cached feed information
+        match repo.get_feed(name).await {
+            Ok(Some(feed)) => {
+                println!("   Title: {}", feed.title.as_deref().unwrap_or("Unknown"));
+                println!("   Articles: {}", feed.articles.len());
+                println!("   Status: {:?}", feed.status);
+                if let Some(updated) = feed.last_updated {
+                    println!("   Last Updated: {}", updated.format("%Y-%m-%d %H:%M:%S UTC"));
+                }
+            },
+            Ok(None) => {
+                println!("   Status: Not cached (run refresh to update)");
+            },
+            Err(_) => {
+                println!("   Status: Error accessing feed data");
+            }
+        }
+    }
+    
+    println!("\n💡 Use 'rss-fuse refresh' to update all feeds");
+    
+    Ok(())
+}
+
+/// Print a feed's articles one per line, with their paths in the running
+/// mount; from the cache, without paths, when nothing is mounted
+pub async fn list_articles(feed_name: String, unread: bool, limit: Option<usize>, sort: ArticleSort,
+                           config_path: Option<PathBuf>) -> Result<()> {
+    let config_file = get_config_file(config_path)?;
+    let config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+
+    if !config.feeds.contains_key(&feed_name) {
+        return Err(Error::NotFound(format!("Feed '{}' not found", feed_name)));
+    }
+
+    let articles = match control::send(&Request::Articles { feed: feed_name.clone() }).await {
+        Ok(Some(Response::Articles { articles })) => articles,
+        _ => {
+            let cache_config = PersistentCacheConfig::default();
+            let read = ReadStateStore::open(cache_config.cache_dir.join("read_state.json"))?;
+            PersistentCache::new(cache_config)?.load()?
+                .and_then(|mut data| data.feeds.remove(&feed_name))
+                .map(|entry| entry.data.articles)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|article| ListedArticle {
+                    read: article.read || read.is_read(&article.id),
+                    id: article.id,
+                    title: article.title,
+                    published: article.published,
+                    path: None,
+                })
+                .collect()
+        }
+    };
+
+    let mut out = io::stdout().lock();
+    for article in order_listing(articles, unread, sort, limit) {
+        // A closed pipe (e.g. `| head`) ends the listing quietly
+        if writeln!(out, "{}", listing_line(&article)).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// `articles` filtered to unread ones if asked, sorted and cut to `limit`
+fn order_listing(mut articles: Vec<ListedArticle>, unread: bool, sort: ArticleSort, limit: Option<usize>) -> Vec<ListedArticle> {
+    if unread {
+        articles.retain(|article| !article.read);
+    }
+    match sort {
+        ArticleSort::Date => articles.sort_by_key(|article| std::cmp::Reverse(article.published)),
+        ArticleSort::Title => articles.sort_by_key(|article| article.title.to_lowercase()),
+    }
+    articles.truncate(limit.unwrap_or(usize::MAX));
+    articles
+}
+
+/// Tab-separated ID, date, title and path; a missing date or path is `-`
+fn listing_line(article: &ListedArticle) -> String {
+    let clean = |text: &str| text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect::<String>();
+    format!(
+        "{}\t{}\t{}\t{}",
+        clean(&article.id),
+        article.published.map(|date| date.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "-".to_string()),
+        clean(&article.title),
+        article.path.as_ref().map(|path| clean(&path.display().to_string())).unwrap_or_else(|| "-".to_string()),
+    )
+}
+
+/// Parse a `--since`/`--until` value. Bare dates mean the start of the day,
+/// or its end when `end_of_day` is set.
+fn parse_date_arg(value: &str, end_of_day: bool) -> Result<chrono::DateTime<chrono::Utc>> {
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&chrono::Utc));
+    }
+    
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| Error::Invalid(format!("Invalid date '{}': expected YYYY-MM-DD or RFC 3339", value)))?;
+    let time = if end_of_day {
+        chrono::NaiveTime::from_hms_opt(23, 59, 59).unwrap()
+    } else {
+        chrono::NaiveTime::MIN
+    };
+    Ok(date.and_time(time).and_utc())
+}
+
+/// Turn CLI search filters into a repository query
+fn build_search_query(args: &SearchArgs) -> Result<ArticleQuery> {
+    let text = args.text.join(" ");
+    Ok(ArticleQuery {
+        feed_name: args.feed.clone(),
+        title_contains: args.title.clone(),
+        text: (!text.trim().is_empty()).then_some(text),
+        author: args.author.clone(),
+        tags: args.tags.clone(),
+        date_from: args.since.as_deref().map(|v| parse_date_arg(v, false)).transpose()?,
+        date_to: args.until.as_deref().map(|v| parse_date_arg(v, true)).transpose()?,
+        ..Default::default()
+    })
+}
+
+/// Search articles in the on-disk cache of configured feeds
+pub async fn search(args: SearchArgs, config_path: Option<PathBuf>) -> Result<()> {
+    info!("Searching articles: {:?}", args);
+    
+    let query = build_search_query(&args)?;
+    
+    // Load configuration
+    let config_file = get_config_file(config_path)?;
+    let config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+    
+    if let Some(ref feed) = args.feed {
+        if !config.feeds.contains_key(feed) {
+            return Err(Error::NotFound(format!("Feed '{}' not found", feed)));
+        }
+    }
+    
+    // Index the cached feeds in a scratch repository
+    let repo = RepositoryFactory::memory();
+    let mut feed_of = std::collections::HashMap::new();
+    if let Some(data) = PersistentCache::new(PersistentCacheConfig::default())?.load()? {
+        for (name, entry) in data.feeds {
+            if !config.feeds.contains_key(&name) {
+                continue;
+            }
+            for article in &entry.data.articles {
+                feed_of.insert(article.id.clone(), name.clone());
+            }
+            repo.save_feed(entry.data).await?;
+        }
+    }
+    
+    if feed_of.is_empty() {
+        println!("📭 No cached articles to search. Mount or run 'rss-fuse refresh' first.");
+        return Ok(());
+    }
+    
+    // Match everything first so the limit keeps the newest articles
+    let query = ArticleQuery { limit: Some(feed_of.len()), ..query };
+    let mut results = repo.search_articles(&query).await?;
+    results.sort_by_key(|article| std::cmp::Reverse(article.published));
+    results.truncate(args.limit);
+    
+    if results.is_empty() {
+        println!("🔍 No matching articles.");
+        return Ok(());
+    }
+    
+    println!("🔍 {} matching article(s):", results.len());
+    for article in &results {
+        println!("\n📰 {}", article.title);
+        println!("   🔗 {}", article.link);
+        let date = article.published
+            .map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| "unknown date".to_string());
+        println!("   📅 {} · {}", date, feed_of.get(&article.id).map(String::as_str).unwrap_or("?"));
+    }
+    
+    Ok(())
+}
+
+/// Articles of `articles` that `selector` names by ID, title or file name;
+/// all of them when there is no selector
+fn select_articles<'a>(articles: &'a [Article], selector: Option<&str>) -> Vec<&'a Article> {
+    match selector {
+        None => articles.iter().collect(),
+        Some(selector) => articles.iter()
+            .filter(|a| {
+                a.id == selector
+                    || a.title == selector
+                    || a.filename() == selector
+                    || a.markdown_filename() == selector
+            })
+            .collect(),
+    }
+}
+
+/// Record articles as read (or unread) so the next mount shows them that way
+pub async fn mark_read(feed_name: String, article: Option<String>, unread: bool, config_path: Option<PathBuf>) -> Result<()> {
+    info!("Marking {} in {} as {}", article.as_deref().unwrap_or("all articles"), feed_name,
+          if unread { "unread" } else { "read" });
+    
+    // Load configuration
+    let config_file = get_config_file(config_path)?;
+    let config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+    
+    if !config.feeds.contains_key(&feed_name) {
+        return Err(Error::NotFound(format!("Feed '{}' not found", feed_name)));
+    }
+    
+    let cache_config = PersistentCacheConfig {
+        compression: config.cache.compression()?,
+        ..PersistentCacheConfig::default()
+    };
+    let articles = PersistentCache::new(cache_config.clone())?.load()?
+        .and_then(|mut data| data.feeds.remove(&feed_name))
+        .map(|entry| entry.data.articles)
+        .unwrap_or_default();
+    if articles.is_empty() {
+        println!("📭 No cached articles for '{}'. Mount or run 'rss-fuse refresh' first.", feed_name);
+        return Ok(());
+    }
+    
+    let selected = select_articles(&articles, article.as_deref());
+    if selected.is_empty() {
+        return Err(Error::NotFound(format!(
+            "No article '{}' in feed '{}'", article.unwrap_or_default(), feed_name
+        )));
+    }
+    
+    let state = if unread { "unread" } else { "read" };
+    let request = Request::MarkRead {
+        feed: feed_name.clone(),
+        articles: selected.iter().map(|a| a.id.clone()).collect(),
+        read: !unread,
+    };
+    match control::send(&request).await? {
+        Some(Response::MarkedRead) => {
+            if selected.len() == 1 {
+                println!("✅ Marked '{}' as {} in the running mount", selected[0].title, state);
+            } else {
+                println!("✅ Marked {} article(s) in '{}' as {} in the running mount", selected.len(), feed_name, state);
+            }
+            return Ok(());
+        },
+        Some(Response::Error { message }) => return Err(Error::InvalidState(message)),
+        _ => {}
+    }
+    
+    // The flag is kept both in read_state.json and on the cached article
+    let _lock = lock_cache(&cache_config.cache_dir, "mark-read")?;
+    let ids: std::collections::HashSet<&str> = selected.iter().map(|a| a.id.as_str()).collect();
+    let store = ReadStateStore::open(cache_config.cache_dir.join("read_state.json"))?;
+    let changed = store.set_read_many(ids.iter().copied(), !unread)?;
+    PersistentCache::new(cache_config)?.set_read_flags(&feed_name, &ids, !unread)?;
+    
+    if selected.len() == 1 {
+        println!("✅ Marked '{}' as {}", selected[0].title, state);
+    } else {
+        println!("✅ Marked {} article(s) in '{}' as {} ({} changed)", selected.len(), feed_name, state, changed);
+    }
+    
+    Ok(())
+}
+
+/// Star (or unstar) an article so it is listed in starred/
+pub async fn star(feed_name: String, article: String, unstar: bool, config_path: Option<PathBuf>) -> Result<()> {
+    info!("{} {} in {}", if unstar { "Unstarring" } else { "Starring" }, article, feed_name);
+
+    let config_file = get_config_file(config_path)?;
+    let config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+
+    if !config.feeds.contains_key(&feed_name) {
+        return Err(Error::NotFound(format!("Feed '{}' not found", feed_name)));
+    }
+
+    let cache_config = PersistentCacheConfig::default();
+    let articles = PersistentCache::new(cache_config.clone())?.load()?
+        .and_then(|mut data| data.feeds.remove(&feed_name))
+        .map(|entry| entry.data.articles)
+        .unwrap_or_default();
+    let selected = match select_articles(&articles, Some(&article)).first() {
+        Some(selected) => (*selected).clone(),
+        None => return Err(Error::NotFound(format!("No article '{}' in feed '{}'", article, feed_name))),
+    };
+
+    let state = if unstar { "Unstarred" } else { "Starred" };
+    let request = Request::Star { feed: feed_name.clone(), article: selected.id.clone(), starred: !unstar };
+    match control::send(&request).await? {
+        Some(Response::Starred) => {
+            println!("⭐ {} '{}' in the running mount", state, selected.title);
+            return Ok(());
+        },
+        Some(Response::Error { message }) => return Err(Error::InvalidState(message)),
+        _ => {}
+    }
+
+    let _lock = lock_cache(&cache_config.cache_dir, "star")?;
+    let store = StarredStore::open(cache_config.cache_dir.join("starred.json"))?;
+    store.set_starred_many([selected.id.as_str()], !unstar)?;
+    println!("⭐ {} '{}'", state, selected.title);
+
+    Ok(())
+}
+
+/// Open a cached article's link in the browser, or only print it
+pub async fn open(feed_name: String, article: String, print: bool, config_path: Option<PathBuf>) -> Result<()> {
+    let config_file = get_config_file(config_path)?;
+    let config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+
+    if !config.feeds.contains_key(&feed_name) {
+        return Err(Error::NotFound(format!("Feed '{}' not found", feed_name)));
+    }
+
+    let articles = PersistentCache::new(PersistentCacheConfig::default())?.load()?
+        .and_then(|mut data| data.feeds.remove(&feed_name))
+        .map(|entry| entry.data.articles)
+        .unwrap_or_default();
+    let selected = match select_articles(&articles, Some(&article)).first() {
+        Some(selected) => (*selected).clone(),
+        None => return Err(Error::NotFound(format!("No article '{}' in feed '{}'", article, feed_name))),
+    };
+    if selected.link.is_empty() {
+        return Err(Error::NotFound(format!("'{}' has no link", selected.title)));
+    }
+
+    if print {
+        println!("{}", selected.link);
+    } else {
+        crate::file_manager::open_url(&selected.link)?;
+        println!("🌐 Opened '{}': {}", selected.title, selected.link);
+    }
+    Ok(())
+}
+
+/// Write cached articles to `args.output` as files of the chosen format,
+/// independent of any mount
+pub async fn export(args: ExportArgs, config_path: Option<PathBuf>) -> Result<()> {
+    info!("Exporting articles: {:?}", args);
+
+    let since = args.since.as_deref().map(|v| parse_date_arg(v, false)).transpose()?;
+
+    let config_file = get_config_file(config_path)?;
+    let config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+
+    if let Some(unknown) = args.feeds.iter().find(|feed| !config.feeds.contains_key(*feed)) {
+        return Err(Error::NotFound(format!("Feed '{}' not found", unknown)));
+    }
+
+    let mut cached = PersistentCache::new(PersistentCacheConfig::default())?.load()?
+        .map(|data| data.feeds)
+        .unwrap_or_default();
+    let dirs = config.feed_dir_paths();
+    let mut exported = 0;
+    let mut feeds = 0;
+    for (name, entry) in &config.feeds {
+        if !args.feeds.is_empty() && !args.feeds.contains(name) {
+            continue;
+        }
+        let Some(feed) = cached.remove(name) else {
+            continue;
+        };
+        let articles: Vec<&Article> = feed.data.articles.iter()
+            .filter(|article| match since {
+                Some(since) => article.published.is_some_and(|published| published >= since),
+                None => true,
+            })
+            .collect();
+        if articles.is_empty() {
+            continue;
+        }
+
+        let extractor = match entry.extractor() {
+            Some(extractor) => ContentExtractor::with_selectors(extractor.selectors())?,
+            None => ContentExtractor::new()?,
+        };
+        let dir = dirs[name.as_str()].iter().fold(args.output.clone(), |dir, component| dir.join(component));
+        exported += export_articles(&dir, name, &articles, args.format, &extractor, config.fuse.text_html_policy)?;
+        feeds += 1;
+    }
+
+    if exported == 0 {
+        println!("📭 No cached articles to export. Mount or run 'rss-fuse refresh' first.");
+    } else {
+        println!("📦 Exported {} article(s) from {} feed(s) to {}", exported, feeds, args.output.display());
+    }
+    Ok(())
+}
+
+/// Write `articles` of `feed_name` into `dir`, one file each named after
+/// its title. Returns how many were written.
+fn export_articles(dir: &Path, feed_name: &str, articles: &[&Article], format: ExportFormat,
+                   extractor: &ContentExtractor, policy: TextHtmlPolicy) -> Result<usize> {
+    fs::create_dir_all(dir)?;
+    let mut taken = std::collections::HashSet::new();
+    for article in articles {
+        let (name, content) = match format {
+            ExportFormat::Markdown => (article.markdown_filename(), extractor.extract_article(article, feed_name)
+                .unwrap_or_else(|_| article.to_text_with_policy(policy))),
+            ExportFormat::Html => (article.html_filename(), extractor.extract_article_html(article, feed_name)
+                .unwrap_or_else(|_| article.to_text_with_policy(policy))),
+            ExportFormat::Text => (article.filename(), article.to_text_with_policy(policy)),
+        };
+        let mut unique = name.clone();
+        let mut n = 1;
+        while !taken.insert(unique.clone()) {
+            n += 1;
+            unique = naming::with_collision_suffix(&name, n, naming::MAX_NAME_BYTES);
+        }
+        fs::write(dir.join(&unique), content)?;
+    }
+    Ok(articles.len())
+}
+
+/// Export read and star state to a file, or apply one to the cached articles
+pub async fn state(action: StateCommand, config_path: Option<PathBuf>) -> Result<()> {
+    let config_file = get_config_file(config_path)?;
+    let config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+
+    let cache_config = PersistentCacheConfig::default();
+    let feeds: Vec<Feed> = PersistentCache::new(cache_config.clone())?.load()?
+        .map(|data| data.feeds.into_values().map(|entry| entry.data).collect())
+        .unwrap_or_default();
+    let feeds: Vec<Feed> = feeds.into_iter().filter(|feed| config.feeds.contains_key(&feed.name)).collect();
+    let read = ReadStateStore::open(cache_config.cache_dir.join("read_state.json"))?;
+    let starred = StarredStore::open(cache_config.cache_dir.join("starred.json"))?;
+
+    match action {
+        StateCommand::Export { file } => {
+            let articles = feeds.iter()
+                .flat_map(|feed| feed.articles.iter().map(move |article| (feed.name.as_str(), article)));
+            let state = StateFile::collect(articles, &read, &starred);
+            state.save(&file)?;
+            println!("💾 Wrote the state of {} article(s) to {}", state.articles.len(), file.display());
+        }
+        StateCommand::Import { file } => {
+            let _lock = lock_cache(&cache_config.cache_dir, "state import")?;
+            let state = StateFile::load(&file)?;
+            let matched = state.match_articles(feeds.iter().flat_map(|feed| &feed.articles), &config.settings.url_normalizer());
+            let ids = |flag: fn(&(String, bool, bool)) -> bool| {
+                matched.articles.iter().filter(move |entry| flag(entry)).map(|(id, _, _)| id.as_str()).collect::<Vec<_>>()
+            };
+            let changed = read.set_read_many(ids(|entry| entry.1), true)?
+                + read.set_read_many(ids(|entry| !entry.1), false)?
+                + starred.set_starred_many(ids(|entry| entry.2), true)?
+                + starred.set_starred_many(ids(|entry| !entry.2), false)?;
+            println!("📥 Applied {} to {} cached article(s) ({} flag(s) changed)",
+                     file.display(), matched.articles.len(), changed);
+            if matched.unmatched > 0 {
+                println!("   {} entries name articles not cached yet; import again after 'rss-fuse refresh'",
+                         matched.unmatched);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Manually refresh feeds
+pub async fn refresh(feed_name: Option<String>, config_path: Option<PathBuf>) -> Result<()> {
+    info!("Refreshing feeds: {:?}", feed_name);
+    
+    // A running mount refreshes its own feeds so the results show up there
+    if let Some(Response::Refreshed { feeds }) = control::send(&Request::Refresh { feed: feed_name.clone() }).await? {
+        print_mount_refresh(&feeds);
+        return Ok(());
+    }
+    
+    // Load configuration
+    let config_file = get_config_file(config_path)?;
+    let config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found.".to_string()));
+    };
+    
+    if config.feeds.is_empty() {
+        println!("📋 No feeds configured yet.");
+        return Ok(());
+    }
+    
+    let repo = RepositoryFactory::memory()
+        .with_retry_policy(config.settings.retry_policy())
+        .with_feed_limits(config.settings.feed_limits())
+        .with_url_normalizer(config.settings.url_normalizer())
+        .with_rate_limiter(RateLimiter::new(config.network.rate_limits()))
+        .with_network(config.network.options()?)?;
+    for (name, entry) in &config.feeds {
+        repo.set_id_strategy(name, entry.id_strategy());
+        repo.set_max_articles(name, entry.max_articles(&config.settings));
+        repo.set_network(name, entry.network(&config.network)?)?;
+        repo.set_filter(name, config.filters.filter_for(name)?);
+    }
+    
+    match feed_name {
+        Some(name) => {
+            // Refresh specific feed
+            if let Some(entry) = config.feeds.get(&name) {
+                if !entry.enabled() {
+                    return Err(Error::InvalidState(format!("Feed '{}' is disabled", name)));
+                }
+                println!("🔄 Refreshing feed: {}", name);
+                match repo.refresh_feed(&name, entry.url()).await {
+                    Ok((feed, result)) => {
+                        println!("✅ {} updated successfully ({})", name, describe_refresh(&feed, &result));
+                    },
+                    Err(e) => {
+                        error!("Failed to refresh {}: {}", name, e);
+                        println!("❌ Failed to refresh {}: {}", name, e);
+                    }
+                }
+            } else {
+                return Err(Error::NotFound(format!("Feed '{}' not found", name)));
+            }
+        },
+        None => {
+            // Refresh all feeds, several at a time
+            use futures::stream::{self, StreamExt};
+            
+            let concurrency = config.settings.concurrent_fetches.max(1);
+            let mut feeds: Vec<_> = config.feeds.iter().filter(|(_, entry)| entry.enabled()).collect();
+            feeds.sort_by_key(|(name, _)| name.as_str());
+            println!("🔄 Refreshing {} feeds ({} at a time)...", feeds.len(), concurrency);
+            let mut success_count = 0;
+            let mut error_count = 0;
+            
+            let mut progress = ProgressBar::new(feeds.len());
+            let mut results = stream::iter(feeds)
+                .map(|(name, entry)| {
+                    let repo = &repo;
+                    async move { (name, repo.refresh_feed(name, entry.url()).await) }
+                })
+                .buffer_unordered(concurrency);
+            
+            while let Some((name, result)) = results.next().await {
+                match result {
+                    Ok((feed, result)) => {
+                        progress.item_done(&format!("   {} ... ✅ ({})", name, describe_refresh(&feed, &result)));
+                        success_count += 1;
+                    },
+                    Err(e) => {
+                        progress.item_done(&format!("   {} ... ❌ Error: {}", name, e));
+                        error!("Failed to refresh {}: {}", name, e);
+                        error_count += 1;
+                    }
+                }
+            }
+            progress.clear();
+            
+            println!("\n📊 Refresh Summary:");
+            println!("   ✅ Successful: {}", success_count);
+            if error_count > 0 {
+                println!("   ❌ Failed: {}", error_count);
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// Completion bar kept below the lines printed for finished items. Drawn
+/// only when stdout is a terminal, so redirected output stays plain.
+struct ProgressBar {
+    done: usize,
+    total: usize,
+    visible: bool,
+}
+
+impl ProgressBar {
+    const WIDTH: usize = 30;
+    
+    fn new(total: usize) -> Self {
+        let bar = Self { done: 0, total, visible: io::stdout().is_terminal() };
+        bar.draw();
+        bar
+    }
+    
+    /// Print `line` for a finished item above the bar and advance it
+    fn item_done(&mut self, line: &str) {
+        self.done += 1;
+        self.clear();
+        println!("{}", line);
+        self.draw();
+    }
+    
+    fn draw(&self) {
+        if !self.visible {
+            return;
+        }
+        let filled = Self::WIDTH * self.done / self.total.max(1);
+        print!("   [{}{}] {}/{}", "█".repeat(filled), "░".repeat(Self::WIDTH - filled), self.done, self.total);
+        let _ = io::stdout().flush();
+    }
+    
+    fn clear(&self) {
+        if self.visible {
+            print!("\r\x1b[K");
+            let _ = io::stdout().flush();
+        }
+    }
+}
+
+/// Article counts after a refresh, e.g. "12 articles, 2 new, 1 updated, 0 removed"
+fn describe_refresh(feed: &Feed, result: &FeedResult) -> String {
+    format!(
+        "{} articles, {} new, {} updated, {} removed",
+        feed.articles.len(), result.articles_added, result.articles_updated, result.articles_removed
+    )
+}
+
+/// Print what a running mount reported for a refresh
+fn print_mount_refresh(results: &[RefreshResult]) {
+    println!("🔄 Refreshed in the running mount:");
+    let mut error_count = 0;
+    for result in results {
+        match result {
+            RefreshResult::Updated { name, articles, added, updated, removed } => {
+                println!("   {} ... ✅ ({} articles, {} new, {} updated, {} removed)", name, articles, added, updated, removed)
+            }
+            RefreshResult::NotModified { name } => println!("   {} ... ✅ (not modified)", name),
+            RefreshResult::Failed { name, error } => {
+                println!("   {} ... ❌ Error: {}", name, error);
+                error_count += 1;
+            }
+        }
+    }
+    
+    if results.len() > 1 {
+        println!("\n📊 Refresh Summary:");
+        println!("   ✅ Successful: {}", results.len() - error_count);
+        if error_count > 0 {
+            println!("   ❌ Failed: {}", error_count);
+        }
+    }
+}
+
+/// Remove cached articles beyond the configured retention limits
+pub async fn prune(config_path: Option<PathBuf>) -> Result<()> {
+    info!("Pruning cached articles");
+    
+    // A running mount owns the cache; let it prune so it does not write the
+    // articles back on its next save
+    if let Some(Response::Pruned { stats }) = control::send(&Request::Prune).await? {
+        println!("🧹 Pruned the running mount");
+        print_cleanup_stats(&stats);
+        return Ok(());
+    }
+    
+    let config_file = get_config_file(config_path)?;
+    let config = if config_file.exists() {
+        Config::load(&config_file)?
+    } else {
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
+    };
+    
+    let persistent_config = PersistentCacheConfig {
+        compression: config.cache.compression()?,
+        ..PersistentCacheConfig::default()
+    };
+    let _lock = lock_cache(&persistent_config.cache_dir, "prune")?;
+    let summary_dir = persistent_config.cache_dir.join("summaries");
+    let repo = RepositoryFactory::with_persistent_cache(
+        config.storage_config(),
+        CacheConfig {
+            default_ttl: std::time::Duration::from_secs(config.settings.cache_duration),
+            ..CacheConfig::default()
+        },
+        persistent_config,
+    )?;
+    for (name, entry) in &config.feeds {
+        repo.set_max_articles(name, entry.max_articles(&config.settings));
+    }
     
-    println!("✅ Feed '{}' removed successfully!", name);
-    println!("   Removed URL: {}", url);
+    println!("🧹 Pruning cached articles...");
+    let stats = repo.prune().await?;
+    print_cleanup_stats(&stats);
     
+    let mut kept = Vec::new();
+    for name in config.feeds.keys() {
+        if let Some(feed) = repo.get_feed(name).await? {
+            kept.extend(feed.articles.into_iter().map(|article| article.id));
+        }
+    }
+    let summaries = summarizer::retain_summaries(
+        &summary_dir,
+        kept.iter().map(String::as_str),
+    )?;
+    if summaries > 0 {
+        println!("   Deleted {} summaries of pruned articles", summaries);
+    }
     Ok(())
 }
 
-/// List all configured RSS feeds
-pub async fn list_feeds(config_path: Option<PathBuf>) -> Result<()> {
-    info!("Listing feeds");
+/// Fetch feeds into the on-disk cache, in load order, so the next mount
+/// shows current articles straight away
+pub async fn warm(feed_name: Option<String>, config_path: Option<PathBuf>) -> Result<()> {
+    info!("Warming the cache: {:?}", feed_name);
+    
+    // A running mount's cache is live already; refresh it there instead
+    if let Some(Response::Refreshed { feeds }) = control::send(&Request::Refresh { feed: feed_name.clone() }).await? {
+        println!("🔥 Refreshed the running mount, whose cache is already in use");
+        print_mount_refresh(&feeds);
+        return Ok(());
+    }
     
-    // Load configuration
     let config_file = get_config_file(config_path)?;
     let config = if config_file.exists() {
         Config::load(&config_file)?
@@ -175,114 +1292,370 @@ pub async fn list_feeds(config_path: Option<PathBuf>) -> Result<()> {
         return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
     };
     
-    if config.feeds.is_empty() {
-        println!("📋 No feeds configured yet.");
-        println!("   Add feeds with: rss-fuse add-feed <name> <url>");
-        return Ok(());
+    let persistent_config = PersistentCacheConfig {
+        max_size_mb: config.cache.max_size_mb as u64,
+        compression: config.cache.compression()?,
+        ..PersistentCacheConfig::default()
+    };
+    let _lock = lock_cache(&persistent_config.cache_dir, "warm")?;
+    let read_state = ReadStateStore::open(persistent_config.cache_dir.join("read_state.json"))?;
+    let repo = RepositoryFactory::with_persistent_cache(
+        config.storage_config(),
+        CacheConfig {
+            default_ttl: std::time::Duration::from_secs(config.settings.cache_duration),
+            ..CacheConfig::default()
+        },
+        persistent_config,
+    )?
+        .with_read_state(std::sync::Arc::new(read_state))
+        .with_retry_policy(config.settings.retry_policy())
+        .with_feed_limits(config.settings.feed_limits())
+        .with_url_normalizer(config.settings.url_normalizer())
+        .with_rate_limiter(RateLimiter::new(config.network.rate_limits()))
+        .with_network(config.network.options()?)?;
+    for (name, entry) in &config.feeds {
+        repo.set_id_strategy(name, entry.id_strategy());
+        repo.set_max_articles(name, entry.max_articles(&config.settings));
+        repo.set_network(name, entry.network(&config.network)?)?;
+        repo.set_filter(name, config.filters.filter_for(name)?);
     }
     
-    println!("📋 Configured RSS Feeds:");
-    println!("========================");
-    
-    // Create repository to get additional information
-    let repo = RepositoryFactory::memory();
+    let names = match feed_name {
+        Some(name) => match config.feeds.get(&name) {
+            Some(entry) if !entry.enabled() => return Err(Error::InvalidState(format!("Feed '{}' is disabled", name))),
+            Some(_) => vec![name],
+            None => return Err(Error::NotFound(format!("Feed '{}' not found", name))),
+        },
+        None => load_order(&config, &repo).into_iter().filter(|name| config.feeds[name].enabled()).collect(),
+    };
     
-    for (name, url) in &config.feeds {
-        println!("\n📰 {}", name);
-        println!("   URL: {}", url);
-        
-        // Try to get cached feed information
-        match repo.get_feed(name).await {
-            Ok(Some(feed)) => {
-                println!("   Title: {}", feed.title.as_deref().unwrap_or("Unknown"));
-                println!("   Articles: {}", feed.articles.len());
-                println!("   Status: {:?}", feed.status);
-                if let Some(updated) = feed.last_updated {
-                    println!("   Last Updated: {}", updated.format("%Y-%m-%d %H:%M:%S UTC"));
-                }
-            },
-            Ok(None) => {
-                println!("   Status: Not cached (run refresh to update)");
+    // Feeds start in load order, several at a time
+    use futures::stream::{self, StreamExt};
+    let concurrency = config.settings.concurrent_fetches.max(1);
+    println!("🔥 Warming the cache with {} feeds ({} at a time)...", names.len(), concurrency);
+    let mut cached = 0;
+    let mut progress = ProgressBar::new(names.len());
+    let mut results = stream::iter(&names)
+        .map(|name| {
+            let repo = &repo;
+            let url = config.feeds[name].url();
+            async move { (name, repo.refresh_feed(name, url).await) }
+        })
+        .buffered(concurrency);
+    while let Some((name, result)) = results.next().await {
+        match result {
+            Ok((feed, result)) => {
+                progress.item_done(&format!("   {} ... ✅ ({})", name, describe_refresh(&feed, &result)));
+                cached += 1;
             },
-            Err(_) => {
-                println!("   Status: Error accessing feed data");
+            Err(e) => {
+                progress.item_done(&format!("   {} ... ❌ Error: {}", name, e));
+                error!("Failed to warm {}: {}", name, e);
             }
         }
     }
+    progress.clear();
     
-    println!("\n💡 Use 'rss-fuse refresh' to update all feeds");
-    
+    repo.save_cache().await?;
+    println!("✅ Cached {} of {} feeds", cached, names.len());
     Ok(())
 }
 
-/// Manually refresh feeds
-pub async fn refresh(feed_name: Option<String>, config_path: Option<PathBuf>) -> Result<()> {
-    info!("Refreshing feeds: {:?}", feed_name);
-    
-    // Load configuration
+fn print_cleanup_stats(stats: &CleanupStats) {
+    if stats.articles_removed == 0 {
+        println!("   Nothing to remove, all articles are within the retention limits");
+    } else {
+        println!("   🗑️  Removed {} articles ({:.1} KB)", stats.articles_removed, stats.bytes_freed as f64 / 1024.0);
+    }
+}
+
+/// Exchange subscriptions and read state with the reader set up in `[sync]`
+pub async fn sync(config_path: Option<PathBuf>) -> Result<()> {
+    info!("Syncing with the reader");
+
+    // A running mount applies the result to its own files
+    match control::send(&Request::Sync).await? {
+        Some(Response::Synced { report }) => {
+            println!("🔄 Synced the running mount");
+            print_sync_report(&report);
+            return Ok(());
+        },
+        Some(Response::Error { message }) => return Err(Error::InvalidState(message)),
+        _ => {}
+    }
+
     let config_file = get_config_file(config_path)?;
     let config = if config_file.exists() {
         Config::load(&config_file)?
     } else {
-        return Err(Error::NotFound("Configuration file not found.".to_string()));
+        return Err(Error::NotFound("Configuration file not found. Run 'rss-fuse init' first.".to_string()));
     };
-    
-    if config.feeds.is_empty() {
-        println!("📋 No feeds configured yet.");
-        return Ok(());
+    let settings = config.sync.as_ref().ok_or_else(|| Error::NotFound(
+        "No [sync] section in the config. Add one with the reader's endpoint and username.".to_string()
+    ))?;
+
+    println!("🔄 Syncing with {}...", settings.endpoint);
+    let client = sync::connect(settings, &config.network).await?;
+    let mut report = SyncReport::default();
+    if settings.import_subscriptions {
+        report.feeds_added = sync::import_subscriptions(&client, &config_file).await?;
     }
+
+    // Read and starred state are compared for the cached articles; feeds just added
+    // take part once they have been fetched
+    let cache_config = PersistentCacheConfig::default();
+    let _lock = lock_cache(&cache_config.cache_dir, "sync")?;
+    let cached = PersistentCache::new(cache_config.clone())?.load()?
+        .map(|data| data.feeds)
+        .unwrap_or_default();
+    let store = ReadStateStore::open(cache_config.cache_dir.join("read_state.json"))?;
+    let starred = StarredStore::open(cache_config.cache_dir.join("starred.json"))?;
+    let local: Vec<LocalArticle> = cached.iter()
+        .filter(|(name, _)| config.feeds.contains_key(*name))
+        .flat_map(|(name, entry)| entry.data.articles.iter()
+            .map(|article| LocalArticle::new(
+                name,
+                article,
+                article.read || store.is_read(&article.id),
+                article.starred || starred.is_starred(&article.id),
+            )))
+        .collect();
+
+    let plan = sync::sync_state(&client, settings.max_items, &local, &config.settings.url_normalizer()).await?;
+    store.set_read_many(plan.mark_local.iter().map(|(_, id)| id.as_str()), true)?;
+    starred.set_starred_many(plan.star_local.iter().map(|(_, id)| id.as_str()), true)?;
+    report.record(&plan);
+
+    print_sync_report(&report);
+    Ok(())
+}
+
+fn print_sync_report(report: &SyncReport) {
+    if report.feeds_added.is_empty() {
+        println!("   No new subscriptions");
+    } else {
+        println!("   ➕ Added {} feed(s): {}", report.feeds_added.len(), report.feeds_added.join(", "));
+    }
+    println!("   📖 {} article(s) marked read here, {} on the server",
+             report.marked_read_locally, report.marked_read_remotely);
+    println!("   ⭐ {} article(s) starred here, {} on the server",
+             report.starred_locally, report.starred_remotely);
+}
+
+/// Show, clear, save or locate the on-disk cache
+pub async fn cache(action: CacheCommand) -> Result<()> {
+    let cache = PersistentCache::new(PersistentCacheConfig::default())?;
+    let cache_dir = cache.cache_dir().to_path_buf();
     
-    let repo = RepositoryFactory::memory();
-    
-    match feed_name {
-        Some(name) => {
-            // Refresh specific feed
-            if let Some(url) = config.feeds.get(&name) {
-                println!("🔄 Refreshing feed: {}", name);
-                match repo.refresh_feed(&name, url).await {
-                    Ok(feed) => {
-                        println!("✅ {} updated successfully ({} articles)", name, feed.articles.len());
-                    },
-                    Err(e) => {
-                        error!("Failed to refresh {}: {}", name, e);
-                        println!("❌ Failed to refresh {}: {}", name, e);
-                    }
-                }
-            } else {
-                return Err(Error::NotFound(format!("Feed '{}' not found", name)));
+    match action {
+        CacheCommand::Path => println!("{}", cache_dir.display()),
+        CacheCommand::Stats => {
+            let stats = cache.stats()?;
+            println!("💾 Cache: {}", cache_dir.display());
+            println!("   📦 Size on disk: {:.1} KB total, feed cache {:.1} KB",
+                     dir_size(&cache_dir) as f64 / 1024.0, stats.file_bytes as f64 / 1024.0);
+            if let Some(ratio) = stats.compression_ratio() {
+                println!("   🗜️  Compression: {:.1}x ({:.1} KB uncompressed, {} shared bodies referenced {} times)",
+                         ratio, stats.raw_bytes as f64 / 1024.0, stats.bodies, stats.body_refs);
+            }
+            println!("   📰 Feeds: {}", stats.feeds);
+            println!("   📄 Articles: {}", stats.articles);
+            if stats.expired > 0 {
+                println!("   ⌛ Expired entries: {}", stats.expired);
+            }
+            if stats.unreadable > 0 {
+                println!("   ⚠️  Unreadable feed files: {} (set aside on the next load)", stats.unreadable);
+            }
+            if let Some(saved_at) = stats.saved_at {
+                println!("   🕒 Saved: {}", chrono::DateTime::<chrono::Utc>::from(saved_at).format("%Y-%m-%d %H:%M:%S UTC"));
             }
+            
+            // Hit rates only exist in the memory of a running mount
+            match control::send(&Request::Status).await {
+                Ok(Some(Response::Status(report))) => match report.cache {
+                    Some(cache) => println!(
+                        "   📈 Hit rate (running mount): {:.1}% ({} hits, {} misses)",
+                        cache.hit_rate * 100.0, cache.hits, cache.misses
+                    ),
+                    None => println!("   📈 Hit rate: not reported by the running mount"),
+                },
+                Ok(_) => println!("   📈 Hit rate: no running mount"),
+                Err(e) => println!("⚠️  The running mount did not report hit rates: {}", e),
+            }
+        }
+        CacheCommand::Save => match control::send(&Request::SaveCache).await? {
+            Some(_) => println!("✅ Running mount saved its cache to {}", cache.cache_path().display()),
+            None => println!("📭 No running mount; the cache on disk is already current"),
         },
-        None => {
-            // Refresh all feeds
-            println!("🔄 Refreshing all feeds...");
-            let mut success_count = 0;
-            let mut error_count = 0;
+        CacheCommand::Clear => {
+            // A mount would write its in-memory copy straight back
+            if control::send(&Request::Status).await?.is_some() {
+                return Err(Error::InvalidState(
+                    "A mount is running; unmount it before clearing the cache".to_string()
+                ));
+            }
             
-            for (name, url) in &config.feeds {
-                print!("   {} ... ", name);
-                std::io::Write::flush(&mut std::io::stdout()).unwrap();
-                
-                match repo.refresh_feed(name, url).await {
-                    Ok(feed) => {
-                        println!("✅ ({} articles)", feed.articles.len());
-                        success_count += 1;
-                    },
-                    Err(e) => {
-                        println!("❌ Error: {}", e);
-                        error!("Failed to refresh {}: {}", name, e);
-                        error_count += 1;
-                    }
+            let _lock = lock_cache(&cache_dir, "cache clear")?;
+            
+            // Besides the feed cache, the HTTP cache and downloaded
+            // enclosures and images live here; read_state.json stays
+            let mut freed = cache.clear()?;
+            for dir in [cache_dir.join("http"), cache_dir.join("enclosures"), cache_dir.join("images")] {
+                if dir.exists() {
+                    freed += dir_size(&dir);
+                    fs::remove_dir_all(&dir)?;
                 }
             }
+            println!("🗑️  Cleared {} ({:.1} KB freed)", cache_dir.display(), freed as f64 / 1024.0);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Lock the cache in `cache_dir` while a command rewrites it, failing if a
+/// mount (or another command) is using it without answering on the
+/// control socket
+fn lock_cache(cache_dir: &Path, command: &str) -> Result<InstanceLock> {
+    let path = lock::cache_lock_path(cache_dir);
+    InstanceLock::try_acquire(&path, command)?.ok_or_else(|| {
+        let holder = InstanceLock::holder(&path)
+            .map_or_else(|| "another process".to_string(), |holder| holder.to_string());
+        Error::AlreadyExists(format!(
+            "The cache in {} is in use by {}. Try again once it has finished or been unmounted",
+            cache_dir.display(), holder
+        ))
+    })
+}
+
+/// Total size of the files under `path`
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries.flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// `rss-fuse config` actions
+pub async fn config(action: ConfigCommand, config_path: Option<PathBuf>) -> Result<()> {
+    let config_file = get_config_file(config_path)?;
+    match action {
+        ConfigCommand::Validate { strict } => {
+            let content = fs::read_to_string(&config_file)
+                .map_err(|_| Error::NotFound(config_file.display().to_string()))?;
+            let diagnostics = config_check::check(&content, strict);
+            for diagnostic in &diagnostics {
+                println!("{}: {}", config_file.display(), diagnostic);
+            }
             
-            println!("\n📊 Refresh Summary:");
-            println!("   ✅ Successful: {}", success_count);
-            if error_count > 0 {
-                println!("   ❌ Failed: {}", error_count);
+            let errors = diagnostics.iter().filter(|d| d.level == Level::Error).count();
+            let warnings = diagnostics.len() - errors;
+            if diagnostics.is_empty() {
+                println!("✅ {} is valid", config_file.display());
+            } else {
+                println!("{} error(s), {} warning(s)", errors, warnings);
+            }
+            if errors > 0 {
+                return Err(Error::Config(format!("{} has {} error(s)", config_file.display(), errors)));
+            }
+            Ok(())
+        }
+        ConfigCommand::Edit => {
+            let original = fs::read_to_string(&config_file)
+                .map_err(|_| Error::NotFound(config_file.display().to_string()))?;
+            // Edit next to the file a symlinked config points at, so saving
+            // replaces that file rather than the link
+            let target = fs::canonicalize(&config_file)?;
+            let dir = target.parent().unwrap_or(Path::new("."));
+            let draft = tempfile::Builder::new().prefix(".config-edit-").suffix(".toml").tempfile_in(dir)?;
+            fs::write(draft.path(), &original)?;
+            
+            let edited = edit_until_valid(draft.path(), run_editor, || {
+                print!("Edit again? [Y/n] ");
+                io::stdout().flush()?;
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                Ok(!input.trim().eq_ignore_ascii_case("n"))
+            })?;
+            match edited {
+                None => println!("❌ Changes discarded; {} is unchanged", config_file.display()),
+                Some(content) if content == original => println!("No changes made"),
+                Some(_) => {
+                    persist_config(draft, &target)?;
+                    println!("✅ Saved {}", config_file.display());
+                    println!("   A running mount picks up feed changes automatically");
+                }
             }
+            Ok(())
+        }
+        ConfigCommand::Show => {
+            let config = Config::load_with_env(&config_file)?;
+            let content = toml::to_string_pretty(&config).map_err(|e| Error::Config(e.to_string()))?;
+            println!("# Effective configuration from {}", config_file.display());
+            println!("# (defaults and RSS_FUSE_* environment overrides applied)\n");
+            print!("{}", content);
+            Ok(())
+        }
+        ConfigCommand::Path => {
+            println!("{}", config_file.display());
+            Ok(())
         }
     }
-    
+}
+
+/// Let `edit` change the draft at `path` until it has no errors, printing
+/// what is wrong after each round. `retry` decides whether to edit again
+/// after a failed round; returns the valid text, or `None` when given up.
+fn edit_until_valid(
+    path: &Path,
+    mut edit: impl FnMut(&Path) -> Result<()>,
+    mut retry: impl FnMut() -> Result<bool>,
+) -> Result<Option<String>> {
+    loop {
+        edit(path)?;
+        let content = fs::read_to_string(path)?;
+        let diagnostics = config_check::check(&content, false);
+        for diagnostic in &diagnostics {
+            println!("{}", diagnostic);
+        }
+        if !diagnostics.iter().any(|d| d.level == Level::Error) {
+            return Ok(Some(content));
+        }
+        println!("❌ The config has errors and was not saved");
+        if !retry()? {
+            return Ok(None);
+        }
+    }
+}
+
+/// Move the edited `draft` over `target`, giving it `target`'s permissions
+/// instead of the private mode temporary files are created with
+fn persist_config(draft: tempfile::NamedTempFile, target: &Path) -> Result<()> {
+    let permissions = fs::metadata(target)?.permissions();
+    fs::set_permissions(draft.path(), permissions)?;
+    draft.persist(target).map_err(|e| Error::Io(e.error))?;
+    Ok(())
+}
+
+/// Open `path` in `$VISUAL`, `$EDITOR` or `vi`, waiting for it to exit
+fn run_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL").ok()
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = std::process::Command::new(program).args(words).arg(path).status()
+        .map_err(|e| Error::Config(format!("Could not start editor '{}': {}", editor, e)))?;
+    if !status.success() {
+        return Err(Error::Config(format!("Editor '{}' exited with {}", editor, status)));
+    }
     Ok(())
 }
 
@@ -292,6 +1665,9 @@ pub async fn status(specific_mount_point: Option<PathBuf>) -> Result<()> {
     
     println!("📊 RSS-FUSE Status");
     println!("==================");
+    if let Some(name) = profile::current() {
+        println!("👤 Profile: {}", name);
+    }
     
     // Check configuration
     let config_dir = get_config_dir()?;
@@ -303,6 +1679,14 @@ pub async fn status(specific_mount_point: Option<PathBuf>) -> Result<()> {
         let config = Config::load(&config_file)?;
         println!("   📰 Feeds configured: {}", config.feeds.len());
         
+        let usage = ResourceUsage::measure(&config);
+        for exceeded in limits::check(&config.limits.hard, &usage) {
+            println!("   ⛔ Hard limit exceeded: {}", exceeded);
+        }
+        for exceeded in limits::check(&config.limits.soft, &usage) {
+            println!("   ⚠️  Soft limit exceeded: {}", exceeded);
+        }
+        
         // Repository statistics
         let repo = RepositoryFactory::memory();
         if let Ok(stats) = FeedRepository::get_stats(&repo).await {
@@ -335,6 +1719,7 @@ pub async fn status(specific_mount_point: Option<PathBuf>) -> Result<()> {
     // Check mount status
     println!("\n🗂️  Mount Status:");
     let fuse_ops = crate::fuse::FuseOperations::new();
+    let show_operations = specific_mount_point.is_some();
     
     if let Some(specific_path) = specific_mount_point {
         // Check specific mount point
@@ -421,6 +1806,28 @@ pub async fn status(specific_mount_point: Option<PathBuf>) -> Result<()> {
         }
     }
     
+    // Feeds as the running mount sees them
+    match control::send(&Request::Status).await {
+        Ok(Some(Response::Status(report))) => {
+            println!("\n📡 Running Mount ({}):", control::socket_path().display());
+            for feed in &report.feeds {
+                println!("   📰 {}: {}, {} articles ({} unread)", feed.name, feed.status, feed.articles, feed.unread);
+                if let Some(error) = &feed.error {
+                    println!("      ❌ {}", error);
+                }
+            }
+            if show_operations && !report.operations.is_empty() {
+                println!("\n⏱️  FUSE Operations:");
+                for op in &report.operations {
+                    println!("   {}: {} calls, avg {:.2}ms, p95 {}, max {:.2}ms",
+                        op.op, op.count, op.avg_ms(), op.quantile_label(0.95), op.max_ms);
+                }
+            }
+        },
+        Ok(_) => {}
+        Err(e) => println!("\n⚠️  Running mount did not answer: {}", e),
+    }
+    
     // System information
     println!("\n🖥️  System Information:");
     println!("   📍 Config directory: {}", config_dir.display());
@@ -430,10 +1837,7 @@ pub async fn status(specific_mount_point: Option<PathBuf>) -> Result<()> {
     // Check for required tools
     println!("\n🛠️  System Tools:");
     let tools = [
-        ("fusermount", "FUSE unmounting"),
-        ("umount", "Fallback unmounting"),
-        ("lsof", "Process detection"),
-        ("fuser", "Process management"),
+        ("fusermount", "FUSE unmounting as a regular user"),
     ];
     
     for (tool, description) in &tools {
@@ -448,6 +1852,15 @@ pub async fn status(specific_mount_point: Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+/// Run every `doctor` check, failing if any of them did
+pub async fn doctor(config_path: Option<PathBuf>) -> Result<()> {
+    let config_file = get_config_file(config_path)?;
+    match doctor::run(&config_file).await {
+        0 => Ok(()),
+        failures => Err(Error::InvalidState(format!("{} doctor check(s) failed", failures))),
+    }
+}
+
 /// Generate shell completions
 pub fn generate_completions(shell: Shell) {
     let mut cmd = Cli::command();
@@ -484,12 +1897,15 @@ pub async fn demo_filesystem(detailed: bool, config_path: Option<PathBuf>) -> Re
     println!("\n📁 Virtual Filesystem Structure:");
     println!("├── /");
     
-    for (name, url) in &config.feeds {
+    for (name, entry) in &config.feeds {
         print!("│   ├── {} ... ", name);
         std::io::Write::flush(&mut std::io::stdout()).unwrap();
         
-        match repo.refresh_feed(name, url).await {
-            Ok(feed) => {
+        repo.set_id_strategy(name, entry.id_strategy());
+        repo.set_max_articles(name, entry.max_articles(&config.settings));
+        repo.set_filter(name, config.filters.filter_for(name)?);
+        match repo.refresh_feed(name, entry.url()).await {
+            Ok((feed, _)) => {
                 let article_count = feed.articles.len();
                 println!("📁 ({} articles)", article_count);
                 feed_count += 1;
@@ -534,6 +1950,7 @@ pub async fn demo_filesystem(detailed: bool, config_path: Option<PathBuf>) -> Re
                 // Show meta directory
                 println!("│   └── .meta/");
                 println!("│       ├── config.toml");
+                println!("│       ├── feed.epub");
                 println!("│       ├── feed.xml");
                 println!("│       └── stats.json");
                 
@@ -591,9 +2008,9 @@ pub fn init_logging(debug: bool, verbose: bool) -> Result<()> {
 /// Get the configuration directory path
 fn get_config_dir() -> Result<PathBuf> {
     if let Some(config_home) = std::env::var_os("XDG_CONFIG_HOME") {
-        Ok(PathBuf::from(config_home).join("rss-fuse"))
+        Ok(PathBuf::from(config_home).join(profile::dir_name()))
     } else if let Some(home) = std::env::var_os("HOME") {
-        Ok(PathBuf::from(home).join(".config").join("rss-fuse"))
+        Ok(PathBuf::from(home).join(".config").join(profile::dir_name()))
     } else {
         Err(Error::Config("Cannot determine configuration directory".to_string()))
     }
@@ -613,8 +2030,7 @@ fn create_default_config(mount_point: &Path) -> Result<String> {
 # Generated on {}
 
 [settings]
-# Default mount point
-mount_point = "{}"
+# Mount with: rss-fuse{} mount {}
 
 # Feed refresh interval in seconds (default: 1 hour)
 refresh_interval = 3600
@@ -625,22 +2041,123 @@ cache_duration = 14400
 # Maximum number of articles per feed (default: 100)
 max_articles = 100
 
-# Include article content in files (default: true)
-article_content = true
+# Feeds fetched at the same time by `refresh` and `add-feed --stdin` (default: 5)
+concurrent_fetches = 5
+
+# Drop articles published more than this many days ago (default: keep)
+# max_article_age_days = 30
+
+# Feeds larger than this are rejected: response size in MB, number of
+# items, and bytes of one item's content (defaults: 20, 10000, 1 MB)
+# max_feed_size_mb = 20
+# max_feed_items = 10000
+# max_article_size = 1048576
+
+# Query parameters removed from article links so the same story keeps one
+# ID; a trailing * matches a prefix (default: utm_*, fbclid, gclid and other
+# common trackers)
+# tracking_params = ["utm_*", "fbclid", "gclid", "ref"]
+
+# Retries of a failed fetch, with exponential backoff starting at
+# retry_backoff_ms and capped at retry_max_backoff seconds
+retry_attempts = 3
+retry_backoff_ms = 1000
+retry_max_backoff = 60
+
+# Failed refreshes in a row before a feed is reported as errored
+failure_threshold = 3
+
+# Article bodies: "feed" serves what the feed provides; "fetch-on-read"
+# downloads the linked page when a file is first opened and serves its
+# main content as Markdown (default: "feed")
+article_content = "feed"
 
 # FUSE filesystem options
 [fuse]
 # Allow other users to access the filesystem
 allow_other = false
 
-# Allow root to access the filesystem
-allow_root = false
-
 # Automatic unmount on process exit
 auto_unmount = true
 
-# Read-only filesystem
-read_only = true
+# Transliterate non-Latin feed and article names to ASCII (e.g. "статьи" -> "stati")
+transliterate_names = false
+
+# Article files: "markdown" (.md with YAML frontmatter), "text" (.txt) or
+# "html" (standalone .html pages to open in a browser)
+output_format = "markdown"
+
+# Article file names, e.g. "{{published:%Y-%m-%d}}-{{title}}.{{ext}}" or
+# "{{index:03}}-{{title}}" (also settable per feed); unset uses the title.
+# Placeholders: title, author, feed, id, ext, published[:strftime], index[:width]
+# filename_template = "{{published:%Y-%m-%d}}-{{title}}.{{ext}}"
+
+# HTML in plain-text articles: "strip" (readable text), "entities" (decode
+# entities only) or "raw"
+text_html_policy = "strip"
+
+# `rm` on an article marks it read. Mounts read-write when on
+delete_marks_read = false
+
+# Hide articles that have been marked read
+hide_read_articles = true
+
+# Virtual unread/ and read/ folders inside each feed
+read_state_views = false
+
+# A .meta folder in each feed with its config.toml, raw feed.xml, stats.json
+# and feed.epub
+feed_meta = true
+
+# Top-level folders of symlinks across all feeds: latest/ holds the newest
+# N articles (0 disables it), today/ those published in the last 24 hours
+latest_articles = 0
+today_dir = false
+
+# Top-level starred/ folder of symlinks to starred articles. `ln -s` an
+# article into it (or `rss-fuse star`) to star it; rm the link to unstar.
+# Mounts read-write when on
+starred_dir = false
+
+# Top-level tags/ folder with a folder per article tag (category), each
+# holding symlinks to the articles carrying it across all feeds
+tags_dir = false
+
+# Podcast audio and other enclosures, listed next to their article (e.g.
+# "Episode 1.mp3"): "stream" reads from the server on demand, "download"
+# caches the whole file on first read for offline use, "off" hides them
+enclosures = "stream"
+
+# Download the images articles embed and point the Markdown at copies in an
+# assets/ folder next to the article, so articles read fully offline
+offline_images = false
+
+# Writable control files: `echo <feed> > .rss-fuse/refresh` fetches a feed
+# now, and saving .rss-fuse/feeds.toml writes the feed list back to this file.
+# Mounts read-write when on
+control_files = false
+
+# Log a warning for any filesystem operation (lookup, readdir, read, ...)
+# taking at least this many milliseconds; 0 turns the warnings off
+slow_op_threshold_ms = 1000
+
+# Article bodies over this many bytes stay on disk (in the cache directory)
+# while mounted and are read back when their file is read; 0 keeps them all
+# in memory
+spill_content_bytes = 32768
+
+# Feed directories with more articles than this list them in <year>/<month>/
+# folders by publication date; 0 keeps every feed flat
+date_folders_after = 500
+
+# Listing a feed directory whose last fetch is older than this many seconds
+# refreshes that feed in the background; 0 turns it off
+refresh_on_access = 0
+
+# Stories another feed carries too (same link or near-identical title):
+# "show", "hide", "group" (into a duplicates/ folder) or "annotate" (also_in
+# in the frontmatter); the copy in the feed whose name sorts first stays put
+duplicates = "show"
 
 # File manager auto-open configuration
 [fuse.auto_open]
@@ -665,6 +2182,17 @@ launch_delay = 2
 # Auto-detect available file managers
 auto_detect = true
 
+# Visual cue for old articles
+[fuse.article_aging]
+enabled = false
+
+# Articles published more than this many days ago are "old"
+after_days = 30
+
+# How to mark old articles: "permissions" (mode 0400), "suffix" (Title.old.md)
+# or "folder" (moved into an old/ subdirectory)
+marker = "permissions"
+
 [feeds]
 # Add your RSS feeds here
 # Format: "feed-name" = "https://example.com/feed.xml"
@@ -672,6 +2200,116 @@ auto_detect = true
 # Example:
 # "hacker-news" = "https://hnrss.org/frontpage"
 # "rust-blog" = "https://blog.rust-lang.org/feed.xml"
+#
+# Feeds can also be tables with per-feed settings:
+# [feeds."flaky-feed"]
+# url = "https://example.com/rss"
+# id_strategy = "content-hash"   # guid | link | title+date | content-hash
+# max_visible_articles = 200     # newest listed; the rest stay cached
+# languages = ["en", "de"]       # hide articles detected in other languages
+# headers = {{ "Accept-Language" = "de" }}  # sent on top of [network] headers
+# notify = false                 # leave out of [notifications]
+# enabled = false                # keep the cached articles but stop fetching
+# priority = 10                 # load before feeds with a lower priority (0)
+#
+# Group feeds into folders with a "group/name" key or a group table;
+# both of these show up as tech/rust-blog/ on the mount:
+# "tech/rust-blog" = "https://blog.rust-lang.org/feed.xml"
+# [feeds.tech]
+# "rust-blog" = "https://blog.rust-lang.org/feed.xml"
+
+[limits]
+# Resource budgets (all optional). Soft limits warn in status/mount output,
+# hard limits make add-feed refuse new subscriptions.
+# [limits.soft]
+# max_feeds = 50
+# max_total_articles = 5000
+# max_cache_mb = 100
+# [limits.hard]
+# max_feeds = 200
+
+[network]
+# Proxy for all requests: http://, https://, socks5:// or socks5h:// (resolves
+# names through the proxy, as Tor needs). Without it HTTP_PROXY/HTTPS_PROXY
+# are honoured; "none" ignores them. A feed table can set its own `proxy`.
+# proxy = "socks5h://127.0.0.1:9050"
+
+# Extra trusted root certificates (PEM), e.g. a corporate CA
+# ca_certificates = ["/etc/ssl/certs/corp-ca.pem"]
+
+# Accept any TLS certificate. Disables HTTPS protection; last resort only.
+insecure_skip_verify = false
+
+# Politeness: requests per minute to any one host (e.g. many subreddits),
+# after a burst of host_burst back-to-back requests. Unlimited if unset.
+# requests_per_minute = 30
+host_burst = 1
+
+# Requests in flight at once across refreshes, page fetches and downloads
+# max_concurrent_requests = 16
+
+# User-Agent and extra headers of every request; a feed table can set its
+# own `user_agent` and `headers`. Defaults to RSS-FUSE/<version>.
+# user_agent = "Mozilla/5.0 (compatible; rss-fuse)"
+# headers = {{ "Accept-Language" = "en" }}
+
+# Top-level folders combining the articles of several feeds, newest first;
+# stories with the same link are listed once
+# [virtual-feeds]
+# all-rust = ["rust-blog", "this-week-in-rust"]
+
+# Keyword rules for fetched articles, applied in order. Patterns are regexes
+# on title, content, author or tags; all given must match. Actions: drop,
+# mark-read, tag (needs `tag`) or folder (needs `folder`, a subfolder of the
+# feed directory). `feeds` limits a rule to feeds or groups.
+# [[filters.rules]]
+# title = "(?i)sponsored|giveaway"
+# action = "drop"
+#
+# [[filters.rules]]
+# feeds = ["tech"]
+# tags = "(?i)^security$"
+# action = "folder"
+# folder = "security"
+
+# Sync subscriptions and read state with a Google Reader API server
+# (FreshRSS, Miniflux, The Old Reader). Read is sticky; stars are not synced.
+# [sync]
+# endpoint = "https://rss.example.com/api/greader.php"
+# username = "alice"
+# password = "api-password"  # or set RSS_FUSE_SYNC_PASSWORD
+# import_subscriptions = true
+# interval = 900
+# max_items = 1000
+
+# Serve feed health, cache counters and FUSE operation counts to Prometheus
+# at http://<listen>/metrics while mounted
+# [metrics]
+# listen = "127.0.0.1:9184"
+
+# Announce articles added while mounted, at most once per batch_window
+# seconds: on the desktop via notify-send, and as a JSON POST to each
+# webhook (Slack incoming webhooks, ntfy topics, ...)
+# [notifications]
+# desktop = true
+# webhooks = ["https://ntfy.sh/my-feeds"]
+# batch_window = 60
+
+# Summarize each article with a command that reads its text on stdin and
+# prints the summary (title and link are in RSS_FUSE_TITLE and RSS_FUSE_LINK).
+# Summaries go in the frontmatter and in each feed's summary/ directory.
+# [summarizer]
+# command = "ollama run llama3 'Summarize this article in two sentences:'"
+# timeout = 120
+
+# Score articles by the keywords they mention (twice the weight in the
+# title) for triage. The score goes in the frontmatter; articles scoring at
+# least priority_at get "! " in front of their file name, and top/ links the
+# best top_articles of them, numbered by rank.
+# [scoring]
+# keywords = {{ rust = 5, "memory safety" = 3, crypto = -5 }}
+# priority_at = 10
+# top_articles = 20
 
 [cache]
 # Maximum cache size in MB (default: 100MB)
@@ -680,6 +2318,23 @@ max_size_mb = 100
 # Cache cleanup interval in seconds (default: 5 minutes)
 cleanup_interval = 300
 
+# Keep raw feed responses on disk so refreshes and restarts can reuse them,
+# honouring the server's Cache-Control max-age (default: true)
+http_cache = true
+
+# Reuse cached responses for this many seconds regardless of max-age
+# http_max_age = 300
+
+# Where feeds and articles are stored: memory:// keeps them for the
+# lifetime of the process, dir:///path keeps an archive of Markdown files
+# with JSON metadata there (default: memory://)
+# storage = "dir:///var/lib/rss-fuse"
+
+# Compression of the on-disk feed cache: zstd, lz4 or none (default: zstd).
+# compression_level applies to zstd only, 1 (fastest) to 22 (smallest)
+# compression = "zstd"
+# compression_level = 3
+
 [logging]
 # Log level: error, warn, info, debug, trace
 level = "info"
@@ -691,6 +2346,7 @@ log_to_file = true
 log_file = "logs/rss-fuse.log"
 "#, 
         chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        profile::cli_flag(),
         mount_point.display()
     );
     
@@ -718,6 +2374,57 @@ mod tests {
         assert!(config.contains("[feeds]"));
         assert!(config.contains("[fuse]"));
         assert!(config.contains(&mount_point.display().to_string()));
+        
+        // Every option the template sets is one the config knows
+        let problems = crate::config_check::check(&config, true);
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+    
+    #[test]
+    fn test_edit_until_valid() {
+        let temp_dir = TempDir::new().unwrap();
+        let draft = temp_dir.path().join("draft.toml");
+        let valid = "[feeds]\nnews = \"https://news.example/rss\"\n\n[settings]\n";
+        
+        // A broken edit is offered again, then fixed
+        let mut rounds = vec!["[feeds\n", valid].into_iter();
+        let mut retries = 0;
+        let edited = edit_until_valid(
+            &draft,
+            |path| Ok(fs::write(path, rounds.next().unwrap())?),
+            || { retries += 1; Ok(true) },
+        ).unwrap();
+        assert_eq!(edited.as_deref(), Some(valid));
+        assert_eq!(retries, 1);
+        
+        // Giving up on a broken edit saves nothing
+        let edited = edit_until_valid(
+            &draft,
+            |path| Ok(fs::write(path, "[settings]\nrefresh_interval = 0\n")?),
+            || Ok(false),
+        ).unwrap();
+        assert_eq!(edited, None);
+    }
+    
+    #[test]
+    fn test_persist_config_through_symlink() {
+        use std::os::unix::fs::PermissionsExt;
+        
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("dotfiles.toml");
+        let link = temp_dir.path().join("config.toml");
+        fs::write(&target, "old").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o644)).unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        
+        let resolved = fs::canonicalize(&link).unwrap();
+        let draft = tempfile::NamedTempFile::new_in(resolved.parent().unwrap()).unwrap();
+        fs::write(draft.path(), "new").unwrap();
+        persist_config(draft, &resolved).unwrap();
+        
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_to_string(&link).unwrap(), "new");
+        assert_eq!(fs::metadata(&target).unwrap().permissions().mode() & 0o777, 0o644);
     }
     
     #[tokio::test]
@@ -735,6 +2442,125 @@ mod tests {
         init(mount_point).await.unwrap();
     }
     
+    #[test]
+    fn test_parse_batch_line() {
+        assert_eq!(parse_batch_line(""), None);
+        assert_eq!(parse_batch_line("  # comment"), None);
+        assert_eq!(
+            parse_batch_line("https://example.com/feed.xml"),
+            Some(Ok(BatchFeedEntry { name: None, url: "https://example.com/feed.xml".to_string() }))
+        );
+        assert_eq!(
+            parse_batch_line("rust  https://blog.rust-lang.org/feed.xml"),
+            Some(Ok(BatchFeedEntry {
+                name: Some("rust".to_string()),
+                url: "https://blog.rust-lang.org/feed.xml".to_string(),
+            }))
+        );
+        assert!(matches!(parse_batch_line("a b c"), Some(Err(_))));
+        assert!(matches!(parse_batch_line("ftp://example.com/feed"), Some(Err(_))));
+    }
+    
+    #[test]
+    fn test_parse_feed_choice() {
+        assert_eq!(parse_feed_choice("1\n", 3), Some(0));
+        assert_eq!(parse_feed_choice(" 3 ", 3), Some(2));
+        assert_eq!(parse_feed_choice("0", 3), None);
+        assert_eq!(parse_feed_choice("4", 3), None);
+        assert_eq!(parse_feed_choice("", 3), None);
+    }
+    
+    #[test]
+    fn test_select_articles() {
+        let article = |title: &str| crate::feed::test_article(title, title, "feed");
+        let articles = vec![article("First"), article("Second")];
+
+        assert_eq!(select_articles(&articles, None).len(), 2);
+        assert_eq!(select_articles(&articles, Some("Second"))[0].title, "Second");
+        assert_eq!(select_articles(&articles, Some("First.md"))[0].title, "First");
+        assert_eq!(select_articles(&articles, Some(articles[1].id.as_str()))[0].title, "Second");
+        assert!(select_articles(&articles, Some("Third")).is_empty());
+    }
+    
+    #[test]
+    fn test_export_articles() {
+        let temp_dir = TempDir::new().unwrap();
+        let article = |title: &str, content: &str| {
+            let mut article = crate::feed::test_article(content, title, "feed");
+            article.content = Some(format!("<p>{}</p>", content));
+            article
+        };
+        let articles = [article("Same", "first"), article("Same", "second")];
+        let articles: Vec<&Article> = articles.iter().collect();
+        let extractor = ContentExtractor::new().unwrap();
+
+        let dir = temp_dir.path().join("feed");
+        let written = export_articles(&dir, "feed", &articles, ExportFormat::Markdown, &extractor, TextHtmlPolicy::Strip).unwrap();
+        assert_eq!(written, 2);
+        let first = fs::read_to_string(dir.join("Same.md")).unwrap();
+        assert!(first.starts_with("---\n") && first.contains("first"));
+        assert!(fs::read_to_string(dir.join("Same (2).md")).unwrap().contains("second"));
+
+        export_articles(&dir, "feed", &articles[..1], ExportFormat::Text, &extractor, TextHtmlPolicy::Strip).unwrap();
+        assert!(fs::read_to_string(dir.join("Same.txt")).unwrap().starts_with("Title: Same"));
+    }
+
+    #[test]
+    fn test_list_articles_output() {
+        let listed = |id: &str, title: &str, day: Option<u32>, read: bool| ListedArticle {
+            id: id.to_string(),
+            title: title.to_string(),
+            published: day.map(|day| chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2024, 3, day, 9, 0, 0).unwrap()),
+            read,
+            path: None,
+        };
+        let articles = vec![
+            listed("a", "beta", Some(1), false),
+            listed("b", "Alpha", Some(3), true),
+            listed("c", "gamma\tnews", None, false),
+        ];
+
+        let ids = |listing: Vec<ListedArticle>| listing.into_iter().map(|a| a.id).collect::<Vec<_>>();
+        assert_eq!(ids(order_listing(articles.clone(), false, ArticleSort::Date, None)), ["b", "a", "c"]);
+        assert_eq!(ids(order_listing(articles.clone(), false, ArticleSort::Title, Some(2))), ["b", "a"]);
+        assert_eq!(ids(order_listing(articles.clone(), true, ArticleSort::Date, None)), ["a", "c"]);
+
+        assert_eq!(listing_line(&articles[2]), "c\t-\tgamma news\t-");
+        let mounted = ListedArticle { path: Some(PathBuf::from("/mnt/rss/feed/beta.md")), ..articles[0].clone() };
+        assert_eq!(listing_line(&mounted), "a\t2024-03-01\tbeta\t/mnt/rss/feed/beta.md");
+    }
+
+    #[test]
+    fn test_build_search_query() {
+        let args = SearchArgs {
+            text: vec!["rust".to_string(), "async".to_string()],
+            feed: Some("tech".to_string()),
+            since: Some("2024-01-01".to_string()),
+            until: Some("2024-01-31".to_string()),
+            tags: vec!["ai".to_string()],
+            limit: 10,
+            ..Default::default()
+        };
+        let query = build_search_query(&args).unwrap();
+        assert_eq!(query.text.as_deref(), Some("rust async"));
+        assert_eq!(query.feed_name.as_deref(), Some("tech"));
+        assert_eq!(query.tags, ["ai"]);
+        assert_eq!(query.date_from.unwrap().to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        assert_eq!(query.date_to.unwrap().to_rfc3339(), "2024-01-31T23:59:59+00:00");
+        
+        let rfc = SearchArgs { since: Some("2024-03-15T10:00:00+02:00".to_string()), ..Default::default() };
+        assert_eq!(build_search_query(&rfc).unwrap().date_from.unwrap().to_rfc3339(), "2024-03-15T08:00:00+00:00");
+        
+        let bad = SearchArgs { since: Some("last week".to_string()), ..Default::default() };
+        assert!(matches!(build_search_query(&bad), Err(Error::Invalid(_))));
+    }
+    
+    #[test]
+    fn test_feed_name_from_url() {
+        assert_eq!(feed_name_from_url("https://blog.rust-lang.org/feed.xml"), "blog-rust-lang-org");
+        assert_eq!(feed_name_from_url("https://www.example.com/rss"), "example-com");
+    }
+    
     #[test]
     fn test_init_logging() {
         // Test that logging initialization doesn't panic
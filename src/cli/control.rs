@@ -0,0 +1,543 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{Mutex, Notify};
+use tracing::{debug, info, warn};
+
+use crate::cli::reload::{log_changes, ConfigWatcher, FeedChanges};
+use crate::error::{Error, Result};
+use crate::feed::scheduler::refresh_once;
+use crate::fuse::filesystem::{ListedArticle, RecentArticle, StatusReport};
+use crate::fuse::RssFuseFilesystem;
+use crate::profile;
+use crate::storage::{CleanupStats, FeedRepository, RefreshOutcome, Repository};
+use crate::sync::{self, LocalArticle, SyncReport};
+
+/// Where a running mount listens for commands:
+/// `$XDG_RUNTIME_DIR/rss-fuse.sock`, or a per-user file in the temp dir.
+/// A profile `work` uses `rss-fuse-work.sock` instead.
+pub fn socket_path() -> PathBuf {
+    let name = profile::dir_name();
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join(format!("{}.sock", name)),
+        None => std::env::temp_dir().join(format!("{}-{}.sock", name, unsafe { libc::getuid() })),
+    }
+}
+
+/// A command sent to the running mount, one JSON object per line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "kebab-case")]
+pub enum Request {
+    /// Fetch one feed, or all of them, now
+    Refresh { feed: Option<String> },
+    /// Report the state of every mounted feed
+    Status,
+    /// List the articles that arrived last, across all feeds
+    Recent { limit: usize },
+    /// List a feed's articles with the paths of their files
+    Articles { feed: String },
+    /// Re-read the config file and apply feed changes without waiting for
+    /// the next poll
+    Reload,
+    /// Pick up a feed's new URL from the config file and fetch from it now
+    SetFeedUrl { feed: String, url: String },
+    /// Apply the article retention limits now
+    Prune,
+    /// Write the in-memory cache to disk now
+    SaveCache,
+    /// Exchange subscriptions and read state with the `[sync]` reader now
+    Sync,
+    /// Star or unstar an article
+    Star { feed: String, article: String, starred: bool },
+    /// Mark articles of a feed read or unread
+    MarkRead { feed: String, articles: Vec<String>, read: bool },
+    /// Save the cache, unmount and exit, as on Ctrl+C
+    Stop,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "kebab-case")]
+pub enum Response {
+    Refreshed { feeds: Vec<RefreshResult> },
+    Status(StatusReport),
+    Recent { articles: Vec<RecentArticle> },
+    Articles { articles: Vec<ListedArticle> },
+    /// `None` when the mount already had the current config
+    Reloaded { changes: Option<FeedChanges> },
+    FeedUrlSet,
+    Pruned { stats: CleanupStats },
+    CacheSaved,
+    Synced { report: SyncReport },
+    Starred,
+    MarkedRead,
+    Stopping,
+    Error { message: String },
+}
+
+/// What refreshing a single feed did
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "kebab-case")]
+pub enum RefreshResult {
+    Updated { name: String, articles: usize, added: usize, updated: usize, removed: usize },
+    NotModified { name: String },
+    Failed { name: String, error: String },
+}
+
+/// Answers requests on behalf of a running mount
+pub struct ControlServer {
+    repo: Arc<Repository>,
+    filesystem: Arc<RssFuseFilesystem>,
+    watcher: Arc<Mutex<ConfigWatcher>>,
+    shutdown: Arc<Notify>,
+    mount_point: Option<PathBuf>,
+}
+
+impl ControlServer {
+    pub fn new(
+        repo: Arc<Repository>,
+        filesystem: Arc<RssFuseFilesystem>,
+        watcher: Arc<Mutex<ConfigWatcher>>,
+    ) -> Self {
+        Self { repo, filesystem, watcher, shutdown: Arc::new(Notify::new()), mount_point: None }
+    }
+
+    /// Report article paths below `mount_point` rather than relative ones
+    pub fn with_mount_point(mut self, mount_point: PathBuf) -> Self {
+        self.mount_point = Some(mount_point);
+        self
+    }
+
+    /// Signal `shutdown` when a `stop` request arrives
+    pub fn with_shutdown(mut self, shutdown: Arc<Notify>) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Listen on `path`, replacing a socket left behind by a mount that
+    /// exited uncleanly. Fails if another mount is still answering on it.
+    pub fn bind(path: &Path) -> Result<UnixListener> {
+        if path.exists() {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(Error::AlreadyExists(format!(
+                    "Another mount is listening on {}", path.display()
+                )));
+            }
+            std::fs::remove_file(path)?;
+        }
+        Ok(UnixListener::bind(path)?)
+    }
+
+    /// Serve connections until the task is dropped
+    pub async fn serve(self: Arc<Self>, listener: UnixListener) {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let server = Arc::clone(&self);
+                    tokio::spawn(async move {
+                        if let Err(e) = server.handle_connection(stream).await {
+                            debug!("Control connection closed: {}", e);
+                        }
+                    });
+                },
+                Err(e) => warn!("Failed to accept control connection: {}", e),
+            }
+        }
+    }
+
+    async fn handle_connection(&self, stream: UnixStream) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            let response = match serde_json::from_str::<Request>(&line) {
+                Ok(request) => self.handle(request).await,
+                Err(e) => Response::Error { message: format!("Malformed request: {}", e) },
+            };
+            let mut json = serde_json::to_vec(&response)?;
+            json.push(b'\n');
+            writer.write_all(&json).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn handle(&self, request: Request) -> Response {
+        debug!("Control request: {:?}", request);
+        match request {
+            Request::Refresh { feed } => self.refresh(feed).await,
+            Request::Status => {
+                // Collecting repository stats blocks on the runtime
+                let filesystem = Arc::clone(&self.filesystem);
+                match tokio::task::spawn_blocking(move || filesystem.status_report()).await {
+                    Ok(report) => Response::Status(report),
+                    Err(e) => Response::Error { message: e.to_string() },
+                }
+            },
+            Request::Recent { limit } => Response::Recent { articles: self.filesystem.recent_articles(limit) },
+            Request::Articles { feed } => match self.filesystem.feed_articles(&feed) {
+                Some(mut articles) => {
+                    if let Some(mount_point) = &self.mount_point {
+                        for article in &mut articles {
+                            article.path = article.path.take().map(|path| mount_point.join(path));
+                        }
+                    }
+                    Response::Articles { articles }
+                },
+                None => Response::Error { message: format!("Feed '{}' is not mounted", feed) },
+            },
+            Request::Prune => match prune_mount(&self.repo, &self.filesystem, &self.watcher).await {
+                Ok(stats) => Response::Pruned { stats },
+                Err(e) => Response::Error { message: format!("Prune failed: {}", e) },
+            },
+            Request::Sync => match sync_mount(&self.repo, &self.filesystem, &self.watcher).await {
+                Ok(report) => Response::Synced { report },
+                Err(e) => Response::Error { message: format!("Sync failed: {}", e) },
+            },
+            Request::Star { feed, article, starred } => match self.filesystem.set_article_starred(&feed, &article, starred) {
+                Ok(()) => Response::Starred,
+                Err(e) => Response::Error { message: format!("Star not changed: {}", e) },
+            },
+            Request::MarkRead { feed, articles, read } => {
+                let result = articles.iter()
+                    .try_for_each(|article| self.filesystem.set_article_read(&feed, article, read));
+                match result {
+                    Ok(()) => Response::MarkedRead,
+                    Err(e) => Response::Error { message: format!("Read state not changed: {}", e) },
+                }
+            },
+            Request::Stop => {
+                info!("Stopping on request");
+                self.shutdown.notify_one();
+                Response::Stopping
+            },
+            Request::SaveCache => match self.repo.save_cache().await {
+                Ok(()) => Response::CacheSaved,
+                Err(e) => Response::Error { message: format!("Cache not saved: {}", e) },
+            },
+            Request::Reload => {
+                let mut watcher = self.watcher.lock().await;
+                match watcher.reload() {
+                    Ok(changes) => {
+                        if let Some(changes) = &changes {
+                            log_changes(changes);
+                        }
+                        Response::Reloaded { changes }
+                    },
+                    Err(e) => Response::Error { message: format!("Config not reloaded: {}", e) },
+                }
+            },
+            Request::SetFeedUrl { feed, url } => {
+                // The reload refetches a moved feed straight away, without
+                // the validators of its old URL
+                let mut watcher = self.watcher.lock().await;
+                if let Err(e) = watcher.reload() {
+                    return Response::Error { message: format!("Config not reloaded: {}", e) };
+                }
+                match watcher.config().feeds.get(&feed) {
+                    Some(entry) if entry.url() == url => Response::FeedUrlSet,
+                    Some(entry) => Response::Error {
+                        message: format!("Feed '{}' is configured with {}, not {}", feed, entry.url(), url),
+                    },
+                    None => Response::Error { message: format!("Feed '{}' not found", feed) },
+                }
+            },
+        }
+    }
+
+    async fn refresh(&self, feed: Option<String>) -> Response {
+        let (feeds, concurrency): (Vec<(String, String)>, usize) = {
+            let watcher = self.watcher.lock().await;
+            let config = watcher.config();
+            let feeds = match feed {
+                Some(name) => match config.feeds.get(&name) {
+                    Some(entry) if !entry.enabled() => {
+                        return Response::Error { message: format!("Feed '{}' is disabled", name) };
+                    }
+                    Some(entry) => vec![(name, entry.url().to_string())],
+                    None => return Response::Error { message: format!("Feed '{}' not found", name) },
+                },
+                None => {
+                    let mut feeds: Vec<_> = config.feeds.iter()
+                        .filter(|(_, entry)| entry.enabled())
+                        .map(|(name, entry)| (name.clone(), entry.url().to_string()))
+                        .collect();
+                    feeds.sort();
+                    feeds
+                }
+            };
+            (feeds, config.settings.concurrent_fetches.max(1))
+        };
+
+        let results = stream::iter(feeds)
+            .map(|(name, url)| self.refresh_one(name, url))
+            .buffered(concurrency)
+            .collect()
+            .await;
+        Response::Refreshed { feeds: results }
+    }
+
+    async fn refresh_one(&self, name: String, url: String) -> RefreshResult {
+        info!("Refreshing {} on request", name);
+        match self.repo.refresh_feed_if_modified(&name, &url).await {
+            Ok(RefreshOutcome::Updated(feed, result)) => {
+                let articles = feed.articles.len();
+                match self.filesystem.add_feed_from_cache(feed, false) {
+                    Ok(()) => RefreshResult::Updated {
+                        name,
+                        articles,
+                        added: result.articles_added,
+                        updated: result.articles_updated,
+                        removed: result.articles_removed,
+                    },
+                    Err(e) => RefreshResult::Failed { name, error: e.to_string() },
+                }
+            },
+            Ok(RefreshOutcome::NotModified) => RefreshResult::NotModified { name },
+            Err(e) => RefreshResult::Failed { name, error: e.to_string() },
+        }
+    }
+}
+
+/// Apply the retention limits to a running mount's repository, show the
+/// trimmed feeds and delete the summaries of articles no longer kept
+pub async fn prune_mount(
+    repo: &Repository,
+    filesystem: &RssFuseFilesystem,
+    watcher: &Mutex<ConfigWatcher>,
+) -> Result<CleanupStats> {
+    let stats = repo.prune().await?;
+    let names: Vec<String> = watcher.lock().await.config().feeds.keys().cloned().collect();
+    let mut kept = Vec::new();
+    for name in names {
+        if let Some(feed) = repo.get_feed(&name).await? {
+            kept.extend(feed.articles.iter().map(|article| article.id.clone()));
+            if stats.articles_removed > 0 {
+                filesystem.add_feed_from_cache(feed, false)?;
+            }
+        }
+    }
+    let summaries = filesystem.retain_summaries(kept.iter().map(String::as_str))?;
+    if summaries > 0 {
+        debug!("Deleted {} summaries of pruned articles", summaries);
+    }
+    Ok(stats)
+}
+
+/// Prune a running mount every `interval` until the task is dropped
+pub async fn prune_periodically(
+    repo: Arc<Repository>,
+    filesystem: Arc<RssFuseFilesystem>,
+    watcher: Arc<Mutex<ConfigWatcher>>,
+    interval: Duration,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+        match prune_mount(&repo, &filesystem, &watcher).await {
+            Ok(stats) if stats.articles_removed > 0 => {
+                info!("Retention removed {} articles ({} bytes)", stats.articles_removed, stats.bytes_freed);
+            },
+            Ok(_) => debug!("Retention check: nothing to remove"),
+            Err(e) => warn!("Failed to prune articles: {}", e),
+        }
+    }
+}
+
+/// Render a running mount's status files afresh every `interval` until the
+/// task is dropped
+pub async fn refresh_status_periodically(filesystem: Arc<RssFuseFilesystem>, interval: Duration) {
+    loop {
+        filesystem.refresh_status().await;
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Refresh the feeds `requests` names, one at a time, until the sender is
+/// dropped. Feeds no longer configured or disabled since are skipped.
+pub async fn refresh_requested(
+    repo: Arc<Repository>,
+    filesystem: Arc<RssFuseFilesystem>,
+    watcher: Arc<Mutex<ConfigWatcher>>,
+    mut requests: tokio::sync::mpsc::UnboundedReceiver<String>,
+) {
+    while let Some(name) = requests.recv().await {
+        let url = watcher.lock().await.config().feeds.get(&name)
+            .filter(|entry| entry.enabled())
+            .map(|entry| entry.url().to_string());
+        if let Some(url) = url {
+            refresh_once(&repo, filesystem.as_ref(), &name, &url).await;
+        }
+    }
+}
+
+/// Sync a running mount with the `[sync]` reader: add the account's new
+/// subscriptions to the config file (the mount picks them up by reloading
+/// it) and exchange read and starred state for the feeds it already has
+pub async fn sync_mount(
+    repo: &Repository,
+    filesystem: &RssFuseFilesystem,
+    watcher: &Mutex<ConfigWatcher>,
+) -> Result<SyncReport> {
+    let (config, config_file) = {
+        let watcher = watcher.lock().await;
+        (watcher.config().clone(), watcher.path().to_path_buf())
+    };
+    let settings = config.sync.as_ref()
+        .ok_or_else(|| Error::Config("No [sync] section in the config".to_string()))?;
+    let client = sync::connect(settings, &config.network).await?;
+
+    let mut report = SyncReport::default();
+    if settings.import_subscriptions {
+        report.feeds_added = sync::import_subscriptions(&client, &config_file).await?;
+        if !report.feeds_added.is_empty() {
+            if let Some(changes) = watcher.lock().await.reload()? {
+                log_changes(&changes);
+            }
+        }
+    }
+
+    let mut local = Vec::new();
+    for name in config.feeds.keys() {
+        if let Some(feed) = repo.get_feed(name).await? {
+            local.extend(feed.articles.iter().map(|article| LocalArticle::new(
+                name,
+                article,
+                article.read || repo.is_article_read(&article.id),
+                article.starred || repo.is_article_starred(&article.id),
+            )));
+        }
+    }
+    let plan = sync::sync_state(&client, settings.max_items, &local, repo.url_normalizer()).await?;
+    for (feed, id) in &plan.mark_local {
+        filesystem.mark_article_read_by_id(feed, id)?;
+    }
+    for (feed, id) in &plan.star_local {
+        filesystem.set_article_starred(feed, id, true)?;
+    }
+
+    report.record(&plan);
+    Ok(report)
+}
+
+/// Sync a running mount every `interval` until the task is dropped
+pub async fn sync_periodically(
+    repo: Arc<Repository>,
+    filesystem: Arc<RssFuseFilesystem>,
+    watcher: Arc<Mutex<ConfigWatcher>>,
+    interval: Duration,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+        match sync_mount(&repo, &filesystem, &watcher).await {
+            Ok(report) => debug!(
+                "Synced: {} feeds added, {} marked read here, {} marked read remotely, {} starred here, {} starred remotely",
+                report.feeds_added.len(), report.marked_read_locally, report.marked_read_remotely,
+                report.starred_locally, report.starred_remotely
+            ),
+            Err(e) => warn!("Failed to sync with the reader: {}", e),
+        }
+    }
+}
+
+/// Send `request` to the running mount. Returns `None` if no mount is
+/// listening, so the caller can fall back to working on its own.
+pub async fn send(request: &Request) -> Result<Option<Response>> {
+    send_to(&socket_path(), request).await
+}
+
+pub async fn send_to(path: &Path, request: &Request) -> Result<Option<Response>> {
+    let stream = match UnixStream::connect(path).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            debug!("No mount listening on {}: {}", path.display(), e);
+            return Ok(None);
+        }
+    };
+
+    let (reader, mut writer) = stream.into_split();
+    let mut json = serde_json::to_vec(request)?;
+    json.push(b'\n');
+    writer.write_all(&json).await?;
+
+    let line = BufReader::new(reader).lines().next_line().await?
+        .ok_or_else(|| Error::InvalidState("Mount closed the control connection".to_string()))?;
+    match serde_json::from_str(&line)? {
+        Response::Error { message } => Err(Error::InvalidState(message)),
+        response => Ok(Some(response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::reload::FeedScheduler;
+    use crate::config::{Config, FeedEntry};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_request_format() {
+        let json = serde_json::to_string(&Request::Refresh { feed: Some("news".to_string()) }).unwrap();
+        assert_eq!(json, r#"{"request":"refresh","feed":"news"}"#);
+        assert_eq!(serde_json::from_str::<Request>(r#"{"request":"status"}"#).unwrap(), Request::Status);
+    }
+
+    #[tokio::test]
+    async fn test_control_socket() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+        let socket = temp_dir.path().join("control.sock");
+        Config::default().save(&config_file).unwrap();
+
+        let repo = Arc::new(crate::storage::RepositoryFactory::memory());
+        let filesystem = Arc::new(RssFuseFilesystem::new());
+        let scheduler = FeedScheduler::new(repo.clone(), Arc::clone(&filesystem));
+        let watcher = ConfigWatcher::new(config_file.clone(), Config::load(&config_file).unwrap(), scheduler);
+        let server = Arc::new(ControlServer::new(repo, Arc::clone(&filesystem), Arc::new(Mutex::new(watcher))));
+        tokio::spawn(server.serve(ControlServer::bind(&socket).unwrap()));
+
+        // A second mount must not steal the socket
+        assert!(ControlServer::bind(&socket).is_err());
+
+        let mut config = Config::load(&config_file).unwrap();
+        config.feeds.insert("news".to_string(), FeedEntry::Url("http://127.0.0.1:9/feed".to_string()));
+        config.save(&config_file).unwrap();
+        match send_to(&socket, &Request::Reload).await.unwrap() {
+            Some(Response::Reloaded { changes: Some(changes) }) => assert_eq!(changes.added, vec!["news"]),
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert!(filesystem.get_node_by_name(1, "news").is_some());
+
+        match send_to(&socket, &Request::Refresh { feed: Some("news".to_string()) }).await.unwrap() {
+            Some(Response::Refreshed { feeds }) => assert!(matches!(feeds[..], [RefreshResult::Failed { .. }])),
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert!(send_to(&socket, &Request::Refresh { feed: Some("missing".to_string()) }).await.is_err());
+
+        // A new URL is confirmed once the mount runs with it
+        config.feeds.insert("news".to_string(), FeedEntry::Url("http://127.0.0.1:9/moved".to_string()));
+        config.save(&config_file).unwrap();
+        let moved = Request::SetFeedUrl { feed: "news".to_string(), url: "http://127.0.0.1:9/moved".to_string() };
+        assert!(matches!(send_to(&socket, &moved).await.unwrap(), Some(Response::FeedUrlSet)));
+        let stale = Request::SetFeedUrl { feed: "news".to_string(), url: "http://127.0.0.1:9/feed".to_string() };
+        assert!(send_to(&socket, &stale).await.is_err());
+
+        match send_to(&socket, &Request::Recent { limit: 10 }).await.unwrap() {
+            Some(Response::Recent { articles }) => assert!(articles.is_empty()),
+            other => panic!("unexpected response: {:?}", other),
+        }
+
+        let shutdown = Arc::new(Notify::new());
+        let stopping = Arc::clone(&shutdown);
+        let stopped = tokio::spawn(async move { stopping.notified().await });
+        let repo = Arc::new(crate::storage::RepositoryFactory::memory());
+        let scheduler = FeedScheduler::new(repo.clone(), Arc::clone(&filesystem));
+        let watcher = ConfigWatcher::new(config_file.clone(), Config::load(&config_file).unwrap(), scheduler);
+        let server = ControlServer::new(repo, filesystem, Arc::new(Mutex::new(watcher))).with_shutdown(shutdown);
+        assert!(matches!(server.handle(Request::Stop).await, Response::Stopping));
+        stopped.await.unwrap();
+
+        // Nothing listening: callers fall back to working alone
+        assert!(send_to(&temp_dir.path().join("none.sock"), &Request::Status).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,296 @@
+//! `rss-fuse doctor`: check everything a mount depends on (FUSE, the
+//! config, the cache directory, each feed's server, existing mounts) and
+//! say how to fix what is broken
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use futures::stream::{self, StreamExt};
+
+use crate::config::Config;
+use crate::feed::fetcher::FeedFetcher;
+use crate::fuse::operations::active_mounts;
+use crate::fuse::FuseOperations;
+
+/// Feeds answering slower than this get a warning
+const SLOW_FEED: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Failure,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub severity: Severity,
+    pub detail: String,
+    /// What to do about a warning or failure
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), severity: Severity::Ok, detail: detail.into(), fix: None }
+    }
+
+    fn warning(name: impl Into<String>, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name: name.into(), severity: Severity::Warning, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    fn failure(name: impl Into<String>, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name: name.into(), severity: Severity::Failure, detail: detail.into(), fix: Some(fix.into()) }
+    }
+}
+
+/// Run every check, printing them by section. Returns the number of failures.
+pub async fn run(config_file: &Path) -> usize {
+    let (config_check, config) = check_config(config_file);
+    let mut sections = vec![
+        ("🔌 FUSE", vec![
+            check_fuse_device(Path::new("/dev/fuse")),
+            check_fusermount(),
+            check_allow_other(config.as_ref(), Path::new("/etc/fuse.conf")),
+        ]),
+        ("⚙️  Configuration", vec![config_check]),
+        ("💾 Cache", vec![check_writable(
+            "Cache directory",
+            &dirs::cache_dir().unwrap_or_else(|| PathBuf::from(".")).join(crate::profile::dir_name()),
+        )]),
+    ];
+    if let Some(config) = &config {
+        sections.push(("🌐 Feeds", check_feeds(config).await));
+    }
+    sections.push(("🗂️  Mounts", check_mounts()));
+
+    println!("🩺 RSS-FUSE Doctor");
+    println!("==================");
+    for (title, checks) in &sections {
+        println!("\n{}:", title);
+        for check in checks {
+            print_check(check);
+        }
+    }
+
+    let count = |severity| sections.iter().flat_map(|(_, checks)| checks).filter(|c| c.severity == severity).count();
+    let (failures, warnings) = (count(Severity::Failure), count(Severity::Warning));
+    println!();
+    if failures == 0 && warnings == 0 {
+        println!("✅ Everything looks good");
+    } else {
+        println!("{} failed, {} warning(s)", failures, warnings);
+    }
+    failures
+}
+
+fn print_check(check: &Check) {
+    let icon = match check.severity {
+        Severity::Ok => "✅",
+        Severity::Warning => "⚠️ ",
+        Severity::Failure => "❌",
+    };
+    println!("   {} {}: {}", icon, check.name, check.detail);
+    if let Some(fix) = &check.fix {
+        println!("      → {}", fix);
+    }
+}
+
+/// The FUSE device exists and this user can open it
+pub fn check_fuse_device(device: &Path) -> Check {
+    const NAME: &str = "FUSE device";
+    if !device.exists() {
+        return Check::failure(NAME, format!("{} not found", device.display()),
+            "Load the module (sudo modprobe fuse) or install FUSE (e.g. sudo apt install fuse3)");
+    }
+    match std::fs::OpenOptions::new().read(true).write(true).open(device) {
+        Ok(_) => Check::ok(NAME, format!("{} is usable", device.display())),
+        Err(e) => Check::failure(NAME, format!("cannot open {}: {}", device.display(), e),
+            format!("Give your user read/write access to {} (often via the 'fuse' group)", device.display())),
+    }
+}
+
+/// A `fusermount` binary is on `PATH`, for mounting and unmounting as a user
+pub fn check_fusermount() -> Check {
+    const NAME: &str = "fusermount";
+    match ["fusermount3", "fusermount"].into_iter().find_map(find_in_path) {
+        Some(path) => Check::ok(NAME, path.display().to_string()),
+        None => Check::failure(NAME, "not found on PATH", "Install FUSE (e.g. sudo apt install fuse3)"),
+    }
+}
+
+fn find_in_path(binary: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).map(|dir| dir.join(binary)).find(|candidate| candidate.is_file())
+}
+
+/// `allow_other` needs `user_allow_other` in `/etc/fuse.conf`
+pub fn check_allow_other(config: Option<&Config>, fuse_conf: &Path) -> Check {
+    const NAME: &str = "allow_other";
+    let enabled = std::fs::read_to_string(fuse_conf).is_ok_and(|content| allows_other(&content));
+    match (config.is_some_and(|config| config.fuse.allow_other), enabled) {
+        (_, true) => Check::ok(NAME, format!("user_allow_other is set in {}", fuse_conf.display())),
+        (false, false) => Check::ok(NAME, "not used"),
+        (true, false) => Check::failure(NAME, format!("[fuse] allow_other is on but {} lacks user_allow_other", fuse_conf.display()),
+            format!("Add a 'user_allow_other' line to {} or set allow_other = false", fuse_conf.display())),
+    }
+}
+
+/// Whether a `fuse.conf` enables `user_allow_other`
+fn allows_other(fuse_conf: &str) -> bool {
+    fuse_conf.lines().any(|line| line.split('#').next().unwrap_or("").trim() == "user_allow_other")
+}
+
+/// The config file loads and validates
+pub fn check_config(config_file: &Path) -> (Check, Option<Config>) {
+    const NAME: &str = "Config file";
+    if !config_file.exists() {
+        return (Check::failure(NAME, format!("{} not found", config_file.display()),
+            "Run 'rss-fuse init <mount-point>'"), None);
+    }
+    match Config::load(config_file) {
+        Ok(config) => {
+            let detail = format!("{} ({} feeds)", config_file.display(), config.feeds.len());
+            let check = if config.feeds.is_empty() {
+                Check::warning(NAME, detail, "Add feeds with 'rss-fuse add-feed <name> <url>'")
+            } else {
+                Check::ok(NAME, detail)
+            };
+            (check, Some(config))
+        }
+        Err(e) => (Check::failure(NAME, format!("{}: {}", config_file.display(), e),
+            format!("Fix the error in {}", config_file.display())), None),
+    }
+}
+
+/// `dir` exists (or can be created) and accepts new files
+pub fn check_writable(name: &str, dir: &Path) -> Check {
+    let probe = dir.join(format!(".doctor-{}", std::process::id()));
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&probe, b"ok"))
+        .and_then(|_| std::fs::remove_file(&probe));
+    match result {
+        Ok(()) => Check::ok(name, format!("{} is writable", dir.display())),
+        Err(e) => Check::failure(name, format!("cannot write to {}: {}", dir.display(), e),
+            format!("Check the permissions and free space of {}", dir.display())),
+    }
+}
+
+/// Reach each feed's server with its network settings and time the answer
+pub async fn check_feeds(config: &Config) -> Vec<Check> {
+    let mut feeds: Vec<_> = config.feeds.iter().collect();
+    feeds.sort_by(|a, b| a.0.cmp(b.0));
+
+    stream::iter(feeds)
+        .map(|(name, entry)| async move {
+            let network = match entry.network(&config.network).and_then(|network| match network {
+                Some(network) => Ok(network),
+                None => config.network.options(),
+            }) {
+                Ok(network) => network,
+                Err(e) => return Check::failure(name, e.to_string(), "Fix the feed's network settings"),
+            };
+            let fetcher = match FeedFetcher::new().with_network(network) {
+                Ok(fetcher) => fetcher,
+                Err(e) => return Check::failure(name, e.to_string(), "Fix the [network] settings"),
+            };
+
+            let started = Instant::now();
+            let result = fetcher.check_feed_availability(entry.url()).await;
+            feed_check(name, entry.url(), result.map(|info| info.status_code), started.elapsed())
+        })
+        .buffered(config.settings.concurrent_fetches.max(1))
+        .collect()
+        .await
+}
+
+fn feed_check(name: &str, url: &str, status: crate::error::Result<u16>, latency: Duration) -> Check {
+    let millis = latency.as_millis();
+    match status {
+        Ok(status) if (200..400).contains(&status) && latency > SLOW_FEED => Check::warning(
+            name, format!("HTTP {} in {}ms", status, millis),
+            "The server is slow; raise refresh_interval if refreshes time out",
+        ),
+        Ok(status) if (200..400).contains(&status) => Check::ok(name, format!("HTTP {} in {}ms", status, millis)),
+        Ok(status) => Check::failure(name, format!("HTTP {} from {}", status, url),
+            format!("Check the URL, or point the feed elsewhere with 'rss-fuse set-feed-url {} <url>'", name)),
+        Err(e) => Check::failure(name, format!("{} ({})", e, url),
+            "Check your connection and [network] proxy settings, or whether the site is down"),
+    }
+}
+
+/// Every RSS-FUSE mount still answers
+pub fn check_mounts() -> Vec<Check> {
+    let fuse_ops = FuseOperations::new();
+    let mounts = active_mounts();
+    if mounts.is_empty() {
+        return vec![Check::ok("Mounts", "none active")];
+    }
+    mounts.into_iter()
+        .map(|mount_point| {
+            let name = mount_point.display().to_string();
+            if fuse_ops.is_mount_stale(&mount_point) {
+                Check::failure(name.clone(), "stale: mounted but not responding",
+                    format!("rss-fuse unmount --force {}", name))
+            } else {
+                Check::ok(name, "mounted and responsive")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows_other() {
+        assert!(allows_other("# mount_max = 1000\nuser_allow_other\n"));
+        assert!(allows_other("  user_allow_other  # for rss-fuse"));
+        assert!(!allows_other("#user_allow_other\n"));
+        assert!(!allows_other(""));
+    }
+
+    #[test]
+    fn test_check_writable_and_config() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(check_writable("Cache", &dir.path().join("cache")).severity, Severity::Ok);
+        assert_eq!(check_writable("Cache", &dir.path().join("nested/below")).severity, Severity::Ok);
+
+        let file = dir.path().join("plain");
+        std::fs::write(&file, "").unwrap();
+        let blocked = check_writable("Cache", &file.join("cache"));
+        assert_eq!(blocked.severity, Severity::Failure);
+        assert!(blocked.fix.is_some());
+
+        let (check, config) = check_config(&dir.path().join("missing.toml"));
+        assert_eq!(check.severity, Severity::Failure);
+        assert!(config.is_none());
+
+        let config_file = dir.path().join("config.toml");
+        std::fs::write(&config_file, "[feeds]\nnews = \"not a url\"\n\n[settings]\n").unwrap();
+        assert_eq!(check_config(&config_file).0.severity, Severity::Failure);
+        std::fs::write(&config_file, "[feeds]\nnews = \"https://news.example/rss\"\n\n[settings]\n").unwrap();
+        let (check, config) = check_config(&config_file);
+        assert_eq!(check.severity, Severity::Ok);
+
+        let mut config = config.unwrap();
+        config.fuse.allow_other = true;
+        let missing = dir.path().join("fuse.conf");
+        assert_eq!(check_allow_other(Some(&config), &missing).severity, Severity::Failure);
+        std::fs::write(&missing, "user_allow_other\n").unwrap();
+        assert_eq!(check_allow_other(Some(&config), &missing).severity, Severity::Ok);
+    }
+
+    #[test]
+    fn test_feed_check() {
+        let fast = Duration::from_millis(80);
+        assert_eq!(feed_check("news", "https://n.example", Ok(200), fast).severity, Severity::Ok);
+        assert_eq!(feed_check("news", "https://n.example", Ok(200), SLOW_FEED * 2).severity, Severity::Warning);
+        assert_eq!(feed_check("news", "https://n.example", Ok(404), fast).severity, Severity::Failure);
+        let down = feed_check("news", "https://n.example", Err(crate::error::Error::Timeout("slow".to_string())), fast);
+        assert_eq!(down.severity, Severity::Failure);
+        assert!(down.detail.contains("https://n.example"));
+    }
+}
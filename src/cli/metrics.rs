@@ -0,0 +1,263 @@
+//! `[metrics] listen`: a plain HTTP endpoint serving feed health, repository
+//! counters and FUSE operation counts in the Prometheus text format
+
+use std::fmt::Write as _;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::debug;
+
+use crate::fuse::filesystem::StatusReport;
+use crate::fuse::stats::LATENCY_BUCKETS_MS;
+use crate::fuse::RssFuseFilesystem;
+use crate::storage::{Repository, RepositoryMetrics};
+
+/// Longest request head read before giving up on a client
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Answer `GET /metrics` until the task is dropped
+pub async fn serve(listener: TcpListener, repo: Arc<Repository>, filesystem: Arc<RssFuseFilesystem>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let (repo, filesystem) = (Arc::clone(&repo), Arc::clone(&filesystem));
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, repo, filesystem).await {
+                        debug!("Metrics connection closed: {}", e);
+                    }
+                });
+            }
+            Err(e) => debug!("Metrics endpoint accept failed: {}", e),
+        }
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    repo: Arc<Repository>,
+    filesystem: Arc<RssFuseFilesystem>,
+) -> std::io::Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+
+    let request_line = String::from_utf8_lossy(&head).lines().next().unwrap_or("").to_string();
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next().map(|target| target.split('?').next().unwrap_or(""))) {
+        (Some("GET"), Some("/metrics")) => {
+            // Collecting repository stats blocks on the runtime
+            let report = tokio::task::spawn_blocking(move || {
+                render(&filesystem.status_report(), Some(&repo.metrics()))
+            }).await;
+            match report {
+                Ok(body) => ("200 OK", body),
+                Err(e) => ("500 Internal Server Error", format!("{}\n", e)),
+            }
+        }
+        (Some("GET"), _) => ("404 Not Found", "Metrics are served at /metrics\n".to_string()),
+        _ => ("405 Method Not Allowed", "Only GET is supported\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, CONTENT_TYPE, body.len(), body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// The exposition served at `/metrics`
+pub fn render(report: &StatusReport, repo: Option<&RepositoryMetrics>) -> String {
+    let mut out = Exposition::default();
+
+    out.family("rss_fuse_feed_up", "gauge", "1 unless the feed's last refresh failed");
+    for feed in &report.feeds {
+        out.sample("rss_fuse_feed_up", &[("feed", &feed.name)], if feed.status == "error" { 0.0 } else { 1.0 });
+    }
+    out.family("rss_fuse_feed_articles", "gauge", "Articles currently listed for the feed");
+    for feed in &report.feeds {
+        out.sample("rss_fuse_feed_articles", &[("feed", &feed.name)], feed.articles as f64);
+    }
+    out.family("rss_fuse_feed_unread", "gauge", "Unread articles of the feed");
+    for feed in &report.feeds {
+        out.sample("rss_fuse_feed_unread", &[("feed", &feed.name)], feed.unread as f64);
+    }
+    out.family("rss_fuse_feed_consecutive_failures", "gauge", "Refreshes failed in a row");
+    for feed in &report.feeds {
+        out.sample("rss_fuse_feed_consecutive_failures", &[("feed", &feed.name)], feed.consecutive_failures as f64);
+    }
+    out.family("rss_fuse_feed_fetch_duration_seconds", "gauge", "Duration of the feed's most recent fetch");
+    for feed in &report.feeds {
+        if let Some(ms) = feed.fetch_latency_ms {
+            out.sample("rss_fuse_feed_fetch_duration_seconds", &[("feed", &feed.name)], ms as f64 / 1000.0);
+        }
+    }
+    out.family("rss_fuse_feed_last_success_timestamp_seconds", "gauge", "When the feed last refreshed successfully");
+    for feed in &report.feeds {
+        if let Some(at) = feed.last_success {
+            out.sample("rss_fuse_feed_last_success_timestamp_seconds", &[("feed", &feed.name)], at.timestamp() as f64);
+        }
+    }
+
+    if let Some(cache) = &report.cache {
+        out.gauge("rss_fuse_cache_entries", "Articles held in the in-memory cache", cache.entries as f64);
+        out.gauge("rss_fuse_cache_memory_bytes", "Estimated size of the in-memory cache", cache.memory_usage_bytes as f64);
+        out.gauge("rss_fuse_stored_feeds", "Feeds in persistent storage", cache.stored_feeds as f64);
+        out.gauge("rss_fuse_stored_articles", "Articles in persistent storage", cache.stored_articles as f64);
+        out.gauge("rss_fuse_storage_size_bytes", "Size of persistent storage", cache.storage_size_bytes as f64);
+    }
+
+    if let Some(repo) = repo {
+        out.counter("rss_fuse_repository_cache_hits_total", "Feed lookups answered from the cache", repo.cache_hits);
+        out.counter("rss_fuse_repository_cache_misses_total", "Feed lookups that missed the cache", repo.cache_misses);
+        out.counter("rss_fuse_repository_storage_reads_total", "Reads from persistent storage", repo.storage_reads);
+        out.counter("rss_fuse_repository_storage_writes_total", "Writes to persistent storage", repo.storage_writes);
+        out.counter("rss_fuse_repository_feed_refreshes_total", "Feed refreshes performed", repo.feed_refreshes);
+        out.counter("rss_fuse_repository_operations_total", "Timed repository operations", repo.operation_count);
+        out.family("rss_fuse_repository_operation_seconds_total", "counter", "Time spent in timed repository operations");
+        out.sample("rss_fuse_repository_operation_seconds_total", &[], repo.total_response_time_ms as f64 / 1000.0);
+    }
+
+    out.family("rss_fuse_fuse_operations_total", "counter", "FUSE callbacks served, by operation");
+    for op in &report.operations {
+        out.sample("rss_fuse_fuse_operations_total", &[("op", &op.op)], op.count as f64);
+    }
+    const DURATION: &str = "rss_fuse_fuse_operation_duration_seconds";
+    out.family(DURATION, "histogram", "Time taken by FUSE callbacks, by operation");
+    for op in &report.operations {
+        for (bound, within) in LATENCY_BUCKETS_MS.iter().zip(&op.buckets) {
+            let le = (bound / 1000.0).to_string();
+            out.sample(&format!("{}_bucket", DURATION), &[("op", &op.op), ("le", &le)], *within as f64);
+        }
+        out.sample(&format!("{}_bucket", DURATION), &[("op", &op.op), ("le", "+Inf")], op.count as f64);
+        out.sample(&format!("{}_sum", DURATION), &[("op", &op.op)], op.total_ms / 1000.0);
+        out.sample(&format!("{}_count", DURATION), &[("op", &op.op)], op.count as f64);
+    }
+
+    out.text
+}
+
+/// Text being built in the Prometheus exposition format
+#[derive(Default)]
+struct Exposition {
+    text: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.text, "# HELP {} {}", name, help);
+        let _ = writeln!(self.text, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.text.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels.iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+                .collect();
+            let _ = write!(self.text, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.text, " {}", value);
+    }
+
+    fn gauge(&mut self, name: &str, help: &str, value: f64) {
+        self.family(name, "gauge", help);
+        self.sample(name, &[], value);
+    }
+
+    fn counter(&mut self, name: &str, help: &str, value: u64) {
+        self.family(name, "counter", help);
+        self.sample(name, &[], value as f64);
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuse::filesystem::FeedReport;
+    use crate::fuse::stats::OpReport;
+    use chrono::Utc;
+
+    fn feed_report(name: &str, status: &str) -> FeedReport {
+        FeedReport {
+            name: name.to_string(),
+            url: None,
+            title: None,
+            status: status.to_string(),
+            articles: 12,
+            unread: 3,
+            last_updated: None,
+            last_success: None,
+            last_failure: None,
+            fetch_latency_ms: Some(250),
+            consecutive_failures: 0,
+            error: None,
+            last_refresh: None,
+            status_history: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let report = StatusReport {
+            generated_at: Utc::now(),
+            feeds: vec![feed_report("tech/hn", "active"), feed_report("odd\"name", "error")],
+            cache: None,
+            operations: vec![OpReport {
+                op: "lookup".to_string(),
+                count: 40,
+                total_ms: 12.0,
+                max_ms: 3.0,
+                buckets: vec![30, 38, 39, 40, 40, 40, 40, 40, 40, 40],
+            }],
+        };
+        let repo = RepositoryMetrics { cache_hits: 7, total_response_time_ms: 1500, ..Default::default() };
+
+        let text = render(&report, Some(&repo));
+        assert!(text.contains("# TYPE rss_fuse_feed_up gauge\n"));
+        assert!(text.contains("rss_fuse_feed_up{feed=\"tech/hn\"} 1\n"));
+        assert!(text.contains("rss_fuse_feed_up{feed=\"odd\\\"name\"} 0\n"));
+        assert!(text.contains("rss_fuse_feed_fetch_duration_seconds{feed=\"tech/hn\"} 0.25\n"));
+        assert!(text.contains("rss_fuse_repository_cache_hits_total 7\n"));
+        assert!(text.contains("rss_fuse_repository_operation_seconds_total 1.5\n"));
+        assert!(text.contains("rss_fuse_fuse_operations_total{op=\"lookup\"} 40\n"));
+        assert!(text.contains("# TYPE rss_fuse_fuse_operation_duration_seconds histogram\n"));
+        assert!(text.contains("rss_fuse_fuse_operation_duration_seconds_bucket{op=\"lookup\",le=\"0.0005\"} 38\n"));
+        assert!(text.contains("rss_fuse_fuse_operation_duration_seconds_bucket{op=\"lookup\",le=\"+Inf\"} 40\n"));
+        assert!(text.contains("rss_fuse_fuse_operation_duration_seconds_sum{op=\"lookup\"} 0.012\n"));
+        assert!(!text.contains("rss_fuse_cache_entries"));
+    }
+
+    #[tokio::test]
+    async fn test_serve() {
+        let fs = Arc::new(RssFuseFilesystem::new());
+        let repo = Arc::new(crate::storage::RepositoryFactory::memory());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, repo, fs));
+
+        let get = |path: &'static str| async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream.write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+        let response = get("/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("rss_fuse_repository_feed_refreshes_total 0"));
+        assert!(get("/").await.starts_with("HTTP/1.1 404"));
+    }
+}
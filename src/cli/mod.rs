@@ -1,7 +1,12 @@
 pub mod commands;
+pub mod control;
+pub mod doctor;
+pub mod metrics;
 pub mod mount;
+pub mod reload;
+pub mod watch;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use crate::error::Result;
 use std::path::PathBuf;
 
@@ -18,6 +23,11 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub config: Option<PathBuf>,
     
+    /// Separate feed set with its own config, cache, logs and control
+    /// socket, so several can be mounted at once
+    #[arg(long, global = true, env = "RSS_FUSE_PROFILE")]
+    pub profile: Option<String>,
+    
     /// Enable verbose output
     #[arg(short, long, global = true)]
     pub verbose: bool,
@@ -59,6 +69,11 @@ pub enum Commands {
         /// Override file manager command
         #[arg(long)]
         file_manager: Option<String>,
+        
+        /// Stop the rss-fuse using this cache or mount point and take its
+        /// place
+        #[arg(long)]
+        takeover: bool,
     },
     
     /// Unmount the filesystem
@@ -71,13 +86,44 @@ pub enum Commands {
         force: bool,
     },
     
+    /// Unmount and mount again at the same point with the same options, e.g.
+    /// after editing the config or upgrading; the running mount saves its
+    /// cache first
+    Remount {
+        /// Mount point directory
+        mount_point: PathBuf,
+        
+        /// Force the unmount if the mount point is busy
+        #[arg(short, long)]
+        force: bool,
+        
+        /// Disable automatic file manager launch
+        #[arg(long)]
+        no_auto_open: bool,
+        
+        /// Override file manager command
+        #[arg(long)]
+        file_manager: Option<String>,
+    },
+    
     /// Add a new RSS feed
     AddFeed {
         /// Feed name
-        name: String,
+        #[arg(required_unless_present = "stdin")]
+        name: Option<String>,
         
         /// Feed URL
-        url: String,
+        #[arg(required_unless_present = "stdin")]
+        url: Option<String>,
+        
+        /// Read `name url` pairs (or bare URLs) line-by-line from stdin
+        #[arg(long, conflicts_with_all = ["name", "url"])]
+        stdin: bool,
+        
+        /// If URL is a web page advertising several feeds, take the first
+        /// instead of asking
+        #[arg(long, conflicts_with = "stdin")]
+        auto: bool,
     },
     
     /// Remove an RSS feed
@@ -86,15 +132,142 @@ pub enum Commands {
         name: String,
     },
     
+    /// Rename a feed, keeping its cached articles and read state
+    RenameFeed {
+        /// Current feed name
+        old: String,
+        
+        /// New feed name
+        new: String,
+    },
+    
+    /// Change the URL a feed is fetched from
+    SetFeedUrl {
+        /// Feed name
+        name: String,
+        
+        /// New feed URL
+        url: String,
+    },
+    
+    /// Stop fetching a feed, keeping its cached articles visible
+    DisableFeed {
+        /// Feed name
+        name: String,
+    },
+    
+    /// Resume fetching a disabled feed
+    EnableFeed {
+        /// Feed name
+        name: String,
+    },
+    
     /// List all configured feeds
     ListFeeds,
     
+    /// List a feed's articles one per line: ID, date, title and path in the
+    /// mount, separated by tabs for fzf, cut or xargs
+    ListArticles {
+        /// Feed name
+        feed: String,
+        
+        /// Only unread articles
+        #[arg(long)]
+        unread: bool,
+        
+        /// Print at most this many
+        #[arg(long)]
+        limit: Option<usize>,
+        
+        /// Order: newest first, or by title
+        #[arg(long, value_enum, default_value_t = ArticleSort::Date)]
+        sort: ArticleSort,
+    },
+    
+    /// Search cached articles
+    Search(SearchArgs),
+    
+    /// Mark articles as read (every cached article of the feed if none is given)
+    MarkRead {
+        /// Feed name
+        feed: String,
+        
+        /// Article ID, title or file name
+        article: Option<String>,
+        
+        /// Mark as unread instead
+        #[arg(long)]
+        unread: bool,
+    },
+    
+    /// Star an article so it is listed in starred/
+    Star {
+        /// Feed name
+        feed: String,
+        
+        /// Article ID, title or file name
+        article: String,
+        
+        /// Unstar instead
+        #[arg(long)]
+        unstar: bool,
+    },
+    
+    /// Open an article's link in $BROWSER (or xdg-open)
+    Open {
+        /// Feed name
+        feed: String,
+        
+        /// Article ID, title or file name
+        article: String,
+        
+        /// Print the link instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+    
+    /// Write cached articles to a directory as real files, one
+    /// subdirectory per feed, e.g. for a notes archive
+    Export(ExportArgs),
+    
     /// Refresh feeds manually
     Refresh {
         /// Specific feed name (if not provided, refresh all)
         feed: Option<String>,
     },
     
+    /// Fetch feeds into the on-disk cache before mounting, highest
+    /// priority first
+    Warm {
+        /// Specific feed name (if not provided, warm all)
+        feed: Option<String>,
+    },
+    
+    /// Remove cached articles beyond the retention limits
+    Prune,
+    
+    /// Sync subscriptions and read state with the [sync] reader
+    Sync,
+    
+    /// Back up or restore read and star state
+    #[command(after_long_help = STATE_FILE_HELP)]
+    State {
+        #[command(subcommand)]
+        action: StateCommand,
+    },
+    
+    /// Inspect or manage the on-disk article cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
+    
+    /// Check or work with the config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    
     /// Show RSS-FUSE status
     Status {
         /// Check mount status for specific path
@@ -102,6 +275,18 @@ pub enum Commands {
         mount_point: Option<PathBuf>,
     },
     
+    /// Follow the running mount in a terminal UI: feed status, refreshes
+    /// and the articles that arrived last
+    Watch {
+        /// Seconds between status updates
+        #[arg(short, long, default_value_t = 2)]
+        interval: u64,
+    },
+    
+    /// Check FUSE, the config, the cache, every feed and existing mounts,
+    /// suggesting fixes; exits non-zero if a check fails
+    Doctor,
+    
     /// Generate shell completions
     Completions {
         /// Shell type
@@ -117,36 +302,246 @@ pub enum Commands {
     },
 }
 
+/// `rss-fuse cache` actions
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheCommand {
+    /// Show the cache's size on disk, entry counts and hit rates
+    Stats,
+    
+    /// Delete cached feeds, HTTP responses, enclosures and images (read state is kept)
+    Clear,
+    
+    /// Make the running mount write its cache to disk now
+    Save,
+    
+    /// Print the cache directory
+    Path,
+}
+
+/// `rss-fuse state` actions
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum StateCommand {
+    /// Write the read and starred articles of the cache to a file
+    Export {
+        /// State file to write
+        file: PathBuf,
+    },
+    
+    /// Apply a state file to the cached articles it names
+    Import {
+        /// State file to read
+        file: PathBuf,
+    },
+}
+
+/// Format of the files `rss-fuse state` reads and writes
+const STATE_FILE_HELP: &str = r#"State file format (version 1):
+
+  {
+    "version": 1,
+    "exported_at": "2024-03-15T10:00:00Z",
+    "articles": [
+      { "feed": "hacker-news", "guid": "https://news.example/item/1",
+        "url": "https://news.example/item/1", "read": true, "starred": false }
+    ]
+  }
+
+Only read or starred articles are exported. On import an entry applies to
+the cached article with the same guid, or else with the same link once
+tracking parameters, `www.` and trailing slashes are ignored, in any feed;
+`feed` is informational. Entries for articles not cached yet are skipped.
+Running mounts pick imported state up when remounted."#;
+
+/// `rss-fuse config` actions
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigCommand {
+    /// Check the config for errors, unknown keys and doubtful values,
+    /// by line; exits non-zero on errors
+    Validate {
+        /// Treat unknown keys as errors
+        #[arg(long)]
+        strict: bool,
+    },
+    
+    /// Open the config in $VISUAL or $EDITOR; the edit is only saved once
+    /// it validates
+    Edit,
+    
+    /// Print the effective configuration, with defaults and environment
+    /// overrides filled in
+    Show,
+    
+    /// Print the config file's path
+    Path,
+}
+
+/// Filters for `rss-fuse search`; all given filters must match
+#[derive(Args, Debug, Clone, Default)]
+pub struct SearchArgs {
+    /// Free-text terms matched against title, description and content
+    pub text: Vec<String>,
+    
+    /// Only search this feed
+    #[arg(long)]
+    pub feed: Option<String>,
+    
+    /// Title contains this text (case-insensitive)
+    #[arg(long)]
+    pub title: Option<String>,
+    
+    /// Exact author name
+    #[arg(long)]
+    pub author: Option<String>,
+    
+    /// Published on or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long)]
+    pub since: Option<String>,
+    
+    /// Published on or before this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long)]
+    pub until: Option<String>,
+    
+    /// Required tag/category; repeat for several
+    #[arg(long = "tag")]
+    pub tags: Vec<String>,
+    
+    /// Maximum number of results
+    #[arg(long, default_value_t = 50)]
+    pub limit: usize,
+}
+
+/// Articles to write for `rss-fuse export`, and where
+#[derive(Args, Debug, Clone)]
+pub struct ExportArgs {
+    /// Directory to write to; files from an earlier export are overwritten
+    #[arg(short, long)]
+    pub output: PathBuf,
+    
+    /// File format
+    #[arg(long, value_enum, default_value_t = ExportFormat::Markdown)]
+    pub format: ExportFormat,
+    
+    /// Only export this feed; repeat for several
+    #[arg(long = "feed")]
+    pub feeds: Vec<String>,
+    
+    /// Only articles published on or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long)]
+    pub since: Option<String>,
+}
+
+/// Order of `rss-fuse list-articles`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArticleSort {
+    /// Newest first; undated articles last
+    Date,
+    /// Alphabetically, ignoring case
+    Title,
+}
+
+/// File format of exported articles
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Markdown with YAML frontmatter, as in the mount
+    Markdown,
+    /// Standalone HTML pages
+    Html,
+    /// Plain text
+    Text,
+}
+
 impl Cli {
     pub async fn run(self) -> Result<()> {
         // Initialize logging
         commands::init_logging(self.debug, self.verbose)?;
+        crate::profile::select(self.profile.clone())?;
         
         match self.command {
             Commands::Init { mount_point } => {
                 commands::init(mount_point).await
             }
-            Commands::Mount { mount_point, daemon, allow_other, foreground, no_auto_open, file_manager } => {
-                mount::mount(mount_point, daemon, allow_other, foreground, no_auto_open, file_manager, self.config).await
+            Commands::Mount { mount_point, daemon, allow_other, foreground, no_auto_open, file_manager, takeover } => {
+                mount::mount(mount_point, daemon, allow_other, foreground, no_auto_open, file_manager, takeover, self.config).await
             }
             Commands::Unmount { mount_point, force } => {
                 mount::unmount(mount_point, force).await
             }
-            Commands::AddFeed { name, url } => {
-                commands::add_feed(name, url, self.config).await
+            Commands::Remount { mount_point, force, no_auto_open, file_manager } => {
+                mount::remount(mount_point, force, no_auto_open, file_manager, self.config).await
+            }
+            Commands::AddFeed { name, url, stdin, auto } => {
+                match (stdin, name, url) {
+                    (true, _, _) => commands::add_feeds_from_stdin(self.config).await,
+                    (false, Some(name), Some(url)) => commands::add_feed(name, url, auto, self.config).await,
+                    _ => Err(crate::error::Error::Invalid("add-feed requires NAME and URL, or --stdin".to_string())),
+                }
             }
             Commands::RemoveFeed { name } => {
                 commands::remove_feed(name, self.config).await
             }
+            Commands::RenameFeed { old, new } => {
+                commands::rename_feed(old, new, self.config).await
+            }
+            Commands::SetFeedUrl { name, url } => {
+                commands::set_feed_url(name, url, self.config).await
+            }
+            Commands::DisableFeed { name } => {
+                commands::set_feed_enabled(name, false, self.config).await
+            }
+            Commands::EnableFeed { name } => {
+                commands::set_feed_enabled(name, true, self.config).await
+            }
             Commands::ListFeeds => {
                 commands::list_feeds(self.config).await
             }
+            Commands::ListArticles { feed, unread, limit, sort } => {
+                commands::list_articles(feed, unread, limit, sort, self.config).await
+            }
+            Commands::Search(args) => {
+                commands::search(args, self.config).await
+            }
+            Commands::MarkRead { feed, article, unread } => {
+                commands::mark_read(feed, article, unread, self.config).await
+            }
+            Commands::Star { feed, article, unstar } => {
+                commands::star(feed, article, unstar, self.config).await
+            }
+            Commands::Open { feed, article, print } => {
+                commands::open(feed, article, print, self.config).await
+            }
+            Commands::Export(args) => {
+                commands::export(args, self.config).await
+            }
             Commands::Refresh { feed } => {
                 commands::refresh(feed, self.config).await
             }
+            Commands::Warm { feed } => {
+                commands::warm(feed, self.config).await
+            }
+            Commands::Prune => {
+                commands::prune(self.config).await
+            }
+            Commands::Sync => {
+                commands::sync(self.config).await
+            }
+            Commands::State { action } => {
+                commands::state(action, self.config).await
+            }
+            Commands::Cache { action } => {
+                commands::cache(action).await
+            }
+            Commands::Config { action } => {
+                commands::config(action, self.config).await
+            }
             Commands::Status { mount_point } => {
                 commands::status(mount_point).await
             }
+            Commands::Watch { interval } => {
+                watch::run(std::time::Duration::from_secs(interval.max(1))).await
+            }
+            Commands::Doctor => {
+                commands::doctor(self.config).await
+            }
             Commands::Completions { shell } => {
                 commands::generate_completions(shell);
                 Ok(())
@@ -1,15 +1,29 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use std::io::Write;
 use tokio::signal;
-use tracing::{info, warn, error, debug};
+use tokio::sync::Notify;
+use tracing::{info, warn, error};
 
-use crate::config::Config;
-use crate::storage::{Repository, RepositoryFactory, CacheConfig, PersistentCacheConfig, FeedRepository};
+use crate::config::{Config, FilesystemConfig};
+use crate::storage::{Repository, RepositoryFactory, CacheConfig, PersistentCacheConfig, ReadStateStore, StarredStore, ContentStore};
+use crate::feed::fetcher::FeedFetcher;
+use crate::feed::http_cache::HttpCache;
+use crate::content::summarizer::{CommandSummarizer, SummaryCache};
+use crate::feed::notifications::Notifier;
+use crate::feed::rate_limit::RateLimiter;
 use crate::fuse::{FuseOperations, MountOptions};
+use crate::fuse::operations::active_mounts;
+use crate::fuse::{marker, mounts};
+use crate::fuse::marker::{MountArgs, MountMarker};
+use crate::cli::control::{self, ControlServer, Request, Response};
+use crate::cli::metrics;
+use crate::cli::reload::{load_order, watch_config, ConfigWatcher, FeedScheduler};
 use crate::file_manager::FileManagerLauncher;
 use crate::error::{Error, Result};
+use crate::lock::{self, InstanceLock};
+use crate::profile;
 
 /// Mount RSS feeds as a FUSE filesystem
 pub async fn mount(
@@ -19,6 +33,7 @@ pub async fn mount(
     foreground: bool,
     no_auto_open: bool,
     file_manager_override: Option<String>,
+    takeover: bool,
     config_path: Option<PathBuf>,
 ) -> Result<()> {
     info!("Mounting RSS-FUSE at: {}", mount_point.display());
@@ -37,6 +52,8 @@ pub async fn mount(
     };
     println!("✅ ({:.0}ms)", mount_start.elapsed().as_millis());
     
+    crate::limits::print_soft_limit_warnings(&config, &crate::limits::ResourceUsage::measure(&config));
+    
     if config.feeds.is_empty() {
         warn!("No feeds configured. The filesystem will be empty.");
         println!("⚠️  No feeds configured yet.");
@@ -56,24 +73,121 @@ pub async fn mount(
     // Setup persistent cache configuration
     let cache_dir = dirs::cache_dir()
         .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| "/tmp".into()))
-        .join("rss-fuse");
+        .join(profile::dir_name());
+    
+    // One process per mount point and per cache: two writing the cache
+    // would overwrite each other's saves
+    let purpose = format!("mount at {}", mount_point.display());
+    let _mount_lock = lock_instance(
+        &marker::lock_path(&marker::markers_dir(), &mount_point), &purpose,
+        &format!("Mount point {}", mount_point.display()), takeover,
+    ).await?;
+    let _cache_lock = lock_instance(
+        &lock::cache_lock_path(&cache_dir), &purpose,
+        &format!("The cache in {}", cache_dir.display()), takeover,
+    ).await?;
+    
+    let read_state = Arc::new(ReadStateStore::open(cache_dir.join("read_state.json"))
+        .map_err(|e| Error::Storage(format!("Failed to load read state: {}", e)))?);
+    let starred = Arc::new(StarredStore::open(cache_dir.join("starred.json"))
+        .map_err(|e| Error::Storage(format!("Failed to load starred articles: {}", e)))?);
+    
+    let enclosure_dir = cache_dir.join("enclosures");
+    let image_dir = cache_dir.join("images");
+    let icon_dir = cache_dir.join("icons");
+    let summary_dir = cache_dir.join("summaries");
+    let content_store = match config.fuse.spill_content_bytes {
+        0 => None,
+        threshold => Some(ContentStore::for_process(&cache_dir, threshold)?),
+    };
+    
+    let http_cache = config.cache.http_cache.then(|| {
+        HttpCache::new(cache_dir.join("http"))
+            .with_max_age_override(config.cache.http_max_age.map(Duration::from_secs))
+    });
     
     let persistent_config = PersistentCacheConfig {
         cache_dir,
         max_age_days: 7, // Keep cache for 1 week
         max_size_mb: config.cache.max_size_mb as u64,
-        enable_compression: true,
+        compression: config.cache.compression()?,
     };
     
-    let repo = Arc::new(RepositoryFactory::with_persistent_cache(
-        crate::storage::StorageConfig::default(),
+    // One limiter for feed refreshes and on-read fetches alike
+    let rate_limiter = RateLimiter::new(config.network.rate_limits());
+    
+    let mut repo = RepositoryFactory::with_persistent_cache(
+        config.storage_config(),
         cache_config,
         persistent_config,
-    ).map_err(|e| Error::Storage(format!("Failed to create repository with persistent cache: {}", e)))?);
+    ).map_err(|e| Error::Storage(format!("Failed to create repository with persistent cache: {}", e)))?
+        .with_read_state(read_state)
+        .with_starred(starred)
+        .with_retry_policy(config.settings.retry_policy())
+        .with_feed_limits(config.settings.feed_limits())
+        .with_url_normalizer(config.settings.url_normalizer())
+        .with_failure_threshold(config.settings.failure_threshold)
+        .with_rate_limiter(rate_limiter.clone())
+        .with_network(config.network.options()?)?;
+    if let Some(http_cache) = http_cache {
+        repo = repo.with_http_cache(http_cache);
+    }
+    if !config.notifications.is_disabled() {
+        let http = FeedFetcher::build_client(&config.network.options()?)?;
+        let notifier = Arc::new(Notifier::new(&config.notifications, http));
+        tokio::spawn(Arc::clone(&notifier).run());
+        repo = repo.with_notifier(notifier);
+    }
+    let repo = Arc::new(repo);
     
     // Create FUSE operations first
-    let fuse_ops = FuseOperations::new();
+    let fs_config = FilesystemConfig {
+        article_content: config.settings.article_content,
+        scoring: config.scoring.clone(),
+        ..config.fuse.clone()
+    };
+    let fuse_ops = FuseOperations::with_config(&fs_config).with_mount_args(MountArgs {
+        daemon,
+        allow_other,
+        foreground,
+        no_auto_open,
+        file_manager: file_manager_override.clone(),
+        config_path: Some(config_file.clone()),
+    });
+    fuse_ops.filesystem.set_repository((*repo).clone());
+    fuse_ops.filesystem.set_enclosure_dir(enclosure_dir);
+    fuse_ops.filesystem.set_image_dir(image_dir);
+    fuse_ops.filesystem.set_icon_dir(icon_dir);
+    if let Some(command) = &config.summarizer.command {
+        let summarizer = CommandSummarizer::new(command.clone(), Duration::from_secs(config.summarizer.timeout));
+        fuse_ops.filesystem.set_summaries(Arc::new(
+            SummaryCache::new(Arc::new(summarizer), summary_dir).with_clock(repo.clock())
+        ));
+    }
+    if let Some(content_store) = content_store {
+        fuse_ops.filesystem.set_content_store(content_store);
+    }
+    fuse_ops.filesystem.set_page_fetcher(
+        FeedFetcher::new().with_rate_limiter(rate_limiter).with_network(config.network.options()?)?
+    );
+    fuse_ops.filesystem.set_config_path(config_file.clone());
+    fuse_ops.filesystem.set_virtual_feeds(config.virtual_feeds.clone());
+    let mut scheduler = FeedScheduler::new(repo.clone(), Arc::clone(&fuse_ops.filesystem))
+        .with_concurrency(config.settings.concurrent_fetches);
+    for (name, entry) in &config.feeds {
+        scheduler.configure_feed(name, entry, &config)?;
+    }
     
+    // Clean up after an earlier instance that died while mounted here
+    match fuse_ops.recover_crashed_mount(&mount_point) {
+        Ok(Some(previous)) => {
+            println!("🔧 Previous RSS-FUSE (pid {}, mounted {}) exited without unmounting; cleaned up",
+                     previous.pid, previous.mounted_at.format("%Y-%m-%d %H:%M:%S UTC"));
+        },
+        Ok(None) => {},
+        Err(e) => warn!("Failed to clean up after the previous mount at {}: {}", mount_point.display(), e),
+    }
+
     // Check if mount point is already mounted
     if fuse_ops.is_mounted(&mount_point) {
         println!("⚠️  Mount point is already mounted: {}", mount_point.display());
@@ -137,6 +251,11 @@ pub async fn mount(
     
     // Create placeholder directories for all configured feeds
     println!("📂 Setting up feed placeholders...");
+    for (name, dir_name) in config.renumbered_feed_dirs() {
+        if let Err(e) = fuse_ops.filesystem.set_feed_dir_name(&name, &dir_name) {
+            warn!("Failed to list {} as {}: {}", name, dir_name, e);
+        }
+    }
     for (name, _url) in &config.feeds {
         if let Err(e) = fuse_ops.filesystem.add_loading_placeholder(name) {
             warn!("Failed to create placeholder for {}: {}", name, e);
@@ -163,7 +282,8 @@ pub async fn mount(
         uid: None,
         gid: None,
         auto_unmount: false, // Disable to avoid auto-enabling allow_other
-        read_only: true,
+        // The kernel rejects unlink and writes on ro mounts before they reach us
+        read_only: !config.fuse.delete_marks_read && !config.fuse.control_files && !config.fuse.starred_dir,
     };
     
     // Mount point has already been validated above
@@ -173,139 +293,83 @@ pub async fn mount(
     println!("   📁 Mount point: {}", mount_point.display());
     println!("   🔧 Options: {}", format_mount_options(&mount_options));
     
-    // Start cache-first loading task
-    let cache_repo = repo.clone();
-    let cache_config = config.clone();
-    let cache_fuse = Arc::clone(&fuse_ops.filesystem);
-    
-    tokio::spawn(async move {
-        info!("Starting cache-first feed loading");
-        
-        // Phase 1: Load cached content immediately
-        for (name, url) in &cache_config.feeds {
-            debug!("Checking cache for feed: {}", name);
-            
-            match cache_repo.load_feed_cache_first(name, url).await {
-                Ok(Some(feed)) => {
-                    info!("Found cached feed: {} ({} articles, age: {:?})", 
-                          name, feed.articles.len(), 
-                          feed.last_updated.map(|t| chrono::Utc::now().signed_duration_since(t)));
-                    
-                    // Add cached content immediately
-                    if let Err(e) = cache_fuse.add_feed_from_cache(feed, true) {
-                        error!("Failed to add cached feed {} to filesystem: {}", name, e);
-                    }
-                },
-                Ok(None) => {
-                    debug!("No cached content for feed: {}", name);
-                    // Keep loading placeholder - background refresh will update it
-                },
-                Err(e) => {
-                    warn!("Failed to load cached feed {}: {}", name, e);
-                }
-            }
-        }
-        
-        info!("Cache loading phase completed");
-    });
-    
-    // Start background refresh task (runs immediately for fresh content)
-    let refresh_repo = repo.clone();
-    let refresh_config = config.clone();
-    let refresh_fuse = Arc::clone(&fuse_ops.filesystem);
+    // Show cached articles right away, then fetch every feed in the
+    // background and keep refreshing it on its own schedule
+    scheduler.load_cached(&config).await;
+    info!("Cache loading phase completed");
+    for name in load_order(&config, &repo) {
+        scheduler.schedule(&name, &config.feeds[&name], &config, Duration::ZERO);
+    }
     
-    tokio::spawn(async move {
-        info!("Starting background feed refresh");
-        
-        // Small delay to let cache loading complete first
-        tokio::time::sleep(Duration::from_millis(100)).await;
-        
-        for (name, url) in &refresh_config.feeds {
-            debug!("Background refreshing feed: {} from {}", name, url);
-            
-            match refresh_repo.refresh_feed_background(name, url).await {
-                Ok(Some(feed)) => {
-                    info!("Successfully refreshed feed: {} ({} articles)", name, feed.articles.len());
-                    
-                    // Update filesystem with fresh content
-                    if let Err(e) = refresh_fuse.add_feed_from_cache(feed, false) {
-                        error!("Failed to update refreshed feed {} in filesystem: {}", name, e);
-                    }
-                },
-                Ok(None) => {
-                    debug!("Background refresh failed for feed: {} (cached content remains)", name);
-                },
-                Err(e) => {
-                    error!("Background refresh error for feed {}: {}", name, e);
-                    
-                    // Only add error placeholder if we don't have cached content
-                    if refresh_repo.get_feed(name).await.unwrap_or(None).is_none() {
-                        if let Err(err) = refresh_fuse.add_error_placeholder(name, &e.to_string()) {
-                            error!("Failed to add error placeholder for {}: {}", name, err);
-                        }
-                    }
-                }
-            }
+    // Pick up feeds added, removed or edited in the config while mounted
+    let watcher = Arc::new(tokio::sync::Mutex::new(ConfigWatcher::new(config_file.clone(), config.clone(), scheduler)));
+    tokio::spawn(watch_config(Arc::clone(&watcher)));
+    
+    // Enforce article retention limits between refreshes
+    tokio::spawn(control::prune_periodically(
+        repo.clone(),
+        Arc::clone(&fuse_ops.filesystem),
+        Arc::clone(&watcher),
+        Duration::from_secs(config.cache.cleanup_interval.max(1)),
+    ));
+
+    // Keep .rss-fuse/status.* current without the FUSE thread collecting it
+    tokio::spawn(control::refresh_status_periodically(
+        Arc::clone(&fuse_ops.filesystem),
+        STATUS_REFRESH_INTERVAL,
+    ));
+
+    // Refresh stale feeds as their directories are listed, and feeds named
+    // in .rss-fuse/refresh, away from the FUSE thread
+    if config.fuse.refresh_on_access > 0 || config.fuse.control_files {
+        let (requests, pending) = tokio::sync::mpsc::unbounded_channel();
+        fuse_ops.filesystem.set_refresh_requests(requests);
+        tokio::spawn(control::refresh_requested(
+            repo.clone(),
+            Arc::clone(&fuse_ops.filesystem),
+            Arc::clone(&watcher),
+            pending,
+        ));
+    }
+
+    // Keep subscriptions and read state in step with a hosted reader
+    if let Some(interval) = config.sync.as_ref().and_then(|sync| sync.interval) {
+        tokio::spawn(control::sync_periodically(
+            repo.clone(),
+            Arc::clone(&fuse_ops.filesystem),
+            Arc::clone(&watcher),
+            Duration::from_secs(interval),
+        ));
+    }
+
+    // Let CLI commands reach this mount instead of working on their own
+    let shutdown = Arc::new(Notify::new());
+    let socket_path = control::socket_path();
+    let control_socket = match ControlServer::bind(&socket_path) {
+        Ok(listener) => {
+            let server = Arc::new(ControlServer::new(repo.clone(), Arc::clone(&fuse_ops.filesystem), watcher)
+                .with_shutdown(Arc::clone(&shutdown))
+                .with_mount_point(mount_point.clone()));
+            tokio::spawn(server.serve(listener));
+            info!("Listening for commands on {}", socket_path.display());
+            Some(socket_path)
+        },
+        Err(e) => {
+            warn!("Control socket unavailable, CLI commands will not reach this mount: {}", e);
+            None
         }
-        
-        info!("Background refresh completed");
-    });
-    
-    // Start periodic refresh task  
-    let periodic_repo = repo.clone();
-    let periodic_config = config.clone();
-    let periodic_fuse = Arc::clone(&fuse_ops.filesystem);
+    };
     
-    tokio::spawn(async move {
-        // Wait for initial loading and background refresh to complete
-        tokio::time::sleep(Duration::from_secs(30)).await;
-        
-        let interval_secs = periodic_config.settings.refresh_interval;
-        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
-        
-        loop {
-            interval.tick().await;
-            info!("Running periodic feed refresh (interval: {}s)", interval_secs);
-            
-            // Create a vector of tasks for parallel refresh
-            let mut refresh_tasks = Vec::new();
-            
-            for (name, url) in &periodic_config.feeds {
-                let repo = periodic_repo.clone();
-                let fuse = Arc::clone(&periodic_fuse);
-                let feed_name = name.clone();
-                let feed_url = url.clone();
-                
-                let task = tokio::spawn(async move {
-                    match repo.refresh_feed_background(&feed_name, &feed_url).await {
-                        Ok(Some(feed)) => {
-                            debug!("Periodic refresh: {} ({} articles)", feed_name, feed.articles.len());
-                            
-                            // Update FUSE filesystem with fresh content
-                            if let Err(e) = fuse.add_feed_from_cache(feed, false) {
-                                warn!("Failed to update refreshed feed {} in filesystem: {}", feed_name, e);
-                            }
-                        },
-                        Ok(None) => {
-                            debug!("Periodic refresh failed for {}, keeping cached content", feed_name);
-                        },
-                        Err(e) => {
-                            warn!("Periodic refresh error for {}: {}", feed_name, e);
-                        }
-                    }
-                });
-                
-                refresh_tasks.push(task);
-            }
-            
-            // Wait for all refresh tasks to complete
-            for task in refresh_tasks {
-                let _ = task.await;
-            }
-            
-            debug!("Periodic refresh cycle completed");
+    // Serve feed health and counters to Prometheus
+    if let Some(addr) = config.metrics.listen {
+        match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => {
+                tokio::spawn(metrics::serve(listener, repo.clone(), Arc::clone(&fuse_ops.filesystem)));
+                info!("Serving metrics on http://{}/metrics", addr);
+            },
+            Err(e) => warn!("Metrics endpoint unavailable on {}: {}", addr, e),
         }
-    });
+    }
     
     // Prepare file manager launcher
     let mut file_manager_config = config.fuse.auto_open.clone();
@@ -323,17 +387,21 @@ pub async fn mount(
 
     // Mount the filesystem
     let result = if foreground {
-        mount_foreground(fuse_ops, mount_point.clone(), mount_options, file_manager_launcher, repo.clone()).await
+        mount_foreground(fuse_ops, mount_point.clone(), mount_options, file_manager_launcher, repo.clone(), &shutdown).await
     } else if daemon {
         mount_daemon(fuse_ops, mount_point.clone(), mount_options, file_manager_launcher, repo.clone()).await
     } else {
         // Default to foreground mode for now
-        mount_foreground(fuse_ops, mount_point.clone(), mount_options, file_manager_launcher, repo.clone()).await
+        mount_foreground(fuse_ops, mount_point.clone(), mount_options, file_manager_launcher, repo.clone(), &shutdown).await
     };
 
     if result.is_ok() {
         println!("⚡ Total startup time: {:.0}ms", mount_start.elapsed().as_millis());
     }
+    
+    if let Some(path) = control_socket {
+        let _ = std::fs::remove_file(path);
+    }
 
     result
 }
@@ -345,6 +413,7 @@ async fn mount_foreground(
     mount_options: MountOptions,
     file_manager_launcher: FileManagerLauncher,
     repo: Arc<Repository>,
+    shutdown: &Notify,
 ) -> Result<()> {
     println!("\n🚀 Starting RSS-FUSE filesystem...");
     println!("   Mode: Foreground");
@@ -375,23 +444,26 @@ async fn mount_foreground(
             }
             
             // Wait for shutdown signal
-            wait_for_shutdown().await;
+            wait_for_shutdown(shutdown).await;
             
             println!("\n🔄 Shutting down...");
             
             // Save cache before unmounting
             println!("💾 Saving cache to disk...");
-            if let Err(e) = repo.save_cache() {
+            if let Err(e) = repo.save_cache().await {
                 warn!("Failed to save cache on shutdown: {}", e);
             } else {
                 println!("✅ Cache saved successfully");
             }
             
-            // Unmount filesystem
-            if let Err(e) = fuse_ops.unmount(&mount_point, false) {
-                warn!("Failed to unmount filesystem: {}", e);
-            } else {
-                println!("✅ Filesystem unmounted successfully");
+            // Unmount, letting the request being served finish first
+            println!("⏳ Waiting for filesystem operations to finish...");
+            match tokio::task::block_in_place(|| fuse_ops.shutdown(SHUTDOWN_TIMEOUT)) {
+                Ok(()) => println!("✅ Filesystem unmounted successfully"),
+                Err(e) => {
+                    warn!("Failed to unmount filesystem: {}", e);
+                    println!("⚠️  {}", e);
+                }
             }
         },
         Err(e) => {
@@ -525,18 +597,167 @@ pub async fn unmount(mount_point: PathBuf, force: bool) -> Result<()> {
     Ok(())
 }
 
-/// Wait for shutdown signal (Ctrl+C)
-async fn wait_for_shutdown() {
-    match signal::ctrl_c().await {
-        Ok(()) => {
-            info!("Received shutdown signal");
+/// Unmount the filesystem at `mount_point` and mount it again with the
+/// current config and binary, and the options it was mounted with. A mount
+/// served by this profile is asked to save its cache and stop; a stale mount
+/// is cleaned up; anything else mounted there is unmounted as
+/// `rss-fuse unmount` would.
+pub async fn remount(
+    mount_point: PathBuf,
+    force: bool,
+    no_auto_open: bool,
+    file_manager_override: Option<String>,
+    config_path: Option<PathBuf>,
+) -> Result<()> {
+    info!("Remounting RSS-FUSE at: {}", mount_point.display());
+    println!("🔄 Remounting RSS-FUSE at {}", mount_point.display());
+
+    let fuse_ops = FuseOperations::new();
+    let socket_path = control::socket_path();
+    let served_here = active_mounts().contains(&marker::normalize(&mount_point));
+    // Read before the running mount stops and removes its marker
+    let recorded = MountMarker::read(&marker::markers_dir(), &mount_point).map(|marker| marker.args);
+
+    let stopped = if served_here {
+        match control::send_to(&socket_path, &Request::Stop).await {
+            Ok(Some(Response::Stopping)) => {
+                print!("💾 Waiting for the running mount to save its cache and exit... ");
+                std::io::stdout().flush().unwrap();
+                let deadline = std::time::Instant::now() + STOP_TIMEOUT;
+                while (fuse_ops.is_mounted(&mount_point) || socket_path.exists()) && std::time::Instant::now() < deadline {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+                let exited = !fuse_ops.is_mounted(&mount_point);
+                println!("{}", if exited { "✅" } else { "⏱️  timed out" });
+                exited
+            },
+            Ok(_) => false,
+            Err(e) => {
+                warn!("Running mount did not stop: {}", e);
+                false
+            },
+        }
+    } else {
+        false
+    };
+
+    if !stopped {
+        if fuse_ops.is_mount_stale(&mount_point) {
+            println!("🔧 Cleaning up stale mount: {}", mount_point.display());
+            fuse_ops.cleanup_stale_mount(&mount_point)?;
+        } else if fuse_ops.is_mounted(&mount_point) {
+            unmount(mount_point.clone(), force).await?;
+        }
+    }
+
+    let args = remount_args(recorded, no_auto_open, file_manager_override, config_path);
+    mount(
+        mount_point, args.daemon, args.allow_other, args.foreground,
+        args.no_auto_open, args.file_manager, false, args.config_path,
+    ).await
+}
+
+/// Options for a remount: those `recorded` for the earlier mount, with any
+/// given on the command line taking precedence. Without a record, the
+/// defaults of `rss-fuse mount`.
+fn remount_args(
+    recorded: Option<MountArgs>,
+    no_auto_open: bool,
+    file_manager_override: Option<String>,
+    config_path: Option<PathBuf>,
+) -> MountArgs {
+    let recorded = recorded.unwrap_or_else(|| MountArgs { foreground: true, ..MountArgs::default() });
+    MountArgs {
+        no_auto_open: no_auto_open || recorded.no_auto_open,
+        file_manager: file_manager_override.or(recorded.file_manager),
+        config_path: config_path.or(recorded.config_path),
+        ..recorded
+    }
+}
+
+/// Take the lock at `path` for this mount. With `takeover` the process
+/// holding it is sent `SIGTERM`, on which a mount saves its cache and
+/// unmounts, and the lock is taken once it has exited.
+async fn lock_instance(path: &Path, purpose: &str, what: &str, takeover: bool) -> Result<InstanceLock> {
+    if let Some(lock) = InstanceLock::try_acquire(path, purpose)? {
+        return Ok(lock);
+    }
+    let Some(holder) = InstanceLock::holder(path) else {
+        return Err(Error::AlreadyExists(format!(
+            "{} is in use by another process (locked through {})", what, path.display()
+        )));
+    };
+    if !takeover {
+        return Err(Error::AlreadyExists(format!(
+            "{} is in use by {}. Unmount it first, or mount with --takeover to replace it", what, holder
+        )));
+    }
+
+    print!("🔁 Taking over from {}... ", holder);
+    std::io::stdout().flush().unwrap();
+    mounts::terminate(holder.pid).map_err(|e| Error::PermissionDenied(format!(
+        "Failed to stop rss-fuse pid {}: {}", holder.pid, e
+    )))?;
+    let deadline = std::time::Instant::now() + STOP_TIMEOUT;
+    loop {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        if let Some(lock) = InstanceLock::try_acquire(path, purpose)? {
+            println!("✅");
+            return Ok(lock);
+        }
+        if std::time::Instant::now() >= deadline {
+            println!("⏱️  timed out");
+            return Err(Error::Timeout(format!(
+                "{} did not exit within {}s", holder, STOP_TIMEOUT.as_secs()
+            )));
+        }
+    }
+}
+
+/// Wait for shutdown signal (Ctrl+C, SIGTERM, or a `stop` request on the
+/// control socket)
+async fn wait_for_shutdown(stop: &Notify) {
+    tokio::select! {
+        result = signal::ctrl_c() => match result {
+            Ok(()) => {
+                info!("Received shutdown signal");
+            },
+            Err(err) => {
+                warn!("Failed to listen for shutdown signal: {}", err);
+            },
+        },
+        _ = terminated() => {
+            info!("Received SIGTERM");
+        },
+        _ = stop.notified() => {
+            info!("Received stop request");
+        },
+    }
+}
+
+/// Resolves on `SIGTERM`, as sent by `mount --takeover`; never when the
+/// signal cannot be caught
+async fn terminated() {
+    match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            terminate.recv().await;
         },
         Err(err) => {
-            warn!("Failed to listen for shutdown signal: {}", err);
+            warn!("Failed to listen for SIGTERM: {}", err);
+            std::future::pending::<()>().await;
         },
     }
 }
 
+/// How long Ctrl+C waits for the FUSE session to end after unmounting
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long `remount` waits for a running mount to exit after asking it to
+const STOP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How often a mount renders its status files afresh
+const STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Format mount options for display
 fn format_mount_options(options: &MountOptions) -> String {
     let mut opts = Vec::new();
@@ -575,9 +796,9 @@ fn get_config_file(config_path: Option<PathBuf>) -> Result<PathBuf> {
 /// Get the configuration directory path
 fn get_config_dir() -> Result<PathBuf> {
     if let Some(config_home) = std::env::var_os("XDG_CONFIG_HOME") {
-        Ok(PathBuf::from(config_home).join("rss-fuse"))
+        Ok(PathBuf::from(config_home).join(profile::dir_name()))
     } else if let Some(home) = std::env::var_os("HOME") {
-        Ok(PathBuf::from(home).join(".config").join("rss-fuse"))
+        Ok(PathBuf::from(home).join(".config").join(profile::dir_name()))
     } else {
         Err(Error::Config("Cannot determine configuration directory".to_string()))
     }
@@ -621,6 +842,28 @@ mod tests {
         assert_eq!(formatted, "default");
     }
     
+    #[test]
+    fn test_remount_args() {
+        let recorded = MountArgs {
+            daemon: true,
+            allow_other: true,
+            file_manager: Some("thunar".to_string()),
+            config_path: Some(PathBuf::from("/home/me/.config/rss-fuse/config.toml")),
+            ..MountArgs::default()
+        };
+        assert_eq!(remount_args(Some(recorded.clone()), false, None, None), recorded);
+
+        // Options given to remount win
+        let args = remount_args(Some(recorded.clone()), true, Some("nautilus".to_string()), Some(PathBuf::from("other.toml")));
+        assert!(args.daemon && args.allow_other && args.no_auto_open);
+        assert_eq!(args.file_manager.as_deref(), Some("nautilus"));
+        assert_eq!(args.config_path, Some(PathBuf::from("other.toml")));
+
+        // Nothing recorded: as `rss-fuse mount` with no options
+        let args = remount_args(None, false, None, None);
+        assert!(args.foreground && !args.daemon && !args.allow_other);
+    }
+    
     #[tokio::test]
     async fn test_unmount_nonexistent() {
         let temp_dir = TempDir::new().unwrap();
@@ -631,4 +874,20 @@ mod tests {
         // We expect this to fail, but it shouldn't panic
         assert!(result.is_err());
     }
+    
+    #[tokio::test]
+    async fn test_lock_instance_refuses_second_mount() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = lock::cache_lock_path(temp_dir.path());
+        let _first = lock_instance(&path, "mount at /tmp/a", "The cache", false).await.unwrap();
+        
+        match lock_instance(&path, "mount at /tmp/b", "The cache", false).await {
+            Err(Error::AlreadyExists(message)) => {
+                assert!(message.contains(&format!("pid {}", std::process::id())), "{}", message);
+                assert!(message.contains("mount at /tmp/a"), "{}", message);
+                assert!(message.contains("--takeover"), "{}", message);
+            },
+            other => panic!("expected the lock to be refused, got {:?}", other.map(|_| ())),
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,489 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::config::{Config, FeedEntry};
+use crate::error::Result;
+use crate::feed::{Feed, FeedStatus};
+use crate::feed::scheduler::RefreshScheduler;
+use crate::fuse::filesystem::FeedLoadingStatus;
+use crate::fuse::naming::FilenameTemplate;
+use crate::fuse::RssFuseFilesystem;
+use crate::storage::Repository;
+
+/// How often a running mount checks its config file for changes
+pub const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Feeds that differ between two versions of the configuration
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Feeds whose URL or per-feed settings changed
+    pub changed: Vec<String>,
+}
+
+impl FeedChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare the feed lists of two configurations. A feed also counts as
+/// changed when a global setting it inherits (refresh interval, article
+/// limit, filename template) changed.
+pub fn diff_feeds(old: &Config, new: &Config) -> FeedChanges {
+    let mut changes = FeedChanges::default();
+    for (name, entry) in &new.feeds {
+        match old.feeds.get(name) {
+            None => changes.added.push(name.clone()),
+            Some(previous) => {
+                let changed = previous != entry
+                    || previous.refresh_interval(&old.settings) != entry.refresh_interval(&new.settings)
+                    || previous.max_articles(&old.settings) != entry.max_articles(&new.settings)
+                    || previous.filename_template(&old.fuse) != entry.filename_template(&new.fuse)
+                    || old.filters.rules_for(name) != new.filters.rules_for(name);
+                if changed {
+                    changes.changed.push(name.clone());
+                }
+            }
+        }
+    }
+    changes.removed = old.feeds.keys().filter(|name| !new.feeds.contains_key(*name)).cloned().collect();
+
+    changes.added.sort();
+    changes.removed.sort();
+    changes.changed.sort();
+    changes
+}
+
+/// Configured feeds in the order they are loaded: higher `priority` first,
+/// then those whose newest read article is more recent, then by name
+pub fn load_order(config: &Config, repo: &Repository) -> Vec<String> {
+    let mut feeds: Vec<_> = config.feeds.iter()
+        .map(|(name, entry)| (name, entry.priority(), repo.newest_read(name)))
+        .collect();
+    feeds.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then(a.0.cmp(b.0)));
+    feeds.into_iter().map(|(name, ..)| name.clone()).collect()
+}
+
+/// Periodic refreshes of a running mount, one per feed, kept in step with
+/// the configuration
+pub struct FeedScheduler {
+    repo: Arc<Repository>,
+    filesystem: Arc<RssFuseFilesystem>,
+    refresh: RefreshScheduler,
+}
+
+impl FeedScheduler {
+    pub fn new(repo: Arc<Repository>, filesystem: Arc<RssFuseFilesystem>) -> Self {
+        let refresh = RefreshScheduler::new(repo.clone(), filesystem.clone());
+        Self {
+            repo,
+            filesystem,
+            refresh,
+        }
+    }
+
+    /// Fetch at most `limit` feeds at the same time
+    pub fn with_concurrency(mut self, limit: usize) -> Self {
+        self.refresh = self.refresh.with_concurrency(limit);
+        self
+    }
+
+    /// Show the cached content of every configured feed, in load order
+    pub async fn load_cached(&self, config: &Config) {
+        let feeds: Vec<(String, String)> = load_order(config, &self.repo).into_iter()
+            .map(|name| {
+                let url = config.feeds[&name].url().to_string();
+                (name, url)
+            })
+            .collect();
+        self.refresh.load_cached(&feeds).await;
+    }
+
+    /// Hand a feed's per-feed settings to the repository and filesystem
+    pub fn configure_feed(&self, name: &str, entry: &FeedEntry, config: &Config) -> Result<()> {
+        self.repo.set_id_strategy(name, entry.id_strategy());
+        self.repo.set_max_articles(name, entry.max_articles(&config.settings));
+        self.repo.set_network(name, entry.network(&config.network)?)?;
+        self.repo.set_filter(name, config.filters.filter_for(name)?);
+        self.repo.set_enabled(name, entry.enabled());
+        // Unset ones replace what an earlier version of the config set
+        self.filesystem.set_content_selectors(name, entry.extractor().map(|extractor| extractor.selectors()))?;
+        let template = entry.filename_template(&config.fuse).map(FilenameTemplate::parse).transpose()?;
+        self.filesystem.set_filename_template(name, template);
+        self.filesystem.set_max_visible_articles(name, entry.max_visible_articles());
+        self.filesystem.set_languages(name, entry.languages());
+        if let Some(notifier) = self.repo.notifier() {
+            notifier.set_enabled(name, entry.notify());
+        }
+        self.filesystem.set_feed_config(name, entry.to_toml(name)?);
+        Ok(())
+    }
+
+    /// Start (or restart) the refresh schedule of a feed. The first refresh
+    /// runs after `initial_delay`. Disabled feeds are not scheduled.
+    pub fn schedule(&mut self, name: &str, entry: &FeedEntry, config: &Config, initial_delay: Duration) {
+        if !entry.enabled() {
+            self.refresh.stop(name);
+            // Cached articles stay; a feed that never loaded shows up empty
+            if self.filesystem.get_loading_status(name) == Some(FeedLoadingStatus::Loading) {
+                let feed = Feed {
+                    name: name.to_string(),
+                    url: entry.url().to_string(),
+                    title: None,
+                    description: None,
+                    last_updated: None,
+                    articles: Vec::new(),
+                    status: FeedStatus::Disabled,
+                };
+                if let Err(e) = self.filesystem.add_feed(feed) {
+                    warn!("Failed to show disabled feed {}: {}", name, e);
+                }
+            }
+            return;
+        }
+        let interval = Duration::from_secs(entry.refresh_interval(&config.settings));
+        self.refresh.start(name, entry.url(), interval, initial_delay);
+    }
+
+    /// Stop refreshing a feed and remove its directory
+    pub fn remove(&mut self, name: &str) {
+        self.refresh.stop(name);
+        if let Err(e) = self.filesystem.remove_feed(name) {
+            warn!("Failed to remove feed {}: {}", name, e);
+        }
+    }
+
+    /// Bring feed directories and refresh schedules in line with `new`
+    pub fn apply(&mut self, old: &Config, new: &Config) -> FeedChanges {
+        let changes = diff_feeds(old, new);
+        if old.virtual_feeds != new.virtual_feeds {
+            info!("Virtual feeds changed");
+            self.filesystem.set_virtual_feeds(new.virtual_feeds.clone());
+        }
+
+        for name in &changes.removed {
+            info!("Feed removed from config: {}", name);
+            self.remove(name);
+        }
+
+        // Feeds numbered apart from one another follow the feeds that
+        // came or went. A feed may be moving into a name another is leaving,
+        // so moves that fail are tried again until no more succeed.
+        let old_dirs = old.feed_dir_paths();
+        let mut moves: Vec<(&str, String)> = new.feed_dir_paths().into_iter()
+            .filter(|(name, path)| old_dirs.get(name) != Some(path))
+            .filter_map(|(name, mut path)| Some((name, path.pop()?)))
+            .collect();
+        while !moves.is_empty() {
+            let pending = moves.len();
+            moves.retain(|(name, dir_name)| self.filesystem.set_feed_dir_name(name, dir_name).is_err());
+            if moves.len() == pending {
+                for (name, dir_name) in &moves {
+                    warn!("Failed to list feed {} as {}", name, dir_name);
+                }
+                break;
+            }
+        }
+
+        for name in changes.added.iter().chain(&changes.changed) {
+            let entry = &new.feeds[name];
+            if let Err(e) = self.configure_feed(name, entry, new) {
+                warn!("Failed to apply settings of feed {}: {}", name, e);
+                continue;
+            }
+
+            let is_new = !old.feeds.contains_key(name);
+            if is_new {
+                info!("Feed added to config: {}", name);
+                if let Err(e) = self.filesystem.add_loading_placeholder(name) {
+                    warn!("Failed to create placeholder for {}: {}", name, e);
+                }
+            } else {
+                info!("Feed settings changed: {}", name);
+            }
+
+            // New, moved and re-enabled feeds are fetched straight away; the
+            // rest just pick up their new interval
+            let fetch_now = old.feeds.get(name)
+                .map_or(true, |previous| previous.url() != entry.url() || !previous.enabled());
+            let initial_delay = if fetch_now {
+                Duration::ZERO
+            } else {
+                Duration::from_secs(entry.refresh_interval(&new.settings))
+            };
+            self.schedule(name, entry, new, initial_delay);
+        }
+
+        changes
+    }
+}
+
+/// The configuration a running mount was last brought in line with, and
+/// the file it came from
+pub struct ConfigWatcher {
+    path: PathBuf,
+    config: Config,
+    scheduler: FeedScheduler,
+    last_modified: Option<SystemTime>,
+    last_content: String,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: PathBuf, config: Config, scheduler: FeedScheduler) -> Self {
+        Self {
+            last_modified: Self::modified(&path),
+            last_content: std::fs::read_to_string(&path).unwrap_or_default(),
+            path,
+            config,
+            scheduler,
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reload if the file was modified since the last check
+    pub fn check(&mut self) -> Result<Option<FeedChanges>> {
+        let modified = Self::modified(&self.path);
+        if modified == self.last_modified {
+            return Ok(None);
+        }
+        self.last_modified = modified;
+        self.reload()
+    }
+
+    /// Re-read the file and apply its feed changes. Returns `None` if the
+    /// content is what was applied last. A file that fails to load is
+    /// reported once and the last good configuration kept.
+    pub fn reload(&mut self) -> Result<Option<FeedChanges>> {
+        let content = std::fs::read_to_string(&self.path)?;
+        // Editors often touch the file without changing it
+        if content == self.last_content {
+            return Ok(None);
+        }
+        self.last_content = content;
+
+        let new_config = Config::load(&self.path)?;
+        let changes = self.scheduler.apply(&self.config, &new_config);
+        self.config = new_config;
+        Ok(Some(changes))
+    }
+
+    fn modified(path: &PathBuf) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+}
+
+/// Poll the watched file and apply changes to the running mount until the
+/// task is dropped
+pub async fn watch_config(watcher: Arc<tokio::sync::Mutex<ConfigWatcher>>) {
+    loop {
+        tokio::time::sleep(CONFIG_POLL_INTERVAL).await;
+
+        let mut watcher = watcher.lock().await;
+        match watcher.check() {
+            Ok(Some(changes)) => log_changes(&changes),
+            Ok(None) => {}
+            Err(e) => warn!("Ignoring invalid config change in {}: {}", watcher.path.display(), e),
+        }
+    }
+}
+
+/// Log the outcome of a reload
+pub fn log_changes(changes: &FeedChanges) {
+    if changes.is_empty() {
+        debug!("Config reloaded, feeds unchanged");
+    } else {
+        info!(
+            "Config reloaded: {} added, {} removed, {} changed",
+            changes.added.len(), changes.removed.len(), changes.changed.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuse::inode::NodeType;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn config_with(feeds: &[(&str, &str)]) -> Config {
+        let mut config = Config::default();
+        for (name, url) in feeds {
+            config.feeds.insert(name.to_string(), FeedEntry::Url(url.to_string()));
+        }
+        config
+    }
+
+    #[test]
+    fn test_diff_feeds() {
+        let old = config_with(&[("a", "https://a.example/feed"), ("b", "https://b.example/feed")]);
+        let new = config_with(&[("a", "https://a.example/feed"), ("b", "https://b.example/rss"), ("c", "https://c.example/feed")]);
+
+        let changes = diff_feeds(&old, &new);
+        assert_eq!(changes.added, vec!["c"]);
+        assert_eq!(changes.changed, vec!["b"]);
+        assert!(changes.removed.is_empty());
+
+        let changes = diff_feeds(&new, &old);
+        assert_eq!(changes.removed, vec!["c"]);
+        assert!(diff_feeds(&old, &old).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_order() {
+        use crate::config::FeedConfig;
+        use crate::feed::test_article;
+        use crate::storage::{FeedRepository, RepositoryFactory};
+
+        let mut config = config_with(&[("a", "https://a.example/feed"), ("b", "https://b.example/feed"),
+                                       ("c", "https://c.example/feed"), ("d", "https://d.example/feed")]);
+        config.feeds.insert("urgent".to_string(), FeedEntry::Table(FeedConfig {
+            url: "https://urgent.example/feed".to_string(),
+            priority: Some(10),
+            ..FeedConfig::default()
+        }));
+
+        // Feeds with read articles come next, the most recently published first
+        let repo = RepositoryFactory::memory();
+        for (name, day) in [("c", 1), ("d", 2)] {
+            let mut article = test_article(&format!("{}-1", name), &format!("{} article", name), name);
+            article.published = Some(chrono::DateTime::from_timestamp(day * 86400, 0).unwrap());
+            article.read = true;
+            repo.save_feed(Feed {
+                name: name.to_string(),
+                url: config.feeds[name].url().to_string(),
+                title: None,
+                description: None,
+                last_updated: None,
+                articles: vec![article],
+                status: FeedStatus::Active,
+            }).await.unwrap();
+        }
+
+        assert_eq!(load_order(&config, &repo), ["urgent", "d", "c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_diff_feeds_inherited_settings() {
+        let old = config_with(&[("a", "https://a.example/feed")]);
+        let mut new = config_with(&[("a", "https://a.example/feed")]);
+        new.settings.refresh_interval = old.settings.refresh_interval * 2;
+
+        assert_eq!(diff_feeds(&old, &new).changed, vec!["a"]);
+    }
+
+    /// A server answering every request with the same one-article feed
+    async fn feed_server() -> MockServer {
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <item><title>A</title><link>https://example.com/a</link></item>
+            </channel></rss>"#;
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_configure_feed_clears_removed_settings() {
+        use crate::config::FeedConfig;
+        use crate::feed::test_article;
+
+        let repo = Arc::new(crate::storage::RepositoryFactory::memory());
+        let filesystem = Arc::new(RssFuseFilesystem::new());
+        let scheduler = FeedScheduler::new(repo, Arc::clone(&filesystem));
+        let feed = || Feed {
+            name: "news".to_string(),
+            url: "https://news.example/feed".to_string(),
+            title: None,
+            description: None,
+            last_updated: None,
+            articles: vec![test_article("hello", "Hello", "news")],
+            status: FeedStatus::Active,
+        };
+        let listed = || {
+            let dir = filesystem.get_node_by_name(1, "news").unwrap();
+            filesystem.get_node_by_name(dir.ino, "001-Hello.md").is_some()
+        };
+
+        let templated = FeedEntry::Table(FeedConfig {
+            url: "https://news.example/feed".to_string(),
+            filename_template: Some("{index:03}-{title}".to_string()),
+            ..FeedConfig::default()
+        });
+        scheduler.configure_feed("news", &templated, &Config::default()).unwrap();
+        filesystem.add_feed(feed()).unwrap();
+        assert!(listed());
+
+        let plain = FeedEntry::Url("https://news.example/feed".to_string());
+        scheduler.configure_feed("news", &plain, &Config::default()).unwrap();
+        filesystem.add_feed(feed()).unwrap();
+        assert!(!listed());
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_applies_feed_changes() {
+        let repo = Arc::new(crate::storage::RepositoryFactory::memory());
+        let filesystem = Arc::new(RssFuseFilesystem::new());
+        let mut scheduler = FeedScheduler::new(repo, Arc::clone(&filesystem));
+
+        let server = feed_server().await;
+        let old = config_with(&[("a", &format!("{}/a", server.uri()))]);
+        let new = config_with(&[("b", &format!("{}/b", server.uri()))]);
+        scheduler.apply(&Config::default(), &old);
+        assert!(filesystem.get_node_by_name(1, "a").is_some());
+
+        let changes = scheduler.apply(&old, &new);
+        assert_eq!(changes.added, vec!["b"]);
+        assert_eq!(changes.removed, vec!["a"]);
+        assert!(filesystem.get_node_by_name(1, "a").is_none());
+        assert!(filesystem.get_node_by_name(1, "b").is_some());
+        assert_eq!(scheduler.refresh.scheduled(), vec!["b"]);
+
+        // A disabled feed is shown but not fetched until enabled again
+        let mut disabled = new.clone();
+        disabled.feeds.get_mut("b").unwrap().set_enabled(false);
+        assert_eq!(scheduler.apply(&new, &disabled).changed, vec!["b"]);
+        assert!(scheduler.refresh.scheduled().is_empty());
+        assert!(filesystem.get_node_by_name(1, "b").is_some());
+        assert_eq!(filesystem.feed_report("b").status, "disabled");
+        scheduler.apply(&disabled, &new);
+        assert_eq!(scheduler.refresh.scheduled(), vec!["b"]);
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_numbers_feeds_sharing_a_directory() {
+        let repo = Arc::new(crate::storage::RepositoryFactory::memory());
+        let filesystem = Arc::new(RssFuseFilesystem::new());
+        let mut scheduler = FeedScheduler::new(repo, Arc::clone(&filesystem));
+
+        let server = feed_server().await;
+        let colon = config_with(&[("a:b", &format!("{}/a", server.uri()))]);
+        scheduler.apply(&Config::default(), &colon);
+        let dir = filesystem.get_node_by_name(1, "a-b").unwrap();
+
+        // A feed named exactly like the directory takes it over
+        let both = config_with(&[("a:b", &format!("{}/a", server.uri())), ("a-b", &format!("{}/b", server.uri()))]);
+        scheduler.apply(&colon, &both);
+        assert_eq!(filesystem.get_node_by_name(1, "a-b (2)").unwrap().ino, dir.ino);
+        assert!(matches!(filesystem.get_node_by_name(1, "a-b").unwrap().node_type, NodeType::FeedDirectory(ref name) if name == "a-b"));
+
+        // And hands it back when removed
+        scheduler.apply(&both, &colon);
+        assert_eq!(filesystem.get_node_by_name(1, "a-b").unwrap().ino, dir.ino);
+        assert!(filesystem.get_node_by_name(1, "a-b (2)").is_none());
+    }
+}
@@ -0,0 +1,499 @@
+//! `rss-fuse watch`: a terminal UI on a running mount. It polls the mount's
+//! control socket for feed status and the articles that arrived last, and
+//! sends refreshes on request; links open in the browser.
+
+use std::collections::BTreeSet;
+use std::io;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Row, Table, TableState};
+use ratatui::{Frame, Terminal};
+use tokio::sync::mpsc;
+use url::Url;
+
+use crate::cli::control::{self, RefreshResult, Request, Response};
+use crate::error::{Error, Result};
+use crate::file_manager;
+use crate::fuse::filesystem::{FeedReport, RecentArticle, StatusReport};
+
+/// Articles listed in the lower pane
+const RECENT_LIMIT: usize = 100;
+
+/// Which list the arrow keys move in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Feeds,
+    Articles,
+}
+
+/// What a key press asks of the mount or the desktop
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Action {
+    /// Refresh one feed, or all of them
+    Refresh(Option<String>),
+    /// Open a link that passed `file_manager::browser_url`
+    Open(Url),
+}
+
+/// Answers arriving from the mount
+#[derive(Debug)]
+enum Update {
+    Status(StatusReport),
+    Recent(Vec<RecentArticle>),
+    Refreshed(Vec<RefreshResult>),
+    Failed { feeds: Vec<String>, error: String },
+}
+
+/// Everything shown on screen
+struct App {
+    status: Option<StatusReport>,
+    recent: Vec<RecentArticle>,
+    focus: Pane,
+    selected_feed: usize,
+    selected_article: usize,
+    /// Feeds with a refresh request in flight
+    refreshing: BTreeSet<String>,
+    /// Outcome of the last action, shown in the bottom line
+    message: Option<String>,
+    quit: bool,
+}
+
+impl App {
+    fn new() -> Self {
+        Self {
+            status: None,
+            recent: Vec::new(),
+            focus: Pane::Feeds,
+            selected_feed: 0,
+            selected_article: 0,
+            refreshing: BTreeSet::new(),
+            message: None,
+            quit: false,
+        }
+    }
+
+    fn feeds(&self) -> &[FeedReport] {
+        self.status.as_ref().map_or(&[], |status| status.feeds.as_slice())
+    }
+
+    fn selected_feed(&self) -> Option<&FeedReport> {
+        self.feeds().get(self.selected_feed)
+    }
+
+    fn selected_article(&self) -> Option<&RecentArticle> {
+        self.recent.get(self.selected_article)
+    }
+
+    fn key(&mut self, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Left | KeyCode::Right => {
+                self.focus = match self.focus {
+                    Pane::Feeds => Pane::Articles,
+                    Pane::Articles => Pane::Feeds,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Char('r') => {
+                let feed = match self.focus {
+                    Pane::Feeds => self.selected_feed().map(|feed| feed.name.clone()),
+                    Pane::Articles => self.selected_article().map(|article| article.feed.clone()),
+                }?;
+                self.refreshing.insert(feed.clone());
+                return Some(Action::Refresh(Some(feed)));
+            }
+            KeyCode::Char('R') => {
+                let names: Vec<String> = self.feeds().iter().map(|feed| feed.name.clone()).collect();
+                self.refreshing.extend(names);
+                return Some(Action::Refresh(None));
+            }
+            KeyCode::Enter | KeyCode::Char('o') if self.focus == Pane::Articles => {
+                // Links come from the feed; anything but a web link stays closed
+                match file_manager::browser_url(&self.selected_article()?.link) {
+                    Ok(url) => return Some(Action::Open(url)),
+                    Err(e) => self.message = Some(e.to_string()),
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let (selected, len) = match self.focus {
+            Pane::Feeds => (&mut self.selected_feed, self.status.as_ref().map_or(0, |s| s.feeds.len())),
+            Pane::Articles => (&mut self.selected_article, self.recent.len()),
+        };
+        *selected = selected.saturating_add_signed(delta).min(len.saturating_sub(1));
+    }
+
+    fn update(&mut self, update: Update) {
+        match update {
+            Update::Status(status) => {
+                self.selected_feed = self.selected_feed.min(status.feeds.len().saturating_sub(1));
+                self.status = Some(status);
+            }
+            Update::Recent(recent) => {
+                self.selected_article = self.selected_article.min(recent.len().saturating_sub(1));
+                self.recent = recent;
+            }
+            Update::Refreshed(results) => {
+                let summary: Vec<String> = results.iter().map(|result| match result {
+                    RefreshResult::Updated { name, added, updated, removed, .. } => {
+                        self.refreshing.remove(name);
+                        format!("{}: {} new, {} updated, {} removed", name, added, updated, removed)
+                    }
+                    RefreshResult::NotModified { name } => {
+                        self.refreshing.remove(name);
+                        format!("{}: not modified", name)
+                    }
+                    RefreshResult::Failed { name, error } => {
+                        self.refreshing.remove(name);
+                        format!("{}: {}", name, error)
+                    }
+                }).collect();
+                self.message = Some(summary.join(" · "));
+            }
+            Update::Failed { feeds, error } => {
+                for feed in &feeds {
+                    self.refreshing.remove(feed);
+                }
+                self.message = Some(error);
+            }
+        }
+    }
+}
+
+/// Run the UI until `q` is pressed, polling the mount every `interval`
+pub async fn run(interval: Duration) -> Result<()> {
+    let Some(Response::Status(status)) = control::send(&Request::Status).await? else {
+        return Err(Error::NotFound(
+            "No running mount answered on the control socket. Start one with 'rss-fuse mount'.".to_string()
+        ));
+    };
+
+    let mut terminal = TerminalGuard::enter()?;
+    let mut app = App::new();
+    app.update(Update::Status(status));
+
+    let (updates_tx, mut updates) = mpsc::unbounded_channel();
+    let mut keys = spawn_key_reader();
+    let mut ticks = tokio::time::interval(interval);
+
+    while !app.quit {
+        terminal.0.draw(|frame| draw(frame, &app))?;
+        tokio::select! {
+            _ = ticks.tick() => {
+                tokio::spawn(poll(updates_tx.clone()));
+            }
+            Some(update) = updates.recv() => {
+                let refreshed = matches!(update, Update::Refreshed(_));
+                app.update(update);
+                if refreshed {
+                    tokio::spawn(poll(updates_tx.clone()));
+                }
+            }
+            key = keys.recv() => match key {
+                Some(key) => match app.key(key) {
+                    Some(Action::Refresh(feed)) => {
+                        let pending = match &feed {
+                            Some(feed) => vec![feed.clone()],
+                            None => app.feeds().iter().map(|feed| feed.name.clone()).collect(),
+                        };
+                        tokio::spawn(refresh(feed, pending, updates_tx.clone()));
+                    }
+                    Some(Action::Open(url)) => {
+                        app.message = Some(match file_manager::open_browser_url(&url) {
+                            Ok(()) => format!("Opened {}", url),
+                            Err(e) => e.to_string(),
+                        });
+                    }
+                    None => {}
+                },
+                None => break,
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Fetch status and recent articles from the mount
+async fn poll(updates: mpsc::UnboundedSender<Update>) {
+    let update = match control::send(&Request::Status).await {
+        Ok(Some(Response::Status(status))) => Update::Status(status),
+        Ok(_) => Update::Failed { feeds: Vec::new(), error: "The mount is no longer running".to_string() },
+        Err(e) => Update::Failed { feeds: Vec::new(), error: e.to_string() },
+    };
+    let _ = updates.send(update);
+    if let Ok(Some(Response::Recent { articles })) = control::send(&Request::Recent { limit: RECENT_LIMIT }).await {
+        let _ = updates.send(Update::Recent(articles));
+    }
+}
+
+async fn refresh(feed: Option<String>, pending: Vec<String>, updates: mpsc::UnboundedSender<Update>) {
+    let update = match control::send(&Request::Refresh { feed }).await {
+        Ok(Some(Response::Refreshed { feeds })) => Update::Refreshed(feeds),
+        Ok(_) => Update::Failed { feeds: pending, error: "The mount is no longer running".to_string() },
+        Err(e) => Update::Failed { feeds: pending, error: format!("Refresh failed: {}", e) },
+    };
+    let _ = updates.send(update);
+}
+
+/// Read key presses on a thread of their own, as crossterm blocks
+fn spawn_key_reader() -> mpsc::UnboundedReceiver<KeyEvent> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while !tx.is_closed() {
+            match event::poll(Duration::from_millis(200)) {
+                Ok(true) => match event::read() {
+                    Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                        if tx.send(key).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(_) => break,
+                },
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+    });
+    rx
+}
+
+/// Raw mode on the alternate screen, restored when dropped
+struct TerminalGuard(Terminal<CrosstermBackend<io::Stdout>>);
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        enable_raw_mode()?;
+        crossterm::execute!(io::stdout(), EnterAlternateScreen)?;
+        Ok(Self(Terminal::new(CrosstermBackend::new(io::stdout()))?))
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = crossterm::execute!(self.0.backend_mut(), LeaveAlternateScreen);
+        let _ = self.0.show_cursor();
+    }
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let [header, feeds, articles, footer] = *Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Percentage(45), Constraint::Min(3), Constraint::Length(1)])
+        .split(frame.size())
+    else {
+        return;
+    };
+
+    let unread: usize = app.feeds().iter().map(|feed| feed.unread).sum();
+    let mut summary = format!(" rss-fuse watch · {} feeds · {} unread", app.feeds().len(), unread);
+    if let Some(cache) = app.status.as_ref().and_then(|status| status.cache.as_ref()) {
+        summary.push_str(&format!(" · cache hit rate {:.0}%", cache.hit_rate * 100.0));
+    }
+    frame.render_widget(Paragraph::new(summary).style(Style::default().add_modifier(Modifier::BOLD)), header);
+
+    let highlight = |pane| match app.focus == pane {
+        true => Style::default().add_modifier(Modifier::REVERSED),
+        false => Style::default().add_modifier(Modifier::BOLD),
+    };
+
+    let now = Utc::now();
+    let rows = app.feeds().iter().map(|feed| {
+        let status = if app.refreshing.contains(&feed.name) { "refreshing" } else { feed.status.as_str() };
+        let changes = feed.last_refresh.as_ref()
+            .map(|result| format!("+{} ~{} -{}", result.articles_added, result.articles_updated, result.articles_removed))
+            .unwrap_or_default();
+        let style = match status {
+            "error" => Style::default().fg(Color::Red),
+            "refreshing" | "updating" | "loading" => Style::default().fg(Color::Yellow),
+            _ => Style::default(),
+        };
+        Row::new([
+            feed.name.clone(),
+            status.to_string(),
+            feed.articles.to_string(),
+            feed.unread.to_string(),
+            feed.last_success.map(|at| age(now, at)).unwrap_or_else(|| "never".to_string()),
+            changes,
+        ]).style(style)
+    });
+    let table = Table::new(rows, [
+        Constraint::Percentage(35),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Length(9),
+        Constraint::Min(10),
+    ])
+        .header(Row::new(["Feed", "Status", "Articles", "Unread", "Fetched", "Last refresh"])
+            .style(Style::default().add_modifier(Modifier::UNDERLINED)))
+        .block(Block::default().borders(Borders::ALL).title(" Feeds "))
+        .highlight_style(highlight(Pane::Feeds));
+    let mut table_state = TableState::default().with_selected(Some(app.selected_feed));
+    frame.render_stateful_widget(table, feeds, &mut table_state);
+
+    let items: Vec<ListItem> = app.recent.iter().map(|article| {
+        let when = article.published.or(article.cached_at)
+            .map(|at| at.with_timezone(&Local).format("%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "           ".to_string());
+        let title = match article.read {
+            true => Span::raw(article.title.clone()),
+            false => Span::styled(article.title.clone(), Style::default().add_modifier(Modifier::BOLD)),
+        };
+        ListItem::new(Line::from(vec![
+            Span::styled(format!("{} ", when), Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("{} ", article.feed), Style::default().fg(Color::Cyan)),
+            title,
+        ]))
+    }).collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(" Recent articles "))
+        .highlight_style(highlight(Pane::Articles));
+    let mut list_state = ListState::default().with_selected(Some(app.selected_article));
+    frame.render_stateful_widget(list, articles, &mut list_state);
+
+    let bottom = match (&app.message, app.refreshing.len()) {
+        (_, pending) if pending > 0 => format!(" Refreshing {} feed{}…", pending, if pending == 1 { "" } else { "s" }),
+        (Some(message), _) => format!(" {}", message),
+        (None, _) => " q quit · Tab switch pane · ↑↓ move · r refresh feed · R refresh all · Enter open link".to_string(),
+    };
+    frame.render_widget(Paragraph::new(bottom), footer);
+}
+
+/// How long ago `at` was, e.g. "5m ago"
+fn age(now: DateTime<Utc>, at: DateTime<Utc>) -> String {
+    let secs = (now - at).num_seconds().max(0);
+    match secs {
+        0..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::FeedResult;
+    use ratatui::backend::TestBackend;
+
+    fn feed_report(name: &str, status: &str) -> FeedReport {
+        FeedReport {
+            name: name.to_string(),
+            url: None,
+            title: None,
+            status: status.to_string(),
+            articles: 12,
+            unread: 3,
+            last_updated: None,
+            last_success: Some(Utc::now() - chrono::Duration::minutes(5)),
+            last_failure: None,
+            fetch_latency_ms: None,
+            consecutive_failures: 0,
+            error: None,
+            last_refresh: Some(FeedResult {
+                feed_name: name.to_string(),
+                success: true,
+                error: None,
+                articles_added: 2,
+                articles_updated: 1,
+                articles_removed: 0,
+            }),
+            status_history: Vec::new(),
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.update(Update::Status(StatusReport {
+            generated_at: Utc::now(),
+            feeds: vec![feed_report("news", "active"), feed_report("blog", "error")],
+            cache: None,
+            operations: Vec::new(),
+        }));
+        app.update(Update::Recent(vec![RecentArticle {
+            feed: "blog".to_string(),
+            id: "1".to_string(),
+            title: "Hello world".to_string(),
+            link: "https://blog.example/hello".to_string(),
+            published: None,
+            cached_at: Some(Utc::now()),
+            read: false,
+        }]));
+        app
+    }
+
+    fn render(app: &App) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer.get(x, y).symbol()).collect())
+            .collect()
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> Option<Action> {
+        app.key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_keys() {
+        let mut app = app();
+        assert_eq!(press(&mut app, KeyCode::Down), None);
+        assert_eq!(press(&mut app, KeyCode::Down), None);
+        assert_eq!(app.selected_feed, 1);
+        assert_eq!(press(&mut app, KeyCode::Char('r')), Some(Action::Refresh(Some("blog".to_string()))));
+        assert!(app.refreshing.contains("blog"));
+
+        // Enter only opens links in the article list
+        assert_eq!(press(&mut app, KeyCode::Enter), None);
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(press(&mut app, KeyCode::Enter), Some(Action::Open(Url::parse("https://blog.example/hello").unwrap())));
+
+        assert_eq!(press(&mut app, KeyCode::Char('R')), Some(Action::Refresh(None)));
+        app.update(Update::Refreshed(vec![
+            RefreshResult::Updated { name: "news".to_string(), articles: 12, added: 2, updated: 0, removed: 1 },
+            RefreshResult::NotModified { name: "blog".to_string() },
+        ]));
+        assert!(app.refreshing.is_empty());
+        assert_eq!(app.message.as_deref(), Some("news: 2 new, 0 updated, 1 removed · blog: not modified"));
+
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn test_open_refuses_other_schemes() {
+        let mut app = app();
+        app.recent[0].link = "javascript:alert(1)".to_string();
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(press(&mut app, KeyCode::Enter), None);
+        assert!(app.message.as_deref().is_some_and(|message| message.contains("javascript")));
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('R'));
+        let lines = render(&app);
+        let screen = lines.join("\n");
+        assert!(lines[0].contains("2 feeds · 6 unread"));
+        assert!(screen.contains("news") && screen.contains("+2 ~1 -0") && screen.contains("5m ago"));
+        assert!(screen.contains("Hello world"));
+        assert!(lines[19].contains("Refreshing 2 feeds"));
+    }
+}
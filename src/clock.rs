@@ -0,0 +1,105 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+
+/// Source of the current time for expiry, aging and scheduling decisions
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    fn now(&self) -> SystemTime;
+
+    fn now_utc(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from(self.now())
+    }
+}
+
+/// Shared handle to a clock, cheap to clone into caches and managers
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Clock backed by the operating system's wall time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Returns the default wall-clock time source
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// Manually driven clock for deterministic tests.
+///
+/// Clones share the same instant, so a test can keep one handle and advance
+/// time while the code under test holds another.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<SystemTime>>,
+}
+
+impl MockClock {
+    pub fn new(start: SystemTime) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    /// Start at the given UTC timestamp
+    pub fn at(start: DateTime<Utc>) -> Self {
+        Self::new(start.into())
+    }
+
+    /// Move time forward
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock() += by;
+    }
+
+    /// Jump to an arbitrary instant, which may be in the past
+    pub fn set(&self, to: SystemTime) {
+        *self.now.lock() = to;
+    }
+
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_advance_is_shared() {
+        let clock = MockClock::default();
+        let shared = clock.shared();
+        let start = shared.now();
+
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(shared.now().duration_since(start).unwrap(), Duration::from_secs(90));
+
+        clock.set(start);
+        assert_eq!(shared.now(), start);
+    }
+
+    #[test]
+    fn test_mock_clock_utc() {
+        let start = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let clock = MockClock::at(start);
+        clock.advance(Duration::from_secs(86_400));
+        assert_eq!(clock.now_utc(), start + chrono::Duration::days(1));
+    }
+}
@@ -1,11 +1,27 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
 use crate::error::{ConfigError, Result};
+use crate::feed::IdStrategy;
+use crate::feed::fetcher::{FeedLimits, NetworkOptions, ProxyMode, RetryPolicy};
+use crate::feed::parser::ParseLimits;
+use crate::feed::urls::{UrlNormalizer, DEFAULT_TRACKING_PARAMS};
+use crate::feed::filter::{ArticleFilter, FilterAction, FilterRule};
+use crate::feed::rate_limit::RateLimits;
+use regex::Regex;
+use std::time::Duration;
+use crate::content::extractor::ContentSelectors;
+use crate::content::language;
+use crate::fuse::naming::{self, FilenameTemplate};
+use crate::storage::{Codec, Compression, StorageBackend, StorageConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
-    pub feeds: HashMap<String, String>,
+    /// Feeds keyed by name. A `group/name` key (or a `[feeds.group]` table of
+    /// entries) places the feed in a nested directory on the mount.
+    #[serde(deserialize_with = "deserialize_feeds")]
+    pub feeds: HashMap<String, FeedEntry>,
     pub settings: Settings,
     #[serde(default)]
     pub fuse: FilesystemConfig,
@@ -13,6 +29,338 @@ pub struct Config {
     pub cache: CacheSettings,
     #[serde(default)]
     pub logging: LoggingConfig,
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default, skip_serializing_if = "FiltersConfig::is_empty")]
+    pub filters: FiltersConfig,
+    /// `[virtual-feeds]`: top-level directories combining the articles of
+    /// the listed feeds, e.g. `all-rust = ["rust-blog", "twir"]`
+    #[serde(default, rename = "virtual-feeds", skip_serializing_if = "BTreeMap::is_empty")]
+    pub virtual_feeds: BTreeMap<String, Vec<String>>,
+    /// Hosted reader to mirror subscriptions and read state with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync: Option<SyncConfig>,
+    #[serde(default, skip_serializing_if = "MetricsConfig::is_disabled")]
+    pub metrics: MetricsConfig,
+    #[serde(default, skip_serializing_if = "NotificationsConfig::is_disabled")]
+    pub notifications: NotificationsConfig,
+    #[serde(default, skip_serializing_if = "SummarizerConfig::is_disabled")]
+    pub summarizer: SummarizerConfig,
+    #[serde(default, skip_serializing_if = "ScoringConfig::is_disabled")]
+    pub scoring: ScoringConfig,
+}
+
+/// A feed subscription: either a bare URL (`name = "https://..."`) or a
+/// `[feeds.<name>]` table carrying per-feed settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)] // one per configured feed, read far more than moved
+pub enum FeedEntry {
+    Url(String),
+    Table(FeedConfig),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeedConfig {
+    pub url: String,
+    
+    /// How article IDs are derived for this feed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_strategy: Option<IdStrategy>,
+    
+    /// Seconds between refreshes of this feed, overriding `settings.refresh_interval`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_interval: Option<u64>,
+    
+    /// Articles kept for this feed, overriding `settings.max_articles`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_articles: Option<usize>,
+    
+    /// Content selectors for sites whose markup the defaults miss
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extractor: Option<ExtractorConfig>,
+    
+    /// Article file name pattern, overriding `fuse.filename_template`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename_template: Option<String>,
+    
+    /// Proxy for this feed, overriding `network.proxy`; `"none"` connects directly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    
+    /// `User-Agent` for this feed, overriding `network.user_agent`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    
+    /// Headers sent with this feed's requests on top of `network.headers`,
+    /// replacing any of the same name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    
+    /// Most articles listed in the feed directory, newest first; older
+    /// ones stay cached but are not shown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_visible_articles: Option<usize>,
+    
+    /// Whether new articles of this feed are announced through
+    /// `[notifications]`; on unless set to false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify: Option<bool>,
+    
+    /// Whether the feed is fetched; a disabled feed keeps showing its
+    /// cached articles. On unless set to false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    
+    /// Languages listed, as ISO 639-1 codes (`["en", "de"]`); articles
+    /// detected as written in another language are hidden
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub languages: Option<Vec<String>>,
+    
+    /// Feeds with a higher priority are loaded first on mount and by
+    /// `warm`; 0 unless set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+}
+
+/// `[feeds.<name>.extractor]`: selectors (`tag`, `.class` or `#id`) that
+/// replace the built-in lists; omitted lists keep the defaults
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ExtractorConfig {
+    /// Elements holding the main article body, tried in order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub article: Option<Vec<String>>,
+    
+    /// Elements that make up the body text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<Vec<String>>,
+    
+    /// Elements stripped before conversion (ads, share buttons, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remove: Option<Vec<String>>,
+}
+
+impl ExtractorConfig {
+    /// The effective selectors, filling gaps from the defaults
+    pub fn selectors(&self) -> ContentSelectors {
+        let defaults = ContentSelectors::default();
+        ContentSelectors {
+            article: self.article.clone().unwrap_or(defaults.article),
+            content: self.content.clone().unwrap_or(defaults.content),
+            remove: self.remove.clone().unwrap_or(defaults.remove),
+        }
+    }
+    
+    fn validate(&self, feed_name: &str) -> Result<()> {
+        let lists = [&self.article, &self.content, &self.remove];
+        if lists.into_iter().flatten().flatten().any(|selector| selector.trim().is_empty()) {
+            return Err(ConfigError::Invalid(format!(
+                "Feed '{}' has an empty content selector", feed_name
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl FeedEntry {
+    pub fn url(&self) -> &str {
+        match self {
+            FeedEntry::Url(url) => url,
+            FeedEntry::Table(table) => &table.url,
+        }
+    }
+    
+    pub fn set_url(&mut self, url: String) {
+        match self {
+            FeedEntry::Url(current) => *current = url,
+            FeedEntry::Table(table) => table.url = url,
+        }
+    }
+    
+    pub fn id_strategy(&self) -> IdStrategy {
+        match self {
+            FeedEntry::Url(_) => IdStrategy::default(),
+            FeedEntry::Table(table) => table.id_strategy.unwrap_or_default(),
+        }
+    }
+    
+    /// Refresh interval in seconds, falling back to the global setting
+    pub fn refresh_interval(&self, settings: &Settings) -> u64 {
+        match self {
+            FeedEntry::Table(FeedConfig { refresh_interval: Some(secs), .. }) => *secs,
+            _ => settings.refresh_interval,
+        }
+    }
+    
+    /// Article limit, falling back to the global setting
+    pub fn max_articles(&self, settings: &Settings) -> usize {
+        match self {
+            FeedEntry::Table(FeedConfig { max_articles: Some(max), .. }) => *max,
+            _ => settings.max_articles,
+        }
+    }
+    
+    /// Per-feed content selectors, if configured
+    pub fn extractor(&self) -> Option<&ExtractorConfig> {
+        match self {
+            FeedEntry::Url(_) => None,
+            FeedEntry::Table(table) => table.extractor.as_ref(),
+        }
+    }
+    
+    /// Article file name pattern, falling back to the filesystem-wide one
+    pub fn filename_template<'a>(&'a self, fuse: &'a FilesystemConfig) -> Option<&'a str> {
+        match self {
+            FeedEntry::Table(FeedConfig { filename_template: Some(template), .. }) => Some(template),
+            _ => fuse.filename_template.as_deref(),
+        }
+    }
+    
+    /// Cap on the articles listed in the feed directory, if any
+    pub fn max_visible_articles(&self) -> Option<usize> {
+        match self {
+            FeedEntry::Url(_) => None,
+            FeedEntry::Table(table) => table.max_visible_articles,
+        }
+    }
+    
+    /// Languages the feed's listing is limited to, if any
+    pub fn languages(&self) -> Option<&[String]> {
+        match self {
+            FeedEntry::Url(_) => None,
+            FeedEntry::Table(table) => table.languages.as_deref(),
+        }
+    }
+    
+    /// Whether new articles are announced through `[notifications]`
+    pub fn notify(&self) -> bool {
+        match self {
+            FeedEntry::Url(_) => true,
+            FeedEntry::Table(table) => table.notify.unwrap_or(true),
+        }
+    }
+    
+    /// Load priority, higher first
+    pub fn priority(&self) -> i32 {
+        match self {
+            FeedEntry::Table(FeedConfig { priority: Some(priority), .. }) => *priority,
+            _ => 0,
+        }
+    }
+    
+    /// Whether the feed is fetched
+    pub fn enabled(&self) -> bool {
+        match self {
+            FeedEntry::Url(_) => true,
+            FeedEntry::Table(table) => table.enabled.unwrap_or(true),
+        }
+    }
+    
+    /// Turn fetching on or off, turning a plain URL entry into a table to
+    /// record `enabled = false`
+    pub fn set_enabled(&mut self, enabled: bool) {
+        match self {
+            FeedEntry::Url(url) if !enabled => {
+                *self = FeedEntry::Table(FeedConfig {
+                    url: std::mem::take(url),
+                    enabled: Some(false),
+                    ..FeedConfig::default()
+                });
+            }
+            FeedEntry::Url(_) => {}
+            FeedEntry::Table(table) => table.enabled = (!enabled).then_some(false),
+        }
+    }
+    
+    /// Network settings for this feed if its proxy, user agent or headers
+    /// differ from `network`'s
+    pub fn network(&self, network: &NetworkConfig) -> Result<Option<NetworkOptions>> {
+        let FeedEntry::Table(table) = self else {
+            return Ok(None);
+        };
+        if table.proxy.is_none() && table.user_agent.is_none() && table.headers.is_empty() {
+            return Ok(None);
+        }
+        let mut options = network.options()?;
+        if let Some(proxy) = &table.proxy {
+            options.proxy = ProxyMode::parse(proxy)?;
+        }
+        if let Some(user_agent) = &table.user_agent {
+            options.user_agent = Some(user_agent.clone());
+        }
+        for (name, value) in &table.headers {
+            options.headers.retain(|configured, _| !configured.eq_ignore_ascii_case(name));
+            options.headers.insert(name.clone(), value.clone());
+        }
+        options.header_map()?;
+        Ok(Some(options))
+    }
+    
+    /// This entry as a `[feeds]` snippet that could be pasted into a config file
+    pub fn to_toml(&self, name: &str) -> Result<String> {
+        let snippet = HashMap::from([("feeds", HashMap::from([(name, self)]))]);
+        toml::to_string_pretty(&snippet).map_err(|e| ConfigError::Invalid(e.to_string()))
+    }
+}
+
+impl From<String> for FeedEntry {
+    fn from(url: String) -> Self {
+        FeedEntry::Url(url)
+    }
+}
+
+/// Deserialize `[feeds]`, flattening group tables into `group/name` keys.
+/// A table with a `url` key is a feed; any other table is a group.
+fn deserialize_feeds<'de, D>(deserializer: D) -> std::result::Result<HashMap<String, FeedEntry>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    #[allow(clippy::large_enum_variant)] // short-lived, flattened straight into the map
+    enum FeedNode {
+        Entry(FeedEntry),
+        Group(HashMap<String, FeedNode>),
+    }
+
+    fn flatten<E: serde::de::Error>(
+        prefix: &str,
+        nodes: HashMap<String, FeedNode>,
+        feeds: &mut HashMap<String, FeedEntry>,
+    ) -> std::result::Result<(), E> {
+        for (key, node) in nodes {
+            let name = if prefix.is_empty() { key } else { format!("{}/{}", prefix, key) };
+            match node {
+                FeedNode::Entry(entry) => {
+                    if feeds.insert(name.clone(), entry).is_some() {
+                        return Err(E::custom(format!("feed '{}' is declared more than once", name)));
+                    }
+                }
+                FeedNode::Group(children) => flatten(&name, children, feeds)?,
+            }
+        }
+        Ok(())
+    }
+
+    let nodes = HashMap::<String, FeedNode>::deserialize(deserializer)?;
+    let mut feeds = HashMap::new();
+    flatten("", nodes, &mut feeds)?;
+    Ok(feeds)
+}
+
+/// Check that a feed name is usable as a (possibly `group/`-prefixed) path
+pub fn validate_feed_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(ConfigError::Invalid("Feed name cannot be empty".to_string()));
+    }
+    if name.split('/').any(|part| part.is_empty() || part == "." || part == "..") {
+        return Err(ConfigError::Invalid(format!(
+            "Feed name '{}' must be '/'-separated non-empty path components", name
+        )));
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,23 +374,87 @@ pub struct Settings {
     #[serde(default = "default_max_articles")]
     pub max_articles: usize,
     
-    #[serde(default = "default_concurrent_fetches")]
+    /// Feeds fetched at the same time when refreshing or adding many
+    #[serde(default = "default_concurrent_fetches", alias = "max_concurrent_fetches")]
     pub concurrent_fetches: usize,
     
-    #[serde(default = "default_article_content")]
-    pub article_content: bool,
-    
-    #[serde(default = "default_user_agent")]
-    pub user_agent: String,
+    /// Where article bodies come from (`"feed"` or `"fetch-on-read"`)
+    #[serde(default, deserialize_with = "deserialize_article_content")]
+    pub article_content: ArticleContentMode,
     
     #[serde(default = "default_timeout")]
     pub timeout: u64,
     
+    /// Retries of a failed fetch (timeouts, connection errors, 429, 5xx)
     #[serde(default = "default_retry_attempts")]
     pub retry_attempts: usize,
     
+    /// Delay before the first retry in milliseconds; doubles per retry
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    
+    /// Upper bound for a single retry delay, in seconds
+    #[serde(default = "default_retry_max_backoff")]
+    pub retry_max_backoff: u64,
+    
+    /// Randomise retry delays so failing feeds do not retry in lockstep
+    #[serde(default = "default_retry_jitter")]
+    pub retry_jitter: bool,
+    
+    /// Consecutive failed refreshes before a feed is reported as errored.
+    /// Failing feeds are also refreshed less often until they recover.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    
+    /// Largest content plus summary of one feed item, in bytes; a feed
+    /// with a bigger item is rejected
     #[serde(default = "default_max_article_size")]
     pub max_article_size: usize,
+    
+    /// Largest feed response in megabytes, enforced while downloading
+    #[serde(default = "default_max_feed_size_mb")]
+    pub max_feed_size_mb: u64,
+    
+    /// Most items a feed document may contain
+    #[serde(default = "default_max_feed_items")]
+    pub max_feed_items: usize,
+    
+    /// Days an article is kept after publication; unlimited if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_article_age_days: Option<u32>,
+    
+    /// Query parameters removed from article links before IDs are derived;
+    /// `utm_*` matches every parameter starting with `utm_`
+    #[serde(default = "default_tracking_params")]
+    pub tracking_params: Vec<String>,
+}
+
+impl Settings {
+    /// Retry policy for feed fetches
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            retries: self.retry_attempts,
+            initial_backoff: Duration::from_millis(self.retry_backoff_ms),
+            max_backoff: Duration::from_secs(self.retry_max_backoff),
+            jitter: self.retry_jitter,
+        }
+    }
+    
+    /// Rewrites article links to their canonical form
+    pub fn url_normalizer(&self) -> UrlNormalizer {
+        UrlNormalizer::new(self.tracking_params.clone())
+    }
+    
+    /// Size limits for feed responses and their items
+    pub fn feed_limits(&self) -> FeedLimits {
+        FeedLimits {
+            max_response_size: self.max_feed_size_mb.saturating_mul(1024 * 1024),
+            parse: ParseLimits {
+                max_items: self.max_feed_items,
+                max_item_size: self.max_article_size,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,6 +476,285 @@ pub struct FilesystemConfig {
     
     #[serde(default)]
     pub auto_open: FileManagerConfig,
+    
+    #[serde(default)]
+    pub article_aging: ArticleAgingConfig,
+    
+    /// Transliterate feed directory and article file names to ASCII
+    /// (e.g. "статьи" → "stati"); titles inside files are left untouched
+    #[serde(default)]
+    pub transliterate_names: bool,
+    
+    /// Article file format: Markdown with YAML frontmatter (`.md`), plain
+    /// text (`.txt`) or a standalone page for browsers (`.html`)
+    #[serde(default)]
+    pub output_format: OutputFormat,
+    
+    /// Article file name pattern, e.g. `{published:%Y-%m-%d}-{title}.{ext}`;
+    /// unset names files after the article title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename_template: Option<String>,
+    
+    /// How embedded HTML is handled when articles are rendered as plain text
+    #[serde(default)]
+    pub text_html_policy: TextHtmlPolicy,
+    
+    /// Let `rm` on an article file mark it as read instead of failing with
+    /// EROFS. Mounts read-write so the kernel forwards the unlink; off by
+    /// default, which keeps the mount read-only.
+    #[serde(default = "default_delete_marks_read")]
+    pub delete_marks_read: bool,
+    
+    /// Leave read articles out of feed directories
+    #[serde(default = "default_hide_read_articles")]
+    pub hide_read_articles: bool,
+    
+    /// Add virtual `unread/` and `read/` listings to each feed directory
+    #[serde(default)]
+    pub read_state_views: bool,
+    
+    /// Add a `.meta` directory with `config.toml`, `feed.xml` and
+    /// `stats.json` to each feed directory
+    #[serde(default = "default_feed_meta")]
+    pub feed_meta: bool,
+    
+    /// Size of the top-level `latest/` directory of symlinks to the newest
+    /// articles across all feeds; 0 leaves it out
+    #[serde(default)]
+    pub latest_articles: usize,
+    
+    /// Add a top-level `today/` directory of symlinks to articles published
+    /// in the last 24 hours
+    #[serde(default)]
+    pub today_dir: bool,
+    
+    /// Add a top-level `starred/` directory of symlinks to starred articles;
+    /// linking an article into it stars it, removing the link unstars it.
+    /// Off by default; turning it on mounts read-write.
+    #[serde(default = "default_starred_dir")]
+    pub starred_dir: bool,
+    
+    /// Add a top-level `tags/` directory with a directory per article tag,
+    /// each holding symlinks to the articles carrying it across all feeds
+    #[serde(default)]
+    pub tags_dir: bool,
+    
+    /// How enclosures (podcast audio and other attached media) appear next
+    /// to their article: `"off"`, `"stream"` or `"download"`
+    #[serde(default)]
+    pub enclosures: EnclosureMode,
+    
+    /// Download the images articles embed into the cache and point the
+    /// Markdown at copies in an `assets/` directory next to the article
+    #[serde(default)]
+    pub offline_images: bool,
+    
+    /// Add writable `.rss-fuse/refresh` (write feed names to fetch them now)
+    /// and `.rss-fuse/feeds.toml` (the feed list, saved back to the config),
+    /// and let `mv` rename feed directories until the next remount.
+    /// Off by default; turning it on mounts read-write.
+    #[serde(default = "default_control_files")]
+    pub control_files: bool,
+    
+    /// Log a warning for FUSE operations taking at least this many
+    /// milliseconds; 0 turns the warnings off
+    #[serde(default = "default_slow_op_threshold_ms")]
+    pub slow_op_threshold_ms: u64,
+    
+    /// Article bodies longer than this many bytes are kept in the cache
+    /// directory while mounted and read back when their file is read, so
+    /// memory grows with the number of articles rather than their length;
+    /// 0 keeps every body in memory
+    #[serde(default = "default_spill_content_bytes")]
+    pub spill_content_bytes: usize,
+    
+    /// Feed directories listing more articles than this sort them into
+    /// `<year>/<month>/` subdirectories by publication date; 0 never does
+    #[serde(default = "default_date_folders_after")]
+    pub date_folders_after: usize,
+    
+    /// Listing a feed directory whose last fetch is older than this many
+    /// seconds refreshes that feed in the background; 0 leaves refreshes to
+    /// the schedule
+    #[serde(default)]
+    pub refresh_on_access: u64,
+    
+    /// Treatment of stories another mounted feed carries too (same link or
+    /// near-identical title): listed as usual, hidden, moved to a
+    /// `duplicates/` folder, or noted in the frontmatter as `also_in`
+    #[serde(default)]
+    pub duplicates: DuplicateMode,
+    
+    /// Copied from `[settings] article_content` when mounting
+    #[serde(skip)]
+    pub article_content: ArticleContentMode,
+    
+    /// Copied from `[scoring]` when mounting
+    #[serde(skip)]
+    pub scoring: ScoringConfig,
+}
+
+/// Source of the body text served in article files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArticleContentMode {
+    /// Whatever the feed provides (often only a summary)
+    #[default]
+    Feed,
+    /// Download the linked page the first time an article file is opened and
+    /// serve its extracted main content instead
+    FetchOnRead,
+}
+
+/// Accepts the mode names, plus the booleans older configs used for
+/// `article_content`, which map to `feed`
+fn deserialize_article_content<'de, D>(deserializer: D) -> std::result::Result<ArticleContentMode, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Legacy(bool),
+        Mode(ArticleContentMode),
+    }
+
+    Ok(match Raw::deserialize(deserializer)? {
+        Raw::Legacy(enabled) => {
+            tracing::debug!("Reading legacy article_content = {} as \"feed\"", enabled);
+            ArticleContentMode::Feed
+        },
+        Raw::Mode(mode) => mode,
+    })
+}
+
+/// Treatment of media attached to articles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnclosureMode {
+    /// Leave enclosures out of the mount
+    Off,
+    /// List each enclosure as a file whose reads are fetched from the
+    /// remote server with range requests
+    #[default]
+    Stream,
+    /// Download an enclosure in full on first read and serve it from the
+    /// local cache afterwards, so it stays playable offline
+    Download,
+}
+
+/// Treatment of a story found in more than one feed. Hiding and grouping
+/// apply to every copy except the one in the feed whose name sorts first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateMode {
+    /// List every copy like any other article
+    #[default]
+    Show,
+    /// Leave the other copies out of their feed directories
+    Hide,
+    /// List the other copies in a `duplicates/` folder of their feed directory
+    Group,
+    /// List every copy, with the other feeds carrying it as `also_in` in
+    /// the frontmatter
+    Annotate,
+}
+
+/// Representation of article files in the mount
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Markdown,
+    Text,
+    Html,
+}
+
+/// Treatment of HTML in description/content when rendering `.txt` output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextHtmlPolicy {
+    /// Convert HTML to readable plain text, decoding entities
+    #[default]
+    Strip,
+    /// Keep markup but decode entities such as `&amp;` and `&#8217;`
+    Entities,
+    /// Emit the feed's HTML unchanged
+    Raw,
+}
+
+/// How articles past the aging threshold are marked in the mounted tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgingMarker {
+    /// Present old articles with reduced permissions (e.g. 0400)
+    Permissions,
+    /// Append a suffix to the file stem (e.g. `Title.old.md`)
+    Suffix,
+    /// Move old articles into a subfolder of the feed directory (e.g. `old/`)
+    Folder,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArticleAgingConfig {
+    /// Enable the aging cue for old articles
+    #[serde(default)]
+    pub enabled: bool,
+    
+    /// Articles published more than this many days ago are considered old
+    #[serde(default = "default_aging_days")]
+    pub after_days: u64,
+    
+    /// How old articles are marked
+    #[serde(default = "default_aging_marker")]
+    pub marker: AgingMarker,
+    
+    /// File mode presented for old articles when marker = "permissions"
+    #[serde(default = "default_aging_permissions")]
+    pub permissions: u32,
+    
+    /// Suffix inserted before the extension when marker = "suffix"
+    #[serde(default = "default_aging_suffix")]
+    pub suffix: String,
+    
+    /// Subfolder name used when marker = "folder"
+    #[serde(default = "default_aging_folder")]
+    pub folder: String,
+}
+
+impl ArticleAgingConfig {
+    /// Whether an article published at `published` counts as old at `now`.
+    /// Articles without a publish date never age.
+    pub fn is_aged(&self, published: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        match published {
+            Some(published) => now.signed_duration_since(published) > chrono::Duration::days(self.after_days as i64),
+            None => false,
+        }
+    }
+    
+    /// Insert the configured suffix between the file stem and its extension
+    pub fn apply_suffix(&self, filename: &str) -> String {
+        match filename.rfind('.') {
+            Some(dot) if dot > 0 => format!("{}{}{}", &filename[..dot], self.suffix, &filename[dot..]),
+            _ => format!("{}{}", filename, self.suffix),
+        }
+    }
+}
+
+impl Default for ArticleAgingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            after_days: default_aging_days(),
+            marker: default_aging_marker(),
+            permissions: default_aging_permissions(),
+            suffix: default_aging_suffix(),
+            folder: default_aging_folder(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -88,54 +779,484 @@ pub struct FileManagerConfig {
     #[serde(default = "default_terminal_command")]
     pub terminal_command: String,
     
-    /// Delay in seconds before launching file manager (allows mount to stabilize)
-    #[serde(default = "default_launch_delay")]
-    pub launch_delay: u64,
+    /// Delay in seconds before launching file manager (allows mount to stabilize)
+    #[serde(default = "default_launch_delay")]
+    pub launch_delay: u64,
+    
+    /// Auto-detect available file managers and use the first found
+    #[serde(default = "default_auto_detect")]
+    pub auto_detect: bool,
+}
+
+impl Default for FileManagerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: default_file_manager(),
+            args: Vec::new(),
+            new_terminal: false,
+            terminal_command: default_terminal_command(),
+            launch_delay: default_launch_delay(),
+            auto_detect: default_auto_detect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSettings {
+    #[serde(default = "default_max_size_mb")]
+    pub max_size_mb: usize,
+    
+    #[serde(default = "default_cleanup_interval")]
+    pub cleanup_interval: u64,
+    
+    /// Keep raw feed responses on disk to avoid re-downloading them
+    #[serde(default = "default_http_cache")]
+    pub http_cache: bool,
+    
+    /// Seconds a cached response is reused without asking the server,
+    /// overriding its `Cache-Control: max-age`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_max_age: Option<u64>,
+    
+    /// Where feeds and articles are stored: `memory://`, `sqlite://path`
+    /// or `dir:///path` for one JSON file per feed
+    #[serde(default = "default_storage")]
+    pub storage: String,
+    
+    /// Codec of the on-disk feed cache: zstd, lz4 or none
+    #[serde(default)]
+    pub compression: Codec,
+    
+    /// zstd level, 1 (fastest) to 22 (smallest); lz4 has a single level
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression_level: Option<i32>,
+}
+
+impl CacheSettings {
+    /// Codec and level the feed cache is written with
+    pub fn compression(&self) -> Result<Compression> {
+        Compression::new(self.compression, self.compression_level)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    
+    #[serde(default)]
+    pub log_to_file: bool,
+    
+    #[serde(default = "default_log_file")]
+    pub log_file: String,
+    
+    #[serde(default)]
+    pub json_format: bool,
+    
+    #[serde(default)]
+    pub console: bool,
+}
+
+/// `[network]`: how feeds, pages and media are reached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// `http://`, `https://`, `socks5://` or `socks5h://` proxy URL, or
+    /// `"none"` to ignore `HTTP_PROXY` and friends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    
+    /// PEM files with extra root certificates, e.g. a corporate CA
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ca_certificates: Vec<PathBuf>,
+    
+    /// Accept invalid TLS certificates. Defeats HTTPS; use only as a last resort.
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+    
+    /// Requests per minute to any one host; unlimited if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_per_minute: Option<u32>,
+    
+    /// Requests a host may receive back to back before `requests_per_minute` applies
+    #[serde(default = "default_host_burst")]
+    pub host_burst: u32,
+    
+    /// Requests in flight at once across feeds, pages and downloads; unlimited if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_requests: Option<usize>,
+    
+    /// `User-Agent` of every request, for sites that block unknown agents;
+    /// `RSS-FUSE/<version>` if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    
+    /// Headers added to every request, such as `Accept-Language`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            ca_certificates: Vec::new(),
+            insecure_skip_verify: false,
+            requests_per_minute: None,
+            host_burst: default_host_burst(),
+            max_concurrent_requests: None,
+            user_agent: None,
+            headers: BTreeMap::new(),
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// Per-host and overall request limits
+    pub fn rate_limits(&self) -> RateLimits {
+        RateLimits {
+            requests_per_minute: self.requests_per_minute,
+            burst: self.host_burst,
+            max_concurrent: self.max_concurrent_requests,
+        }
+    }
+    
+    pub fn options(&self) -> Result<NetworkOptions> {
+        let options = NetworkOptions {
+            proxy: self.proxy.as_deref().map(ProxyMode::parse).transpose()?.unwrap_or_default(),
+            ca_certificates: self.ca_certificates.clone(),
+            insecure_skip_verify: self.insecure_skip_verify,
+            user_agent: self.user_agent.clone(),
+            headers: self.headers.clone(),
+        };
+        options.header_map()?;
+        Ok(options)
+    }
+}
+
+/// `[filters]`: keyword rules run over fetched articles before they are
+/// stored
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FiltersConfig {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<FilterRuleConfig>,
+}
+
+/// A `[[filters.rules]]` entry. Patterns are regular expressions (prefix
+/// `(?i)` to ignore case); all that are given must match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterRuleConfig {
+    /// Feeds (or groups, e.g. `tech` for every `tech/...` feed) the rule
+    /// applies to; all feeds if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feeds: Vec<String>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    
+    pub action: FilterActionKind,
+    
+    /// Tag added by `action = "tag"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    
+    /// Subfolder of the feed directory for `action = "folder"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FilterActionKind {
+    Drop,
+    MarkRead,
+    Tag,
+    Folder,
+}
+
+impl FiltersConfig {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+    
+    /// The rules that apply to `feed_name`, in config order
+    pub fn rules_for(&self, feed_name: &str) -> Vec<&FilterRuleConfig> {
+        self.rules.iter().filter(|rule| rule.applies_to(feed_name)).collect()
+    }
+    
+    /// Compiled rules for `feed_name`
+    pub fn filter_for(&self, feed_name: &str) -> Result<ArticleFilter> {
+        let rules = self.rules_for(feed_name).into_iter()
+            .map(FilterRuleConfig::compile)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ArticleFilter::new(rules))
+    }
+    
+    fn validate(&self) -> Result<()> {
+        for rule in &self.rules {
+            rule.compile()?;
+        }
+        Ok(())
+    }
+}
+
+impl FilterRuleConfig {
+    pub fn applies_to(&self, feed_name: &str) -> bool {
+        self.feeds.is_empty() || self.feeds.iter().any(|feed| {
+            feed_name == feed || feed_name.strip_prefix(feed.as_str()).is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+    
+    pub fn compile(&self) -> Result<FilterRule> {
+        let pattern = |field: &str, pattern: &Option<String>| -> Result<Option<Regex>> {
+            pattern.as_deref()
+                .map(|pattern| Regex::new(pattern).map_err(|e| ConfigError::Invalid(format!(
+                    "Filter {} pattern '{}' is not a valid regex: {}", field, pattern, e
+                ))))
+                .transpose()
+        };
+        let named = |what: &str, value: &Option<String>| -> Result<String> {
+            match value.as_deref().map(str::trim) {
+                Some(value) if !value.is_empty() && !value.contains('/') && !value.starts_with('.') => Ok(value.to_string()),
+                _ => Err(ConfigError::Invalid(format!(
+                    "Filter action \"{}\" needs a `{}` without '/' or a leading '.'", what, what
+                ))),
+            }
+        };
+        
+        let action = match self.action {
+            FilterActionKind::Drop => FilterAction::Drop,
+            FilterActionKind::MarkRead => FilterAction::MarkRead,
+            FilterActionKind::Tag => FilterAction::Tag(named("tag", &self.tag)?),
+            FilterActionKind::Folder => FilterAction::Folder(named("folder", &self.folder)?),
+        };
+        let rule = FilterRule {
+            title: pattern("title", &self.title)?,
+            content: pattern("content", &self.content)?,
+            author: pattern("author", &self.author)?,
+            tags: pattern("tags", &self.tags)?,
+            action,
+        };
+        if rule.title.is_none() && rule.content.is_none() && rule.author.is_none() && rule.tags.is_none() {
+            return Err(ConfigError::Invalid(
+                "Filter rules need at least one of title, content, author or tags".to_string()
+            ));
+        }
+        Ok(rule)
+    }
+}
+
+/// `[sync]`: a hosted reader speaking the Google Reader API (FreshRSS,
+/// Miniflux, The Old Reader)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// API root, e.g. `https://rss.example.com/api/greader.php` for FreshRSS
+    pub endpoint: String,
+    
+    pub username: String,
+    
+    /// API password; `RSS_FUSE_SYNC_PASSWORD` takes precedence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    
+    /// Add the account's subscriptions missing from `[feeds]`
+    #[serde(default = "default_sync_import_subscriptions")]
+    pub import_subscriptions: bool,
+    
+    /// Seconds between syncs of a running mount; only on demand if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u64>,
+    
+    /// Newest server items compared with local articles per sync
+    #[serde(default = "default_sync_max_items")]
+    pub max_items: usize,
+}
+
+impl SyncConfig {
+    pub fn password(&self) -> Result<String> {
+        std::env::var("RSS_FUSE_SYNC_PASSWORD").ok()
+            .or_else(|| self.password.clone())
+            .ok_or_else(|| ConfigError::Config(
+                "Sync needs a password: set [sync] password or RSS_FUSE_SYNC_PASSWORD".to_string()
+            ))
+    }
+    
+    fn validate(&self) -> Result<()> {
+        url::Url::parse(&self.endpoint)
+            .map_err(|_| ConfigError::InvalidUrl(self.endpoint.clone()))?;
+        if self.interval == Some(0) || self.max_items == 0 {
+            return Err(ConfigError::Invalid("Sync interval and max_items must be greater than 0".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// `[metrics]`: Prometheus endpoint of a running mount
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Address serving `GET /metrics`, e.g. `127.0.0.1:9184`; off if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listen: Option<std::net::SocketAddr>,
+}
+
+impl MetricsConfig {
+    pub fn is_disabled(&self) -> bool {
+        self.listen.is_none()
+    }
+}
+
+/// `[notifications]`: announce articles added by a running mount's
+/// refreshes on the desktop and to webhooks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    /// Show a desktop notification through `notify-send`
+    #[serde(default)]
+    pub desktop: bool,
+    
+    /// URLs receiving a JSON `POST` per batch, e.g. a Slack incoming
+    /// webhook or an ntfy topic
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<String>,
+    
+    /// Seconds to collect new articles before notifying, so a burst of
+    /// refreshes ends up in one message
+    #[serde(default = "default_notification_batch_window")]
+    pub batch_window: u64,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            desktop: false,
+            webhooks: Vec::new(),
+            batch_window: default_notification_batch_window(),
+        }
+    }
+}
+
+impl NotificationsConfig {
+    pub fn is_disabled(&self) -> bool {
+        !self.desktop && self.webhooks.is_empty()
+    }
     
-    /// Auto-detect available file managers and use the first found
-    #[serde(default = "default_auto_detect")]
-    pub auto_detect: bool,
+    fn validate(&self) -> Result<()> {
+        for webhook in &self.webhooks {
+            match url::Url::parse(webhook) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                _ => return Err(ConfigError::InvalidUrl(webhook.clone())),
+            }
+        }
+        Ok(())
+    }
 }
 
-impl Default for FileManagerConfig {
+/// `[summarizer]`: a command that summarizes each article of a running
+/// mount, e.g. a script around a local language model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummarizerConfig {
+    /// Shell command given an article's text on stdin (and its title and
+    /// link in `RSS_FUSE_TITLE` and `RSS_FUSE_LINK`) that prints a summary;
+    /// unset turns summaries off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    
+    /// Seconds one run may take before it is stopped
+    #[serde(default = "default_summarizer_timeout")]
+    pub timeout: u64,
+}
+
+impl Default for SummarizerConfig {
     fn default() -> Self {
         Self {
-            enabled: false,
-            command: default_file_manager(),
-            args: Vec::new(),
-            new_terminal: false,
-            terminal_command: default_terminal_command(),
-            launch_delay: default_launch_delay(),
-            auto_detect: default_auto_detect(),
+            command: None,
+            timeout: default_summarizer_timeout(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CacheSettings {
-    #[serde(default = "default_max_size_mb")]
-    pub max_size_mb: usize,
+impl SummarizerConfig {
+    pub fn is_disabled(&self) -> bool {
+        self.command.is_none()
+    }
     
-    #[serde(default = "default_cleanup_interval")]
-    pub cleanup_interval: u64,
+    fn validate(&self) -> Result<()> {
+        if self.command.as_deref().is_some_and(|command| command.trim().is_empty()) {
+            return Err(ConfigError::Invalid("Summarizer command must not be empty".to_string()));
+        }
+        if self.timeout == 0 {
+            return Err(ConfigError::Invalid("Summarizer timeout must be greater than 0".to_string()));
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LoggingConfig {
-    #[serde(default = "default_log_level")]
-    pub level: String,
+/// `[scoring]`: weighted keywords that rank articles for triage
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoringConfig {
+    /// Keyword or phrase to weight, e.g. `rust = 5` or `"crypto" = -3`. An
+    /// article scores the weights of the keywords it mentions, those in its
+    /// title twice.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keywords: BTreeMap<String, i64>,
     
+    /// Articles scoring at least this get `!` in front of their file name,
+    /// which sorts them first; unset leaves names alone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_at: Option<i64>,
+    
+    /// Size of the top-level `top/` directory of symlinks to the
+    /// best-scoring articles, numbered by rank; 0 leaves it out
     #[serde(default)]
-    pub log_to_file: bool,
+    pub top_articles: usize,
+}
+
+impl ScoringConfig {
+    pub fn is_disabled(&self) -> bool {
+        self.keywords.is_empty()
+    }
     
-    #[serde(default = "default_log_file")]
-    pub log_file: String,
+    fn validate(&self) -> Result<()> {
+        if self.keywords.keys().any(|keyword| keyword.trim().is_empty()) {
+            return Err(ConfigError::Invalid("Scoring keywords must not be empty".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Resource budgets. Soft limits produce warnings in `status` and `mount`
+/// output; hard limits make `add-feed` refuse new subscriptions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LimitsConfig {
+    #[serde(default)]
+    pub soft: ResourceLimits,
     
     #[serde(default)]
-    pub json_format: bool,
+    pub hard: ResourceLimits,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// Maximum number of subscribed feeds
+    #[serde(default)]
+    pub max_feeds: Option<usize>,
     
+    /// Maximum number of cached articles across all feeds
     #[serde(default)]
-    pub console: bool,
+    pub max_total_articles: Option<usize>,
+    
+    /// Maximum on-disk cache size in MB
+    #[serde(default)]
+    pub max_cache_mb: Option<u64>,
 }
 
 impl Config {
@@ -145,6 +1266,7 @@ impl Config {
         
         let config: Config = toml::from_str(&content)?;
         config.validate()?;
+        crate::config_check::warn_unknown_keys(&content, path.as_ref());
         Ok(config)
     }
     
@@ -161,15 +1283,251 @@ impl Config {
         Ok(())
     }
     
+    /// Article retention limits: `settings.max_articles` per feed,
+    /// `settings.max_article_age_days`, and the cache size budget, on the
+    /// backend named by `cache.storage`
+    pub fn storage_config(&self) -> StorageConfig {
+        StorageConfig {
+            max_articles_per_feed: Some(self.settings.max_articles),
+            max_article_age_days: self.settings.max_article_age_days,
+            max_storage_size_mb: Some(self.cache.max_size_mb as u64),
+            connection_string: self.cache.storage.clone(),
+            ..StorageConfig::default()
+        }
+    }
+    
+    /// The `[feeds]` table on its own, as shown in `.rss-fuse/feeds.toml`
+    pub fn feeds_toml(&self) -> Result<String> {
+        let feeds: BTreeMap<&String, &FeedEntry> = self.feeds.iter().collect();
+        toml::to_string_pretty(&HashMap::from([("feeds", feeds)]))
+            .map_err(|e| ConfigError::Invalid(e.to_string()))
+    }
+    
+    /// Replace the feed list with the `[feeds]` table in `content`. The
+    /// current list is kept if the result does not validate.
+    pub fn set_feeds_toml(&mut self, content: &str) -> Result<()> {
+        #[derive(Deserialize)]
+        struct FeedList {
+            #[serde(default, deserialize_with = "deserialize_feeds")]
+            feeds: HashMap<String, FeedEntry>,
+        }
+        
+        let list: FeedList = toml::from_str(content)?;
+        let previous = std::mem::replace(&mut self.feeds, list.feeds);
+        if let Err(e) = self.validate() {
+            self.feeds = previous;
+            return Err(e);
+        }
+        Ok(())
+    }
+    
+    /// Check that `name` can be added as a new feed: valid, unused, and not
+    /// clashing with an existing feed group
+    pub fn check_new_feed_name(&self, name: &str) -> Result<()> {
+        validate_feed_name(name)?;
+        if self.feeds.contains_key(name) {
+            return Err(ConfigError::AlreadyExists(format!("Feed '{}' already exists", name)));
+        }
+        
+        // Compare directories rather than names: "a:b" and "a-b" both live in a-b/
+        let dir = self.feed_slug_path(name);
+        let dirs = self.feed_dir_paths();
+        if let Some((existing, _)) = dirs.iter().find(|(_, existing)| **existing == dir) {
+            return Err(ConfigError::AlreadyExists(format!(
+                "Feed '{}' would share the directory '{}' with feed '{}'; choose another name",
+                name, dir.join("/"), existing
+            )));
+        }
+        let clash = dirs.iter().find(|(_, existing)| existing.starts_with(&dir) || dir.starts_with(existing));
+        if let Some((existing, _)) = clash {
+            return Err(ConfigError::Invalid(format!(
+                "Feed '{}' conflicts with existing feed '{}'", name, existing
+            )));
+        }
+        if let Some(top) = name.split('/').next().filter(|top| self.virtual_feeds.contains_key(*top)) {
+            return Err(ConfigError::Invalid(format!(
+                "Feed '{}' conflicts with virtual feed '{}'", name, top
+            )));
+        }
+        Ok(())
+    }
+    
+    /// Directories a feed is presented at in the mount, one per component of
+    /// its name, e.g. `["Tech", "Rust Blog"]` for `Tech/Rust Blog`
+    pub fn feed_dir_path(&self, name: &str) -> Vec<String> {
+        match self.feeds.contains_key(name) {
+            true => self.feed_dir_paths().remove(name).unwrap_or_default(),
+            false => self.feed_slug_path(name),
+        }
+    }
+    
+    /// `feed_dir_path` of every configured feed. Feeds whose slugs collide,
+    /// such as "a:b" and "a-b", or match a group's directory, are told apart
+    /// by numbering all but one: the feed named exactly like the directory,
+    /// else the first by name keeps it and the others become "a-b (2)" and on.
+    pub fn feed_dir_paths(&self) -> HashMap<&str, Vec<String>> {
+        let mut slugs: Vec<(&str, Vec<String>)> = self.feeds.keys()
+            .map(|name| (name.as_str(), self.feed_slug_path(name)))
+            .collect();
+        slugs.sort_by_key(|(name, slug)| (slug.join("/") != *name, *name));
+        let groups: HashSet<Vec<String>> = slugs.iter()
+            .flat_map(|(_, slug)| (1..slug.len()).map(|len| slug[..len].to_vec()))
+            .collect();
+        
+        let mut taken = HashSet::new();
+        let mut paths = HashMap::new();
+        for (name, mut path) in slugs {
+            let leaf = path.last().cloned().unwrap_or_default();
+            let mut n = 2;
+            while taken.contains(&path) || groups.contains(&path) {
+                *path.last_mut().unwrap() = naming::fit_name(&leaf, &format!(" ({})", n), naming::MAX_NAME_BYTES);
+                n += 1;
+            }
+            taken.insert(path.clone());
+            paths.insert(name, path);
+        }
+        paths
+    }
+    
+    /// Feeds whose slug is taken by another feed or a group, with the
+    /// numbered directory name they are presented under instead
+    pub fn renumbered_feed_dirs(&self) -> HashMap<String, String> {
+        self.feed_dir_paths().into_iter()
+            .filter(|(name, path)| *path != self.feed_slug_path(name))
+            .filter_map(|(name, mut path)| Some((name.to_string(), path.pop()?)))
+            .collect()
+    }
+    
+    fn feed_slug_path(&self, name: &str) -> Vec<String> {
+        name.split('/')
+            .map(|component| naming::feed_dir_name(component, self.fuse.transliterate_names))
+            .collect()
+    }
+    
+    /// Follow a feed renamed to `new`, or removed if `None`, in the virtual
+    /// feeds listing it. Virtual feeds left without feeds are dropped.
+    pub fn retarget_virtual_feeds(&mut self, old: &str, new: Option<&str>) {
+        for members in self.virtual_feeds.values_mut() {
+            match new {
+                Some(new) => members.iter_mut().filter(|member| *member == old).for_each(|member| *member = new.to_string()),
+                None => members.retain(|member| member != old),
+            }
+        }
+        self.virtual_feeds.retain(|_, members| !members.is_empty());
+    }
+    
     pub fn validate(&self) -> Result<()> {
         // Don't require feeds to be configured for basic validation
-        for (name, url) in &self.feeds {
-            if name.is_empty() {
-                return Err(ConfigError::Invalid("Feed name cannot be empty".to_string()).into());
+        for (name, entry) in &self.feeds {
+            validate_feed_name(name)?;
+            
+            // A group directory cannot also be a feed
+            let mut prefix = name.as_str();
+            while let Some((group, _)) = prefix.rsplit_once('/') {
+                if self.feeds.contains_key(group) {
+                    return Err(ConfigError::Invalid(format!(
+                        "Feed '{}' conflicts with feed group of '{}'", group, name
+                    )));
+                }
+                prefix = group;
+            }
+            
+            let url = url::Url::parse(entry.url())
+                .map_err(|_| ConfigError::InvalidUrl(entry.url().to_string()))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(ConfigError::InvalidUrl(format!("{} (feeds are fetched over http or https)", entry.url())));
             }
             
-            url::Url::parse(url)
-                .map_err(|_| ConfigError::InvalidUrl(url.clone()))?;
+            if let Some(extractor) = entry.extractor() {
+                extractor.validate(name)?;
+            }
+            
+            if let Some(template) = entry.filename_template(&self.fuse) {
+                FilenameTemplate::parse(template)?;
+            }
+            
+            if let Some(unknown) = entry.languages().into_iter().flatten().find(|code| language::normalize_code(code).is_none()) {
+                return Err(ConfigError::Invalid(format!(
+                    "Feed '{}' lists unknown language '{}'", name, unknown
+                )));
+            }
+            
+            entry.network(&self.network)?;
+            
+            if let FeedEntry::Table(table) = entry {
+                if table.refresh_interval == Some(0) || table.max_articles == Some(0) {
+                    return Err(ConfigError::Invalid(format!(
+                        "Feed '{}': refresh_interval and max_articles must be greater than 0", name
+                    )));
+                }
+            }
+        }
+        
+        // Names that only collide once slugged are told apart rather than
+        // refused, so the config still loads and the feeds can be renamed
+        let mut renumbered: Vec<_> = self.renumbered_feed_dirs().into_iter().collect();
+        renumbered.sort();
+        for (name, dir_name) in renumbered {
+            tracing::warn!(
+                "Feed '{}' shares its directory with another feed or group; listing it as '{}'", name, dir_name
+            );
+        }
+        let dirs = self.feed_dir_paths();
+        
+        // Enabled top-level symlink directories take their names
+        let reserved = [
+            ("latest", self.fuse.latest_articles > 0),
+            ("today", self.fuse.today_dir),
+            ("starred", self.fuse.starred_dir),
+            ("tags", self.fuse.tags_dir),
+            ("top", self.scoring.top_articles > 0 && !self.scoring.is_disabled()),
+        ];
+        for (dir, enabled) in reserved {
+            if enabled && dirs.values().any(|path| path[0] == dir) {
+                return Err(ConfigError::Invalid(format!(
+                    "Feed or group '{}' conflicts with the {}/ directory", dir, dir
+                )));
+            }
+        }
+        
+        for (name, members) in &self.virtual_feeds {
+            validate_feed_name(name)?;
+            if name.contains('/') || name.starts_with('.') {
+                return Err(ConfigError::Invalid(format!(
+                    "Virtual feed '{}' must be a top-level name without '/' or a leading '.'", name
+                )));
+            }
+            let taken = self.feeds.keys().any(|feed| feed.split('/').next() == Some(name.as_str()))
+                || reserved.iter().any(|(dir, enabled)| *enabled && dir == name);
+            if taken {
+                return Err(ConfigError::Invalid(format!(
+                    "Virtual feed '{}' conflicts with a feed, group or directory of that name", name
+                )));
+            }
+            if members.is_empty() {
+                return Err(ConfigError::Invalid(format!("Virtual feed '{}' lists no feeds", name)));
+            }
+            if let Some(unknown) = members.iter().find(|member| !self.feeds.contains_key(*member)) {
+                return Err(ConfigError::Invalid(format!(
+                    "Virtual feed '{}' lists unknown feed '{}'", name, unknown
+                )));
+            }
+        }
+        
+        self.network.options()?;
+        self.filters.validate()?;
+        if let Some(sync) = &self.sync {
+            sync.validate()?;
+        }
+        self.notifications.validate()?;
+        self.summarizer.validate()?;
+        self.scoring.validate()?;
+        if self.network.requests_per_minute == Some(0) || self.network.host_burst == 0
+            || self.network.max_concurrent_requests == Some(0)
+        {
+            return Err(ConfigError::Invalid(
+                "Network requests_per_minute, host_burst and max_concurrent_requests must be greater than 0".to_string()
+            ));
         }
         
         if self.settings.refresh_interval == 0 {
@@ -180,6 +1538,49 @@ impl Config {
             return Err(ConfigError::Invalid("Max articles must be greater than 0".to_string()).into());
         }
         
+        if self.settings.timeout == 0 || self.settings.concurrent_fetches == 0 || self.settings.max_article_size == 0
+            || self.settings.max_feed_size_mb == 0 || self.settings.max_feed_items == 0
+        {
+            return Err(ConfigError::Invalid(
+                "Settings timeout, concurrent_fetches, max_article_size, max_feed_size_mb and max_feed_items must be greater than 0".to_string()
+            ));
+        }
+        
+        if self.cache.max_size_mb == 0 {
+            return Err(ConfigError::Invalid("Cache max_size_mb must be greater than 0".to_string()));
+        }
+        
+        StorageBackend::parse(&self.cache.storage)
+            .map_err(|e| ConfigError::Invalid(format!("Cache storage: {}", e)))?;
+        self.cache.compression()
+            .map_err(|e| ConfigError::Invalid(format!("Cache compression: {}", e)))?;
+        
+        if self.settings.max_article_age_days == Some(0) {
+            return Err(ConfigError::Invalid("Max article age must be greater than 0".to_string()));
+        }
+        
+        let (soft, hard) = (&self.limits.soft, &self.limits.hard);
+        let soft_above_hard = |soft: Option<u64>, hard: Option<u64>| matches!((soft, hard), (Some(s), Some(h)) if s > h);
+        if soft_above_hard(soft.max_feeds.map(|v| v as u64), hard.max_feeds.map(|v| v as u64))
+            || soft_above_hard(soft.max_total_articles.map(|v| v as u64), hard.max_total_articles.map(|v| v as u64))
+            || soft_above_hard(soft.max_cache_mb, hard.max_cache_mb)
+        {
+            return Err(ConfigError::Invalid("Soft limits must not exceed hard limits".to_string()));
+        }
+        
+        let aging = &self.fuse.article_aging;
+        if aging.enabled {
+            match aging.marker {
+                AgingMarker::Folder if aging.folder.is_empty() || aging.folder.contains('/') => {
+                    return Err(ConfigError::Invalid("Article aging folder must be a single path component".to_string()));
+                }
+                AgingMarker::Suffix if aging.suffix.is_empty() || aging.suffix.contains('/') => {
+                    return Err(ConfigError::Invalid("Article aging suffix must be non-empty and contain no '/'".to_string()));
+                }
+                _ => {}
+            }
+        }
+        
         Ok(())
     }
     
@@ -208,24 +1609,33 @@ impl Config {
             fuse: FilesystemConfig::default(),
             cache: CacheSettings::default(),
             logging: LoggingConfig::default(),
+            limits: LimitsConfig::default(),
+            network: NetworkConfig::default(),
+            filters: FiltersConfig::default(),
+            virtual_feeds: BTreeMap::new(),
+            sync: None,
+            metrics: MetricsConfig::default(),
+            notifications: NotificationsConfig::default(),
+            summarizer: SummarizerConfig::default(),
+            scoring: ScoringConfig::default(),
         }
     }
     
     pub fn config_dir() -> Result<PathBuf> {
         dirs::config_dir()
-            .map(|dir| dir.join("rss-fuse"))
+            .map(|dir| dir.join(crate::profile::dir_name()))
             .ok_or_else(|| ConfigError::Invalid("Could not determine config directory".to_string()).into())
     }
     
     pub fn data_dir() -> Result<PathBuf> {
         dirs::data_dir()
-            .map(|dir| dir.join("rss-fuse"))
+            .map(|dir| dir.join(crate::profile::dir_name()))
             .ok_or_else(|| ConfigError::Invalid("Could not determine data directory".to_string()).into())
     }
     
     pub fn cache_dir() -> Result<PathBuf> {
         dirs::cache_dir()
-            .map(|dir| dir.join("rss-fuse"))
+            .map(|dir| dir.join(crate::profile::dir_name()))
             .ok_or_else(|| ConfigError::Invalid("Could not determine cache directory".to_string()).into())
     }
 }
@@ -237,11 +1647,18 @@ impl Default for Settings {
             cache_duration: default_cache_duration(),
             max_articles: default_max_articles(),
             concurrent_fetches: default_concurrent_fetches(),
-            article_content: default_article_content(),
-            user_agent: default_user_agent(),
+            article_content: ArticleContentMode::default(),
             timeout: default_timeout(),
             retry_attempts: default_retry_attempts(),
+            retry_backoff_ms: default_retry_backoff_ms(),
+            retry_max_backoff: default_retry_max_backoff(),
+            retry_jitter: default_retry_jitter(),
+            failure_threshold: default_failure_threshold(),
             max_article_size: default_max_article_size(),
+            max_feed_size_mb: default_max_feed_size_mb(),
+            max_feed_items: default_max_feed_items(),
+            max_article_age_days: None,
+            tracking_params: default_tracking_params(),
         }
     }
 }
@@ -255,6 +1672,29 @@ impl Default for FilesystemConfig {
             allow_other: false,
             auto_unmount: default_auto_unmount(),
             auto_open: FileManagerConfig::default(),
+            article_aging: ArticleAgingConfig::default(),
+            transliterate_names: false,
+            output_format: OutputFormat::default(),
+            filename_template: None,
+            text_html_policy: TextHtmlPolicy::default(),
+            delete_marks_read: default_delete_marks_read(),
+            hide_read_articles: default_hide_read_articles(),
+            read_state_views: false,
+            feed_meta: default_feed_meta(),
+            latest_articles: 0,
+            today_dir: false,
+            starred_dir: default_starred_dir(),
+            tags_dir: false,
+            enclosures: EnclosureMode::default(),
+            offline_images: false,
+            control_files: default_control_files(),
+            slow_op_threshold_ms: default_slow_op_threshold_ms(),
+            spill_content_bytes: default_spill_content_bytes(),
+            date_folders_after: default_date_folders_after(),
+            refresh_on_access: 0,
+            duplicates: DuplicateMode::default(),
+            article_content: ArticleContentMode::default(),
+            scoring: ScoringConfig::default(),
         }
     }
 }
@@ -264,6 +1704,11 @@ impl Default for CacheSettings {
         Self {
             max_size_mb: default_max_size_mb(),
             cleanup_interval: default_cleanup_interval(),
+            http_cache: default_http_cache(),
+            http_max_age: None,
+            storage: default_storage(),
+            compression: Codec::default(),
+            compression_level: None,
         }
     }
 }
@@ -284,13 +1729,23 @@ fn default_refresh_interval() -> u64 { 300 }
 fn default_cache_duration() -> u64 { 3600 }
 fn default_max_articles() -> usize { 100 }
 fn default_concurrent_fetches() -> usize { 5 }
-fn default_article_content() -> bool { true }
-fn default_user_agent() -> String { 
-    format!("RSS-FUSE/{}", env!("CARGO_PKG_VERSION"))
-}
+fn default_host_burst() -> u32 { 1 }
+fn default_sync_import_subscriptions() -> bool { true }
+fn default_sync_max_items() -> usize { 1000 }
+fn default_notification_batch_window() -> u64 { 60 }
+fn default_summarizer_timeout() -> u64 { 120 }
 fn default_timeout() -> u64 { 30 }
 fn default_retry_attempts() -> usize { 3 }
+fn default_retry_backoff_ms() -> u64 { 1000 }
+fn default_retry_max_backoff() -> u64 { 60 }
+fn default_retry_jitter() -> bool { true }
+fn default_failure_threshold() -> u32 { 3 }
 fn default_max_article_size() -> usize { 1024 * 1024 } // 1MB
+fn default_max_feed_size_mb() -> u64 { 20 }
+fn default_max_feed_items() -> usize { 10_000 }
+fn default_tracking_params() -> Vec<String> {
+    DEFAULT_TRACKING_PARAMS.iter().map(|param| param.to_string()).collect()
+}
 
 fn default_mount_options() -> Vec<String> {
     vec!["ro".to_string(), "auto_unmount".to_string()]
@@ -298,14 +1753,547 @@ fn default_mount_options() -> Vec<String> {
 fn default_file_permissions() -> u32 { 0o644 }
 fn default_dir_permissions() -> u32 { 0o755 }
 fn default_auto_unmount() -> bool { true }
+fn default_delete_marks_read() -> bool { false }
+fn default_hide_read_articles() -> bool { true }
+fn default_feed_meta() -> bool { true }
+fn default_control_files() -> bool { false }
+fn default_starred_dir() -> bool { false }
+fn default_slow_op_threshold_ms() -> u64 { 1000 }
+fn default_spill_content_bytes() -> usize { 32 * 1024 }
+fn default_date_folders_after() -> usize { 500 }
+
+// Article aging defaults
+fn default_aging_days() -> u64 { 30 }
+fn default_aging_marker() -> AgingMarker { AgingMarker::Permissions }
+fn default_aging_permissions() -> u32 { 0o400 }
+fn default_aging_suffix() -> String { ".old".to_string() }
+fn default_aging_folder() -> String { "old".to_string() }
 
 fn default_log_level() -> String { "info".to_string() }
 fn default_max_size_mb() -> usize { 100 }
 fn default_cleanup_interval() -> u64 { 300 }
+fn default_http_cache() -> bool { true }
+fn default_storage() -> String { "memory://".to_string() }
 fn default_log_file() -> String { "logs/rss-fuse.log".to_string() }
 
 // File manager defaults
 fn default_file_manager() -> String { "ranger".to_string() }
 fn default_terminal_command() -> String { "xterm".to_string() }
 fn default_launch_delay() -> u64 { 1 }
-fn default_auto_detect() -> bool { true }
\ No newline at end of file
+fn default_auto_detect() -> bool { true }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_entries_accept_url_or_table() {
+        let config: Config = toml::from_str(r#"
+[feeds]
+"rust-blog" = "https://blog.rust-lang.org/feed.xml"
+
+[feeds."recycled"]
+url = "https://example.com/rss"
+id_strategy = "content-hash"
+
+[settings]
+"#).unwrap();
+
+        let plain = &config.feeds["rust-blog"];
+        assert_eq!(plain.url(), "https://blog.rust-lang.org/feed.xml");
+        assert_eq!(plain.id_strategy(), IdStrategy::Guid);
+
+        let table = &config.feeds["recycled"];
+        assert_eq!(table.url(), "https://example.com/rss");
+        assert_eq!(table.id_strategy(), IdStrategy::ContentHash);
+
+        // Mixed entries survive a save/load round trip
+        let saved = toml::to_string_pretty(&config).unwrap();
+        let reloaded: Config = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.feeds, config.feeds);
+    }
+
+    #[test]
+    fn test_per_feed_overrides() {
+        let config: Config = toml::from_str(r#"
+[feeds]
+"plain" = "https://example.com/rss"
+
+[feeds.busy]
+url = "https://busy.example.com/feed"
+refresh_interval = 600
+max_articles = 20
+max_visible_articles = 10
+notify = false
+languages = ["en", "deu"]
+
+[settings]
+refresh_interval = 3600
+max_articles = 100
+"#).unwrap();
+        config.validate().unwrap();
+
+        let (plain, busy) = (&config.feeds["plain"], &config.feeds["busy"]);
+        assert_eq!(plain.refresh_interval(&config.settings), 3600);
+        assert_eq!(plain.max_articles(&config.settings), 100);
+        assert_eq!(busy.refresh_interval(&config.settings), 600);
+        assert_eq!(busy.max_articles(&config.settings), 20);
+        assert_eq!(plain.max_visible_articles(), None);
+        assert_eq!(busy.max_visible_articles(), Some(10));
+        assert_eq!(busy.languages().unwrap(), ["en", "deu"]);
+        assert!(plain.notify() && !busy.notify());
+        assert!(plain.enabled() && busy.enabled());
+
+        // Disabling a plain entry turns it into a table, and enabling it
+        // drops the key again
+        let mut toggled = plain.clone();
+        toggled.set_enabled(false);
+        assert!(!toggled.enabled());
+        assert_eq!(toggled.to_toml("plain").unwrap(), "[feeds.plain]\nurl = \"https://example.com/rss\"\nenabled = false\n");
+        toggled.set_enabled(true);
+        assert!(toggled.enabled());
+        assert_eq!(toggled.to_toml("plain").unwrap(), "[feeds.plain]\nurl = \"https://example.com/rss\"\n");
+
+        let mut invalid = config.clone();
+        if let FeedEntry::Table(table) = invalid.feeds.get_mut("busy").unwrap() {
+            table.refresh_interval = Some(0);
+        }
+        assert!(invalid.validate().is_err());
+        let mut invalid = config.clone();
+        if let FeedEntry::Table(table) = invalid.feeds.get_mut("busy").unwrap() {
+            table.languages = Some(vec!["english".to_string()]);
+        }
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_recent_directories_reserve_names() {
+        let mut config: Config = toml::from_str(r#"
+[feeds]
+"today/world" = "https://example.com/rss"
+
+[settings]
+"#).unwrap();
+        config.validate().unwrap();
+
+        config.fuse.today_dir = true;
+        assert!(config.validate().is_err());
+        config.fuse.today_dir = false;
+        config.fuse.latest_articles = 10;
+        config.validate().unwrap();
+
+        config.feeds.insert("starred".to_string(), "https://example.com/starred.xml".to_string().into());
+        config.validate().unwrap();
+        config.fuse.starred_dir = true;
+        assert!(config.validate().is_err());
+        config.fuse.starred_dir = false;
+
+        config.feeds.insert("tags/rust".to_string(), "https://example.com/rust.xml".to_string().into());
+        config.validate().unwrap();
+        config.fuse.tags_dir = true;
+        assert!(config.validate().is_err());
+
+        config.feeds.insert("top".to_string(), "https://example.com/top.xml".to_string().into());
+        config.fuse.tags_dir = false;
+        config.validate().unwrap();
+        config.scoring.keywords.insert("rust".to_string(), 1);
+        config.scoring.top_articles = 10;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_feed_extractor_selectors() {
+        let config: Config = toml::from_str(r##"
+[feeds]
+"plain" = "https://example.com/rss"
+
+[feeds.blog]
+url = "https://blog.example.com/feed"
+
+[feeds.blog.extractor]
+article = [".story-body", "#main"]
+remove = [".newsletter-signup"]
+
+[settings]
+"##).unwrap();
+        config.validate().unwrap();
+
+        assert!(config.feeds["plain"].extractor().is_none());
+        let selectors = config.feeds["blog"].extractor().unwrap().selectors();
+        assert_eq!(selectors.article, [".story-body", "#main"]);
+        assert_eq!(selectors.remove, [".newsletter-signup"]);
+        assert_eq!(selectors.content, ContentSelectors::default().content);
+
+        let mut invalid = config.clone();
+        if let FeedEntry::Table(table) = invalid.feeds.get_mut("blog").unwrap() {
+            table.extractor.as_mut().unwrap().remove = Some(vec![" ".to_string()]);
+        }
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_article_content_mode() {
+        let parse = |value: &str| -> ArticleContentMode {
+            let config: Config = toml::from_str(&format!("[feeds]\n\n[settings]\narticle_content = {}\n", value)).unwrap();
+            config.settings.article_content
+        };
+
+        assert_eq!(parse("\"fetch-on-read\""), ArticleContentMode::FetchOnRead);
+        assert_eq!(parse("\"feed\""), ArticleContentMode::Feed);
+        // Older configs used a boolean
+        assert_eq!(parse("true"), ArticleContentMode::Feed);
+        assert!(toml::from_str::<Config>("[feeds]\n\n[settings]\narticle_content = \"sometimes\"\n").is_err());
+    }
+
+    #[test]
+    fn test_concurrent_fetches_alias() {
+        let config: Config = toml::from_str("[feeds]\n\n[settings]\nmax_concurrent_fetches = 12\n").unwrap();
+        assert_eq!(config.settings.concurrent_fetches, 12);
+        let config: Config = toml::from_str("[feeds]\n\n[settings]\n").unwrap();
+        assert_eq!(config.settings.concurrent_fetches, 5);
+    }
+
+    #[test]
+    fn test_tracking_params() {
+        let config: Config = toml::from_str("[feeds]\n\n[settings]\n").unwrap();
+        assert_eq!(config.settings.url_normalizer(), UrlNormalizer::default());
+        let config: Config = toml::from_str("[feeds]\n\n[settings]\ntracking_params = [\"ref\"]\n").unwrap();
+        assert_eq!(config.settings.url_normalizer().normalize("https://a.example/x?ref=rss&utm_source=y"),
+                   "https://a.example/x?utm_source=y");
+    }
+
+    #[test]
+    fn test_feed_limits() {
+        let config: Config = toml::from_str("[feeds]\n\n[settings]\nmax_feed_size_mb = 2\nmax_feed_items = 50\n").unwrap();
+        let limits = config.settings.feed_limits();
+        assert_eq!(limits.max_response_size, 2 * 1024 * 1024);
+        assert_eq!(limits.parse, ParseLimits { max_items: 50, max_item_size: 1024 * 1024 });
+
+        let mut bad = config.clone();
+        bad.settings.max_feed_items = 0;
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_storage_backend() {
+        let config: Config = toml::from_str("[feeds]\nnews = \"https://news.example/rss\"\n\n[settings]\n\n[cache]\nstorage = \"dir:///var/lib/rss-fuse\"\n").unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.storage_config().connection_string, "dir:///var/lib/rss-fuse");
+        assert_eq!(Config::default().storage_config().connection_string, "memory://");
+
+        let mut bad = config.clone();
+        bad.cache.storage = "postgres://localhost".to_string();
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_cache_compression() {
+        let config: Config = toml::from_str("[feeds]\nnews = \"https://news.example/rss\"\n\n[settings]\n\n[cache]\ncompression = \"zstd\"\ncompression_level = 9\n").unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.cache.compression().unwrap(), Compression { codec: Codec::Zstd, level: 9 });
+        assert_eq!(Config::default().cache.compression().unwrap(), Compression::default());
+
+        let mut bad = config.clone();
+        bad.cache.compression = Codec::Lz4;
+        assert!(bad.validate().is_err());
+        bad.cache.compression_level = None;
+        bad.validate().unwrap();
+    }
+
+    #[test]
+    fn test_feed_groups() {
+        let config: Config = toml::from_str(r#"
+[feeds]
+"news/hn" = "https://news.ycombinator.com/rss"
+
+[feeds.tech]
+"rust-blog" = "https://blog.rust-lang.org/feed.xml"
+
+[feeds.tech.recycled]
+url = "https://example.com/rss"
+id_strategy = "link"
+
+[settings]
+"#).unwrap();
+
+        let mut names: Vec<_> = config.feeds.keys().cloned().collect();
+        names.sort();
+        assert_eq!(names, ["news/hn", "tech/recycled", "tech/rust-blog"]);
+        assert_eq!(config.feeds["tech/recycled"].id_strategy(), IdStrategy::Link);
+        config.validate().unwrap();
+
+        // Groups are saved as flat "group/name" keys
+        let saved = toml::to_string_pretty(&config).unwrap();
+        let reloaded: Config = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.feeds, config.feeds);
+
+        assert!(config.check_new_feed_name("tech/other").is_ok());
+        assert!(config.check_new_feed_name("tech").is_err());
+        assert!(config.check_new_feed_name("news/hn/extra").is_err());
+        assert!(config.check_new_feed_name("tech//x").is_err());
+        assert!(matches!(config.check_new_feed_name("news/hn"), Err(ConfigError::AlreadyExists(_))));
+    }
+
+    #[test]
+    fn test_duplicate_grouped_feed_rejected() {
+        let result: std::result::Result<Config, _> = toml::from_str(r#"
+[feeds]
+"tech/rust" = "https://a.example/rss"
+
+[feeds.tech]
+rust = "https://b.example/rss"
+
+[settings]
+"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_feed_names_sharing_a_directory() {
+        let mut config = Config::default();
+        config.feeds.insert("news/a:b".to_string(), "https://a.example/rss".to_string().into());
+        assert_eq!(config.feed_dir_path("news/a:b"), ["news", "a-b"]);
+        
+        assert!(matches!(config.check_new_feed_name("news/a-b"), Err(ConfigError::AlreadyExists(_))));
+        assert!(matches!(config.check_new_feed_name(".news/a|b"), Err(ConfigError::AlreadyExists(_))));
+        assert!(config.check_new_feed_name(".news").is_err());
+        assert!(config.check_new_feed_name("news/a-c").is_ok());
+        
+        // Feeds already sharing a directory still load, told apart by number
+        config.feeds.insert("news/a-b".to_string(), "https://b.example/rss".to_string().into());
+        config.feeds.insert("news/a|b".to_string(), "https://c.example/rss".to_string().into());
+        assert!(config.validate().is_ok());
+        assert_eq!(config.feed_dir_path("news/a-b"), ["news", "a-b"]);
+        assert_eq!(config.feed_dir_path("news/a:b"), ["news", "a-b (2)"]);
+        assert_eq!(config.feed_dir_path("news/a|b"), ["news", "a-b (3)"]);
+        assert_eq!(config.renumbered_feed_dirs().len(), 2);
+        
+        // Including a feed whose directory is another feed's group
+        config.feeds.insert(".news".to_string(), "https://d.example/rss".to_string().into());
+        assert!(config.validate().is_ok());
+        assert_eq!(config.feed_dir_path(".news"), ["news (2)"]);
+        
+        // Exact group names are still refused
+        config.feeds.insert("news".to_string(), "https://e.example/rss".to_string().into());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_feeds_toml_round_trip() {
+        let mut config = Config::default();
+        config.feeds.insert("tech/rust".to_string(), "https://a.example/rss".to_string().into());
+        let listed = config.feeds_toml().unwrap();
+
+        config.set_feeds_toml(&format!("{}\nnews = \"https://b.example/rss\"\n", listed)).unwrap();
+        assert_eq!(config.feeds.len(), 2);
+        assert_eq!(config.feeds["news"].url(), "https://b.example/rss");
+
+        // An invalid list leaves the feeds alone
+        assert!(config.set_feeds_toml("[feeds]\nbad = \"not a url\"\n").is_err());
+        assert_eq!(config.feeds.len(), 2);
+
+        config.set_feeds_toml("").unwrap();
+        assert!(config.feeds.is_empty());
+    }
+
+    #[test]
+    fn test_network_config() {
+        let config: Config = toml::from_str(r#"
+[feeds]
+open = "https://a.example/rss"
+
+[feeds.hidden]
+url = "http://example.onion/rss"
+proxy = "socks5h://127.0.0.1:9050"
+
+[feeds.intranet]
+url = "https://intranet.example/rss"
+proxy = "none"
+
+[settings]
+
+[feeds.picky]
+url = "https://picky.example/rss"
+user_agent = "Mozilla/5.0"
+headers = { "accept-language" = "de" }
+
+[network]
+proxy = "http://proxy.corp:3128"
+ca_certificates = ["/etc/ssl/corp.pem"]
+headers = { "Accept-Language" = "en", "X-Token" = "abc" }
+"#).unwrap();
+        config.validate().unwrap();
+
+        let options = config.network.options().unwrap();
+        assert_eq!(options.proxy, ProxyMode::Url("http://proxy.corp:3128".to_string()));
+        assert_eq!(options.ca_certificates, [PathBuf::from("/etc/ssl/corp.pem")]);
+        assert!(!options.insecure_skip_verify);
+
+        assert_eq!(config.feeds["open"].network(&config.network).unwrap(), None);
+        let hidden = config.feeds["hidden"].network(&config.network).unwrap().unwrap();
+        assert_eq!(hidden.proxy, ProxyMode::Url("socks5h://127.0.0.1:9050".to_string()));
+        assert_eq!(hidden.ca_certificates, options.ca_certificates);
+        let intranet = config.feeds["intranet"].network(&config.network).unwrap().unwrap();
+        assert_eq!(intranet.proxy, ProxyMode::Direct);
+        assert_eq!(intranet.headers, options.headers);
+
+        // Feed headers replace global ones regardless of case
+        let picky = config.feeds["picky"].network(&config.network).unwrap().unwrap();
+        assert_eq!(picky.proxy, options.proxy);
+        assert_eq!(picky.user_agent.as_deref(), Some("Mozilla/5.0"));
+        assert_eq!(picky.headers, BTreeMap::from([
+            ("X-Token".to_string(), "abc".to_string()),
+            ("accept-language".to_string(), "de".to_string()),
+        ]));
+
+        let mut bad = config.clone();
+        bad.network.proxy = Some("gopher://proxy".to_string());
+        assert!(bad.validate().is_err());
+        let mut bad = config.clone();
+        bad.network.headers.insert("Bad Name".to_string(), "x".to_string());
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_rate_limits() {
+        let config: Config = toml::from_str("[feeds]
+
+[settings]
+").unwrap();
+        assert_eq!(config.network.rate_limits(), RateLimits::default());
+
+        let config: Config = toml::from_str(
+            "[feeds]
+
+[settings]
+
+[network]
+requests_per_minute = 30
+host_burst = 3
+max_concurrent_requests = 8
+"
+        ).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.network.rate_limits(), RateLimits {
+            requests_per_minute: Some(30),
+            burst: 3,
+            max_concurrent: Some(8),
+        });
+
+        let mut bad = config.clone();
+        bad.network.requests_per_minute = Some(0);
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_virtual_feeds() {
+        let mut config: Config = toml::from_str(r#"
+[feeds]
+rust-blog = "https://blog.rust-lang.org/feed.xml"
+"rust/twir" = "https://this-week-in-rust.org/rss.xml"
+
+[settings]
+
+[virtual-feeds]
+all-rust = ["rust-blog", "rust/twir"]
+"#).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.virtual_feeds["all-rust"], ["rust-blog", "rust/twir"]);
+        assert!(config.check_new_feed_name("all-rust/more").is_err());
+
+        config.retarget_virtual_feeds("rust/twir", Some("twir"));
+        assert_eq!(config.virtual_feeds["all-rust"], ["rust-blog", "twir"]);
+        config.retarget_virtual_feeds("rust-blog", None);
+        config.retarget_virtual_feeds("twir", None);
+        assert!(config.virtual_feeds.is_empty());
+
+        let mut bad = config.clone();
+        bad.virtual_feeds.insert("all".to_string(), vec!["missing".to_string()]);
+        assert!(bad.validate().is_err());
+
+        let mut bad = config.clone();
+        bad.virtual_feeds.insert("rust".to_string(), vec!["rust-blog".to_string()]);
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_filter_rules() {
+        let config: Config = toml::from_str(r#"
+[feeds]
+"tech/lwn" = "https://lwn.net/headlines/rss"
+news = "https://news.example/rss"
+
+[settings]
+
+[[filters.rules]]
+title = "(?i)sponsored"
+action = "drop"
+
+[[filters.rules]]
+feeds = ["tech"]
+tags = "^security$"
+action = "folder"
+folder = "security"
+"#).unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(config.filters.rules_for("news").len(), 1);
+        assert_eq!(config.filters.rules_for("tech/lwn").len(), 2);
+        assert_eq!(config.filters.rules_for("technology").len(), 1);
+        assert!(!config.filters.filter_for("tech/lwn").unwrap().is_empty());
+
+        let mut bad = config.clone();
+        bad.filters.rules[0].title = Some("(unclosed".to_string());
+        assert!(bad.validate().is_err());
+
+        let mut bad = config.clone();
+        bad.filters.rules[1].folder = Some("../escape".to_string());
+        assert!(bad.validate().is_err());
+
+        let mut bad = config.clone();
+        bad.filters.rules[0].title = None;
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_notifications() {
+        let config: Config = toml::from_str(r#"
+[feeds]
+news = "https://news.example/rss"
+
+[settings]
+
+[notifications]
+webhooks = ["https://ntfy.sh/my-feeds"]
+"#).unwrap();
+        config.validate().unwrap();
+        assert!(!config.notifications.is_disabled());
+        assert!(!config.notifications.desktop);
+        assert_eq!(config.notifications.batch_window, 60);
+        assert!(Config::default().notifications.is_disabled());
+
+        let mut bad = config.clone();
+        bad.notifications.webhooks.push("ftp://example.com/hook".to_string());
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_summarizer() {
+        let config: Config = toml::from_str(r#"
+[feeds]
+news = "https://news.example/rss"
+
+[settings]
+
+[summarizer]
+command = "ollama run llama3 'Summarize in two sentences:'"
+"#).unwrap();
+        config.validate().unwrap();
+        assert!(!config.summarizer.is_disabled());
+        assert_eq!(config.summarizer.timeout, 120);
+        assert!(Config::default().summarizer.is_disabled());
+
+        let mut bad = config.clone();
+        bad.summarizer.command = Some(" ".to_string());
+        assert!(bad.validate().is_err());
+    }
+}
@@ -0,0 +1,368 @@
+//! Thorough config checks for `rss-fuse config validate`: TOML and type
+//! errors, keys the config does not know, the rules of `Config::validate`,
+//! and values that work but are probably not meant. Problems carry the line
+//! of the config file they were found on where there is one.
+
+use std::fmt;
+use std::ops::Range;
+use serde::Deserialize;
+use toml_edit::{ImDocument, Item, TableLike, Value};
+
+use crate::config::{Config, FeedEntry};
+
+/// Refresh intervals below this many seconds get a warning
+const MIN_SENSIBLE_REFRESH: u64 = 60;
+
+/// Per-feed article counts above this get a warning
+const MAX_SENSIBLE_ARTICLES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+/// One problem found in a config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub level: Level,
+    /// 1-based line in the config file
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.level {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        };
+        match self.line {
+            Some(line) => write!(f, "line {}: {}: {}", line, level, self.message),
+            None => write!(f, "{}: {}", level, self.message),
+        }
+    }
+}
+
+/// A step into a TOML document: a table key or an array index
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Check the text of a config file. With `strict`, keys the config does not
+/// know are errors instead of warnings.
+pub fn check(content: &str, strict: bool) -> Vec<Diagnostic> {
+    let error = |span: Option<Range<usize>>, message: String| Diagnostic {
+        level: Level::Error,
+        line: span.map(|span| line_of(content, span.start)),
+        message,
+    };
+
+    let document = match ImDocument::parse(content) {
+        Ok(document) => document,
+        Err(e) => return vec![error(e.span(), e.message().trim().to_string())],
+    };
+    let config: Config = match toml::from_str(content) {
+        Ok(config) => config,
+        Err(e) => return vec![error(e.span(), e.message().trim().to_string())],
+    };
+    let line = |path: &[&str]| key_span(&document, &keys(path)).map(|span| line_of(content, span.start));
+
+    let mut diagnostics: Vec<Diagnostic> = unknown_paths(content)
+        .into_iter()
+        .map(|path| Diagnostic {
+            level: if strict { Level::Error } else { Level::Warning },
+            line: key_span(&document, &path).map(|span| line_of(content, span.start)),
+            message: format!("unknown key '{}' is ignored", display_path(&path)),
+        })
+        .collect();
+
+    if let Err(e) = config.validate() {
+        diagnostics.push(Diagnostic { level: Level::Error, line: None, message: e.to_string() });
+    }
+
+    let mut warn = |line: Option<usize>, message: String| {
+        diagnostics.push(Diagnostic { level: Level::Warning, line, message });
+    };
+    let settings = &config.settings;
+    if settings.refresh_interval < MIN_SENSIBLE_REFRESH {
+        warn(line(&["settings", "refresh_interval"]), format!(
+            "refresh_interval = {} polls every feed more than once a minute; servers may rate-limit you",
+            settings.refresh_interval
+        ));
+    }
+    if settings.timeout >= settings.refresh_interval {
+        warn(line(&["settings", "timeout"]), format!(
+            "timeout = {} is not shorter than refresh_interval = {}; a slow server can delay the next refresh",
+            settings.timeout, settings.refresh_interval
+        ));
+    }
+    if settings.max_articles > MAX_SENSIBLE_ARTICLES {
+        warn(line(&["settings", "max_articles"]), format!(
+            "max_articles = {} keeps very large feed directories in memory", settings.max_articles
+        ));
+    }
+
+    let mut feeds: Vec<_> = config.feeds.iter().collect();
+    feeds.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, entry) in feeds {
+        if let FeedEntry::Table(table) = entry {
+            if table.refresh_interval.is_some_and(|interval| interval < MIN_SENSIBLE_REFRESH) {
+                let mut path: Vec<&str> = vec!["feeds"];
+                path.extend(name.split('/'));
+                path.push("refresh_interval");
+                warn(line(&path).or_else(|| line(&["feeds", name, "refresh_interval"])), format!(
+                    "feed '{}' refreshes more than once a minute; servers may rate-limit you", name
+                ));
+            }
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.line.is_none(), d.line));
+    diagnostics
+}
+
+/// Dotted paths of the keys in `content` the config ignores
+pub fn unknown_keys(content: &str) -> Vec<String> {
+    unknown_paths(content).iter().map(|path| display_path(path)).collect()
+}
+
+/// Keys present in `content` that change nothing when removed and are not
+/// written back when the parsed config is saved: misspelt or retired options
+fn unknown_paths(content: &str) -> Vec<Vec<Segment>> {
+    let Ok(table) = toml::from_str::<toml::Table>(content) else { return Vec::new() };
+    let user = toml::Value::Table(table);
+    let Some(canonical) = canonical(&user) else { return Vec::new() };
+
+    let mut unknown = Vec::new();
+    let mut path = Vec::new();
+    collect_unknown(&user, &user, &canonical, &mut path, &mut unknown);
+    unknown
+}
+
+/// Log a warning for every key of `content` the config ignores
+pub fn warn_unknown_keys(content: &str, source: &std::path::Path) {
+    for key in unknown_keys(content) {
+        tracing::warn!("{}: unknown key '{}' is ignored", source.display(), key);
+    }
+}
+
+/// `value` parsed as a config and serialized again
+fn canonical(value: &toml::Value) -> Option<toml::Value> {
+    let config = Config::deserialize(value.clone()).ok()?;
+    toml::Value::try_from(&config).ok()
+}
+
+fn collect_unknown(
+    root: &toml::Value,
+    value: &toml::Value,
+    canonical: &toml::Value,
+    path: &mut Vec<Segment>,
+    unknown: &mut Vec<Vec<Segment>>,
+) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, child) in table {
+                path.push(Segment::Key(key.clone()));
+                if present(canonical, path) || affects(root, path, canonical) {
+                    collect_unknown(root, child, canonical, path, unknown);
+                } else {
+                    unknown.push(path.clone());
+                }
+                path.pop();
+            }
+        }
+        toml::Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                path.push(Segment::Index(i));
+                collect_unknown(root, item, canonical, path, unknown);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether the saved config still has `path`. `[feeds.<group>]` tables
+/// come back as `"<group>/<name>"` keys.
+fn present(value: &toml::Value, path: &[Segment]) -> bool {
+    match path.split_first() {
+        None => true,
+        Some((Segment::Index(i), rest)) => value.as_array()
+            .and_then(|items| items.get(*i))
+            .is_some_and(|item| present(item, rest)),
+        Some((Segment::Key(key), rest)) => {
+            let Some(table) = value.as_table() else { return false };
+            if let Some(child) = table.get(key) {
+                return present(child, rest);
+            }
+            match rest.split_first() {
+                Some((Segment::Key(next), more)) => {
+                    let mut joined = vec![Segment::Key(format!("{}/{}", key, next))];
+                    joined.extend_from_slice(more);
+                    present(value, &joined)
+                }
+                Some((Segment::Index(_), _)) => false,
+                None => table.keys().any(|name| name.starts_with(&format!("{}/", key))),
+            }
+        }
+    }
+}
+
+/// Whether removing `path` from `root` changes the parsed config
+fn affects(root: &toml::Value, path: &[Segment], canonical: &toml::Value) -> bool {
+    let mut without = root.clone();
+    remove(&mut without, path);
+    self::canonical(&without).as_ref() != Some(canonical)
+}
+
+fn remove(value: &mut toml::Value, path: &[Segment]) {
+    match path {
+        [Segment::Key(key)] => {
+            if let Some(table) = value.as_table_mut() {
+                table.remove(key);
+            }
+        }
+        [Segment::Key(key), rest @ ..] => {
+            if let Some(child) = value.get_mut(key.as_str()) {
+                remove(child, rest);
+            }
+        }
+        [Segment::Index(i), rest @ ..] => {
+            if let Some(child) = value.get_mut(*i) {
+                remove(child, rest);
+            }
+        }
+        [] => {}
+    }
+}
+
+/// Byte range of the last key of `path` in the document
+fn key_span(document: &ImDocument<&str>, path: &[Segment]) -> Option<Range<usize>> {
+    let mut table: &dyn TableLike = document.as_table();
+    let mut span = None;
+    let mut segments = path.iter().peekable();
+    while let Some(segment) = segments.next() {
+        let Segment::Key(key) = segment else { return None };
+        let (key, item) = table.get_key_value(key)?;
+        span = key.span();
+        table = match segments.peek() {
+            None => break,
+            Some(Segment::Index(i)) => {
+                segments.next();
+                match item {
+                    Item::ArrayOfTables(tables) => tables.get(*i)?,
+                    Item::Value(Value::Array(values)) => values.get(*i)?.as_inline_table()?,
+                    _ => return None,
+                }
+            }
+            Some(Segment::Key(_)) => item.as_table_like()?,
+        };
+    }
+    span
+}
+
+fn keys(path: &[&str]) -> Vec<Segment> {
+    path.iter().map(|key| Segment::Key(key.to_string())).collect()
+}
+
+fn display_path(path: &[Segment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) if out.is_empty() => out.push_str(key),
+            Segment::Key(key) => { out.push('.'); out.push_str(key); }
+            Segment::Index(i) => out.push_str(&format!("[{}]", i)),
+        }
+    }
+    out
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(diagnostics: &[Diagnostic]) -> Vec<String> {
+        diagnostics.iter().map(|d| d.to_string()).collect()
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let content = "\
+[feeds]
+news = \"https://news.example/rss\"
+
+[feeds.tech]
+hn = { url = \"https://hn.example/rss\", refresh_intervall = 600 }
+
+[settings]
+max_concurrent_fetches = 3
+refresh_interval = 600
+colour = \"blue\"
+
+[fuse]
+tags_dir = true
+article_content = \"fetch-on-read\"
+
+[[filters.rules]]
+title = \"sponsored\"
+action = \"drop\"
+priority = 1
+
+[typo]
+a = 1
+";
+        let mut found = unknown_keys(content);
+        found.sort();
+        assert_eq!(found, [
+            "feeds.tech.hn.refresh_intervall",
+            "filters.rules[0].priority",
+            "fuse.article_content",
+            "settings.colour",
+            "typo",
+        ]);
+
+        let diagnostics = check(content, false);
+        assert!(diagnostics.iter().all(|d| d.level == Level::Warning));
+        assert_eq!(messages(&diagnostics)[0], "line 5: warning: unknown key 'feeds.tech.hn.refresh_intervall' is ignored");
+        assert!(messages(&diagnostics).contains(&"line 10: warning: unknown key 'settings.colour' is ignored".to_string()));
+        assert!(check(content, true).iter().all(|d| d.level == Level::Error));
+    }
+
+    #[test]
+    fn test_check_errors_and_ranges() {
+        let parse = check("[feeds]\nnews = \"https://news.example/rss\"\n[settings\n", false);
+        assert_eq!(parse.len(), 1);
+        assert_eq!((parse[0].level, parse[0].line), (Level::Error, Some(3)));
+
+        let typed = check("[feeds]\n\n[settings]\nrefresh_interval = \"often\"\n", false);
+        assert_eq!((typed[0].level, typed[0].line), (Level::Error, Some(4)));
+
+        let invalid = check("[feeds]\nnews = \"ftp://news.example/rss\"\n\n[settings]\n", false);
+        assert_eq!(invalid.len(), 1);
+        assert_eq!((invalid[0].level, invalid[0].line), (Level::Error, None));
+
+        let content = "\
+[feeds.news]
+url = \"https://news.example/rss\"
+refresh_interval = 10
+
+[settings]
+refresh_interval = 30
+timeout = 30
+";
+        assert_eq!(messages(&check(content, true)), [
+            "line 3: warning: feed 'news' refreshes more than once a minute; servers may rate-limit you",
+            "line 6: warning: refresh_interval = 30 polls every feed more than once a minute; servers may rate-limit you",
+            "line 7: warning: timeout = 30 is not shorter than refresh_interval = 30; a slow server can delay the next refresh",
+        ]);
+
+        assert!(check(&toml::to_string(&Config::default()).unwrap(), true).is_empty());
+    }
+}
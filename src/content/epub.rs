@@ -0,0 +1,325 @@
+//! EPUB packaging of a feed's articles for e-readers. Chapters go through the
+//! Markdown pipeline and are rendered back out as XHTML, which keeps them
+//! well-formed whatever markup the feed used.
+
+use std::sync::Arc;
+
+use html_escape::{encode_double_quoted_attribute, encode_text};
+use pulldown_cmark::{html, Event, Options, Parser, Tag};
+
+use crate::content::ContentExtractor;
+use crate::feed::Article;
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+const STYLE_CSS: &str = "\
+body { font-family: serif; line-height: 1.5; }
+h1 { font-size: 1.4em; }
+p.meta { font-size: .85em; color: #555; }
+pre { white-space: pre-wrap; font-size: .85em; }
+blockquote { margin-left: 1em; font-style: italic; }
+";
+
+/// An EPUB 2 book with one chapter per article, in the order given
+pub fn feed_epub(feed_name: &str, title: &str, articles: &[Arc<Article>], extractor: &ContentExtractor) -> Vec<u8> {
+    let chapters: Vec<(String, &Article)> = articles.iter()
+        .enumerate()
+        .map(|(index, article)| (format!("chapter-{:04}.xhtml", index + 1), article.as_ref()))
+        .collect();
+    let id = format!("urn:rss-fuse:feed:{}", feed_name);
+
+    let mut zip = ZipWriter::default();
+    // Readers sniff the format from this first, uncompressed entry
+    zip.add("mimetype", b"application/epub+zip");
+    zip.add("META-INF/container.xml", CONTAINER_XML.as_bytes());
+    zip.add("OEBPS/content.opf", package_document(&id, title, &chapters).as_bytes());
+    zip.add("OEBPS/toc.ncx", navigation(&id, title, &chapters).as_bytes());
+    zip.add("OEBPS/style.css", STYLE_CSS.as_bytes());
+    for (file_name, article) in &chapters {
+        let chapter = chapter(article, extractor);
+        zip.add(&format!("OEBPS/{}", file_name), chapter.as_bytes());
+    }
+    zip.finish()
+}
+
+fn package_document(id: &str, title: &str, chapters: &[(String, &Article)]) -> String {
+    let date = chapters.iter()
+        .filter_map(|(_, article)| article.published)
+        .max()
+        .map(|date| format!("    <dc:date>{}</dc:date>\n", date.format("%Y-%m-%d")))
+        .unwrap_or_default();
+    let manifest: String = chapters.iter()
+        .enumerate()
+        .map(|(index, (file_name, _))| format!(
+            "    <item id=\"c{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n", index + 1, file_name,
+        ))
+        .collect();
+    let spine: String = (1..=chapters.len())
+        .map(|index| format!("    <itemref idref=\"c{}\"/>\n", index))
+        .collect();
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"2.0\" unique-identifier=\"book-id\">\n\
+         <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n\
+         \x20   <dc:identifier id=\"book-id\">{id}</dc:identifier>\n\
+         \x20   <dc:title>{title}</dc:title>\n\
+         \x20   <dc:language>en</dc:language>\n\
+         \x20   <dc:creator>RSS-FUSE</dc:creator>\n\
+         {date}\
+         </metadata>\n\
+         <manifest>\n\
+         \x20   <item id=\"ncx\" href=\"toc.ncx\" media-type=\"application/x-dtbncx+xml\"/>\n\
+         \x20   <item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>\n\
+         {manifest}\
+         </manifest>\n\
+         <spine toc=\"ncx\">\n{spine}</spine>\n\
+         </package>\n",
+        id = encode_text(id),
+        title = encode_text(title),
+        date = date,
+        manifest = manifest,
+        spine = spine,
+    )
+}
+
+fn navigation(id: &str, title: &str, chapters: &[(String, &Article)]) -> String {
+    let points: String = chapters.iter()
+        .enumerate()
+        .map(|(index, (file_name, article))| format!(
+            "  <navPoint id=\"p{n}\" playOrder=\"{n}\"><navLabel><text>{title}</text></navLabel><content src=\"{src}\"/></navPoint>\n",
+            n = index + 1,
+            title = encode_text(&article.title),
+            src = file_name,
+        ))
+        .collect();
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <ncx xmlns=\"http://www.daisy.org/z3986/2005/ncx/\" version=\"2005-1\">\n\
+         <head><meta name=\"dtb:uid\" content=\"{id}\"/></head>\n\
+         <docTitle><text>{title}</text></docTitle>\n\
+         <navMap>\n{points}</navMap>\n\
+         </ncx>\n",
+        id = encode_double_quoted_attribute(id),
+        title = encode_text(title),
+        points = points,
+    )
+}
+
+fn chapter(article: &Article, extractor: &ContentExtractor) -> String {
+    let mut meta = Vec::new();
+    if let Some(author) = &article.author {
+        meta.push(encode_text(author).into_owned());
+    }
+    if let Some(published) = &article.published {
+        meta.push(published.format("%Y-%m-%d").to_string());
+    }
+    if !article.link.is_empty() {
+        meta.push(format!("<a href=\"{}\">Source</a>", encode_double_quoted_attribute(&article.link)));
+    }
+    let body = extractor.markdown_body(article)
+        .map(|markdown| markdown_to_xhtml(&markdown))
+        .unwrap_or_default();
+    let title = encode_text(&article.title);
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.1//EN\" \"http://www.w3.org/TR/xhtml11/DTD/xhtml11.dtd\">\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\">\n\
+         <head><title>{title}</title><link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/></head>\n\
+         <body>\n<h1>{title}</h1>\n<p class=\"meta\">{meta}</p>\n{body}</body>\n</html>\n",
+        title = title,
+        meta = meta.join(" · "),
+        body = body,
+    )
+}
+
+/// XHTML for Markdown, leaving out raw HTML (which need not be well-formed)
+/// and images (which would need network access), keeping their alt text
+fn markdown_to_xhtml(markdown: &str) -> String {
+    let events = Parser::new_ext(markdown, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH)
+        .filter(|event| !matches!(
+            event,
+            Event::Html(_) | Event::Start(Tag::Image(..)) | Event::End(Tag::Image(..))
+        ));
+    let mut xhtml = String::new();
+    html::push_html(&mut xhtml, events);
+    xhtml
+}
+
+/// Writes a ZIP archive of uncompressed entries, which is all EPUB needs
+#[derive(Default)]
+struct ZipWriter {
+    data: Vec<u8>,
+    central_directory: Vec<u8>,
+    entries: u16,
+}
+
+/// 1980-01-01 00:00 in MS-DOS format, so the same book is byte-identical
+const DOS_DATE: u16 = (1 << 5) | 1;
+const DOS_TIME: u16 = 0;
+
+impl ZipWriter {
+    fn add(&mut self, name: &str, contents: &[u8]) {
+        let offset = self.data.len() as u32;
+        let crc = crc32(contents);
+        let size = contents.len() as u32;
+
+        // Local file header
+        put_u32(&mut self.data, 0x0403_4b50);
+        self.put_entry_fields(false, crc, size, name);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(contents);
+
+        // Central directory record
+        let mut record = Vec::new();
+        put_u32(&mut record, 0x0201_4b50);
+        put_u16(&mut record, 20); // made by: version 2.0
+        std::mem::swap(&mut self.data, &mut record);
+        self.put_entry_fields(true, crc, size, name);
+        std::mem::swap(&mut self.data, &mut record);
+        put_u16(&mut record, 0); // disk number
+        put_u16(&mut record, 0); // internal attributes
+        put_u32(&mut record, 0); // external attributes
+        put_u32(&mut record, offset);
+        record.extend_from_slice(name.as_bytes());
+        self.central_directory.extend_from_slice(&record);
+        self.entries += 1;
+    }
+
+    /// Fields shared by local and central headers, up to the name
+    fn put_entry_fields(&mut self, central: bool, crc: u32, size: u32, name: &str) {
+        put_u16(&mut self.data, 10); // needed: version 1.0, stored
+        put_u16(&mut self.data, 1 << 11); // names are UTF-8
+        put_u16(&mut self.data, 0); // stored
+        put_u16(&mut self.data, DOS_TIME);
+        put_u16(&mut self.data, DOS_DATE);
+        put_u32(&mut self.data, crc);
+        put_u32(&mut self.data, size);
+        put_u32(&mut self.data, size);
+        put_u16(&mut self.data, name.len() as u16);
+        put_u16(&mut self.data, 0); // extra field length
+        if central {
+            put_u16(&mut self.data, 0); // comment length
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let directory_offset = self.data.len() as u32;
+        let directory_size = self.central_directory.len() as u32;
+        self.data.extend_from_slice(&self.central_directory);
+
+        put_u32(&mut self.data, 0x0605_4b50);
+        put_u16(&mut self.data, 0); // this disk
+        put_u16(&mut self.data, 0); // disk with the directory
+        put_u16(&mut self.data, self.entries);
+        put_u16(&mut self.data, self.entries);
+        put_u32(&mut self.data, directory_size);
+        put_u32(&mut self.data, directory_offset);
+        put_u16(&mut self.data, 0); // comment length
+        self.data
+    }
+}
+
+fn put_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+/// CRC-32 (IEEE) as ZIP expects it
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::test_article;
+    use chrono::{TimeZone, Utc};
+
+    fn article(title: &str, content: &str) -> Arc<Article> {
+        let mut article = test_article(title, title, "news");
+        article.link = "https://example.com/a?x=1&y=2".to_string();
+        article.content = Some(content.to_string());
+        article.author = Some("Ann".to_string());
+        article.published = Some(Utc.with_ymd_and_hms(2024, 3, 5, 9, 30, 0).unwrap());
+        Arc::new(article)
+    }
+
+    /// Names and contents of a stored-only ZIP archive, read via its
+    /// central directory
+    fn unzip(data: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let u16_at = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+        let u32_at = |at: usize| u32::from_le_bytes(data[at..at + 4].try_into().unwrap()) as usize;
+
+        let end = data.len() - 22;
+        assert_eq!(u32_at(end), 0x0605_4b50);
+        let mut record = u32_at(end + 16);
+        (0..u16_at(end + 10)).map(|_| {
+            assert_eq!(u32_at(record), 0x0201_4b50);
+            let (crc, size, name_len) = (u32_at(record + 16), u32_at(record + 24), u16_at(record + 28));
+            let name = String::from_utf8(data[record + 46..record + 46 + name_len].to_vec()).unwrap();
+            let local = u32_at(record + 42);
+            let start = local + 30 + u16_at(local + 26) + u16_at(local + 28);
+            let contents = data[start..start + size].to_vec();
+            assert_eq!(crc32(&contents) as usize, crc);
+            record += 46 + name_len;
+            (name, contents)
+        }).collect()
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_feed_epub() {
+        let extractor = ContentExtractor::new().unwrap();
+        let articles = vec![
+            article("First & best", "<p>Hello <b>world</b><br><img src=\"https://example.com/i.png\" alt=\"pic\"></p>"),
+            article("Second", "<div><p>Unclosed<p>tags</div>"),
+        ];
+
+        let book = feed_epub("news", "News <Daily>", &articles, &extractor);
+        assert_eq!(&book[30..38], b"mimetype");
+        assert_eq!(book, feed_epub("news", "News <Daily>", &articles, &extractor));
+
+        let entries = unzip(&book);
+        let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec![
+            "mimetype", "META-INF/container.xml", "OEBPS/content.opf", "OEBPS/toc.ncx",
+            "OEBPS/style.css", "OEBPS/chapter-0001.xhtml", "OEBPS/chapter-0002.xhtml",
+        ]);
+        assert_eq!(entries[0].1, b"application/epub+zip");
+
+        let text = |index: usize| String::from_utf8(entries[index].1.clone()).unwrap();
+        assert!(text(2).contains("<dc:title>News &lt;Daily&gt;</dc:title>"));
+        assert!(text(2).contains("<dc:date>2024-03-05</dc:date>"));
+        assert!(text(3).contains("<text>First &amp; best</text>"));
+        let chapter = text(5);
+        assert!(chapter.contains("<h1>First &amp; best</h1>"));
+        assert!(chapter.contains("href=\"https://example.com/a?x=1&amp;y=2\""));
+        assert!(chapter.contains("world"));
+        assert!(!chapter.contains("<img"));
+        assert!(!text(6).contains("<div>"));
+    }
+}
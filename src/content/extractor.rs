@@ -1,32 +1,84 @@
+use crate::content::{html, readability, text};
 use crate::error::{Error, Result};
 use crate::feed::{Article, ParsedArticle};
 use chrono::{DateTime, Utc};
 use html2md::parse_html;
 use regex::Regex;
+use html_escape::{encode_double_quoted_attribute, encode_text};
 use select::document::Document;
+use select::node::{Data, Node};
+use select::predicate::{Attr, Class, Name};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
 
 /// Content extractor for converting HTML articles to Markdown with YAML frontmatter
+#[derive(Debug, Clone)]
 pub struct ContentExtractor {
     selectors: ContentSelectors,
     regex_patterns: RegexPatterns,
+    local_images: bool,
 }
 
-#[derive(Debug, Clone)]
+/// Directory next to an article that holds its downloaded images
+pub const ASSETS_DIR: &str = "assets";
+
+#[derive(Debug, Clone, Hash)]
 pub struct ContentSelectors {
     pub article: Vec<String>,
     pub content: Vec<String>,
     pub remove: Vec<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct RegexPatterns {
     code_block: Regex,
     inline_code: Regex,
     whitespace: Regex,
     multiple_newlines: Regex,
+    img_tag: Regex,
+    tiny_dimension: Regex,
+    img_src: Regex,
+}
+
+/// Elements dropped together with everything inside them; anything else
+/// outside ammonia's allow-list loses its tag but keeps its text
+const DROPPED_ELEMENTS: &[&str] = &["script", "style", "iframe", "object", "embed", "svg", "math", "form"];
+
+/// Allow-list sanitizer for feed HTML: known-safe tags and attributes
+/// only, no event handlers, and links limited to safe URL schemes.
+/// `data:` URLs are kept for inline images and nowhere else.
+fn sanitizer() -> &'static ammonia::Builder<'static> {
+    static SANITIZER: OnceLock<ammonia::Builder<'static>> = OnceLock::new();
+    SANITIZER.get_or_init(|| {
+        let mut builder = ammonia::Builder::default();
+        builder
+            .clean_content_tags(DROPPED_ELEMENTS.iter().copied().collect())
+            .add_url_schemes(["data"])
+            .attribute_filter(|element, attribute, value| {
+                let is_data = value.trim_start().get(..5).is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"));
+                if is_data && (element, attribute) != ("img", "src") {
+                    None
+                } else {
+                    Some(value.into())
+                }
+            });
+        builder
+    })
 }
 
+/// Image sources that only exist to track readers
+const TRACKER_SOURCES: &[&str] = &[
+    "feeds.feedburner.com/~r/",
+    "feedsportal.com",
+    "doubleclick.net",
+    "google-analytics.com",
+    "pixel.wp.com",
+    "stats.wordpress.com",
+];
+
 /// YAML frontmatter structure for articles
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ArticleFrontmatter {
@@ -45,6 +97,30 @@ pub struct ArticleFrontmatter {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub guid: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub starred: bool,
+    /// Other feeds carrying the same story
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub also_in: Vec<String>,
+    /// Written by the configured summarizer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Keyword score from `[scoring]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<i64>,
+    /// ISO 639-1 code of the detected language
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// Frontmatter fields that come from the mount rather than the feed
+#[derive(Debug, Clone, Default, Hash)]
+pub struct Annotations {
+    /// Other feeds carrying the same story
+    pub also_in: Vec<String>,
+    pub summary: Option<String>,
+    pub score: Option<i64>,
+    pub language: Option<String>,
 }
 
 impl Default for ContentSelectors {
@@ -91,6 +167,11 @@ impl RegexPatterns {
             inline_code: Regex::new(r"`[^`]+`").map_err(|e| Error::ContentExtraction(e.to_string()))?,
             whitespace: Regex::new(r"[ \t]+").map_err(|e| Error::ContentExtraction(e.to_string()))?,
             multiple_newlines: Regex::new(r"\n{3,}").map_err(|e| Error::ContentExtraction(e.to_string()))?,
+            img_tag: Regex::new(r"(?is)<img\b[^>]*>").map_err(|e| Error::ContentExtraction(e.to_string()))?,
+            tiny_dimension: Regex::new(r#"(?i)\b(width|height)\s*=\s*["']?\s*[01](px)?\s*["'\s/>]"#)
+                .map_err(|e| Error::ContentExtraction(e.to_string()))?,
+            img_src: Regex::new(r#"(?is)(<img\b[^>]*?\ssrc\s*=\s*)("[^"]*"|'[^']*')"#)
+                .map_err(|e| Error::ContentExtraction(e.to_string()))?,
         })
     }
 }
@@ -100,6 +181,7 @@ impl ContentExtractor {
         Ok(Self {
             selectors: ContentSelectors::default(),
             regex_patterns: RegexPatterns::new()?,
+            local_images: false,
         })
     }
 
@@ -107,12 +189,34 @@ impl ContentExtractor {
         Ok(Self {
             selectors,
             regex_patterns: RegexPatterns::new()?,
+            local_images: false,
         })
     }
 
+    /// Point images at local copies under [`ASSETS_DIR`], named by
+    /// [`asset_file_name`], instead of their remote URLs
+    pub fn with_local_images(mut self, enabled: bool) -> Self {
+        self.local_images = enabled;
+        self
+    }
+
+    /// Hash of the settings that shape extracted content, telling apart
+    /// output made with different selectors
+    pub fn config_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.selectors.hash(&mut hasher);
+        self.local_images.hash(&mut hasher);
+        hasher.finish()
+    }
+
     /// Extract and convert article content to Markdown format with YAML frontmatter
     pub fn extract_article(&self, article: &Article, feed_name: &str) -> Result<String> {
-        let frontmatter = self.create_frontmatter(article, feed_name)?;
+        self.extract_article_annotated(article, feed_name, &Annotations::default())
+    }
+
+    /// `extract_article`, adding `annotations` to the frontmatter
+    pub fn extract_article_annotated(&self, article: &Article, feed_name: &str, annotations: &Annotations) -> Result<String> {
+        let frontmatter = self.create_frontmatter(article, feed_name, annotations)?;
         let content = self.extract_content(article)?;
         
         let yaml_frontmatter = serde_yaml::to_string(&frontmatter)
@@ -121,8 +225,31 @@ impl ContentExtractor {
         Ok(format!("---\n{}---\n\n{}", yaml_frontmatter, content))
     }
 
+    /// Render an article as a standalone HTML page: its metadata as a
+    /// header block above the sanitized content
+    pub fn extract_article_html(&self, article: &Article, feed_name: &str) -> Result<String> {
+        self.extract_article_html_annotated(article, feed_name, &Annotations::default())
+    }
+
+    /// `extract_article_html`, adding `annotations` to the header block
+    pub fn extract_article_html_annotated(&self, article: &Article, feed_name: &str, annotations: &Annotations) -> Result<String> {
+        let frontmatter = self.create_frontmatter(article, feed_name, annotations)?;
+        let body = match article.content.as_ref().or(article.description.as_ref()) {
+            Some(content) if text::looks_like_html(content) => {
+                let mut sanitized = self.sanitize_html(&self.clean_html(content));
+                if self.local_images {
+                    sanitized = self.localize_images(&sanitized, &article.link);
+                }
+                sanitized
+            }
+            Some(content) => html::text_to_paragraphs(content),
+            None => String::new(),
+        };
+        Ok(html::render_page(&frontmatter, &body))
+    }
+
     /// Create YAML frontmatter from article metadata
-    fn create_frontmatter(&self, article: &Article, feed_name: &str) -> Result<ArticleFrontmatter> {
+    fn create_frontmatter(&self, article: &Article, feed_name: &str, annotations: &Annotations) -> Result<ArticleFrontmatter> {
         Ok(ArticleFrontmatter {
             title: article.title.clone(),
             author: article.author.clone(),
@@ -133,9 +260,19 @@ impl ContentExtractor {
             categories: vec![], // Could be extracted from content or feed metadata
             description: article.description.clone(),
             guid: Some(article.id.clone()),
+            starred: article.starred,
+            also_in: annotations.also_in.clone(),
+            summary: annotations.summary.clone(),
+            score: annotations.score,
+            language: annotations.language.clone(),
         })
     }
 
+    /// An article's content as Markdown, without the frontmatter
+    pub fn markdown_body(&self, article: &Article) -> Result<String> {
+        self.extract_content(article)
+    }
+
     /// Extract and convert content to Markdown
     fn extract_content(&self, article: &Article) -> Result<String> {
         let html_content = article.content
@@ -143,8 +280,12 @@ impl ContentExtractor {
             .or(article.description.as_ref())
             .ok_or_else(|| Error::ContentExtraction("No content available".to_string()))?;
 
-        // Clean HTML first
-        let cleaned_html = self.clean_html(html_content)?;
+        // Clean HTML first, while the classes and ids the remove selectors
+        // match are still there
+        let mut cleaned_html = self.sanitize_html(&self.clean_html(html_content));
+        if self.local_images {
+            cleaned_html = self.localize_images(&cleaned_html, &article.link);
+        }
         
         // Convert to Markdown
         let markdown = self.html_to_markdown(&cleaned_html)?;
@@ -155,31 +296,74 @@ impl ContentExtractor {
         Ok(processed_markdown)
     }
 
-    /// Clean HTML content by removing unwanted elements
-    fn clean_html(&self, html: &str) -> Result<String> {
+    /// Clean HTML content by removing the elements the `remove` selectors
+    /// match, together with their content
+    fn clean_html(&self, html: &str) -> String {
         let document = Document::from(html);
-        let mut cleaned_html = html.to_string();
-
-        // Remove unwanted elements
-        for selector in &self.selectors.remove {
-            // This is a simplified approach - in a real implementation, 
-            // we'd need more sophisticated HTML manipulation
-            if selector.starts_with('.') {
-                let class_name = &selector[1..];
-                cleaned_html = cleaned_html.replace(&format!("<div class=\"{}\">", class_name), "");
-                cleaned_html = cleaned_html.replace(&format!("<span class=\"{}\">", class_name), "");
-            } else if selector.starts_with('#') {
-                let id_name = &selector[1..];
-                cleaned_html = cleaned_html.replace(&format!("<div id=\"{}\">", id_name), "");
+        let removed: HashSet<usize> = self.selectors.remove.iter()
+            .flat_map(|selector| find_selector(&document, selector))
+            .map(|node| node.index())
+            .collect();
+        if removed.is_empty() {
+            return html.to_string();
+        }
+
+        let mut cleaned_html = String::with_capacity(html.len());
+        for node in document.find(Name("body")).flat_map(|body| body.children()) {
+            write_node(&mut cleaned_html, node, &removed);
+        }
+        cleaned_html
+    }
+
+    /// Strip tracking pixels, then reduce what is left to the sanitizer's
+    /// allow-list: scripts, frames, embedded objects, forms, event handler
+    /// attributes and `javascript:` URLs all go
+    pub fn sanitize_html(&self, html: &str) -> String {
+        let without_pixels = self.regex_patterns.img_tag.replace_all(html, |caps: &regex::Captures| {
+            if self.is_tracking_pixel(&caps[0]) {
+                String::new()
             } else {
-                // Remove by tag name
-                let tag_regex = Regex::new(&format!(r"<{}[^>]*>.*?</{}>", selector, selector))
-                    .map_err(|e| Error::ContentExtraction(e.to_string()))?;
-                cleaned_html = tag_regex.replace_all(&cleaned_html, "").to_string();
+                caps[0].to_string()
             }
+        });
+        sanitizer().clean(&without_pixels).to_string()
+    }
+
+    /// An `<img>` tag sized 1x1 (or smaller), or loaded from a known tracker
+    fn is_tracking_pixel(&self, tag: &str) -> bool {
+        let tiny: Vec<String> = self.regex_patterns.tiny_dimension.captures_iter(tag)
+            .map(|caps| caps[1].to_lowercase())
+            .collect();
+        if tiny.iter().any(|d| d == "width") && tiny.iter().any(|d| d == "height") {
+            return true;
         }
+        let tag = tag.to_lowercase();
+        TRACKER_SOURCES.iter().any(|source| tag.contains(source))
+    }
 
-        Ok(cleaned_html)
+    /// Remote images an article's content embeds, resolved against its
+    /// link, without tracking pixels
+    pub fn image_urls(&self, article: &Article) -> Vec<String> {
+        let Some(html) = article.content.as_ref().or(article.description.as_ref()) else {
+            return Vec::new();
+        };
+        let sanitized = self.sanitize_html(html);
+        let mut urls: Vec<String> = self.regex_patterns.img_src.captures_iter(&sanitized)
+            .filter_map(|caps| resolve_image_url(unquote(&caps[2]), &article.link))
+            .collect();
+        urls.sort();
+        urls.dedup();
+        urls
+    }
+
+    /// Rewrite `<img src>` to the local copy in [`ASSETS_DIR`]
+    fn localize_images(&self, html: &str, base: &str) -> String {
+        self.regex_patterns.img_src.replace_all(html, |caps: &regex::Captures| {
+            match resolve_image_url(unquote(&caps[2]), base) {
+                Some(url) => format!("{}\"{}/{}\"", &caps[1], ASSETS_DIR, asset_file_name(&url)),
+                None => caps[0].to_string(),
+            }
+        }).into_owned()
     }
 
     /// Convert HTML to Markdown using html2md
@@ -234,12 +418,46 @@ impl ContentExtractor {
             updated: None,
             tags: parsed.categories.clone(),
             read: false,
+            starred: false,
+            folder: None,
             cached_at: Some(Utc::now()),
+            guid: parsed.guid.clone(),
+            enclosures: parsed.enclosures.clone(),
+            content_ref: None,
+            description_ref: None,
         };
 
         self.extract_article(&temp_article, feed_name)
     }
 
+    /// Pick the main body out of a full web page using the article
+    /// selectors, then the block that scores best as readable prose, then
+    /// `<body>`. Returns the body's inner HTML.
+    pub fn extract_main_content(&self, page_html: &str) -> Option<String> {
+        let document = Document::from(page_html);
+
+        let main = self.selectors.article.iter()
+            .find_map(|selector| find_selector(&document, selector).next());
+
+        main.or_else(|| readability::main_block(&document))
+            .or_else(|| document.find(Name("body")).next())
+            .map(|node| node.inner_html())
+            .filter(|html| !html.trim().is_empty())
+    }
+
+    /// Copy of `article` whose content is the main body of its fetched web
+    /// page, ready to be rendered like any feed-provided content
+    pub fn with_page_content(&self, article: &Article, page_html: &str) -> Result<Article> {
+        let content = self.extract_main_content(page_html)
+            .ok_or_else(|| Error::ContentExtraction(format!("No main content found at {}", article.link)))?;
+
+        Ok(Article {
+            content: Some(content),
+            content_ref: None,
+            ..article.clone()
+        })
+    }
+
     /// Extract categories from content or metadata
     pub fn extract_categories(&self, article: &Article) -> Vec<String> {
         let mut categories = Vec::new();
@@ -283,6 +501,81 @@ impl ContentExtractor {
     }
 }
 
+/// File name of an image's local copy: a hash of its URL, keeping the
+/// extension so viewers recognise it
+/// Nodes matching a `.class`, `#id` or tag name selector
+fn find_selector<'a>(document: &'a Document, selector: &'a str) -> Box<dyn Iterator<Item = Node<'a>> + 'a> {
+    if let Some(class_name) = selector.strip_prefix('.') {
+        Box::new(document.find(Class(class_name)))
+    } else if let Some(id_name) = selector.strip_prefix('#') {
+        Box::new(document.find(Attr("id", id_name)))
+    } else {
+        Box::new(document.find(Name(selector)))
+    }
+}
+
+/// Elements that never have content or a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+/// Append `node` to `out` as HTML, leaving out the `removed` nodes and
+/// everything inside them
+fn write_node(out: &mut String, node: Node, removed: &HashSet<usize>) {
+    if removed.contains(&node.index()) {
+        return;
+    }
+    match node.data() {
+        Data::Text(text) => out.push_str(&encode_text(&text[..])),
+        Data::Comment(_) => {}
+        Data::Element(..) => {
+            let name = node.name().unwrap_or_default();
+            out.push('<');
+            out.push_str(name);
+            for (attr, value) in node.attrs() {
+                out.push_str(&format!(" {}=\"{}\"", attr, encode_double_quoted_attribute(value)));
+            }
+            out.push('>');
+            if VOID_ELEMENTS.contains(&name) {
+                return;
+            }
+            for child in node.children() {
+                write_node(out, child, removed);
+            }
+            out.push_str(&format!("</{}>", name));
+        }
+    }
+}
+
+pub fn asset_file_name(url: &str) -> String {
+    let ext = url::Url::parse(url).ok()
+        .and_then(|url| {
+            let name = url.path_segments()?.next_back()?.to_string();
+            let (_, ext) = name.rsplit_once('.')?;
+            let valid = !ext.is_empty() && ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric());
+            valid.then(|| ext.to_ascii_lowercase())
+        })
+        .unwrap_or_else(|| "img".to_string());
+    let hash = blake3::hash(url.as_bytes()).to_hex();
+    format!("{}.{}", &hash[..16], ext)
+}
+
+/// Absolute http(s) URL of an image source; `data:` and other schemes are
+/// left alone
+fn resolve_image_url(src: &str, base: &str) -> Option<String> {
+    let src = html_escape::decode_html_entities(src.trim());
+    let url = match url::Url::parse(&src) {
+        Ok(url) => url,
+        Err(url::ParseError::RelativeUrlWithoutBase) => url::Url::parse(base).ok()?.join(&src).ok()?,
+        Err(_) => return None,
+    };
+    matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches(|c| c == '"' || c == '\'')
+}
+
 impl Default for ContentExtractor {
     fn default() -> Self {
         Self::new().expect("Failed to create default ContentExtractor")
@@ -306,7 +599,13 @@ mod tests {
             updated: None,
             tags: vec!["rust".to_string(), "programming".to_string()],
             read: false,
+            starred: false,
+            folder: None,
             cached_at: Some(Utc::now()),
+            guid: None,
+            enclosures: Vec::new(),
+            content_ref: None,
+            description_ref: None,
         }
     }
 
@@ -321,7 +620,7 @@ mod tests {
         let extractor = ContentExtractor::new().unwrap();
         let article = create_test_article();
         
-        let frontmatter = extractor.create_frontmatter(&article, "test-feed").unwrap();
+        let frontmatter = extractor.create_frontmatter(&article, "test-feed", &Annotations::default()).unwrap();
         
         assert_eq!(frontmatter.title, "Test Article");
         assert_eq!(frontmatter.feed, "test-feed");
@@ -372,9 +671,152 @@ mod tests {
         let extractor = ContentExtractor::new().unwrap();
         let html = "<p>Content</p><script>alert('test');</script><div class=\"ads\">Ad content</div>";
         
-        let cleaned = extractor.clean_html(html).unwrap();
+        let cleaned = extractor.clean_html(html);
         
         assert!(!cleaned.contains("script"));
         assert!(cleaned.contains("Content"));
+        assert!(!cleaned.contains("Ad content"));
+
+        let cleaned = extractor.clean_html("<p>Content</p><nav class=\"menu\">\n<a href=\"/\">Home</a>\n</NAV>");
+        assert_eq!(cleaned, "<p>Content</p>");
+
+        // Nothing to remove leaves the HTML as it was
+        let html = "<p class='lead'>Content &amp; more</p>";
+        assert_eq!(extractor.clean_html(html), html);
+    }
+
+    #[test]
+    fn test_remove_selectors() {
+        let selectors = ContentSelectors {
+            remove: vec![".ad".to_string(), "#promo".to_string()],
+            ..ContentSelectors::default()
+        };
+        let extractor = ContentExtractor::with_selectors(selectors).unwrap();
+        let mut article = create_test_article();
+        article.content = Some(concat!(
+            "<p>Story &amp; more</p>",
+            "<div class=\"sponsored ad\"><p>Buy <em>now</em></p></div>",
+            "<section><aside id=\"promo\"><h2>Subscribe today</h2></aside><p>Rest of the story<br>ends</p></section>",
+        ).to_string());
+
+        let markdown = extractor.extract_article(&article, "test-feed").unwrap();
+        let page = extractor.extract_article_html(&article, "test-feed").unwrap();
+        for rendered in [markdown, page] {
+            assert!(rendered.contains("Story &amp; more") || rendered.contains("Story & more"), "{}", rendered);
+            assert!(rendered.contains("Rest of the story"));
+            assert!(!rendered.contains("Buy"), "{}", rendered);
+            assert!(!rendered.contains("Subscribe"), "{}", rendered);
+        }
+    }
+
+    #[test]
+    fn test_extract_main_content() {
+        let extractor = ContentExtractor::new().unwrap();
+        let page = "<html><body><nav>Menu</nav><article><h1>Full story</h1><p>Body text</p></article><footer>Bye</footer></body></html>";
+
+        let main = extractor.extract_main_content(page).unwrap();
+        assert!(main.contains("Body text"));
+        assert!(!main.contains("Menu"));
+
+        let article = extractor.with_page_content(&create_test_article(), page).unwrap();
+        assert!(article.to_markdown("test-feed").unwrap().contains("Body text"));
+
+        let bare = extractor.extract_main_content("<html><body><p>Just a page</p></body></html>").unwrap();
+        assert!(bare.contains("Just a page"));
+
+        // No selector matches, so the block reading most like prose wins
+        let prose = "<p>Researchers said on Monday that the results, while early, point to a cheaper way to store energy.</p>";
+        let page = format!(
+            "<html><body><div class=\"links\"><a href=\"/\">Home</a> <a href=\"/about\">About us</a></div><div class=\"story\">{}</div></body></html>",
+            prose.repeat(3)
+        );
+        let main = extractor.extract_main_content(&page).unwrap();
+        assert!(main.contains("Researchers"));
+        assert!(!main.contains("About us"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_local_images() {
+        let mut article = create_test_article();
+        article.link = "https://example.com/posts/1".to_string();
+        article.content = Some(concat!(
+            "<p>Chart:</p><img alt=\"chart\" src=\"/img/chart.png?w=600\">",
+            "<img src='https://cdn.example.com/photo.JPG'>",
+            "<img src=\"https://example.com/p.gif\" width=\"1\" height=\"1\">",
+            "<img src=\"data:image/png;base64,AAAA\">",
+        ).to_string());
+
+        let extractor = ContentExtractor::new().unwrap();
+        let urls = extractor.image_urls(&article);
+        assert_eq!(urls, vec![
+            "https://cdn.example.com/photo.JPG".to_string(),
+            "https://example.com/img/chart.png?w=600".to_string(),
+        ]);
+        assert!(asset_file_name(&urls[0]).ends_with(".jpg"));
+        assert!(asset_file_name(&urls[1]).ends_with(".png"));
+
+        // Remote URLs stay unless local images are enabled
+        let remote = extractor.extract_article(&article, "test-feed").unwrap();
+        assert!(remote.contains("https://cdn.example.com/photo.JPG"));
+
+        let local = extractor.with_local_images(true).extract_article(&article, "test-feed").unwrap();
+        assert!(local.contains(&format!("assets/{}", asset_file_name(&urls[1]))));
+        assert!(!local.contains("https://cdn.example.com/photo.JPG"));
+        assert!(local.contains("data:image/png"));
+    }
+
+    #[test]
+    fn test_sanitize_html() {
+        let extractor = ContentExtractor::new().unwrap();
+        let html = concat!(
+            "<p>Story <a href=\"javascript:alert(1)\">click</a> <a href='https://example.com'>ok</a></p>",
+            "<img src=\"https://example.com/photo.jpg\" width=\"640\" height=\"1\">",
+            "<img src=\"https://example.com/p.gif\" width=\"1\" height=\"1\" />",
+            "<img src=\"http://feeds.feedburner.com/~r/example/~4/abc\">",
+        );
+
+        let sanitized = extractor.sanitize_html(html);
+        assert!(!sanitized.contains("javascript:"));
+        assert!(sanitized.contains(">click</a>"));
+        assert!(sanitized.contains("href=\"https://example.com\""));
+        assert!(sanitized.contains("photo.jpg"));
+        assert!(!sanitized.contains("p.gif"));
+        assert!(!sanitized.contains("feedburner"));
+
+        // Scripts spanning lines, event handlers and active content all go
+        let html = concat!(
+            "<p>Before</p><script type=\"text/javascript\">\n",
+            "  document.cookie;\n",
+            "</script>",
+            "<img src=\"https://example.com/a.png\" onerror=\"alert(1)\">",
+            "<p onclick='steal()'>Text</p>",
+            "<a href=\" JaVaScript:alert(1)\">js</a>",
+            "<a href=\"data:text/html,<script>alert(1)</script>\">data</a>",
+            "<iframe src=\"https://evil.example\">frame</iframe>",
+            "<svg onload=\"alert(1)\"><text>vector</text></svg>",
+            "<object data=\"x.swf\">obj</object>",
+            "<form action=\"https://evil.example\"><input name=\"pw\">login</form>",
+            "<img src=\"data:image/png;base64,AAAA\">",
+        );
+        let sanitized = extractor.sanitize_html(html);
+        for gone in ["script", "document.cookie", "onerror", "onclick", "alert", "javascript",
+                     "data:text", "iframe", "frame<", "svg", "vector", "object", "form", "login"] {
+            assert!(!sanitized.to_lowercase().contains(gone), "{} survived in {}", gone, sanitized);
+        }
+        assert!(sanitized.contains("Before"));
+        assert!(sanitized.contains("Text"));
+        assert!(sanitized.contains("https://example.com/a.png"));
+        assert!(sanitized.contains("data:image/png"));
+
+        // The same holds for the rendered page and the Markdown
+        let mut article = create_test_article();
+        article.content = Some(html.to_string());
+        let page = extractor.extract_article_html(&article, "test-feed").unwrap();
+        let markdown = extractor.extract_article(&article, "test-feed").unwrap();
+        for rendered in [page, markdown] {
+            assert!(!rendered.contains("document.cookie"));
+            assert!(!rendered.contains("onerror"));
+            assert!(!rendered.to_lowercase().contains("javascript:"));
+        }
+    }
+}
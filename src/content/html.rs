@@ -0,0 +1,132 @@
+//! Standalone HTML pages for articles, readable in a browser without network
+//! access: the frontmatter becomes a header block and the styling is inline
+
+use html_escape::{encode_double_quoted_attribute, encode_text};
+
+use crate::content::extractor::ArticleFrontmatter;
+
+/// Minimal reading style, embedded so the page needs no other files
+const ARTICLE_CSS: &str = "\
+body { max-width: 42em; margin: 2em auto; padding: 0 1em; font: 17px/1.6 Georgia, serif; color: #222; background: #fdfdfb; }
+header { border-bottom: 1px solid #ddd; margin-bottom: 1.5em; }
+header h1 { font-size: 1.8em; line-height: 1.25; margin: 0 0 .3em; }
+header dl { display: grid; grid-template-columns: max-content auto; gap: .1em 1em; font: 14px/1.5 sans-serif; color: #666; }
+header dt { font-weight: bold; }
+header dd { margin: 0; }
+img, video { max-width: 100%; height: auto; }
+pre { overflow-x: auto; padding: .8em; background: #f3f3f0; }
+code { font-size: .9em; }
+blockquote { margin-left: 0; padding-left: 1em; border-left: 3px solid #ccc; color: #555; }
+a { color: #1a5fb4; }
+";
+
+/// A complete HTML document for an article whose body is already sanitized
+pub fn render_page(frontmatter: &ArticleFrontmatter, body_html: &str) -> String {
+    let mut details = Vec::new();
+    if let Some(author) = &frontmatter.author {
+        details.push(("Author", encode_text(author).into_owned()));
+    }
+    if let Some(date) = &frontmatter.date {
+        details.push(("Published", format!(
+            "<time datetime=\"{}\">{}</time>",
+            date.to_rfc3339(),
+            date.format("%Y-%m-%d %H:%M UTC"),
+        )));
+    }
+    details.push(("Feed", encode_text(&frontmatter.feed).into_owned()));
+    if !frontmatter.url.is_empty() {
+        details.push(("Source", format!(
+            "<a href=\"{}\">{}</a>",
+            encode_double_quoted_attribute(&frontmatter.url),
+            encode_text(&frontmatter.url),
+        )));
+    }
+    if !frontmatter.tags.is_empty() {
+        details.push(("Tags", encode_text(&frontmatter.tags.join(", ")).into_owned()));
+    }
+    if !frontmatter.also_in.is_empty() {
+        details.push(("Also in", encode_text(&frontmatter.also_in.join(", ")).into_owned()));
+    }
+    if let Some(summary) = &frontmatter.summary {
+        details.push(("Summary", encode_text(summary).into_owned()));
+    }
+    if let Some(score) = frontmatter.score {
+        details.push(("Score", score.to_string()));
+    }
+    if frontmatter.starred {
+        details.push(("Starred", "★".to_string()));
+    }
+
+    let details: String = details.iter()
+        .map(|(label, value)| format!("<dt>{}</dt><dd>{}</dd>\n", label, value))
+        .collect();
+    let title = encode_text(&frontmatter.title);
+    let lang = frontmatter.language.as_deref()
+        .map(|language| format!(" lang=\"{}\"", encode_double_quoted_attribute(language)))
+        .unwrap_or_default();
+
+    format!(
+        "<!DOCTYPE html>\n<html{lang}>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>\n{css}</style>\n</head>\n<body>\n\
+         <header>\n<h1>{title}</h1>\n<dl>\n{details}</dl>\n</header>\n\
+         <article>\n{body}\n</article>\n</body>\n</html>\n",
+        lang = lang,
+        title = title,
+        css = ARTICLE_CSS,
+        details = details,
+        body = body_html.trim(),
+    )
+}
+
+/// Plain-text content as HTML paragraphs, split on blank lines
+pub fn text_to_paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| format!("<p>{}</p>", encode_text(paragraph).replace('\n', "<br>\n")))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_render_page() {
+        let frontmatter = ArticleFrontmatter {
+            title: "Fish & <Chips>".to_string(),
+            author: Some("Ann".to_string()),
+            date: Some(Utc.with_ymd_and_hms(2024, 3, 5, 9, 30, 0).unwrap()),
+            url: "https://example.com/a?x=1&y=2".to_string(),
+            feed: "news".to_string(),
+            tags: vec!["food".to_string()],
+            categories: vec![],
+            description: None,
+            guid: None,
+            starred: false,
+            also_in: vec!["lobsters".to_string()],
+            summary: Some("Fried food.".to_string()),
+            score: None,
+            language: Some("en".to_string()),
+        };
+
+        let page = render_page(&frontmatter, "<p>Body</p>");
+        assert!(page.starts_with("<!DOCTYPE html>\n<html lang=\"en\">"));
+        assert!(page.contains("<title>Fish &amp; &lt;Chips&gt;</title>"));
+        assert!(page.contains("<dt>Author</dt><dd>Ann</dd>"));
+        assert!(page.contains("<dt>Also in</dt><dd>lobsters</dd>"));
+        assert!(page.contains("<dt>Summary</dt><dd>Fried food.</dd>"));
+        assert!(page.contains("2024-03-05 09:30 UTC"));
+        assert!(page.contains("href=\"https://example.com/a?x=1&amp;y=2\""));
+        assert!(page.contains("<article>\n<p>Body</p>\n</article>"));
+        assert!(page.contains("<style>"));
+    }
+
+    #[test]
+    fn test_text_to_paragraphs() {
+        assert_eq!(text_to_paragraphs("One\nline\n\n\nTwo & more"), "<p>One<br>\nline</p>\n<p>Two &amp; more</p>");
+    }
+}
@@ -0,0 +1,81 @@
+//! Article language detection with `whatlang`, reported as two-letter ISO
+//! 639-1 codes (`en`, `de`) so they match what people write in configs.
+
+use crate::config::TextHtmlPolicy;
+use crate::content::render_html_as_text;
+use crate::feed::Article;
+
+/// Characters of an article looked at; more rarely changes the answer
+const SAMPLE_CHARS: usize = 2000;
+
+/// ISO 639-3 codes as `whatlang` reports them, with their ISO 639-1 code
+const CODES: &[(&str, &str)] = &[
+    ("afr", "af"), ("aka", "ak"), ("amh", "am"), ("ara", "ar"), ("aze", "az"),
+    ("bel", "be"), ("ben", "bn"), ("bul", "bg"), ("cat", "ca"), ("ces", "cs"),
+    ("cmn", "zh"), ("dan", "da"), ("deu", "de"), ("ell", "el"), ("eng", "en"),
+    ("epo", "eo"), ("est", "et"), ("fin", "fi"), ("fra", "fr"), ("guj", "gu"),
+    ("heb", "he"), ("hin", "hi"), ("hrv", "hr"), ("hun", "hu"), ("hye", "hy"),
+    ("ind", "id"), ("ita", "it"), ("jav", "jv"), ("jpn", "ja"), ("kan", "kn"),
+    ("kat", "ka"), ("khm", "km"), ("kor", "ko"), ("lat", "la"), ("lav", "lv"),
+    ("lit", "lt"), ("mal", "ml"), ("mar", "mr"), ("mkd", "mk"), ("mya", "my"),
+    ("nep", "ne"), ("nld", "nl"), ("nob", "nb"), ("ori", "or"), ("pan", "pa"),
+    ("pes", "fa"), ("pol", "pl"), ("por", "pt"), ("ron", "ro"), ("rus", "ru"),
+    ("sin", "si"), ("slk", "sk"), ("slv", "sl"), ("sna", "sn"), ("spa", "es"),
+    ("srp", "sr"), ("swe", "sv"), ("tam", "ta"), ("tel", "te"), ("tgl", "tl"),
+    ("tha", "th"), ("tuk", "tk"), ("tur", "tr"), ("ukr", "uk"), ("urd", "ur"),
+    ("uzb", "uz"), ("vie", "vi"), ("yid", "yi"), ("zul", "zu"),
+];
+
+/// The language an article is written in, from its title and text. `None`
+/// when the text is too short or mixed to tell reliably.
+pub fn detect_language(article: &Article) -> Option<&'static str> {
+    let body = article.content.as_deref().or(article.description.as_deref()).unwrap_or_default();
+    let text = format!("{}\n{}", article.title, render_html_as_text(body, TextHtmlPolicy::Strip));
+    let sample: String = text.chars().take(SAMPLE_CHARS).collect();
+    let info = whatlang::detect(&sample).filter(|info| info.is_reliable())?;
+    let code = info.lang().code();
+    CODES.iter().find(|(long, _)| *long == code).map(|(_, short)| *short)
+}
+
+/// The two-letter code for a language given as a two- or three-letter code,
+/// in any case; `None` for languages that cannot be detected
+pub fn normalize_code(code: &str) -> Option<&'static str> {
+    let code = code.trim().to_ascii_lowercase();
+    CODES.iter()
+        .find(|(long, short)| *long == code || *short == code)
+        .map(|(_, short)| *short)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::test_article;
+
+    fn article(title: &str, description: &str) -> Article {
+        let mut article = test_article("a", title, "test");
+        article.description = Some(description.to_string());
+        article
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let english = article(
+            "The new release is out",
+            "<p>This version brings faster builds, better error messages and a long list of fixes that many people asked for.</p>",
+        );
+        assert_eq!(detect_language(&english), Some("en"));
+        let german = article(
+            "Die neue Version ist da",
+            "<p>Diese Version bringt schnellere Builds, bessere Fehlermeldungen und eine lange Liste von Korrekturen, die sich viele gewünscht haben.</p>",
+        );
+        assert_eq!(detect_language(&german), Some("de"));
+        assert_eq!(detect_language(&article("OK", "")), None);
+    }
+
+    #[test]
+    fn test_normalize_code() {
+        assert_eq!(normalize_code("EN"), Some("en"));
+        assert_eq!(normalize_code("deu"), Some("de"));
+        assert_eq!(normalize_code("xx"), None);
+    }
+}
@@ -1,6 +1,13 @@
+pub mod epub;
 pub mod extractor;
+pub mod html;
+pub mod language;
+pub mod readability;
+pub mod summarizer;
+pub mod text;
 
-pub use extractor::{ContentExtractor, ArticleFrontmatter};
+pub use extractor::{asset_file_name, ContentExtractor, ArticleFrontmatter, ASSETS_DIR};
+pub use text::render_html_as_text;
 
 pub struct ContentSelectors {
     pub article: Vec<String>,
@@ -0,0 +1,107 @@
+//! Readability-style scoring to find the main content of a web page when
+//! none of the article selectors match
+
+use std::collections::HashMap;
+
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Name, Predicate};
+
+/// Paragraphs shorter than this are captions or navigation, not prose
+const MIN_PARAGRAPH_LEN: usize = 25;
+
+/// A block must score at least this to be preferred over the whole `<body>`
+const MIN_SCORE: f64 = 5.0;
+
+/// The block holding most of the page's prose, if any reads like an article.
+/// Each paragraph credits its parent fully and its grandparent by half, so
+/// the container a story's paragraphs share outscores the page wrapper.
+pub fn main_block(document: &Document) -> Option<Node<'_>> {
+    let mut scores: HashMap<usize, f64> = HashMap::new();
+    for paragraph in document.find(Name("p").or(Name("pre"))) {
+        let score = paragraph_score(&paragraph.text());
+        if score == 0.0 {
+            continue;
+        }
+        if let Some(parent) = paragraph.parent() {
+            *scores.entry(parent.index()).or_default() += score;
+            if let Some(grandparent) = parent.parent() {
+                *scores.entry(grandparent.index()).or_default() += score / 2.0;
+            }
+        }
+    }
+
+    scores.into_iter()
+        .filter_map(|(index, score)| {
+            let node = document.nth(index)?;
+            Some((node, score * (1.0 - link_density(&node)) * (0.5 + text_density(&node) / 2.0)))
+        })
+        .filter(|(_, score)| *score >= MIN_SCORE)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(node, _)| node)
+}
+
+/// Longer, comma-rich paragraphs read like prose; short ones score nothing
+fn paragraph_score(text: &str) -> f64 {
+    let len = text.trim().chars().count();
+    if len < MIN_PARAGRAPH_LEN {
+        return 0.0;
+    }
+    1.0 + text.matches(',').count() as f64 + (len as f64 / 100.0).min(3.0)
+}
+
+/// Share of a block's text inside links: high for menus and link lists
+fn link_density(node: &Node) -> f64 {
+    let total = text_len(node);
+    if total == 0 {
+        return 1.0;
+    }
+    let in_links: usize = node.find(Name("a")).map(|link| text_len(&link)).sum();
+    (in_links as f64 / total as f64).min(1.0)
+}
+
+/// Share of a block's markup that is text: low for widget soup
+fn text_density(node: &Node) -> f64 {
+    let html_len = node.inner_html().len();
+    if html_len == 0 {
+        return 0.0;
+    }
+    (node.text().len() as f64 / html_len as f64).min(1.0)
+}
+
+fn text_len(node: &Node) -> usize {
+    node.text().trim().chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROSE: &str = "The committee met on Tuesday, and after a long debate, it agreed to fund the project for another year.";
+
+    #[test]
+    fn test_picks_block_with_most_prose() {
+        let page = format!(
+            "<html><body>\
+             <div id=\"menu\"><p><a href=\"/a\">A rather long navigation link to somewhere</a></p></div>\
+             <div id=\"story\"><p>{0}</p><p>{0}</p><p>{0}</p></div>\
+             <div id=\"aside\"><p>Short note.</p></div>\
+             </body></html>",
+            PROSE
+        );
+        let document = Document::from(page.as_str());
+
+        let block = main_block(&document).unwrap();
+        assert_eq!(block.attr("id"), Some("story"));
+    }
+
+    #[test]
+    fn test_link_lists_do_not_qualify() {
+        let link = "<p><a href=\"/x\">Another headline that links elsewhere, with commas, and more</a></p>";
+        let page = format!("<html><body><div>{}</div></body></html>", link.repeat(5));
+        let document = Document::from(page.as_str());
+
+        assert!(main_block(&document).is_none());
+        assert_eq!(paragraph_score("Too short."), 0.0);
+    }
+}
@@ -0,0 +1,299 @@
+//! Article summaries from a pluggable summarizer, normally a user-configured
+//! command (such as a script around a local language model) that reads the
+//! article's text on stdin and prints the summary. Summaries are kept in the
+//! cache directory, so each article is summarized once; an article that
+//! failed is retried only after a growing pause.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use tokio::io::AsyncWriteExt;
+
+use crate::clock::{system_clock, SharedClock};
+use crate::config::TextHtmlPolicy;
+use crate::content::render_html_as_text;
+use crate::error::{Error, Result};
+use crate::feed::Article;
+
+/// Pause before retrying an article whose summary failed, doubled with each
+/// further failure
+const FAILURE_BACKOFF: Duration = Duration::from_secs(10 * 60);
+
+/// Longest pause between retries of a failing article
+const MAX_FAILURE_BACKOFF: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Produces a summary of an article
+#[async_trait]
+pub trait Summarizer: Send + Sync {
+    /// A summary of `article`, whose body as plain text is `text`
+    async fn summarize(&self, article: &Article, text: &str) -> Result<String>;
+}
+
+/// Runs a shell command per article: the text goes to its stdin, the title
+/// and link to `RSS_FUSE_TITLE` and `RSS_FUSE_LINK`, and what it prints is
+/// the summary. One command runs at a time.
+pub struct CommandSummarizer {
+    command: String,
+    timeout: Duration,
+    running: tokio::sync::Mutex<()>,
+}
+
+impl CommandSummarizer {
+    pub fn new(command: impl Into<String>, timeout: Duration) -> Self {
+        Self { command: command.into(), timeout, running: tokio::sync::Mutex::new(()) }
+    }
+}
+
+#[async_trait]
+impl Summarizer for CommandSummarizer {
+    async fn summarize(&self, article: &Article, text: &str) -> Result<String> {
+        let _running = self.running.lock().await;
+        let mut child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .env("RSS_FUSE_TITLE", &article.title)
+            .env("RSS_FUSE_LINK", &article.link)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        // Written alongside reading the output, so a command that prints
+        // before consuming all of its input cannot block on a full pipe
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let input = text.to_string();
+        let writer = tokio::spawn(async move {
+            // A command may well stop reading early
+            let _ = stdin.write_all(input.as_bytes()).await;
+        });
+        let output = tokio::time::timeout(self.timeout, child.wait_with_output()).await
+            .map_err(|_| Error::Timeout(format!("Summarizer took longer than {}s", self.timeout.as_secs())))??;
+        writer.abort();
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::ContentExtraction(format!("Summarizer exited with {}: {}", output.status, stderr.trim())));
+        }
+        let summary = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if summary.is_empty() {
+            return Err(Error::ContentExtraction("Summarizer printed nothing".to_string()));
+        }
+        Ok(summary)
+    }
+}
+
+/// A summarizer with its results kept on disk, one file per article
+pub struct SummaryCache {
+    summarizer: Arc<dyn Summarizer>,
+    dir: PathBuf,
+    pending: Mutex<HashSet<String>>,
+    failures: Mutex<HashMap<String, Failure>>, // article id -> when to try again
+    clock: SharedClock,
+}
+
+/// Failed summaries of one article
+#[derive(Debug, Clone, Copy)]
+struct Failure {
+    count: u32,
+    retry_at: SystemTime,
+}
+
+impl SummaryCache {
+    pub fn new(summarizer: Arc<dyn Summarizer>, dir: PathBuf) -> Self {
+        Self {
+            summarizer,
+            dir,
+            pending: Mutex::new(HashSet::new()),
+            failures: Mutex::new(HashMap::new()),
+            clock: system_clock(),
+        }
+    }
+
+    /// Time failure backoffs by `clock`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn path(&self, article_id: &str) -> PathBuf {
+        self.dir.join(summary_file_name(article_id))
+    }
+
+    /// The stored summary of an article, if it has been summarized
+    pub fn cached(&self, article_id: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(article_id)).ok()
+    }
+
+    /// Summarize `article` and store the result. `None` if it already has a
+    /// summary, is being summarized, failed too recently or has no text.
+    pub async fn summarize(&self, article: &Article) -> Result<Option<String>> {
+        let Some(body) = article.content.as_deref().or(article.description.as_deref()) else {
+            return Ok(None);
+        };
+        if self.is_backing_off(&article.id) {
+            return Ok(None);
+        }
+        if self.path(&article.id).exists() || !self.pending.lock().insert(article.id.clone()) {
+            return Ok(None);
+        }
+        let text = render_html_as_text(body, TextHtmlPolicy::Strip);
+        let result = self.summarizer.summarize(article, &text).await;
+        self.pending.lock().remove(&article.id);
+
+        let summary = match result {
+            Ok(summary) => summary,
+            Err(e) => {
+                self.record_failure(&article.id);
+                return Err(e);
+            }
+        };
+        self.failures.lock().remove(&article.id);
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.path(&article.id), &summary)?;
+        Ok(Some(summary))
+    }
+
+    fn is_backing_off(&self, article_id: &str) -> bool {
+        self.failures.lock().get(article_id).is_some_and(|failure| self.clock.now() < failure.retry_at)
+    }
+
+    fn record_failure(&self, article_id: &str) {
+        let mut failures = self.failures.lock();
+        let count = failures.get(article_id).map_or(0, |failure| failure.count) + 1;
+        let backoff = FAILURE_BACKOFF.saturating_mul(2u32.saturating_pow(count - 1)).min(MAX_FAILURE_BACKOFF);
+        failures.insert(article_id.to_string(), Failure { count, retry_at: self.clock.now() + backoff });
+    }
+
+    /// Delete the summaries of articles other than `article_ids` and forget
+    /// their failures. Returns how many summaries were deleted.
+    pub fn retain<'a>(&self, article_ids: impl IntoIterator<Item = &'a str>) -> Result<usize> {
+        let keep: HashSet<&str> = article_ids.into_iter().collect();
+        self.failures.lock().retain(|id, _| keep.contains(id.as_str()));
+        retain_summaries(&self.dir, keep)
+    }
+}
+
+/// Delete the summaries in `dir` of articles other than `article_ids`.
+/// Returns how many were deleted.
+pub fn retain_summaries<'a>(dir: &Path, article_ids: impl IntoIterator<Item = &'a str>) -> Result<usize> {
+    let keep: HashSet<String> = article_ids.into_iter().map(summary_file_name).collect();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut removed = 0;
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.ends_with(".txt") && !keep.contains(&name) {
+            std::fs::remove_file(entry.path())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+fn summary_file_name(article_id: &str) -> String {
+    let hash = blake3::hash(article_id.as_bytes()).to_hex();
+    format!("{}.txt", &hash[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::test_article;
+
+    fn article() -> Article {
+        let mut article = test_article("release-2", "Release notes", "news");
+        article.description = Some("<p>Version 2 is <b>out</b>.</p>".to_string());
+        article
+    }
+
+    #[tokio::test]
+    async fn test_command_summary_is_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let command = CommandSummarizer::new("printf '%s: ' \"$RSS_FUSE_TITLE\"; tr a-z A-Z", Duration::from_secs(5));
+        let cache = SummaryCache::new(Arc::new(command), dir.path().to_path_buf());
+        let article = article();
+
+        let summary = cache.summarize(&article).await.unwrap();
+        assert_eq!(summary.as_deref(), Some("Release notes: VERSION 2 IS OUT."));
+        assert_eq!(cache.cached(&article.id), summary);
+        assert_eq!(cache.summarize(&article).await.unwrap(), None);
+    }
+
+    /// Fails every time, counting the attempts
+    #[derive(Default)]
+    struct Failing(Mutex<usize>);
+
+    #[async_trait]
+    impl Summarizer for Failing {
+        async fn summarize(&self, _article: &Article, _text: &str) -> Result<String> {
+            *self.0.lock() += 1;
+            Err(Error::ContentExtraction("model unavailable".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failures_back_off() {
+        let dir = tempfile::tempdir().unwrap();
+        let clock = crate::clock::MockClock::default();
+        let failing = Arc::new(Failing::default());
+        let cache = SummaryCache::new(failing.clone(), dir.path().to_path_buf()).with_clock(clock.shared());
+        let article = article();
+
+        assert!(cache.summarize(&article).await.is_err());
+        assert_eq!(cache.summarize(&article).await.unwrap(), None);
+        assert_eq!(*failing.0.lock(), 1);
+
+        // Retried after the backoff, which then doubles
+        clock.advance(FAILURE_BACKOFF);
+        assert!(cache.summarize(&article).await.is_err());
+        clock.advance(FAILURE_BACKOFF);
+        assert_eq!(cache.summarize(&article).await.unwrap(), None);
+        assert_eq!(*failing.0.lock(), 2);
+        clock.advance(FAILURE_BACKOFF);
+        assert!(cache.summarize(&article).await.is_err());
+        assert_eq!(*failing.0.lock(), 3);
+
+        // Forgetting the article forgets its failures
+        cache.retain([]).unwrap();
+        assert!(cache.summarize(&article).await.is_err());
+        assert_eq!(*failing.0.lock(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_retain_deletes_other_summaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let command = CommandSummarizer::new("cat", Duration::from_secs(5));
+        let cache = SummaryCache::new(Arc::new(command), dir.path().to_path_buf());
+        let kept = article();
+        let mut dropped = article();
+        dropped.id = "dropped".to_string();
+        cache.summarize(&kept).await.unwrap();
+        cache.summarize(&dropped).await.unwrap();
+
+        assert_eq!(cache.retain([kept.id.as_str()]).unwrap(), 1);
+        assert!(cache.cached(&kept.id).is_some());
+        assert!(cache.cached(&dropped.id).is_none());
+        assert_eq!(retain_summaries(&dir.path().join("missing"), []).unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_command_failure() {
+        let failing = CommandSummarizer::new("echo 'no model' >&2; exit 3", Duration::from_secs(5));
+        let error = failing.summarize(&article(), "text").await.unwrap_err();
+        assert!(error.to_string().contains("no model"));
+
+        let slow = CommandSummarizer::new("sleep 5", Duration::from_millis(100));
+        assert!(matches!(slow.summarize(&article(), "text").await, Err(Error::Timeout(_))));
+    }
+}
@@ -0,0 +1,63 @@
+//! Plain-text rendering of HTML fragments from feed descriptions and content
+
+use crate::config::TextHtmlPolicy;
+
+/// Line width used when converting HTML to plain text
+const TEXT_WIDTH: usize = 80;
+
+/// Render an HTML fragment for a `.txt` file according to `policy`
+pub fn render_html_as_text(html: &str, policy: TextHtmlPolicy) -> String {
+    match policy {
+        TextHtmlPolicy::Strip => {
+            if !looks_like_html(html) {
+                return html_escape::decode_html_entities(html).into_owned();
+            }
+            html2text::from_read(html.as_bytes(), TEXT_WIDTH)
+                .trim_end()
+                .to_string()
+        }
+        TextHtmlPolicy::Entities => html_escape::decode_html_entities(html).into_owned(),
+        TextHtmlPolicy::Raw => html.to_string(),
+    }
+}
+
+/// Cheap check for markup so plain-text feeds are passed through unwrapped
+pub(crate) fn looks_like_html(text: &str) -> bool {
+    text.as_bytes()
+        .windows(2)
+        .any(|w| w[0] == b'<' && (w[1].is_ascii_alphabetic() || w[1] == b'/' || w[1] == b'!'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = "<p>Fish &amp; chips &mdash; <strong>fresh</strong></p><p>Second&nbsp;paragraph</p>";
+
+    #[test]
+    fn test_strip_policy() {
+        let text = render_html_as_text(HTML, TextHtmlPolicy::Strip);
+        assert!(!text.contains("<p>"));
+        assert!(!text.contains("&amp;"));
+        assert!(text.contains("Fish & chips — "));
+        assert!(text.contains("Second"));
+    }
+
+    #[test]
+    fn test_strip_policy_plain_text_passthrough() {
+        let text = render_html_as_text("Tom &amp; Jerry, 3 < 4", TextHtmlPolicy::Strip);
+        assert_eq!(text, "Tom & Jerry, 3 < 4");
+    }
+
+    #[test]
+    fn test_entities_policy() {
+        let text = render_html_as_text(HTML, TextHtmlPolicy::Entities);
+        assert!(text.contains("<strong>fresh</strong>"));
+        assert!(text.contains("Fish & chips —"));
+    }
+
+    #[test]
+    fn test_raw_policy() {
+        assert_eq!(render_html_as_text(HTML, TextHtmlPolicy::Raw), HTML);
+    }
+}
@@ -0,0 +1,61 @@
+//! Virtual feeds: one listing combining the articles of several configured
+//! feeds, newest first, with stories posted to more than one of them kept once.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::feed::Article;
+use crate::feed::urls::UrlNormalizer;
+
+/// The `(feed name, article)` entries of a virtual feed over `members`,
+/// given as `(feed name, articles)`, newest first (undated articles last).
+/// Articles sharing a link (see `UrlNormalizer::match_key`) are listed
+/// once, from the first member that carries them.
+pub fn aggregate<'a>(
+    members: impl IntoIterator<Item = (&'a str, &'a [Arc<Article>])>,
+    normalizer: &UrlNormalizer,
+) -> Vec<(String, Arc<Article>)> {
+    let mut seen = HashSet::new();
+    let mut entries: Vec<(String, Arc<Article>)> = members.into_iter()
+        .flat_map(|(name, articles)| articles.iter().map(move |article| (name, article)))
+        .filter(|(_, article)| normalizer.match_key(&article.link).map_or(true, |url| seen.insert(url)))
+        .map(|(name, article)| (name.to_string(), Arc::clone(article)))
+        .collect();
+    entries.sort_by_key(|(_, article)| std::cmp::Reverse(article.published));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::test_article;
+    use chrono::{TimeZone, Utc};
+
+    fn articles(feed_name: &str, articles: &[(&str, &str, u32)]) -> Vec<Arc<Article>> {
+        articles.iter()
+            .map(|(title, link, day)| {
+                let mut article = test_article(link, title, feed_name);
+                article.link = link.to_string();
+                article.published = Some(Utc.with_ymd_and_hms(2024, 3, *day, 0, 0, 0).unwrap());
+                Arc::new(article)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_aggregate_sorts_and_dedups() {
+        let blog = articles("rust-blog", &[("Rust 1.76", "https://blog.rust-lang.org/1.76/", 8), ("Rust 1.75", "https://blog.rust-lang.org/1.75", 1)]);
+        let twir = articles("twir", &[("TWIR 535", "https://this-week-in-rust.org/535", 6)]);
+        let reddit = articles("r-rust", &[("Rust 1.76 is out", "http://blog.rust-lang.org/1.76", 9)]);
+
+        let normalizer = UrlNormalizer::default();
+        let entries = aggregate([("rust-blog", &blog[..]), ("twir", &twir[..]), ("r-rust", &reddit[..])], &normalizer);
+        let titles: Vec<(&str, &str)> = entries.iter().map(|(feed, a)| (feed.as_str(), a.title.as_str())).collect();
+        assert_eq!(titles, [("rust-blog", "Rust 1.76"), ("twir", "TWIR 535"), ("rust-blog", "Rust 1.75")]);
+
+        // The first member listing a story wins
+        let entries = aggregate([("r-rust", &reddit[..]), ("rust-blog", &blog[..])], &normalizer);
+        assert_eq!(entries[0].1.title, "Rust 1.76 is out");
+        assert_eq!(entries.len(), 2);
+    }
+}
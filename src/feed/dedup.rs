@@ -0,0 +1,142 @@
+//! Recognizing one story carried by several feeds, as when an aggregator
+//! and a community site both link the same post: by its link once tracking
+//! parameters and cosmetic differences are removed, or by a near-identical
+//! title.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::feed::Article;
+use crate::feed::urls::UrlNormalizer;
+
+/// Titles sharing at least this fraction of their words are the same story
+const TITLE_SIMILARITY: f64 = 0.8;
+
+/// Titles with fewer words are only matched by link; short ones like
+/// "Weekly update" recur across unrelated stories
+const MIN_TITLE_WORDS: usize = 4;
+
+/// Lowercase words of a title, ignoring punctuation
+fn title_words(title: &str) -> HashSet<String> {
+    title.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether two titles' word sets overlap enough to name the same story
+fn similar_words(a: &HashSet<String>, b: &HashSet<String>) -> bool {
+    if a.len() < MIN_TITLE_WORDS || b.len() < MIN_TITLE_WORDS {
+        return false;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / a.union(b).count() as f64 >= TITLE_SIMILARITY
+}
+
+/// The words of a title under which it is indexed. Any two titles similar
+/// enough share at least one: each keeps all but the words it could lack
+/// and still match, taken in a fixed (alphabetical) order.
+fn indexed_words(words: &HashSet<String>) -> impl Iterator<Item = &String> {
+    let mut sorted: Vec<&String> = words.iter().collect();
+    sorted.sort();
+    // A little under the exact product, so rounding never shortens the prefix
+    let must_share = (TITLE_SIMILARITY * words.len() as f64 - 1e-9).ceil() as usize;
+    let len = (words.len() + 1).saturating_sub(must_share.max(1));
+    sorted.into_iter().take(len)
+}
+
+/// The stories of a set of feeds, to look up which of them carry an article
+#[derive(Debug, Default)]
+pub struct StoryIndex {
+    normalizer: UrlNormalizer,
+    by_url: HashMap<String, BTreeSet<String>>,
+    titles: Vec<(HashSet<String>, String)>,
+    by_word: HashMap<String, Vec<usize>>, // indexed word -> positions in `titles`
+}
+
+impl StoryIndex {
+    /// An empty index matching links as `normalizer` reduces them
+    pub fn new(normalizer: UrlNormalizer) -> Self {
+        Self { normalizer, ..Self::default() }
+    }
+
+    /// Record that `feed_name` carries `article`
+    pub fn add(&mut self, feed_name: &str, article: &Article) {
+        if let Some(url) = self.normalizer.match_key(&article.link) {
+            self.by_url.entry(url).or_default().insert(feed_name.to_string());
+        }
+        let words = title_words(&article.title);
+        if words.len() >= MIN_TITLE_WORDS {
+            let position = self.titles.len();
+            for word in indexed_words(&words) {
+                self.by_word.entry(word.clone()).or_default().push(position);
+            }
+            self.titles.push((words, feed_name.to_string()));
+        }
+    }
+
+    /// Names of the indexed feeds carrying the same story as `article`,
+    /// other than `feed_name`
+    pub fn other_feeds(&self, feed_name: &str, article: &Article) -> BTreeSet<String> {
+        let mut feeds = self.normalizer.match_key(&article.link)
+            .and_then(|url| self.by_url.get(&url).cloned())
+            .unwrap_or_default();
+        let words = title_words(&article.title);
+        let candidates: BTreeSet<usize> = indexed_words(&words)
+            .filter_map(|word| self.by_word.get(word))
+            .flatten()
+            .copied()
+            .collect();
+        feeds.extend(candidates.into_iter()
+            .map(|position| &self.titles[position])
+            .filter(|(other, _)| similar_words(&words, other))
+            .map(|(_, feed)| feed.clone()));
+        feeds.remove(feed_name);
+        feeds
+    }
+}
+
+/// Whether the copy of a story in `feed_name` is the one kept when the same
+/// story is also in `also_in`: the copy in the feed whose name sorts first
+pub fn is_primary(feed_name: &str, also_in: &[String]) -> bool {
+    also_in.iter().all(|other| feed_name < other.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::test_article;
+
+    fn article(title: &str, link: &str) -> Article {
+        let mut article = test_article(link, title, "test");
+        article.link = link.to_string();
+        article
+    }
+
+    #[test]
+    fn test_other_feeds() {
+        let mut index = StoryIndex::new(UrlNormalizer::default());
+        index.add("hn", &article("Show HN: A tiny RSS reader", "https://example.com/reader?utm_medium=rss"));
+        index.add("lobsters", &article("Rewriting the compiler backend in Rust", "https://lobste.rs/s/abc"));
+        index.add("reddit", &article("Weekly update", "https://reddit.com/r/x/1"));
+
+        let same_link = article("A tiny RSS reader", "https://www.example.com/reader/");
+        assert_eq!(index.other_feeds("reddit", &same_link), BTreeSet::from(["hn".to_string()]));
+        assert!(index.other_feeds("hn", &same_link).is_empty());
+
+        let same_title = article("Rewriting the Compiler Backend in Rust!", "https://blog.example.org/backend");
+        assert_eq!(index.other_feeds("hn", &same_title), BTreeSet::from(["lobsters".to_string()]));
+
+        // Too short to match on the title alone
+        assert!(index.other_feeds("hn", &article("Weekly update", "https://other.example/1")).is_empty());
+
+        // Near-identical titles are found through the word index too
+        index.add("lwn", &article("Kernel 6.9 ships with new scheduler and faster io_uring paths", "https://lwn.net/1"));
+        let reworded = article("Linux kernel 6.9 ships with new scheduler and faster io_uring paths", "https://news.example/2");
+        assert_eq!(index.other_feeds("hn", &reworded), BTreeSet::from(["lwn".to_string()]));
+        let different = article("Kernel 6.10 ships with old scheduler and slower io_uring paths", "https://news.example/3");
+        assert!(index.other_feeds("hn", &different).is_empty());
+
+        assert!(is_primary("hn", &["lobsters".to_string()]));
+        assert!(!is_primary("reddit", &["hn".to_string()]));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::{Error, Result};
+use crate::feed::fetcher::FeedFetcher;
+use select::document::Document;
+use select::predicate::Name;
+use tracing::debug;
+
+/// MIME types advertised by `<link rel="alternate">` tags that point at feeds
+const FEED_TYPES: &[&str] = &[
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+    "application/json",
+];
+
+/// A feed advertised by a web page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredFeed {
+    /// Absolute feed URL
+    pub url: String,
+    /// The link's `title` attribute, if any
+    pub title: Option<String>,
+    /// The advertised MIME type
+    pub content_type: String,
+}
+
+/// Find feeds advertised in an HTML page's `<link rel="alternate">` tags.
+/// Relative hrefs are resolved against `base_url`; duplicates are dropped.
+pub fn discover_feeds(html: &str, base_url: &str) -> Vec<DiscoveredFeed> {
+    let base = url::Url::parse(base_url).ok();
+    let document = Document::from(html);
+    let mut feeds: Vec<DiscoveredFeed> = Vec::new();
+
+    for link in document.find(Name("link")) {
+        let is_alternate = link.attr("rel").is_some_and(|rel| {
+            rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("alternate"))
+        });
+        let content_type = match link.attr("type") {
+            Some(t) if is_alternate => t.trim().to_ascii_lowercase(),
+            _ => continue,
+        };
+        if !FEED_TYPES.contains(&content_type.as_str()) {
+            continue;
+        }
+
+        let Some(href) = link.attr("href").map(str::trim).filter(|h| !h.is_empty()) else {
+            continue;
+        };
+        let url = match &base {
+            Some(base) => match base.join(href) {
+                Ok(url) => url.to_string(),
+                Err(_) => continue,
+            },
+            None => href.to_string(),
+        };
+
+        if feeds.iter().any(|feed| feed.url == url) {
+            continue;
+        }
+        feeds.push(DiscoveredFeed {
+            url,
+            title: link.attr("title").map(|t| t.trim().to_string()).filter(|t| !t.is_empty()),
+            content_type,
+        });
+    }
+
+    feeds
+}
+
+/// Fetch a web page and list the feeds it advertises
+pub async fn discover_feeds_at(fetcher: &FeedFetcher, page_url: &str) -> Result<Vec<DiscoveredFeed>> {
+    let html = fetcher.fetch_page(page_url).await?;
+    let feeds = discover_feeds(&html, page_url);
+    debug!("Discovered {} feed(s) at {}", feeds.len(), page_url);
+
+    if feeds.is_empty() {
+        return Err(Error::NotFound(format!("No feeds advertised at {}", page_url)));
+    }
+    Ok(feeds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_feeds() {
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/style.css">
+            <link rel="alternate" type="application/rss+xml" title="Posts" href="/feed.xml">
+            <link rel="Alternate" type="application/atom+xml" href="https://example.com/atom.xml">
+            <link rel="alternate" type="application/feed+json" title=" JSON " href="feed.json">
+            <link rel="alternate" type="text/html" hreflang="de" href="/de/">
+            <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+        </head><body></body></html>"#;
+
+        let feeds = discover_feeds(html, "https://example.com/blog/");
+        let urls: Vec<&str> = feeds.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(urls, [
+            "https://example.com/feed.xml",
+            "https://example.com/atom.xml",
+            "https://example.com/blog/feed.json",
+        ]);
+        assert_eq!(feeds[0].title.as_deref(), Some("Posts"));
+        assert_eq!(feeds[1].title, None);
+        assert_eq!(feeds[2].title.as_deref(), Some("JSON"));
+        assert_eq!(feeds[2].content_type, "application/feed+json");
+    }
+
+    #[test]
+    fn test_discover_no_feeds() {
+        assert!(discover_feeds("<html><body><p>Hello</p></body></html>", "https://example.com").is_empty());
+    }
+}
@@ -1,17 +1,160 @@
 use crate::error::{Error, Result};
-use crate::feed::parser::FeedParser;
+use crate::feed::parser::{FeedParser, ParseLimits};
 use crate::feed::ParsedFeed;
-use reqwest::{Client, Response};
+use crate::feed::http_cache::{CachePolicy, CachedResponse, HttpCache};
+use crate::feed::rate_limit::RateLimiter;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, USER_AGENT};
+use reqwest::{Certificate, Client, Proxy, Response, StatusCode};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
+use tokio::sync::OwnedSemaphorePermit;
 use tokio::time::timeout;
 use tracing::{debug, warn, error};
 
+/// `User-Agent` sent unless one is configured
+pub const DEFAULT_USER_AGENT: &str = concat!("RSS-FUSE/", env!("CARGO_PKG_VERSION"));
+
 #[derive(Debug, Clone)]
 pub struct FeedFetcher {
     client: Client,
     timeout_duration: Duration,
     max_redirects: usize,
-    user_agent: String,
+    http_cache: Option<HttpCache>,
+    retry_policy: RetryPolicy,
+    network: NetworkOptions,
+    rate_limiter: Option<RateLimiter>,
+    paced: bool, // whether requests wait for their host's turn
+    limits: FeedLimits,
+}
+
+/// Largest feed the fetcher accepts, so a misbehaving server gets an
+/// error instead of exhausting memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedLimits {
+    /// Bytes of one feed response, counted while it downloads
+    pub max_response_size: u64,
+    pub parse: ParseLimits,
+}
+
+impl Default for FeedLimits {
+    fn default() -> Self {
+        Self {
+            max_response_size: 20 * 1024 * 1024,
+            parse: ParseLimits::default(),
+        }
+    }
+}
+
+/// Proxy, TLS and request header settings of the HTTP client
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkOptions {
+    pub proxy: ProxyMode,
+    /// PEM files with root certificates to trust besides the system ones
+    pub ca_certificates: Vec<PathBuf>,
+    /// Accept any server certificate. Only for testing or a trusted network.
+    pub insecure_skip_verify: bool,
+    /// `User-Agent` of every request; [`DEFAULT_USER_AGENT`] if unset
+    pub user_agent: Option<String>,
+    /// Headers added to every request, such as `Accept-Language`. They
+    /// replace the fetcher's own `Accept`.
+    pub headers: BTreeMap<String, String>,
+}
+
+impl NetworkOptions {
+    /// The `User-Agent` and extra headers to send. Fails on a header name
+    /// or value HTTP does not allow.
+    pub fn header_map(&self) -> Result<HeaderMap> {
+        let invalid = |what: &str, text: &str| Error::Config(format!("Invalid HTTP header {} '{}'", what, text));
+        let user_agent = self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT);
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_str(user_agent).map_err(|_| invalid("value", user_agent))?);
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid("name", name))?;
+            let value = HeaderValue::from_str(value).map_err(|_| invalid("value", value))?;
+            headers.insert(name, value);
+        }
+        Ok(headers)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ProxyMode {
+    /// Honour the `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY` environment
+    #[default]
+    System,
+    /// Connect directly, ignoring the environment
+    Direct,
+    /// Send every request through this `http://`, `https://` or `socks5://` proxy
+    Url(String),
+}
+
+impl ProxyMode {
+    /// Parse a configured proxy, where `"none"` means a direct connection
+    pub fn parse(value: &str) -> Result<Self> {
+        if value == "none" {
+            return Ok(ProxyMode::Direct);
+        }
+        let url = url::Url::parse(value).map_err(|_| Error::InvalidUrl(value.to_string()))?;
+        match url.scheme() {
+            "http" | "https" | "socks5" | "socks5h" => Ok(ProxyMode::Url(value.to_string())),
+            scheme => Err(Error::Config(format!(
+                "Unsupported proxy scheme '{}' in {} (use http, https, socks5 or socks5h)", scheme, value
+            ))),
+        }
+    }
+}
+
+/// How transient failures (timeouts, connection errors, HTTP 429 and 5xx)
+/// are retried within a single fetch
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub retries: usize,
+    /// Delay before the first retry; doubled for each one after
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Randomise each delay between half and all of its value so feeds
+    /// failing together do not retry in lockstep
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0-based)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let delay = self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+        if !self.jitter {
+            return delay;
+        }
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        delay.mul_f64(0.5 + f64::from(nanos % 1000) / 2000.0)
+    }
+}
+
+/// Whether a status is worth retrying
+fn is_transient_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Where `download_to` writes a file until its download is complete
+pub fn partial_path(path: &std::path::Path) -> PathBuf {
+    path.with_extension("part")
 }
 
 impl Default for FeedFetcher {
@@ -22,19 +165,63 @@ impl Default for FeedFetcher {
 
 impl FeedFetcher {
     pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .redirect(reqwest::redirect::Policy::limited(10))
-            .gzip(true)
-            .build()
-            .expect("Failed to create HTTP client");
+        let network = NetworkOptions::default();
+        let client = Self::build_client(&network).expect("Failed to create HTTP client");
 
         Self {
             client,
             timeout_duration: Duration::from_secs(30),
             max_redirects: 10,
-            user_agent: format!("RSS-FUSE/0.1.0 (+https://github.com/user/rss-fuse)"),
+            http_cache: None,
+            retry_policy: RetryPolicy::default(),
+            network,
+            rate_limiter: None,
+            paced: true,
+            limits: FeedLimits::default(),
+        }
+    }
+
+    /// Connect through a proxy, trust extra certificates and send headers
+    /// as `network` says. Fails if the proxy URL, a certificate file or a
+    /// header is unusable.
+    pub fn with_network(mut self, network: NetworkOptions) -> Result<Self> {
+        self.client = Self::build_client(&network)?;
+        self.network = network;
+        Ok(self)
+    }
+
+    pub fn network(&self) -> &NetworkOptions {
+        &self.network
+    }
+
+    /// An HTTP client set up as `network` says, for other APIs to share
+    pub fn build_client(network: &NetworkOptions) -> Result<Client> {
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .redirect(reqwest::redirect::Policy::limited(10))
+            .gzip(true)
+            .default_headers(network.header_map()?)
+            .danger_accept_invalid_certs(network.insecure_skip_verify);
+
+        builder = match &network.proxy {
+            ProxyMode::System => builder,
+            ProxyMode::Direct => builder.no_proxy(),
+            ProxyMode::Url(url) => builder.proxy(
+                Proxy::all(url).map_err(|e| Error::Config(format!("Invalid proxy {}: {}", url, e)))?
+            ),
+        };
+
+        for path in &network.ca_certificates {
+            let pem = std::fs::read(path).map_err(|e| Error::Config(format!(
+                "Cannot read CA certificate {}: {}", path.display(), e
+            )))?;
+            let certificate = Certificate::from_pem(&pem).map_err(|e| Error::Config(format!(
+                "Invalid CA certificate {}: {}", path.display(), e
+            )))?;
+            builder = builder.add_root_certificate(certificate);
         }
+
+        builder.build().map_err(|e| Error::Config(format!("Failed to create HTTP client: {}", e)))
     }
 
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
@@ -42,24 +229,125 @@ impl FeedFetcher {
         self
     }
 
-    pub fn with_user_agent(mut self, user_agent: String) -> Self {
-        self.user_agent = user_agent;
+    /// Identify as `user_agent` instead of [`DEFAULT_USER_AGENT`]
+    pub fn with_user_agent(self, user_agent: String) -> Result<Self> {
+        let network = NetworkOptions { user_agent: Some(user_agent), ..self.network.clone() };
+        self.with_network(network)
+    }
+
+    /// A GET request for `url` with the configured headers, accepting
+    /// `accept` unless an `Accept` header is configured
+    fn get(&self, url: &str, accept: Option<&str>) -> reqwest::RequestBuilder {
+        let request = self.client.get(url);
+        let configured = self.network.headers.keys().any(|name| name.eq_ignore_ascii_case(ACCEPT.as_str()));
+        match accept {
+            Some(accept) if !configured => request.header(ACCEPT, accept),
+            _ => request,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Reject feed responses and feeds larger than `limits`
+    pub fn with_limits(mut self, limits: FeedLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Space out requests per host and cap those in flight with `limiter`,
+    /// which may be shared with other fetchers
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Send requests without waiting for their host's turn, for reads that
+    /// someone is waiting on. Requests in flight are still capped.
+    pub fn without_pacing(mut self) -> Self {
+        self.paced = false;
+        self
+    }
+
+    /// A concurrent request slot, if requests are capped
+    async fn slot(&self) -> Option<OwnedSemaphorePermit> {
+        match &self.rate_limiter {
+            Some(limiter) => limiter.slot().await,
+            None => None,
+        }
+    }
+
+    /// Wait until `url`'s host may receive another request
+    async fn wait_turn(&self, url: &str) {
+        match &self.rate_limiter {
+            Some(limiter) if self.paced => limiter.wait_turn(url).await,
+            _ => {}
+        }
+    }
+
+    /// Keep raw feed responses in `cache` between fetches
+    pub fn with_http_cache(mut self, cache: HttpCache) -> Self {
+        self.http_cache = Some(cache);
         self
     }
 
     pub async fn fetch_feed(&self, url: &str) -> Result<ParsedFeed> {
+        match self.fetch_feed_conditional(url, &CacheValidators::default()).await? {
+            FetchOutcome::Modified { feed, .. } => Ok(feed),
+            FetchOutcome::NotModified => Err(Error::HttpError(format!(
+                "HTTP 304 for {} without a conditional request", url
+            ))),
+        }
+    }
+
+    /// Fetch a feed, sending `If-None-Match`/`If-Modified-Since` from
+    /// `validators`. Returns `NotModified` when the server answers 304.
+    ///
+    /// With an HTTP cache attached, a fresh cached body is used without a
+    /// request, and a stale one is revalidated with its own validators.
+    pub async fn fetch_feed_conditional(&self, url: &str, validators: &CacheValidators) -> Result<FetchOutcome> {
         debug!("Fetching feed from: {}", url);
 
         // Validate URL first
-        let parser = FeedParser::new();
+        let parser = FeedParser::new().with_limits(self.limits.parse);
         parser.validate_feed_url(url)?;
 
-        // Fetch with timeout
-        let response = timeout(self.timeout_duration, self.fetch_response(url))
-            .await
-            .map_err(|_| Error::Timeout(format!("Request to {} timed out", url)))?;
+        let cached = self.http_cache.as_ref().and_then(|cache| cache.get(url));
+        if let Some(ref cached) = cached {
+            if cached.is_fresh() {
+                debug!("Using fresh cached response for {}", url);
+                if !validators.is_empty() && *validators == cached.validators {
+                    return Ok(FetchOutcome::NotModified);
+                }
+                return Self::parse_cached(&parser, cached);
+            }
+        }
 
-        let response = response?;
+        // The caller's validators describe content it already holds; without
+        // them, revalidate the cached body instead
+        let sent = match &cached {
+            Some(cached) if validators.is_empty() => &cached.validators,
+            _ => validators,
+        };
+
+        let _slot = self.slot().await;
+        let response = self.fetch_response_retrying(url, sent).await?;
+        let policy = CachePolicy::from_headers(response.headers());
+
+        if response.status() == StatusCode::NOT_MODIFIED && !sent.is_empty() {
+            debug!("Feed not modified since last fetch: {}", url);
+            if let Some(cache) = &self.http_cache {
+                if let Err(e) = cache.revalidated(url, policy) {
+                    warn!("Failed to update HTTP cache for {}: {}", url, e);
+                }
+            }
+            return match cached {
+                Some(ref cached) if validators.is_empty() => Self::parse_cached(&parser, cached),
+                _ => Ok(FetchOutcome::NotModified),
+            };
+        }
         
         // Check response status
         if !response.status().is_success() {
@@ -71,25 +359,250 @@ impl FeedFetcher {
             )));
         }
 
-        // Get response body
-        let content = response
-            .bytes()
-            .await
-            .map_err(|e| Error::HttpError(format!("Failed to read response body: {}", e)))?;
+        let fresh_validators = CacheValidators::from_headers(response.headers());
+        let content_type = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let content = self.read_feed_body(response, url).await?;
 
         debug!("Downloaded {} bytes from {}", content.len(), url);
 
-        // Parse the feed
-        let cursor = std::io::Cursor::new(content);
-        parser.parse_feed(cursor)
+        // Parse the feed before caching so broken payloads are not kept
+        let feed = parser.parse_feed_as(&content[..], content_type.as_deref())?;
+        if let Some(cache) = &self.http_cache {
+            if let Err(e) = cache.store(url, &fresh_validators, content_type.as_deref(), policy, &content) {
+                warn!("Failed to cache response for {}: {}", url, e);
+            }
+        }
+        Ok(FetchOutcome::Modified { feed, validators: fresh_validators, raw: content })
+    }
+
+    /// The whole body of a feed response, giving up as soon as it is
+    /// known to exceed the size limit
+    async fn read_feed_body(&self, mut response: Response, url: &str) -> Result<Vec<u8>> {
+        let max = self.limits.max_response_size;
+        let too_large = || Error::ResourceExhausted(format!(
+            "Response from {} is larger than the limit of {} bytes", url, max
+        ));
+        if response.content_length().is_some_and(|len| len > max) {
+            return Err(too_large());
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = self.next_chunk(&mut response, url).await? {
+            if (body.len() + chunk.len()) as u64 > max {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    /// Send the request with a timeout, retrying transient failures
+    async fn fetch_response_retrying(&self, url: &str, validators: &CacheValidators) -> Result<Response> {
+        let mut retry = 0;
+        loop {
+            self.wait_turn(url).await;
+            let result = timeout(self.timeout_duration, self.fetch_response_with(url, validators))
+                .await
+                .unwrap_or_else(|_| Err(Error::Timeout(format!("Request to {} timed out", url))));
+
+            let transient = match &result {
+                Ok(response) => is_transient_status(response.status()),
+                Err(Error::Timeout(_) | Error::HttpError(_)) => true,
+                Err(_) => false,
+            };
+            if !transient || retry >= self.retry_policy.retries {
+                return result;
+            }
+
+            let delay = self.retry_policy.backoff(retry as u32);
+            warn!("Transient failure fetching {} (attempt {}), retrying in {:?}", url, retry + 1, delay);
+            tokio::time::sleep(delay).await;
+            retry += 1;
+        }
+    }
+
+    /// Whether responses are kept in an on-disk HTTP cache
+    pub fn has_http_cache(&self) -> bool {
+        self.http_cache.is_some()
+    }
+
+    /// Body of the cached response for `url`, fresh or not
+    pub fn cached_body(&self, url: &str) -> Option<Vec<u8>> {
+        self.http_cache.as_ref()?.get(url).map(|cached| cached.body)
+    }
+
+    fn parse_cached(parser: &FeedParser, cached: &CachedResponse) -> Result<FetchOutcome> {
+        let feed = parser.parse_feed_as(&cached.body[..], cached.content_type.as_deref())?;
+        Ok(FetchOutcome::Modified { feed, validators: cached.validators.clone(), raw: cached.body.clone() })
+    }
+
+    /// Download a web page (e.g. an article's link) as text
+    pub async fn fetch_page(&self, url: &str) -> Result<String> {
+        debug!("Fetching page from: {}", url);
+        let _slot = self.slot().await;
+        self.wait_turn(url).await;
+
+        let request = self.get(url, Some("text/html, application/xhtml+xml, */*")).send();
+
+        let response = timeout(self.timeout_duration, request)
+            .await
+            .map_err(|_| Error::Timeout(format!("Request to {} timed out", url)))?
+            .map_err(|e| Error::HttpError(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(Error::HttpError(format!(
+                "HTTP {} for {}",
+                response.status().as_u16(),
+                url
+            )));
+        }
+
+        response
+            .text()
+            .await
+            .map_err(|e| Error::HttpError(format!("Failed to read response body: {}", e)))
+    }
+
+    /// Read up to `len` bytes of a remote file starting at `offset` with a
+    /// `Range` request; past the end is empty. `None` when the server
+    /// ignores the range, so the file has to be downloaded instead.
+    pub async fn fetch_range(&self, url: &str, offset: u64, len: u64) -> Result<Option<Vec<u8>>> {
+        if len == 0 {
+            return Ok(Some(Vec::new()));
+        }
+        debug!("Fetching bytes {}+{} of {}", offset, len, url);
+        let _slot = self.slot().await;
+        self.wait_turn(url).await;
+
+        let request = self
+            .get(url, None)
+            .header("Range", format!("bytes={}-{}", offset, offset + len - 1))
+            .send();
+        let response = timeout(self.timeout_duration, request)
+            .await
+            .map_err(|_| Error::Timeout(format!("Request to {} timed out", url)))?
+            .map_err(|e| Error::HttpError(format!("Request failed: {}", e)))?;
+
+        match response.status() {
+            StatusCode::PARTIAL_CONTENT => self.read_body_part(response, url, len).await.map(Some),
+            StatusCode::RANGE_NOT_SATISFIABLE => Ok(Some(Vec::new())),
+            status if status.is_success() => Ok(None),
+            status => Err(Error::HttpError(format!("HTTP {} for {}", status.as_u16(), url))),
+        }
+    }
+
+    /// Download a remote file to `path`, writing through a temporary file so
+    /// an interrupted download never looks complete. Returns the size.
+    pub async fn download_to(&self, url: &str, path: &std::path::Path) -> Result<u64> {
+        self.download_to_with(url, path, |_| {}).await
+    }
+
+    /// `download_to`, calling `progress` with the number of bytes in the
+    /// file at `partial_path(path)` each time more have been written
+    pub async fn download_to_with(&self, url: &str, path: &std::path::Path, progress: impl FnMut(u64)) -> Result<u64> {
+        self.download_capped_with(url, path, None, progress).await
+    }
+
+    /// `download_to`, giving up and leaving nothing behind once the file
+    /// is known to be larger than `max` bytes
+    pub async fn download_capped(&self, url: &str, path: &std::path::Path, max: u64) -> Result<u64> {
+        self.download_capped_with(url, path, Some(max), |_| {}).await
+    }
+
+    /// `download_to_with`, capped at `max` bytes if given
+    pub async fn download_capped_with(
+        &self,
+        url: &str,
+        path: &std::path::Path,
+        max: Option<u64>,
+        mut progress: impl FnMut(u64),
+    ) -> Result<u64> {
+        use std::io::Write;
+        debug!("Downloading {} to {}", url, path.display());
+        let _slot = self.slot().await;
+        self.wait_turn(url).await;
+
+        let request = self.get(url, None).send();
+        let mut response = timeout(self.timeout_duration, request)
+            .await
+            .map_err(|_| Error::Timeout(format!("Request to {} timed out", url)))?
+            .map_err(|e| Error::HttpError(format!("Request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(Error::HttpError(format!("HTTP {} for {}", response.status().as_u16(), url)));
+        }
+        let too_large = |max: u64| Error::ResourceExhausted(format!(
+            "Download of {} is larger than the limit of {} bytes", url, max
+        ));
+        if let Some(max) = max.filter(|max| response.content_length().is_some_and(|len| len > *max)) {
+            return Err(too_large(max));
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp = partial_path(path);
+        let mut file = std::fs::File::create(&temp)?;
+        let mut written = 0u64;
+        while let Some(chunk) = self.next_chunk(&mut response, url).await? {
+            if let Some(max) = max.filter(|max| written + chunk.len() as u64 > *max) {
+                drop(file);
+                let _ = std::fs::remove_file(&temp);
+                return Err(too_large(max));
+            }
+            file.write_all(&chunk)?;
+            written += chunk.len() as u64;
+            progress(written);
+        }
+        file.sync_all()?;
+        std::fs::rename(&temp, path)
+            .map_err(|e| Error::Storage(format!("Failed to rename '{}': {}", temp.display(), e)))?;
+        Ok(written)
+    }
+
+    /// The first `len` bytes of a response body, stopping the download once
+    /// they have arrived
+    async fn read_body_part(&self, mut response: Response, url: &str, len: u64) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        while (data.len() as u64) < len {
+            let Some(chunk) = self.next_chunk(&mut response, url).await? else {
+                break;
+            };
+            let end = chunk.len().min(len as usize - data.len());
+            data.extend_from_slice(&chunk[..end]);
+        }
+        Ok(data)
+    }
+
+    /// Next piece of a response body; each one must arrive within the timeout
+    async fn next_chunk(&self, response: &mut Response, url: &str) -> Result<Option<Vec<u8>>> {
+        let chunk = timeout(self.timeout_duration, response.chunk())
+            .await
+            .map_err(|_| Error::Timeout(format!("Download of {} stalled", url)))?
+            .map_err(|e| Error::HttpError(format!("Failed to read response body: {}", e)))?;
+        Ok(chunk.map(|bytes| bytes.to_vec()))
     }
 
     async fn fetch_response(&self, url: &str) -> Result<Response> {
-        let response = self
-            .client
-            .get(url)
-            .header("User-Agent", &self.user_agent)
-            .header("Accept", "application/rss+xml, application/atom+xml, application/xml, text/xml, */*")
+        self.fetch_response_with(url, &CacheValidators::default()).await
+    }
+
+    async fn fetch_response_with(&self, url: &str, validators: &CacheValidators) -> Result<Response> {
+        let mut request = self.get(
+            url,
+            Some("application/rss+xml, application/atom+xml, application/feed+json, application/xml, text/xml, */*"),
+        );
+
+        if let Some(ref etag) = validators.etag {
+            request = request.header("If-None-Match", etag);
+        }
+        if let Some(ref last_modified) = validators.last_modified {
+            request = request.header("If-Modified-Since", last_modified);
+        }
+
+        let response = request
             .send()
             .await
             .map_err(|e| Error::HttpError(format!("Request failed: {}", e)))?;
@@ -115,6 +628,8 @@ impl FeedFetcher {
         let parser = FeedParser::new();
         parser.validate_feed_url(url)?;
 
+        let _slot = self.slot().await;
+        self.wait_turn(url).await;
         let response = timeout(Duration::from_secs(10), self.fetch_response(url))
             .await
             .map_err(|_| Error::Timeout(format!("Request to {} timed out", url)))?;
@@ -129,15 +644,7 @@ impl FeedFetcher {
             .unwrap_or("unknown")
             .to_string();
 
-        let last_modified = headers
-            .get("last-modified")
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string());
-
-        let etag = headers
-            .get("etag")
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string());
+        let CacheValidators { etag, last_modified } = CacheValidators::from_headers(&headers);
 
         Ok(FeedInfo {
             url: url.to_string(),
@@ -150,6 +657,49 @@ impl FeedFetcher {
     }
 }
 
+/// HTTP cache validators remembered from the last successful fetch, and
+/// kept with the feed's cache file so they outlive a restart
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CacheValidators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string())
+        };
+
+        Self {
+            etag: header("etag"),
+            last_modified: header("last-modified"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Result of a conditional fetch
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)] // short-lived, consumed by the refresh
+pub enum FetchOutcome {
+    Modified {
+        feed: ParsedFeed,
+        validators: CacheValidators,
+        /// The document as downloaded (or as cached)
+        raw: Vec<u8>,
+    },
+    NotModified,
+}
+
 #[derive(Debug, Clone)]
 pub struct FeedInfo {
     pub url: String,
@@ -163,7 +713,7 @@ pub struct FeedInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{method, path};
+    use wiremock::matchers::{header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
     use std::time::Duration;
 
@@ -182,6 +732,69 @@ mod tests {
     </channel>
 </rss>"#;
 
+    #[tokio::test]
+    async fn test_fetch_range() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ranged.mp3"))
+            .and(header("Range", "bytes=2-5"))
+            .respond_with(ResponseTemplate::new(206).set_body_bytes(b"2345".to_vec()))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/plain.mp3"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"0123456789".to_vec()))
+            .mount(&mock_server)
+            .await;
+
+        let fetcher = FeedFetcher::new();
+
+        let ranged = fetcher.fetch_range(&format!("{}/ranged.mp3", mock_server.uri()), 2, 4).await.unwrap();
+        assert_eq!(ranged.unwrap(), b"2345");
+
+        // Servers without range support have to be downloaded
+        let plain = format!("{}/plain.mp3", mock_server.uri());
+        assert!(fetcher.fetch_range(&plain, 8, 4).await.unwrap().is_none());
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let target = temp_dir.path().join("media").join("plain.mp3");
+        let mut reported = Vec::new();
+        assert_eq!(fetcher.download_to_with(&plain, &target, |n| reported.push(n)).await.unwrap(), 10);
+        assert_eq!(std::fs::read(&target).unwrap(), b"0123456789");
+        assert_eq!(reported.last(), Some(&10));
+        assert!(!partial_path(&target).exists());
+
+        let capped = temp_dir.path().join("capped.mp3");
+        assert!(matches!(fetcher.download_capped(&plain, &capped, 4).await, Err(Error::ResourceExhausted(_))));
+        assert!(!capped.exists());
+        assert!(!partial_path(&capped).exists());
+        assert_eq!(fetcher.download_capped(&plain, &capped, 10).await.unwrap(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_unpaced_reads_skip_the_host_turn() {
+        use crate::feed::rate_limit::RateLimits;
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ranged.mp3"))
+            .respond_with(ResponseTemplate::new(206).set_body_bytes(b"0123".to_vec()))
+            .mount(&mock_server)
+            .await;
+        let url = format!("{}/ranged.mp3", mock_server.uri());
+
+        // One request a minute: the second paced request would wait for it
+        let limiter = RateLimiter::new(RateLimits { requests_per_minute: Some(1), ..RateLimits::default() });
+        let fetcher = FeedFetcher::new().with_rate_limiter(limiter);
+        assert!(fetcher.fetch_range(&url, 0, 4).await.unwrap().is_some());
+        let paced = timeout(Duration::from_millis(200), fetcher.fetch_range(&url, 0, 4)).await;
+        assert!(paced.is_err());
+
+        let unpaced = fetcher.without_pacing();
+        let read = timeout(Duration::from_secs(5), unpaced.fetch_range(&url, 0, 4)).await;
+        assert_eq!(read.unwrap().unwrap().unwrap(), b"0123");
+    }
+
     #[tokio::test]
     async fn test_fetch_valid_feed() {
         let mock_server = MockServer::start().await;
@@ -434,12 +1047,193 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_conditional_fetch_not_modified() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(VALID_RSS_RESPONSE)
+                    .insert_header("etag", "\"v1\"")
+                    .insert_header("last-modified", "Wed, 15 Mar 2024 10:00:00 GMT")
+            )
+            .mount(&mock_server)
+            .await;
+
+        let fetcher = FeedFetcher::new();
+        let feed_url = format!("{}/feed.xml", mock_server.uri());
+
+        let validators = match fetcher.fetch_feed_conditional(&feed_url, &CacheValidators::default()).await.unwrap() {
+            FetchOutcome::Modified { feed, validators, .. } => {
+                assert_eq!(feed.title, "Test Feed");
+                validators
+            }
+            FetchOutcome::NotModified => panic!("First fetch must return the feed"),
+        };
+        assert_eq!(validators.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(validators.last_modified.as_deref(), Some("Wed, 15 Mar 2024 10:00:00 GMT"));
+
+        let outcome = fetcher.fetch_feed_conditional(&feed_url, &validators).await.unwrap();
+        assert!(matches!(outcome, FetchOutcome::NotModified));
+    }
+
+    #[tokio::test]
+    async fn test_http_cache_reuses_and_revalidates() {
+        use crate::clock::MockClock;
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(VALID_RSS_RESPONSE)
+                    .insert_header("etag", "\"v1\"")
+                    .insert_header("cache-control", "max-age=300")
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let clock = MockClock::default();
+        let cache = HttpCache::new(temp_dir.path()).with_clock(clock.shared());
+        let fetcher = FeedFetcher::new().with_http_cache(cache.clone());
+        let feed_url = format!("{}/feed.xml", mock_server.uri());
+
+        // Downloaded once, then served from the cache while fresh
+        for _ in 0..2 {
+            let outcome = fetcher.fetch_feed_conditional(&feed_url, &CacheValidators::default()).await.unwrap();
+            assert!(matches!(outcome, FetchOutcome::Modified { ref feed, .. } if feed.title == "Test Feed"));
+        }
+        let held = CacheValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+        let outcome = fetcher.fetch_feed_conditional(&feed_url, &held).await.unwrap();
+        assert!(matches!(outcome, FetchOutcome::NotModified));
+
+        // After a restart with a stale entry, a 304 still yields the feed
+        clock.advance(Duration::from_secs(301));
+        let restarted = FeedFetcher::new().with_http_cache(cache);
+        let outcome = restarted.fetch_feed_conditional(&feed_url, &CacheValidators::default()).await.unwrap();
+        assert!(matches!(outcome, FetchOutcome::Modified { ref feed, .. } if feed.title == "Test Feed"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_failure() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(VALID_RSS_RESPONSE))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let policy = RetryPolicy {
+            retries: 2,
+            initial_backoff: Duration::from_millis(10),
+            ..RetryPolicy::default()
+        };
+        let fetcher = FeedFetcher::new().with_retry_policy(policy);
+        let feed = fetcher.fetch_feed(&format!("{}/feed.xml", mock_server.uri())).await.unwrap();
+        assert_eq!(feed.title, "Test Feed");
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_client_error() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let policy = RetryPolicy { retries: 3, initial_backoff: Duration::from_millis(10), ..RetryPolicy::default() };
+        let fetcher = FeedFetcher::new().with_retry_policy(policy);
+        assert!(fetcher.fetch_feed(&format!("{}/feed.xml", mock_server.uri())).await.is_err());
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy {
+            retries: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            jitter: false,
+        };
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(4));
+        assert_eq!(policy.backoff(3), Duration::from_secs(5));
+
+        let jittered = RetryPolicy { jitter: true, ..policy };
+        let delay = jittered.backoff(1);
+        assert!(delay >= Duration::from_secs(1) && delay <= Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_through_proxy() {
+        let proxy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(VALID_RSS_RESPONSE))
+            .mount(&proxy)
+            .await;
+
+        let network = NetworkOptions { proxy: ProxyMode::Url(proxy.uri()), ..NetworkOptions::default() };
+        let fetcher = FeedFetcher::new().with_network(network).unwrap();
+        let feed = fetcher.fetch_feed("http://feeds.example/feed.xml").await.unwrap();
+        assert_eq!(feed.title, "Test Feed");
+
+        let requests = proxy.received_requests().await.unwrap();
+        assert_eq!(requests[0].url.host_str(), Some("feeds.example"));
+    }
+
+    #[test]
+    fn test_network_options() {
+        assert_eq!(ProxyMode::parse("none").unwrap(), ProxyMode::Direct);
+        assert_eq!(
+            ProxyMode::parse("socks5h://127.0.0.1:9050").unwrap(),
+            ProxyMode::Url("socks5h://127.0.0.1:9050".to_string())
+        );
+        assert!(ProxyMode::parse("ftp://proxy:21").is_err());
+        assert!(ProxyMode::parse("proxy:8080").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let bad_pem = dir.path().join("bad.pem");
+        std::fs::write(&bad_pem, "not a certificate").unwrap();
+        for ca_certificates in [vec![dir.path().join("missing.pem")], vec![bad_pem]] {
+            let network = NetworkOptions { ca_certificates, ..NetworkOptions::default() };
+            assert!(FeedFetcher::new().with_network(network).is_err());
+        }
+
+        let insecure = NetworkOptions { insecure_skip_verify: true, proxy: ProxyMode::Direct, ..NetworkOptions::default() };
+        assert_eq!(FeedFetcher::new().with_network(insecure.clone()).unwrap().network(), &insecure);
+    }
+
     #[tokio::test]
     async fn test_user_agent_header() {
         let mock_server = MockServer::start().await;
         
         Mock::given(method("GET"))
             .and(path("/feed.xml"))
+            .and(header("User-Agent", "CustomBot/1.0"))
             .respond_with(
                 ResponseTemplate::new(200)
                     .set_body_string(VALID_RSS_RESPONSE)
@@ -448,14 +1242,39 @@ mod tests {
             .await;
 
         let custom_user_agent = "CustomBot/1.0".to_string();
-        let fetcher = FeedFetcher::new().with_user_agent(custom_user_agent.clone());
+        let fetcher = FeedFetcher::new().with_user_agent(custom_user_agent).unwrap();
         let feed_url = format!("{}/feed.xml", mock_server.uri());
         
         let result = fetcher.fetch_feed(&feed_url).await;
         assert!(result.is_ok());
-        
-        // Note: We can't easily verify the User-Agent header was sent correctly
-        // without more complex mock setup, but the test ensures the method works
+    }
+
+    #[tokio::test]
+    async fn test_extra_headers() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .and(header("User-Agent", DEFAULT_USER_AGENT))
+            .and(header("Accept-Language", "de"))
+            .and(header("Accept", "application/rss+xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(VALID_RSS_RESPONSE))
+            .mount(&mock_server)
+            .await;
+
+        let headers = BTreeMap::from([
+            ("Accept-Language".to_string(), "de".to_string()),
+            ("accept".to_string(), "application/rss+xml".to_string()),
+        ]);
+        let network = NetworkOptions { headers, ..NetworkOptions::default() };
+        let fetcher = FeedFetcher::new().with_network(network).unwrap();
+        assert!(fetcher.fetch_feed(&format!("{}/feed.xml", mock_server.uri())).await.is_ok());
+
+        let bad_name = NetworkOptions {
+            headers: BTreeMap::from([("Bad Name".to_string(), "x".to_string())]),
+            ..NetworkOptions::default()
+        };
+        assert!(FeedFetcher::new().with_network(bad_name).is_err());
+        assert!(FeedFetcher::new().with_user_agent("Bot\n1.0".to_string()).is_err());
     }
 
     #[tokio::test]
@@ -527,4 +1346,22 @@ mod tests {
         assert_eq!(feed.title, "Large Feed");
         assert_eq!(feed.articles.len(), 1000);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_response_size_limit() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(VALID_RSS_RESPONSE))
+            .mount(&mock_server)
+            .await;
+        let feed_url = format!("{}/feed.xml", mock_server.uri());
+
+        let limits = FeedLimits { max_response_size: 64, ..FeedLimits::default() };
+        let result = FeedFetcher::new().with_limits(limits).fetch_feed(&feed_url).await;
+        assert!(matches!(result, Err(Error::ResourceExhausted(_))));
+
+        let limits = FeedLimits { max_response_size: VALID_RSS_RESPONSE.len() as u64, ..FeedLimits::default() };
+        assert!(FeedFetcher::new().with_limits(limits).fetch_feed(&feed_url).await.is_ok());
+    }
+}
@@ -0,0 +1,156 @@
+//! Keyword rules applied to fetched articles before they are stored: each
+//! rule matches fields against regular expressions and drops, marks read,
+//! tags or files away the articles it matches.
+
+use regex::Regex;
+
+use crate::feed::Article;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterAction {
+    /// Leave the article out entirely
+    Drop,
+    MarkRead,
+    /// Add a tag, shown in frontmatter and `user.rss.tags`
+    Tag(String),
+    /// List the article in a subfolder of its feed directory
+    Folder(String),
+}
+
+/// One rule: every pattern given must match for the action to apply
+#[derive(Debug, Clone)]
+pub struct FilterRule {
+    pub title: Option<Regex>,
+    /// Matched against the raw content, or the description if there is none
+    pub content: Option<Regex>,
+    pub author: Option<Regex>,
+    /// Matches if any one tag matches
+    pub tags: Option<Regex>,
+    pub action: FilterAction,
+}
+
+impl FilterRule {
+    pub fn matches(&self, article: &Article) -> bool {
+        let text = article.content.as_deref().or(article.description.as_deref()).unwrap_or("");
+        self.title.as_ref().map_or(true, |re| re.is_match(&article.title))
+            && self.content.as_ref().map_or(true, |re| re.is_match(text))
+            && self.author.as_ref().map_or(true, |re| article.author.as_deref().is_some_and(|author| re.is_match(author)))
+            && self.tags.as_ref().map_or(true, |re| article.tags.iter().any(|tag| re.is_match(tag)))
+    }
+}
+
+/// The rules that apply to one feed, in config order
+#[derive(Debug, Clone, Default)]
+pub struct ArticleFilter {
+    rules: Vec<FilterRule>,
+}
+
+impl ArticleFilter {
+    pub fn new(rules: Vec<FilterRule>) -> Self {
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Apply every matching rule to freshly fetched articles. Dropped
+    /// articles are left out; the first matching folder rule wins.
+    pub fn apply(&self, articles: Vec<Article>) -> Vec<Article> {
+        if self.is_empty() {
+            return articles;
+        }
+        articles.into_iter()
+            .filter_map(|mut article| {
+                article.folder = None;
+                // Rules match the article as fetched, not as changed by earlier rules
+                let matched: Vec<&FilterRule> = self.rules.iter().filter(|rule| rule.matches(&article)).collect();
+                for rule in matched {
+                    match &rule.action {
+                        FilterAction::Drop => return None,
+                        FilterAction::MarkRead => article.read = true,
+                        FilterAction::Tag(tag) => {
+                            if !article.tags.contains(tag) {
+                                article.tags.push(tag.clone());
+                            }
+                        }
+                        FilterAction::Folder(folder) => {
+                            article.folder.get_or_insert_with(|| folder.clone());
+                        }
+                    }
+                }
+                Some(article)
+            })
+            .collect()
+    }
+
+    /// Whether a drop rule matches, for articles kept from earlier refreshes
+    pub fn drops(&self, article: &Article) -> bool {
+        self.rules.iter().any(|rule| rule.action == FilterAction::Drop && rule.matches(article))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::test_article;
+
+    fn article(title: &str, author: Option<&str>, tags: &[&str]) -> Article {
+        let mut article = test_article(title, title, "news");
+        article.description = Some("<p>Sponsored post</p>".to_string());
+        article.author = author.map(str::to_string);
+        article.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        article
+    }
+
+    fn rule(action: FilterAction) -> FilterRule {
+        FilterRule { title: None, content: None, author: None, tags: None, action }
+    }
+
+    #[test]
+    fn test_rule_matches_all_patterns() {
+        let rust = FilterRule {
+            title: Some(Regex::new("(?i)rust").unwrap()),
+            tags: Some(Regex::new("^lang").unwrap()),
+            ..rule(FilterAction::Drop)
+        };
+        assert!(rust.matches(&article("Rust 2.0", None, &["misc", "languages"])));
+        assert!(!rust.matches(&article("Rust 2.0", None, &["misc"])));
+        assert!(!rust.matches(&article("Go 2.0", None, &["languages"])));
+
+        let by_author = FilterRule { author: Some(Regex::new("Bot$").unwrap()), ..rust };
+        assert!(!by_author.matches(&article("Rust 2.0", None, &["languages"])));
+        assert!(by_author.matches(&article("Rust 2.0", Some("Release Bot"), &["languages"])));
+
+        // Falls back to the description when there is no content
+        let sponsored = FilterRule { content: Some(Regex::new("Sponsored").unwrap()), ..rule(FilterAction::Drop) };
+        assert!(sponsored.matches(&article("Anything", None, &[])));
+    }
+
+    #[test]
+    fn test_apply_actions() {
+        let crypto = |action| FilterRule { title: Some(Regex::new("(?i)crypto").unwrap()), ..rule(action) };
+        let filter = ArticleFilter::new(vec![
+            FilterRule { title: Some(Regex::new("^Ad:").unwrap()), ..rule(FilterAction::Drop) },
+            crypto(FilterAction::Tag("crypto".to_string())),
+            crypto(FilterAction::MarkRead),
+            crypto(FilterAction::Folder("later".to_string())),
+            crypto(FilterAction::Folder("never".to_string())),
+        ]);
+
+        let articles = filter.apply(vec![
+            article("Ad: buy now", None, &[]),
+            article("Crypto winter", None, &["finance"]),
+            article("Rust 2.0", None, &[]),
+        ]);
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].tags, ["finance", "crypto"]);
+        assert!(articles[0].read);
+        assert_eq!(articles[0].folder.as_deref(), Some("later"));
+        assert!(!articles[1].read && articles[1].folder.is_none());
+
+        assert!(filter.drops(&article("Ad: again", None, &[])));
+        assert!(!filter.drops(&articles[0]));
+        assert_eq!(ArticleFilter::default().apply(articles.clone()).len(), 2);
+    }
+}
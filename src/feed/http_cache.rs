@@ -0,0 +1,275 @@
+use crate::clock::{system_clock, SharedClock};
+use crate::error::{Error, Result};
+use crate::feed::fetcher::CacheValidators;
+use reqwest::header::{HeaderMap, CACHE_CONTROL};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use tracing::debug;
+
+/// Disk-backed store of raw feed responses keyed by URL, so rapid refreshes
+/// and restarts can reuse (or revalidate) a payload instead of downloading it
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+    /// Replaces the server's `Cache-Control: max-age` when set
+    max_age_override: Option<Duration>,
+    clock: SharedClock,
+}
+
+/// A cached response body and what is known about its freshness
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub validators: CacheValidators,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+    fresh: bool,
+}
+
+impl CachedResponse {
+    /// Whether the body can be used without asking the server
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+}
+
+/// How a response may be cached according to its `Cache-Control` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Must not be stored at all (`no-store`)
+    NoStore,
+    /// May be reused without revalidation for this long (zero for `no-cache`)
+    MaxAge(Duration),
+    /// No freshness information; revalidate on every use
+    Unspecified,
+}
+
+impl CachePolicy {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let directives: Vec<String> = headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|d| d.trim().to_ascii_lowercase())
+            .collect();
+
+        if directives.iter().any(|d| d == "no-store") {
+            return CachePolicy::NoStore;
+        }
+        if directives.iter().any(|d| d == "no-cache") {
+            return CachePolicy::MaxAge(Duration::ZERO);
+        }
+        directives
+            .iter()
+            .find_map(|d| d.strip_prefix("max-age=")?.trim_matches('"').parse().ok())
+            .map(|secs| CachePolicy::MaxAge(Duration::from_secs(secs)))
+            .unwrap_or(CachePolicy::Unspecified)
+    }
+}
+
+/// Metadata stored next to each body file
+#[derive(Debug, Serialize, Deserialize)]
+struct EntryMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content_type: Option<String>,
+    /// Seconds since the epoch at which the body was (re)validated
+    stored_at: u64,
+    max_age_secs: Option<u64>,
+}
+
+impl HttpCache {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            max_age_override: None,
+            clock: system_clock(),
+        }
+    }
+
+    /// Use this freshness lifetime instead of the server's `max-age`
+    pub fn with_max_age_override(mut self, max_age: Option<Duration>) -> Self {
+        self.max_age_override = max_age;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Look up the cached response for `url`
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        let (meta_path, body_path) = self.paths(url);
+        let meta: EntryMeta = serde_json::from_slice(&fs::read(meta_path).ok()?).ok()?;
+        if meta.url != url {
+            return None;
+        }
+        let body = fs::read(body_path).ok()?;
+
+        let max_age = self.max_age_override.or(meta.max_age_secs.map(Duration::from_secs));
+        let age = Duration::from_secs(self.now_secs().saturating_sub(meta.stored_at));
+        Some(CachedResponse {
+            validators: CacheValidators {
+                etag: meta.etag,
+                last_modified: meta.last_modified,
+            },
+            content_type: meta.content_type,
+            body,
+            fresh: max_age.is_some_and(|max_age| age < max_age),
+        })
+    }
+
+    /// Store a freshly downloaded body, or drop the entry for `no-store`
+    pub fn store(
+        &self,
+        url: &str,
+        validators: &CacheValidators,
+        content_type: Option<&str>,
+        policy: CachePolicy,
+        body: &[u8],
+    ) -> Result<()> {
+        let max_age = match policy {
+            CachePolicy::NoStore => return self.remove(url),
+            CachePolicy::MaxAge(max_age) => Some(max_age.as_secs()),
+            CachePolicy::Unspecified => None,
+        };
+        let meta = EntryMeta {
+            url: url.to_string(),
+            etag: validators.etag.clone(),
+            last_modified: validators.last_modified.clone(),
+            content_type: content_type.map(str::to_string),
+            stored_at: self.now_secs(),
+            max_age_secs: max_age,
+        };
+
+        fs::create_dir_all(&self.dir)?;
+        let (meta_path, body_path) = self.paths(url);
+        Self::write_atomic(&body_path, body)?;
+        Self::write_atomic(&meta_path, &serde_json::to_vec(&meta)?)?;
+        debug!("Cached {} bytes for {}", body.len(), url);
+        Ok(())
+    }
+
+    /// Record that the server confirmed the cached body (HTTP 304)
+    pub fn revalidated(&self, url: &str, policy: CachePolicy) -> Result<()> {
+        let (meta_path, _) = self.paths(url);
+        let Ok(raw) = fs::read(&meta_path) else {
+            return Ok(());
+        };
+        let mut meta: EntryMeta = serde_json::from_slice(&raw)?;
+        meta.stored_at = self.now_secs();
+        match policy {
+            CachePolicy::NoStore => return self.remove(url),
+            CachePolicy::MaxAge(max_age) => meta.max_age_secs = Some(max_age.as_secs()),
+            CachePolicy::Unspecified => {}
+        }
+        Self::write_atomic(&meta_path, &serde_json::to_vec(&meta)?)
+    }
+
+    pub fn remove(&self, url: &str) -> Result<()> {
+        let (meta_path, body_path) = self.paths(url);
+        for path in [meta_path, body_path] {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key = blake3::hash(url.as_bytes()).to_hex();
+        (
+            self.dir.join(format!("{}.json", key)),
+            self.dir.join(format!("{}.body", key)),
+        )
+    }
+
+    fn now_secs(&self) -> u64 {
+        self.clock.now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+    }
+
+    fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, data)
+            .map_err(|e| Error::Storage(format!("Failed to write '{}': {}", temp.display(), e)))?;
+        fs::rename(&temp, path)
+            .map_err(|e| Error::Storage(format!("Failed to rename '{}': {}", temp.display(), e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use reqwest::header::HeaderValue;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_policy_from_headers() {
+        let policy = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(CACHE_CONTROL, HeaderValue::from_str(value).unwrap());
+            CachePolicy::from_headers(&headers)
+        };
+
+        assert_eq!(policy("public, max-age=300"), CachePolicy::MaxAge(Duration::from_secs(300)));
+        assert_eq!(policy("no-cache"), CachePolicy::MaxAge(Duration::ZERO));
+        assert_eq!(policy("no-store, max-age=60"), CachePolicy::NoStore);
+        assert_eq!(policy("private"), CachePolicy::Unspecified);
+        assert_eq!(CachePolicy::from_headers(&HeaderMap::new()), CachePolicy::Unspecified);
+    }
+
+    #[test]
+    fn test_store_and_expire() {
+        let temp_dir = TempDir::new().unwrap();
+        let clock = MockClock::default();
+        let cache = HttpCache::new(temp_dir.path()).with_clock(clock.shared());
+        let url = "https://example.com/feed.xml";
+        let validators = CacheValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+
+        assert!(cache.get(url).is_none());
+        cache.store(url, &validators, Some("application/rss+xml"), CachePolicy::MaxAge(Duration::from_secs(60)), b"<rss/>").unwrap();
+
+        let cached = cache.get(url).unwrap();
+        assert!(cached.is_fresh());
+        assert_eq!(cached.body, b"<rss/>");
+        assert_eq!(cached.validators, validators);
+        assert_eq!(cached.content_type.as_deref(), Some("application/rss+xml"));
+
+        clock.advance(Duration::from_secs(61));
+        assert!(!cache.get(url).unwrap().is_fresh());
+
+        // A 304 restarts the freshness lifetime
+        cache.revalidated(url, CachePolicy::Unspecified).unwrap();
+        assert!(cache.get(url).unwrap().is_fresh());
+
+        cache.store(url, &validators, None, CachePolicy::NoStore, b"<rss/>").unwrap();
+        assert!(cache.get(url).is_none());
+    }
+
+    #[test]
+    fn test_max_age_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let clock = MockClock::default();
+        let url = "https://example.com/feed.xml";
+        HttpCache::new(temp_dir.path())
+            .with_clock(clock.shared())
+            .store(url, &CacheValidators::default(), None, CachePolicy::Unspecified, b"body")
+            .unwrap();
+
+        let plain = HttpCache::new(temp_dir.path()).with_clock(clock.shared());
+        assert!(!plain.get(url).unwrap().is_fresh());
+
+        let overridden = HttpCache::new(temp_dir.path())
+            .with_clock(clock.shared())
+            .with_max_age_override(Some(Duration::from_secs(600)));
+        assert!(overridden.get(url).unwrap().is_fresh());
+        clock.advance(Duration::from_secs(600));
+        assert!(!overridden.get(url).unwrap().is_fresh());
+    }
+}
@@ -1,11 +1,28 @@
 // pub mod manager;
+pub mod aggregate;
+pub mod dedup;
+pub mod discovery;
 pub mod fetcher;
+pub mod filter;
+pub mod http_cache;
+pub mod notifications;
 pub mod parser;
+pub mod rate_limit;
+pub mod scheduler;
+pub mod scoring;
+pub mod urls;
 // pub mod cache;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::config::TextHtmlPolicy;
+use crate::content::render_html_as_text;
+use crate::fuse::naming;
+
+/// Article file names are kept well under the filesystem limit for readability
+const MAX_TITLE_NAME_BYTES: usize = 100;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Feed {
     pub name: String,
@@ -17,15 +34,25 @@ pub struct Feed {
     pub status: FeedStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub enum FeedStatus {
+    #[default]
     Active,
     Error(String),
+    /// A fetch is running; not kept in the status history
     Updating,
+    /// Not fetched; cached articles stay visible
     Disabled,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A feed entering a status
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub status: FeedStatus,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Article {
     pub id: String,
     pub title: String,
@@ -37,7 +64,88 @@ pub struct Article {
     pub updated: Option<DateTime<Utc>>,
     pub tags: Vec<String>,
     pub read: bool,
+    #[serde(default)]
+    pub starred: bool,
+    /// Subfolder of the feed directory a filter rule routed the article to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
     pub cached_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub guid: Option<String>,
+    #[serde(default)]
+    pub enclosures: Vec<Enclosure>,
+    /// Hash of `content` while it is kept in a mount's content store instead
+    /// of memory (see `storage::ContentStore`); `content` is `None` meanwhile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_ref: Option<String>,
+    /// Same as `content_ref`, for `description`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_ref: Option<String>,
+}
+
+/// Media attached to an article, such as a podcast episode's audio
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Enclosure {
+    pub url: String,
+    pub mime_type: Option<String>,
+    /// Size in bytes as advertised by the feed, which may be missing or wrong
+    pub length: Option<u64>,
+}
+
+impl Enclosure {
+    /// File extension for the media, taken from the URL path and falling
+    /// back to the MIME type
+    pub fn extension(&self) -> String {
+        let from_path = url::Url::parse(&self.url).ok().and_then(|url| {
+            let name = url.path_segments()?.next_back()?.to_string();
+            let (_, ext) = name.rsplit_once('.')?;
+            let valid = !ext.is_empty() && ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric());
+            valid.then(|| ext.to_ascii_lowercase())
+        });
+        from_path
+            .or_else(|| self.mime_type.as_deref().and_then(extension_for_mime).map(str::to_string))
+            .unwrap_or_else(|| "bin".to_string())
+    }
+}
+
+fn extension_for_mime(mime: &str) -> Option<&'static str> {
+    let essence = mime.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    let ext = match essence.as_str() {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/mp4" | "audio/x-m4a" | "audio/m4a" => "m4a",
+        "audio/aac" => "aac",
+        "audio/ogg" => "ogg",
+        "audio/opus" => "opus",
+        "audio/flac" => "flac",
+        "audio/wav" | "audio/x-wav" => "wav",
+        "video/mp4" => "mp4",
+        "video/webm" => "webm",
+        "video/quicktime" => "mov",
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "application/pdf" => "pdf",
+        _ => return None,
+    };
+    Some(ext)
+}
+
+/// How a feed's article IDs are derived. Feeds that recycle GUIDs or rewrite
+/// links on every fetch can pick a more stable source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum IdStrategy {
+    /// The entry's GUID, falling back to a hash of the link
+    #[default]
+    #[serde(rename = "guid")]
+    Guid,
+    /// Hash of the article link
+    #[serde(rename = "link")]
+    Link,
+    /// Hash of the title and publish date
+    #[serde(rename = "title+date")]
+    TitleDate,
+    /// Hash of the title and body
+    #[serde(rename = "content-hash")]
+    ContentHash,
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +153,8 @@ pub struct ParsedFeed {
     pub title: String,
     pub description: Option<String>,
     pub link: Option<String>,
+    /// Feed icon or channel image, possibly relative to `link`
+    pub image: Option<String>,
     pub last_build_date: Option<DateTime<Utc>>,
     pub articles: Vec<ParsedArticle>,
 }
@@ -59,26 +169,29 @@ pub struct ParsedArticle {
     pub published: Option<DateTime<Utc>>,
     pub guid: Option<String>,
     pub categories: Vec<String>,
+    pub enclosures: Vec<Enclosure>,
 }
 
-#[derive(Debug)]
+/// What a refresh did to a feed's stored articles
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FeedResult {
     pub feed_name: String,
     pub success: bool,
     pub error: Option<String>,
     pub articles_added: usize,
     pub articles_updated: usize,
+    /// Articles dropped by filter rules or the retention limit
+    pub articles_removed: usize,
 }
 
 impl Article {
     pub fn new(parsed: ParsedArticle, feed_name: &str) -> Self {
-        let id = parsed.guid.unwrap_or_else(|| {
-            format!("{}:{}", feed_name, 
-                blake3::hash(parsed.link.as_bytes()).to_hex().to_string())
-        });
-        
-        Self {
-            id,
+        Self::with_id_strategy(parsed, feed_name, IdStrategy::Guid)
+    }
+
+    pub fn with_id_strategy(parsed: ParsedArticle, feed_name: &str, strategy: IdStrategy) -> Self {
+        let mut article = Self {
+            id: String::new(),
             title: parsed.title,
             link: parsed.link,
             description: parsed.description,
@@ -88,12 +201,78 @@ impl Article {
             updated: None,
             tags: parsed.categories,
             read: false,
+            starred: false,
+            folder: None,
             cached_at: Some(Utc::now()),
+            guid: parsed.guid,
+            enclosures: parsed.enclosures,
+            content_ref: None,
+            description_ref: None,
+        };
+        article.id = article.id_for(feed_name, strategy);
+        article
+    }
+
+    /// Compute this article's ID under `strategy`. Used both when creating
+    /// articles and to migrate cached state after a feed's strategy changes.
+    pub fn id_for(&self, feed_name: &str, strategy: IdStrategy) -> String {
+        let hashed = |input: &str| format!("{}:{}", feed_name, blake3::hash(input.as_bytes()).to_hex());
+        match strategy {
+            IdStrategy::Guid => match &self.guid {
+                Some(guid) => guid.clone(),
+                None => hashed(&self.link),
+            },
+            IdStrategy::Link => hashed(&self.link),
+            IdStrategy::TitleDate => {
+                let date = self.published.map(|d| d.to_rfc3339()).unwrap_or_default();
+                hashed(&format!("{}\n{}", self.title, date))
+            }
+            IdStrategy::ContentHash => {
+                let body = self.content.as_deref().or(self.description.as_deref()).unwrap_or("");
+                hashed(&format!("{}\n{}", self.title, body))
+            }
+        }
+    }
+    
+    /// Take the fields of a newly fetched copy of this article, keeping local
+    /// state (read flag, cache time) and content the fetch left out. Stamps
+    /// `updated` with `now` and returns `true` if anything changed.
+    pub fn merge_from(&mut self, fetched: Article, now: DateTime<Utc>) -> bool {
+        let content = fetched.content.or_else(|| self.content.clone());
+        let published = fetched.published.or(self.published);
+        let changed = self.title != fetched.title
+            || self.link != fetched.link
+            || self.description != fetched.description
+            || self.content != content
+            || self.author != fetched.author
+            || self.published != published
+            || self.tags != fetched.tags
+            || self.enclosures != fetched.enclosures;
+
+        self.id = fetched.id;
+        self.title = fetched.title;
+        self.link = fetched.link;
+        self.description = fetched.description;
+        self.content = content;
+        self.author = fetched.author;
+        self.published = published;
+        self.tags = fetched.tags;
+        self.enclosures = fetched.enclosures;
+        self.guid = fetched.guid.or(self.guid.take());
+        self.folder = fetched.folder;
+        if changed {
+            self.updated = Some(now);
         }
+        changed
     }
     
     /// Legacy method for backward compatibility - returns plain text format
     pub fn to_text(&self) -> String {
+        self.to_text_with_policy(TextHtmlPolicy::default())
+    }
+
+    /// Plain text format, handling embedded HTML according to `policy`
+    pub fn to_text_with_policy(&self, policy: TextHtmlPolicy) -> String {
         let mut text = String::new();
         
         text.push_str(&format!("Title: {}\n", self.title));
@@ -115,9 +294,9 @@ impl Article {
         text.push_str("\n---\n\n");
         
         if let Some(content) = &self.content {
-            text.push_str(content);
+            text.push_str(&render_html_as_text(content, policy));
         } else if let Some(description) = &self.description {
-            text.push_str(description);
+            text.push_str(&render_html_as_text(description, policy));
         } else {
             text.push_str("No content available. Visit the link above to read the full article.");
         }
@@ -134,41 +313,43 @@ impl Article {
     
     /// Get filename with .txt extension (legacy)
     pub fn filename(&self) -> String {
-        let title = self.title
-            .chars()
-            .map(|c| match c {
-                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
-                c if c.is_control() => '-',
-                c => c,
-            })
-            .collect::<String>();
-        
-        let truncated = if title.len() > 100 {
-            format!("{}...", &title[..97])
-        } else {
-            title
-        };
-        
-        format!("{}.txt", truncated)
+        naming::fit_name(&self.sanitized_title(), ".txt", MAX_TITLE_NAME_BYTES)
     }
 
     /// Get filename with .md extension for Markdown format
     pub fn markdown_filename(&self) -> String {
-        let title = self.title
-            .chars()
-            .map(|c| match c {
-                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
-                c if c.is_control() => '-',
-                c => c,
-            })
-            .collect::<String>();
-        
-        let truncated = if title.len() > 100 {
-            format!("{}...", &title[..97])
-        } else {
-            title
-        };
-        
-        format!("{}.md", truncated)
+        naming::fit_name(&self.sanitized_title(), ".md", MAX_TITLE_NAME_BYTES)
+    }
+
+    /// Get filename with .html extension for HTML format
+    pub fn html_filename(&self) -> String {
+        naming::fit_name(&self.sanitized_title(), ".html", MAX_TITLE_NAME_BYTES)
+    }
+
+    /// File name built from `template` for the `index`th article of `feed_name`
+    pub fn templated_filename(&self, template: &naming::FilenameTemplate, feed_name: &str, index: usize, ext: &str) -> String {
+        template.render(self, feed_name, index, ext)
+    }
+
+    fn sanitized_title(&self) -> String {
+        naming::sanitize(&self.title)
     }
-}
\ No newline at end of file
+}
+
+/// An article of `feed` with GUID (and so ID) `id`, linking to
+/// `https://example.com/<id>` and nothing else set; tests fill in the
+/// fields they care about
+#[cfg(test)]
+pub(crate) fn test_article(id: &str, title: &str, feed: &str) -> Article {
+    Article::new(ParsedArticle {
+        title: title.to_string(),
+        link: format!("https://example.com/{}", id),
+        description: None,
+        content: None,
+        author: None,
+        published: None,
+        guid: Some(id.to_string()),
+        categories: Vec::new(),
+        enclosures: Vec::new(),
+    }, feed)
+}
@@ -0,0 +1,257 @@
+//! Announcing articles that refreshes add: a desktop notification through
+//! `notify-send` and a JSON `POST` to each configured webhook. Articles are
+//! collected for a batch window first, so a round of refreshes produces one
+//! message instead of one per feed.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+use crate::config::NotificationsConfig;
+use crate::feed::{Article, Feed};
+
+/// Article titles listed in one notification; the rest are only counted
+const MAX_LISTED: usize = 5;
+
+/// New articles of one feed, as sent to webhooks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewArticles {
+    pub feed: String,
+    pub title: Option<String>,
+    pub articles: Vec<NewArticle>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewArticle {
+    pub title: String,
+    pub link: String,
+}
+
+/// Body of a webhook request. `text` is what Slack and similar services
+/// display; `feeds` carries the details.
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    text: String,
+    feeds: &'a [NewArticles],
+}
+
+/// Collects new articles and sends them out once per batch window
+pub struct Notifier {
+    desktop: bool,
+    webhooks: Vec<String>,
+    batch_window: Duration,
+    http: reqwest::Client,
+    muted: RwLock<HashSet<String>>,
+    pending: Mutex<BTreeMap<String, NewArticles>>,
+    wake: Notify,
+}
+
+impl Notifier {
+    pub fn new(config: &NotificationsConfig, http: reqwest::Client) -> Self {
+        Self {
+            desktop: config.desktop,
+            webhooks: config.webhooks.clone(),
+            batch_window: Duration::from_secs(config.batch_window),
+            http,
+            muted: RwLock::new(HashSet::new()),
+            pending: Mutex::new(BTreeMap::new()),
+            wake: Notify::new(),
+        }
+    }
+
+    /// Turn notifications for `feed_name` on or off
+    pub fn set_enabled(&self, feed_name: &str, enabled: bool) {
+        let mut muted = self.muted.write();
+        if enabled {
+            muted.remove(feed_name);
+        } else {
+            muted.insert(feed_name.to_string());
+        }
+    }
+
+    /// Queue `articles`, just added to `feed`, for the next batch
+    pub fn articles_added<'a>(&self, feed: &Feed, articles: impl IntoIterator<Item = &'a Article>) {
+        if self.muted.read().contains(&feed.name) {
+            return;
+        }
+        let mut pending = self.pending.lock();
+        let entry = pending.entry(feed.name.clone()).or_insert_with(|| NewArticles {
+            feed: feed.name.clone(),
+            title: feed.title.clone(),
+            articles: Vec::new(),
+        });
+        entry.articles.extend(articles.into_iter().map(|article| NewArticle {
+            title: article.title.clone(),
+            link: article.link.clone(),
+        }));
+        if entry.articles.is_empty() {
+            pending.remove(&feed.name);
+            return;
+        }
+        drop(pending);
+        self.wake.notify_one();
+    }
+
+    /// Everything queued since the last batch
+    fn take_batch(&self) -> Vec<NewArticles> {
+        std::mem::take(&mut *self.pending.lock()).into_values().collect()
+    }
+
+    /// Send a batch whenever articles have been queued for the batch window
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            self.wake.notified().await;
+            tokio::time::sleep(self.batch_window).await;
+            let batch = self.take_batch();
+            if !batch.is_empty() {
+                self.send(&batch).await;
+            }
+        }
+    }
+
+    async fn send(&self, batch: &[NewArticles]) {
+        let (summary, body) = render(batch);
+        debug!("Notifying: {}", summary);
+        if self.desktop {
+            let status = tokio::process::Command::new("notify-send")
+                .args(["--app-name=rss-fuse", &summary, &body])
+                .status()
+                .await;
+            match status {
+                Ok(status) if status.success() => {}
+                Ok(status) => warn!("notify-send exited with {}", status),
+                Err(e) => warn!("Desktop notification failed: {}", e),
+            }
+        }
+
+        let payload = WebhookPayload { text: format!("{}\n{}", summary, body), feeds: batch };
+        for webhook in &self.webhooks {
+            let result = self.http.post(webhook).json(&payload).send().await
+                .and_then(|response| response.error_for_status());
+            if let Err(e) = result {
+                warn!("Webhook {} failed: {}", webhook, e);
+            }
+        }
+    }
+}
+
+/// Summary line and body of a notification, e.g. "3 new articles in
+/// hacker-news and rust-blog" followed by up to `MAX_LISTED` titles
+fn render(batch: &[NewArticles]) -> (String, String) {
+    let total: usize = batch.iter().map(|feed| feed.articles.len()).sum();
+    let names: Vec<&str> = batch.iter().map(|feed| feed.feed.as_str()).collect();
+    let feeds = match names.as_slice() {
+        [] => String::new(),
+        [one] => one.to_string(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    };
+    let summary = format!("{} new article{} in {}", total, if total == 1 { "" } else { "s" }, feeds);
+
+    let mut lines: Vec<String> = batch.iter()
+        .flat_map(|feed| feed.articles.iter().map(move |article| {
+            match batch.len() {
+                1 => format!("• {}", article.title),
+                _ => format!("• {}: {}", feed.feed, article.title),
+            }
+        }))
+        .take(MAX_LISTED)
+        .collect();
+    if total > MAX_LISTED {
+        lines.push(format!("… and {} more", total - MAX_LISTED));
+    }
+    (summary, lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::FeedStatus;
+
+    fn feed(name: &str, titles: &[&str]) -> Feed {
+        Feed {
+            name: name.to_string(),
+            url: format!("https://{}.example/rss", name),
+            title: Some(name.to_uppercase()),
+            description: None,
+            last_updated: None,
+            articles: titles.iter().map(|title| Article {
+                id: title.to_string(),
+                title: title.to_string(),
+                link: format!("https://{}.example/{}", name, title),
+                description: None,
+                content: None,
+                author: None,
+                published: None,
+                updated: None,
+                tags: Vec::new(),
+                read: false,
+                starred: false,
+                folder: None,
+                cached_at: None,
+                guid: None,
+                enclosures: Vec::new(),
+                content_ref: None,
+                description_ref: None,
+            }).collect(),
+            status: FeedStatus::Active,
+        }
+    }
+
+    #[test]
+    fn test_batches_new_articles() {
+        let notifier = Notifier::new(&NotificationsConfig::default(), reqwest::Client::new());
+        let news = feed("news", &["a", "b", "c", "d"]);
+        notifier.articles_added(&news, &news.articles[..2]);
+        notifier.articles_added(&news, &news.articles[2..]);
+        let blog = feed("blog", &["post"]);
+        notifier.articles_added(&blog, &blog.articles);
+        notifier.set_enabled("quiet", false);
+        let quiet = feed("quiet", &["ignored"]);
+        notifier.articles_added(&quiet, &quiet.articles);
+
+        let batch = notifier.take_batch();
+        assert_eq!(batch.iter().map(|feed| feed.feed.as_str()).collect::<Vec<_>>(), ["blog", "news"]);
+        assert_eq!(batch[1].articles.len(), 4);
+        assert!(notifier.take_batch().is_empty());
+
+        let (summary, body) = render(&batch);
+        assert_eq!(summary, "5 new articles in blog and news");
+        assert_eq!(body, "• blog: post\n• news: a\n• news: b\n• news: c\n• news: d");
+        let (summary, body) = render(&batch[..1]);
+        assert_eq!((summary.as_str(), body.as_str()), ("1 new article in blog", "• post"));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_receives_batch() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = NotificationsConfig {
+            webhooks: vec![format!("{}/hook", server.uri())],
+            ..NotificationsConfig::default()
+        };
+        let notifier = Notifier::new(&config, reqwest::Client::new());
+        let news = feed("news", &["a"]);
+        notifier.articles_added(&news, &news.articles);
+        notifier.send(&notifier.take_batch()).await;
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["text"], "1 new article in news\n• a");
+        assert_eq!(body["feeds"][0]["title"], "NEWS");
+        assert_eq!(body["feeds"][0]["articles"][0]["link"], "https://news.example/a");
+    }
+}
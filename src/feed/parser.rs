@@ -1,23 +1,197 @@
-use crate::feed::{ParsedFeed, ParsedArticle};
+use crate::feed::{Enclosure, ParsedFeed, ParsedArticle};
 use crate::error::{Error, Result};
 use chrono::{DateTime, Utc};
 use feed_rs::parser as feed_parser;
+use serde::Deserialize;
 use std::io::BufRead;
 
-pub struct FeedParser;
+pub struct FeedParser {
+    limits: ParseLimits,
+}
+
+/// Caps on what one feed may hold, so a runaway feed is rejected instead
+/// of filling memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Most items a feed may contain
+    pub max_items: usize,
+    /// Largest content plus summary of a single item, in bytes
+    pub max_item_size: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_items: 10_000,
+            max_item_size: 1024 * 1024,
+        }
+    }
+}
+
+/// JSON Feed document (https://jsonfeed.org/version/1.1), accepting 1.0's
+/// single `author` too
+#[derive(Debug, Deserialize)]
+struct JsonFeed {
+    version: String,
+    title: Option<String>,
+    home_page_url: Option<String>,
+    description: Option<String>,
+    icon: Option<String>,
+    favicon: Option<String>,
+    #[serde(default)]
+    items: Vec<JsonFeedItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeedItem {
+    id: serde_json::Value,
+    url: Option<String>,
+    external_url: Option<String>,
+    title: Option<String>,
+    content_html: Option<String>,
+    content_text: Option<String>,
+    summary: Option<String>,
+    date_published: Option<DateTime<Utc>>,
+    date_modified: Option<DateTime<Utc>>,
+    #[serde(default)]
+    authors: Vec<JsonFeedAuthor>,
+    author: Option<JsonFeedAuthor>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    attachments: Vec<JsonFeedAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeedAttachment {
+    url: String,
+    mime_type: Option<String>,
+    size_in_bytes: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeedAuthor {
+    name: Option<String>,
+}
 
 impl FeedParser {
     pub fn new() -> Self {
-        Self
+        Self { limits: ParseLimits::default() }
+    }
+
+    /// Reject feeds beyond `limits` instead of the defaults
+    pub fn with_limits(mut self, limits: ParseLimits) -> Self {
+        self.limits = limits;
+        self
     }
 
+    /// Parse an RSS, Atom or JSON Feed document. JSON Feed is recognised by
+    /// a leading `{`.
     pub fn parse_feed<R: BufRead>(&self, reader: R) -> Result<ParsedFeed> {
+        self.parse_feed_as(reader, None)
+    }
+
+    /// Parse a feed served with the given `Content-Type`. JSON types select
+    /// the JSON Feed parser; anything else falls back to sniffing.
+    pub fn parse_feed_as<R: BufRead>(&self, mut reader: R, content_type: Option<&str>) -> Result<ParsedFeed> {
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)
+            .map_err(|e| Error::FeedParse(format!("Failed to read feed: {}", e)))?;
+
+        let is_json_type = content_type
+            .and_then(|ct| ct.split(';').next())
+            .is_some_and(|mime| matches!(mime.trim(), "application/feed+json" | "application/json"));
+        let first_byte = body.strip_prefix("\u{feff}".as_bytes()).unwrap_or(&body)
+            .iter()
+            .copied()
+            .find(|b| !b.is_ascii_whitespace());
+
+        // Servers mislabel XML feeds as JSON often enough to check the body too
+        let feed = if first_byte == Some(b'{') || (is_json_type && first_byte != Some(b'<')) {
+            self.parse_json_feed(&body)?
+        } else {
+            self.parse_xml_feed(&body[..])?
+        };
+        self.check_limits(&feed)?;
+        Ok(feed)
+    }
+
+    /// Fail if `feed` has more items than allowed or an item too large
+    fn check_limits(&self, feed: &ParsedFeed) -> Result<()> {
+        if feed.articles.len() > self.limits.max_items {
+            return Err(Error::FeedParse(format!(
+                "Feed has {} items, more than the limit of {}", feed.articles.len(), self.limits.max_items
+            )));
+        }
+        let size = |text: &Option<String>| text.as_ref().map_or(0, String::len);
+        if let Some(article) = feed.articles.iter()
+            .find(|a| size(&a.content) + size(&a.description) > self.limits.max_item_size)
+        {
+            return Err(Error::FeedParse(format!(
+                "Item '{}' is {} bytes, more than the limit of {}",
+                article.title, size(&article.content) + size(&article.description), self.limits.max_item_size
+            )));
+        }
+        Ok(())
+    }
+
+    fn parse_json_feed(&self, body: &[u8]) -> Result<ParsedFeed> {
+        let feed: JsonFeed = serde_json::from_slice(body)
+            .map_err(|e| Error::FeedParse(format!("Failed to parse JSON feed: {}", e)))?;
+        if !feed.version.starts_with("https://jsonfeed.org/version/") {
+            return Err(Error::FeedParse(format!("Failed to parse JSON feed: unknown version {}", feed.version)));
+        }
+
+        let articles: Vec<ParsedArticle> = feed
+            .items
+            .into_iter()
+            .map(|item| {
+                let guid = match item.id {
+                    serde_json::Value::String(id) => id,
+                    other => other.to_string(),
+                };
+                let content = item.content_html.or_else(|| {
+                    item.content_text.map(|text| format!("<pre>{}</pre>", html_escape::encode_text(&text)))
+                });
+                let author = item.authors.into_iter().chain(item.author)
+                    .find_map(|author| author.name);
+
+                ParsedArticle {
+                    title: item.title.unwrap_or_else(|| "Untitled".to_string()),
+                    link: item.url.or(item.external_url).unwrap_or_default(),
+                    description: item.summary,
+                    content,
+                    author,
+                    published: item.date_published.or(item.date_modified),
+                    guid: Some(guid),
+                    categories: item.tags,
+                    enclosures: item.attachments.into_iter()
+                        .map(|a| Enclosure { url: a.url, mime_type: a.mime_type, length: a.size_in_bytes })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Ok(ParsedFeed {
+            title: feed.title.unwrap_or_else(|| "Untitled Feed".to_string()),
+            description: feed.description,
+            link: feed.home_page_url,
+            image: feed.icon.or(feed.favicon),
+            last_build_date: articles.iter().filter_map(|a| a.published).max(),
+            articles,
+        })
+    }
+
+    fn parse_xml_feed<R: BufRead>(&self, reader: R) -> Result<ParsedFeed> {
         let feed = feed_parser::parse(reader)
             .map_err(|e| Error::FeedParse(format!("Failed to parse feed: {}", e)))?;
 
         let title = feed.title.map(|t| t.content).unwrap_or_else(|| "Untitled Feed".to_string());
         let description = feed.description.map(|d| d.content);
         let link = feed.links.first().map(|l| l.href.clone());
+        // Atom's square `<icon>` suits file managers better than a logo or
+        // RSS `<image>`, which feed-rs both report as the logo
+        let image = feed.icon.or(feed.logo).map(|image| image.uri);
         let last_build_date = feed.updated.or(feed.published);
 
         let articles = feed
@@ -32,6 +206,7 @@ impl FeedParser {
                 let published = entry.published.or(entry.updated);
                 let guid = entry.id;
                 let categories = entry.categories.into_iter().map(|c| c.term).collect();
+                let enclosures = Self::entry_enclosures(&entry.links, &entry.media);
 
                 ParsedArticle {
                     title,
@@ -42,6 +217,7 @@ impl FeedParser {
                     published,
                     guid: Some(guid),
                     categories,
+                    enclosures,
                 }
             })
             .collect();
@@ -50,11 +226,39 @@ impl FeedParser {
             title,
             description,
             link,
+            image,
             last_build_date,
             articles,
         })
     }
 
+    /// RSS `<enclosure>`s and Media RSS content (which feed-rs merges into
+    /// `media`) plus Atom `rel="enclosure"` links, without duplicates
+    fn entry_enclosures(links: &[feed_rs::model::Link], media: &[feed_rs::model::MediaObject]) -> Vec<Enclosure> {
+        let from_media = media.iter()
+            .flat_map(|object| &object.content)
+            .filter_map(|content| Some(Enclosure {
+                url: content.url.as_ref()?.to_string(),
+                mime_type: content.content_type.as_ref().map(|t| t.to_string()),
+                length: content.size,
+            }));
+        let from_links = links.iter()
+            .filter(|link| link.rel.as_deref() == Some("enclosure"))
+            .map(|link| Enclosure {
+                url: link.href.clone(),
+                mime_type: link.media_type.clone(),
+                length: link.length,
+            });
+
+        let mut enclosures: Vec<Enclosure> = Vec::new();
+        for enclosure in from_media.chain(from_links) {
+            if !enclosures.iter().any(|e| e.url == enclosure.url) {
+                enclosures.push(enclosure);
+            }
+        }
+        enclosures
+    }
+
     pub fn validate_feed_url(&self, url: &str) -> Result<()> {
         let parsed_url = url::Url::parse(url)
             .map_err(|e| Error::InvalidUrl(format!("Invalid URL: {}", e)))?;
@@ -173,6 +377,93 @@ mod tests {
         assert_eq!(article.categories, vec!["atom", "test"]);
     }
 
+    const JSON_FEED_SAMPLE: &str = r#"{
+    "version": "https://jsonfeed.org/version/1.1",
+    "title": "Test JSON Feed",
+    "home_page_url": "https://example.com/",
+    "description": "A test JSON feed",
+    "items": [
+        {
+            "id": "https://example.com/json1",
+            "url": "https://example.com/json1",
+            "title": "JSON Article One",
+            "content_html": "<p>Hello from JSON</p>",
+            "summary": "First summary",
+            "date_published": "2024-03-15T09:00:00Z",
+            "authors": [{ "name": "Jason Author" }],
+            "tags": ["json", "test"]
+        },
+        {
+            "id": 2,
+            "external_url": "https://other.example.com/linked",
+            "content_text": "Plain <text>",
+            "date_modified": "2024-03-16T09:00:00+02:00",
+            "author": { "name": "Old Style" }
+        }
+    ]
+}"#;
+
+    #[test]
+    fn test_parse_json_feed() {
+        let parser = FeedParser::new();
+        let result = parser.parse_feed(Cursor::new(JSON_FEED_SAMPLE.as_bytes())).unwrap();
+
+        assert_eq!(result.title, "Test JSON Feed");
+        assert_eq!(result.description.as_deref(), Some("A test JSON feed"));
+        assert_eq!(result.link.as_deref(), Some("https://example.com/"));
+        assert_eq!(result.articles.len(), 2);
+        assert_eq!(result.last_build_date.unwrap().to_rfc3339(), "2024-03-16T07:00:00+00:00");
+
+        let first = &result.articles[0];
+        assert_eq!(first.title, "JSON Article One");
+        assert_eq!(first.link, "https://example.com/json1");
+        assert_eq!(first.content.as_deref(), Some("<p>Hello from JSON</p>"));
+        assert_eq!(first.description.as_deref(), Some("First summary"));
+        assert_eq!(first.author.as_deref(), Some("Jason Author"));
+        assert_eq!(first.categories, vec!["json", "test"]);
+        assert_eq!(first.guid.as_deref(), Some("https://example.com/json1"));
+
+        let second = &result.articles[1];
+        assert_eq!(second.title, "Untitled");
+        assert_eq!(second.link, "https://other.example.com/linked");
+        assert_eq!(second.content.as_deref(), Some("<pre>Plain &lt;text&gt;</pre>"));
+        assert_eq!(second.author.as_deref(), Some("Old Style"));
+        assert_eq!(second.guid.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_parse_json_feed_by_content_type() {
+        let parser = FeedParser::new();
+        let result = parser.parse_feed_as(Cursor::new(JSON_FEED_SAMPLE.as_bytes()), Some("application/feed+json; charset=utf-8"));
+        assert_eq!(result.unwrap().articles.len(), 2);
+
+        // Mislabelled XML is still parsed as XML
+        let mislabelled = parser.parse_feed_as(Cursor::new(RSS_SAMPLE.as_bytes()), Some("application/json"));
+        assert_eq!(mislabelled.unwrap().title, "Test RSS Feed");
+
+        let wrong_version = parser.parse_feed(Cursor::new(r#"{"version": "1", "items": []}"#.as_bytes()));
+        assert!(matches!(wrong_version, Err(Error::FeedParse(_))));
+    }
+
+    #[test]
+    fn test_feed_images() {
+        let parser = FeedParser::new();
+        let rss = r#"<rss version="2.0"><channel><title>R</title>
+            <image><url>https://example.com/logo.png</url><title>R</title><link>https://example.com/</link></image>
+            </channel></rss>"#;
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>A</title><id>urn:a</id>
+            <updated>2024-01-01T00:00:00Z</updated>
+            <logo>https://example.com/banner.png</logo><icon>/favicon.png</icon></feed>"#;
+        let json = r#"{"version": "https://jsonfeed.org/version/1.1", "items": [],
+            "favicon": "https://example.com/small.png", "icon": "https://example.com/big.png"}"#;
+
+        let image = |doc: &str| parser.parse_feed(Cursor::new(doc.as_bytes())).unwrap().image;
+        assert_eq!(image(rss).as_deref(), Some("https://example.com/logo.png"));
+        assert_eq!(image(atom).as_deref(), Some("/favicon.png"));
+        assert_eq!(image(json).as_deref(), Some("https://example.com/big.png"));
+        assert_eq!(image(RSS_SAMPLE), None);
+    }
+
     #[test]
     fn test_parse_malformed_xml() {
         let parser = FeedParser::new();
@@ -289,4 +580,135 @@ mod tests {
         assert_eq!(result.articles[0].title, "Article with <HTML> in CDATA");
         assert!(result.articles[0].description.as_ref().unwrap().contains("<strong>HTML</strong>"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_enclosures() {
+        let parser = FeedParser::new();
+        let podcast = r#"<?xml version="1.0"?>
+<rss version="2.0">
+    <channel>
+        <title>Podcast</title>
+        <item>
+            <title>Episode 1</title>
+            <link>https://example.com/1</link>
+            <enclosure url="https://example.com/episode1.mp3" length="5242880" type="audio/mpeg"/>
+        </item>
+        <item>
+            <title>Notes</title>
+            <link>https://example.com/notes</link>
+        </item>
+    </channel>
+</rss>"#;
+
+        let result = parser.parse_feed(Cursor::new(podcast.as_bytes())).unwrap();
+        assert_eq!(result.articles[0].enclosures, vec![Enclosure {
+            url: "https://example.com/episode1.mp3".to_string(),
+            mime_type: Some("audio/mpeg".to_string()),
+            length: Some(5242880),
+        }]);
+        assert!(result.articles[1].enclosures.is_empty());
+
+        let json = r#"{"version": "https://jsonfeed.org/version/1.1", "items": [
+            {"id": "1", "title": "Episode", "attachments": [
+                {"url": "https://example.com/stream?id=1", "mime_type": "audio/x-m4a", "size_in_bytes": 42}
+            ]}
+        ]}"#;
+        let result = parser.parse_feed(Cursor::new(json.as_bytes())).unwrap();
+        let enclosure = &result.articles[0].enclosures[0];
+        assert_eq!(enclosure.length, Some(42));
+        assert_eq!(enclosure.extension(), "m4a");
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let limits = ParseLimits { max_items: 1, max_item_size: 1024 };
+        let parser = FeedParser::new().with_limits(limits);
+        let result = parser.parse_feed(Cursor::new(RSS_SAMPLE.as_bytes()));
+        assert!(matches!(result, Err(Error::FeedParse(message)) if message.contains("2 items")));
+
+        let huge = format!(
+            r#"{{"version": "https://jsonfeed.org/version/1.1", "items": [{{"id": "1", "content_text": "{}"}}]}}"#,
+            "x".repeat(2048)
+        );
+        let result = parser.parse_feed(Cursor::new(huge.as_bytes()));
+        assert!(matches!(result, Err(Error::FeedParse(message)) if message.contains("limit of 1024")));
+
+        let roomy = FeedParser::new().with_limits(ParseLimits { max_items: 2, ..limits });
+        assert_eq!(roomy.parse_feed(Cursor::new(RSS_SAMPLE.as_bytes())).unwrap().articles.len(), 2);
+    }
+
+    // Feeds come from arbitrary servers: whatever the bytes, parsing must
+    // return an error rather than panic
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn parse(bytes: &[u8]) -> Result<ParsedFeed> {
+            FeedParser::new().parse_feed(Cursor::new(bytes))
+        }
+
+        /// An RSS feed with one item whose description is `body`
+        fn rss_with(body: &str) -> String {
+            format!(
+                "<?xml version=\"1.0\"?><rss version=\"2.0\"><channel><title>T</title>\
+                 <item><title>A</title><description>{}</description></item></channel></rss>",
+                body
+            )
+        }
+
+        proptest! {
+            #[test]
+            fn arbitrary_bytes(bytes in proptest::collection::vec(any::<u8>(), 0..2048)) {
+                let _ = parse(&bytes);
+            }
+
+            #[test]
+            fn truncated_feeds(sample in prop::sample::select(vec![RSS_SAMPLE, ATOM_SAMPLE, JSON_FEED_SAMPLE]), cut in 0usize..4096) {
+                let bytes = sample.as_bytes();
+                let _ = parse(&bytes[..cut.min(bytes.len())]);
+            }
+
+            #[test]
+            fn invalid_utf8(position in 0usize..1024, garbage in proptest::collection::vec(0x80u8..=0xff, 1..16)) {
+                let mut bytes = RSS_SAMPLE.as_bytes().to_vec();
+                let position = position.min(bytes.len());
+                bytes.splice(position..position, garbage);
+                let _ = parse(&bytes);
+            }
+
+            #[test]
+            fn nested_cdata(depth in 1usize..400, text in "[a-z<>\\]&]{0,16}") {
+                let open = "<![CDATA[<div>".repeat(depth);
+                let close = "</div>]]>".repeat(depth);
+                let _ = parse(rss_with(&format!("{}{}{}", open, text, close)).as_bytes());
+            }
+
+            #[test]
+            fn nested_elements(depth in 1usize..2000, closed in any::<bool>()) {
+                let open = "<x>".repeat(depth);
+                let close = if closed { "</x>".repeat(depth) } else { String::new() };
+                let _ = parse(rss_with(&format!("{}{}", open, close)).as_bytes());
+            }
+
+            #[test]
+            fn enormous_attributes(length in 0usize..200_000, quote in prop::sample::select(vec!['"', '\''])) {
+                let value = "a".repeat(length);
+                let feed = format!(
+                    "<?xml version=\"1.0\"?><rss version=\"2.0\"><channel><title>T</title>\
+                     <item><title>A</title><enclosure url={q}{v}{q} type={q}{v}{q}/></item></channel></rss>",
+                    q = quote, v = value
+                );
+                let _ = parse(feed.as_bytes());
+            }
+
+            #[test]
+            fn arbitrary_json_items(text in "\\PC{0,64}", number in any::<i64>()) {
+                let json = serde_json::json!({
+                    "version": "https://jsonfeed.org/version/1.1",
+                    "items": [{"id": number, "title": text, "content_text": text, "date_published": text}]
+                });
+                let _ = parse(json.to_string().as_bytes());
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Politeness limits for outgoing requests: a token bucket per host, so feeds
+//! sharing a server do not hit it all at once, and a ceiling on requests in
+//! flight across every fetcher the limiter is attached to.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{Duration, Instant};
+use tracing::debug;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimits {
+    /// Sustained requests per minute to one host; unlimited if unset
+    pub requests_per_minute: Option<u32>,
+    /// Requests one host may receive back to back before the rate applies
+    pub burst: u32,
+    /// Requests in flight at once across all hosts; unlimited if unset
+    pub max_concurrent: Option<usize>,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: None,
+            burst: 1,
+            max_concurrent: None,
+        }
+    }
+}
+
+/// Shared by clones, so one limiter can cover feed refreshes, page fetches
+/// and downloads alike
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>, // host -> its tokens
+    slots: Option<Arc<Semaphore>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64, // negative while requests are queued for the host
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        let slots = limits.max_concurrent.map(|max| Arc::new(Semaphore::new(max.max(1))));
+        Self {
+            limits,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            slots,
+        }
+    }
+
+    pub fn limits(&self) -> &RateLimits {
+        &self.limits
+    }
+
+    /// Wait for one of the concurrent request slots, held until the permit
+    /// is dropped. `None` when there is no ceiling.
+    pub async fn slot(&self) -> Option<OwnedSemaphorePermit> {
+        let slots = Arc::clone(self.slots.as_ref()?);
+        slots.acquire_owned().await.ok()
+    }
+
+    /// Wait until another request may be sent to `url`'s host. Each caller
+    /// takes a token right away and sleeps off any deficit, so waiting
+    /// requests go out in turn at the configured rate.
+    pub async fn wait_turn(&self, url: &str) {
+        let Some(rpm) = self.limits.requests_per_minute.filter(|&rpm| rpm > 0) else {
+            return;
+        };
+        let per_second = f64::from(rpm) / 60.0;
+        let capacity = f64::from(self.limits.burst.max(1));
+        let host = host_of(url);
+
+        let wait = {
+            let now = Instant::now();
+            let mut buckets = self.buckets.lock();
+            let bucket = buckets.entry(host.clone()).or_insert(Bucket { tokens: capacity, updated: now });
+            let refill = now.duration_since(bucket.updated).as_secs_f64() * per_second;
+            bucket.tokens = (bucket.tokens + refill).min(capacity) - 1.0;
+            bucket.updated = now;
+            (bucket.tokens < 0.0).then(|| Duration::from_secs_f64(-bucket.tokens / per_second))
+        };
+
+        if let Some(wait) = wait {
+            debug!("Waiting {:?} before the next request to {}", wait, host);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// The bucket a URL's requests count against
+fn host_of(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_else(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_per_host_rate() {
+        let limiter = RateLimiter::new(RateLimits {
+            requests_per_minute: Some(600), // one every 100ms
+            burst: 2,
+            ..RateLimits::default()
+        });
+
+        let start = Instant::now();
+        limiter.wait_turn("https://reddit.example/r/rust/.rss").await;
+        limiter.wait_turn("https://REDDIT.example/r/linux/.rss").await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        // Other hosts have their own bucket
+        limiter.wait_turn("https://other.example/feed").await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        limiter.wait_turn("https://reddit.example/r/programming/.rss").await;
+        limiter.wait_turn("https://reddit.example/r/golang/.rss").await;
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_ceiling() {
+        let limiter = RateLimiter::new(RateLimits { max_concurrent: Some(2), ..RateLimits::default() });
+        let first = limiter.slot().await;
+        let _second = limiter.slot().await;
+        assert!(first.is_some());
+
+        let third = tokio::time::timeout(Duration::from_millis(50), limiter.slot()).await;
+        assert!(third.is_err());
+
+        drop(first);
+        let third = tokio::time::timeout(Duration::from_millis(50), limiter.slot()).await;
+        assert!(third.unwrap().is_some());
+
+        assert!(RateLimiter::new(RateLimits::default()).slot().await.is_none());
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::clock::SharedClock;
+use crate::error::Error;
+use crate::feed::Feed;
+use crate::storage::{FeedRepository, RefreshOutcome, Repository};
+
+/// Fraction of the interval by which periodic refreshes are spread out by default
+const DEFAULT_JITTER: f64 = 0.1;
+
+/// Draws the random share a delay is spread by, in `-1.0..=1.0`
+pub type JitterSource = Arc<dyn Fn() -> f64 + Send + Sync>;
+
+/// Jitter taken from the sub-second part of `clock`'s time, so a mock clock
+/// makes it predictable
+pub fn clock_jitter(clock: SharedClock) -> JitterSource {
+    Arc::new(move || {
+        let nanos = clock.now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        f64::from(nanos % 2001) / 1000.0 - 1.0
+    })
+}
+
+/// Receives the results of scheduled refreshes, e.g. to update a mount
+pub trait RefreshHook: Send + Sync {
+    /// New content for a feed, either loaded from the cache or freshly fetched
+    fn feed_updated(&self, feed: Feed, from_cache: bool);
+
+    /// A refresh failed; whatever content the feed had is still stored
+    fn feed_failed(&self, _name: &str, _error: &Error) {}
+}
+
+/// Periodic background refreshes, one schedule per feed. Failing feeds back
+/// off according to the repository's health tracking, and each delay is
+/// jittered so feeds with the same interval do not fetch in lockstep.
+/// Dropping the scheduler stops every schedule.
+pub struct RefreshScheduler {
+    repo: Arc<Repository>,
+    hook: Arc<dyn RefreshHook>,
+    jitter: f64,
+    jitter_source: JitterSource,
+    permits: Arc<Semaphore>,
+    tasks: HashMap<String, JoinHandle<()>>,
+}
+
+impl RefreshScheduler {
+    pub fn new(repo: Arc<Repository>, hook: Arc<dyn RefreshHook>) -> Self {
+        let jitter_source = clock_jitter(repo.clock());
+        Self {
+            repo,
+            hook,
+            jitter: DEFAULT_JITTER,
+            jitter_source,
+            permits: Arc::new(Semaphore::new(Semaphore::MAX_PERMITS)),
+            tasks: HashMap::new(),
+        }
+    }
+
+    /// Spread each delay randomly by up to `fraction` of it (0 disables)
+    pub fn with_jitter(mut self, fraction: f64) -> Self {
+        self.jitter = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Draw each delay's spread from `source` instead of the repository clock
+    pub fn with_jitter_source(mut self, source: JitterSource) -> Self {
+        self.jitter_source = source;
+        self
+    }
+
+    /// Fetch at most `limit` feeds at the same time
+    pub fn with_concurrency(mut self, limit: usize) -> Self {
+        self.permits = Arc::new(Semaphore::new(limit.max(1)));
+        self
+    }
+
+    /// Hand the cached content of `feeds` (name, URL) to the hook, so they
+    /// show up before the first fetch completes
+    pub async fn load_cached(&self, feeds: &[(String, String)]) {
+        for (name, url) in feeds {
+            match self.repo.load_feed_cache_first(name, url).await {
+                Ok(Some(feed)) => {
+                    debug!("Found cached feed: {} ({} articles)", name, feed.articles.len());
+                    self.hook.feed_updated(feed, true);
+                },
+                Ok(None) => debug!("No cached content for feed: {}", name),
+                Err(e) => warn!("Failed to load cached feed {}: {}", name, e),
+            }
+        }
+    }
+
+    /// Start (or restart) refreshing `name` from `url` every `interval`.
+    /// The first refresh runs after `initial_delay`.
+    pub fn start(&mut self, name: &str, url: &str, interval: Duration, initial_delay: Duration) {
+        let repo = Arc::clone(&self.repo);
+        let hook = Arc::clone(&self.hook);
+        let permits = Arc::clone(&self.permits);
+        let jitter = self.jitter;
+        let jitter_source = Arc::clone(&self.jitter_source);
+        let feed_name = name.to_string();
+        let feed_url = url.to_string();
+
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(initial_delay).await;
+            loop {
+                {
+                    let _permit = permits.acquire().await;
+                    refresh_once(&repo, hook.as_ref(), &feed_name, &feed_url).await;
+                }
+
+                // Failing feeds back off until they recover
+                let delay = repo.refresh_delay(&feed_name, interval);
+                tokio::time::sleep(jittered(delay, jitter, jitter_source())).await;
+            }
+        });
+
+        if let Some(previous) = self.tasks.insert(name.to_string(), task) {
+            previous.abort();
+        }
+    }
+
+    /// Stop refreshing `name`. Returns whether it was scheduled.
+    pub fn stop(&mut self, name: &str) -> bool {
+        match self.tasks.remove(name) {
+            Some(task) => {
+                task.abort();
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Stop every schedule
+    pub fn stop_all(&mut self) {
+        for (_, task) in self.tasks.drain() {
+            task.abort();
+        }
+    }
+
+    pub fn is_scheduled(&self, name: &str) -> bool {
+        self.tasks.contains_key(name)
+    }
+
+    /// Names of the scheduled feeds, sorted
+    pub fn scheduled(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.tasks.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+impl Drop for RefreshScheduler {
+    fn drop(&mut self) {
+        self.stop_all();
+    }
+}
+
+/// Refresh one feed and report the outcome to `hook`
+pub async fn refresh_once(repo: &Repository, hook: &dyn RefreshHook, name: &str, url: &str) {
+    match repo.refresh_feed_if_modified(name, url).await {
+        Ok(RefreshOutcome::Updated(feed, result)) => {
+            debug!("Refreshed {} ({} articles, {} new, {} updated, {} removed)", name, feed.articles.len(),
+                   result.articles_added, result.articles_updated, result.articles_removed);
+            hook.feed_updated(feed, false);
+        },
+        Ok(RefreshOutcome::NotModified) => {
+            debug!("{} not modified, keeping current content", name);
+        },
+        Err(e) => {
+            warn!("Refresh of {} failed: {}", name, e);
+            hook.feed_failed(name, &e);
+        }
+    }
+}
+
+/// `delay` lengthened or shortened by `sample` (-1.0 ..= 1.0) times `fraction` of it
+fn jittered(delay: Duration, fraction: f64, sample: f64) -> Duration {
+    if fraction <= 0.0 {
+        return delay;
+    }
+    delay.mul_f64(1.0 + sample.clamp(-1.0, 1.0) * fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use tokio::sync::mpsc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// What the scheduler reported: (feed, from_cache) for an update, or
+    /// (feed, None) for a failure
+    type Event = (String, Option<bool>);
+
+    /// Forwards every report to the test. Once the scheduler and its tasks
+    /// are gone the sender is dropped and the channel closes.
+    struct Recorder(mpsc::UnboundedSender<Event>);
+
+    impl RefreshHook for Recorder {
+        fn feed_updated(&self, feed: Feed, from_cache: bool) {
+            let _ = self.0.send((feed.name, Some(from_cache)));
+        }
+
+        fn feed_failed(&self, name: &str, _error: &Error) {
+            let _ = self.0.send((name.to_string(), None));
+        }
+    }
+
+    fn recorder() -> (Arc<Recorder>, mpsc::UnboundedReceiver<Event>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Arc::new(Recorder(sender)), receiver)
+    }
+
+    /// Next report; the timeout only guards against a hung test
+    async fn next(events: &mut mpsc::UnboundedReceiver<Event>) -> Option<Event> {
+        tokio::time::timeout(Duration::from_secs(10), events.recv()).await
+            .expect("no report from the scheduler")
+    }
+
+    async fn feed_server() -> MockServer {
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <item><title>A</title><link>https://example.com/a</link></item>
+            </channel></rss>"#;
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/broken.xml"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[test]
+    fn test_jittered() {
+        let delay = Duration::from_secs(100);
+        assert_eq!(jittered(delay, 0.0, 1.0), delay);
+        assert_eq!(jittered(delay, 0.1, -1.0), Duration::from_secs(90));
+        assert_eq!(jittered(delay, 0.1, 0.0), delay);
+        assert_eq!(jittered(delay, 0.1, 1.0), Duration::from_secs(110));
+        assert_eq!(jittered(delay, 0.1, 5.0), Duration::from_secs(110));
+    }
+
+    #[test]
+    fn test_clock_jitter_follows_the_clock() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let clock = MockClock::new(start);
+        let source = clock_jitter(clock.shared());
+        assert_eq!(source(), -1.0);
+
+        clock.set(start + Duration::from_nanos(1000));
+        assert_eq!(source(), 0.0);
+        clock.set(start + Duration::from_nanos(2000));
+        assert_eq!(source(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_refreshes_until_stopped() {
+        let server = feed_server().await;
+        let url = format!("{}/feed.xml", server.uri());
+        let repo = Arc::new(crate::storage::RepositoryFactory::memory());
+        let (recorder, mut events) = recorder();
+        let mut scheduler = RefreshScheduler::new(Arc::clone(&repo), recorder)
+            .with_jitter_source(Arc::new(|| 0.0))
+            .with_concurrency(2);
+
+        scheduler.start("news", &url, Duration::from_millis(1), Duration::ZERO);
+        assert!(scheduler.is_scheduled("news"));
+        assert_eq!(next(&mut events).await, Some(("news".to_string(), Some(false))));
+        assert_eq!(next(&mut events).await, Some(("news".to_string(), Some(false))));
+
+        assert!(scheduler.stop("news"));
+        assert!(!scheduler.stop("news"));
+        assert!(scheduler.scheduled().is_empty());
+
+        // The first fetch stored the feed, so it can now come from the cache
+        scheduler.load_cached(&[("news".to_string(), url.clone())]).await;
+        drop(scheduler);
+
+        // Only the stopped task's last refreshes can still arrive before the
+        // cached load; after it the channel closes, so nothing refreshes on
+        let mut rest = Vec::new();
+        while let Some(event) = next(&mut events).await {
+            rest.push(event);
+        }
+        assert_eq!(rest.last(), Some(&("news".to_string(), Some(true))));
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_reports_failures() {
+        let server = feed_server().await;
+        let repo = Arc::new(crate::storage::RepositoryFactory::memory());
+        let (recorder, mut events) = recorder();
+        let mut scheduler = RefreshScheduler::new(repo, recorder);
+
+        scheduler.start("broken", &format!("{}/broken.xml", server.uri()), Duration::from_secs(3600), Duration::ZERO);
+        assert_eq!(next(&mut events).await, Some(("broken".to_string(), None)));
+
+        scheduler.start("other", &format!("{}/feed.xml", server.uri()), Duration::from_secs(3600), Duration::from_secs(3600));
+        drop(scheduler);
+        assert_eq!(next(&mut events).await, None);
+    }
+}
@@ -0,0 +1,98 @@
+//! Keyword scores for triage: each article scores the weights of the
+//! `[scoring]` keywords it mentions, so important articles can be marked
+//! and ranked.
+
+use std::collections::BTreeMap;
+
+use crate::config::TextHtmlPolicy;
+use crate::content::render_html_as_text;
+use crate::feed::Article;
+
+/// Scores articles against a set of weighted keywords
+#[derive(Debug, Clone, Default)]
+pub struct Scorer {
+    /// Keyword normalized like article text (see `normalize`), and its weight
+    keywords: Vec<(String, i64)>,
+}
+
+impl Scorer {
+    pub fn new(keywords: &BTreeMap<String, i64>) -> Self {
+        Self {
+            keywords: keywords.iter()
+                .map(|(keyword, weight)| (normalize(keyword), *weight))
+                .filter(|(keyword, _)| !keyword.trim().is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keywords.is_empty()
+    }
+
+    /// Sum of the weights of the keywords `article` mentions in its title,
+    /// tags or text, whole words only and ignoring case. A keyword in the
+    /// title counts twice.
+    pub fn score(&self, article: &Article) -> i64 {
+        if self.keywords.is_empty() {
+            return 0;
+        }
+        let title = normalize(&article.title);
+        let body = [article.description.as_deref(), article.content.as_deref()].into_iter()
+            .flatten()
+            .map(|html| render_html_as_text(html, TextHtmlPolicy::Strip))
+            .chain(article.tags.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let body = normalize(&body);
+        self.keywords.iter()
+            .map(|(keyword, weight)| {
+                if title.contains(keyword.as_str()) {
+                    weight * 2
+                } else if body.contains(keyword.as_str()) {
+                    *weight
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+}
+
+/// Lowercase words separated and surrounded by single spaces, so a keyword
+/// normalized the same way matches whole words with `contains`
+fn normalize(text: &str) -> String {
+    let words: Vec<String> = text.split(|c: char| !c.is_alphanumeric() && c != '+' && c != '#')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::test_article;
+
+    fn article(title: &str, description: &str, tags: &[&str]) -> Article {
+        let mut article = test_article("a", title, "test");
+        article.description = Some(description.to_string());
+        article.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        article
+    }
+
+    #[test]
+    fn test_score() {
+        let scorer = Scorer::new(&BTreeMap::from([
+            ("Rust".to_string(), 5),
+            ("memory safety".to_string(), 3),
+            ("crypto".to_string(), -4),
+            ("C++".to_string(), 1),
+        ]));
+
+        let post = article("Rust in the kernel", "<p>A step for <b>memory-safety</b>.</p>", &[]);
+        assert_eq!(scorer.score(&post), 5 * 2 + 3);
+        assert_eq!(scorer.score(&article("Trust issues", "Rustic cryptography", &[])), 0);
+        assert_eq!(scorer.score(&article("Weekly news", "Interop with C++", &["crypto"])), 1 - 4);
+        assert_eq!(Scorer::default().score(&post), 0);
+    }
+}
@@ -0,0 +1,122 @@
+//! Canonical article links: feeds that append tracking parameters or vary
+//! a link's case or trailing slash between fetches would otherwise hand out
+//! the same story under new IDs.
+
+/// Query parameters stripped unless configured otherwise; a trailing `*`
+/// matches any parameter starting with what precedes it
+pub const DEFAULT_TRACKING_PARAMS: &[&str] = &[
+    "utm_*", "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_hsenc", "_hsmi",
+];
+
+/// Rewrites article links to one canonical form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlNormalizer {
+    strip: Vec<String>,
+}
+
+impl Default for UrlNormalizer {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRACKING_PARAMS.iter().map(|param| param.to_string()).collect())
+    }
+}
+
+impl UrlNormalizer {
+    /// Strip the query parameters named in `strip` (see
+    /// [`DEFAULT_TRACKING_PARAMS`] for the syntax)
+    pub fn new(strip: Vec<String>) -> Self {
+        Self { strip }
+    }
+
+    fn strips(&self, name: &str) -> bool {
+        self.strip.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == pattern,
+        })
+    }
+
+    /// `link` with scheme and host in lowercase, the default port, the
+    /// stripped parameters and a trailing slash after the path removed.
+    /// Links that are not absolute URLs are only trimmed.
+    pub fn normalize(&self, link: &str) -> String {
+        let link = link.trim();
+        let Ok(mut url) = url::Url::parse(link) else {
+            return link.to_string();
+        };
+        if url.cannot_be_a_base() {
+            return link.to_string();
+        }
+
+        // Filter the raw query so kept parameters keep their encoding
+        let query: Vec<String> = url.query().unwrap_or("")
+            .split('&')
+            .filter(|param| {
+                let name = param.split_once('=').map_or(*param, |(name, _)| name);
+                !name.is_empty() && !self.strips(name)
+            })
+            .map(str::to_string)
+            .collect();
+        url.set_query((!query.is_empty()).then(|| query.join("&")).as_deref());
+
+        if url.path().len() > 1 && url.path().ends_with('/') {
+            let path = url.path().trim_end_matches('/').to_string();
+            url.set_path(if path.is_empty() { "/" } else { &path });
+        }
+        url.to_string()
+    }
+
+    /// What identifies the story behind `link` when matching articles across
+    /// feeds or machines: the normalized link without scheme, `www.`,
+    /// fragment or trailing slash. `None` for an empty link.
+    pub fn match_key(&self, link: &str) -> Option<String> {
+        let link = link.trim();
+        if link.is_empty() {
+            return None;
+        }
+        let normalized = match link.contains("://") {
+            true => self.normalize(link),
+            false => self.normalize(&format!("http://{}", link)),
+        };
+        let link = normalized.split_once('#').map_or(normalized.as_str(), |(link, _)| link);
+        let link = link.strip_prefix("https://").or_else(|| link.strip_prefix("http://")).unwrap_or(link);
+        let link = link.strip_prefix("www.").unwrap_or(link);
+        Some(link.trim_end_matches('/').to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let normalizer = UrlNormalizer::default();
+        assert_eq!(
+            normalizer.normalize(" HTTPS://Example.COM:443/Post/?utm_source=rss&id=7&fbclid=abc#comments "),
+            "https://example.com/Post?id=7#comments"
+        );
+        assert_eq!(normalizer.normalize("https://example.com/?utm_medium=feed"), "https://example.com/");
+        assert_eq!(normalizer.normalize("https://example.com/a%20b/?q=a%2Bb"), "https://example.com/a%20b?q=a%2Bb");
+        assert_eq!(normalizer.normalize("not a url"), "not a url");
+        assert_eq!(normalizer.normalize("mailto:someone@example.com"), "mailto:someone@example.com");
+    }
+
+    #[test]
+    fn test_match_key() {
+        let normalizer = UrlNormalizer::default();
+        let key = |link| normalizer.match_key(link);
+        assert_eq!(key("https://www.Example.com/post/?utm_source=hn&id=3#comments").as_deref(), Some("example.com/post?id=3"));
+        assert_eq!(key("http://example.com/post"), key("https://example.com/post/?fbclid=abc"));
+        assert_eq!(key("www.example.com/post/"), key("https://example.com/post"));
+        assert_ne!(key("https://example.com/post?id=3"), key("https://example.com/post?id=4"));
+        assert_eq!(key("  "), None);
+    }
+
+    #[test]
+    fn test_configured_strip_list() {
+        let normalizer = UrlNormalizer::new(vec!["ref".to_string(), "share_*".to_string()]);
+        assert_eq!(
+            normalizer.normalize("https://example.com/post?ref=hn&share_id=1&utm_source=x"),
+            "https://example.com/post?utm_source=x"
+        );
+    }
+}
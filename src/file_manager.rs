@@ -2,6 +2,7 @@ use std::path::Path;
 use std::process::Command;
 use std::time::Duration;
 use tracing::{info, warn, error, debug};
+use url::Url;
 use crate::config::FileManagerConfig;
 use crate::error::{Error, Result};
 
@@ -254,6 +255,56 @@ impl FileManagerLauncher {
     }
 }
 
+/// `link` as a URL that is safe to hand to a browser. Links come from feeds,
+/// so only absolute `http` and `https` URLs are accepted: `file:` and custom
+/// protocol handlers are refused, as is anything that reads as an option.
+pub fn browser_url(link: &str) -> Result<Url> {
+    if link.starts_with('-') {
+        return Err(Error::InvalidUrl(format!("Refusing to open '{}'", link)));
+    }
+    let url = Url::parse(link).map_err(|e| Error::InvalidUrl(format!("{}: {}", link, e)))?;
+    match url.scheme() {
+        "http" | "https" => Ok(url),
+        scheme => Err(Error::InvalidUrl(format!("Refusing to open a {}: link", scheme))),
+    }
+}
+
+/// Open `link` in the first browser of `$BROWSER` (a `:`-separated list)
+/// that starts, falling back to `xdg-open` (`open` on macOS). The browser's
+/// output is discarded so it cannot disturb a terminal UI. Links that
+/// `browser_url` refuses are not opened.
+pub fn open_url(link: &str) -> Result<()> {
+    open_browser_url(&browser_url(link)?)
+}
+
+/// Open a URL already checked by `browser_url`, as `open_url` does
+pub fn open_browser_url(url: &Url) -> Result<()> {
+    let browsers = std::env::var("BROWSER").unwrap_or_default();
+    launch_browser(url, browsers.split(':'))
+}
+
+fn launch_browser<'a>(url: &Url, browsers: impl IntoIterator<Item = &'a str>) -> Result<()> {
+    let fallback = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+    let mut last_error = None;
+    for browser in browsers.into_iter().filter(|b| !b.is_empty()).chain([fallback]) {
+        let spawned = Command::new(browser)
+            .arg(url.as_str())
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn();
+        match spawned {
+            Ok(mut child) => {
+                debug!("Opened {} with {}", url, browser);
+                std::thread::spawn(move || child.wait());
+                return Ok(());
+            }
+            Err(e) => last_error = Some(format!("{}: {}", browser, e)),
+        }
+    }
+    Err(Error::Config(format!("No browser could open {} ({})", url, last_error.unwrap_or_default())))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -292,6 +343,22 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_open_url_tries_each_browser() {
+        let url = browser_url("https://example.com").unwrap();
+        assert!(launch_browser(&url, ["nonexistent_browser_12345", "true"]).is_ok());
+    }
+
+    #[test]
+    fn test_browser_url_only_accepts_web_links() {
+        assert_eq!(browser_url("https://example.com/a?b=c").unwrap().as_str(), "https://example.com/a?b=c");
+        assert!(browser_url("http://example.com").is_ok());
+        for link in ["file:///etc/passwd", "javascript:alert(1)", "vscode://open?file=x",
+                     "--new-window", "-https://example.com", "/relative/path", ""] {
+            assert!(matches!(browser_url(link), Err(Error::InvalidUrl(_))), "{} should be refused", link);
+        }
+    }
+
     #[test]
     fn test_suggestions() {
         let suggestions = FileManagerLauncher::get_suggestions();
@@ -0,0 +1,132 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Rendered content at least this large is spooled to disk on open rather
+/// than kept in memory for the lifetime of the file handle
+pub const SPOOL_THRESHOLD: usize = 1024 * 1024;
+
+/// Bytes behind an open file, served a range at a time so a `read` never
+/// has to produce (or clone) the whole file
+pub trait ContentProvider: Send + Sync {
+    fn size(&self) -> u64;
+
+    /// Up to `size` bytes starting at `offset`; empty past the end
+    fn read_at(&self, offset: u64, size: u32) -> std::io::Result<Vec<u8>>;
+}
+
+/// Content held in memory, shared rather than copied between reads
+#[derive(Debug, Clone)]
+pub struct BytesContent(Arc<[u8]>);
+
+impl BytesContent {
+    pub fn new(data: impl Into<Arc<[u8]>>) -> Self {
+        Self(data.into())
+    }
+}
+
+impl ContentProvider for BytesContent {
+    fn size(&self) -> u64 {
+        self.0.len() as u64
+    }
+
+    fn read_at(&self, offset: u64, size: u32) -> std::io::Result<Vec<u8>> {
+        let start = (offset.min(self.size())) as usize;
+        let end = start.saturating_add(size as usize).min(self.0.len());
+        Ok(self.0[start..end].to_vec())
+    }
+}
+
+/// Content read from a file on disk with positioned reads
+#[derive(Debug)]
+pub struct FileContent {
+    file: File,
+    size: u64,
+}
+
+impl FileContent {
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let file = File::open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { file, size })
+    }
+
+    /// Write `data` to an anonymous file in `dir`. The file is unlinked
+    /// straight away, so it disappears when the provider is dropped.
+    pub fn spool<P: AsRef<Path>>(dir: P, data: &[u8]) -> std::io::Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let path = dir.as_ref().join(format!(
+            "rss-fuse-{}-{}.spool",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut file = OpenOptions::new().read(true).write(true).create_new(true).open(&path)?;
+        let written = file.write_all(data);
+        std::fs::remove_file(&path)?;
+        written?;
+        Ok(Self { file, size: data.len() as u64 })
+    }
+}
+
+impl ContentProvider for FileContent {
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn read_at(&self, offset: u64, size: u32) -> std::io::Result<Vec<u8>> {
+        let len = size.min(self.size.saturating_sub(offset).min(u32::MAX as u64) as u32) as usize;
+        let mut data = vec![0; len];
+        let mut filled = 0;
+        while filled < len {
+            match self.file.read_at(&mut data[filled..], offset + filled as u64)? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        data.truncate(filled);
+        Ok(data)
+    }
+}
+
+/// Keep `data` in memory, or spool it to `dir` if it is large
+pub fn provider_for(data: Arc<[u8]>, dir: &Path) -> Arc<dyn ContentProvider> {
+    if data.len() >= SPOOL_THRESHOLD {
+        match FileContent::spool(dir, &data) {
+            Ok(spooled) => return Arc::new(spooled),
+            Err(e) => tracing::warn!("Failed to spool {} bytes to {}: {}", data.len(), dir.display(), e),
+        }
+    }
+    Arc::new(BytesContent(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_bytes_content() {
+        let content = BytesContent::new(b"0123456789".to_vec());
+        assert_eq!(content.size(), 10);
+        assert_eq!(content.read_at(2, 3).unwrap(), b"234");
+        assert_eq!(content.read_at(8, 100).unwrap(), b"89");
+        assert!(content.read_at(20, 4).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_spooled_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let data: Vec<u8> = (0..SPOOL_THRESHOLD + 10).map(|i| (i % 251) as u8).collect();
+
+        let content = provider_for(data.clone().into(), temp_dir.path());
+        // The spool file is anonymous; nothing is left in the directory
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+        assert_eq!(content.size(), data.len() as u64);
+        assert_eq!(content.read_at(SPOOL_THRESHOLD as u64, 100).unwrap(), &data[SPOOL_THRESHOLD..]);
+        assert_eq!(content.read_at(1000, 4).unwrap(), &data[1000..1004]);
+        assert!(content.read_at(data.len() as u64, 4).unwrap().is_empty());
+    }
+}
@@ -1,21 +1,42 @@
+use std::borrow::Cow;
 use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
 use chrono::{DateTime, Utc};
 
 use fuser::{
     Filesystem, Request, ReplyAttr, ReplyData, ReplyDirectory, ReplyEntry, ReplyOpen,
-    FileAttr, FileType, FUSE_ROOT_ID,
+    ReplyEmpty, ReplyWrite, ReplyCreate, ReplyXattr, ReplyStatfs, FileAttr, FileType, TimeOrNow, FUSE_ROOT_ID,
 };
-use libc::{ENOENT, ENOTDIR, EISDIR, EINVAL};
+use lru::LruCache;
+use libc::{ENOENT, ENOTDIR, EISDIR, EINVAL, EIO, EROFS, ERANGE, EEXIST, EXDEV, EFBIG, EAGAIN};
 use parking_lot::RwLock;
-use tracing::{debug, warn, error};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn, error};
 
 use crate::fuse::{create_file_attr, create_file_attr_with_times};
-use crate::fuse::inode::{InodeManager, NodeType};
-use crate::feed::{Feed, Article};
+use crate::fuse::content::{self, BytesContent, ContentProvider, FileContent};
+use crate::fuse::naming::{self, FilenameTemplate};
+use crate::fuse::notify::{Invalidation, KernelNotifier};
+use crate::fuse::stats::{OpReport, OpStats};
+use crate::fuse::inode::{ArticleView, GeneratedFile, InodeManager, NodeType, RecentView, VNode};
+use crate::feed::{aggregate, dedup, Article, Enclosure, Feed, FeedResult, FeedStatus, StatusChange};
+use crate::feed::fetcher::FeedFetcher;
+use crate::feed::urls::UrlNormalizer;
+use crate::feed::scoring::Scorer;
+use crate::feed::scheduler::RefreshHook;
+use crate::content::{asset_file_name, epub, language};
+use crate::content::summarizer::SummaryCache;
+use crate::content::extractor::ContentSelectors;
 use crate::error::Result;
+use crate::storage::{ArticleRepository, ContentStore, FeedRepository, Repository, RepositoryStats};
+use crate::config::{AgingMarker, ArticleContentMode, Config, DuplicateMode, EnclosureMode, FilesystemConfig};
 
 /// Feed loading status
 #[derive(Debug, Clone, PartialEq)]
@@ -25,12 +46,269 @@ pub enum FeedLoadingStatus {
     Error(String),
 }
 
+/// Errno for a missing extended attribute
+#[cfg(target_os = "macos")]
+const ENOATTR: i32 = libc::ENOATTR;
+#[cfg(not(target_os = "macos"))]
+const ENOATTR: i32 = libc::ENODATA;
+
+/// Contents of `.rss-fuse/status.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusReport {
+    pub generated_at: DateTime<Utc>,
+    pub feeds: Vec<FeedReport>,
+    /// Absent until a repository is attached
+    pub cache: Option<CacheReport>,
+    /// FUSE operations served so far, by name
+    #[serde(default)]
+    pub operations: Vec<OpReport>,
+}
+
+/// Health of a single feed as shown in the status files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedReport {
+    pub name: String,
+    pub url: Option<String>,
+    pub title: Option<String>,
+    /// One of `loading`, `active`, `updating`, `disabled` or `error`
+    pub status: String,
+    pub articles: usize,
+    pub unread: usize,
+    pub last_updated: Option<DateTime<Utc>>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_failure: Option<DateTime<Utc>>,
+    /// Duration of the most recent fetch
+    pub fetch_latency_ms: Option<u64>,
+    pub consecutive_failures: u32,
+    pub error: Option<String>,
+    /// Articles added, updated and removed by the most recent refresh
+    pub last_refresh: Option<FeedResult>,
+    /// Recent status changes, oldest first
+    #[serde(default)]
+    pub status_history: Vec<StatusChange>,
+}
+
+/// An article as listed by `rss-fuse watch`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentArticle {
+    pub feed: String,
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    pub published: Option<DateTime<Utc>>,
+    /// When the mount first stored the article
+    pub cached_at: Option<DateTime<Utc>>,
+    pub read: bool,
+}
+
+/// An article of a feed and the file presenting it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListedArticle {
+    pub id: String,
+    pub title: String,
+    pub published: Option<DateTime<Utc>>,
+    pub read: bool,
+    /// File below the mount point; `None` for an article not listed, e.g.
+    /// one past the feed's visible limit
+    pub path: Option<PathBuf>,
+}
+
+/// Repository cache and storage figures
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheReport {
+    pub entries: usize,
+    pub memory_usage_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+    pub stored_feeds: usize,
+    pub stored_articles: usize,
+    pub storage_size_bytes: u64,
+}
+
+impl CacheReport {
+    fn from_stats(stats: &RepositoryStats) -> Self {
+        Self {
+            entries: stats.cache.total_entries,
+            memory_usage_bytes: stats.cache.memory_usage_bytes,
+            hits: stats.cache.hits,
+            misses: stats.cache.misses,
+            hit_rate: stats.cache.hit_rate(),
+            stored_feeds: stats.storage.total_feeds,
+            stored_articles: stats.storage.total_articles,
+            storage_size_bytes: stats.storage.storage_size_bytes,
+        }
+    }
+}
+
+/// Space and inode figures reported to `statfs`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsUsage {
+    /// The configured storage limit, or `used_bytes` when there is none
+    pub total_bytes: u64,
+    /// Article files plus the repository's in-memory cache
+    pub used_bytes: u64,
+    pub inodes: u64,
+}
+
+/// Block size reported by `statfs`, matching the files' `blksize`
+const STATFS_BLOCK_SIZE: u32 = 4096;
+
+/// Longest file name `statfs` advertises
+const STATFS_NAME_MAX: u32 = 255;
+
+/// Largest content a control file takes; writes beyond it fail with `EFBIG`
+const MAX_CONTROL_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Most `.search/` directories kept for queries that were only looked up;
+/// beyond it the least recently used are dropped
+const MAX_LOOKED_UP_SEARCHES: usize = 32;
+
+/// How long a downloaded feed icon is kept before a refresh fetches it again
+const ICON_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Largest feed icon downloaded; bigger ones are not served
+const MAX_ICON_BYTES: u64 = 1024 * 1024;
+
+/// Largest article image downloaded for `offline_images`; reading a bigger
+/// one fails with `EFBIG`
+const MAX_IMAGE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Mutating operations. The filesystem is read-only, so all of these are
+/// answered with `EROFS` unless a feature explicitly allows them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteOp {
+    Write,
+    Setattr,
+    Mknod,
+    Mkdir,
+    Unlink,
+    Rmdir,
+    Symlink,
+    Rename,
+    Link,
+    Create,
+    Setxattr,
+    Removexattr,
+    Fallocate,
+    CopyFileRange,
+    /// Linking an article into `starred/`, or removing its link there
+    Star,
+}
+
 /// Main FUSE filesystem implementation for RSS-FUSE
 pub struct RssFuseFilesystem {
     inode_manager: Arc<InodeManager>,
-    feeds: RwLock<HashMap<String, Feed>>,
+    feeds: RwLock<HashMap<String, MountedFeed>>,
     config_content: RwLock<String>,
     loading_status: RwLock<HashMap<String, FeedLoadingStatus>>,
+    repository: RwLock<Option<Repository>>,
+    runtime: RwLock<Option<tokio::runtime::Handle>>,
+    page_fetcher: RwLock<FeedFetcher>,
+    failed_fetches: RwLock<HashSet<String>>, // article ids whose page could not be fetched
+    feed_configs: RwLock<HashMap<String, String>>, // feed name -> `[feeds]` snippet
+    enclosure_dir: RwLock<Option<PathBuf>>, // where `download` mode keeps enclosures
+    image_dir: RwLock<Option<PathBuf>>, // where `offline_images` keeps article images
+    icon_dir: RwLock<Option<PathBuf>>, // where feed icons are kept
+    summaries: RwLock<Option<Arc<SummaryCache>>>, // summarizer for articles, if configured
+    feed_errors: RwLock<HashMap<String, String>>, // feed name -> report behind `.error.txt`
+    open_files: RwLock<HashMap<u64, OpenFile>>, // file handle -> content snapshot
+    next_fh: AtomicU64,
+    config_path: RwLock<Option<PathBuf>>, // config file behind `.rss-fuse/feeds.toml`
+    pending_writes: RwLock<HashMap<u64, PendingWrite>>, // file handle -> control file being written
+    notifier: Arc<RwLock<Option<KernelNotifier>>>, // shared with clones, set once mounted
+    epubs: RwLock<HashMap<String, CachedEpub>>, // feed name -> last book built
+    virtual_feeds: RwLock<BTreeMap<String, Vec<String>>>, // virtual feed name -> member feeds
+    op_stats: Arc<OpStats>, // shared with clones, which serve the mount
+    access_refresh: Arc<parking_lot::Mutex<AccessRefresh>>, // shared with clones
+    downloads: Arc<parking_lot::Mutex<HashMap<PathBuf, Arc<Download>>>>, // local path -> download in progress
+    oversized_images: Arc<RwLock<HashSet<String>>>, // image urls over MAX_IMAGE_BYTES, not downloaded again
+    searches: Arc<parking_lot::Mutex<Searches>>, // shared with clones, like the inodes
+    status: Arc<RwLock<StatusCache>>, // shared with clones, refreshed in the background
+}
+
+/// Repository figures and status files as last collected by
+/// `refresh_status`, so FUSE callbacks never wait on the repository for them
+#[derive(Debug, Default)]
+struct StatusCache {
+    cache: Option<CacheReport>,
+    json: Option<Arc<[u8]>>,
+    markdown: Option<Arc<[u8]>>,
+}
+
+/// Queries with a `.search/` directory. Those made with `mkdir` stay until
+/// removed; those only looked up (by `ls`, shell completion) are evicted.
+#[derive(Debug)]
+struct Searches {
+    saved: HashSet<String>,
+    looked_up: LruCache<String, ()>,
+}
+
+impl Default for Searches {
+    fn default() -> Self {
+        Self {
+            saved: HashSet::new(),
+            looked_up: LruCache::new(NonZeroUsize::new(MAX_LOOKED_UP_SEARCHES).unwrap()),
+        }
+    }
+}
+
+/// Where `refresh_on_access` sends the feeds to refresh, and when each was
+/// last sent
+#[derive(Debug, Default)]
+struct AccessRefresh {
+    requests: Option<tokio::sync::mpsc::UnboundedSender<String>>,
+    requested: HashMap<String, DateTime<Utc>>, // by the repository clock
+}
+
+/// A file being downloaded in the background, for reads to wait on
+#[derive(Debug, Default)]
+struct Download {
+    progress: parking_lot::Mutex<DownloadProgress>,
+    changed: parking_lot::Condvar,
+}
+
+#[derive(Debug, Default)]
+struct DownloadProgress {
+    received: u64,
+    finished: Option<std::result::Result<u64, i32>>, // size, or the errno to reply with
+}
+
+/// A feed as presented. Its articles are shared with the nodes listing
+/// them rather than copied into each.
+#[derive(Debug, Clone)]
+struct MountedFeed {
+    feed: Feed, // `articles` moved out to the field below
+    articles: Vec<Arc<Article>>,
+}
+
+impl MountedFeed {
+    fn new(mut feed: Feed) -> Self {
+        let articles = std::mem::take(&mut feed.articles).into_iter().map(Arc::new).collect();
+        Self { feed, articles }
+    }
+}
+
+/// A feed's EPUB and a fingerprint of what it was built from
+#[derive(Debug, Clone)]
+struct CachedEpub {
+    fingerprint: u64,
+    book: Arc<[u8]>,
+}
+
+/// Content of a file as it was when opened
+#[derive(Clone)]
+struct OpenFile {
+    ino: u64,
+    content: Arc<dyn ContentProvider>,
+}
+
+/// Data written to a control file through one open handle
+#[derive(Debug, Clone)]
+struct PendingWrite {
+    file: GeneratedFile,
+    data: Vec<u8>,
+    dirty: bool,
 }
 
 impl Clone for RssFuseFilesystem {
@@ -40,6 +318,29 @@ impl Clone for RssFuseFilesystem {
             feeds: RwLock::new(self.feeds.read().clone()),
             config_content: RwLock::new(self.config_content.read().clone()),
             loading_status: RwLock::new(self.loading_status.read().clone()),
+            repository: RwLock::new(self.repository.read().clone()),
+            runtime: RwLock::new(self.runtime.read().clone()),
+            page_fetcher: RwLock::new(self.page_fetcher()),
+            failed_fetches: RwLock::new(self.failed_fetches.read().clone()),
+            feed_configs: RwLock::new(self.feed_configs.read().clone()),
+            enclosure_dir: RwLock::new(self.enclosure_dir.read().clone()),
+            image_dir: RwLock::new(self.image_dir.read().clone()),
+            icon_dir: RwLock::new(self.icon_dir.read().clone()),
+            summaries: RwLock::new(self.summaries.read().clone()),
+            feed_errors: RwLock::new(self.feed_errors.read().clone()),
+            open_files: RwLock::new(self.open_files.read().clone()),
+            next_fh: AtomicU64::new(self.next_fh.load(Ordering::Relaxed)),
+            config_path: RwLock::new(self.config_path.read().clone()),
+            pending_writes: RwLock::new(self.pending_writes.read().clone()),
+            notifier: Arc::clone(&self.notifier),
+            epubs: RwLock::new(self.epubs.read().clone()),
+            virtual_feeds: RwLock::new(self.virtual_feeds.read().clone()),
+            op_stats: Arc::clone(&self.op_stats),
+            access_refresh: Arc::clone(&self.access_refresh),
+            downloads: Arc::clone(&self.downloads),
+            oversized_images: Arc::clone(&self.oversized_images),
+            searches: Arc::clone(&self.searches),
+            status: Arc::clone(&self.status),
         }
     }
 }
@@ -58,536 +359,2789 @@ impl RssFuseFilesystem {
             feeds: RwLock::new(HashMap::new()),
             config_content: RwLock::new(String::new()),
             loading_status: RwLock::new(HashMap::new()),
+            repository: RwLock::new(None),
+            runtime: RwLock::new(None),
+            page_fetcher: RwLock::new(FeedFetcher::new()),
+            failed_fetches: RwLock::new(HashSet::new()),
+            feed_configs: RwLock::new(HashMap::new()),
+            enclosure_dir: RwLock::new(None),
+            image_dir: RwLock::new(None),
+            icon_dir: RwLock::new(None),
+            summaries: RwLock::new(None),
+            feed_errors: RwLock::new(HashMap::new()),
+            open_files: RwLock::new(HashMap::new()),
+            next_fh: AtomicU64::new(1),
+            config_path: RwLock::new(None),
+            pending_writes: RwLock::new(HashMap::new()),
+            notifier: Arc::new(RwLock::new(None)),
+            epubs: RwLock::new(HashMap::new()),
+            virtual_feeds: RwLock::new(BTreeMap::new()),
+            op_stats: Arc::new(OpStats::default()),
+            access_refresh: Arc::new(parking_lot::Mutex::new(AccessRefresh::default())),
+            downloads: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            oversized_images: Arc::new(RwLock::new(HashSet::new())),
+            searches: Arc::new(parking_lot::Mutex::new(Searches::default())),
+            status: Arc::new(RwLock::new(StatusCache::default())),
         }
     }
 
-    /// Add a loading placeholder directory for a feed
-    pub fn add_loading_placeholder(&self, feed_name: &str) -> Result<()> {
-        // Update loading status
-        self.loading_status.write().insert(feed_name.to_string(), FeedLoadingStatus::Loading);
-        
-        // Create feed directory
-        if let Err(e) = self.inode_manager.create_feed_directory(feed_name) {
-            warn!("Failed to create feed directory for {}: {}", feed_name, e);
-            return Err(crate::error::Error::Fuse(e.to_string()));
+    /// Create a filesystem that applies the presentation settings from `config`
+    pub fn with_config(config: &FilesystemConfig) -> Self {
+        let fs = Self::new();
+        fs.inode_manager.set_config(config.clone());
+        fs.op_stats.set_slow_threshold(Duration::from_millis(config.slow_op_threshold_ms));
+        if let Err(e) = fs.inode_manager.create_recent_directories() {
+            error!("Failed to create latest/today directories: {}", e);
+        }
+        if config.control_files {
+            if let Err(e) = fs.inode_manager.create_control_files() {
+                error!("Failed to create control files: {}", e);
+            }
         }
+        fs
+    }
 
-        // Add a loading placeholder file
-        let loading_content = format!(
-            "📡 Loading feed: {}\n\
-            ⏳ Please wait while we fetch the latest articles...\n\
-            🔄 This file will be replaced with actual articles once loading completes.\n\
-            \n\
-            Status: Fetching RSS feed\n\
-            Started: {}\n\
-            \n\
-            If this takes too long, check:\n\
-            • Your internet connection\n\
-            • The feed URL is correct\n\
-            • The RSS server is responding\n",
-            feed_name,
-            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
-        );
+    /// Send kernel cache invalidations through `notifier` whenever a feed's
+    /// directory changes. Applies to every clone of this filesystem.
+    pub fn set_notifier(&self, notifier: KernelNotifier) {
+        *self.notifier.write() = Some(notifier);
+    }
 
-        // Create a placeholder article
-        let placeholder_article = Arc::new(Article {
-            id: format!("loading-{}", feed_name),
-            title: format!("⏳ Loading {}...", feed_name),
-            link: "".to_string(),
-            description: Some("Feed is currently loading. Please wait...".to_string()),
-            content: Some(loading_content),
-            author: Some("RSS-FUSE".to_string()),
-            published: Some(chrono::Utc::now()),
-            updated: None,
-            tags: vec!["loading".to_string()],
-            read: false,
-            cached_at: Some(chrono::Utc::now()),
-        });
+    /// Send the names of feeds to refresh under `refresh_on_access`, or
+    /// written to `.rss-fuse/refresh`, to `requests`. Applies to every clone
+    /// of this filesystem.
+    pub fn set_refresh_requests(&self, requests: tokio::sync::mpsc::UnboundedSender<String>) {
+        self.access_refresh.lock().requests = Some(requests);
+    }
 
-        if let Err(e) = self.inode_manager.create_article_file(feed_name, placeholder_article) {
-            warn!("Failed to create loading placeholder for {}: {}", feed_name, e);
+    /// Attach the repository backing read state (`rm`) and `.search`.
+    /// Call from within the tokio runtime so FUSE callbacks can reach it.
+    pub fn set_repository(&self, repository: Repository) {
+        *self.repository.write() = Some(repository);
+        *self.runtime.write() = tokio::runtime::Handle::try_current().ok();
+        if let Err(e) = self.inode_manager.search_root() {
+            warn!("Failed to create .search directory: {}", e);
         }
+    }
 
-        // Update directory timestamps to show loading state change
-        self.refresh_directory_timestamps(feed_name);
-
-        Ok(())
+    /// The time source for timestamps and aging in this filesystem
+    pub fn clock(&self) -> crate::clock::SharedClock {
+        self.inode_manager.clock()
     }
 
-    /// Add an error placeholder when feed loading fails
-    pub fn add_error_placeholder(&self, feed_name: &str, error_message: &str) -> Result<()> {
-        // Update loading status
-        self.loading_status.write().insert(feed_name.to_string(), FeedLoadingStatus::Error(error_message.to_string()));
-        
-        // Remove existing content
-        self.remove_feed(feed_name)?;
-        
-        // Create feed directory
-        if let Err(e) = self.inode_manager.create_feed_directory(feed_name) {
-            warn!("Failed to create feed directory for {}: {}", feed_name, e);
-            return Err(crate::error::Error::Fuse(e.to_string()));
+    /// Run a repository future from a synchronous FUSE callback
+    fn block_on<F: std::future::Future>(&self, future: F) -> F::Output {
+        match self.runtime.read().clone() {
+            // FUSE callbacks run on fuser's own thread, outside the runtime
+            Some(handle) if tokio::runtime::Handle::try_current().is_err() => handle.block_on(future),
+            _ => futures::executor::block_on(future),
         }
+    }
 
-        // Add an error placeholder file
-        let error_content = format!(
-            "❌ Failed to load feed: {}\n\
-            \n\
-            Error: {}\n\
-            \n\
-            ⏰ Last attempt: {}\n\
-            \n\
-            📋 Troubleshooting:\n\
-            • Check your internet connection\n\
-            • Verify the feed URL is correct\n\
-            • Ensure the RSS server is accessible\n\
-            • Check RSS-FUSE logs for detailed error information\n\
-            \n\
-            🔄 The feed will be retried automatically on the next refresh cycle.\n\
-            \n\
-            💡 You can also try:\n\
-            • rss-fuse refresh {}\n\
-            • rss-fuse remove-feed {} && rss-fuse add-feed {} <new-url>\n",
-            feed_name,
-            error_message,
-            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-            feed_name,
-            feed_name,
-            feed_name
-        );
+    /// Get or create `.search/<query>` and fill it with matching articles.
+    /// A `saved` search (made with `mkdir`) is kept until removed; others
+    /// are dropped once too many newer ones have been looked up.
+    pub fn search(&self, query: &str, saved: bool) -> Option<VNode> {
+        if query.trim().is_empty() || query.starts_with('.') {
+            return None;
+        }
+        self.repository.read().as_ref()?;
 
-        // Create an error article
-        let error_article = Arc::new(Article {
-            id: format!("error-{}", feed_name),
-            title: format!("❌ Error loading {}", feed_name),
-            link: "".to_string(),
-            description: Some("Feed failed to load. See details inside.".to_string()),
-            content: Some(error_content),
-            author: Some("RSS-FUSE".to_string()),
-            published: Some(chrono::Utc::now()),
-            updated: None,
-            tags: vec!["error".to_string()],
-            read: false,
-            cached_at: Some(chrono::Utc::now()),
-        });
+        let root_ino = self.inode_manager.search_root().ok()?;
+        let node = match self.inode_manager.get_node_by_name(root_ino, query) {
+            Some(node) => node,
+            None => {
+                let ino = self.inode_manager
+                    .create_node(root_ino, query.to_string(), NodeType::SearchResults(query.to_string()))
+                    .ok()?;
+                self.inode_manager.get_node(ino)?
+            }
+        };
 
-        if let Err(e) = self.inode_manager.create_article_file(feed_name, error_article) {
-            warn!("Failed to create error placeholder for {}: {}", feed_name, e);
+        let evicted = {
+            let mut searches = self.searches.lock();
+            if saved {
+                searches.looked_up.pop(query);
+                searches.saved.insert(query.to_string());
+                None
+            } else if searches.saved.contains(query) {
+                None
+            } else {
+                searches.looked_up.push(query.to_string(), ())
+                    .map(|(evicted, ())| evicted)
+                    .filter(|evicted| evicted != query)
+            }
+        };
+        if let Some(evicted) = evicted {
+            self.forget_search(&evicted);
         }
 
-        // Update directory timestamps to show error state change
-        self.refresh_directory_timestamps(feed_name);
-
-        Ok(())
+        self.refresh_search(&node);
+        Some(node)
     }
 
-    pub fn add_feed(&self, feed: Feed) -> Result<()> {
-        let feed_name = feed.name.clone();
-        
-        // Update loading status
-        self.loading_status.write().insert(feed_name.clone(), FeedLoadingStatus::Loaded);
-        
-        // Remove existing content (including placeholders)
-        self.remove_feed(&feed_name)?;
-        
-        // Create feed directory
-        if let Err(e) = self.inode_manager.create_feed_directory(&feed_name) {
-            warn!("Failed to create feed directory for {}: {}", feed_name, e);
+    /// Remove `.search/<query>`. Returns `None` if there is no such search.
+    pub fn forget_search(&self, query: &str) -> Option<std::result::Result<(), String>> {
+        {
+            let mut searches = self.searches.lock();
+            searches.saved.remove(query);
+            searches.looked_up.pop(query);
         }
-
-        // Add articles
-        for article in &feed.articles {
-            let article_arc = Arc::new(article.clone());
-            if let Err(e) = self.inode_manager.create_article_file(&feed_name, article_arc) {
-                warn!("Failed to create article file for {}: {}", article.title, e);
-            }
+        let root_ino = self.inode_manager.search_root().ok()?;
+        let node = self.inode_manager.get_node_by_name(root_ino, query)?;
+        if let Some(notifier) = self.notifier.read().clone() {
+            notifier.invalidate(Invalidation::Entry { parent: root_ino, name: query.into() });
         }
-
-        // Store feed data
-        self.feeds.write().insert(feed_name.clone(), feed);
-        
-        // Update directory timestamps to trigger file manager refresh
-        self.refresh_directory_timestamps(&feed_name);
-        
-        Ok(())
+        Some(self.inode_manager.remove_subtree(node.ino))
     }
 
-    /// Add or update feed content from cache (first load or background refresh)
-    /// This method is optimized for cache-first loading scenarios
-    pub fn add_feed_from_cache(&self, feed: Feed, is_from_cache: bool) -> Result<()> {
-        let feed_name = feed.name.clone();
-        
-        // Update loading status based on source
-        let status = if is_from_cache {
-            FeedLoadingStatus::Loaded // Will be updated when fresh content arrives
-        } else {
-            FeedLoadingStatus::Loaded
+    /// Re-run the query behind a `.search/<query>` directory
+    pub fn refresh_search(&self, node: &VNode) {
+        let NodeType::SearchResults(query) = &node.node_type else {
+            return;
         };
-        self.loading_status.write().insert(feed_name.clone(), status);
-        
-        // Check if we already have content for this feed
-        let has_existing_content = {
-            let feeds = self.feeds.read();
-            feeds.contains_key(&feed_name)
+        let Some(repository) = self.repository.read().clone() else {
+            return;
         };
-        
-        // If this is fresh content updating cached content, be more selective about updates
-        if !is_from_cache && has_existing_content {
-            // This is a background refresh update - compare article counts
-            let existing_article_count = {
-                let feeds = self.feeds.read();
-                feeds.get(&feed_name).map(|f| f.articles.len()).unwrap_or(0)
-            };
-            
-            debug!("Updating {} with fresh content: {} -> {} articles", 
-                   feed_name, existing_article_count, feed.articles.len());
-        } else if is_from_cache {
-            debug!("Loading {} from cache: {} articles", feed_name, feed.articles.len());
-        }
-        
-        // Remove existing content and add new content
-        self.remove_feed(&feed_name)?;
-        
-        // Create feed directory
-        if let Err(e) = self.inode_manager.create_feed_directory(&feed_name) {
-            warn!("Failed to create feed directory for {}: {}", feed_name, e);
-        }
 
-        // Add articles
-        for article in &feed.articles {
-            let article_arc = Arc::new(article.clone());
-            if let Err(e) = self.inode_manager.create_article_file(&feed_name, article_arc) {
-                warn!("Failed to create article file for {}: {}", article.title, e);
+        let article_query = crate::storage::ArticleQuery {
+            text: Some(query.clone()),
+            ..Default::default()
+        };
+        let results = match self.block_on(repository.search_articles(&article_query)) {
+            Ok(results) => results,
+            Err(e) => {
+                warn!("Search for '{}' failed: {}", query, e);
+                return;
             }
-        }
+        };
 
-        // Store feed data
-        self.feeds.write().insert(feed_name.clone(), feed);
-        
-        // Update directory timestamps to trigger file manager refresh
-        self.refresh_directory_timestamps(&feed_name);
-        
-        Ok(())
-    }
+        let feed_of: HashMap<String, String> = self.feeds.read().iter()
+            .flat_map(|(name, feed)| feed.articles.iter().map(move |a| (a.id.clone(), name.clone())))
+            .collect();
+        let entries: Vec<(String, Arc<Article>)> = results.into_iter()
+            .map(|article| (feed_of.get(&article.id).cloned().unwrap_or_default(), article))
+            .collect();
 
-    pub fn remove_feed(&self, feed_name: &str) -> Result<()> {
-        // Find and remove feed directory
-        if let Some(feed_node) = self.inode_manager.get_node_by_name(FUSE_ROOT_ID, feed_name) {
-            // Remove all articles first
-            let children = self.inode_manager.list_children(feed_node.ino);
-            for child in children {
-                if let Err(e) = self.inode_manager.remove_node(child.ino) {
-                    warn!("Failed to remove article {}: {}", child.name, e);
-                }
-            }
-            
-            // Remove the directory itself
-            if let Err(e) = self.inode_manager.remove_node(feed_node.ino) {
-                warn!("Failed to remove feed directory {}: {}", feed_name, e);
-            }
+        if let Err(e) = self.inode_manager.sync_article_directory(node.ino, &entries) {
+            warn!("Failed to populate search results for '{}': {}", query, e);
         }
-
-        // Remove from feeds map
-        self.feeds.write().remove(feed_name);
-        
-        Ok(())
     }
 
-    pub fn get_total_inodes(&self) -> usize {
-        self.inode_manager.get_total_nodes()
+    fn is_search_root(&self, ino: u64) -> bool {
+        self.inode_manager.get_node(ino)
+            .is_some_and(|node| matches!(node.node_type, NodeType::SearchRoot))
     }
 
-    pub fn get_feeds_count(&self) -> usize {
-        self.feeds.read().len()
+    fn is_article_read(&self, article: &Article) -> bool {
+        article.read || self.repository.read().as_ref().is_some_and(|repo| repo.is_article_read(&article.id))
     }
 
-    pub fn get_node(&self, ino: u64) -> Option<crate::fuse::inode::VNode> {
-        self.inode_manager.get_node(ino)
+    fn is_article_starred(&self, article: &Article) -> bool {
+        article.starred || self.repository.read().as_ref().is_some_and(|repo| repo.is_article_starred(&article.id))
     }
 
-    pub fn list_children(&self, parent_ino: u64) -> Vec<crate::fuse::inode::VNode> {
-        self.inode_manager.list_children(parent_ino)
+    /// Whether an article is left out of its feed directory
+    fn is_hidden(&self, article: &Article) -> bool {
+        self.inode_manager.config().hide_read_articles && self.is_article_read(article)
     }
 
-    pub fn get_article_content(&self, ino: u64) -> Option<String> {
-        self.inode_manager.get_article_content(ino)
-    }
+    /// Recompute the entries of an `unread/` or `read/` view directory
+    pub fn refresh_view(&self, node: &VNode) {
+        let NodeType::ViewDirectory(feed_name, view) = &node.node_type else {
+            return;
+        };
 
-    pub fn get_node_by_name(&self, parent_ino: u64, name: &str) -> Option<crate::fuse::inode::VNode> {
-        self.inode_manager.get_node_by_name(parent_ino, name)
-    }
+        let articles: Vec<Arc<Article>> = match self.feeds.read().get(feed_name) {
+            Some(feed) => feed.articles.iter()
+                .filter(|a| view.includes(self.is_article_read(a)))
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
 
-    pub fn get_loading_status(&self, feed_name: &str) -> Option<FeedLoadingStatus> {
-        self.loading_status.read().get(feed_name).cloned()
+        if let Err(e) = self.inode_manager.sync_view_directory(node.ino, &articles) {
+            warn!("Failed to refresh {} view of {}: {}", view.dir_name(), feed_name, e);
+        }
     }
 
-    pub fn refresh_directory_timestamps(&self, feed_name: &str) {
-        // Update the feed directory's modification time
-        if let Some(feed_node) = self.inode_manager.get_node_by_name(FUSE_ROOT_ID, feed_name) {
-            self.inode_manager.touch_directory_and_parents(feed_node.ino);
-        }
-        
-        // Also update root directory timestamp to ensure top-level changes are detected
-        self.inode_manager.touch_node_modified(FUSE_ROOT_ID);
-    }
+    /// Recompute the symlinks in `latest/`, `today/`, `starred/` or `top/`
+    pub fn refresh_recent(&self, node: &VNode) {
+        let NodeType::RecentDirectory(view) = node.node_type else {
+            return;
+        };
 
-    pub fn refresh_all_directory_timestamps(&self) {
-        // Update all feed directories
-        let root_children = self.inode_manager.list_children(FUSE_ROOT_ID);
-        for child in root_children {
-            if child.is_directory() {
-                if let NodeType::FeedDirectory(feed_name) = &child.node_type {
-                    self.refresh_directory_timestamps(feed_name);
+        let mut entries: Vec<(String, Arc<Article>)> = self.feeds.read().iter()
+            .flat_map(|(name, feed)| feed.articles.iter().map(move |a| (name.clone(), Arc::clone(a))))
+            .filter(|(_, article)| !self.is_hidden(article))
+            .collect();
+        // Newest first; undated articles sort last
+        entries.sort_by_key(|(_, article)| std::cmp::Reverse(article.published));
+
+        match view {
+            RecentView::Latest => entries.truncate(self.inode_manager.config().latest_articles),
+            RecentView::Today => {
+                let since = self.inode_manager.clock().now_utc() - chrono::Duration::hours(24);
+                entries.retain(|(_, article)| article.published.is_some_and(|published| published >= since));
+            }
+            RecentView::Starred => entries.retain(|(_, article)| self.is_article_starred(article)),
+            RecentView::Top => {
+                let score = |article: &Article| self.inode_manager.score(&article.id).unwrap_or(0);
+                entries.retain(|(_, article)| score(article) > 0);
+                // Stable, so equal scores stay newest first
+                entries.sort_by_key(|(_, article)| std::cmp::Reverse(score(article)));
+                entries.truncate(self.inode_manager.config().scoring.top_articles);
+                if let Err(e) = self.inode_manager.sync_ranked_link_directory(node.ino, &entries) {
+                    warn!("Failed to refresh top/: {}", e);
                 }
+                return;
             }
         }
+
+        if let Err(e) = self.inode_manager.sync_link_directory(node.ino, &entries) {
+            warn!("Failed to refresh {}/: {}", view.dir_name(), e);
+        }
     }
 
-    /// Get TTL based on content state - dynamic content gets shorter cache times
-    pub fn get_ttl_for_node(&self, node: &crate::fuse::inode::VNode) -> Duration {
-        use std::time::Duration;
-        
+    /// Recompute the tag directories in `tags/`, or the symlinks in one of
+    /// them, from the tags articles carry
+    pub fn refresh_tags(&self, node: &VNode) {
         match &node.node_type {
-            crate::fuse::inode::NodeType::FeedDirectory(feed_name) => {
-                match self.loading_status.read().get(feed_name) {
-                    Some(FeedLoadingStatus::Loading) => Duration::from_secs(0), // No cache while loading
-                    Some(FeedLoadingStatus::Error(_)) => Duration::from_secs(2), // Short cache for errors
-                    Some(FeedLoadingStatus::Loaded) => Duration::from_secs(30), // Longer cache for stable content
-                    None => Duration::from_secs(1), // Default for unconfigured feeds
+            NodeType::TagsRoot => {
+                let names: BTreeSet<String> = self.feeds.read().values()
+                    .flat_map(|feed| feed.articles.iter())
+                    .filter(|article| !self.is_hidden(article))
+                    .flat_map(|article| article.tags.iter().filter_map(|tag| naming::tag_dir_name(tag)))
+                    .collect();
+                if let Err(e) = self.inode_manager.sync_tag_directories(node.ino, &names) {
+                    warn!("Failed to refresh tags/: {}", e);
                 }
-            },
-            crate::fuse::inode::NodeType::ArticleFile(feed_name, _) => {
-                match self.loading_status.read().get(feed_name) {
-                    Some(FeedLoadingStatus::Loading) => Duration::from_secs(0), // No cache while loading
-                    Some(FeedLoadingStatus::Error(_)) => Duration::from_secs(2), // Short cache for errors  
-                    Some(FeedLoadingStatus::Loaded) => Duration::from_secs(60), // Long cache for stable articles
-                    None => Duration::from_secs(1), // Default
+            }
+            NodeType::TagDirectory(name) => {
+                let mut entries: Vec<(String, Arc<Article>)> = self.feeds.read().iter()
+                    .flat_map(|(feed_name, feed)| feed.articles.iter().map(move |a| (feed_name, a)))
+                    .filter(|(_, article)| !self.is_hidden(article))
+                    .filter(|(_, article)| article.tags.iter().any(|tag| naming::tag_dir_name(tag).as_ref() == Some(name)))
+                    .map(|(feed_name, article)| (feed_name.clone(), Arc::clone(article)))
+                    .collect();
+                entries.sort_by_key(|(_, article)| std::cmp::Reverse(article.published));
+
+                if let Err(e) = self.inode_manager.sync_link_directory(node.ino, &entries) {
+                    warn!("Failed to refresh tags/{}/: {}", name, e);
                 }
-            },
-            _ => Duration::from_secs(10), // Longer cache for static content (meta files, etc.)
+            }
+            _ => {}
         }
     }
 
-    pub fn update_config(&self, content: String) {
-        let content_len = content.len() as u64;
-        *self.config_content.write() = content;
-        
-        // Update the config file size
-        if let Some(config_node) = self.inode_manager.get_node_by_name(1, ".rss-fuse")
-            .and_then(|meta| self.inode_manager.get_node_by_name(meta.ino, "config.toml")) {
-            self.inode_manager.update_node_size(config_node.ino, content_len);
+    /// Present `[virtual-feeds]`: a top-level directory per virtual feed,
+    /// listing the articles of its member feeds
+    pub fn set_virtual_feeds(&self, virtual_feeds: BTreeMap<String, Vec<String>>) {
+        for node in self.inode_manager.list_children(FUSE_ROOT_ID) {
+            if matches!(&node.node_type, NodeType::VirtualFeedDirectory(name) if !virtual_feeds.contains_key(name)) {
+                if let Err(e) = self.inode_manager.remove_subtree(node.ino) {
+                    warn!("Failed to remove virtual feed {}: {}", node.name, e);
+                }
+            }
+        }
+        for name in virtual_feeds.keys() {
+            if self.inode_manager.get_node_by_name(FUSE_ROOT_ID, name).is_none() {
+                if let Err(e) = self.inode_manager.create_node(FUSE_ROOT_ID, name.clone(), NodeType::VirtualFeedDirectory(name.clone())) {
+                    warn!("Failed to create virtual feed {}: {}", name, e);
+                }
+            }
         }
+        *self.virtual_feeds.write() = virtual_feeds;
     }
 
-    fn node_to_file_attr(&self, node: &crate::fuse::inode::VNode) -> FileAttr {
-        let kind = node.file_type;
-        let perm = match kind {
-            FileType::Directory => 0o755,
-            FileType::RegularFile => 0o644,
-            _ => 0o644,
+    /// Under `refresh_on_access`, ask for a refresh of the feed behind a
+    /// listed directory when its last fetch is older than the threshold.
+    /// A feed is asked for at most once per threshold, so repeated listings
+    /// of a failing feed don't add up.
+    pub fn refresh_if_stale(&self, node: &VNode) {
+        let NodeType::FeedDirectory(name) = &node.node_type else {
+            return;
+        };
+        let threshold = Duration::from_secs(self.inode_manager.config().refresh_on_access);
+        if threshold.is_zero() {
+            return;
+        }
+        let Some(repo) = self.repository.read().clone() else {
+            return;
+        };
+        let health = repo.feed_health(name);
+        // Not fetched yet: the first scheduled refresh is on its way
+        let Some(last_fetch) = health.last_success.max(health.last_failure) else {
+            return;
         };
+        // Health is recorded with the repository's clock, so ages are too
+        let now = repo.clock().now_utc();
+        let age = (now - last_fetch).to_std().unwrap_or_default();
+        if age < threshold || !repo.is_enabled(name) {
+            return;
+        }
 
-        create_file_attr_with_times(
-            node.ino, 
-            node.size, 
-            kind, 
-            perm,
-            node.accessed_time,
-            node.modified_time,
-            node.created_time,
-            node.created_time,
-        )
+        let mut access = self.access_refresh.lock();
+        let recently_requested = access.requested.get(name)
+            .is_some_and(|at| (now - *at).to_std().unwrap_or_default() < threshold);
+        if recently_requested {
+            return;
+        }
+        let sent = access.requests.as_ref().is_some_and(|requests| requests.send(name.clone()).is_ok());
+        if sent {
+            debug!("{} was fetched {}s ago, refreshing on access", name, age.as_secs());
+            access.requested.insert(name.clone(), now);
+        }
     }
 
-    fn lookup_node(&self, parent: u64, name: &OsStr) -> Option<crate::fuse::inode::VNode> {
-        let name_str = name.to_str()?;
-        self.inode_manager.get_node_by_name(parent, name_str)
+    /// Recompute the articles of a virtual feed directory from its members
+    pub fn refresh_virtual_feed(&self, node: &VNode) {
+        let NodeType::VirtualFeedDirectory(name) = &node.node_type else {
+            return;
+        };
+        let members = self.virtual_feeds.read().get(name).cloned().unwrap_or_default();
+
+        let entries: Vec<(String, Arc<Article>)> = {
+            let feeds = self.feeds.read();
+            let members = members.iter()
+                .filter_map(|member| feeds.get_key_value(member))
+                .map(|(name, feed)| (name.as_str(), feed.articles.as_slice()));
+            aggregate::aggregate(members, &self.url_normalizer())
+                .into_iter()
+                .filter(|(_, article)| !self.is_hidden(article))
+                .collect()
+        };
+        if let Err(e) = self.inode_manager.sync_article_directory(node.ino, &entries) {
+            warn!("Failed to refresh virtual feed {}: {}", name, e);
+        }
     }
-}
 
-impl Filesystem for RssFuseFilesystem {
-    fn lookup(&mut self, _req: &Request, parent: u64, name: &OsStr, reply: ReplyEntry) {
-        debug!("lookup(parent: {}, name: {:?})", parent, name);
+    /// Mark the article behind `node` as read, hiding it if configured.
+    /// Returns the errno to reply with on failure.
+    pub fn mark_article_read(&self, node: &crate::fuse::inode::VNode) -> std::result::Result<(), i32> {
+        let (feed_name, article) = match &node.node_type {
+            NodeType::ArticleFile(feed_name, article) => (feed_name, article),
+            _ if node.is_directory() => return Err(EISDIR),
+            _ => return Err(EROFS),
+        };
 
-        match self.lookup_node(parent, name) {
-            Some(node) => {
-                let attr = self.node_to_file_attr(&node);
-                let ttl = self.get_ttl_for_node(&node);
-                reply.entry(&ttl, &attr, 0);
-            }
-            None => {
-                debug!("lookup: not found");
-                reply.error(ENOENT);
-            }
+        // Loading and error placeholders are not real articles
+        let is_feed_article = self.feeds.read().get(feed_name)
+            .is_some_and(|feed| feed.articles.iter().any(|a| a.id == article.id));
+        if !is_feed_article {
+            return Err(EROFS);
         }
-    }
 
-    fn getattr(&mut self, _req: &Request, ino: u64, reply: ReplyAttr) {
-        debug!("getattr(ino: {})", ino);
+        let repository = self.repository.read().clone().ok_or(EROFS)?;
+        if let Err(e) = repository.mark_article_read(feed_name, &article.id) {
+            warn!("Failed to mark article {} as read: {}", article.id, e);
+            return Err(EIO);
+        }
 
-        match self.inode_manager.get_node(ino) {
-            Some(node) => {
-                let attr = self.node_to_file_attr(&node);
-                let ttl = self.get_ttl_for_node(&node);
-                reply.attr(&ttl, &attr);
+        if let Some(feed) = self.feeds.write().get_mut(feed_name) {
+            if let Some(stored) = feed.articles.iter_mut().find(|a| a.id == article.id) {
+                Arc::make_mut(stored).read = true;
             }
-            None => {
-                debug!("getattr: inode {} not found", ino);
-                reply.error(ENOENT);
+        }
+
+        // Drop the article from listings it no longer belongs in; view
+        // directories are recomputed on their next readdir anyway
+        let hide = self.inode_manager.config().hide_read_articles;
+        for presented in self.inode_manager.find_article_nodes(feed_name, &article.id) {
+            let in_view = self.inode_manager.get_node(presented.parent_ino)
+                .and_then(|parent| match parent.node_type {
+                    NodeType::ViewDirectory(_, view) => Some(view),
+                    _ => None,
+                });
+            let remove = match in_view {
+                Some(ArticleView::Read) => false,
+                Some(ArticleView::Unread) => true,
+                None => hide,
+            };
+            if remove {
+                self.inode_manager.remove_node(presented.ino).map_err(|_| ENOENT)?;
             }
         }
+        Ok(())
     }
 
-    fn readdir(
-        &mut self,
-        _req: &Request,
-        ino: u64,
-        _fh: u64,
-        offset: i64,
-        mut reply: ReplyDirectory,
-    ) {
-        debug!("readdir(ino: {}, offset: {})", ino, offset);
+    /// Mark an article read by ID, as deleting its file would. Articles not
+    /// presented as a file (e.g. already hidden) are marked in the repository.
+    pub fn mark_article_read_by_id(&self, feed_name: &str, article_id: &str) -> Result<()> {
+        let node = self.inode_manager.find_article_nodes(feed_name, article_id).into_iter()
+            .find(|node| matches!(node.node_type, NodeType::ArticleFile(..)));
+        match node {
+            Some(node) => self.mark_article_read(&node).map_err(|errno| crate::error::Error::Fuse(
+                format!("Failed to mark article {} as read (errno {})", article_id, errno)
+            )),
+            None => match self.repository.read().clone() {
+                Some(repository) => repository.mark_article_read(feed_name, article_id),
+                None => Ok(()),
+            },
+        }
+    }
 
-        let node = match self.inode_manager.get_node(ino) {
-            Some(node) => node,
-            None => {
-                reply.error(ENOENT);
-                return;
+    /// Mark an article read or unread by ID, as `rss-fuse mark-read` asks of
+    /// a running mount. An article hidden while read is listed again.
+    pub fn set_article_read(&self, feed_name: &str, article_id: &str, read: bool) -> Result<()> {
+        if read {
+            return self.mark_article_read_by_id(feed_name, article_id);
+        }
+        let repository = self.repository.read().clone()
+            .ok_or_else(|| crate::error::Error::InvalidState("No repository to keep read state in".to_string()))?;
+        repository.mark_article_unread(feed_name, article_id)?;
+
+        let feed = {
+            let mut feeds = self.feeds.write();
+            let Some(mounted) = feeds.get_mut(feed_name) else {
+                return Ok(());
+            };
+            let Some(stored) = mounted.articles.iter_mut().find(|a| a.id == article_id) else {
+                return Ok(());
+            };
+            Arc::make_mut(stored).read = false;
+            Feed {
+                articles: mounted.articles.iter().map(|article| (**article).clone()).collect(),
+                ..mounted.feed.clone()
             }
         };
-
-        if !node.is_directory() {
-            reply.error(ENOTDIR);
-            return;
+        self.inode_manager.update_article(article_id, |article| article.read = false);
+        if self.inode_manager.config().hide_read_articles {
+            self.add_feed(feed)?;
         }
+        Ok(())
+    }
 
-        let mut entries = vec![
-            (1, FileType::Directory, ".".to_string()),
-            (node.parent_ino, FileType::Directory, "..".to_string()),
-        ];
-
-        // Add child entries
-        let children = self.inode_manager.list_children(ino);
-        for child in children {
-            entries.push((child.ino, child.file_type, child.name));
-        }
+    /// Star or unstar an article, persisting the flag and updating its
+    /// frontmatter and the `starred/` listing
+    pub fn set_article_starred(&self, feed_name: &str, article_id: &str, starred: bool) -> Result<()> {
+        let repository = self.repository.read().clone()
+            .ok_or_else(|| crate::error::Error::InvalidState("No repository to keep stars in".to_string()))?;
+        repository.set_article_starred(feed_name, article_id, starred)?;
 
-        // Apply offset
-        for (i, (child_ino, file_type, name)) in entries.into_iter().enumerate().skip(offset as usize) {
-            debug!("  entry: {} -> {} ({})", name, child_ino, i + 1);
-            
-            if reply.add(child_ino, (i + 1) as i64, file_type, &name) {
-                break; // Buffer is full
+        if let Some(feed) = self.feeds.write().get_mut(feed_name) {
+            if let Some(stored) = feed.articles.iter_mut().find(|a| a.id == article_id) {
+                Arc::make_mut(stored).starred = starred;
             }
         }
+        self.inode_manager.update_article(article_id, |article| article.starred = starred);
 
-        reply.ok();
+        if let Some(dir) = self.inode_manager.get_node_by_name(FUSE_ROOT_ID, RecentView::Starred.dir_name()) {
+            self.refresh_recent(&dir);
+        }
+        Ok(())
     }
 
-    fn open(&mut self, _req: &Request, ino: u64, flags: i32, reply: ReplyOpen) {
-        debug!("open(ino: {}, flags: {})", ino, flags);
+    fn is_starred_root(&self, ino: u64) -> bool {
+        self.inode_manager.get_node(ino)
+            .is_some_and(|node| matches!(node.node_type, NodeType::RecentDirectory(RecentView::Starred)))
+    }
 
-        let node = match self.inode_manager.get_node(ino) {
-            Some(node) => node,
-            None => {
-                reply.error(ENOENT);
-                return;
-            }
+    /// Star the article behind `node` (its file, or a link to it) and list
+    /// it in the `starred/` directory `dir_ino` as `name`. Returns the errno
+    /// to reply with on failure.
+    pub fn star_into(&self, node: &VNode, dir_ino: u64, name: &str) -> std::result::Result<VNode, i32> {
+        let (feed_name, article) = match &node.node_type {
+            NodeType::ArticleFile(feed_name, article) | NodeType::ArticleLink(feed_name, article, _) => (feed_name, article),
+            _ => return Err(EINVAL),
         };
+        let is_feed_article = self.feeds.read().get(feed_name)
+            .is_some_and(|feed| feed.articles.iter().any(|a| a.id == article.id));
+        if !is_feed_article {
+            return Err(EINVAL);
+        }
+        if self.inode_manager.get_node_by_name(dir_ino, name).is_some() {
+            return Err(EEXIST);
+        }
 
-        if node.is_directory() {
-            reply.error(EISDIR);
-            return;
+        if let Err(e) = self.set_article_starred(feed_name, &article.id, true) {
+            warn!("Failed to star article {}: {}", article.id, e);
+            return Err(EIO);
         }
 
-        // For now, we'll allow all opens and use the inode as file handle
-        reply.opened(ino, 0);
+        // Listed under its own name now; keep the one asked for instead
+        let listed = self.inode_manager.list_children(dir_ino).into_iter()
+            .find(|child| matches!(&child.node_type, NodeType::ArticleLink(_, a, _) if a.id == article.id));
+        match listed {
+            Some(link) if link.name == name => Ok(link),
+            listed => {
+                if let Some(link) = listed {
+                    self.inode_manager.remove_node(link.ino).map_err(|_| EIO)?;
+                }
+                let article = self.feeds.read().get(feed_name)
+                    .and_then(|feed| feed.articles.iter().find(|a| a.id == article.id).cloned())
+                    .ok_or(ENOENT)?;
+                let ino = self.inode_manager.create_article_link(dir_ino, name, feed_name, article)
+                    .map_err(|_| ENOENT)?;
+                self.inode_manager.get_node(ino).ok_or(EIO)
+            }
+        }
     }
 
-    fn read(
-        &mut self,
-        _req: &Request,
-        ino: u64,
-        _fh: u64,
-        offset: i64,
-        size: u32,
-        _flags: i32,
-        _lock: Option<u64>,
-        reply: ReplyData,
-    ) {
-        debug!("read(ino: {}, offset: {}, size: {})", ino, offset, size);
+    /// Unstar the article a link in `starred/` points at
+    pub fn unstar_link(&self, node: &VNode) -> std::result::Result<(), i32> {
+        let NodeType::ArticleLink(feed_name, article, _) = &node.node_type else {
+            return Err(if node.is_directory() { EISDIR } else { EROFS });
+        };
+        if let Err(e) = self.set_article_starred(feed_name, &article.id, false) {
+            warn!("Failed to unstar article {}: {}", article.id, e);
+            return Err(EIO);
+        }
+        Ok(())
+    }
 
-        let node = match self.inode_manager.get_node(ino) {
-            Some(node) => node,
-            None => {
-                reply.error(ENOENT);
-                return;
-            }
+    /// In `fetch-on-read` mode, download and extract the full page behind
+    /// an article file the first time it is opened. Returns whether the
+    /// file now serves fetched content; failures fall back to the feed's copy.
+    pub fn fetch_full_content(&self, node: &VNode) -> bool {
+        let NodeType::ArticleFile(feed_name, article) = &node.node_type else {
+            return false;
         };
+        if self.inode_manager.config().article_content != ArticleContentMode::FetchOnRead {
+            return false;
+        }
+        if self.inode_manager.has_full_content(&article.id) {
+            return true;
+        }
+        // HTTP needs the tokio runtime, and each page is only tried once
+        if self.runtime.read().is_none() || self.failed_fetches.read().contains(&article.id) {
+            return false;
+        }
 
-        if node.is_directory() {
-            reply.error(EISDIR);
-            return;
+        // Loading and error placeholders have nothing to fetch
+        let is_feed_article = self.feeds.read().get(feed_name)
+            .is_some_and(|feed| feed.articles.iter().any(|a| a.id == article.id));
+        if !is_feed_article {
+            return false;
         }
 
-        let content = match &node.node_type {
-            NodeType::ArticleFile(_, _) => {
-                match self.inode_manager.get_article_content(ino) {
-                    Some(content) => content,
-                    None => {
-                        error!("Failed to get article content for inode {}", ino);
-                        reply.error(ENOENT);
-                        return;
-                    }
-                }
-            }
-            NodeType::ConfigFile => {
-                self.config_content.read().clone()
+        let fetched = self.block_on(self.read_fetcher().fetch_page(&article.link))
+            .and_then(|page| self.inode_manager.extractor(feed_name).with_page_content(article, &page));
+        match fetched {
+            Ok(full) => {
+                debug!("Fetched full content for {} from {}", article.id, article.link);
+                self.inode_manager.set_full_content(Arc::new(full));
+                true
             }
-            _ => {
-                warn!("Attempted to read unsupported file type: {:?}", node.node_type);
-                reply.error(EINVAL);
-                return;
+            Err(e) => {
+                warn!("Failed to fetch full content from {}: {}", article.link, e);
+                self.failed_fetches.write().insert(article.id.clone());
+                false
             }
+        }
+    }
+
+    /// Rename a feed directory in place (`mv` within its parent directory)
+    /// until the next remount. Returns the errno to reply with on failure.
+    pub fn rename_feed_directory(&self, node: &VNode, new_parent: u64, new_name: &str) -> std::result::Result<(), i32> {
+        let NodeType::FeedDirectory(feed_name) = &node.node_type else {
+            return Err(EROFS);
         };
+        self.check_write(WriteOp::Rename)?;
+        if new_parent != node.parent_ino {
+            return Err(EXDEV);
+        }
+        if self.inode_manager.get_node_by_name(new_parent, new_name).is_some() {
+            return Err(EEXIST);
+        }
+        self.inode_manager.rename_feed_directory(feed_name, new_name).map_err(|e| {
+            warn!("Failed to rename feed directory {}: {}", feed_name, e);
+            EINVAL
+        })?;
+        info!("Renamed directory of feed {} to {}", feed_name, new_name);
+        Ok(())
+    }
 
-        let content_bytes = content.as_bytes();
-        let start = offset as usize;
-        let end = std::cmp::min(start + size as usize, content_bytes.len());
+    /// Keep enclosures downloaded in `download` mode under `dir`
+    pub fn set_enclosure_dir(&self, dir: PathBuf) {
+        *self.enclosure_dir.write() = Some(dir);
+    }
 
-        if start >= content_bytes.len() {
-            reply.data(&[]);
-            return;
+    /// Local copy of an enclosure in `download` mode, whether or not it has
+    /// been downloaded yet
+    fn enclosure_path(&self, enclosure: &Enclosure) -> Option<PathBuf> {
+        if self.inode_manager.config().enclosures != EnclosureMode::Download {
+            return None;
         }
-
-        let data = &content_bytes[start..end];
-        reply.data(data);
+        Some(self.enclosure_dir.read().as_ref()?.join(enclosure_file_name(enclosure)))
     }
 
-    fn release(
-        &mut self,
-        _req: &Request,
-        ino: u64,
-        _fh: u64,
-        _flags: i32,
-        _lock_owner: Option<u64>,
-        _flush: bool,
-        reply: fuser::ReplyEmpty,
-    ) {
-        debug!("release(ino: {})", ino);
-        reply.ok();
+    /// Where a streamed enclosure is downloaded to when its server does not
+    /// support range requests: the content store, which goes away with the
+    /// mount, or else the enclosure directory
+    fn streamed_copy_path(&self, enclosure: &Enclosure) -> Option<PathBuf> {
+        let dir = match self.inode_manager.content_store() {
+            Some(store) => store.path().to_path_buf(),
+            None => self.enclosure_dir.read().clone()?,
+        };
+        Some(dir.join(enclosure_file_name(enclosure)))
     }
-}
 
-impl Default for RssFuseFilesystem {
-    fn default() -> Self {
-        Self::new()
+    /// Keep article bodies longer than `[fuse] spill_content_bytes` in
+    /// `store` rather than in memory. Articles added before keep theirs.
+    pub fn set_content_store(&self, store: ContentStore) {
+        self.inode_manager.set_content_store(Arc::new(store));
     }
-}
+
+    /// Remove stored bodies no article refers to any more, e.g. after a
+    /// refresh replaced a feed's articles
+    fn prune_content(&self) {
+        let Some(store) = self.inode_manager.content_store() else {
+            return;
+        };
+        let removed = store.prune(|| {
+            let mut live = self.inode_manager.content_refs();
+            for article in self.feeds.read().values().flat_map(|feed| &feed.articles) {
+                live.extend(article.content_ref.iter().chain(&article.description_ref).cloned());
+            }
+            live
+        });
+        if removed > 0 {
+            debug!("Removed {} article bodies no longer listed", removed);
+        }
+    }
+
+    /// Keep images downloaded for `offline_images` under `dir`
+    pub fn set_image_dir(&self, dir: PathBuf) {
+        *self.image_dir.write() = Some(dir);
+    }
+
+    /// Local copy of an article image, whether or not it has been downloaded yet
+    fn asset_path(&self, url: &str) -> Option<PathBuf> {
+        Some(self.image_dir.read().as_ref()?.join(asset_file_name(url)))
+    }
+
+    /// Download the images listed for a feed that are not cached yet, in the
+    /// background, so its articles stay readable offline
+    fn prefetch_assets(&self, feed_name: &str) {
+        let Some(runtime) = self.runtime.read().clone() else {
+            return;
+        };
+        let missing: Vec<(String, PathBuf)> = self.inode_manager.asset_urls(feed_name).into_iter()
+            .filter_map(|url| Some((self.asset_path(&url)?, url)))
+            .filter(|(path, url)| !path.exists() && !self.oversized_images.read().contains(url))
+            .map(|(path, url)| (url, path))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        let fetcher = self.page_fetcher();
+        let oversized = Arc::clone(&self.oversized_images);
+        runtime.spawn(async move {
+            for (url, path) in missing {
+                match fetcher.download_capped(&url, &path, MAX_IMAGE_BYTES).await {
+                    Ok(_) => {}
+                    Err(crate::error::Error::ResourceExhausted(e)) => {
+                        debug!("Skipping image: {}", e);
+                        oversized.write().insert(url);
+                    }
+                    Err(e) => debug!("Failed to download image {}: {}", url, e),
+                }
+            }
+        });
+    }
+
+    /// Keep feed icons under `dir`
+    pub fn set_icon_dir(&self, dir: PathBuf) {
+        *self.icon_dir.write() = Some(dir);
+    }
+
+    /// Local copy of a feed's icon, whether or not it has been downloaded yet
+    fn icon_path(&self, feed_name: &str) -> Option<PathBuf> {
+        let hash = blake3::hash(feed_name.as_bytes()).to_hex();
+        Some(self.icon_dir.read().as_ref()?.join(&hash[..16]))
+    }
+
+    /// Download the icon found by the last refresh of `feed_name` in the
+    /// background, unless a copy younger than `ICON_MAX_AGE` is cached, and
+    /// name `.meta/icon.*` after the type of image it turns out to be
+    fn prefetch_icon(&self, feed_name: &str) {
+        let Some(runtime) = self.runtime.read().clone() else {
+            return;
+        };
+        let Some(path) = self.icon_path(feed_name) else {
+            return;
+        };
+        let fresh = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age < ICON_MAX_AGE);
+        let url = self.repository.read().as_ref().and_then(|repo| repo.feed_icon_url(feed_name));
+        let url = url.filter(|_| !fresh);
+        if url.is_none() && !fresh {
+            return;
+        }
+
+        let fetcher = self.page_fetcher();
+        let inode_manager = Arc::clone(&self.inode_manager);
+        let feed_name = feed_name.to_string();
+        runtime.spawn(async move {
+            if let Some(url) = url {
+                if let Err(e) = fetcher.download_capped(&url, &path, MAX_ICON_BYTES).await {
+                    debug!("Failed to download icon {}: {}", url, e);
+                    return;
+                }
+            }
+            name_icon(&inode_manager, &feed_name, &path);
+        });
+    }
+
+    /// Summarize articles with `summaries` as their feeds load
+    pub fn set_summaries(&self, summaries: Arc<SummaryCache>) {
+        *self.summaries.write() = Some(summaries);
+    }
+
+    /// Delete the stored summaries of articles other than `article_ids`,
+    /// e.g. those retention removed. Returns how many were deleted.
+    pub fn retain_summaries<'a>(&self, article_ids: impl IntoIterator<Item = &'a str>) -> Result<usize> {
+        match self.summaries.read().clone() {
+            Some(summaries) => summaries.retain(article_ids),
+            None => Ok(0),
+        }
+    }
+
+    /// Pick up the stored summaries of a feed's articles before it is listed
+    fn load_summaries(&self, feed: &Feed) {
+        let Some(summaries) = self.summaries.read().clone() else {
+            return;
+        };
+        for article in &feed.articles {
+            if !self.inode_manager.has_summary(&article.id) {
+                if let Some(summary) = summaries.cached(&article.id) {
+                    self.inode_manager.insert_summary(&article.id, &summary);
+                }
+            }
+        }
+    }
+
+    /// Summarize a feed's articles that have no summary yet in the
+    /// background, adding each to the mount as it is ready
+    fn summarize_missing(&self, feed_name: &str) {
+        let (Some(summaries), Some(runtime)) = (self.summaries.read().clone(), self.runtime.read().clone()) else {
+            return;
+        };
+        let missing: Vec<Article> = match self.feeds.read().get(feed_name) {
+            Some(feed) => feed.articles.iter()
+                .filter(|article| !self.inode_manager.has_summary(&article.id))
+                .map(|article| self.inode_manager.with_bodies(article).into_owned())
+                .collect(),
+            None => return,
+        };
+        if missing.is_empty() {
+            return;
+        }
+
+        let inode_manager = Arc::clone(&self.inode_manager);
+        runtime.spawn(async move {
+            for article in missing {
+                match summaries.summarize(&article).await {
+                    Ok(Some(summary)) => inode_manager.set_summary(&article.id, &summary),
+                    Ok(None) => {}
+                    Err(e) => warn!("Failed to summarize {}: {}", article.title, e),
+                }
+            }
+        });
+    }
+
+    /// Contents of a feed's `.meta/icon.*`; empty until downloaded
+    fn feed_icon(&self, feed_name: &str) -> Vec<u8> {
+        self.icon_path(feed_name)
+            .and_then(|path| std::fs::read(path).ok())
+            .unwrap_or_default()
+    }
+
+    /// Read part of an article image. One the refresh did not download is
+    /// fetched in the background, and reads fail with `EAGAIN` until it is
+    /// in place, or with `EFBIG` if it is over `MAX_IMAGE_BYTES`. Returns
+    /// the errno to reply with on failure.
+    pub fn read_asset(&self, node: &VNode, offset: u64, size: u32) -> std::result::Result<Vec<u8>, i32> {
+        let NodeType::AssetFile(_, url) = &node.node_type else {
+            return Err(EINVAL);
+        };
+        let path = self.asset_path(url).ok_or(EIO)?;
+        if !path.exists() {
+            if self.oversized_images.read().contains(url) {
+                return Err(EFBIG);
+            }
+            let download = self.start_download(url, &path, Some(MAX_IMAGE_BYTES))?;
+            let progress = download.progress.lock();
+            match progress.finished {
+                Some(Err(errno)) => return Err(errno),
+                Some(Ok(_)) => {}
+                None => return Err(EAGAIN),
+            }
+        }
+        FileContent::open(&path).and_then(|file| file.read_at(offset, size)).map_err(|e| {
+            warn!("Failed to read {}: {}", path.display(), e);
+            EIO
+        })
+    }
+
+    /// Read part of an enclosure: from the server with a range request, or
+    /// in `download` mode from the local copy, downloading it on first use.
+    /// A server without range support is downloaded from once, too.
+    /// Returns the errno to reply with on failure.
+    pub fn read_enclosure(&self, node: &VNode, offset: u64, size: u32) -> std::result::Result<Vec<u8>, i32> {
+        let NodeType::EnclosureFile(_, article, index) = &node.node_type else {
+            return Err(EINVAL);
+        };
+        let enclosure = article.enclosures.get(*index).ok_or(ENOENT)?;
+        // HTTP needs the tokio runtime
+        if self.runtime.read().is_none() {
+            return Err(EIO);
+        }
+
+        let path = match self.enclosure_path(enclosure) {
+            Some(path) => path,
+            None => {
+                let copy = self.streamed_copy_path(enclosure);
+                let copied = copy.as_ref()
+                    .is_some_and(|copy| copy.exists() || self.downloads.lock().contains_key(copy));
+                if !copied {
+                    let streamed = self.block_on(self.read_fetcher().fetch_range(&enclosure.url, offset, size as u64))
+                        .map_err(|e| {
+                            warn!("Failed to stream {}: {}", enclosure.url, e);
+                            EIO
+                        })?;
+                    if let Some(data) = streamed {
+                        return Ok(data);
+                    }
+                    debug!("{} does not support range requests; downloading it", enclosure.url);
+                }
+                copy.ok_or(EIO)?
+            }
+        };
+        self.read_download(node, &enclosure.url, &path, offset, size)
+    }
+
+    /// Read part of the local copy of `url` at `path`. A missing copy is
+    /// downloaded in the background, and the read waits only until the
+    /// bytes it asks for have arrived.
+    fn read_download(&self, node: &VNode, url: &str, path: &std::path::Path, offset: u64, size: u32) -> std::result::Result<Vec<u8>, i32> {
+        let read = |path: &std::path::Path| FileContent::open(path).and_then(|file| file.read_at(offset, size));
+        if !path.exists() {
+            let download = self.start_download(url, path, None)?;
+            let mut progress = download.progress.lock();
+            let wanted = offset.saturating_add(size as u64);
+            while progress.finished.is_none() && progress.received < wanted {
+                download.changed.wait(&mut progress);
+            }
+            match progress.finished {
+                Some(Ok(length)) => self.inode_manager.update_node_size(node.ino, length),
+                Some(Err(errno)) => return Err(errno),
+                None => {
+                    drop(progress);
+                    // The download may complete, and move the file, meanwhile
+                    let partial = crate::feed::fetcher::partial_path(path);
+                    if let Ok(data) = read(&partial) {
+                        return Ok(data);
+                    }
+                }
+            }
+        }
+        read(path).map_err(|e| {
+            warn!("Failed to read {}: {}", path.display(), e);
+            EIO
+        })
+    }
+
+    /// The download of `url` to `path`, started in the background unless one
+    /// is already running, and given up above `max` bytes if set
+    fn start_download(&self, url: &str, path: &std::path::Path, max: Option<u64>) -> std::result::Result<Arc<Download>, i32> {
+        // HTTP needs the tokio runtime
+        let runtime = self.runtime.read().clone().ok_or(EIO)?;
+        let mut running = self.downloads.lock();
+        if let Some(download) = running.get(path) {
+            return Ok(Arc::clone(download));
+        }
+        let download = Arc::new(Download::default());
+        running.insert(path.to_path_buf(), Arc::clone(&download));
+        drop(running);
+
+        let fetcher = self.read_fetcher();
+        let downloads = Arc::clone(&self.downloads);
+        let oversized = Arc::clone(&self.oversized_images);
+        let (url, path, task) = (url.to_string(), path.to_path_buf(), Arc::clone(&download));
+        runtime.spawn(async move {
+            let result = fetcher.download_capped_with(&url, &path, max, |received| {
+                task.progress.lock().received = received;
+                task.changed.notify_all();
+            }).await;
+            let result = match result {
+                Ok(length) => {
+                    debug!("Downloaded {} bytes from {}", length, url);
+                    Ok(length)
+                }
+                Err(crate::error::Error::ResourceExhausted(e)) => {
+                    warn!("Not downloading {}: {}", url, e);
+                    oversized.write().insert(url.clone());
+                    Err(EFBIG)
+                }
+                Err(e) => {
+                    warn!("Failed to download {}: {}", url, e);
+                    Err(EIO)
+                }
+            };
+            downloads.lock().remove(&path);
+            task.progress.lock().finished = Some(result);
+            task.changed.notify_all();
+        });
+        Ok(download)
+    }
+
+    /// Snapshot the content of a file being opened and return a handle that
+    /// `read_content` serves byte ranges from. Large content is spooled to
+    /// disk. `None` for files that are read some other way (enclosures).
+    pub fn open_content(&self, node: &VNode) -> Option<u64> {
+        let data: Arc<[u8]> = match &node.node_type {
+            NodeType::ArticleFile(_, _) => self.inode_manager.get_article_content(node.ino)?.into(),
+            NodeType::ConfigFile => self.config_content.read().as_bytes().into(),
+            NodeType::GeneratedFile(file) => self.generated_content(file),
+            _ => return None,
+        };
+        let fh = self.next_fh.fetch_add(1, Ordering::Relaxed);
+        let content = content::provider_for(data, &std::env::temp_dir());
+        self.open_files.write().insert(fh, OpenFile { ino: node.ino, content });
+        Some(fh)
+    }
+
+    /// Read from a handle returned by `open_content` or `open_control`;
+    /// `None` if it is unknown
+    pub fn read_content(&self, fh: u64, offset: u64, size: u32) -> Option<std::io::Result<Vec<u8>>> {
+        if let Some(write) = self.pending_writes.read().get(&fh) {
+            return Some(BytesContent::new(write.data.clone()).read_at(offset, size));
+        }
+        let provider = self.open_files.read().get(&fh).map(|file| Arc::clone(&file.content))?;
+        Some(provider.read_at(offset, size))
+    }
+
+    /// Drop the snapshot behind a handle once the file is closed
+    pub fn close_content(&self, fh: u64) {
+        let closed = self.open_files.write().remove(&fh).is_some();
+        self.pending_writes.write().remove(&fh);
+        if closed {
+            self.release_retired();
+        }
+    }
+
+    /// Extract a feed's articles with custom selectors, or the default ones
+    /// for `None`
+    pub fn set_content_selectors(&self, feed_name: &str, selectors: Option<ContentSelectors>) -> Result<()> {
+        self.inode_manager.set_content_selectors(feed_name, selectors)
+            .map_err(crate::error::Error::Fuse)
+    }
+
+    /// How much the mount holds, for `df`. The repository's share is as
+    /// last collected by `refresh_status`, so `statfs` never waits on it.
+    pub fn usage(&self) -> FsUsage {
+        let mut used_bytes = self.inode_manager.total_file_size();
+        if let Some(cache) = &self.status.read().cache {
+            used_bytes += cache.memory_usage_bytes as u64;
+        }
+        let limit_bytes = self.repository.read().as_ref()
+            .and_then(|repo| repo.retention().max_storage_size_mb)
+            .map(|mb| mb * 1024 * 1024);
+
+        FsUsage {
+            total_bytes: limit_bytes.unwrap_or(0).max(used_bytes),
+            used_bytes,
+            inodes: self.inode_manager.get_total_nodes() as u64,
+        }
+    }
+
+    /// Snapshot of every known feed's state and the repository's cache
+    pub fn status_report(&self) -> StatusReport {
+        let cache = self.repository.read().clone().and_then(|repo| {
+            match self.block_on(FeedRepository::get_stats(&repo)) {
+                Ok(stats) => Some(CacheReport::from_stats(&stats)),
+                Err(e) => {
+                    warn!("Failed to collect repository stats: {}", e);
+                    None
+                }
+            }
+        });
+        self.build_status_report(cache)
+    }
+
+    fn build_status_report(&self, cache: Option<CacheReport>) -> StatusReport {
+        let mut names: Vec<String> = self.feeds.read().keys()
+            .chain(self.loading_status.read().keys())
+            .cloned()
+            .collect();
+        names.sort();
+        names.dedup();
+
+        StatusReport {
+            generated_at: self.repository_clock().now_utc(),
+            feeds: names.iter().map(|name| self.feed_report(name)).collect(),
+            cache,
+            operations: self.op_stats.report(),
+        }
+    }
+
+    /// Collect the repository figures and render the status files afresh.
+    /// Run in the background; the status files serve what it last rendered.
+    pub async fn refresh_status(&self) {
+        let repository = self.repository.read().clone();
+        let cache = match repository {
+            Some(repo) => match FeedRepository::get_stats(&repo).await {
+                Ok(stats) => Some(CacheReport::from_stats(&stats)),
+                Err(e) => {
+                    warn!("Failed to collect repository stats: {}", e);
+                    self.status.read().cache.clone()
+                }
+            },
+            None => None,
+        };
+        let report = self.build_status_report(cache.clone());
+
+        let mut status = self.status.write();
+        status.json = Some(pretty_json(&report).into());
+        status.markdown = Some(render_status_markdown(&report).into_bytes().into());
+        status.cache = cache;
+    }
+
+    /// `status.json` or `status.md` as last rendered. Before the first
+    /// background refresh they are rendered here with whatever repository
+    /// figures were last collected.
+    fn status_file(&self, file: &GeneratedFile) -> Arc<[u8]> {
+        let pick = |status: &StatusCache| match file {
+            GeneratedFile::StatusMarkdown => status.markdown.clone(),
+            _ => status.json.clone(),
+        };
+        if let Some(content) = pick(&self.status.read()) {
+            return content;
+        }
+
+        let report = self.build_status_report(self.status.read().cache.clone());
+        let mut status = self.status.write();
+        status.json.get_or_insert_with(|| pretty_json(&report).into());
+        status.markdown.get_or_insert_with(|| render_status_markdown(&report).into_bytes().into());
+        pick(&status).unwrap_or_else(|| Arc::from(&[][..]))
+    }
+
+    /// The repository's clock, which feed health is recorded with
+    fn repository_clock(&self) -> crate::clock::SharedClock {
+        self.repository.read().as_ref().map_or_else(|| self.clock(), |repo| repo.clock())
+    }
+
+    /// The `limit` articles that arrived last across all feeds, newest
+    /// first; articles fetched together are ordered by publication date
+    pub fn recent_articles(&self, limit: usize) -> Vec<RecentArticle> {
+        let feeds = self.feeds.read();
+        let mut articles: Vec<(&String, &Arc<Article>)> = feeds.iter()
+            .flat_map(|(name, feed)| feed.articles.iter().map(move |article| (name, article)))
+            .collect();
+        articles.sort_by_key(|(_, article)| std::cmp::Reverse((article.cached_at, article.published)));
+        articles.into_iter()
+            .take(limit)
+            .map(|(name, article)| RecentArticle {
+                feed: name.clone(),
+                id: article.id.clone(),
+                title: article.title.clone(),
+                link: article.link.clone(),
+                published: article.published,
+                cached_at: article.cached_at,
+                read: self.is_article_read(article),
+            })
+            .collect()
+    }
+
+    /// Every article of a mounted feed with the path of its file, or
+    /// `None` if the feed is not mounted
+    pub fn feed_articles(&self, feed_name: &str) -> Option<Vec<ListedArticle>> {
+        let feeds = self.feeds.read();
+        let feed = feeds.get(feed_name)?;
+        Some(feed.articles.iter()
+            .map(|article| ListedArticle {
+                id: article.id.clone(),
+                title: article.title.clone(),
+                published: article.published,
+                read: self.is_article_read(article),
+                path: self.inode_manager.find_article_nodes(feed_name, &article.id).iter()
+                    .find(|node| matches!(node.node_type, NodeType::ArticleFile(..)))
+                    .and_then(|node| self.inode_manager.path_of(node.ino))
+                    .map(PathBuf::from),
+            })
+            .collect())
+    }
+
+    /// Current state of a single feed
+    pub fn feed_report(&self, name: &str) -> FeedReport {
+        let feeds = self.feeds.read();
+        let feed = feeds.get(name);
+        let repository = self.repository.read().clone();
+        let (health, last_refresh, status_history) = repository.as_ref()
+            .map(|repo| (repo.feed_health(name), repo.last_refresh_result(name), repo.status_history(name)))
+            .unwrap_or_default();
+        // The repository follows refreshes as they run; the mounted copy
+        // only knows the status it was stored with
+        let current = repository.as_ref()
+            .and_then(|repo| repo.feed_status(name))
+            .or_else(|| feed.map(|f| f.feed.status.clone()));
+        let (status, error) = match (self.loading_status.read().get(name), current) {
+            (_, Some(FeedStatus::Disabled)) => ("disabled", None),
+            (Some(FeedLoadingStatus::Loading), _) => ("loading", None),
+            (Some(FeedLoadingStatus::Error(e)), _) => ("error", Some(e.clone())),
+            (_, Some(FeedStatus::Error(e))) => ("error", Some(e)),
+            (_, Some(FeedStatus::Updating)) => ("updating", None),
+            _ => ("active", None),
+        };
+        let articles = feed.map(|f| f.articles.as_slice()).unwrap_or_default();
+
+        FeedReport {
+            name: name.to_string(),
+            url: feed.map(|f| f.feed.url.clone()),
+            title: feed.and_then(|f| f.feed.title.clone()),
+            status: status.to_string(),
+            articles: articles.len(),
+            unread: articles.iter().filter(|a| !self.is_article_read(a)).count(),
+            last_updated: feed.and_then(|f| f.feed.last_updated),
+            last_success: health.last_success,
+            last_failure: health.last_failure,
+            fetch_latency_ms: health.last_fetch_duration.map(|d| d.as_millis() as u64),
+            consecutive_failures: health.consecutive_failures,
+            error: error.or(health.last_error),
+            last_refresh,
+            status_history,
+        }
+    }
+
+    /// Record the `[feeds]` entry shown in a feed's `.meta/config.toml`
+    pub fn set_feed_config(&self, feed_name: &str, content: String) {
+        self.feed_configs.write().insert(feed_name.to_string(), content);
+    }
+
+    /// Render one of the generated meta files
+    pub fn generated_content(&self, file: &GeneratedFile) -> Arc<[u8]> {
+        let content = match file {
+            GeneratedFile::StatusJson | GeneratedFile::StatusMarkdown => return self.status_file(file),
+            GeneratedFile::FeedConfig(name) => self.feed_configs.read().get(name)
+                .cloned()
+                .unwrap_or_else(|| format!("# No configuration recorded for {}\n", name))
+                .into_bytes(),
+            GeneratedFile::FeedXml(name) => {
+                let url = self.feeds.read().get(name).map(|f| f.feed.url.clone());
+                let repository = self.repository.read().clone();
+                // Shared with the repository rather than copied
+                return url.zip(repository)
+                    .and_then(|(url, repo)| repo.raw_feed(name, &url))
+                    .unwrap_or_else(|| Arc::from(&[][..]));
+            }
+            GeneratedFile::FeedStats(name) => pretty_json(&self.feed_report(name)),
+            GeneratedFile::FeedEpub(name) => return self.feed_epub(name),
+            GeneratedFile::FeedIcon(name) => self.feed_icon(name),
+            GeneratedFile::FeedError(name) => self.feed_errors.read().get(name)
+                .cloned()
+                .unwrap_or_default()
+                .into_bytes(),
+            GeneratedFile::FeedList => match self.load_config() {
+                Ok(config) => config.feeds_toml().unwrap_or_default().into_bytes(),
+                Err(_) => Vec::new(),
+            },
+            GeneratedFile::Refresh => Vec::new(),
+        };
+        content.into()
+    }
+
+    /// A feed's articles as an EPUB book. Building one renders every article,
+    /// so it is built when the file is opened, not when it is listed, and kept
+    /// until its inputs change: the articles, the feed title or the extractor.
+    fn feed_epub(&self, feed_name: &str) -> Arc<[u8]> {
+        let articles = self.inode_manager.feed_articles(feed_name);
+        let title = self.feeds.read().get(feed_name)
+            .and_then(|feed| feed.feed.title.clone())
+            .unwrap_or_else(|| feed_name.to_string());
+        let extractor = self.inode_manager.extractor(feed_name);
+        let mut hasher = DefaultHasher::new();
+        (&title, Arc::as_ptr(&extractor)).hash(&mut hasher);
+        for article in &articles {
+            (&article.id, &article.title, &article.content, &article.description).hash(&mut hasher);
+            (&article.content_ref, &article.description_ref).hash(&mut hasher);
+        }
+        let fingerprint = hasher.finish();
+
+        if let Some(cached) = self.epubs.read().get(feed_name) {
+            if cached.fingerprint == fingerprint {
+                return Arc::clone(&cached.book);
+            }
+        }
+
+        let articles: Vec<Arc<Article>> = articles.iter()
+            .map(|article| match self.inode_manager.with_bodies(article) {
+                Cow::Borrowed(_) => Arc::clone(article),
+                Cow::Owned(restored) => Arc::new(restored),
+            })
+            .collect();
+        let book: Arc<[u8]> = epub::feed_epub(feed_name, &title, &articles, &extractor).into();
+        self.epubs.write().insert(feed_name.to_string(), CachedEpub { fingerprint, book: Arc::clone(&book) });
+        book
+    }
+
+    /// Fetch article pages, enclosures and images with `fetcher`, e.g. one
+    /// set up for the configured proxy
+    pub fn set_page_fetcher(&self, fetcher: FeedFetcher) {
+        *self.page_fetcher.write() = fetcher;
+    }
+
+    /// How links are compared across feeds: as the repository rewrites them
+    fn url_normalizer(&self) -> UrlNormalizer {
+        self.repository.read().as_ref().map(|repo| repo.url_normalizer().clone()).unwrap_or_default()
+    }
+
+    fn page_fetcher(&self) -> FeedFetcher {
+        self.page_fetcher.read().clone()
+    }
+
+    /// Fetcher for reads that a FUSE callback waits on, which skip the
+    /// per-host pacing background fetches get
+    fn read_fetcher(&self) -> FeedFetcher {
+        self.page_fetcher().without_pacing()
+    }
+
+    /// Config file that `.rss-fuse/feeds.toml` reads and writes, and that
+    /// `.rss-fuse/refresh` looks feed URLs up in
+    pub fn set_config_path(&self, path: PathBuf) {
+        *self.config_path.write() = Some(path);
+    }
+
+    fn load_config(&self) -> std::result::Result<Config, i32> {
+        let path = self.config_path.read().clone().ok_or(EIO)?;
+        Config::load(&path).map_err(|e| {
+            warn!("Failed to load {}: {}", path.display(), e);
+            EIO
+        })
+    }
+
+    /// Whether `node` is a control file that accepts writes
+    fn is_writable_control(&self, node: &VNode) -> bool {
+        matches!(&node.node_type, NodeType::GeneratedFile(file) if file.is_control())
+            && self.inode_manager.config().control_files
+    }
+
+    /// Start writing to a control file. Without `truncate` the handle starts
+    /// from the current content, so partial writes edit it in place.
+    pub fn open_control(&self, node: &VNode, truncate: bool) -> Option<u64> {
+        let NodeType::GeneratedFile(file) = &node.node_type else {
+            return None;
+        };
+        if !self.is_writable_control(node) {
+            return None;
+        }
+        let data = if truncate { Vec::new() } else { self.generated_content(file).to_vec() };
+        let fh = self.next_fh.fetch_add(1, Ordering::Relaxed);
+        self.pending_writes.write().insert(fh, PendingWrite { file: file.clone(), data, dirty: false });
+        Some(fh)
+    }
+
+    /// Write to a handle returned by `open_control`. Feed names written to
+    /// `refresh` are queued for a background refresh; `feeds.toml` is saved
+    /// when the file is flushed.
+    pub fn write_control(&self, fh: u64, offset: u64, data: &[u8]) -> std::result::Result<u32, i32> {
+        let end = offset.checked_add(data.len() as u64)
+            .filter(|end| *end <= MAX_CONTROL_FILE_BYTES)
+            .ok_or(EFBIG)?;
+        let file = {
+            let mut pending = self.pending_writes.write();
+            let write = pending.get_mut(&fh).ok_or(EIO)?;
+            let end = end as usize;
+            if write.data.len() < end {
+                write.data.resize(end, 0);
+            }
+            write.data[offset as usize..end].copy_from_slice(data);
+            write.dirty = true;
+            write.file.clone()
+        };
+
+        if file == GeneratedFile::Refresh {
+            let names = std::str::from_utf8(data).map_err(|_| EINVAL)?;
+            self.refresh_by_name(names)?;
+        }
+        Ok(data.len() as u32)
+    }
+
+    /// Apply what was written to `feeds.toml` through `fh`. Content that is
+    /// not a valid feed list is rejected with `EINVAL` and the config left
+    /// alone.
+    pub fn flush_control(&self, fh: u64) -> std::result::Result<(), i32> {
+        let data = match self.pending_writes.write().get_mut(&fh) {
+            Some(write) if write.dirty && write.file == GeneratedFile::FeedList => {
+                write.dirty = false;
+                write.data.clone()
+            }
+            _ => return Ok(()),
+        };
+
+        let content = String::from_utf8(data).map_err(|_| EINVAL)?;
+        let mut config = self.load_config()?;
+        if let Err(e) = config.set_feeds_toml(&content) {
+            warn!("Rejected feed list written to feeds.toml: {}", e);
+            return Err(EINVAL);
+        }
+        let path = self.config_path.read().clone().ok_or(EIO)?;
+        config.save(&path).map_err(|e| {
+            error!("Failed to save {}: {}", path.display(), e);
+            EIO
+        })?;
+        info!("Saved feed list from feeds.toml ({} feeds)", config.feeds.len());
+        Ok(())
+    }
+
+    /// Queue the whitespace-separated feeds in `names` for a refresh. The
+    /// fetch happens in the background: a FUSE callback waiting on it would
+    /// hold up every other request to the mount.
+    fn refresh_by_name(&self, names: &str) -> std::result::Result<(), i32> {
+        let config = self.load_config()?;
+        if let Some(name) = names.split_whitespace().find(|name| !config.feeds.contains_key(*name)) {
+            warn!("Refresh requested for unknown feed {}", name);
+            return Err(EINVAL);
+        }
+
+        let requests = self.access_refresh.lock().requests.clone().ok_or(EIO)?;
+        for name in names.split_whitespace() {
+            info!("Refreshing {} on request", name);
+            requests.send(name.to_string()).map_err(|_| EIO)?;
+        }
+        Ok(())
+    }
+
+    /// Extended attributes (`user.rss.*`) of an article file, or the name
+    /// of the feed behind a feed directory, whose own name is a slug of it.
+    /// Other nodes have none. Absent metadata is left out rather than
+    /// reported empty.
+    pub fn xattrs(&self, node: &VNode) -> Vec<(&'static str, Vec<u8>)> {
+        let article = match &node.node_type {
+            NodeType::ArticleFile(_, article) => article,
+            NodeType::FeedDirectory(feed_name) => return vec![("user.rss.feed", feed_name.clone().into_bytes())],
+            _ => return Vec::new(),
+        };
+
+        let mut attrs = Vec::new();
+        if !article.link.is_empty() {
+            attrs.push(("user.rss.link", article.link.clone().into_bytes()));
+        }
+        if let Some(author) = &article.author {
+            attrs.push(("user.rss.author", author.clone().into_bytes()));
+        }
+        if let Some(published) = article.published {
+            attrs.push(("user.rss.published", published.to_rfc3339().into_bytes()));
+        }
+        if !article.tags.is_empty() {
+            attrs.push(("user.rss.tags", article.tags.join(",").into_bytes()));
+        }
+        if let Some(language) = self.inode_manager.language(&article.id) {
+            attrs.push(("user.rss.language", language.as_bytes().to_vec()));
+        }
+        attrs.push(("user.rss.read", self.is_article_read(article).to_string().into_bytes()));
+        attrs.push(("user.rss.starred", self.is_article_starred(article).to_string().into_bytes()));
+        attrs
+    }
+
+    /// Name a feed's article files after `template`, or after their titles
+    /// for `None`
+    pub fn set_filename_template(&self, feed_name: &str, template: Option<FilenameTemplate>) {
+        self.inode_manager.set_filename_template(feed_name, template);
+    }
+
+    /// List at most `limit` of a feed's newest articles from its next refresh
+    pub fn set_max_visible_articles(&self, feed_name: &str, limit: Option<usize>) {
+        self.inode_manager.set_max_visible_articles(feed_name, limit);
+    }
+
+    /// List only a feed's articles in `languages` (ISO 639-1 codes) from
+    /// its next refresh; `None` lists all
+    pub fn set_languages(&self, feed_name: &str, languages: Option<&[String]>) {
+        let languages = languages.map(|codes| codes.iter().filter_map(|code| language::normalize_code(code)).map(str::to_string).collect());
+        self.inode_manager.set_language_filter(feed_name, languages);
+    }
+
+    /// List a feed's directory as `dir_name`, used when its own name is
+    /// taken by another feed
+    pub fn set_feed_dir_name(&self, feed_name: &str, dir_name: &str) -> Result<()> {
+        self.inode_manager.set_feed_dir_name(feed_name, dir_name).map_err(crate::error::Error::Fuse)
+    }
+
+    /// Add a loading placeholder directory for a feed
+    pub fn add_loading_placeholder(&self, feed_name: &str) -> Result<()> {
+        // Update loading status
+        self.loading_status.write().insert(feed_name.to_string(), FeedLoadingStatus::Loading);
+        
+        // Create feed directory
+        if let Err(e) = self.inode_manager.create_feed_directory(feed_name) {
+            warn!("Failed to create feed directory for {}: {}", feed_name, e);
+            return Err(crate::error::Error::Fuse(e.to_string()));
+        }
+
+        // Add a loading placeholder file
+        let loading_content = format!(
+            "📡 Loading feed: {}\n\
+            ⏳ Please wait while we fetch the latest articles...\n\
+            🔄 This file will be replaced with actual articles once loading completes.\n\
+            \n\
+            Status: Fetching RSS feed\n\
+            Started: {}\n\
+            \n\
+            If this takes too long, check:\n\
+            • Your internet connection\n\
+            • The feed URL is correct\n\
+            • The RSS server is responding\n",
+            feed_name,
+            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        );
+
+        // Create a placeholder article
+        let placeholder_article = Arc::new(Article {
+            id: format!("loading-{}", feed_name),
+            title: format!("⏳ Loading {}...", feed_name),
+            link: "".to_string(),
+            description: Some("Feed is currently loading. Please wait...".to_string()),
+            content: Some(loading_content),
+            author: Some("RSS-FUSE".to_string()),
+            published: Some(chrono::Utc::now()),
+            updated: None,
+            tags: vec!["loading".to_string()],
+            read: false,
+            starred: false,
+            folder: None,
+            cached_at: Some(chrono::Utc::now()),
+            guid: None,
+            enclosures: Vec::new(),
+            content_ref: None,
+            description_ref: None,
+        });
+
+        if let Err(e) = self.inode_manager.create_article_file(feed_name, placeholder_article) {
+            warn!("Failed to create loading placeholder for {}: {}", feed_name, e);
+        }
+
+        // Update directory timestamps to show loading state change
+        self.refresh_directory_timestamps(feed_name);
+        self.invalidate_feed(feed_name);
+
+        Ok(())
+    }
+
+    /// Report a failed load. A feed with articles keeps them and gets an
+    /// `.error.txt` next to them; only an empty feed is replaced by an
+    /// error placeholder article.
+    pub fn add_error_placeholder(&self, feed_name: &str, error_message: &str) -> Result<()> {
+        // Update loading status
+        self.loading_status.write().insert(feed_name.to_string(), FeedLoadingStatus::Error(error_message.to_string()));
+        let error_content = render_error_report(feed_name, error_message);
+
+        let has_articles = self.feeds.read().get(feed_name).is_some_and(|feed| !feed.articles.is_empty());
+        if has_articles {
+            self.feed_errors.write().insert(feed_name.to_string(), error_content);
+            if let Err(e) = self.inode_manager.add_feed_error_file(feed_name) {
+                warn!("Failed to add error file for {}: {}", feed_name, e);
+            }
+            self.refresh_directory_timestamps(feed_name);
+            self.invalidate_feed(feed_name);
+            return Ok(());
+        }
+        
+        // Remove existing content
+        self.remove_feed(feed_name)?;
+        
+        // Create feed directory
+        if let Err(e) = self.inode_manager.create_feed_directory(feed_name) {
+            warn!("Failed to create feed directory for {}: {}", feed_name, e);
+            return Err(crate::error::Error::Fuse(e.to_string()));
+        }
+
+        // Create an error article
+        let error_article = Arc::new(Article {
+            id: format!("error-{}", feed_name),
+            title: format!("❌ Error loading {}", feed_name),
+            link: "".to_string(),
+            description: Some("Feed failed to load. See details inside.".to_string()),
+            content: Some(error_content),
+            author: Some("RSS-FUSE".to_string()),
+            published: Some(chrono::Utc::now()),
+            updated: None,
+            tags: vec!["error".to_string()],
+            read: false,
+            starred: false,
+            folder: None,
+            cached_at: Some(chrono::Utc::now()),
+            guid: None,
+            enclosures: Vec::new(),
+            content_ref: None,
+            description_ref: None,
+        });
+
+        if let Err(e) = self.inode_manager.create_article_file(feed_name, error_article) {
+            warn!("Failed to create error placeholder for {}: {}", feed_name, e);
+        }
+
+        // Update directory timestamps to show error state change
+        self.refresh_directory_timestamps(feed_name);
+        self.invalidate_feed(feed_name);
+
+        Ok(())
+    }
+
+    pub fn add_feed(&self, feed: Feed) -> Result<()> {
+        let feed_name = feed.name.clone();
+        
+        // Update loading status
+        self.loading_status.write().insert(feed_name.clone(), FeedLoadingStatus::Loaded);
+        
+        // Replace existing content (including placeholders)
+        self.replace_feed(feed);
+        
+        // Update directory timestamps to trigger file manager refresh
+        self.refresh_directory_timestamps(&feed_name);
+        self.invalidate_feed(&feed_name);
+        
+        Ok(())
+    }
+
+    /// Add or update feed content from cache (first load or background refresh)
+    /// This method is optimized for cache-first loading scenarios
+    pub fn add_feed_from_cache(&self, feed: Feed, is_from_cache: bool) -> Result<()> {
+        let feed_name = feed.name.clone();
+        
+        // Update loading status based on source
+        let status = if is_from_cache {
+            FeedLoadingStatus::Loaded // Will be updated when fresh content arrives
+        } else {
+            FeedLoadingStatus::Loaded
+        };
+        self.loading_status.write().insert(feed_name.clone(), status);
+        
+        // Check if we already have content for this feed
+        let has_existing_content = {
+            let feeds = self.feeds.read();
+            feeds.contains_key(&feed_name)
+        };
+        
+        // If this is fresh content updating cached content, be more selective about updates
+        if !is_from_cache && has_existing_content {
+            // This is a background refresh update - compare article counts
+            let existing_article_count = {
+                let feeds = self.feeds.read();
+                feeds.get(&feed_name).map(|f| f.articles.len()).unwrap_or(0)
+            };
+            
+            debug!("Updating {} with fresh content: {} -> {} articles", 
+                   feed_name, existing_article_count, feed.articles.len());
+        } else if is_from_cache {
+            debug!("Loading {} from cache: {} articles", feed_name, feed.articles.len());
+        }
+        
+        // Cached content dates from the refresh that fetched it, not from now
+        let fetched_at = if is_from_cache {
+            feed.articles.iter().filter_map(|a| a.cached_at).max()
+        } else {
+            None
+        };
+
+        // Replace existing content with the new content
+        self.load_summaries(&feed);
+        self.replace_feed(feed);
+        
+        // Update directory timestamps to trigger file manager refresh
+        self.refresh_directory_timestamps(&feed_name);
+        if !is_from_cache {
+            self.prefetch_assets(&feed_name);
+            self.prefetch_icon(&feed_name);
+        }
+        self.summarize_missing(&feed_name);
+        if let Some(fetched_at) = fetched_at {
+            if let Some(feed_node) = self.inode_manager.get_feed_directory(&feed_name) {
+                self.inode_manager.set_node_modified(feed_node.ino, fetched_at.into());
+            }
+        }
+        self.invalidate_feed(&feed_name);
+        
+        Ok(())
+    }
+
+    /// Present a feed's articles in a new directory that takes the place of
+    /// the current one (placeholders included) in one step, so a reader
+    /// listing or opening it mid-refresh never finds it missing or empty
+    fn replace_feed(&self, mut feed: Feed) {
+        let feed_name = feed.name.clone();
+        self.feed_errors.write().remove(&feed_name);
+        let staged = match self.inode_manager.stage_feed_directory(&feed_name) {
+            Ok(_) => true,
+            Err(e) => {
+                // Fall back to rebuilding the directory in place
+                warn!("Failed to stage directory for {}: {}", feed_name, e);
+                if let Err(e) = self.inode_manager.remove_feed_directory(&feed_name) {
+                    warn!("Failed to remove feed directory {}: {}", feed_name, e);
+                }
+                false
+            }
+        };
+
+        self.inode_manager.detect_languages(&feed.articles);
+        let scoring = self.inode_manager.config().scoring;
+        if !scoring.is_disabled() {
+            let scorer = Scorer::new(&scoring.keywords);
+            self.inode_manager.set_scores(feed.articles.iter().map(|article| (article.id.clone(), scorer.score(article))));
+        }
+
+        // Long bodies go to disk before the articles are copied into nodes
+        let store = self.inode_manager.content_store();
+        let pinned = store.as_ref().map(|store| store.spill_guard());
+        for article in &mut feed.articles {
+            self.inode_manager.spill_bodies(article);
+        }
+
+        // Add articles
+        // Numbered before hiding, so reading an article doesn't rename the rest
+        let feed = MountedFeed::new(feed);
+        self.mark_duplicates(&feed_name, &feed.articles);
+        let listing = feed.articles.iter().enumerate()
+            .filter(|(_, article)| !self.is_hidden(article))
+            .map(|(index, article)| (index + 1, Arc::clone(article)))
+            .collect();
+        self.inode_manager.create_feed_listing(&feed_name, listing);
+        if staged {
+            if let Err(e) = self.inode_manager.commit_feed_directory(&feed_name) {
+                warn!("Failed to swap in directory for {}: {}", feed_name, e);
+            }
+        } else if self.inode_manager.get_feed_directory(&feed_name).is_none() {
+            if let Err(e) = self.inode_manager.create_feed_directory(&feed_name) {
+                warn!("Failed to create feed directory for {}: {}", feed_name, e);
+            }
+        }
+
+        // Store feed data
+        self.feeds.write().insert(feed_name, feed);
+        drop(pinned);
+        self.release_retired();
+        self.prune_content();
+    }
+
+    /// Tell the inode manager which of a feed's articles other mounted feeds
+    /// carry too, as of now; feeds loaded later are noticed on its next
+    /// refresh
+    fn mark_duplicates(&self, feed_name: &str, articles: &[Arc<Article>]) {
+        if self.inode_manager.config().duplicates == DuplicateMode::Show {
+            return;
+        }
+        let mut index = dedup::StoryIndex::new(self.url_normalizer());
+        for (name, feed) in self.feeds.read().iter().filter(|(name, _)| *name != feed_name) {
+            for article in &feed.articles {
+                index.add(name, article);
+            }
+        }
+        let duplicates = articles.iter()
+            .filter_map(|article| {
+                let others = index.other_feeds(feed_name, article);
+                (!others.is_empty()).then(|| (article.id.clone(), others.into_iter().collect()))
+            })
+            .collect();
+        self.inode_manager.set_duplicates(feed_name, duplicates);
+    }
+
+    /// Let go of replaced feed directories no open file refers to any more
+    fn release_retired(&self) {
+        let in_use: HashSet<u64> = self.open_files.read().values().map(|file| file.ino).collect();
+        self.inode_manager.release_retired(&in_use);
+    }
+
+    pub fn remove_feed(&self, feed_name: &str) -> Result<()> {
+        // Remove the feed directory, its articles and any emptied groups
+        if let Err(e) = self.inode_manager.remove_feed_directory(feed_name) {
+            warn!("Failed to remove feed directory {}: {}", feed_name, e);
+        }
+
+        // Remove from feeds map
+        self.feeds.write().remove(feed_name);
+        self.inode_manager.set_duplicates(feed_name, HashMap::new());
+        self.invalidate_feed(feed_name);
+        
+        Ok(())
+    }
+
+    /// Drop the kernel's cached lookups along a feed's directory path. The
+    /// directory gets a new inode whenever it is rebuilt, so cached entries
+    /// would otherwise keep pointing at the old one.
+    fn invalidate_feed(&self, feed_name: &str) {
+        let Some(notifier) = self.notifier.read().clone() else {
+            return;
+        };
+
+        let mut parent = FUSE_ROOT_ID;
+        for dir_name in self.inode_manager.feed_dir_names(feed_name) {
+            notifier.invalidate(Invalidation::Inode(parent));
+            notifier.invalidate(Invalidation::Entry { parent, name: dir_name.clone().into() });
+            match self.inode_manager.get_node_by_name(parent, &dir_name) {
+                Some(node) => parent = node.ino,
+                None => break,
+            }
+        }
+    }
+
+    pub fn get_total_inodes(&self) -> usize {
+        self.inode_manager.get_total_nodes()
+    }
+
+    pub fn get_feeds_count(&self) -> usize {
+        self.feeds.read().len()
+    }
+
+    pub fn get_node(&self, ino: u64) -> Option<crate::fuse::inode::VNode> {
+        self.inode_manager.get_node(ino)
+    }
+
+    pub fn list_children(&self, parent_ino: u64) -> Vec<crate::fuse::inode::VNode> {
+        self.inode_manager.list_children(parent_ino)
+    }
+
+    pub fn get_article_content(&self, ino: u64) -> Option<Arc<str>> {
+        self.inode_manager.get_article_content(ino)
+    }
+
+    pub fn get_node_by_name(&self, parent_ino: u64, name: &str) -> Option<crate::fuse::inode::VNode> {
+        self.inode_manager.get_node_by_name(parent_ino, name)
+    }
+
+    pub fn get_loading_status(&self, feed_name: &str) -> Option<FeedLoadingStatus> {
+        self.loading_status.read().get(feed_name).cloned()
+    }
+
+    pub fn refresh_directory_timestamps(&self, feed_name: &str) {
+        // Update the feed directory's modification time
+        if let Some(feed_node) = self.inode_manager.get_feed_directory(feed_name) {
+            self.inode_manager.touch_directory_and_parents(feed_node.ino);
+        }
+        
+        // Also update root directory timestamp to ensure top-level changes are detected
+        self.inode_manager.touch_node_modified(FUSE_ROOT_ID);
+    }
+
+    pub fn refresh_all_directory_timestamps(&self) {
+        // Update all feed directories, including those nested in groups
+        let mut pending = self.inode_manager.list_children(FUSE_ROOT_ID);
+        while let Some(child) = pending.pop() {
+            match &child.node_type {
+                NodeType::FeedDirectory(feed_name) => self.refresh_directory_timestamps(feed_name),
+                NodeType::GroupDirectory(_) => pending.extend(self.inode_manager.list_children(child.ino)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Get TTL based on content state - dynamic content gets shorter cache times
+    pub fn get_ttl_for_node(&self, node: &crate::fuse::inode::VNode) -> Duration {
+        use std::time::Duration;
+        
+        match &node.node_type {
+            crate::fuse::inode::NodeType::FeedDirectory(feed_name) => {
+                match self.loading_status.read().get(feed_name) {
+                    Some(FeedLoadingStatus::Loading) => Duration::from_secs(0), // No cache while loading
+                    Some(FeedLoadingStatus::Error(_)) => Duration::from_secs(2), // Short cache for errors
+                    Some(FeedLoadingStatus::Loaded) => Duration::from_secs(30), // Longer cache for stable content
+                    None => Duration::from_secs(1), // Default for unconfigured feeds
+                }
+            },
+            crate::fuse::inode::NodeType::ArticleFile(feed_name, _) => {
+                match self.loading_status.read().get(feed_name) {
+                    Some(FeedLoadingStatus::Loading) => Duration::from_secs(0), // No cache while loading
+                    Some(FeedLoadingStatus::Error(_)) => Duration::from_secs(2), // Short cache for errors  
+                    Some(FeedLoadingStatus::Loaded) => Duration::from_secs(60), // Long cache for stable articles
+                    None => Duration::from_secs(1), // Default
+                }
+            },
+            crate::fuse::inode::NodeType::ViewDirectory(_, _) |
+            crate::fuse::inode::NodeType::SearchResults(_) |
+            crate::fuse::inode::NodeType::RecentDirectory(_) |
+            crate::fuse::inode::NodeType::TagsRoot |
+            crate::fuse::inode::NodeType::TagDirectory(_) |
+            crate::fuse::inode::NodeType::VirtualFeedDirectory(_) |
+            crate::fuse::inode::NodeType::GeneratedFile(_) => Duration::from_secs(0), // Computed contents
+            _ => Duration::from_secs(10), // Longer cache for static content (meta files, etc.)
+        }
+    }
+
+    pub fn update_config(&self, content: String) {
+        let content_len = content.len() as u64;
+        *self.config_content.write() = content;
+        
+        // Update the config file size
+        if let Some(config_node) = self.inode_manager.get_node_by_name(1, ".rss-fuse")
+            .and_then(|meta| self.inode_manager.get_node_by_name(meta.ino, "config.toml")) {
+            self.inode_manager.update_node_size(config_node.ino, content_len);
+        }
+    }
+
+    fn node_to_file_attr(&self, node: &crate::fuse::inode::VNode) -> FileAttr {
+        let kind = node.file_type;
+        let mut perm = match kind {
+            FileType::Directory => 0o755,
+            FileType::RegularFile => 0o644,
+            FileType::Symlink => 0o777,
+            _ => 0o644,
+        };
+
+        if let NodeType::ArticleFile(_, article) = &node.node_type {
+            let aging = self.inode_manager.config().article_aging;
+            if aging.marker == AgingMarker::Permissions && aging.is_aged(article.published, self.inode_manager.clock().now_utc()) {
+                perm = (aging.permissions & 0o7777) as u16;
+            }
+        }
+
+        // A downloaded enclosure's real size replaces the advertised one
+        if let NodeType::EnclosureFile(_, article, index) = &node.node_type {
+            let downloaded = article.enclosures.get(*index)
+                .and_then(|enclosure| self.enclosure_path(enclosure))
+                .and_then(|path| std::fs::metadata(path).ok());
+            if let Some(metadata) = downloaded {
+                return create_file_attr_with_times(
+                    node.ino, metadata.len(), kind, perm,
+                    node.accessed_time, node.modified_time, node.modified_time, node.created_time,
+                );
+            }
+        }
+
+        // Downloaded images are sized from their local copy
+        if let NodeType::AssetFile(_, url) = &node.node_type {
+            if let Some(metadata) = self.asset_path(url).and_then(|path| std::fs::metadata(path).ok()) {
+                return create_file_attr_with_times(
+                    node.ino, metadata.len(), kind, perm,
+                    node.accessed_time, node.modified_time, node.modified_time, node.created_time,
+                );
+            }
+        }
+
+        // Generated files are rendered to learn their size and are always new
+        if let NodeType::GeneratedFile(file) = &node.node_type {
+            let size = match file {
+                // Sized as last built; opening the file builds it afresh
+                GeneratedFile::FeedEpub(name) => self.epubs.read().get(name).map_or(0, |cached| cached.book.len()),
+                GeneratedFile::FeedIcon(name) => self.icon_path(name)
+                    .and_then(|path| std::fs::metadata(path).ok())
+                    .map_or(0, |metadata| metadata.len() as usize),
+                _ => self.generated_content(file).len(),
+            } as u64;
+            let now = SystemTime::now();
+            return create_file_attr_with_times(node.ino, size, kind, perm, now, now, now, node.created_time);
+        }
+
+        // Article files are rendered when first measured; later changes to
+        // the article render them afresh
+        let size = match &node.node_type {
+            NodeType::ArticleFile(_, _) => self.inode_manager.get_article_content(node.ino)
+                .map_or(node.size, |content| content.len() as u64),
+            _ => node.size,
+        };
+
+        create_file_attr_with_times(
+            node.ino, 
+            size, 
+            kind, 
+            perm,
+            node.accessed_time,
+            node.modified_time,
+            node.modified_time,
+            node.created_time,
+        )
+    }
+
+    /// Decide whether a mutating operation is permitted. Returns the errno to
+    /// reply with when it is not.
+    pub fn check_write(&self, op: WriteOp) -> std::result::Result<(), i32> {
+        match op {
+            // rm on an article marks it read
+            WriteOp::Unlink if self.inode_manager.config().delete_marks_read
+                && self.repository.read().is_some() => Ok(()),
+            // mv on a feed directory renames it, like the control files
+            // change the feed list
+            WriteOp::Rename if self.inode_manager.config().control_files => Ok(()),
+            WriteOp::Star if self.inode_manager.config().starred_dir
+                && self.repository.read().is_some() => Ok(()),
+            _ => {
+                debug!("rejecting {:?} on read-only filesystem", op);
+                Err(EROFS)
+            }
+        }
+    }
+
+    /// Errno for a mutating operation that has no handler of its own
+    fn write_errno(&self, op: WriteOp) -> i32 {
+        self.check_write(op).err().unwrap_or(EROFS)
+    }
+
+    /// What `write` on the open handle `fh` does: control files collect the
+    /// data, every other file is read-only
+    fn write_handle(&self, fh: u64, offset: u64, data: &[u8]) -> std::result::Result<u32, i32> {
+        if self.pending_writes.read().contains_key(&fh) {
+            return self.write_control(fh, offset, data);
+        }
+        Err(self.write_errno(WriteOp::Write))
+    }
+
+    /// What `unlink` of `name` in `parent` does: removing a link in
+    /// `starred/` unstars the article, removing an article marks it read
+    fn unlink_entry(&self, parent: u64, name: &OsStr) -> std::result::Result<(), i32> {
+        if self.is_starred_root(parent) {
+            self.check_write(WriteOp::Star)?;
+            return match self.lookup_node(parent, name) {
+                Some(node) => self.unstar_link(&node),
+                None => Err(ENOENT),
+            };
+        }
+        self.check_write(WriteOp::Unlink)?;
+
+        match self.lookup_node(parent, name) {
+            Some(node) => self.mark_article_read(&node),
+            None => Err(ENOENT),
+        }
+    }
+
+    /// Why `create` of `name` in `parent` fails; nothing can be created
+    fn create_errno(&self, _parent: u64, _name: &OsStr) -> i32 {
+        self.write_errno(WriteOp::Create)
+    }
+
+    /// Whether `open` flags request write access
+    fn open_flags_wants_write(&self, flags: i32) -> bool {
+        (flags & libc::O_ACCMODE) != libc::O_RDONLY
+            || (flags & (libc::O_TRUNC | libc::O_APPEND)) != 0
+    }
+
+    /// Errno for an `open` with the given flags, if the flags request write access
+    fn open_flags_error(&self, flags: i32) -> Option<i32> {
+        if self.open_flags_wants_write(flags) {
+            self.check_write(WriteOp::Write).err()
+        } else {
+            None
+        }
+    }
+
+    fn lookup_node(&self, parent: u64, name: &OsStr) -> Option<crate::fuse::inode::VNode> {
+        let name_str = name.to_str()?;
+        if let Some(parent_node) = self.inode_manager.get_node(parent) {
+            match parent_node.node_type {
+                NodeType::ViewDirectory(_, _) => self.refresh_view(&parent_node),
+                NodeType::RecentDirectory(_) => self.refresh_recent(&parent_node),
+                NodeType::TagsRoot | NodeType::TagDirectory(_) => self.refresh_tags(&parent_node),
+                NodeType::VirtualFeedDirectory(_) => self.refresh_virtual_feed(&parent_node),
+                // Looking up `.search/<query>` runs the query
+                NodeType::SearchRoot => return self.search(name_str, false),
+                _ => {}
+            }
+        }
+        self.inode_manager.get_node_by_name(parent, name_str)
+    }
+}
+
+/// File name for the local copy of an enclosure
+fn enclosure_file_name(enclosure: &Enclosure) -> String {
+    let key = blake3::hash(enclosure.url.as_bytes()).to_hex();
+    format!("{}.{}", key, enclosure.extension())
+}
+
+/// `.meta` file name for an icon, from the image type its first bytes
+/// show; `None` for anything that is not an image
+fn icon_file_name(data: &[u8]) -> Option<&'static str> {
+    let text = String::from_utf8_lossy(&data[..data.len().min(512)]).trim_start().to_ascii_lowercase();
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("icon.png")
+    } else if data.starts_with(b"\0\0\x01\0") {
+        Some("icon.ico")
+    } else if data.starts_with(b"\xff\xd8\xff") {
+        Some("icon.jpg")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("icon.gif")
+    } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+        Some("icon.webp")
+    } else if text.starts_with("<svg") || (text.starts_with("<?xml") && text.contains("<svg")) {
+        Some("icon.svg")
+    } else {
+        None
+    }
+}
+
+/// Name a downloaded icon's `.meta` file after its image type, discarding
+/// downloads that are not images, such as HTML error pages
+fn name_icon(inode_manager: &InodeManager, feed_name: &str, path: &Path) {
+    let Ok(data) = std::fs::read(path) else {
+        return;
+    };
+    match icon_file_name(&data) {
+        Some(name) => inode_manager.set_icon_name(feed_name, name),
+        None => {
+            debug!("Discarding icon of {}: not an image", feed_name);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Answer an xattr request: a `size` of 0 asks for the length only
+fn reply_xattr(reply: ReplyXattr, size: u32, data: &[u8]) {
+    if size == 0 {
+        reply.size(data.len() as u32);
+    } else if data.len() > size as usize {
+        reply.error(ERANGE);
+    } else {
+        reply.data(data);
+    }
+}
+
+fn pretty_json<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut json = serde_json::to_vec_pretty(value).unwrap_or_default();
+    json.push(b'\n');
+    json
+}
+
+/// What a failed load shows: the error placeholder article of an empty
+/// feed, or `.error.txt` beside a feed's articles
+fn render_error_report(feed_name: &str, error_message: &str) -> String {
+    format!(
+        "❌ Failed to load feed: {}\n\
+        \n\
+        Error: {}\n\
+        \n\
+        ⏰ Last attempt: {}\n\
+        \n\
+        📋 Troubleshooting:\n\
+        • Check your internet connection\n\
+        • Verify the feed URL is correct\n\
+        • Ensure the RSS server is accessible\n\
+        • Check RSS-FUSE logs for detailed error information\n\
+        \n\
+        🔄 The feed will be retried automatically on the next refresh cycle.\n\
+        \n\
+        💡 You can also try:\n\
+        • rss-fuse refresh {}\n\
+        • rss-fuse remove-feed {} && rss-fuse add-feed {} <new-url>\n",
+        feed_name,
+        error_message,
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        feed_name,
+        feed_name,
+        feed_name
+    )
+}
+
+fn render_status_markdown(report: &StatusReport) -> String {
+    let time = |t: Option<DateTime<Utc>>| {
+        t.map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string()).unwrap_or_else(|| "-".to_string())
+    };
+
+    let mut out = format!(
+        "# RSS-FUSE Status\n\nGenerated: {}\n\n## Feeds\n\n",
+        time(Some(report.generated_at))
+    );
+    if report.feeds.is_empty() {
+        out.push_str("No feeds loaded.\n");
+    } else {
+        out.push_str("| Feed | Status | Articles | Unread | Last updated | Failures | Error |\n");
+        out.push_str("|------|--------|----------|--------|--------------|----------|-------|\n");
+        for feed in &report.feeds {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} |\n",
+                feed.name,
+                feed.status,
+                feed.articles,
+                feed.unread,
+                time(feed.last_updated),
+                feed.consecutive_failures,
+                feed.error.as_deref().unwrap_or("-").replace('|', "\\|").replace('\n', " "),
+            ));
+        }
+    }
+
+    if let Some(cache) = &report.cache {
+        out.push_str(&format!(
+            "\n## Cache\n\n\
+            - Entries: {}\n\
+            - Memory: {} bytes\n\
+            - Hit rate: {:.1}% ({} hits, {} misses)\n\
+            - Stored: {} feeds, {} articles, {} bytes\n",
+            cache.entries,
+            cache.memory_usage_bytes,
+            cache.hit_rate * 100.0,
+            cache.hits,
+            cache.misses,
+            cache.stored_feeds,
+            cache.stored_articles,
+            cache.storage_size_bytes,
+        ));
+    }
+
+    if !report.operations.is_empty() {
+        out.push_str("\n## Operations\n\n");
+        out.push_str("| Operation | Calls | Avg | p95 | Max |\n");
+        out.push_str("|-----------|-------|-----|-----|-----|\n");
+        for op in &report.operations {
+            out.push_str(&format!(
+                "| {} | {} | {:.2}ms | {} | {:.2}ms |\n",
+                op.op, op.count, op.avg_ms(), op.quantile_label(0.95), op.max_ms,
+            ));
+        }
+    }
+    out
+}
+
+/// Scheduled refreshes update the mounted directories
+impl RefreshHook for RssFuseFilesystem {
+    fn feed_updated(&self, feed: Feed, from_cache: bool) {
+        let name = feed.name.clone();
+        if let Err(e) = self.add_feed_from_cache(feed, from_cache) {
+            error!("Failed to update feed {} in filesystem: {}", name, e);
+        }
+    }
+
+    fn feed_failed(&self, name: &str, error: &crate::error::Error) {
+        if let Err(e) = self.add_error_placeholder(name, &error.to_string()) {
+            error!("Failed to add error placeholder for {}: {}", name, e);
+        }
+    }
+}
+
+impl Filesystem for RssFuseFilesystem {
+    fn lookup(&mut self, _req: &Request, parent: u64, name: &OsStr, reply: ReplyEntry) {
+        let _op = self.op_stats.time("lookup", parent);
+        debug!("lookup(parent: {}, name: {:?})", parent, name);
+
+        match self.lookup_node(parent, name) {
+            Some(node) => {
+                let attr = self.node_to_file_attr(&node);
+                let ttl = self.get_ttl_for_node(&node);
+                reply.entry(&ttl, &attr, 0);
+            }
+            None => {
+                debug!("lookup: not found");
+                reply.error(ENOENT);
+            }
+        }
+    }
+
+    fn getattr(&mut self, _req: &Request, ino: u64, reply: ReplyAttr) {
+        let _op = self.op_stats.time("getattr", ino);
+        debug!("getattr(ino: {})", ino);
+
+        match self.inode_manager.get_node(ino) {
+            Some(node) => {
+                let attr = self.node_to_file_attr(&node);
+                let ttl = self.get_ttl_for_node(&node);
+                reply.attr(&ttl, &attr);
+            }
+            None => {
+                debug!("getattr: inode {} not found", ino);
+                reply.error(ENOENT);
+            }
+        }
+    }
+
+    fn readdir(
+        &mut self,
+        _req: &Request,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        mut reply: ReplyDirectory,
+    ) {
+        let _op = self.op_stats.time("readdir", ino);
+        debug!("readdir(ino: {}, offset: {})", ino, offset);
+
+        let node = match self.inode_manager.get_node(ino) {
+            Some(node) => node,
+            None => {
+                reply.error(ENOENT);
+                return;
+            }
+        };
+
+        if !node.is_directory() {
+            reply.error(ENOTDIR);
+            return;
+        }
+
+        // Virtual views and searches are recomputed at the start of each listing
+        if offset == 0 {
+            self.refresh_view(&node);
+            self.refresh_search(&node);
+            self.refresh_recent(&node);
+            self.refresh_tags(&node);
+            self.refresh_virtual_feed(&node);
+            self.refresh_if_stale(&node);
+        }
+
+        let mut entries = vec![
+            (1, FileType::Directory, ".".to_string()),
+            (node.parent_ino, FileType::Directory, "..".to_string()),
+        ];
+
+        // Add child entries
+        let children = self.inode_manager.list_children(ino);
+        for child in children {
+            entries.push((child.ino, child.file_type, child.name));
+        }
+
+        // Apply offset
+        for (i, (child_ino, file_type, name)) in entries.into_iter().enumerate().skip(offset as usize) {
+            debug!("  entry: {} -> {} ({})", name, child_ino, i + 1);
+            
+            if reply.add(child_ino, (i + 1) as i64, file_type, &name) {
+                break; // Buffer is full
+            }
+        }
+
+        reply.ok();
+    }
+
+    fn statfs(&mut self, _req: &Request, ino: u64, reply: ReplyStatfs) {
+        let _op = self.op_stats.time("statfs", ino);
+        debug!("statfs");
+
+        let usage = self.usage();
+        let block_size = u64::from(STATFS_BLOCK_SIZE);
+        let blocks = (usage.total_bytes + block_size - 1) / block_size;
+        let free_blocks = blocks.saturating_sub((usage.used_bytes + block_size - 1) / block_size);
+        reply.statfs(
+            blocks,
+            free_blocks,
+            free_blocks,
+            usage.inodes,
+            0, // nodes only appear as feeds refresh
+            STATFS_BLOCK_SIZE,
+            STATFS_NAME_MAX,
+            STATFS_BLOCK_SIZE,
+        );
+    }
+
+    fn readlink(&mut self, _req: &Request, ino: u64, reply: ReplyData) {
+        let _op = self.op_stats.time("readlink", ino);
+        debug!("readlink(ino: {})", ino);
+
+        match self.inode_manager.get_node(ino).map(|node| node.node_type) {
+            Some(NodeType::ArticleLink(_, _, target)) => reply.data(target.as_bytes()),
+            Some(_) => reply.error(EINVAL),
+            None => reply.error(ENOENT),
+        }
+    }
+
+    fn open(&mut self, _req: &Request, ino: u64, flags: i32, reply: ReplyOpen) {
+        let _op = self.op_stats.time("open", ino);
+        debug!("open(ino: {}, flags: {})", ino, flags);
+
+        let node = match self.inode_manager.get_node(ino) {
+            Some(node) => node,
+            None => {
+                reply.error(ENOENT);
+                return;
+            }
+        };
+
+        if node.is_directory() {
+            reply.error(EISDIR);
+            return;
+        }
+
+        if self.open_flags_wants_write(flags) && self.is_writable_control(&node) {
+            let truncate = (flags & libc::O_TRUNC) != 0;
+            match self.open_control(&node, truncate) {
+                Some(fh) => reply.opened(fh, fuser::consts::FOPEN_DIRECT_IO),
+                None => reply.error(EIO),
+            }
+            return;
+        }
+
+        if let Some(errno) = self.open_flags_error(flags) {
+            reply.error(errno);
+            return;
+        }
+
+        // Fetched content changes the file size after the kernel last saw
+        // it, so bypass the page cache for such files; the same goes for
+        // generated files, which change on every read, and enclosures and
+        // images, whose length may be missing or wrong until downloaded
+        let open_flags = if matches!(node.node_type, NodeType::GeneratedFile(_) | NodeType::EnclosureFile(..) | NodeType::AssetFile(..))
+            || self.fetch_full_content(&node)
+        {
+            fuser::consts::FOPEN_DIRECT_IO
+        } else {
+            0
+        };
+
+        // Files without a content snapshot get handle 0 and are read directly
+        reply.opened(self.open_content(&node).unwrap_or(0), open_flags);
+    }
+
+    fn read(
+        &mut self,
+        _req: &Request,
+        ino: u64,
+        fh: u64,
+        offset: i64,
+        size: u32,
+        _flags: i32,
+        _lock: Option<u64>,
+        reply: ReplyData,
+    ) {
+        let _op = self.op_stats.time("read", ino);
+        debug!("read(ino: {}, offset: {}, size: {})", ino, offset, size);
+
+        // Opened files are served from the snapshot taken by open()
+        if let Some(result) = self.read_content(fh, offset as u64, size) {
+            match result {
+                Ok(data) => reply.data(&data),
+                Err(e) => {
+                    error!("Failed to read content of inode {}: {}", ino, e);
+                    reply.error(EIO);
+                }
+            }
+            return;
+        }
+
+        let node = match self.inode_manager.get_node(ino) {
+            Some(node) => node,
+            None => {
+                reply.error(ENOENT);
+                return;
+            }
+        };
+
+        if node.is_directory() {
+            reply.error(EISDIR);
+            return;
+        }
+
+        let content = match &node.node_type {
+            NodeType::ArticleFile(_, _) | NodeType::SummaryFile(_, _) => {
+                match self.inode_manager.get_article_content(ino) {
+                    Some(content) => content,
+                    None => {
+                        error!("Failed to get article content for inode {}", ino);
+                        reply.error(ENOENT);
+                        return;
+                    }
+                }
+            }
+            NodeType::ConfigFile => {
+                self.config_content.read().as_str().into()
+            }
+            NodeType::GeneratedFile(GeneratedFile::FeedEpub(name)) => {
+                let data = self.feed_epub(name);
+                let start = (offset as usize).min(data.len());
+                let end = (start + size as usize).min(data.len());
+                reply.data(&data[start..end]);
+                return;
+            }
+            NodeType::GeneratedFile(file) => {
+                let data = self.generated_content(file);
+                let start = (offset as usize).min(data.len());
+                let end = (start + size as usize).min(data.len());
+                reply.data(&data[start..end]);
+                return;
+            }
+            NodeType::EnclosureFile(..) => {
+                match self.read_enclosure(&node, offset as u64, size) {
+                    Ok(data) => reply.data(&data),
+                    Err(errno) => reply.error(errno),
+                }
+                return;
+            }
+            NodeType::AssetFile(..) => {
+                match self.read_asset(&node, offset as u64, size) {
+                    Ok(data) => reply.data(&data),
+                    Err(errno) => reply.error(errno),
+                }
+                return;
+            }
+            _ => {
+                warn!("Attempted to read unsupported file type: {:?}", node.node_type);
+                reply.error(EINVAL);
+                return;
+            }
+        };
+
+        let content_bytes = content.as_bytes();
+        let start = offset as usize;
+        let end = std::cmp::min(start + size as usize, content_bytes.len());
+
+        if start >= content_bytes.len() {
+            reply.data(&[]);
+            return;
+        }
+
+        let data = &content_bytes[start..end];
+        reply.data(data);
+    }
+
+    fn release(
+        &mut self,
+        _req: &Request,
+        ino: u64,
+        fh: u64,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        _flush: bool,
+        reply: fuser::ReplyEmpty,
+    ) {
+        let _op = self.op_stats.time("release", ino);
+        debug!("release(ino: {})", ino);
+        self.close_content(fh);
+        reply.ok();
+    }
+
+    fn setattr(
+        &mut self,
+        _req: &Request,
+        ino: u64,
+        mode: Option<u32>,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        size: Option<u64>,
+        atime: Option<TimeOrNow>,
+        mtime: Option<TimeOrNow>,
+        _ctime: Option<SystemTime>,
+        fh: Option<u64>,
+        _crtime: Option<SystemTime>,
+        _chgtime: Option<SystemTime>,
+        _bkuptime: Option<SystemTime>,
+        flags: Option<u32>,
+        reply: ReplyAttr,
+    ) {
+        let _op = self.op_stats.time("setattr", ino);
+        debug!("setattr(ino: {})", ino);
+
+        let node = match self.inode_manager.get_node(ino) {
+            Some(node) => node,
+            None => {
+                reply.error(ENOENT);
+                return;
+            }
+        };
+
+        // Truncating a control file empties what its handle will write
+        if self.is_writable_control(&node) && mode.is_none() && uid.is_none() && gid.is_none() && flags.is_none() {
+            if let (Some(size), Some(fh)) = (size, fh) {
+                if let Some(write) = self.pending_writes.write().get_mut(&fh) {
+                    write.data.truncate(size as usize);
+                    write.dirty = true;
+                }
+            }
+            let attr = self.node_to_file_attr(&node);
+            reply.attr(&self.get_ttl_for_node(&node), &attr);
+            return;
+        }
+
+        let changes_something = mode.is_some() || uid.is_some() || gid.is_some()
+            || size.is_some() || atime.is_some() || mtime.is_some() || flags.is_some();
+        if changes_something {
+            if let Err(errno) = self.check_write(WriteOp::Setattr) {
+                reply.error(errno);
+                return;
+            }
+        }
+
+        let attr = self.node_to_file_attr(&node);
+        reply.attr(&self.get_ttl_for_node(&node), &attr);
+    }
+
+    fn write(
+        &mut self,
+        _req: &Request,
+        ino: u64,
+        fh: u64,
+        offset: i64,
+        data: &[u8],
+        _write_flags: u32,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        reply: ReplyWrite,
+    ) {
+        let _op = self.op_stats.time("write", ino);
+        debug!("write(ino: {}, offset: {}, size: {})", ino, offset, data.len());
+        match self.write_handle(fh, offset as u64, data) {
+            Ok(written) => reply.written(written),
+            Err(errno) => reply.error(errno),
+        }
+    }
+
+    fn flush(&mut self, _req: &Request, ino: u64, fh: u64, _lock_owner: u64, reply: ReplyEmpty) {
+        let _op = self.op_stats.time("flush", ino);
+        debug!("flush(ino: {})", ino);
+        match self.flush_control(fh) {
+            Ok(()) => reply.ok(),
+            Err(errno) => reply.error(errno),
+        }
+    }
+
+    fn mknod(
+        &mut self,
+        _req: &Request,
+        parent: u64,
+        name: &OsStr,
+        _mode: u32,
+        _umask: u32,
+        _rdev: u32,
+        reply: ReplyEntry,
+    ) {
+        let _op = self.op_stats.time("mknod", parent);
+        debug!("mknod(parent: {}, name: {:?})", parent, name);
+        reply.error(self.write_errno(WriteOp::Mknod));
+    }
+
+    fn mkdir(
+        &mut self,
+        _req: &Request,
+        parent: u64,
+        name: &OsStr,
+        _mode: u32,
+        _umask: u32,
+        reply: ReplyEntry,
+    ) {
+        let _op = self.op_stats.time("mkdir", parent);
+        debug!("mkdir(parent: {}, name: {:?})", parent, name);
+        if self.is_search_root(parent) {
+            match name.to_str().and_then(|query| self.search(query, true)) {
+                Some(node) => {
+                    let attr = self.node_to_file_attr(&node);
+                    reply.entry(&self.get_ttl_for_node(&node), &attr, 0);
+                }
+                None => reply.error(EINVAL),
+            }
+            return;
+        }
+        reply.error(self.write_errno(WriteOp::Mkdir));
+    }
+
+    fn unlink(&mut self, _req: &Request, parent: u64, name: &OsStr, reply: ReplyEmpty) {
+        let _op = self.op_stats.time("unlink", parent);
+        debug!("unlink(parent: {}, name: {:?})", parent, name);
+        match self.unlink_entry(parent, name) {
+            Ok(()) => reply.ok(),
+            Err(errno) => reply.error(errno),
+        }
+    }
+
+    fn rmdir(&mut self, _req: &Request, parent: u64, name: &OsStr, reply: ReplyEmpty) {
+        let _op = self.op_stats.time("rmdir", parent);
+        debug!("rmdir(parent: {}, name: {:?})", parent, name);
+        if self.is_search_root(parent) {
+            // Forget a saved search
+            match name.to_str().and_then(|query| self.forget_search(query)) {
+                Some(Ok(())) => reply.ok(),
+                Some(Err(_)) => reply.error(EIO),
+                None => reply.error(ENOENT),
+            }
+            return;
+        }
+        reply.error(self.write_errno(WriteOp::Rmdir));
+    }
+
+    fn symlink(
+        &mut self,
+        _req: &Request,
+        parent: u64,
+        link_name: &OsStr,
+        target: &std::path::Path,
+        reply: ReplyEntry,
+    ) {
+        let _op = self.op_stats.time("symlink", parent);
+        debug!("symlink(parent: {}, name: {:?}, target: {:?})", parent, link_name, target);
+        if !self.is_starred_root(parent) {
+            reply.error(self.write_errno(WriteOp::Symlink));
+            return;
+        }
+        if let Err(errno) = self.check_write(WriteOp::Star) {
+            reply.error(errno);
+            return;
+        }
+
+        // ln -s <article> starred/ stars the article
+        let result = match (self.inode_manager.resolve_link_target(parent, target), link_name.to_str()) {
+            (Some(node), Some(name)) => self.star_into(&node, parent, name),
+            (None, _) => Err(ENOENT),
+            (_, None) => Err(EINVAL),
+        };
+        match result {
+            Ok(link) => {
+                let attr = self.node_to_file_attr(&link);
+                reply.entry(&self.get_ttl_for_node(&link), &attr, 0);
+            }
+            Err(errno) => reply.error(errno),
+        }
+    }
+
+    fn rename(
+        &mut self,
+        _req: &Request,
+        parent: u64,
+        name: &OsStr,
+        newparent: u64,
+        newname: &OsStr,
+        _flags: u32,
+        reply: ReplyEmpty,
+    ) {
+        let _op = self.op_stats.time("rename", parent);
+        debug!("rename(parent: {}, name: {:?}, newname: {:?})", parent, name, newname);
+
+        // Feed directories can be renamed; everything else is read-only
+        let node = self.lookup_node(parent, name);
+        match (node, newname.to_str()) {
+            (Some(node), Some(newname)) if matches!(node.node_type, NodeType::FeedDirectory(_)) => {
+                match self.rename_feed_directory(&node, newparent, newname) {
+                    Ok(()) => reply.ok(),
+                    Err(errno) => reply.error(errno),
+                }
+            }
+            _ => reply.error(self.write_errno(WriteOp::Rename)),
+        }
+    }
+
+    fn link(
+        &mut self,
+        _req: &Request,
+        ino: u64,
+        newparent: u64,
+        newname: &OsStr,
+        reply: ReplyEntry,
+    ) {
+        let _op = self.op_stats.time("link", ino);
+        debug!("link(ino: {}, newname: {:?})", ino, newname);
+        let node = self.inode_manager.get_node(ino);
+        let (Some(node), Some(name)) = (node, newname.to_str()) else {
+            reply.error(ENOENT);
+            return;
+        };
+        if !self.is_starred_root(newparent) {
+            reply.error(self.write_errno(WriteOp::Link));
+            return;
+        }
+        if let Err(errno) = self.check_write(WriteOp::Star) {
+            reply.error(errno);
+            return;
+        }
+
+        // A hard link into starred/ stars the article too, though what shows
+        // up there is a symlink; the kernel is answered with the linked file
+        match self.star_into(&node, newparent, name) {
+            Ok(_) => {
+                let node = self.inode_manager.get_node(ino).unwrap_or(node);
+                let attr = self.node_to_file_attr(&node);
+                reply.entry(&self.get_ttl_for_node(&node), &attr, 0);
+            }
+            Err(errno) => reply.error(errno),
+        }
+    }
+
+    fn create(
+        &mut self,
+        _req: &Request,
+        parent: u64,
+        name: &OsStr,
+        _mode: u32,
+        _umask: u32,
+        _flags: i32,
+        reply: ReplyCreate,
+    ) {
+        let _op = self.op_stats.time("create", parent);
+        debug!("create(parent: {}, name: {:?})", parent, name);
+        reply.error(self.create_errno(parent, name));
+    }
+
+    fn setxattr(
+        &mut self,
+        _req: &Request,
+        ino: u64,
+        name: &OsStr,
+        _value: &[u8],
+        _flags: i32,
+        _position: u32,
+        reply: ReplyEmpty,
+    ) {
+        let _op = self.op_stats.time("setxattr", ino);
+        debug!("setxattr(ino: {}, name: {:?})", ino, name);
+        reply.error(self.write_errno(WriteOp::Setxattr));
+    }
+
+    fn getxattr(&mut self, _req: &Request, ino: u64, name: &OsStr, size: u32, reply: ReplyXattr) {
+        let _op = self.op_stats.time("getxattr", ino);
+        debug!("getxattr(ino: {}, name: {:?}, size: {})", ino, name, size);
+
+        let Some(node) = self.inode_manager.get_node(ino) else {
+            reply.error(ENOENT);
+            return;
+        };
+        let value = self.xattrs(&node).into_iter()
+            .find(|(attr, _)| name.to_str() == Some(*attr))
+            .map(|(_, value)| value);
+
+        match value {
+            None => reply.error(ENOATTR),
+            Some(value) => reply_xattr(reply, size, &value),
+        }
+    }
+
+    fn listxattr(&mut self, _req: &Request, ino: u64, size: u32, reply: ReplyXattr) {
+        let _op = self.op_stats.time("listxattr", ino);
+        debug!("listxattr(ino: {}, size: {})", ino, size);
+
+        let Some(node) = self.inode_manager.get_node(ino) else {
+            reply.error(ENOENT);
+            return;
+        };
+        // NUL-terminated names, back to back
+        let names: Vec<u8> = self.xattrs(&node).into_iter()
+            .flat_map(|(attr, _)| attr.bytes().chain(std::iter::once(0)))
+            .collect();
+        reply_xattr(reply, size, &names);
+    }
+
+    fn removexattr(&mut self, _req: &Request, ino: u64, name: &OsStr, reply: ReplyEmpty) {
+        let _op = self.op_stats.time("removexattr", ino);
+        debug!("removexattr(ino: {}, name: {:?})", ino, name);
+        reply.error(self.write_errno(WriteOp::Removexattr));
+    }
+
+    fn fallocate(
+        &mut self,
+        _req: &Request,
+        ino: u64,
+        _fh: u64,
+        _offset: i64,
+        _length: i64,
+        _mode: i32,
+        reply: ReplyEmpty,
+    ) {
+        let _op = self.op_stats.time("fallocate", ino);
+        debug!("fallocate(ino: {})", ino);
+        reply.error(self.write_errno(WriteOp::Fallocate));
+    }
+
+    fn copy_file_range(
+        &mut self,
+        _req: &Request,
+        _ino_in: u64,
+        _fh_in: u64,
+        _offset_in: i64,
+        ino_out: u64,
+        _fh_out: u64,
+        _offset_out: i64,
+        _len: u64,
+        _flags: u32,
+        reply: ReplyWrite,
+    ) {
+        let _op = self.op_stats.time("copy_file_range", ino_out);
+        debug!("copy_file_range(ino_out: {})", ino_out);
+        reply.error(self.write_errno(WriteOp::CopyFileRange));
+    }
+}
+
+impl Default for RssFuseFilesystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::feed::{Article, ParsedArticle, FeedStatus};
+    use crate::fuse::inode::{DUPLICATES_DIR, SUMMARY_DIR};
     use chrono::Utc;
 
     fn create_test_feed() -> Feed {
@@ -600,93 +3154,1335 @@ mod tests {
             published: Some(Utc::now()),
             guid: Some("test-guid".to_string()),
             categories: vec!["test".to_string()],
+            enclosures: Vec::new(),
+        };
+
+        let article = Article::new(parsed_article, "test-feed");
+
+        Feed {
+            name: "test-feed".to_string(),
+            url: "https://example.com/feed.xml".to_string(),
+            title: Some("Test Feed".to_string()),
+            description: Some("A test feed".to_string()),
+            last_updated: Some(Utc::now()),
+            articles: vec![article],
+            status: FeedStatus::Active,
+        }
+    }
+
+    #[test]
+    fn test_filesystem_creation() {
+        let fs = RssFuseFilesystem::new();
+        
+        // Root should exist
+        let root = fs.inode_manager.get_node(FUSE_ROOT_ID).unwrap();
+        assert_eq!(root.ino, FUSE_ROOT_ID);
+        assert!(root.is_directory());
+    }
+
+    #[test]
+    fn test_add_feed() {
+        let fs = RssFuseFilesystem::new();
+        let feed = create_test_feed();
+        
+        fs.add_feed(feed).unwrap();
+        
+        // Should have feed directory
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        assert!(feed_node.is_directory());
+        
+        // Should have article file next to .meta
+        let children = fs.inode_manager.list_children(feed_node.ino);
+        assert_eq!(children.len(), 2);
+        assert!(children.iter().any(|n| n.is_file() && n.name == "Test Article.md"));
+        assert!(children.iter().any(|n| n.is_directory() && n.name == ".meta"));
+    }
+
+    #[test]
+    fn test_remove_feed() {
+        let fs = RssFuseFilesystem::new();
+        let feed = create_test_feed();
+        
+        fs.add_feed(feed).unwrap();
+        fs.remove_feed("test-feed").unwrap();
+        
+        // Feed directory should be gone
+        assert!(fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").is_none());
+    }
+
+    #[test]
+    fn test_refresh_invalidates_kernel_entries() {
+        let fs = RssFuseFilesystem::new();
+        fs.add_feed_from_cache(create_test_feed(), true).unwrap();
+
+        let (notifier, invalidations) = KernelNotifier::new();
+        fs.clone().set_notifier(notifier);
+        fs.add_feed_from_cache(create_test_feed(), false).unwrap();
+
+        let sent: Vec<Invalidation> = invalidations.try_iter().collect();
+        let entry = Invalidation::Entry { parent: FUSE_ROOT_ID, name: "test-feed".into() };
+        assert!(sent.contains(&entry));
+        assert!(sent.contains(&Invalidation::Inode(FUSE_ROOT_ID)));
+
+        fs.remove_feed("test-feed").unwrap();
+        assert!(invalidations.try_iter().any(|i| i == entry));
+    }
+
+    #[test]
+    fn test_cached_feed_dates_from_fetch() {
+        let fs = RssFuseFilesystem::new();
+        let mut feed = create_test_feed();
+        let fetched_at = Utc::now() - chrono::Duration::hours(5);
+        feed.articles[0].cached_at = Some(fetched_at);
+        let published = feed.articles[0].published.unwrap();
+
+        fs.add_feed_from_cache(feed.clone(), true).unwrap();
+        let dir = fs.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        assert_eq!(fs.node_to_file_attr(&dir).mtime, SystemTime::from(fetched_at));
+        let article = fs.get_node_by_name(dir.ino, "Test Article.md").unwrap();
+        let attr = fs.node_to_file_attr(&article);
+        assert_eq!(attr.mtime, SystemTime::from(published));
+        assert_eq!(attr.ctime, SystemTime::from(published));
+
+        // Fresh content dates from now
+        fs.add_feed_from_cache(feed, false).unwrap();
+        let dir = fs.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        assert!(fs.node_to_file_attr(&dir).mtime > SystemTime::from(fetched_at));
+    }
+
+    #[test]
+    fn test_config_update() {
+        let fs = RssFuseFilesystem::new();
+        let config_content = r#"
+[feeds]
+"test-feed" = "https://example.com/feed.xml"
+
+[settings]
+refresh_interval = 300
+"#.to_string();
+
+        fs.update_config(config_content.clone());
+        
+        // Config content should be updated
+        assert_eq!(*fs.config_content.read(), config_content);
+    }
+
+    #[test]
+    fn test_meta_structure() {
+        let fs = RssFuseFilesystem::new();
+        
+        // Should have .rss-fuse directory
+        let meta = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, ".rss-fuse").unwrap();
+        assert!(meta.is_directory());
+        
+        // Should have subdirectories and config file
+        let children = fs.inode_manager.list_children(meta.ino);
+        assert_eq!(children.len(), 5); // logs, cache, config.toml, status.json, status.md
+        
+        let names: Vec<String> = children.iter().map(|n| n.name.clone()).collect();
+        assert!(names.contains(&"logs".to_string()));
+        assert!(names.contains(&"cache".to_string()));
+        assert!(names.contains(&"config.toml".to_string()));
+        assert!(names.contains(&"status.json".to_string()));
+        assert!(names.contains(&"status.md".to_string()));
+    }
+
+    #[test]
+    fn test_status_files() {
+        let fs = RssFuseFilesystem::new();
+        fs.add_feed(create_test_feed()).unwrap();
+        fs.add_error_placeholder("broken", "connection refused").unwrap();
+
+        let meta = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, ".rss-fuse").unwrap();
+        let status = fs.inode_manager.get_node_by_name(meta.ino, "status.json").unwrap();
+        let attr = fs.node_to_file_attr(&status);
+        let content = String::from_utf8(fs.generated_content(&GeneratedFile::StatusJson).to_vec()).unwrap();
+        assert_eq!(attr.size, content.len() as u64);
+        assert_eq!(fs.get_ttl_for_node(&status), Duration::from_secs(0));
+
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let feeds = json["feeds"].as_array().unwrap();
+        assert_eq!(feeds.len(), 2);
+        assert_eq!(feeds[0]["name"], "broken");
+        assert_eq!(feeds[0]["status"], "error");
+        assert_eq!(feeds[0]["error"], "connection refused");
+        assert_eq!(feeds[1]["name"], "test-feed");
+        assert_eq!(feeds[1]["status"], "active");
+        assert_eq!(feeds[1]["articles"], 1);
+        assert_eq!(feeds[1]["unread"], 1);
+        assert!(json["cache"].is_null());
+
+        let markdown = String::from_utf8(fs.generated_content(&GeneratedFile::StatusMarkdown).to_vec()).unwrap();
+        assert!(markdown.contains("| broken | error | 0 | 0 | - | 0 | connection refused |"));
+        assert!(markdown.contains("| test-feed | active | 1 | 1 |"));
+    }
+
+    #[tokio::test]
+    async fn test_status_files_refresh_in_background() {
+        use crate::clock::{Clock, MockClock};
+
+        let clock = MockClock::default();
+        let fs = RssFuseFilesystem::new();
+        fs.set_repository(Repository::with_clock(
+            Arc::new(crate::storage::MemoryStorage::default()),
+            crate::storage::CacheConfig::default(),
+            clock.shared(),
+        ));
+        fs.add_feed(create_test_feed()).unwrap();
+        let status = || -> serde_json::Value {
+            serde_json::from_slice(&fs.generated_content(&GeneratedFile::StatusJson)).unwrap()
+        };
+
+        // Rendered once without waiting on the repository, then served as is
+        let first = status();
+        assert!(first["cache"].is_null());
+        assert_eq!(first["generated_at"], serde_json::json!(clock.now_utc()));
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(status(), first);
+
+        fs.refresh_status().await;
+        let refreshed = status();
+        assert!(refreshed["cache"].is_object());
+        assert_eq!(refreshed["generated_at"], serde_json::json!(clock.now_utc()));
+        let markdown = fs.generated_content(&GeneratedFile::StatusMarkdown);
+        assert!(String::from_utf8_lossy(&markdown).contains("| test-feed | active |"));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_on_access() {
+        use crate::clock::MockClock;
+        use crate::storage::FeedRepository;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <item><title>A</title><link>https://example.com/a</link></item>
+            </channel></rss>"#;
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss))
+            .mount(&server)
+            .await;
+
+        let clock = MockClock::default();
+        let repo = Repository::with_clock(
+            Arc::new(crate::storage::MemoryStorage::default()),
+            crate::storage::CacheConfig::default(),
+            clock.shared(),
+        );
+        let (feed, _) = repo.refresh_feed("t", &format!("{}/feed.xml", server.uri())).await.unwrap();
+
+        let mount = |refresh_on_access: u64| {
+            let fs = RssFuseFilesystem::with_config(&FilesystemConfig { refresh_on_access, ..FilesystemConfig::default() });
+            fs.set_repository(repo.clone());
+            fs.add_feed(feed.clone()).unwrap();
+            let (requests, pending) = tokio::sync::mpsc::unbounded_channel();
+            fs.set_refresh_requests(requests);
+            (fs, pending)
+        };
+        let listed = |fs: &RssFuseFilesystem, pending: &mut tokio::sync::mpsc::UnboundedReceiver<String>| {
+            let dir = fs.inode_manager.get_feed_directory("t").unwrap();
+            fs.refresh_if_stale(&dir);
+            fs.refresh_if_stale(&dir);
+            std::iter::from_fn(|| pending.try_recv().ok()).collect::<Vec<String>>()
+        };
+
+        // Fresh from the fetch
+        let (fs, mut pending) = mount(3600);
+        assert!(listed(&fs, &mut pending).is_empty());
+
+        // Stale two hours later, then asked for once per threshold
+        clock.advance(Duration::from_secs(2 * 3600));
+        assert_eq!(listed(&fs, &mut pending), ["t"]);
+        clock.advance(Duration::from_secs(1800));
+        assert!(listed(&fs, &mut pending).is_empty());
+        clock.advance(Duration::from_secs(1800));
+        assert_eq!(listed(&fs, &mut pending), ["t"]);
+
+        let (fs, mut pending) = mount(5 * 3600);
+        assert!(listed(&fs, &mut pending).is_empty());
+        let (fs, mut pending) = mount(0);
+        assert!(listed(&fs, &mut pending).is_empty());
+    }
+
+    #[test]
+    fn test_error_keeps_loaded_articles() {
+        let fs = RssFuseFilesystem::new();
+        fs.add_feed(create_test_feed()).unwrap();
+        fs.add_error_placeholder("test-feed", "connection reset").unwrap();
+
+        let names = |fs: &RssFuseFilesystem, feed: &str| -> Vec<String> {
+            let dir = fs.inode_manager.get_feed_directory(feed).unwrap();
+            let mut names: Vec<String> = fs.inode_manager.list_children(dir.ino).into_iter()
+                .filter(|node| !node.is_directory())
+                .map(|node| node.name)
+                .collect();
+            names.sort();
+            names
         };
+        assert_eq!(names(&fs, "test-feed"), [".error.txt", "Test Article.md"]);
+        let report = String::from_utf8(fs.generated_content(&GeneratedFile::FeedError("test-feed".to_string())).to_vec()).unwrap();
+        assert!(report.contains("Error: connection reset"));
+        assert_eq!(fs.feed_report("test-feed").status, "error");
+
+        // Failing again doesn't add a second file; the next load removes it
+        fs.add_error_placeholder("test-feed", "timed out").unwrap();
+        assert_eq!(names(&fs, "test-feed"), [".error.txt", "Test Article.md"]);
+        fs.add_feed(create_test_feed()).unwrap();
+        assert_eq!(names(&fs, "test-feed"), ["Test Article.md"]);
+        assert!(fs.generated_content(&GeneratedFile::FeedError("test-feed".to_string())).is_empty());
+
+        // A feed without articles shows the error in their place
+        fs.add_error_placeholder("broken", "connection refused").unwrap();
+        let broken = names(&fs, "broken");
+        assert_eq!(broken.len(), 1);
+        assert!(broken[0].contains("Error loading broken"));
+    }
+
+    #[test]
+    fn test_duplicate_stories() {
+        let feed = |name: &str, title: &str, link: &str| {
+            let mut feed = create_test_feed();
+            feed.name = name.to_string();
+            feed.articles[0].title = title.to_string();
+            feed.articles[0].link = link.to_string();
+            feed.articles[0].id = format!("{}-1", name);
+            feed
+        };
+        let names = |fs: &RssFuseFilesystem, feed: &str, folder: Option<&str>| -> Vec<String> {
+            let mut dir = fs.inode_manager.get_feed_directory(feed).unwrap();
+            if let Some(folder) = folder {
+                dir = fs.inode_manager.get_node_by_name(dir.ino, folder).unwrap();
+            }
+            fs.inode_manager.list_children(dir.ino).into_iter()
+                .map(|node| node.name)
+                .filter(|name| !name.starts_with('.'))
+                .collect()
+        };
+
+        for (mode, expected) in [(DuplicateMode::Hide, 0), (DuplicateMode::Group, 1), (DuplicateMode::Annotate, 1)] {
+            let fs = RssFuseFilesystem::with_config(&FilesystemConfig { duplicates: mode, ..FilesystemConfig::default() });
+            fs.add_feed(feed("hn", "A tiny RSS reader", "https://example.com/reader?utm_source=hn")).unwrap();
+            fs.add_feed(feed("reddit", "Look, a tiny RSS reader", "http://www.example.com/reader/")).unwrap();
+            fs.add_feed(feed("lobsters", "Unrelated story", "https://example.org/other")).unwrap();
+
+            assert_eq!(names(&fs, "hn", None), ["A tiny RSS reader.md"]);
+            let reddit = names(&fs, "reddit", None);
+            assert_eq!(reddit.len(), expected, "{:?}", mode);
+            match mode {
+                DuplicateMode::Group => {
+                    assert_eq!(reddit, [DUPLICATES_DIR]);
+                    assert_eq!(names(&fs, "reddit", Some(DUPLICATES_DIR)), ["Look, a tiny RSS reader.md"]);
+                }
+                DuplicateMode::Annotate => {
+                    let file = fs.inode_manager.get_node_by_name(
+                        fs.inode_manager.get_feed_directory("reddit").unwrap().ino,
+                        &reddit[0],
+                    ).unwrap();
+                    let content = fs.inode_manager.get_article_content(file.ino).unwrap();
+                    assert!(content.contains("also_in:\n- hn\n"), "{}", content);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_article_summaries() {
+        struct FirstWords;
+
+        #[async_trait::async_trait]
+        impl crate::content::summarizer::Summarizer for FirstWords {
+            async fn summarize(&self, _article: &Article, text: &str) -> Result<String> {
+                Ok(text.split_whitespace().take(2).collect::<Vec<_>>().join(" "))
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let summaries = Arc::new(SummaryCache::new(Arc::new(FirstWords), dir.path().to_path_buf()));
+        let feed = create_test_feed();
+        summaries.summarize(&feed.articles[0]).await.unwrap();
+
+        // Stored summaries are listed with the feed
+        let fs = RssFuseFilesystem::new();
+        fs.set_summaries(summaries);
+        fs.add_feed_from_cache(feed, true).unwrap();
+        let feed_dir = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let summary_dir = fs.inode_manager.get_node_by_name(feed_dir.ino, SUMMARY_DIR).unwrap();
+        let file = fs.inode_manager.get_node_by_name(summary_dir.ino, "Test Article.txt").unwrap();
+        assert_eq!(file.size, 16);
+        assert_eq!(&*fs.inode_manager.get_article_content(file.ino).unwrap(), "Test description");
+        let article = fs.inode_manager.get_node_by_name(feed_dir.ino, "Test Article.md").unwrap();
+        assert!(fs.inode_manager.get_article_content(article.ino).unwrap().contains("summary: Test description\n"));
+
+        // Ones finished later are added to the listed files
+        fs.inode_manager.set_summary("test-guid", "Shorter");
+        assert_eq!(&*fs.inode_manager.get_article_content(file.ino).unwrap(), "Shorter");
+        assert_eq!(fs.inode_manager.get_node(file.ino).unwrap().size, 7);
+        let content = fs.inode_manager.get_article_content(article.ino).unwrap();
+        assert!(content.contains("summary: Shorter\n"));
+        assert_eq!(fs.inode_manager.get_node(article.ino).unwrap().size, content.len() as u64);
+    }
+
+    #[test]
+    fn test_usage() {
+        let fs = RssFuseFilesystem::new();
+        fs.add_feed(create_test_feed()).unwrap();
+
+        let usage = fs.usage();
+        assert!(usage.used_bytes > 0);
+        assert_eq!(usage.total_bytes, usage.used_bytes);
+        assert_eq!(usage.inodes, fs.get_total_inodes() as u64);
+
+        let mut limits = crate::storage::StorageConfig::unlimited();
+        limits.max_storage_size_mb = Some(1);
+        fs.set_repository(crate::storage::RepositoryFactory::memory().with_retention(limits));
+        let files = fs.usage();
+        assert_eq!(files.total_bytes, 1024 * 1024);
+        assert_eq!(files.used_bytes, usage.used_bytes);
+
+        // The repository's share is counted as last collected in the background
+        fs.status.write().cache = Some(CacheReport {
+            entries: 1,
+            memory_usage_bytes: 4096,
+            hits: 0,
+            misses: 0,
+            hit_rate: 0.0,
+            stored_feeds: 1,
+            stored_articles: 1,
+            storage_size_bytes: 0,
+        });
+        assert_eq!(fs.usage().used_bytes, usage.used_bytes + 4096);
+        assert!(fs.usage().used_bytes < fs.usage().total_bytes);
+    }
 
-        let article = Article::new(parsed_article, "test-feed");
+    #[test]
+    fn test_aged_article_permissions() {
+        let mut config = FilesystemConfig::default();
+        config.article_aging.enabled = true;
+        let fs = RssFuseFilesystem::with_config(&config);
 
-        Feed {
-            name: "test-feed".to_string(),
-            url: "https://example.com/feed.xml".to_string(),
-            title: Some("Test Feed".to_string()),
-            description: Some("A test feed".to_string()),
-            last_updated: Some(Utc::now()),
-            articles: vec![article],
-            status: FeedStatus::Active,
+        let mut feed = create_test_feed();
+        let mut old = feed.articles[0].clone();
+        old.title = "Old Article".to_string();
+        old.published = Some(Utc::now() - chrono::Duration::days(90));
+        feed.articles.push(old);
+        fs.add_feed(feed).unwrap();
+
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        let fresh = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+        let old = fs.inode_manager.get_node_by_name(feed_node.ino, "Old Article.md").unwrap();
+        assert_eq!(fs.node_to_file_attr(&fresh).perm, 0o644);
+        assert_eq!(fs.node_to_file_attr(&old).perm, 0o400);
+    }
+
+    #[test]
+    fn test_write_operations_return_erofs() {
+        let fs = RssFuseFilesystem::new();
+        let ops = [
+            WriteOp::Write, WriteOp::Setattr, WriteOp::Mknod, WriteOp::Mkdir,
+            WriteOp::Unlink, WriteOp::Rmdir, WriteOp::Symlink, WriteOp::Rename,
+            WriteOp::Link, WriteOp::Create, WriteOp::Setxattr, WriteOp::Removexattr,
+            WriteOp::Fallocate, WriteOp::CopyFileRange, WriteOp::Star,
+        ];
+        for op in ops {
+            assert_eq!(fs.check_write(op), Err(EROFS), "{:?} should be rejected", op);
         }
+
+        // The handlers behind write, create and unlink reject them as well
+        fs.set_repository(crate::storage::RepositoryFactory::memory());
+        fs.add_feed(create_test_feed()).unwrap();
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        let article = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+
+        assert_eq!(fs.write_handle(article.ino, 0, b"text"), Err(EROFS));
+        assert_eq!(fs.create_errno(feed_node.ino, OsStr::new("new.md")), EROFS);
+        assert_eq!(fs.unlink_entry(feed_node.ino, OsStr::new("Test Article.md")), Err(EROFS));
+        assert!(fs.inode_manager.get_node(article.ino).is_some());
     }
 
     #[test]
-    fn test_filesystem_creation() {
+    fn test_unlink_marks_article_read() {
+        let fs = RssFuseFilesystem::with_config(&FilesystemConfig {
+            delete_marks_read: true,
+            ..FilesystemConfig::default()
+        });
+        let repo = crate::storage::RepositoryFactory::memory();
+        fs.set_repository(repo.clone());
+        assert_eq!(fs.check_write(WriteOp::Unlink), Ok(()));
+        assert_eq!(fs.check_write(WriteOp::Rmdir), Err(EROFS));
+
+        let feed = create_test_feed();
+        let article_id = feed.articles[0].id.clone();
+        fs.add_feed(feed.clone()).unwrap();
+
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        let article = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+        assert_eq!(fs.mark_article_read(&feed_node), Err(EISDIR));
+        fs.mark_article_read(&article).unwrap();
+
+        assert!(repo.is_article_read(&article_id));
+        assert!(fs.inode_manager.get_node(article.ino).is_none());
+
+        // Stays hidden when the feed is refreshed
+        fs.add_feed_from_cache(feed, false).unwrap();
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        assert!(!fs.inode_manager.list_children(feed_node.ino).iter().any(|n| n.is_file()));
+    }
+
+    #[test]
+    fn test_unlink_keeps_article_when_not_hiding() {
+        let config = FilesystemConfig { hide_read_articles: false, ..FilesystemConfig::default() };
+        let fs = RssFuseFilesystem::with_config(&config);
+        fs.set_repository(crate::storage::RepositoryFactory::memory());
+        fs.add_feed(create_test_feed()).unwrap();
+
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        let article = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+        fs.mark_article_read(&article).unwrap();
+        assert!(fs.inode_manager.get_node(article.ino).is_some());
+    }
+
+    #[test]
+    fn test_mark_read_unread_read() {
         let fs = RssFuseFilesystem::new();
-        
-        // Root should exist
-        let root = fs.inode_manager.get_node(FUSE_ROOT_ID).unwrap();
-        assert_eq!(root.ino, FUSE_ROOT_ID);
-        assert!(root.is_directory());
+        let repo = crate::storage::RepositoryFactory::memory();
+        fs.set_repository(repo.clone());
+
+        // Read as fetched, so hidden from the start
+        let mut feed = create_test_feed();
+        feed.articles[0].read = true;
+        let article_id = feed.articles[0].id.clone();
+        fs.add_feed(feed).unwrap();
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        assert!(fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").is_none());
+
+        fs.set_article_read("test-feed", &article_id, false).unwrap();
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        assert!(fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").is_some());
+        assert!(!repo.is_article_read(&article_id));
+        assert!(!fs.is_article_read(&fs.feeds.read()["test-feed"].articles[0]));
+
+        fs.set_article_read("test-feed", &article_id, true).unwrap();
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        assert!(fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").is_none());
+        assert!(repo.is_article_read(&article_id));
     }
 
     #[test]
-    fn test_add_feed() {
+    fn test_read_state_views() {
+        let config = FilesystemConfig {
+            read_state_views: true,
+            hide_read_articles: false,
+            ..FilesystemConfig::default()
+        };
+        let fs = RssFuseFilesystem::with_config(&config);
+        fs.set_repository(crate::storage::RepositoryFactory::memory());
+
+        let mut feed = create_test_feed();
+        let mut second = feed.articles[0].clone();
+        second.id = "second".to_string();
+        second.title = "Second Article".to_string();
+        feed.articles.push(second);
+        fs.add_feed(feed).unwrap();
+
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        let unread = fs.inode_manager.get_node_by_name(feed_node.ino, "unread").unwrap();
+        let read = fs.inode_manager.get_node_by_name(feed_node.ino, "read").unwrap();
+        let names = |node: &VNode| {
+            fs.refresh_view(node);
+            let mut names: Vec<String> = fs.inode_manager.list_children(node.ino).into_iter().map(|n| n.name).collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names(&unread), ["Second Article.md", "Test Article.md"]);
+        assert!(names(&read).is_empty());
+
+        // rm inside unread/ moves the article to read/
+        let target = fs.lookup_node(unread.ino, OsStr::new("Second Article.md")).unwrap();
+        fs.mark_article_read(&target).unwrap();
+        assert_eq!(names(&unread), ["Test Article.md"]);
+        assert_eq!(names(&read), ["Second Article.md"]);
+
+        // Not hiding, so the feed directory still lists it
+        assert!(fs.inode_manager.get_node_by_name(feed_node.ino, "Second Article.md").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_search_directory() {
+        use crate::storage::FeedRepository;
         let fs = RssFuseFilesystem::new();
+        assert!(fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, ".search").is_none());
+
+        let repo = crate::storage::RepositoryFactory::memory();
         let feed = create_test_feed();
-        
+        repo.save_feed(feed.clone()).await.unwrap();
+        fs.set_repository(repo);
         fs.add_feed(feed).unwrap();
-        
-        // Should have feed directory
-        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
-        assert!(feed_node.is_directory());
-        
-        // Should have article file
-        let children = fs.inode_manager.list_children(feed_node.ino);
+
+        let search = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, ".search").unwrap();
+        let results = fs.lookup_node(search.ino, OsStr::new("test description")).unwrap();
+        assert!(results.is_directory());
+        let children = fs.inode_manager.list_children(results.ino);
         assert_eq!(children.len(), 1);
-        assert!(children[0].is_file());
+        assert_eq!(children[0].name, "Test Article.md");
+        assert!(fs.get_article_content(children[0].ino).unwrap().contains("Test Article"));
+
+        let empty = fs.lookup_node(search.ino, OsStr::new("nothing matches this")).unwrap();
+        assert!(fs.inode_manager.list_children(empty.ino).is_empty());
+        assert!(fs.lookup_node(search.ino, OsStr::new(".hidden")).is_none());
+
+        // Searches only looked up are evicted, oldest first; made ones stay
+        fs.search("kept", true).unwrap();
+        for n in 0..MAX_LOOKED_UP_SEARCHES {
+            fs.lookup_node(search.ino, OsStr::new(&format!("probe {}", n))).unwrap();
+        }
+        let names: HashSet<String> = fs.inode_manager.list_children(search.ino).into_iter()
+            .map(|node| node.name)
+            .collect();
+        assert_eq!(names.len(), MAX_LOOKED_UP_SEARCHES + 1);
+        assert!(names.contains("kept") && names.contains("probe 0"));
+        assert!(!names.contains("test description") && !names.contains("nothing matches this"));
+
+        // Looking a search up again keeps it around
+        fs.lookup_node(search.ino, OsStr::new("probe 0")).unwrap();
+        fs.lookup_node(search.ino, OsStr::new("one more")).unwrap();
+        assert!(fs.inode_manager.get_node_by_name(search.ino, "probe 0").is_some());
+        assert!(fs.inode_manager.get_node_by_name(search.ino, "probe 1").is_none());
+
+        assert!(fs.forget_search("kept").unwrap().is_ok());
+        assert!(fs.forget_search("kept").is_none());
     }
 
     #[test]
-    fn test_remove_feed() {
+    fn test_rename_feed_directory() {
+        // Read-only unless the control files are turned on
+        let read_only = RssFuseFilesystem::new();
+        read_only.add_feed(create_test_feed()).unwrap();
+        let node = read_only.inode_manager.get_feed_directory("test-feed").unwrap();
+        assert_eq!(read_only.rename_feed_directory(&node, 1, "renamed"), Err(EROFS));
+
+        let fs = RssFuseFilesystem::with_config(&FilesystemConfig {
+            control_files: true,
+            ..FilesystemConfig::default()
+        });
+        fs.add_feed(create_test_feed()).unwrap();
+        let node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+
+        assert_eq!(fs.rename_feed_directory(&node, 1, ".rss-fuse"), Err(EEXIST));
+        assert_eq!(fs.rename_feed_directory(&node, node.ino, "renamed"), Err(EXDEV));
+        fs.rename_feed_directory(&node, 1, "renamed").unwrap();
+        assert!(fs.get_node_by_name(1, "test-feed").is_none());
+
+        // A refresh under the configured name lands in the renamed directory
+        fs.add_feed(create_test_feed()).unwrap();
+        let renamed = fs.get_node_by_name(1, "renamed").unwrap();
+        assert!(fs.get_node_by_name(renamed.ino, "Test Article.md").is_some());
+        assert!(fs.get_node_by_name(1, "test-feed").is_none());
+
+        let meta = fs.get_node_by_name(1, ".rss-fuse").unwrap();
+        assert_eq!(fs.rename_feed_directory(&meta, 1, "meta"), Err(EROFS));
+    }
+
+    #[test]
+    fn test_open_content_snapshot() {
         let fs = RssFuseFilesystem::new();
-        let feed = create_test_feed();
-        
+        fs.add_feed(create_test_feed()).unwrap();
+        let feed_node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let node = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+        let rendered = fs.get_article_content(node.ino).unwrap();
+        let rendered = rendered.as_bytes();
+
+        let fh = fs.open_content(&node).unwrap();
+        assert_eq!(fs.read_content(fh, 0, 4).unwrap().unwrap(), &rendered[..4]);
+        assert_eq!(fs.read_content(fh, 4, u32::MAX).unwrap().unwrap(), &rendered[4..]);
+        assert!(fs.read_content(fh, rendered.len() as u64, 16).unwrap().unwrap().is_empty());
+
+        // Each open gets its own handle; closing one leaves the other
+        let other = fs.open_content(&node).unwrap();
+        assert_ne!(fh, other);
+        fs.close_content(fh);
+        assert!(fs.read_content(fh, 0, 4).is_none());
+        assert!(fs.read_content(other, 0, 4).is_some());
+
+        assert!(fs.open_content(&feed_node).is_none());
+    }
+
+    #[test]
+    fn test_control_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+        let mut config = Config::default();
+        config.feeds.insert("news".to_string(), "https://news.example/rss".to_string().into());
+        config.save(&config_file).unwrap();
+
+        let fs = RssFuseFilesystem::with_config(&FilesystemConfig {
+            control_files: true,
+            ..FilesystemConfig::default()
+        });
+        fs.set_repository(crate::storage::RepositoryFactory::memory());
+        fs.set_config_path(config_file.clone());
+        let meta = fs.get_node_by_name(1, ".rss-fuse").unwrap();
+        let feeds_node = fs.get_node_by_name(meta.ino, "feeds.toml").unwrap();
+        let refresh_node = fs.get_node_by_name(meta.ino, "refresh").unwrap();
+
+        let listed = String::from_utf8(fs.open_content(&feeds_node).and_then(|fh| {
+            fs.read_content(fh, 0, u32::MAX)
+        }).unwrap().unwrap()).unwrap();
+        assert!(listed.contains("news = \"https://news.example/rss\""));
+
+        // Saving the feed list writes it back to the config
+        let fh = fs.open_control(&feeds_node, true).unwrap();
+        fs.write_control(fh, 0, b"[feeds]\nother = \"https://other.example/rss\"\n").unwrap();
+        fs.flush_control(fh).unwrap();
+        fs.close_content(fh);
+        let saved = Config::load(&config_file).unwrap();
+        assert_eq!(saved.feeds.keys().collect::<Vec<_>>(), ["other"]);
+
+        // An invalid list is rejected and the config left alone
+        let fh = fs.open_control(&feeds_node, true).unwrap();
+        fs.write_control(fh, 0, b"[feeds]\nbroken = \"not a url\"\n").unwrap();
+        assert_eq!(fs.flush_control(fh), Err(EINVAL));
+        assert!(Config::load(&config_file).unwrap().feeds.contains_key("other"));
+
+        // Names written to refresh are queued, not fetched in the write
+        let (requests, mut pending) = tokio::sync::mpsc::unbounded_channel();
+        fs.set_refresh_requests(requests);
+        let fh = fs.open_control(&refresh_node, true).unwrap();
+        assert_eq!(fs.write_control(fh, 0, b"missing\n"), Err(EINVAL));
+        assert_eq!(fs.write_control(fh, 0, b"other\n"), Ok(6));
+        assert_eq!(pending.try_recv().ok().as_deref(), Some("other"));
+        assert!(pending.try_recv().is_err());
+
+        // Control files don't grow without bound
+        assert_eq!(fs.write_control(fh, MAX_CONTROL_FILE_BYTES, b"x"), Err(EFBIG));
+        assert_eq!(fs.write_control(fh, u64::MAX, b"x"), Err(EFBIG));
+
+        // Other meta files stay read-only
+        let status = fs.get_node_by_name(meta.ino, "status.json").unwrap();
+        assert!(fs.open_control(&status, true).is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_enclosure_files() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/episode.mp3"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"0123456789".to_vec()))
+            .mount(&server)
+            .await;
+
+        let podcast_feed = || {
+            let mut feed = create_test_feed();
+            feed.articles[0].enclosures = vec![Enclosure {
+                url: format!("{}/episode.mp3", server.uri()),
+                mime_type: Some("audio/mpeg".to_string()),
+                length: Some(12),
+            }];
+            feed
+        };
+        let read_enclosure = |fs: &Arc<RssFuseFilesystem>, node: &VNode, offset: u64| {
+            let (fs, node) = (Arc::clone(fs), node.clone());
+            tokio::task::spawn_blocking(move || fs.read_enclosure(&node, offset, 4))
+        };
+
+        // Streamed: listed next to the article with the advertised size. The
+        // server ignores ranges, so it is downloaded once into the content store.
+        let store_dir = tempfile::TempDir::new().unwrap();
+        let fs = Arc::new(RssFuseFilesystem::new());
+        fs.set_repository(crate::storage::RepositoryFactory::memory());
+        fs.set_content_store(ContentStore::open(store_dir.path(), 64).unwrap());
+        fs.add_feed(podcast_feed()).unwrap();
+        let feed_node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let node = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.mp3").unwrap();
+        assert_eq!(fs.node_to_file_attr(&node).size, 12);
+        assert_eq!(read_enclosure(&fs, &node, 6).await.unwrap().unwrap(), b"6789");
+        assert_eq!(read_enclosure(&fs, &node, 0).await.unwrap().unwrap(), b"0123");
+        assert_eq!(read_enclosure(&fs, &node, 8).await.unwrap().unwrap(), b"89");
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+        assert_eq!(std::fs::read_dir(store_dir.path()).unwrap().count(), 1);
+
+        // Downloaded: fetched once, then served with its real size
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = FilesystemConfig { enclosures: EnclosureMode::Download, ..FilesystemConfig::default() };
+        let fs = Arc::new(RssFuseFilesystem::with_config(&config));
+        fs.set_repository(crate::storage::RepositoryFactory::memory());
+        fs.set_enclosure_dir(temp_dir.path().to_path_buf());
+        fs.add_feed(podcast_feed()).unwrap();
+        let feed_node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let node = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.mp3").unwrap();
+        assert_eq!(read_enclosure(&fs, &node, 0).await.unwrap().unwrap(), b"0123");
+        // A read past the end waits for the whole download
+        assert!(read_enclosure(&fs, &node, 10).await.unwrap().unwrap().is_empty());
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        assert_eq!(fs.node_to_file_attr(&node).size, 10);
+
+        let config = FilesystemConfig { enclosures: EnclosureMode::Off, ..FilesystemConfig::default() };
+        let fs = RssFuseFilesystem::with_config(&config);
+        fs.add_feed(podcast_feed()).unwrap();
+        let feed_node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        assert!(fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.mp3").is_none());
+    }
+
+    #[test]
+    fn test_refresh_swaps_feed_directory() {
+        let fs = RssFuseFilesystem::new();
+        let clock = crate::clock::MockClock::at(Utc::now());
+        fs.inode_manager.set_clock(clock.shared());
+        fs.add_feed(create_test_feed()).unwrap();
+
+        let old_dir = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let old_article = fs.inode_manager.get_node_by_name(old_dir.ino, "Test Article.md").unwrap();
+        let old_meta = fs.inode_manager.get_node_by_name(old_dir.ino, ".meta").unwrap();
+        let old_stats = fs.inode_manager.get_node_by_name(old_meta.ino, "stats.json").unwrap();
+        let fh = fs.open_content(&old_stats).unwrap();
+
+        let mut refreshed = create_test_feed();
+        refreshed.articles[0].title = "Updated Article".to_string();
+        fs.add_feed(refreshed).unwrap();
+
+        // The new listing replaces the old one in a single step
+        let new_dir = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        assert_ne!(new_dir.ino, old_dir.ino);
+        let names: Vec<String> = fs.inode_manager.list_children(new_dir.ino).into_iter().map(|n| n.name).collect();
+        assert!(names.contains(&"Updated Article.md".to_string()));
+        assert!(!names.contains(&"Test Article.md".to_string()));
+        assert_eq!(fs.inode_manager.list_children(FUSE_ROOT_ID).iter().filter(|n| n.name == "test-feed").count(), 1);
+        // The article keeps its inode under its new name
+        let updated = fs.inode_manager.get_node_by_name(new_dir.ino, "Updated Article.md").unwrap();
+        assert_eq!(updated.ino, old_article.ino);
+
+        // Readers of the old directory can finish
+        assert!(fs.read_content(fh, 0, 16).unwrap().is_ok());
+        assert!(fs.inode_manager.get_node(old_stats.ino).is_some());
+        assert!(fs.inode_manager.get_node_by_name(old_dir.ino, ".meta").is_some());
+
+        // ...and the old nodes go once closed and past the grace period
+        clock.advance(Duration::from_secs(120));
+        fs.add_feed(create_test_feed()).unwrap();
+        assert!(fs.inode_manager.get_node(old_dir.ino).is_some());
+        fs.close_content(fh);
+        assert!(fs.inode_manager.get_node(old_dir.ino).is_none());
+        assert!(fs.inode_manager.get_node(old_stats.ino).is_none());
+        assert!(fs.inode_manager.get_node(old_article.ino).is_some());
+    }
+
+    #[test]
+    fn test_articles_shared_with_nodes() {
+        let fs = RssFuseFilesystem::new();
+        fs.add_feed(create_test_feed()).unwrap();
+
+        let stored = Arc::clone(&fs.feeds.read()["test-feed"].articles[0]);
+        let feed_node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let node = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+        assert!(matches!(&node.node_type, NodeType::ArticleFile(_, article) if Arc::ptr_eq(article, &stored)));
+    }
+
+    #[test]
+    fn test_spilled_content_read_back() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let fs = RssFuseFilesystem::new();
+        fs.set_content_store(ContentStore::open(temp_dir.path().join("content"), 64).unwrap());
+
+        let body = format!("<p>{}</p>", "A long paragraph. ".repeat(10));
+        let mut feed = create_test_feed();
+        feed.articles[0].content = Some(body.clone());
+        fs.add_feed(feed.clone()).unwrap();
+
+        let stored = Arc::clone(&fs.feeds.read()["test-feed"].articles[0]);
+        assert_eq!(stored.content, None);
+        let hash = stored.content_ref.clone().unwrap();
+        assert!(temp_dir.path().join("content").join(&hash).exists());
+
+        let feed_node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let article = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+        let markdown = fs.inode_manager.get_article_content(article.ino).unwrap();
+        assert!(markdown.contains("A long paragraph."));
+        assert_eq!(fs.inode_manager.get_node(article.ino).unwrap().size, markdown.len() as u64);
+
+        // A refresh with a different body drops the old one from disk
+        feed.articles[0].content = Some(body.replace("long", "longer"));
+        fs.add_feed(feed).unwrap();
+        assert!(!temp_dir.path().join("content").join(&hash).exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_offline_images() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/img/chart.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"PNGDATA".to_vec()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/img/huge.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0; MAX_IMAGE_BYTES as usize + 1]))
+            .mount(&server)
+            .await;
+
+        let mut feed = create_test_feed();
+        feed.articles[0].link = format!("{}/posts/1", server.uri());
+        feed.articles[0].content = Some(
+            "<p>Results</p><img src=\"/img/chart.png\" alt=\"chart\"><img src=\"/img/huge.png\">".to_string()
+        );
+        let image_url = format!("{}/img/chart.png", server.uri());
+        let image_name = crate::content::asset_file_name(&image_url);
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = FilesystemConfig { offline_images: true, ..FilesystemConfig::default() };
+        let fs = Arc::new(RssFuseFilesystem::with_config(&config));
+        fs.set_repository(crate::storage::RepositoryFactory::memory());
+        fs.set_image_dir(temp_dir.path().to_path_buf());
+        fs.add_feed(feed).unwrap();
+
+        let feed_node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let article = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+        let markdown = fs.inode_manager.get_article_content(article.ino).unwrap();
+        assert!(markdown.contains(&format!("assets/{}", image_name)));
+
+        let assets = fs.inode_manager.get_node_by_name(feed_node.ino, "assets").unwrap();
+        let image = fs.inode_manager.get_node_by_name(assets.ino, &image_name).unwrap();
+        // Reads never wait on the download: they ask to be retried until
+        // the image is in place
+        let read = |name: &str| {
+            let node = fs.inode_manager.get_node_by_name(assets.ino, name).unwrap();
+            fs.read_asset(&node, 0, 64)
+        };
+        let read_eventually = |name: String| async move {
+            loop {
+                match read(&name) {
+                    Err(EAGAIN) => tokio::time::sleep(Duration::from_millis(10)).await,
+                    result => return result,
+                }
+            }
+        };
+        assert_eq!(read_eventually(image_name.clone()).await.unwrap(), b"PNGDATA");
+        assert_eq!(fs.node_to_file_attr(&image).size, 7);
+        assert!(temp_dir.path().join(&image_name).exists());
+
+        // Images over the cap are neither kept nor fetched again
+        let huge_name = crate::content::asset_file_name(&format!("{}/img/huge.png", server.uri()));
+        assert_eq!(read_eventually(huge_name.clone()).await, Err(EFBIG));
+        assert!(!temp_dir.path().join(&huge_name).exists());
+        assert_eq!(read(&huge_name), Err(EFBIG));
+
+        // Off by default: remote links stay and nothing is listed
+        let fs = RssFuseFilesystem::new();
+        fs.add_feed(create_test_feed()).unwrap();
+        let feed_node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        assert!(fs.inode_manager.get_node_by_name(feed_node.ino, "assets").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_on_read() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/test"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<html><body><nav>Menu</nav><article><p>The whole story</p></article></body></html>",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = FilesystemConfig { article_content: ArticleContentMode::FetchOnRead, ..FilesystemConfig::default() };
+        let fs = Arc::new(RssFuseFilesystem::with_config(&config));
+        fs.set_repository(crate::storage::RepositoryFactory::memory());
+        let mut feed = create_test_feed();
+        feed.articles[0].link = format!("{}/test", server.uri());
+        fs.add_feed(feed).unwrap();
+
+        let feed_node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let node = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+        assert!(!fs.get_article_content(node.ino).unwrap().contains("The whole story"));
+
+        // FUSE callbacks run off the runtime; opening twice fetches once
+        let fetcher = Arc::clone(&fs);
+        let opened = node.clone();
+        let fetched = tokio::task::spawn_blocking(move || {
+            fetcher.fetch_full_content(&opened) && fetcher.fetch_full_content(&opened)
+        }).await.unwrap();
+        assert!(fetched);
+
+        let content = fs.get_article_content(node.ino).unwrap();
+        assert!(content.contains("The whole story"));
+        assert!(!content.contains("Menu"));
+        assert_eq!(fs.get_node(node.ino).unwrap().size, content.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_feed_meta_directory() {
+        use crate::config::FeedEntry;
+        use crate::storage::FeedRepository;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <item><title>A</title><link>https://example.com/a</link></item>
+            </channel></rss>"#;
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss))
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/feed.xml", server.uri());
+        let repo = crate::storage::RepositoryFactory::memory();
+        let (feed, _) = repo.refresh_feed("t", &url).await.unwrap();
+
+        let fs = RssFuseFilesystem::new();
+        fs.set_repository(repo);
+        fs.set_feed_config("t", FeedEntry::Url(url.clone()).to_toml("t").unwrap());
+        fs.add_feed(feed).unwrap();
+
+        let feed_node = fs.inode_manager.get_feed_directory("t").unwrap();
+        let meta = fs.inode_manager.get_node_by_name(feed_node.ino, ".meta").unwrap();
+        let mut names: Vec<String> = fs.inode_manager.list_children(meta.ino).into_iter().map(|n| n.name).collect();
+        names.sort();
+        assert_eq!(names, ["config.toml", "feed.epub", "feed.xml", "icon.png", "stats.json"]);
+
+        let read = |file: GeneratedFile| String::from_utf8(fs.generated_content(&file).to_vec()).unwrap();
+        assert_eq!(read(GeneratedFile::FeedXml("t".to_string())), rss);
+        assert_eq!(read(GeneratedFile::FeedConfig("t".to_string())), format!("[feeds]\nt = \"{}\"\n", url));
+
+        let stats: serde_json::Value = serde_json::from_str(&read(GeneratedFile::FeedStats("t".to_string()))).unwrap();
+        assert_eq!(stats["articles"], 1);
+        assert_eq!(stats["url"], url.as_str());
+        assert!(stats["fetch_latency_ms"].is_u64());
+        assert_eq!(stats["last_refresh"]["articles_added"], 1);
+        assert_eq!(stats["last_refresh"]["articles_removed"], 0);
+        assert_eq!(stats["status_history"][0]["status"], "Active");
+
+        // Listing the book does not build it; opening it does
+        let epub_node = fs.inode_manager.get_node_by_name(meta.ino, "feed.epub").unwrap();
+        assert_eq!(fs.node_to_file_attr(&epub_node).size, 0);
+        assert!(fs.epubs.read().is_empty());
+        let book = fs.generated_content(&GeneratedFile::FeedEpub("t".to_string()));
+        assert!(book.starts_with(b"PK\x03\x04"));
+        assert_eq!(&book[30..58], b"mimetypeapplication/epub+zip");
+        assert_eq!(fs.node_to_file_attr(&epub_node).size, book.len() as u64);
+        assert!(Arc::ptr_eq(&fs.feed_epub("t"), &fs.feed_epub("t")));
+
+        // The feed declares no icon, so its site's favicon is used
+        assert_eq!(fs.repository.read().as_ref().unwrap().feed_icon_url("t"), Some(format!("{}/favicon.ico", server.uri())));
+        let icon = GeneratedFile::FeedIcon("t".to_string());
+        assert!(fs.generated_content(&icon).is_empty());
+        let icon_dir = tempfile::tempdir().unwrap();
+        fs.set_icon_dir(icon_dir.path().to_path_buf());
+        std::fs::create_dir_all(icon_dir.path()).unwrap();
+        std::fs::write(fs.icon_path("t").unwrap(), b"\x89PNG\r\n\x1a\n").unwrap();
+        assert_eq!(&fs.generated_content(&icon)[..], b"\x89PNG\r\n\x1a\n");
+        let icon_node = fs.inode_manager.get_node_by_name(meta.ino, "icon.png").unwrap();
+        assert_eq!(fs.node_to_file_attr(&icon_node).size, 8);
+
+        // A favicon is served under the name of its actual type
+        std::fs::write(fs.icon_path("t").unwrap(), b"\0\0\x01\0\x01\0").unwrap();
+        name_icon(&fs.inode_manager, "t", &fs.icon_path("t").unwrap());
+        assert!(fs.inode_manager.get_node_by_name(meta.ino, "icon.png").is_none());
+        assert_eq!(fs.inode_manager.get_node_by_name(meta.ino, "icon.ico").unwrap().ino, icon_node.ino);
+        assert_eq!(icon_file_name(b"\xff\xd8\xff\xe0"), Some("icon.jpg"));
+        assert_eq!(icon_file_name(b"  <svg xmlns=\"http://www.w3.org/2000/svg\"/>"), Some("icon.svg"));
+
+        // Error pages in place of an image are dropped
+        std::fs::write(fs.icon_path("t").unwrap(), b"<html>Not Found</html>").unwrap();
+        name_icon(&fs.inode_manager, "t", &fs.icon_path("t").unwrap());
+        assert!(!fs.icon_path("t").unwrap().exists());
+        assert!(fs.generated_content(&icon).is_empty());
+
+        // Disabled through the config
+        let config = FilesystemConfig { feed_meta: false, ..FilesystemConfig::default() };
+        let plain = RssFuseFilesystem::with_config(&config);
+        plain.add_feed(create_test_feed()).unwrap();
+        let feed_node = plain.inode_manager.get_feed_directory("test-feed").unwrap();
+        assert!(plain.inode_manager.get_node_by_name(feed_node.ino, ".meta").is_none());
+    }
+
+    #[test]
+    fn test_xattrs() {
+        let fs = RssFuseFilesystem::new();
+        fs.add_feed(create_test_feed()).unwrap();
+
+        let feed_node = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let node = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+        let attrs: HashMap<&str, String> = fs.xattrs(&node).into_iter()
+            .map(|(name, value)| (name, String::from_utf8(value).unwrap()))
+            .collect();
+
+        assert_eq!(attrs["user.rss.link"], "https://example.com/test");
+        assert_eq!(attrs["user.rss.author"], "Test Author");
+        assert_eq!(attrs["user.rss.tags"], "test");
+        assert_eq!(attrs["user.rss.read"], "false");
+        assert_eq!(attrs["user.rss.starred"], "false");
+        assert!(DateTime::parse_from_rfc3339(&attrs["user.rss.published"]).is_ok());
+
+        // Feed directories name their feed; other directories carry nothing
+        let feed_attrs = fs.xattrs(&feed_node);
+        assert_eq!(feed_attrs, [("user.rss.feed", b"test-feed".to_vec())]);
+        assert!(fs.xattrs(&fs.inode_manager.get_node(1).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_feed_directory_slug() {
+        let fs = RssFuseFilesystem::new();
+        let mut feed = create_test_feed();
+        feed.name = "Tech/.Rust: News\u{200B}".to_string();
+        fs.add_feed(feed).unwrap();
+
+        let group = fs.inode_manager.get_node_by_name(1, "Tech").unwrap();
+        let dir = fs.inode_manager.get_node_by_name(group.ino, "Rust- News").unwrap();
+        assert_eq!(fs.inode_manager.get_feed_directory("Tech/.Rust: News\u{200B}").unwrap().ino, dir.ino);
+        assert_eq!(fs.xattrs(&dir)[0].1, "Tech/.Rust: News\u{200B}".as_bytes());
+    }
+
+    #[test]
+    fn test_recent_directories() {
+        let config = FilesystemConfig { latest_articles: 1, today_dir: true, ..FilesystemConfig::default() };
+        let fs = RssFuseFilesystem::with_config(&config);
+        let mut feed = create_test_feed();
+        let mut older = feed.articles[0].clone();
+        older.id = "older".to_string();
+        older.title = "Older Article".to_string();
+        older.published = Some(Utc::now() - chrono::Duration::days(2));
+        feed.articles.push(older);
+        feed.name = "tech/news".to_string();
+        fs.add_feed(feed).unwrap();
+
+        let links = |dir: &str| {
+            let node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, dir).unwrap();
+            fs.refresh_recent(&node);
+            fs.inode_manager.list_children(node.ino).into_iter()
+                .map(|n| match n.node_type {
+                    NodeType::ArticleLink(_, _, target) => (n.name, target),
+                    other => panic!("unexpected {:?}", other),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let latest = links("latest");
+        assert_eq!(latest, [("Test Article.md".to_string(), "../tech/news/Test Article.md".to_string())]);
+        assert_eq!(links("today"), latest);
+
+        // Links follow the article list and are stable between listings
+        let latest_dir = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "latest").unwrap();
+        let link = fs.inode_manager.list_children(latest_dir.ino).remove(0);
+        assert_eq!(fs.node_to_file_attr(&link).kind, FileType::Symlink);
+        fs.refresh_recent(&latest_dir);
+        assert_eq!(fs.inode_manager.list_children(latest_dir.ino)[0].ino, link.ino);
+
+        let recent = fs.recent_articles(5);
+        assert_eq!(recent.iter().map(|a| a.title.as_str()).collect::<Vec<_>>(), ["Test Article", "Older Article"]);
+        assert_eq!((recent[0].feed.as_str(), recent[0].read), ("tech/news", false));
+        assert_eq!(fs.recent_articles(1).len(), 1);
+
+        // Disabled by default
+        assert!(RssFuseFilesystem::new().inode_manager.get_node_by_name(FUSE_ROOT_ID, "latest").is_none());
+    }
+
+    #[test]
+    fn test_keyword_scores() {
+        let scoring = crate::config::ScoringConfig {
+            keywords: BTreeMap::from([("rust".to_string(), 5), ("description".to_string(), 1)]),
+            priority_at: Some(10),
+            top_articles: 5,
+        };
+        let fs = RssFuseFilesystem::with_config(&FilesystemConfig { scoring, ..FilesystemConfig::default() });
+        let mut feed = create_test_feed();
+        let mut rust = feed.articles[0].clone();
+        rust.id = "rust".to_string();
+        rust.title = "Rust 2.0".to_string();
+        rust.published = Some(Utc::now() - chrono::Duration::days(2));
+        let mut other = feed.articles[0].clone();
+        other.id = "other".to_string();
+        other.title = "Other".to_string();
+        other.description = None;
+        feed.articles.extend([rust, other]);
+        fs.add_feed(feed).unwrap();
+
+        let feed_dir = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let mut names: Vec<String> = fs.inode_manager.list_children(feed_dir.ino).into_iter()
+            .map(|node| node.name)
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        names.sort();
+        assert_eq!(names, ["! Rust 2.0.md", "Other.md", "Test Article.md"]);
+        let marked = fs.inode_manager.get_node_by_name(feed_dir.ino, "! Rust 2.0.md").unwrap();
+        assert!(fs.inode_manager.get_article_content(marked.ino).unwrap().contains("score: 11\n"));
+
+        // Ranked by score, leaving out articles scoring nothing
+        let top = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "top").unwrap();
+        fs.refresh_recent(&top);
+        let mut links: Vec<String> = fs.inode_manager.list_children(top.ino).into_iter().map(|node| node.name).collect();
+        links.sort();
+        assert_eq!(links, ["01 ! Rust 2.0.md", "02 Test Article.md"]);
+    }
+
+    #[test]
+    fn test_language_filter() {
+        let fs = RssFuseFilesystem::new();
+        fs.set_languages("test-feed", Some(&["EN".to_string()]));
+        let mut feed = create_test_feed();
+        let mut german = feed.articles[0].clone();
+        german.id = "german".to_string();
+        german.title = "Die neue Version ist da".to_string();
+        german.description = Some("Diese Version bringt schnellere Builds, bessere Fehlermeldungen und eine lange Liste von Korrekturen.".to_string());
+        let mut english = feed.articles[0].clone();
+        english.id = "english".to_string();
+        english.title = "The new release is out".to_string();
+        english.description = Some("This version brings faster builds, better error messages and a long list of fixes.".to_string());
+        feed.articles.extend([german, english]);
+        fs.add_feed(feed).unwrap();
+
+        // Articles too short to tell stay listed
+        let feed_dir = fs.inode_manager.get_feed_directory("test-feed").unwrap();
+        let mut names: Vec<String> = fs.inode_manager.list_children(feed_dir.ino).into_iter()
+            .map(|node| node.name)
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        names.sort();
+        assert_eq!(names, ["Test Article.md", "The new release is out.md"]);
+        let english = fs.inode_manager.get_node_by_name(feed_dir.ino, "The new release is out.md").unwrap();
+        assert!(fs.inode_manager.get_article_content(english.ino).unwrap().contains("language: en\n"));
+    }
+
+    #[test]
+    fn test_tags_directory() {
+        let config = FilesystemConfig { tags_dir: true, ..FilesystemConfig::default() };
+        let fs = RssFuseFilesystem::with_config(&config);
+        let mut feed = create_test_feed();
+        feed.articles[0].tags = vec!["Rust".to_string(), "C/C++".to_string()];
         fs.add_feed(feed).unwrap();
+
+        let mut other = create_test_feed();
+        other.name = "other-feed".to_string();
+        other.articles[0].id = "other".to_string();
+        other.articles[0].tags = vec!["Rust".to_string()];
+        fs.add_feed(other).unwrap();
+
+        let tags = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "tags").unwrap();
+        fs.refresh_tags(&tags);
+        let names: Vec<String> = fs.inode_manager.list_children(tags.ino).into_iter().map(|n| n.name).collect();
+        assert_eq!(names, ["C-C++", "Rust"]);
+
+        let rust = fs.lookup_node(tags.ino, OsStr::new("Rust")).unwrap();
+        fs.refresh_tags(&rust);
+        let mut targets: Vec<String> = fs.inode_manager.list_children(rust.ino).into_iter()
+            .map(|n| match n.node_type {
+                NodeType::ArticleLink(_, _, target) => target,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        targets.sort();
+        assert_eq!(targets, ["../../other-feed/Test Article.md", "../../test-feed/Test Article.md"]);
+
+        // Tags nothing carries any more go away on the next listing
         fs.remove_feed("test-feed").unwrap();
-        
-        // Feed directory should be gone
-        assert!(fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").is_none());
+        fs.refresh_tags(&tags);
+        let names: Vec<String> = fs.inode_manager.list_children(tags.ino).into_iter().map(|n| n.name).collect();
+        assert_eq!(names, ["Rust"]);
+        assert!(RssFuseFilesystem::new().inode_manager.get_node_by_name(FUSE_ROOT_ID, "tags").is_none());
     }
 
     #[test]
-    fn test_config_update() {
+    fn test_virtual_feeds() {
         let fs = RssFuseFilesystem::new();
-        let config_content = r#"
-[feeds]
-"test-feed" = "https://example.com/feed.xml"
+        let mut feed = create_test_feed();
+        let mut older = feed.articles[0].clone();
+        older.id = "older".to_string();
+        older.title = "Older Article".to_string();
+        older.link = "https://example.com/older".to_string();
+        older.published = Some(Utc::now() - chrono::Duration::days(2));
+        feed.articles.push(older);
+        fs.add_feed(feed).unwrap();
 
-[settings]
-refresh_interval = 300
-"#.to_string();
+        // The same story in another feed is listed once
+        let mut other = create_test_feed();
+        other.name = "other-feed".to_string();
+        other.articles[0].id = "other".to_string();
+        other.articles[0].title = "Repost".to_string();
+        fs.add_feed(other).unwrap();
 
-        fs.update_config(config_content.clone());
-        
-        // Config content should be updated
-        assert_eq!(*fs.config_content.read(), config_content);
+        let members = vec!["test-feed".to_string(), "other-feed".to_string()];
+        fs.set_virtual_feeds(BTreeMap::from([("all".to_string(), members)]));
+        let all = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "all").unwrap();
+        fs.refresh_virtual_feed(&all);
+        let names: Vec<String> = fs.inode_manager.list_children(all.ino).into_iter().map(|n| n.name).collect();
+        assert_eq!(names, ["Test Article.md", "Older Article.md"]);
+        assert!(fs.inode_manager.list_children(all.ino).iter().all(|n| n.is_file()));
+
+        fs.set_virtual_feeds(BTreeMap::new());
+        assert!(fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "all").is_none());
     }
 
     #[test]
-    fn test_meta_structure() {
+    fn test_starred_directory() {
+        let fs = RssFuseFilesystem::with_config(&FilesystemConfig {
+            starred_dir: true,
+            ..FilesystemConfig::default()
+        });
+        let repo = crate::storage::RepositoryFactory::memory();
+        fs.set_repository(repo.clone());
+        assert_eq!(fs.check_write(WriteOp::Star), Ok(()));
+        let mut feed = create_test_feed();
+        let mut second = feed.articles[0].clone();
+        second.id = "second".to_string();
+        second.title = "Second Article".to_string();
+        feed.articles.push(second);
+        fs.add_feed(feed).unwrap();
+
+        let starred = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "starred").unwrap();
+        let feed_node = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, "test-feed").unwrap();
+        let article = fs.inode_manager.get_node_by_name(feed_node.ino, "Test Article.md").unwrap();
+        let article_id = match &article.node_type {
+            NodeType::ArticleFile(_, a) => a.id.clone(),
+            other => panic!("unexpected {:?}", other),
+        };
+
+        // ln -s ../test-feed/Test\ Article.md starred/favourite.md
+        let target = fs.inode_manager.resolve_link_target(starred.ino, std::path::Path::new("../test-feed/Test Article.md")).unwrap();
+        let link = fs.star_into(&target, starred.ino, "favourite.md").unwrap();
+        assert!(repo.is_article_starred(&article_id));
+        assert!(matches!(&link.node_type, NodeType::ArticleLink(_, _, t) if t == "../test-feed/Test Article.md"));
+        fs.refresh_recent(&starred);
+        assert_eq!(fs.inode_manager.list_children(starred.ino).iter().map(|n| n.ino).collect::<Vec<_>>(), [link.ino]);
+
+        // The file itself now says so
+        let content = fs.inode_manager.get_article_content(article.ino).unwrap();
+        assert!(content.contains("starred: true"));
+        let attrs: HashMap<_, _> = fs.xattrs(&fs.inode_manager.get_node(article.ino).unwrap()).into_iter().collect();
+        assert_eq!(attrs["user.rss.starred"], b"true");
+
+        // Absolute targets name the mount point first
+        let second = fs.inode_manager.resolve_link_target(starred.ino, std::path::Path::new("/home/me/rss/test-feed/Second Article.md")).unwrap();
+        assert_eq!(second.name, "Second Article.md");
+        fs.star_into(&second, starred.ino, "Second Article.md").unwrap();
+        assert_eq!(fs.star_into(&second, starred.ino, "favourite.md").err(), Some(EEXIST));
+        assert_eq!(fs.star_into(&feed_node, starred.ino, "dir").err(), Some(EINVAL));
+
+        // rm starred/favourite.md unstars
+        fs.unstar_link(&fs.inode_manager.get_node(link.ino).unwrap()).unwrap();
+        assert!(!repo.is_article_starred(&article_id));
+        let names: Vec<String> = fs.inode_manager.list_children(starred.ino).into_iter().map(|n| n.name).collect();
+        assert_eq!(names, ["Second Article.md"]);
+        assert!(!fs.inode_manager.get_article_content(article.ino).unwrap().contains("starred:"));
+    }
+
+    #[test]
+    fn test_open_flags_error() {
         let fs = RssFuseFilesystem::new();
-        
-        // Should have .rss-fuse directory
-        let meta = fs.inode_manager.get_node_by_name(FUSE_ROOT_ID, ".rss-fuse").unwrap();
-        assert!(meta.is_directory());
-        
-        // Should have subdirectories and config file
-        let children = fs.inode_manager.list_children(meta.ino);
-        assert_eq!(children.len(), 3); // logs, cache, config.toml
-        
-        let names: Vec<String> = children.iter().map(|n| n.name.clone()).collect();
-        assert!(names.contains(&"logs".to_string()));
-        assert!(names.contains(&"cache".to_string()));
-        assert!(names.contains(&"config.toml".to_string()));
+        assert_eq!(fs.open_flags_error(libc::O_RDONLY), None);
+        assert_eq!(fs.open_flags_error(libc::O_WRONLY), Some(EROFS));
+        assert_eq!(fs.open_flags_error(libc::O_RDWR), Some(EROFS));
+        assert_eq!(fs.open_flags_error(libc::O_RDONLY | libc::O_TRUNC), Some(EROFS));
+        assert_eq!(fs.open_flags_error(libc::O_WRONLY | libc::O_APPEND), Some(EROFS));
     }
 
     #[test]
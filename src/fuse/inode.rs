@@ -1,9 +1,24 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
-use parking_lot::RwLock;
+use std::time::{Duration, SystemTime};
+use std::num::NonZeroUsize;
+use dashmap::DashMap;
+use lru::LruCache;
+use parking_lot::{Mutex, RwLock};
+use tracing::warn;
 use fuser::FileType;
-use crate::feed::Article;
+use crate::feed::{dedup, Article};
+use crate::config::{AgingMarker, DuplicateMode, EnclosureMode, FilesystemConfig, OutputFormat};
+use crate::fuse::naming::{self, FilenameTemplate};
+use crate::clock::{system_clock, SharedClock};
+use crate::content::{asset_file_name, ContentExtractor, ASSETS_DIR};
+use crate::content::extractor::{Annotations, ContentSelectors};
+use crate::content::language;
+use crate::storage::ContentStore;
 
 /// Virtual filesystem node types
 #[derive(Debug, Clone)]
@@ -15,6 +30,120 @@ pub enum NodeType {
     ConfigFile,     // config.toml
     LogsDirectory,  // logs directory
     CacheDirectory, // cache directory
+    AgedDirectory(String), // Feed name; holds articles past the aging threshold
+    FilterFolder(String), // Feed name; holds articles routed there by a filter rule
+    DateFolder(String), // Feed name; holds articles published in one year or month
+    DuplicatesFolder(String), // Feed name; holds stories another feed carries too
+    SummaryDirectory(String), // Feed name; summaries of the feed's articles
+    SummaryFile(String, Arc<Article>), // Feed name, Article data; content is the article's summary
+    GroupDirectory(String), // Group path (e.g. "tech"); holds feeds declared as "tech/<name>"
+    ViewDirectory(String, ArticleView), // Feed name; contents computed from read state on readdir
+    SearchRoot,             // .search directory at the mount root
+    SearchResults(String),  // Query; contents computed from a repository search
+    FeedMetaDirectory(String), // Feed name; the feed's .meta directory
+    RecentDirectory(RecentView), // latest/, today/, starred/ or top/ at the mount root
+    TagsRoot,               // tags/ directory at the mount root
+    TagDirectory(String),   // Tag directory name; symlinks to articles carrying the tag
+    VirtualFeedDirectory(String), // Virtual feed name; articles of its member feeds
+    ArticleLink(String, Arc<Article>, String), // Feed name, Article data, symlink target
+    EnclosureFile(String, Arc<Article>, usize), // Feed name, Article data, index into its enclosures
+    AssetsDirectory(String), // Feed name; images embedded by the articles next to it
+    AssetFile(String, String), // Feed name, image URL; downloaded on refresh or first read
+    GeneratedFile(GeneratedFile), // Meta file whose content is produced on every read
+}
+
+/// Meta files rendered from live state rather than stored content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratedFile {
+    StatusJson,
+    StatusMarkdown,
+    FeedConfig(String), // Feed name
+    FeedXml(String),    // Feed name
+    FeedStats(String),  // Feed name
+    FeedEpub(String),   // Feed name; built from the feed's articles when they change
+    FeedIcon(String),   // Feed name; favicon or channel image downloaded on refresh
+    FeedError(String),  // Feed name; why the last refresh failed, next to the articles
+    FeedList,           // Writable `[feeds]` table, saved back to the config
+    Refresh,            // Feed names written here are fetched right away
+}
+
+impl GeneratedFile {
+    /// Reports in the global `.rss-fuse` directory
+    pub const STATUS: [GeneratedFile; 2] = [GeneratedFile::StatusJson, GeneratedFile::StatusMarkdown];
+
+    /// Writable control files in the global `.rss-fuse` directory
+    pub const CONTROL: [GeneratedFile; 2] = [GeneratedFile::FeedList, GeneratedFile::Refresh];
+
+    /// Files in a feed's `.meta` directory
+    pub fn feed_files(feed_name: &str) -> [GeneratedFile; 5] {
+        [
+            GeneratedFile::FeedConfig(feed_name.to_string()),
+            GeneratedFile::FeedXml(feed_name.to_string()),
+            GeneratedFile::FeedStats(feed_name.to_string()),
+            GeneratedFile::FeedEpub(feed_name.to_string()),
+            GeneratedFile::FeedIcon(feed_name.to_string()),
+        ]
+    }
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            GeneratedFile::StatusJson => "status.json",
+            GeneratedFile::StatusMarkdown => "status.md",
+            GeneratedFile::FeedConfig(_) => "config.toml",
+            GeneratedFile::FeedXml(_) => "feed.xml",
+            GeneratedFile::FeedStats(_) => "stats.json",
+            GeneratedFile::FeedEpub(_) => "feed.epub",
+            GeneratedFile::FeedIcon(_) => "icon.png",
+            GeneratedFile::FeedError(_) => ".error.txt",
+            GeneratedFile::FeedList => "feeds.toml",
+            GeneratedFile::Refresh => "refresh",
+        }
+    }
+
+    /// Whether writing to the file does something
+    pub fn is_control(&self) -> bool {
+        matches!(self, GeneratedFile::FeedList | GeneratedFile::Refresh)
+    }
+}
+
+/// Top-level directories of symlinks to recent articles across all feeds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecentView {
+    Latest,  // The newest `latest_articles` articles
+    Today,   // Articles published in the last 24 hours
+    Starred, // Articles the user starred; links made or removed here star and unstar
+    Top,     // The best-scoring `[scoring] top_articles` articles, numbered by rank
+}
+
+impl RecentView {
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            RecentView::Latest => "latest",
+            RecentView::Today => "today",
+            RecentView::Starred => "starred",
+            RecentView::Top => "top",
+        }
+    }
+}
+
+/// Virtual per-feed listings filtered by read state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArticleView {
+    Unread,
+    Read,
+}
+
+impl ArticleView {
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            ArticleView::Unread => "unread",
+            ArticleView::Read => "read",
+        }
+    }
+
+    pub fn includes(self, is_read: bool) -> bool {
+        is_read == (self == ArticleView::Read)
+    }
 }
 
 /// Virtual filesystem node
@@ -39,17 +168,39 @@ impl VNode {
             NodeType::FeedDirectory(_) | 
             NodeType::MetaDirectory | 
             NodeType::LogsDirectory | 
-            NodeType::CacheDirectory => (FileType::Directory, 0),
-            NodeType::ArticleFile(feed_name, article) => {
-                // Use markdown format by default, fallback to text on error
-                let content = article.to_markdown(feed_name)
-                    .unwrap_or_else(|_| article.to_text());
-                (FileType::RegularFile, content.len() as u64)
-            },
+            NodeType::CacheDirectory |
+            NodeType::AgedDirectory(_) |
+            NodeType::FilterFolder(_) |
+            NodeType::DateFolder(_) |
+            NodeType::DuplicatesFolder(_) |
+            NodeType::SummaryDirectory(_) |
+            NodeType::GroupDirectory(_) |
+            NodeType::ViewDirectory(_, _) |
+            NodeType::SearchRoot |
+            NodeType::SearchResults(_) |
+            NodeType::FeedMetaDirectory(_) |
+            NodeType::AssetsDirectory(_) |
+            NodeType::RecentDirectory(_) |
+            NodeType::TagsRoot |
+            NodeType::TagDirectory(_) |
+            NodeType::VirtualFeedDirectory(_) => (FileType::Directory, 0),
+            NodeType::ArticleLink(_, _, target) => (FileType::Symlink, target.len() as u64),
+            // Sized by InodeManager::create_article_file, which knows the render settings
+            NodeType::ArticleFile(_, _) => (FileType::RegularFile, 0),
+            // The feed's advertised length; corrected once a download completes
+            NodeType::EnclosureFile(_, article, index) => (
+                FileType::RegularFile,
+                article.enclosures.get(*index).and_then(|e| e.length).unwrap_or(0),
+            ),
+            NodeType::AssetFile(_, _) => (FileType::RegularFile, 0), // Sized once downloaded
+            NodeType::SummaryFile(_, _) => (FileType::RegularFile, 0), // Sized by InodeManager::create_summary_file
             NodeType::ConfigFile => (FileType::RegularFile, 0), // Will be computed when needed
+            NodeType::GeneratedFile(_) => (FileType::RegularFile, 0), // Sized when its attributes are read
         };
 
+        // Nodes standing for an article date from it, so sorting by time works
         let now = SystemTime::now();
+        let time = article_time(&node_type).unwrap_or(now);
         Self {
             ino,
             parent_ino,
@@ -58,8 +209,8 @@ impl VNode {
             file_type,
             size,
             children: Vec::new(),
-            created_time: now,
-            modified_time: now,
+            created_time: time,
+            modified_time: time,
             accessed_time: now,
         }
     }
@@ -82,198 +233,1461 @@ impl VNode {
         self.accessed_time = SystemTime::now();
     }
 
-    pub fn touch_modified(&mut self) {
-        let now = SystemTime::now();
-        self.modified_time = now;
-        self.accessed_time = now;
-    }
+    pub fn touch_modified(&mut self) {
+        let now = SystemTime::now();
+        self.modified_time = now;
+        self.accessed_time = now;
+    }
+
+    pub fn update_content(&mut self, new_size: u64) {
+        self.size = new_size;
+        // Fetched full text or a finished download keeps the article's date
+        if article_time(&self.node_type).is_none() {
+            self.touch_modified();
+        }
+    }
+
+    pub fn is_directory(&self) -> bool {
+        matches!(self.file_type, FileType::Directory)
+    }
+
+    pub fn is_file(&self) -> bool {
+        matches!(self.file_type, FileType::RegularFile)
+    }
+}
+
+/// When the article behind an article, link or enclosure node was
+/// published, falling back to when it was updated or first cached
+fn article_time(node_type: &NodeType) -> Option<SystemTime> {
+    let article = match node_type {
+        NodeType::ArticleFile(_, article)
+        | NodeType::ArticleLink(_, article, _)
+        | NodeType::EnclosureFile(_, article, _)
+        | NodeType::SummaryFile(_, article) => article,
+        _ => return None,
+    };
+    article.published.or(article.updated).or(article.cached_at).map(SystemTime::from)
+}
+
+/// How many rendered article files are kept for repeated reads
+const RENDERED_ARTICLES: usize = 256;
+
+/// What an article file's content is rendered from. A rendering is reused
+/// by every file of the article until one of these changes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RenderKey {
+    feed: String,
+    article_id: String,
+    format: OutputFormat,
+    extractor: u64, // the feed's extractor settings (selectors)
+    source: u64,    // the article as listed and its annotations
+}
+
+/// Articles' files in their feed directory are numbered above this bit,
+/// from a hash of the feed and article ID, so an article keeps its inode
+/// number across refreshes and remounts. Other nodes are numbered from 2 up.
+const STABLE_INO_BIT: u64 = 1 << 62;
+
+/// The `attempt`th candidate inode number for an article's file; later
+/// attempts resolve collisions
+fn stable_article_ino(feed_name: &str, article_id: &str, attempt: u32) -> u64 {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(feed_name.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(article_id.as_bytes());
+    if attempt > 0 {
+        hasher.update(&attempt.to_le_bytes());
+    }
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&hasher.finalize().as_bytes()[..8]);
+    STABLE_INO_BIT | (u64::from_le_bytes(bytes) & (STABLE_INO_BIT - 1))
+}
+
+/// Whether `ino` is `ancestor` or below it
+fn is_within(nodes: &DashMap<u64, VNode>, mut ino: u64, ancestor: u64) -> bool {
+    loop {
+        if ino == ancestor {
+            return true;
+        }
+        match nodes.get(&ino).map(|node| node.parent_ino) {
+            Some(parent_ino) if parent_ino != ino => ino = parent_ino,
+            _ => return false,
+        }
+    }
+}
+
+/// How long a replaced feed directory stays readable for lookups and
+/// listings that started before the swap
+const RETIRED_GRACE: Duration = Duration::from_secs(60);
+
+/// Folder of a feed directory holding stories other feeds carry too, with
+/// `duplicates = "group"`
+pub const DUPLICATES_DIR: &str = "duplicates";
+
+/// Directory of a feed holding its articles' summaries, as `<article>.txt`
+pub const SUMMARY_DIR: &str = "summary";
+
+/// Nodes of a feed directory replaced on refresh. They can no longer be
+/// found by name, but readers that found them before the swap can finish.
+#[derive(Debug)]
+struct RetiredTree {
+    inos: Vec<u64>,
+    at: SystemTime,
+}
+
+/// Inode manager for the virtual filesystem. Nodes and names are kept in
+/// concurrent maps, so lookups rarely wait for a refresh adding nodes.
+pub struct InodeManager {
+    nodes: DashMap<u64, VNode>,
+    next_ino: AtomicU64,
+    name_to_ino: DashMap<(u64, String), u64>, // (parent_ino, name) -> ino
+    swap: Mutex<()>,  // held while a staged feed directory is swapped in
+    swaps: AtomicU64, // counts swaps begun and finished; odd during one
+    config: RwLock<FilesystemConfig>,
+    clock: RwLock<SharedClock>,
+    full_content: RwLock<HashMap<String, Arc<Article>>>, // article id -> copy carrying fetched page content
+    extractors: RwLock<HashMap<String, Arc<ContentExtractor>>>, // feed name -> extractor with custom selectors
+    default_extractor: RwLock<Arc<ContentExtractor>>,
+    filename_templates: RwLock<HashMap<String, FilenameTemplate>>, // feed name -> article file name pattern
+    visible_limits: RwLock<HashMap<String, usize>>, // feed name -> most articles listed
+    duplicates: RwLock<HashMap<String, HashMap<String, Vec<String>>>>, // feed name -> article id -> other feeds carrying it
+    summaries: RwLock<HashMap<String, Arc<str>>>, // article id -> summary from the configured summarizer
+    scores: RwLock<HashMap<String, i64>>, // article id -> keyword score, with [scoring] keywords set
+    languages: RwLock<HashMap<String, Option<&'static str>>>, // article id -> detected language, once detected
+    language_filters: RwLock<HashMap<String, Vec<String>>>, // feed name -> languages listed
+    feed_paths: RwLock<HashMap<String, String>>, // feed name -> path it is presented at after a live rename
+    icon_names: RwLock<HashMap<String, &'static str>>, // feed name -> `.meta` icon file named for its image type
+    content_store: RwLock<Option<Arc<ContentStore>>>, // where long article bodies are kept, if anywhere
+    rendered: Mutex<LruCache<RenderKey, Arc<str>>>, // rendering inputs -> article file content
+    staged: RwLock<HashMap<String, u64>>, // feed name -> directory being built to replace its current one
+    adoptions: RwLock<HashMap<String, Vec<(u64, u64)>>>, // feed name -> (staged file, stable inode it takes over on commit)
+    retired: Mutex<Vec<RetiredTree>>,
+}
+
+impl InodeManager {
+    pub fn new() -> Self {
+        let manager = Self {
+            nodes: DashMap::new(),
+            next_ino: AtomicU64::new(2), // Start from 2, 1 is reserved for root
+            name_to_ino: DashMap::new(),
+            swap: Mutex::new(()),
+            swaps: AtomicU64::new(0),
+            config: RwLock::new(FilesystemConfig::default()),
+            clock: RwLock::new(system_clock()),
+            full_content: RwLock::new(HashMap::new()),
+            extractors: RwLock::new(HashMap::new()),
+            default_extractor: RwLock::new(Arc::new(ContentExtractor::default())),
+            filename_templates: RwLock::new(HashMap::new()),
+            visible_limits: RwLock::new(HashMap::new()),
+            duplicates: RwLock::new(HashMap::new()),
+            summaries: RwLock::new(HashMap::new()),
+            scores: RwLock::new(HashMap::new()),
+            languages: RwLock::new(HashMap::new()),
+            language_filters: RwLock::new(HashMap::new()),
+            feed_paths: RwLock::new(HashMap::new()),
+            icon_names: RwLock::new(HashMap::new()),
+            content_store: RwLock::new(None),
+            rendered: Mutex::new(LruCache::new(NonZeroUsize::new(RENDERED_ARTICLES).unwrap())),
+            staged: RwLock::new(HashMap::new()),
+            adoptions: RwLock::new(HashMap::new()),
+            retired: Mutex::new(Vec::new()),
+        };
+
+        // Create root directory
+        manager.create_root();
+        manager
+    }
+
+    fn create_root(&self) {
+        let root = VNode::new(1, 1, "/".to_string(), NodeType::Root);
+        self.nodes.insert(1, root);
+        self.name_to_ino.insert((1, "/".to_string()), 1);
+    }
+
+    pub fn allocate_ino(&self) -> u64 {
+        self.next_ino.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub fn get_node(&self, ino: u64) -> Option<VNode> {
+        self.nodes.get(&ino).map(|node| node.clone())
+    }
+
+    /// Apply `f` to a node in place, if it exists. The node's shard of the
+    /// map is locked meanwhile: `f` must not look up other nodes.
+    fn update_node<R>(&self, ino: u64, f: impl FnOnce(&mut VNode) -> R) -> Option<R> {
+        self.nodes.get_mut(&ino).map(|mut node| f(&mut node))
+    }
+
+    /// Collect what `f` returns for each node. Nodes changed meanwhile may
+    /// be seen before or after the change.
+    fn filter_nodes<T>(&self, mut f: impl FnMut(&u64, &VNode) -> Option<T>) -> Vec<T> {
+        self.nodes.iter().filter_map(|entry| f(entry.key(), entry.value())).collect()
+    }
+
+    pub fn get_node_by_name(&self, parent_ino: u64, name: &str) -> Option<VNode> {
+        let key = (parent_ino, name.to_string());
+        self.between_swaps(|| {
+            let ino = *self.name_to_ino.get(&key)?;
+            self.get_node(ino)
+        })
+    }
+
+    /// Run `read` until no directory swap overlaps it, so it sees the tree
+    /// either before or after each swap. Readers take no shared lock.
+    fn between_swaps<T>(&self, read: impl Fn() -> T) -> T {
+        loop {
+            let before = self.swaps.load(Ordering::Acquire);
+            if before % 2 == 0 {
+                let value = read();
+                if self.swaps.load(Ordering::Acquire) == before {
+                    return value;
+                }
+            }
+            std::thread::yield_now();
+        }
+    }
+
+    pub fn create_node(&self, parent_ino: u64, name: String, node_type: NodeType) -> Result<u64, String> {
+        self.create_node_at(None, parent_ino, name, node_type)
+    }
+
+    /// Create a node numbered `ino`, or the next free number if `None`
+    fn create_node_at(&self, ino: Option<u64>, parent_ino: u64, name: String, node_type: NodeType) -> Result<u64, String> {
+        // Check if parent exists and is a directory
+        let is_directory = self.nodes.get(&parent_ino).map(|parent| parent.is_directory())
+            .ok_or("Parent directory not found")?;
+        
+        if !is_directory {
+            return Err("Parent is not a directory".to_string());
+        }
+
+        // Check if name already exists
+        if self.get_node_by_name(parent_ino, &name).is_some() {
+            return Err("File already exists".to_string());
+        }
+
+        let ino = ino.unwrap_or_else(|| self.allocate_ino());
+        let node = VNode::new(ino, parent_ino, name.clone(), node_type);
+
+        // Add to parent's children
+        self.nodes.insert(ino, node);
+        self.update_node(parent_ino, |parent| parent.add_child(ino));
+
+        // Add to name lookup
+        self.name_to_ino.insert((parent_ino, name), ino);
+
+        // Touch parent directory to update its modification time
+        self.touch_directory_and_parents(parent_ino);
+
+        Ok(ino)
+    }
+
+    pub fn remove_node(&self, ino: u64) -> Result<(), String> {
+        if ino == 1 {
+            return Err("Cannot remove root directory".to_string());
+        }
+
+        let node = self.get_node(ino)
+            .ok_or("Node not found")?;
+
+        let parent_ino = node.parent_ino;
+
+        // Remove from parent's children
+        self.update_node(node.parent_ino, |parent| parent.remove_child(ino));
+        self.nodes.remove(&ino);
+
+        // Remove from name lookup
+        self.name_to_ino.remove(&(node.parent_ino, node.name));
+
+        // Touch parent directory to update its modification time
+        self.touch_directory_and_parents(parent_ino);
+
+        Ok(())
+    }
+
+    /// Remove a node together with everything below it
+    pub fn remove_subtree(&self, ino: u64) -> Result<(), String> {
+        for child in self.list_children(ino) {
+            self.remove_subtree(child.ino)?;
+        }
+        self.remove_node(ino)
+    }
+
+    pub fn list_children(&self, parent_ino: u64) -> Vec<VNode> {
+        self.between_swaps(|| {
+            let children = self.nodes.get(&parent_ino).map(|parent| parent.children.clone()).unwrap_or_default();
+            children.iter().filter_map(|child_ino| self.get_node(*child_ino)).collect()
+        })
+    }
+
+    pub fn update_node_size(&self, ino: u64, size: u64) {
+        self.update_node(ino, |node| node.update_content(size));
+    }
+
+    pub fn touch_node_accessed(&self, ino: u64) {
+        self.update_node(ino, VNode::touch_accessed);
+    }
+
+    pub fn touch_node_modified(&self, ino: u64) {
+        self.update_node(ino, VNode::touch_modified);
+    }
+
+    /// Date a node's modification and change times to `time`
+    pub fn set_node_modified(&self, ino: u64, time: SystemTime) {
+        self.update_node(ino, |node| node.modified_time = time);
+    }
+
+    pub fn touch_directory_and_parents(&self, ino: u64) {
+        let mut current_ino = ino;
+        loop {
+            let touched = self.update_node(current_ino, |node| {
+                if node.is_directory() {
+                    node.touch_modified();
+                }
+                node.parent_ino
+            });
+            let Some(parent_ino) = touched else {
+                break;
+            };
+
+            if current_ino == parent_ino || parent_ino == 1 {
+                // Reached root or self-referencing node
+                break;
+            }
+            current_ino = parent_ino;
+        }
+    }
+
+    /// Directory name presented for a feed or group path component
+    pub fn feed_dir_name(&self, component: &str) -> String {
+        naming::feed_dir_name(component, self.config.read().transliterate_names)
+    }
+
+    /// Directory names from the root down to a feed's directory: its groups,
+    /// then the feed itself
+    pub fn feed_dir_names(&self, feed_name: &str) -> Vec<String> {
+        self.feed_path(feed_name).split('/').map(|component| self.feed_dir_name(component)).collect()
+    }
+
+    /// Look up a feed's directory by feed name, descending through any
+    /// group directories named by its `group/` prefix
+    pub fn get_feed_directory(&self, feed_name: &str) -> Option<VNode> {
+        let mut parent_ino = 1;
+        for component in self.feed_path(feed_name).split('/') {
+            parent_ino = self.get_node_by_name(parent_ino, &self.feed_dir_name(component))?.ino;
+        }
+        self.get_node(parent_ino)
+            .filter(|node| matches!(&node.node_type, NodeType::FeedDirectory(name) if name == feed_name))
+    }
+
+    /// Create a feed's directory, creating group directories for a
+    /// `group/sub/name` style feed name as needed
+    pub fn create_feed_directory(&self, feed_name: &str) -> Result<u64, String> {
+        let (parent_ino, dir_name) = self.feed_parent(feed_name)?;
+        let feed_ino = self.create_node(parent_ino, dir_name, NodeType::FeedDirectory(feed_name.to_string()))?;
+        self.create_feed_contents(feed_ino, feed_name)?;
+        Ok(feed_ino)
+    }
+
+    /// Put `.error.txt` in a feed's directory next to its articles. The
+    /// next successful refresh builds a directory without it.
+    pub fn add_feed_error_file(&self, feed_name: &str) -> Result<(), String> {
+        let feed_node = self.get_feed_directory(feed_name).ok_or("Feed directory not found")?;
+        let file = GeneratedFile::FeedError(feed_name.to_string());
+        if self.get_node_by_name(feed_node.ino, file.file_name()).is_none() {
+            self.create_node(feed_node.ino, file.file_name().to_string(), NodeType::GeneratedFile(file))?;
+        }
+        Ok(())
+    }
+
+    /// Start building a new directory for a feed, out of sight: articles
+    /// created for the feed go into it until `commit_feed_directory` puts
+    /// it in place of the current one
+    pub fn stage_feed_directory(&self, feed_name: &str) -> Result<u64, String> {
+        self.discard_staged_directory(feed_name);
+        let (parent_ino, dir_name) = self.feed_parent(feed_name)?;
+        let ino = self.allocate_ino();
+        let node = VNode::new(ino, parent_ino, dir_name, NodeType::FeedDirectory(feed_name.to_string()));
+        self.nodes.insert(ino, node);
+        self.staged.write().insert(feed_name.to_string(), ino);
+        if let Err(e) = self.create_feed_contents(ino, feed_name) {
+            self.discard_staged_directory(feed_name);
+            return Err(e);
+        }
+        Ok(ino)
+    }
+
+    /// Swap a staged directory in for the feed's current one in a single
+    /// step, so a reader sees either the old listing or the new one. The
+    /// old nodes are retired rather than removed; see `release_retired`.
+    pub fn commit_feed_directory(&self, feed_name: &str) -> Result<u64, String> {
+        let ino = self.staged.write().remove(feed_name).ok_or("No directory staged for the feed")?;
+        let old = self.get_feed_directory(feed_name);
+        let adoptions = self.adoptions.write().remove(feed_name).unwrap_or_default();
+        let (parent_ino, name) = self.nodes.get(&ino).map(|node| (node.parent_ino, node.name.clone()))
+            .ok_or("Staged directory not found")?;
+        let adopted = {
+            let _swap = self.swap.lock();
+            self.swaps.fetch_add(1, Ordering::AcqRel);
+            if let Some(old) = &old {
+                self.name_to_ino.remove(&(old.parent_ino, old.name.clone()));
+                self.update_node(old.parent_ino, |old_parent| old_parent.remove_child(old.ino));
+            }
+            self.name_to_ino.insert((parent_ino, name), ino);
+            self.update_node(parent_ino, |parent| parent.add_child(ino));
+            let adopted = self.adopt_stable_inos(adoptions);
+            self.swaps.fetch_add(1, Ordering::AcqRel);
+            adopted
+        };
+        self.touch_directory_and_parents(parent_ino);
+
+        for ino in &adopted {
+            self.unretire(*ino);
+        }
+        if let Some(old) = old {
+            let mut inos = self.subtree_inos(old.ino);
+            inos.retain(|ino| !adopted.contains(ino));
+            self.retired.lock().push(RetiredTree { inos, at: self.clock().now() });
+        }
+        Ok(ino)
+    }
+
+    /// Drop a directory staged for a feed but not committed
+    pub fn discard_staged_directory(&self, feed_name: &str) {
+        self.adoptions.write().remove(feed_name);
+        if let Some(ino) = self.staged.write().remove(feed_name) {
+            self.forget_nodes(&self.subtree_inos(ino));
+        }
+    }
+
+    /// Remove retired directories once their grace period is over and
+    /// none of their nodes is in `in_use` (e.g. held open)
+    pub fn release_retired(&self, in_use: &HashSet<u64>) {
+        let now = self.clock().now();
+        let released: Vec<RetiredTree> = {
+            let mut retired = self.retired.lock();
+            let (released, kept) = std::mem::take(&mut *retired).into_iter().partition(|tree| {
+                now.duration_since(tree.at).unwrap_or_default() >= RETIRED_GRACE
+                    && !tree.inos.iter().any(|ino| in_use.contains(ino))
+            });
+            *retired = kept;
+            released
+        };
+        for tree in released {
+            self.forget_nodes(&tree.inos);
+        }
+    }
+
+    fn retired_inos(&self) -> HashSet<u64> {
+        self.retired.lock().iter().flat_map(|tree| tree.inos.iter().copied()).collect()
+    }
+
+    /// A node and everything below it
+    fn subtree_inos(&self, ino: u64) -> Vec<u64> {
+        let mut inos = Vec::new();
+        let mut pending = vec![ino];
+        while let Some(ino) = pending.pop() {
+            if let Some(children) = self.nodes.get(&ino).map(|node| node.children.clone()) {
+                inos.push(ino);
+                pending.extend(children);
+            }
+        }
+        inos
+    }
+
+    /// Remove nodes that are no longer linked into the tree. Their names
+    /// are only unregistered where they still point at them.
+    fn forget_nodes(&self, inos: &[u64]) {
+        for ino in inos {
+            if let Some((_, node)) = self.nodes.remove(ino) {
+                self.name_to_ino.remove_if(&(node.parent_ino, node.name), |_, named| named == ino);
+            }
+        }
+        // Names in forgotten directories may point at files adopted elsewhere
+        let forgotten: HashSet<&u64> = inos.iter().collect();
+        self.name_to_ino.retain(|(parent_ino, _), _| !forgotten.contains(parent_ino));
+    }
+
+    /// Directory a feed's directory goes in, creating its groups as needed,
+    /// and the feed directory's name
+    fn feed_parent(&self, feed_name: &str) -> Result<(u64, String), String> {
+        let path = self.feed_path(feed_name);
+        let (groups, leaf) = match path.rsplit_once('/') {
+            Some((groups, leaf)) => (Some(groups), leaf),
+            None => (None, path.as_str()),
+        };
+
+        let mut parent_ino = 1;
+        if let Some(groups) = groups {
+            let mut group_path = String::new();
+            for component in groups.split('/') {
+                if !group_path.is_empty() {
+                    group_path.push('/');
+                }
+                group_path.push_str(component);
+
+                let dir_name = self.feed_dir_name(component);
+                parent_ino = match self.get_node_by_name(parent_ino, &dir_name) {
+                    Some(node) if matches!(node.node_type, NodeType::GroupDirectory(_)) => node.ino,
+                    Some(_) => return Err(format!("'{}' is not a feed group", group_path)),
+                    None => self.create_node(parent_ino, dir_name, NodeType::GroupDirectory(group_path.clone()))?,
+                };
+            }
+        }
+
+        Ok((parent_ino, self.feed_dir_name(leaf)))
+    }
+
+    /// The views and `.meta` directory inside a feed's directory
+    fn create_feed_contents(&self, feed_ino: u64, feed_name: &str) -> Result<(), String> {
+        if self.config.read().read_state_views {
+            for view in [ArticleView::Unread, ArticleView::Read] {
+                self.create_node(feed_ino, view.dir_name().to_string(), NodeType::ViewDirectory(feed_name.to_string(), view))?;
+            }
+        }
+        if self.config.read().feed_meta {
+            let meta_ino = self.create_node(feed_ino, ".meta".to_string(), NodeType::FeedMetaDirectory(feed_name.to_string()))?;
+            for file in GeneratedFile::feed_files(feed_name) {
+                let name = match file {
+                    GeneratedFile::FeedIcon(_) => self.icon_names.read().get(feed_name).copied().unwrap_or(file.file_name()),
+                    _ => file.file_name(),
+                };
+                self.create_node(meta_ino, name.to_string(), NodeType::GeneratedFile(file))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `group/name` path a feed's directory is presented at: its name, unless
+    /// the directory was renamed while mounted
+    fn feed_path(&self, feed_name: &str) -> String {
+        self.feed_paths.read().get(feed_name).cloned().unwrap_or_else(|| feed_name.to_string())
+    }
+
+    /// Present a feed's directory as `new_name` within the same parent. The
+    /// feed keeps its name internally, and refreshes land in the renamed
+    /// directory.
+    pub fn rename_feed_directory(&self, feed_name: &str, new_name: &str) -> Result<(), String> {
+        let node = self.get_feed_directory(feed_name).ok_or("Feed directory not found")?;
+        if new_name.is_empty() || new_name.contains('/') {
+            return Err(format!("Invalid feed directory name '{}'", new_name));
+        }
+        let dir_name = self.feed_dir_name(new_name);
+        if self.get_node_by_name(node.parent_ino, &dir_name).is_some() {
+            return Err(format!("'{}' already exists", dir_name));
+        }
+
+        self.name_to_ino.remove(&(node.parent_ino, node.name.clone()));
+        self.name_to_ino.insert((node.parent_ino, dir_name.clone()), node.ino);
+        self.update_node(node.ino, |renamed| {
+            renamed.name = dir_name;
+            renamed.touch_modified();
+        });
+        self.update_node(node.parent_ino, VNode::touch_modified);
+
+        let path = match self.feed_path(feed_name).rsplit_once('/') {
+            Some((groups, _)) => format!("{}/{}", groups, new_name),
+            None => new_name.to_string(),
+        };
+        self.feed_paths.write().insert(feed_name.to_string(), path);
+        Ok(())
+    }
+
+    /// Present a feed's directory as `dir_name` within its group, e.g. a
+    /// numbered name when its own is taken by another feed. Moves the
+    /// directory if it already exists.
+    pub fn set_feed_dir_name(&self, feed_name: &str, dir_name: &str) -> Result<(), String> {
+        if self.get_feed_directory(feed_name).is_some() {
+            if self.feed_dir_names(feed_name).last() == Some(&self.feed_dir_name(dir_name)) {
+                return Ok(());
+            }
+            return self.rename_feed_directory(feed_name, dir_name);
+        }
+        let path = match feed_name.rsplit_once('/') {
+            Some((groups, _)) => format!("{}/{}", groups, dir_name),
+            None => dir_name.to_string(),
+        };
+        self.feed_paths.write().insert(feed_name.to_string(), path);
+        Ok(())
+    }
+
+    /// Present a feed's `.meta` icon as `file_name`, once the type of the
+    /// downloaded image is known
+    pub fn set_icon_name(&self, feed_name: &str, file_name: &'static str) {
+        if self.icon_names.write().insert(feed_name.to_string(), file_name) == Some(file_name) {
+            return;
+        }
+        let Some(meta) = self.get_feed_directory(feed_name).and_then(|dir| self.get_node_by_name(dir.ino, ".meta")) else {
+            return;
+        };
+        let icon = meta.children.iter()
+            .filter_map(|&ino| self.get_node(ino))
+            .find(|node| matches!(node.node_type, NodeType::GeneratedFile(GeneratedFile::FeedIcon(_))));
+        let Some(icon) = icon.filter(|icon| icon.name != file_name) else {
+            return;
+        };
+
+        self.name_to_ino.remove(&(meta.ino, icon.name.clone()));
+        self.name_to_ino.insert((meta.ino, file_name.to_string()), icon.ino);
+        self.update_node(icon.ino, |renamed| {
+            renamed.name = file_name.to_string();
+            renamed.touch_modified();
+        });
+        self.update_node(meta.ino, VNode::touch_modified);
+    }
+
+    /// Remove a feed's directory and contents, then any group directories
+    /// left empty by the removal
+    pub fn remove_feed_directory(&self, feed_name: &str) -> Result<(), String> {
+        let Some(feed_node) = self.get_feed_directory(feed_name) else {
+            return Ok(());
+        };
+
+        let mut parent_ino = feed_node.parent_ino;
+        self.remove_subtree(feed_node.ino)?;
+
+        while let Some(parent) = self.get_node(parent_ino) {
+            if !matches!(parent.node_type, NodeType::GroupDirectory(_)) || !parent.children.is_empty() {
+                break;
+            }
+            parent_ino = parent.parent_ino;
+            self.remove_node(parent.ino)?;
+        }
+        Ok(())
+    }
+
+    /// Add an article that is not part of a feed listing (e.g. a placeholder);
+    /// it is named after its title regardless of any filename template
+    pub fn create_article_file(&self, feed_name: &str, article: Arc<Article>) -> Result<u64, String> {
+        self.create_indexed_article_file(feed_name, article, None)
+    }
+
+    /// Add the files of a feed listing, given as (1-based index, article).
+    /// Only the newest `max_visible_articles` are listed, and a listing
+    /// longer than `date_folders_after` is split into year/month folders.
+    pub fn create_feed_listing(&self, feed_name: &str, mut articles: Vec<(usize, Arc<Article>)>) {
+        if let Some(&limit) = self.visible_limits.read().get(feed_name) {
+            if articles.len() > limit {
+                // Newest first; undated articles sort last
+                articles.sort_by_key(|(_, article)| std::cmp::Reverse(article.published));
+                articles.truncate(limit);
+                articles.sort_by_key(|(index, _)| *index);
+            }
+        }
+        if let Some(languages) = self.language_filters.read().get(feed_name) {
+            articles.retain(|(_, article)| {
+                self.language(&article.id).map_or(true, |language| languages.iter().any(|listed| listed == language))
+            });
+        }
+        if self.config.read().duplicates == DuplicateMode::Hide {
+            articles.retain(|(_, article)| self.is_primary_copy(feed_name, &article.id));
+        }
+        let threshold = self.config.read().date_folders_after;
+        let dated = threshold > 0 && articles.len() > threshold;
+        for (index, article) in articles {
+            if let Err(e) = self.create_listed_article_file(feed_name, &article, Some(index), dated) {
+                warn!("Failed to create article file for {}: {}", article.title, e);
+            }
+        }
+    }
+
+    /// Add the `index`th (1-based) article of a feed listing
+    pub fn create_indexed_article_file(&self, feed_name: &str, article: Arc<Article>, index: Option<usize>) -> Result<u64, String> {
+        self.create_listed_article_file(feed_name, &article, index, false)
+    }
+
+    /// Add an article's file, in `<year>/<month>/` below its folder if
+    /// `dated` and the article has a date
+    fn create_listed_article_file(&self, feed_name: &str, article: &Arc<Article>, index: Option<usize>, dated: bool) -> Result<u64, String> {
+        // Get or create feed directory, preferring one being built to replace it
+        let staged = self.staged.read().get(feed_name).copied();
+        let feed_ino = match staged.or_else(|| Some(self.get_feed_directory(feed_name)?.ino)) {
+            Some(ino) => ino,
+            None => self.create_feed_directory(feed_name)?,
+        };
+
+        let (filename, aged) = self.article_file_name(feed_name, article, index);
+        let mut parent_ino = feed_ino;
+        if self.config.read().duplicates == DuplicateMode::Group && !self.is_primary_copy(feed_name, &article.id) {
+            parent_ino = match self.get_node_by_name(feed_ino, DUPLICATES_DIR) {
+                Some(node) => node.ino,
+                None => self.create_node(feed_ino, DUPLICATES_DIR.to_string(), NodeType::DuplicatesFolder(feed_name.to_string()))?,
+            };
+        }
+        if let Some(folder) = &article.folder {
+            parent_ino = match self.get_node_by_name(parent_ino, folder) {
+                Some(node) => node.ino,
+                None => self.create_node(parent_ino, folder.clone(), NodeType::FilterFolder(feed_name.to_string()))?,
+            };
+        }
+        let aging = self.config.read().article_aging.clone();
+        if aged && aging.marker == AgingMarker::Folder {
+            let base_ino = parent_ino;
+            parent_ino = match self.get_node_by_name(base_ino, &aging.folder) {
+                Some(node) => node.ino,
+                None => self.create_node(
+                    base_ino,
+                    aging.folder.clone(),
+                    NodeType::AgedDirectory(feed_name.to_string()),
+                )?,
+            };
+        }
+        if let Some(date) = article.published.or(article.cached_at).filter(|_| dated) {
+            for folder in [date.format("%Y").to_string(), date.format("%m").to_string()] {
+                parent_ino = match self.get_node_by_name(parent_ino, &folder) {
+                    Some(node) => node.ino,
+                    None => self.create_node(parent_ino, folder, NodeType::DateFolder(feed_name.to_string()))?,
+                };
+            }
+        }
+
+        let filename = self.unused_name(parent_ino, filename);
+        let stem = filename.rsplit_once('.').map_or(filename.as_str(), |(stem, _)| stem).to_string();
+        let stable_ino = self.claim_article_ino(feed_name, &article.id);
+        let ino = self.create_article_node(Some(stable_ino), parent_ino, feed_name, filename, Arc::clone(article))?;
+        self.create_enclosure_files(parent_ino, feed_name, &stem, article)?;
+        self.create_summary_file(feed_ino, feed_name, &stem, article)?;
+        let full = self.full_content.read().get(&article.id).cloned();
+        self.create_asset_files(parent_ino, feed_name, &self.with_bodies(full.as_deref().unwrap_or(article)))?;
+        Ok(ino)
+    }
+
+    /// List the images an article embeds in the `assets/` directory next to
+    /// it, where its Markdown points when `offline_images` is on
+    fn create_asset_files(&self, parent_ino: u64, feed_name: &str, article: &Article) -> Result<(), String> {
+        if !self.config.read().offline_images {
+            return Ok(());
+        }
+        let urls = self.extractor(feed_name).image_urls(article);
+        if urls.is_empty() {
+            return Ok(());
+        }
+        let assets_ino = match self.get_node_by_name(parent_ino, ASSETS_DIR) {
+            Some(node) => node.ino,
+            None => self.create_node(parent_ino, ASSETS_DIR.to_string(), NodeType::AssetsDirectory(feed_name.to_string()))?,
+        };
+        for url in urls {
+            let name = asset_file_name(&url);
+            if self.get_node_by_name(assets_ino, &name).is_none() {
+                self.create_node(assets_ino, name, NodeType::AssetFile(feed_name.to_string(), url))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Image files of a feed that are listed but may not be downloaded yet
+    pub fn asset_urls(&self, feed_name: &str) -> Vec<String> {
+        let mut urls: Vec<String> = self.filter_nodes(|_, node| match &node.node_type {
+            NodeType::AssetFile(feed, url) if feed == feed_name => Some(url.clone()),
+            _ => None,
+        });
+        urls.sort();
+        urls.dedup();
+        urls
+    }
+
+    /// A feed's articles, newest first, wherever in its directory they are
+    pub fn feed_articles(&self, feed_name: &str) -> Vec<Arc<Article>> {
+        let retired = self.retired_inos();
+        let mut articles: Vec<Arc<Article>> = self.filter_nodes(|ino, node| match &node.node_type {
+            NodeType::ArticleFile(feed, article) if feed == feed_name && !retired.contains(ino) => Some(Arc::clone(article)),
+            _ => None,
+        });
+        articles.sort_by(|a, b| b.published.cmp(&a.published).then_with(|| a.id.cmp(&b.id)));
+        articles.dedup_by(|a, b| a.id == b.id);
+        articles
+    }
+
+    /// List an article's enclosures next to its file, named after it with
+    /// the media's extension (e.g. `Episode 1.mp3`)
+    fn create_enclosure_files(&self, parent_ino: u64, feed_name: &str, stem: &str, article: &Arc<Article>) -> Result<(), String> {
+        if self.config.read().enclosures == EnclosureMode::Off {
+            return Ok(());
+        }
+        for (index, enclosure) in article.enclosures.iter().enumerate() {
+            let filename = naming::fit_name(stem, &format!(".{}", enclosure.extension()), naming::MAX_NAME_BYTES);
+            let filename = self.unused_name(parent_ino, filename);
+            self.create_node(parent_ino, filename, NodeType::EnclosureFile(feed_name.to_string(), Arc::clone(article), index))?;
+        }
+        Ok(())
+    }
+
+    /// List at most `limit` articles in the directory of `feed_name`; `None`
+    /// lists them all
+    pub fn set_max_visible_articles(&self, feed_name: &str, limit: Option<usize>) {
+        match limit {
+            Some(limit) => self.visible_limits.write().insert(feed_name.to_string(), limit),
+            None => self.visible_limits.write().remove(feed_name),
+        };
+    }
+
+    /// List an article's summary, if it has one, in the feed's `summary/`
+    /// directory under the article's file name
+    fn create_summary_file(&self, feed_ino: u64, feed_name: &str, stem: &str, article: &Arc<Article>) -> Result<(), String> {
+        let Some(summary) = self.summary(&article.id) else {
+            return Ok(());
+        };
+        let summary_ino = match self.get_node_by_name(feed_ino, SUMMARY_DIR) {
+            Some(node) => node.ino,
+            None => self.create_node(feed_ino, SUMMARY_DIR.to_string(), NodeType::SummaryDirectory(feed_name.to_string()))?,
+        };
+        let filename = naming::fit_name(stem, ".txt", naming::MAX_NAME_BYTES);
+        let ino = match self.get_node_by_name(summary_ino, &filename) {
+            Some(node) => node.ino,
+            None => self.create_node(summary_ino, filename, NodeType::SummaryFile(feed_name.to_string(), Arc::clone(article)))?,
+        };
+        self.update_node_size(ino, summary.len() as u64);
+        Ok(())
+    }
+
+    fn summary(&self, article_id: &str) -> Option<Arc<str>> {
+        self.summaries.read().get(article_id).cloned()
+    }
+
+    pub fn has_summary(&self, article_id: &str) -> bool {
+        self.summaries.read().contains_key(article_id)
+    }
+
+    /// Keep a summary for articles listed from now on
+    pub fn insert_summary(&self, article_id: &str, summary: &str) {
+        self.summaries.write().insert(article_id.to_string(), summary.into());
+    }
+
+    /// Add a summary to the article's files already listed: their frontmatter
+    /// and the `summary/` directory of their feed
+    pub fn set_summary(&self, article_id: &str, summary: &str) {
+        self.insert_summary(article_id, summary);
+        let files: Vec<VNode> = self.filter_nodes(|_, node| {
+            matches!(&node.node_type, NodeType::ArticleFile(_, article) if article.id == article_id).then(|| node.clone())
+        });
+        for file in files {
+            let NodeType::ArticleFile(feed_name, article) = &file.node_type else {
+                continue;
+            };
+            let stem = file.name.rsplit_once('.').map_or(file.name.as_str(), |(stem, _)| stem);
+            let Some(feed_dir) = self.get_feed_directory(feed_name) else {
+                continue;
+            };
+            if let Err(e) = self.create_summary_file(feed_dir.ino, feed_name, stem, article) {
+                warn!("Failed to list summary of {}: {}", article_id, e);
+            }
+        }
+    }
+
+    /// Detect the language of each of `articles` not seen before
+    pub fn detect_languages<'a>(&self, articles: impl IntoIterator<Item = &'a Article>) {
+        let new: Vec<(String, Option<&'static str>)> = {
+            let known = self.languages.read();
+            articles.into_iter()
+                .filter(|article| !known.contains_key(&article.id))
+                .map(|article| (article.id.clone(), language::detect_language(article)))
+                .collect()
+        };
+        self.languages.write().extend(new);
+    }
+
+    /// ISO 639-1 code of an article's language, if it could be detected
+    pub fn language(&self, article_id: &str) -> Option<&'static str> {
+        self.languages.read().get(article_id).copied().flatten()
+    }
+
+    /// List only articles of `feed_name` in `languages` (or whose language
+    /// is unknown) from its next refresh; `None` lists all
+    pub fn set_language_filter(&self, feed_name: &str, languages: Option<Vec<String>>) {
+        match languages {
+            Some(languages) => self.language_filters.write().insert(feed_name.to_string(), languages),
+            None => self.language_filters.write().remove(feed_name),
+        };
+    }
+
+    /// Record the keyword scores of articles, by id, computed on refresh
+    pub fn set_scores(&self, scores: impl IntoIterator<Item = (String, i64)>) {
+        self.scores.write().extend(scores);
+    }
+
+    pub fn score(&self, article_id: &str) -> Option<i64> {
+        self.scores.read().get(article_id).copied()
+    }
+
+    /// Record which of a feed's articles, by id, other feeds carry too and
+    /// which those are. Applies to the feed's listing built next.
+    pub fn set_duplicates(&self, feed_name: &str, duplicates: HashMap<String, Vec<String>>) {
+        if duplicates.is_empty() {
+            self.duplicates.write().remove(feed_name);
+        } else {
+            self.duplicates.write().insert(feed_name.to_string(), duplicates);
+        }
+    }
+
+    /// Other feeds carrying the story of an article of `feed_name`
+    fn also_in(&self, feed_name: &str, article_id: &str) -> Vec<String> {
+        self.duplicates.read().get(feed_name)
+            .and_then(|articles| articles.get(article_id).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Whether an article is listed normally when hiding or grouping duplicates
+    fn is_primary_copy(&self, feed_name: &str, article_id: &str) -> bool {
+        dedup::is_primary(feed_name, &self.also_in(feed_name, article_id))
+    }
+
+    /// Name articles of `feed_name` with `template` instead of their title;
+    /// `None` goes back to titles
+    pub fn set_filename_template(&self, feed_name: &str, template: Option<FilenameTemplate>) {
+        match template {
+            Some(template) => self.filename_templates.write().insert(feed_name.to_string(), template),
+            None => self.filename_templates.write().remove(feed_name),
+        };
+    }
+
+    /// File name for an article, and whether it is past the aging threshold.
+    /// The aging suffix is applied here; folder placement is up to the caller.
+    fn article_file_name(&self, feed_name: &str, article: &Article, index: Option<usize>) -> (String, bool) {
+        let (aging, transliterate, format) = {
+            let config = self.config.read();
+            (config.article_aging.clone(), config.transliterate_names, config.output_format)
+        };
+        let template = index.and_then(|index| Some((self.filename_templates.read().get(feed_name)?.clone(), index)));
+        let mut filename = match (template, format) {
+            (Some((template, index)), OutputFormat::Markdown) => article.templated_filename(&template, feed_name, index, "md"),
+            (Some((template, index)), OutputFormat::Text) => article.templated_filename(&template, feed_name, index, "txt"),
+            (Some((template, index)), OutputFormat::Html) => article.templated_filename(&template, feed_name, index, "html"),
+            (None, OutputFormat::Markdown) => article.markdown_filename(),
+            (None, OutputFormat::Text) => article.filename(),
+            (None, OutputFormat::Html) => article.html_filename(),
+        };
+        if transliterate {
+            filename = naming::transliterate(&filename);
+        }
+        let aged = aging.is_aged(article.published, self.clock().now_utc());
+        if aged && aging.marker == AgingMarker::Suffix {
+            filename = aging.apply_suffix(&filename);
+        }
+        let priority_at = self.config.read().scoring.priority_at;
+        if priority_at.is_some_and(|threshold| self.score(&article.id).is_some_and(|score| score >= threshold)) {
+            filename = format!("! {}", filename);
+        }
+        (naming::fit_filename(&filename, naming::MAX_NAME_BYTES), aged)
+    }
+
+    /// `filename`, or `filename (2)`, `filename (3)`... if it is taken in `parent_ino`
+    fn unused_name(&self, parent_ino: u64, filename: String) -> String {
+        if self.get_node_by_name(parent_ino, &filename).is_none() {
+            return filename;
+        }
+        (2..)
+            .map(|n| naming::with_collision_suffix(&filename, n, naming::MAX_NAME_BYTES))
+            .find(|candidate| self.get_node_by_name(parent_ino, candidate).is_none())
+            .unwrap_or(filename)
+    }
+
+    /// Article files are rendered when first read or measured, not here;
+    /// until then their size is that of the article's text
+    fn create_article_node(&self, ino: Option<u64>, parent_ino: u64, feed_name: &str, filename: String, article: Arc<Article>) -> Result<u64, String> {
+        let estimate = [Some(&article.title), article.description.as_ref(), article.content.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(String::len)
+            .sum::<usize>() as u64;
+        let ino = self.create_node_at(ino, parent_ino, filename, NodeType::ArticleFile(feed_name.to_string(), article))?;
+        self.update_node(ino, |node| node.size = estimate);
+        Ok(ino)
+    }
+
+    /// Inode number for an article's file in its feed directory: its stable
+    /// number, unless another article's file holds it. While a replacement
+    /// directory is being built the current copy keeps the number, and the
+    /// new file gets a temporary one to take it over from on commit.
+    fn claim_article_ino(&self, feed_name: &str, article_id: &str) -> u64 {
+        let staged = self.staged.read().get(feed_name).copied();
+        let retired = self.retired_inos();
+        let mut adoptions = self.adoptions.write();
+        let adopting = adoptions.entry(feed_name.to_string()).or_default();
+
+        let mut attempt = 0;
+        loop {
+            let ino = stable_article_ino(feed_name, article_id, attempt);
+            attempt += 1;
+            if adopting.iter().any(|(_, stable)| *stable == ino) {
+                continue;
+            }
+            let same_article = self.nodes.get(&ino).map(|node| matches!(&node.node_type,
+                NodeType::ArticleFile(feed, article) if feed == feed_name && article.id == article_id));
+            let Some(same_article) = same_article else {
+                return ino;
+            };
+            // Files already in the directory being built are not replaced
+            let replaced = staged.is_some_and(|dir| !is_within(&self.nodes, ino, dir));
+            if same_article && replaced {
+                let temp = self.allocate_ino();
+                adopting.push((temp, ino));
+                return temp;
+            }
+            if same_article && retired.contains(&ino) {
+                // A replaced copy still in its grace period; this one takes over
+                self.unretire(ino);
+                return ino;
+            }
+        }
+    }
+
+    /// Take an inode out of the retired directories, so it is not removed
+    /// with them
+    fn unretire(&self, ino: u64) {
+        for tree in self.retired.lock().iter_mut() {
+            tree.inos.retain(|retired| *retired != ino);
+        }
+    }
+
+    /// Give staged files the stable numbers of the copies they replace.
+    /// Called during a swap.
+    fn adopt_stable_inos(&self, adoptions: Vec<(u64, u64)>) -> Vec<u64> {
+        let mut adopted = Vec::new();
+        for (temp, stable) in adoptions {
+            let Some((_, mut node)) = self.nodes.remove(&temp) else {
+                continue;
+            };
+            // The replaced copy's directory still lists the number, so
+            // readers finishing with it find the new file under the old name
+            node.ino = stable;
+            self.update_node(node.parent_ino, |parent| {
+                parent.remove_child(temp);
+                parent.add_child(stable);
+            });
+            self.name_to_ino.insert((node.parent_ino, node.name.clone()), stable);
+            self.nodes.insert(stable, node);
+            adopted.push(stable);
+        }
+        adopted
+    }
+
+    /// Bring a view directory's entries in line with `articles`, keeping the
+    /// inodes of articles that are still listed
+    pub fn sync_view_directory(&self, view_ino: u64, articles: &[Arc<Article>]) -> Result<(), String> {
+        let node = self.get_node(view_ino).ok_or("View directory not found")?;
+        let NodeType::ViewDirectory(feed_name, _) = &node.node_type else {
+            return Err("Not a view directory".to_string());
+        };
+
+        let entries: Vec<(String, Arc<Article>)> = articles.iter()
+            .map(|article| (feed_name.clone(), Arc::clone(article)))
+            .collect();
+        self.sync_article_directory(view_ino, &entries)
+    }
+
+    /// Make a computed directory list exactly the given `(feed name, article)`
+    /// entries. Articles already listed keep their inode; a name clash between
+    /// feeds is resolved by prefixing the feed name, any other by numbering.
+    pub fn sync_article_directory(&self, dir_ino: u64, entries: &[(String, Arc<Article>)]) -> Result<(), String> {
+        let wanted: std::collections::HashSet<&str> = entries.iter().map(|(_, a)| a.id.as_str()).collect();
+        let mut present = std::collections::HashSet::new();
+        for child in self.list_children(dir_ino) {
+            match &child.node_type {
+                NodeType::ArticleFile(_, article) if wanted.contains(article.id.as_str()) => {
+                    present.insert(article.id.clone());
+                }
+                _ => self.remove_node(child.ino)?,
+            }
+        }
+
+        for (index, (feed_name, article)) in entries.iter().enumerate().filter(|(_, (_, a))| !present.contains(&a.id)) {
+            let (mut filename, _) = self.article_file_name(feed_name, article, Some(index + 1));
+            if self.get_node_by_name(dir_ino, &filename).is_some() {
+                let prefixed = format!("{} - {}", feed_name.replace('/', "-"), filename);
+                filename = naming::fit_filename(&prefixed, naming::MAX_NAME_BYTES);
+            }
+            let filename = self.unused_name(dir_ino, filename);
+            self.create_article_node(None, dir_ino, feed_name, filename, Arc::clone(article))?;
+        }
+        Ok(())
+    }
+
+    /// Make a symlink directory list exactly the given `(feed name, article)`
+    /// entries, each pointing at the article's file in its feed directory.
+    /// Articles without a file there (e.g. hidden as read) are skipped.
+    pub fn sync_link_directory(&self, dir_ino: u64, entries: &[(String, Arc<Article>)]) -> Result<(), String> {
+        let mut wanted = HashMap::new();
+        for (feed_name, article) in entries {
+            if let Some((file_name, target)) = self.link_target(dir_ino, feed_name, &article.id) {
+                wanted.insert(article.id.as_str(), (feed_name, article, file_name, target));
+            }
+        }
+
+        for child in self.list_children(dir_ino) {
+            let kept = match &child.node_type {
+                NodeType::ArticleLink(_, article, target)
+                    if wanted.get(article.id.as_str()).is_some_and(|(.., wanted_target)| wanted_target == target) =>
+                {
+                    Some(article.id.clone())
+                }
+                _ => None,
+            };
+            match kept {
+                Some(id) => {
+                    wanted.remove(id.as_str());
+                }
+                None => self.remove_node(child.ino)?,
+            }
+        }
+
+        // Insert in entry order so clashes are resolved the same way each time
+        for (_, article) in entries {
+            let Some((feed_name, article, mut file_name, target)) = wanted.remove(article.id.as_str()) else {
+                continue;
+            };
+            if self.get_node_by_name(dir_ino, &file_name).is_some() {
+                let prefixed = format!("{} - {}", feed_name.replace('/', "-"), file_name);
+                file_name = naming::fit_filename(&prefixed, naming::MAX_NAME_BYTES);
+            }
+            if self.get_node_by_name(dir_ino, &file_name).is_none() {
+                self.create_node(dir_ino, file_name, NodeType::ArticleLink(feed_name.clone(), Arc::clone(article), target))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Make `dir_ino` hold symlinks to exactly `entries`, named after the
+    /// articles' files with their place in `entries` in front (`01 Title.md`),
+    /// so sorting by name keeps the given order
+    pub fn sync_ranked_link_directory(&self, dir_ino: u64, entries: &[(String, Arc<Article>)]) -> Result<(), String> {
+        let width = entries.len().to_string().len().max(2);
+        let mut wanted = HashMap::new();
+        for (rank, (feed_name, article)) in entries.iter().enumerate() {
+            if let Some((file_name, target)) = self.link_target(dir_ino, feed_name, &article.id) {
+                let name = naming::fit_filename(&format!("{:0width$} {}", rank + 1, file_name, width = width), naming::MAX_NAME_BYTES);
+                wanted.insert(name, (feed_name, article, target));
+            }
+        }
 
-    pub fn update_content(&mut self, new_size: u64) {
-        self.size = new_size;
-        self.touch_modified();
+        for child in self.list_children(dir_ino) {
+            let kept = matches!(&child.node_type, NodeType::ArticleLink(_, _, target)
+                if wanted.get(&child.name).is_some_and(|(.., wanted_target)| wanted_target == target));
+            if kept {
+                wanted.remove(&child.name);
+            } else {
+                self.remove_node(child.ino)?;
+            }
+        }
+        for (name, (feed_name, article, target)) in wanted {
+            self.create_node(dir_ino, name, NodeType::ArticleLink(feed_name.clone(), Arc::clone(article), target))?;
+        }
+        Ok(())
     }
 
-    pub fn is_directory(&self) -> bool {
-        matches!(self.file_type, FileType::Directory)
+    /// Add a symlink named `name` in `dir_ino` to an article's file, as
+    /// [`sync_link_directory`](Self::sync_link_directory) would list it
+    pub fn create_article_link(&self, dir_ino: u64, name: &str, feed_name: &str, article: Arc<Article>) -> Result<u64, String> {
+        let (_, target) = self.link_target(dir_ino, feed_name, &article.id)
+            .ok_or_else(|| format!("Article {} has no file to link to", article.id))?;
+        self.create_node(dir_ino, name.to_string(), NodeType::ArticleLink(feed_name.to_string(), article, target))
     }
 
-    pub fn is_file(&self) -> bool {
-        matches!(self.file_type, FileType::RegularFile)
+    /// File name and relative target of a link in `dir_ino` to an article's
+    /// file in its feed directory
+    fn link_target(&self, dir_ino: u64, feed_name: &str, article_id: &str) -> Option<(String, String)> {
+        let dir_path = self.path_of(dir_ino)?;
+        let up = "../".repeat(dir_path.matches('/').count() + 1);
+        self.find_article_nodes(feed_name, article_id).into_iter()
+            .filter(|node| matches!(node.node_type, NodeType::ArticleFile(_, _)))
+            .filter(|node| !matches!(
+                self.get_node(node.parent_ino).map(|parent| parent.node_type),
+                Some(NodeType::ViewDirectory(_, _))
+            ))
+            .find_map(|node| Some((node.name.clone(), format!("{}{}", up, self.path_of(node.ino)?))))
     }
-}
 
-/// Inode manager for the virtual filesystem
-pub struct InodeManager {
-    nodes: RwLock<HashMap<u64, VNode>>,
-    next_ino: RwLock<u64>,
-    name_to_ino: RwLock<HashMap<(u64, String), u64>>, // (parent_ino, name) -> ino
-}
+    /// The node a symlink target resolves to. Relative targets are followed
+    /// from `dir_ino`; absolute ones name the mount point first, which is not
+    /// known here, so the longest tail that exists below the root is taken.
+    pub fn resolve_link_target(&self, dir_ino: u64, target: &std::path::Path) -> Option<VNode> {
+        use std::path::Component;
 
-impl InodeManager {
-    pub fn new() -> Self {
-        let mut manager = Self {
-            nodes: RwLock::new(HashMap::new()),
-            next_ino: RwLock::new(2), // Start from 2, 1 is reserved for root
-            name_to_ino: RwLock::new(HashMap::new()),
+        let walk = |start: u64, components: &[Component]| -> Option<VNode> {
+            let mut node = self.get_node(start)?;
+            for component in components {
+                node = match component {
+                    Component::CurDir => node,
+                    Component::ParentDir => self.get_node(node.parent_ino)?,
+                    Component::Normal(name) => self.get_node_by_name(node.ino, name.to_str()?)?,
+                    Component::RootDir | Component::Prefix(_) => return None,
+                };
+            }
+            Some(node)
         };
 
-        // Create root directory
-        manager.create_root();
-        manager
+        let components: Vec<Component> = target.components().collect();
+        if target.is_absolute() {
+            (1..components.len()).find_map(|skip| walk(1, &components[skip..]))
+        } else {
+            walk(dir_ino, &components)
+        }
     }
 
-    fn create_root(&self) {
-        let root = VNode::new(1, 1, "/".to_string(), NodeType::Root);
-        self.nodes.write().insert(1, root);
-        self.name_to_ino.write().insert((1, "/".to_string()), 1);
-    }
+    /// Apply `update` to every copy of an article held by nodes (and its
+    /// fetched full content). Its files are rendered afresh when next read.
+    pub fn update_article(&self, article_id: &str, update: impl Fn(&mut Article)) {
+        if let Some(full) = self.full_content.write().get_mut(article_id) {
+            update(Arc::make_mut(full));
+        }
 
-    pub fn allocate_ino(&self) -> u64 {
-        let mut next_ino = self.next_ino.write();
-        let ino = *next_ino;
-        *next_ino += 1;
-        ino
+        for mut node in self.nodes.iter_mut() {
+            match &mut node.node_type {
+                NodeType::ArticleFile(_, article)
+                | NodeType::ArticleLink(_, article, _)
+                | NodeType::EnclosureFile(_, article, _) if article.id == article_id => update(Arc::make_mut(article)),
+                _ => {}
+            }
+        }
     }
 
-    pub fn get_node(&self, ino: u64) -> Option<VNode> {
-        self.nodes.read().get(&ino).cloned()
+    /// Path of a node relative to the mount root
+    pub fn path_of(&self, ino: u64) -> Option<String> {
+        let mut components = Vec::new();
+        let mut node = self.get_node(ino)?;
+        while node.ino != 1 {
+            components.push(node.name.clone());
+            node = self.get_node(node.parent_ino)?;
+        }
+        components.reverse();
+        Some(components.join("/"))
     }
 
-    pub fn get_node_by_name(&self, parent_ino: u64, name: &str) -> Option<VNode> {
-        let name_to_ino = self.name_to_ino.read();
-        let ino = name_to_ino.get(&(parent_ino, name.to_string()))?;
-        self.nodes.read().get(ino).cloned()
+    /// Create the `latest/`, `today/`, `starred/`, `top/` and `tags/` directories
+    /// enabled in the config
+    pub fn create_recent_directories(&self) -> Result<(), String> {
+        let config = self.config.read().clone();
+        let views = [
+            (RecentView::Latest, config.latest_articles > 0),
+            (RecentView::Today, config.today_dir),
+            (RecentView::Starred, config.starred_dir),
+            (RecentView::Top, config.scoring.top_articles > 0 && !config.scoring.is_disabled()),
+        ];
+        for (view, enabled) in views {
+            if enabled && self.get_node_by_name(1, view.dir_name()).is_none() {
+                self.create_node(1, view.dir_name().to_string(), NodeType::RecentDirectory(view))?;
+            }
+        }
+        if config.tags_dir && self.get_node_by_name(1, "tags").is_none() {
+            self.create_node(1, "tags".to_string(), NodeType::TagsRoot)?;
+        }
+        Ok(())
     }
 
-    pub fn create_node(&self, parent_ino: u64, name: String, node_type: NodeType) -> Result<u64, String> {
-        // Check if parent exists and is a directory
-        let parent = self.get_node(parent_ino)
-            .ok_or("Parent directory not found")?;
-        
-        if !parent.is_directory() {
-            return Err("Parent is not a directory".to_string());
+    /// Make `tags/` hold exactly the named tag directories, keeping the
+    /// inodes of tags that are still present
+    pub fn sync_tag_directories(&self, root_ino: u64, names: &std::collections::BTreeSet<String>) -> Result<(), String> {
+        for child in self.list_children(root_ino) {
+            if !matches!(&child.node_type, NodeType::TagDirectory(name) if names.contains(name)) {
+                self.remove_subtree(child.ino)?;
+            }
+        }
+        for name in names {
+            if self.get_node_by_name(root_ino, name).is_none() {
+                self.create_node(root_ino, name.clone(), NodeType::TagDirectory(name.clone()))?;
+            }
         }
+        Ok(())
+    }
 
-        // Check if name already exists
-        if self.get_node_by_name(parent_ino, &name).is_some() {
-            return Err("File already exists".to_string());
+    /// The `.search` directory, created on first use
+    pub fn search_root(&self) -> Result<u64, String> {
+        match self.get_node_by_name(1, ".search") {
+            Some(node) => Ok(node.ino),
+            None => self.create_node(1, ".search".to_string(), NodeType::SearchRoot),
         }
+    }
 
-        let ino = self.allocate_ino();
-        let node = VNode::new(ino, parent_ino, name.clone(), node_type);
+    /// All nodes in a feed's directory tree that present the given article:
+    /// its file and any enclosures
+    pub fn find_article_nodes(&self, feed_name: &str, article_id: &str) -> Vec<VNode> {
+        let Some(feed_dir) = self.get_feed_directory(feed_name) else {
+            return Vec::new();
+        };
 
-        // Add to parent's children
-        {
-            let mut nodes = self.nodes.write();
-            nodes.insert(ino, node);
-            if let Some(parent) = nodes.get_mut(&parent_ino) {
-                parent.add_child(ino);
+        let mut found = Vec::new();
+        let mut pending = vec![feed_dir.ino];
+        while let Some(ino) = pending.pop() {
+            for child in self.list_children(ino) {
+                match &child.node_type {
+                    NodeType::ArticleFile(_, article) | NodeType::EnclosureFile(_, article, _)
+                        if article.id == article_id => found.push(child),
+                    _ if child.is_directory() => pending.push(child.ino),
+                    _ => {}
+                }
             }
         }
-
-        // Add to name lookup
-        self.name_to_ino.write().insert((parent_ino, name), ino);
-
-        // Touch parent directory to update its modification time
-        self.touch_directory_and_parents(parent_ino);
-
-        Ok(ino)
+        found
     }
 
-    pub fn remove_node(&self, ino: u64) -> Result<(), String> {
-        if ino == 1 {
-            return Err("Cannot remove root directory".to_string());
+    /// Use custom content selectors when rendering (and fetching) a feed's
+    /// articles; `None` goes back to the default ones
+    pub fn set_content_selectors(&self, feed_name: &str, selectors: Option<ContentSelectors>) -> Result<(), String> {
+        match selectors {
+            Some(selectors) => {
+                let extractor = ContentExtractor::with_selectors(selectors).map_err(|e| e.to_string())?
+                    .with_local_images(self.config.read().offline_images);
+                self.extractors.write().insert(feed_name.to_string(), Arc::new(extractor));
+            }
+            None => {
+                self.extractors.write().remove(feed_name);
+            }
+        }
+        // Renderings with the old selectors can no longer be asked for
+        let mut rendered = self.rendered.lock();
+        let stale: Vec<RenderKey> = rendered.iter()
+            .map(|(key, _)| key)
+            .filter(|key| key.feed == feed_name)
+            .cloned()
+            .collect();
+        for key in stale {
+            rendered.pop(&key);
         }
+        Ok(())
+    }
 
-        let node = self.get_node(ino)
-            .ok_or("Node not found")?;
+    /// Extractor for a feed's articles: its custom one, or the default
+    pub fn extractor(&self, feed_name: &str) -> Arc<ContentExtractor> {
+        self.extractors.read().get(feed_name).cloned()
+            .unwrap_or_else(|| Arc::clone(&self.default_extractor.read()))
+    }
 
-        let parent_ino = node.parent_ino;
+    pub fn has_full_content(&self, article_id: &str) -> bool {
+        self.full_content.read().contains_key(article_id)
+    }
 
-        // Remove from parent's children
-        {
-            let mut nodes = self.nodes.write();
-            if let Some(parent) = nodes.get_mut(&node.parent_ino) {
-                parent.remove_child(ino);
+    /// Serve `article` (carrying content fetched from its link) in place of
+    /// the feed's copy, now and for nodes created on later refreshes
+    pub fn set_full_content(&self, mut article: Arc<Article>) {
+        let id = article.id.clone();
+        let store = self.content_store();
+        let _pinned = store.as_ref().map(|store| store.spill_guard());
+        self.spill_bodies(Arc::make_mut(&mut article));
+        self.full_content.write().insert(id.clone(), Arc::clone(&article));
+
+        let files: Vec<(u64, String)> = self.filter_nodes(|_, node| match &node.node_type {
+            NodeType::ArticleFile(feed_name, article) if article.id == id => Some((node.parent_ino, feed_name.clone())),
+            _ => None,
+        });
+        for (parent_ino, feed_name) in files {
+            // The fetched page may embed images the feed's copy did not
+            if let Err(e) = self.create_asset_files(parent_ino, &feed_name, &self.with_bodies(&article)) {
+                warn!("Failed to list images of {}: {}", article.id, e);
             }
-            nodes.remove(&ino);
         }
+    }
 
-        // Remove from name lookup
-        self.name_to_ino.write().remove(&(node.parent_ino, node.name));
-
-        // Touch parent directory to update its modification time
-        self.touch_directory_and_parents(parent_ino);
+    /// Keep article bodies over the configured size in `store` from now on
+    pub fn set_content_store(&self, store: Arc<ContentStore>) {
+        *self.content_store.write() = Some(store);
+    }
 
-        Ok(())
+    pub fn content_store(&self) -> Option<Arc<ContentStore>> {
+        self.content_store.read().clone()
     }
 
-    pub fn list_children(&self, parent_ino: u64) -> Vec<VNode> {
-        let nodes = self.nodes.read();
-        if let Some(parent) = nodes.get(&parent_ino) {
-            parent.children.iter()
-                .filter_map(|&child_ino| nodes.get(&child_ino))
-                .cloned()
-                .collect()
-        } else {
-            Vec::new()
+    /// Move an article's long bodies to the content store, if there is one.
+    /// On failure they stay in memory.
+    pub fn spill_bodies(&self, article: &mut Article) {
+        if let Some(store) = self.content_store() {
+            if let Err(e) = store.spill(article) {
+                warn!("Failed to move the body of {} to disk: {}", article.id, e);
+            }
         }
     }
 
-    pub fn update_node_size(&self, ino: u64, size: u64) {
-        if let Some(node) = self.nodes.write().get_mut(&ino) {
-            node.update_content(size);
+    /// An article with any bodies kept in the content store read back
+    pub fn with_bodies<'a>(&self, article: &'a Article) -> Cow<'a, Article> {
+        match self.content_store() {
+            Some(store) => store.restore(article),
+            None => Cow::Borrowed(article),
         }
     }
 
-    pub fn touch_node_accessed(&self, ino: u64) {
-        if let Some(node) = self.nodes.write().get_mut(&ino) {
-            node.touch_accessed();
-        }
+    /// Hashes of the stored bodies that nodes and fetched pages refer to
+    pub fn content_refs(&self) -> HashSet<String> {
+        let full_content = self.full_content.read();
+        let node_articles = self.filter_nodes(|_, node| match &node.node_type {
+            NodeType::ArticleFile(_, article) | NodeType::ArticleLink(_, article, _)
+                | NodeType::EnclosureFile(_, article, _) => Some(Arc::clone(article)),
+            _ => None,
+        });
+        full_content.values().chain(&node_articles)
+            .flat_map(|article| article.content_ref.iter().chain(&article.description_ref))
+            .cloned()
+            .collect()
     }
 
-    pub fn touch_node_modified(&self, ino: u64) {
-        if let Some(node) = self.nodes.write().get_mut(&ino) {
-            node.touch_modified();
+    /// An article's file content in the configured format, reusing an
+    /// earlier rendering from the same inputs. Markdown and HTML fall back
+    /// to plain text (with the configured HTML policy) on error.
+    fn render_article(&self, feed_name: &str, article: &Article) -> Arc<str> {
+        let full = self.full_content.read().get(&article.id).cloned();
+        let article = full.as_deref().unwrap_or(article);
+        let (format, policy, annotate) = {
+            let config = self.config.read();
+            (config.output_format, config.text_html_policy, config.duplicates == DuplicateMode::Annotate)
+        };
+        let annotations = Annotations {
+            also_in: if annotate { self.also_in(feed_name, &article.id) } else { Vec::new() },
+            summary: self.summary(&article.id).map(|summary| summary.to_string()),
+            score: self.score(&article.id),
+            language: self.language(&article.id).map(str::to_string),
+        };
+        let extractor = self.extractor(feed_name);
+        // Spilled bodies are hashed by their reference, so this reads nothing back
+        let mut source = DefaultHasher::new();
+        article.hash(&mut source);
+        annotations.hash(&mut source);
+        let key = RenderKey {
+            feed: feed_name.to_string(),
+            article_id: article.id.clone(),
+            format,
+            extractor: extractor.config_hash(),
+            source: source.finish(),
+        };
+        if let Some(content) = self.rendered.lock().get(&key) {
+            return Arc::clone(content);
         }
+
+        let article = self.with_bodies(article);
+        let article = article.as_ref();
+        let content: Arc<str> = match format {
+            OutputFormat::Markdown => extractor.extract_article_annotated(article, feed_name, &annotations)
+                .unwrap_or_else(|_| article.to_text_with_policy(policy)),
+            OutputFormat::Text => article.to_text_with_policy(policy),
+            OutputFormat::Html => extractor.extract_article_html_annotated(article, feed_name, &annotations)
+                .unwrap_or_else(|_| article.to_text_with_policy(policy)),
+        }.into();
+        self.rendered.lock().put(key, Arc::clone(&content));
+        content
     }
 
-    pub fn touch_directory_and_parents(&self, ino: u64) {
-        let mut current_ino = ino;
-        loop {
-            let parent_ino = {
-                let mut nodes = self.nodes.write();
-                if let Some(node) = nodes.get_mut(&current_ino) {
-                    if node.is_directory() {
-                        node.touch_modified();
-                    }
-                    node.parent_ino
-                } else {
-                    break;
-                }
-            };
+    /// Apply presentation settings. Naming and placement changes apply to
+    /// nodes created after the change (i.e. on the next refresh).
+    pub fn set_config(&self, config: FilesystemConfig) {
+        let local_images = config.offline_images;
+        *self.config.write() = config;
+        self.rendered.lock().clear();
 
-            if current_ino == parent_ino || parent_ino == 1 {
-                // Reached root or self-referencing node
-                break;
-            }
-            current_ino = parent_ino;
+        let rebuild = |extractor: &Arc<ContentExtractor>| Arc::new((**extractor).clone().with_local_images(local_images));
+        let default = rebuild(&self.default_extractor.read());
+        *self.default_extractor.write() = default;
+        for extractor in self.extractors.write().values_mut() {
+            *extractor = rebuild(extractor);
         }
     }
 
-    pub fn create_feed_directory(&self, feed_name: &str) -> Result<u64, String> {
-        self.create_node(1, feed_name.to_string(), NodeType::FeedDirectory(feed_name.to_string()))
+    pub fn config(&self) -> FilesystemConfig {
+        self.config.read().clone()
     }
 
-    pub fn create_article_file(&self, feed_name: &str, article: Arc<Article>) -> Result<u64, String> {
-        // Get or create feed directory
-        let feed_ino = match self.get_node_by_name(1, feed_name) {
-            Some(node) => node.ino,
-            None => self.create_feed_directory(feed_name)?,
-        };
+    /// Replace the time source used for article aging
+    pub fn set_clock(&self, clock: SharedClock) {
+        *self.clock.write() = clock;
+    }
 
-        let filename = article.markdown_filename();
-        self.create_node(feed_ino, filename, NodeType::ArticleFile(feed_name.to_string(), article))
+    pub fn clock(&self) -> SharedClock {
+        Arc::clone(&self.clock.read())
     }
 
     pub fn create_meta_structure(&self) -> Result<(), String> {
@@ -286,27 +1700,51 @@ impl InodeManager {
         
         // Create config file
         self.create_node(meta_ino, "config.toml".to_string(), NodeType::ConfigFile)?;
+
+        // Create status reports
+        for file in GeneratedFile::STATUS {
+            self.create_node(meta_ino, file.file_name().to_string(), NodeType::GeneratedFile(file))?;
+        }
         
         Ok(())
     }
 
+    /// Add the writable control files to `.rss-fuse`
+    pub fn create_control_files(&self) -> Result<(), String> {
+        let meta = self.get_node_by_name(1, ".rss-fuse")
+            .ok_or_else(|| "missing .rss-fuse directory".to_string())?;
+        for file in GeneratedFile::CONTROL {
+            self.create_node(meta.ino, file.file_name().to_string(), NodeType::GeneratedFile(file))?;
+        }
+        Ok(())
+    }
+
     pub fn get_total_nodes(&self) -> usize {
-        self.nodes.read().len()
-    }
-
-    pub fn get_article_content(&self, ino: u64) -> Option<String> {
-        let nodes = self.nodes.read();
-        if let Some(node) = nodes.get(&ino) {
-            match &node.node_type {
-                NodeType::ArticleFile(feed_name, article) => {
-                    // Use markdown format by default, fallback to text on error
-                    Some(article.to_markdown(feed_name)
-                        .unwrap_or_else(|_| article.to_text()))
-                },
-                _ => None,
+        self.nodes.len()
+    }
+
+    /// Combined size of every file, as `getattr` last reported them
+    pub fn total_file_size(&self) -> u64 {
+        let retired = self.retired_inos();
+        self.filter_nodes(|ino, node| (node.file_type == FileType::RegularFile && !retired.contains(ino)).then_some(node.size))
+            .into_iter()
+            .sum()
+    }
+
+    /// An article file's content, rendered on first read (or `getattr`)
+    /// and shared by later ones. The file is resized to match.
+    pub fn get_article_content(&self, ino: u64) -> Option<Arc<str>> {
+        let node = self.get_node(ino)?;
+        match &node.node_type {
+            NodeType::ArticleFile(feed_name, article) => {
+                let content = self.render_article(feed_name, article);
+                if node.size != content.len() as u64 {
+                    self.update_node_size(ino, content.len() as u64);
+                }
+                Some(content)
             }
-        } else {
-            None
+            NodeType::SummaryFile(_, article) => self.summary(&article.id),
+            _ => None,
         }
     }
 }
@@ -321,7 +1759,7 @@ impl Default for InodeManager {
 mod tests {
     use super::*;
     use crate::feed::{Article, ParsedArticle};
-    use chrono::Utc;
+    use chrono::{TimeZone, Utc};
 
     fn create_test_article() -> Article {
         let parsed = ParsedArticle {
@@ -333,6 +1771,7 @@ mod tests {
             published: Some(Utc::now()),
             guid: Some("test-guid".to_string()),
             categories: vec!["test".to_string()],
+            enclosures: Vec::new(),
         };
         Article::new(parsed, "test-feed")
     }
@@ -374,11 +1813,53 @@ mod tests {
         
         assert_eq!(article_node.name, article.filename());
         assert!(article_node.is_file());
-        assert!(article_node.size > 0);
         
-        // Content should be retrievable
+        // Content should be retrievable, and sizes the file
         let content = manager.get_article_content(article_ino).unwrap();
         assert!(content.contains("Test Article"));
+        assert!(manager.get_node(article_ino).unwrap().size > 0);
+    }
+
+    #[test]
+    fn test_article_nodes_date_from_publication() {
+        let manager = InodeManager::new();
+        let mut article = create_test_article();
+        let published = Utc::now() - chrono::Duration::days(3);
+        article.published = Some(published);
+
+        let ino = manager.create_article_file("tech-news", Arc::new(article)).unwrap();
+        let node = manager.get_node(ino).unwrap();
+        assert_eq!(node.modified_time, SystemTime::from(published));
+        assert_eq!(node.created_time, SystemTime::from(published));
+
+        // A size change, e.g. from fetched full text, keeps the date
+        manager.update_node_size(ino, 1);
+        assert_eq!(manager.get_node(ino).unwrap().modified_time, SystemTime::from(published));
+
+        let feed_dir = manager.get_node(node.parent_ino).unwrap();
+        assert!(feed_dir.modified_time > SystemTime::from(published));
+    }
+
+    #[test]
+    fn test_filename_template_and_collisions() {
+        let manager = InodeManager::new();
+        manager.set_filename_template("tech-news", Some(FilenameTemplate::parse("{index:02}-{title}").unwrap()));
+
+        let mut article = create_test_article();
+        let first = manager.create_indexed_article_file("tech-news", Arc::new(article.clone()), Some(1)).unwrap();
+        assert_eq!(manager.get_node(first).unwrap().name, "01-Test Article.md");
+
+        // Outside a listing the title is used, and clashing names are numbered
+        article.id = "other".to_string();
+        let plain = manager.create_article_file("tech-news", Arc::new(article.clone())).unwrap();
+        assert_eq!(manager.get_node(plain).unwrap().name, "Test Article.md");
+        article.id = "third".to_string();
+        let clash = manager.create_article_file("tech-news", Arc::new(article)).unwrap();
+        assert_eq!(manager.get_node(clash).unwrap().name, "Test Article (2).md");
+
+        // Feeds without a template keep title names
+        let other = manager.create_indexed_article_file("other-feed", Arc::new(create_test_article()), Some(1)).unwrap();
+        assert_eq!(manager.get_node(other).unwrap().name, "Test Article.md");
     }
 
     #[test]
@@ -431,6 +1912,141 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn create_old_article() -> Article {
+        let mut article = create_test_article();
+        article.published = Some(Utc::now() - chrono::Duration::days(90));
+        article
+    }
+
+    fn aging(marker: AgingMarker) -> FilesystemConfig {
+        let mut config = FilesystemConfig::default();
+        config.article_aging.enabled = true;
+        config.article_aging.marker = marker;
+        config
+    }
+
+    #[test]
+    fn test_aged_article_suffix() {
+        let manager = InodeManager::new();
+        manager.set_config(aging(AgingMarker::Suffix));
+
+        let old = Arc::new(create_old_article());
+        let ino = manager.create_article_file("tech-news", old.clone()).unwrap();
+        assert_eq!(manager.get_node(ino).unwrap().name, "Test Article.old.md");
+
+        // Fresh articles keep their normal name
+        let mut fresh = create_test_article();
+        fresh.title = "Fresh Article".to_string();
+        let ino = manager.create_article_file("tech-news", Arc::new(fresh.clone())).unwrap();
+        assert_eq!(manager.get_node(ino).unwrap().name, fresh.markdown_filename());
+    }
+
+    #[test]
+    fn test_aged_article_folder() {
+        let manager = InodeManager::new();
+        manager.set_config(aging(AgingMarker::Folder));
+
+        let old = Arc::new(create_old_article());
+        let ino = manager.create_article_file("tech-news", old.clone()).unwrap();
+
+        let feed = manager.get_node_by_name(1, "tech-news").unwrap();
+        let old_dir = manager.get_node_by_name(feed.ino, "old").unwrap();
+        assert!(old_dir.is_directory());
+        assert_eq!(manager.get_node(ino).unwrap().parent_ino, old_dir.ino);
+
+        // Removing the feed subtree removes the aged folder and its articles
+        manager.remove_subtree(feed.ino).unwrap();
+        assert!(manager.get_node(old_dir.ino).is_none());
+        assert!(manager.get_node(ino).is_none());
+    }
+
+    #[test]
+    fn test_article_ages_as_clock_advances() {
+        let manager = InodeManager::new();
+        manager.set_config(aging(AgingMarker::Suffix));
+        let clock = crate::clock::MockClock::at(Utc::now());
+        manager.set_clock(clock.shared());
+
+        let article = create_test_article();
+        let ino = manager.create_article_file("tech-news", Arc::new(article.clone())).unwrap();
+        assert_eq!(manager.get_node(ino).unwrap().name, "Test Article.md");
+        manager.remove_node(ino).unwrap();
+
+        clock.advance(std::time::Duration::from_secs(31 * 86_400));
+        let ino = manager.create_article_file("tech-news", Arc::new(article)).unwrap();
+        assert_eq!(manager.get_node(ino).unwrap().name, "Test Article.old.md");
+    }
+
+    #[test]
+    fn test_grouped_feed_directories() {
+        let manager = InodeManager::new();
+        let ino = manager.create_article_file("tech/rust-blog", Arc::new(create_test_article())).unwrap();
+        manager.create_feed_directory("tech/go-blog").unwrap();
+
+        let group = manager.get_node_by_name(1, "tech").unwrap();
+        assert!(matches!(&group.node_type, NodeType::GroupDirectory(path) if path == "tech"));
+        let feed = manager.get_feed_directory("tech/rust-blog").unwrap();
+        assert_eq!(feed.parent_ino, group.ino);
+        assert_eq!(feed.name, "rust-blog");
+        assert_eq!(manager.get_node(ino).unwrap().parent_ino, feed.ino);
+        assert!(manager.get_feed_directory("tech").is_none());
+
+        // Group directories are pruned once their last feed is removed
+        manager.remove_feed_directory("tech/rust-blog").unwrap();
+        assert!(manager.get_node(ino).is_none());
+        assert!(manager.get_node_by_name(1, "tech").is_some());
+        manager.remove_feed_directory("tech/go-blog").unwrap();
+        assert!(manager.get_node_by_name(1, "tech").is_none());
+
+        // A feed directory cannot double as a group
+        manager.create_feed_directory("news").unwrap();
+        assert!(manager.create_feed_directory("news/hn").is_err());
+    }
+
+    #[test]
+    fn test_rename_feed_directory() {
+        let manager = InodeManager::new();
+        let ino = manager.create_article_file("tech/rust-blog", Arc::new(create_test_article())).unwrap();
+        manager.create_feed_directory("tech/go-blog").unwrap();
+        assert!(manager.rename_feed_directory("tech/rust-blog", "go-blog").is_err());
+
+        manager.rename_feed_directory("tech/rust-blog", "rust").unwrap();
+        let group = manager.get_node_by_name(1, "tech").unwrap();
+        let feed = manager.get_feed_directory("tech/rust-blog").unwrap();
+        assert_eq!(feed.name, "rust");
+        assert_eq!(manager.get_node_by_name(group.ino, "rust").unwrap().ino, feed.ino);
+        assert!(manager.get_node_by_name(group.ino, "rust-blog").is_none());
+        assert_eq!(manager.get_node(ino).unwrap().parent_ino, feed.ino);
+
+        // Recreating the directory (as a refresh does) keeps the new name
+        manager.remove_feed_directory("tech/rust-blog").unwrap();
+        manager.create_feed_directory("tech/rust-blog").unwrap();
+        assert!(manager.get_node_by_name(manager.get_node_by_name(1, "tech").unwrap().ino, "rust").is_some());
+    }
+
+    #[test]
+    fn test_transliterated_names() {
+        let manager = InodeManager::new();
+        manager.set_config(FilesystemConfig {
+            transliterate_names: true,
+            ..FilesystemConfig::default()
+        });
+
+        let mut article = create_test_article();
+        article.title = "Новости".to_string();
+        let article = Arc::new(article);
+        let ino = manager.create_article_file("статьи", article.clone()).unwrap();
+
+        let feed = manager.get_feed_directory("статьи").unwrap();
+        assert_eq!(feed.name, "stati");
+        assert!(matches!(&feed.node_type, NodeType::FeedDirectory(name) if name == "статьи"));
+
+        let node = manager.get_node(ino).unwrap();
+        assert_eq!(node.name, "Novosti.md");
+        // Original title is kept in the content
+        assert!(manager.get_article_content(ino).unwrap().contains("Новости"));
+    }
+
     #[test]
     fn test_node_removal() {
         let manager = InodeManager::new();
@@ -445,4 +2061,202 @@ mod tests {
         let root_children = manager.list_children(1);
         assert!(root_children.iter().all(|n| n.ino != feed_ino));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_custom_content_selectors() {
+        let manager = InodeManager::new();
+        let mut article = create_test_article();
+        article.content = Some("<p>Keep this</p><aside>Drop this</aside>".to_string());
+        let article = Arc::new(article);
+
+        let ino = manager.create_article_file("test-feed", Arc::clone(&article)).unwrap();
+        assert!(manager.get_article_content(ino).unwrap().contains("Drop this"));
+
+        let selectors = ContentSelectors { remove: vec!["aside".to_string()], ..ContentSelectors::default() };
+        manager.set_content_selectors("test-feed", Some(selectors)).unwrap();
+        let content = manager.get_article_content(ino).unwrap();
+        assert!(content.contains("Keep this"));
+        assert!(!content.contains("Drop this"));
+
+        // Other feeds keep the defaults
+        let other = manager.create_article_file("other-feed", article).unwrap();
+        assert!(manager.get_article_content(other).unwrap().contains("Drop this"));
+    }
+
+    #[test]
+    fn test_text_output_format() {
+        let manager = InodeManager::new();
+        manager.set_config(FilesystemConfig { output_format: OutputFormat::Text, ..FilesystemConfig::default() });
+
+        let ino = manager.create_article_file("test-feed", Arc::new(create_test_article())).unwrap();
+        let node = manager.get_node(ino).unwrap();
+        assert_eq!(node.name, "Test Article.txt");
+
+        let content = manager.get_article_content(ino).unwrap();
+        assert!(content.starts_with("Title: Test Article\n"));
+        assert!(!content.starts_with("---"));
+        assert_eq!(manager.get_node(ino).unwrap().size, content.len() as u64);
+    }
+
+    #[test]
+    fn test_html_output_format() {
+        let manager = InodeManager::new();
+        manager.set_config(FilesystemConfig { output_format: OutputFormat::Html, ..FilesystemConfig::default() });
+
+        let mut article = create_test_article();
+        article.content = Some("<p>Hello <a href=\"javascript:void(0)\">there</a></p><script>track()</script>".to_string());
+        let ino = manager.create_article_file("test-feed", Arc::new(article)).unwrap();
+        let node = manager.get_node(ino).unwrap();
+        assert_eq!(node.name, "Test Article.html");
+
+        let content = manager.get_article_content(ino).unwrap();
+        assert!(content.starts_with("<!DOCTYPE html>"));
+        assert!(content.contains("<h1>Test Article</h1>"));
+        assert!(content.contains("<dt>Feed</dt><dd>test-feed</dd>"));
+        assert!(content.contains("Hello <a rel=\"noopener noreferrer\">there</a>"));
+        assert!(!content.contains("javascript:"));
+        assert!(!content.contains("track()"));
+        assert_eq!(manager.get_node(ino).unwrap().size, content.len() as u64);
+    }
+
+    #[test]
+    fn test_rendered_content_shared_between_reads() {
+        let manager = InodeManager::new();
+        let article = Arc::new(create_test_article());
+        let ino = manager.create_article_file("test-feed", Arc::clone(&article)).unwrap();
+
+        // The node holds the caller's article, not a copy
+        let node = manager.get_node(ino).unwrap();
+        assert!(matches!(&node.node_type, NodeType::ArticleFile(_, held) if Arc::ptr_eq(held, &article)));
+
+        let first = manager.get_article_content(ino).unwrap();
+        let second = manager.get_article_content(ino).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Changes to the article render it afresh
+        manager.update_article(&article.id, |article| article.starred = true);
+        let updated = manager.get_article_content(ino).unwrap();
+        assert!(!Arc::ptr_eq(&first, &updated));
+        assert!(updated.contains("starred: true"));
+        assert_eq!(manager.get_node(ino).unwrap().size, updated.len() as u64);
+    }
+
+    #[test]
+    fn test_article_rendered_on_first_read() {
+        let manager = InodeManager::new();
+        let article = Arc::new(create_test_article());
+        let ino = manager.create_article_file("test-feed", Arc::clone(&article)).unwrap();
+        assert_eq!(manager.rendered.lock().len(), 0);
+
+        let first = manager.get_article_content(ino).unwrap();
+        assert_eq!(manager.rendered.lock().len(), 1);
+        assert_eq!(manager.get_node(ino).unwrap().size, first.len() as u64);
+
+        // Another file of the same article reuses the rendering
+        let view = manager.create_node(1, "view".to_string(), NodeType::FeedDirectory("test-feed".to_string())).unwrap();
+        let copy = manager.create_node(view, "copy.md".to_string(), NodeType::ArticleFile("test-feed".to_string(), Arc::clone(&article))).unwrap();
+        assert!(Arc::ptr_eq(&first, &manager.get_article_content(copy).unwrap()));
+
+        // New selectors for the feed drop its renderings
+        let selectors = ContentSelectors { remove: vec!["aside".to_string()], ..ContentSelectors::default() };
+        manager.set_content_selectors("test-feed", Some(selectors)).unwrap();
+        assert_eq!(manager.rendered.lock().len(), 0);
+        let rerendered = manager.get_article_content(ino).unwrap();
+        assert!(!Arc::ptr_eq(&first, &rerendered));
+
+        // As does a change to the article's content
+        manager.update_article(&article.id, |article| article.content = Some("<p>Revised</p>".to_string()));
+        let revised = manager.get_article_content(ino).unwrap();
+        assert!(revised.contains("Revised"));
+        assert_eq!(manager.get_node(ino).unwrap().size, revised.len() as u64);
+    }
+
+    #[test]
+    fn test_stable_article_inodes() {
+        let manager = InodeManager::new();
+        let article = Arc::new(create_test_article());
+        let feed_ino = manager.create_feed_directory("tech-news").unwrap();
+        let ino = manager.create_article_file("tech-news", Arc::clone(&article)).unwrap();
+        assert_eq!(ino, stable_article_ino("tech-news", &article.id, 0));
+
+        // Same number after the feed directory is rebuilt or swapped
+        manager.remove_subtree(feed_ino).unwrap();
+        manager.create_feed_directory("tech-news").unwrap();
+        assert_eq!(manager.create_article_file("tech-news", Arc::clone(&article)).unwrap(), ino);
+
+        manager.stage_feed_directory("tech-news").unwrap();
+        assert_ne!(manager.create_article_file("tech-news", Arc::clone(&article)).unwrap(), ino);
+        let new_feed_ino = manager.commit_feed_directory("tech-news").unwrap();
+        let node = manager.get_node(ino).unwrap();
+        assert_eq!(node.parent_ino, new_feed_ino);
+        assert_eq!(manager.feed_articles("tech-news").len(), 1);
+
+        // Another feed, or a second article with the same ID, gets its own number
+        manager.create_feed_directory("other").unwrap();
+        let other = manager.create_article_file("other", Arc::clone(&article)).unwrap();
+        assert_ne!(other, ino);
+        let duplicate = manager.create_article_file("other", Arc::clone(&article)).unwrap();
+        assert_eq!(duplicate, stable_article_ino("other", &article.id, 1));
+    }
+
+    #[test]
+    fn test_lookups_during_refreshes() {
+        let manager = Arc::new(InodeManager::new());
+        let article = Arc::new(create_test_article());
+        manager.create_feed_directory("tech-news").unwrap();
+        manager.create_article_file("tech-news", Arc::clone(&article)).unwrap();
+
+        let refresher = {
+            let manager = Arc::clone(&manager);
+            std::thread::spawn(move || {
+                for _ in 0..200 {
+                    manager.stage_feed_directory("tech-news").unwrap();
+                    manager.create_article_file("tech-news", Arc::clone(&article)).unwrap();
+                    manager.commit_feed_directory("tech-news").unwrap();
+                }
+            })
+        };
+
+        // Readers see the old directory or the new one, never a gap
+        while !refresher.is_finished() {
+            let feed_dir = manager.get_feed_directory("tech-news").unwrap();
+            let listed = manager.list_children(feed_dir.ino);
+            assert!(listed.iter().any(|node| node.name == "Test Article.md"));
+            assert!(manager.get_node_by_name(feed_dir.ino, "Test Article.md").is_some());
+        }
+        refresher.join().unwrap();
+    }
+
+    #[test]
+    fn test_feed_listing_cap_and_date_folders() {
+        let manager = InodeManager::new();
+        manager.set_config(FilesystemConfig { date_folders_after: 2, ..FilesystemConfig::default() });
+        manager.set_max_visible_articles("archive", Some(3));
+
+        let dated = |n: u32, month: u32| {
+            let mut article = create_test_article();
+            article.id = format!("article-{}", n);
+            article.title = format!("Article {}", n);
+            article.published = Some(Utc.with_ymd_and_hms(2024, month, 1, 12, 0, 0).unwrap());
+            Arc::new(article)
+        };
+        // Listed in feed order, oldest first
+        let listing = (1..=4).map(|n| (n as usize, dated(n, n))).collect();
+        manager.create_feed_listing("archive", listing);
+
+        let feed_ino = manager.get_feed_directory("archive").unwrap().ino;
+        let year = manager.get_node_by_name(feed_ino, "2024").unwrap();
+        assert!(matches!(year.node_type, NodeType::DateFolder(_)));
+        let months: Vec<String> = manager.list_children(year.ino).into_iter().map(|n| n.name).collect();
+        assert_eq!(months, vec!["02", "03", "04"]);
+        let april = manager.get_node_by_name(year.ino, "04").unwrap();
+        assert!(manager.get_node_by_name(april.ino, "Article 4.md").is_some());
+        assert_eq!(manager.feed_articles("archive").len(), 3);
+
+        // Short listings stay flat
+        manager.set_max_visible_articles("archive", None);
+        manager.create_feed_listing("small", vec![(1, dated(5, 5)), (2, dated(6, 6))]);
+        let small_ino = manager.get_feed_directory("small").unwrap().ino;
+        assert!(manager.get_node_by_name(small_ino, "Article 5.md").is_some());
+    }
+}
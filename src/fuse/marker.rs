@@ -0,0 +1,225 @@
+//! Mount markers: a small file per mount point naming the process that
+//! serves it. A process that crashes leaves its mount behind with nobody
+//! answering requests; its marker lets the next mount tell such a leftover
+//! from a mount still in use and clean it up without asking.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use crate::profile;
+
+/// The process serving a mount point
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MountMarker {
+    pub mount_point: PathBuf,
+    pub pid: u32,
+    /// The process's start time in clock ticks after boot, from
+    /// `/proc/<pid>/stat`, to tell it from a later process given its pid
+    pub process_start: Option<u64>,
+    /// When the file system was mounted
+    pub mounted_at: DateTime<Utc>,
+    /// How `rss-fuse mount` was run, for `remount` to do the same
+    #[serde(default)]
+    pub args: MountArgs,
+}
+
+/// Options of `rss-fuse mount` that `remount` carries over
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MountArgs {
+    pub daemon: bool,
+    pub allow_other: bool,
+    pub foreground: bool,
+    pub no_auto_open: bool,
+    pub file_manager: Option<String>,
+    pub config_path: Option<PathBuf>,
+}
+
+impl MountMarker {
+    /// A marker naming this process as serving `mount_point` since `mounted_at`
+    pub fn for_current_process(mount_point: &Path, mounted_at: DateTime<Utc>) -> Self {
+        let pid = std::process::id();
+        Self {
+            mount_point: normalize(mount_point),
+            pid,
+            process_start: process_start(pid),
+            mounted_at,
+            args: MountArgs::default(),
+        }
+    }
+
+    /// The marker, recording that the mount was made with `args`
+    pub fn with_args(mut self, args: MountArgs) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Store the marker in `dir`, replacing any earlier one for its mount point
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let mut temp_file = tempfile::NamedTempFile::new_in(dir)?;
+        serde_json::to_writer(&mut temp_file, self)?;
+        temp_file.flush()?;
+        temp_file.persist(marker_path(dir, &self.mount_point)).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    /// The marker stored in `dir` for `mount_point`, if any
+    pub fn read(dir: &Path, mount_point: &Path) -> Option<Self> {
+        let contents = fs::read(marker_path(dir, &normalize(mount_point))).ok()?;
+        serde_json::from_slice(&contents).ok()
+    }
+
+    /// Forget the marker for `mount_point`
+    pub fn remove(dir: &Path, mount_point: &Path) {
+        let _ = fs::remove_file(marker_path(dir, &normalize(mount_point)));
+    }
+
+    /// Whether the process that wrote the marker is still running. Without
+    /// `/proc` a reused pid passes for the original process.
+    pub fn is_running(&self) -> bool {
+        match (self.process_start, process_start(self.pid)) {
+            (Some(recorded), Some(current)) => recorded == current,
+            (_, Some(_)) => true,
+            (_, None) => process_exists(self.pid) && !Path::new("/proc/self").exists(),
+        }
+    }
+}
+
+/// Where markers are kept: `$XDG_RUNTIME_DIR/rss-fuse-mounts`, which goes
+/// away on reboot along with the mounts, or a per-user directory in the
+/// temp dir. Each profile has its own.
+pub fn markers_dir() -> PathBuf {
+    let name = profile::dir_name();
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join(format!("{}-mounts", name)),
+        None => std::env::temp_dir().join(format!("{}-mounts-{}", name, unsafe { libc::getuid() })),
+    }
+}
+
+/// The lock file a mount of `mount_point` holds in `dir`, beside its marker
+pub fn lock_path(dir: &Path, mount_point: &Path) -> PathBuf {
+    marker_path(dir, &normalize(mount_point)).with_extension("lock")
+}
+
+fn marker_path(dir: &Path, mount_point: &Path) -> PathBuf {
+    let hash = blake3::hash(mount_point.as_os_str().as_bytes()).to_hex();
+    dir.join(format!("{}.json", &hash[..16]))
+}
+
+/// Links followed when resolving a mount point, as for `ELOOP`
+const MAX_LINKS: usize = 40;
+
+/// `mount_point` as an absolute path with its parent resolved and, when it is
+/// a symlink, followed to the directory it names, so every spelling of a
+/// mount point keys the same marker. The mount point itself is never
+/// canonicalized: a disconnected FUSE mount cannot be resolved. The mount
+/// table spells mount points this way too.
+pub fn normalize(mount_point: &Path) -> PathBuf {
+    let mut path = std::env::current_dir().map_or_else(|_| mount_point.to_path_buf(), |dir| dir.join(mount_point));
+    for _ in 0..MAX_LINKS {
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            return path;
+        };
+        let parent = parent.canonicalize().unwrap_or_else(|_| parent.to_path_buf());
+        let resolved = parent.join(name);
+        match fs::read_link(&resolved) {
+            // Relative targets are taken from the link's own directory
+            Ok(target) => path = parent.join(target),
+            Err(_) => return resolved,
+        }
+    }
+    path
+}
+
+/// Start time of a running process: field 22 of `/proc/<pid>/stat`,
+/// counted after the command name, which may itself hold spaces
+fn process_start(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(19)?.parse().ok()
+}
+
+/// Whether a process with `pid` exists, whoever it belongs to
+fn process_exists(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    let signalled = unsafe { libc::kill(pid, 0) } == 0;
+    signalled || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marker_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mount_point = dir.path().join("feeds");
+        assert_eq!(MountMarker::read(dir.path(), &mount_point), None);
+
+        let args = MountArgs { daemon: true, file_manager: Some("thunar".to_string()), ..MountArgs::default() };
+        let marker = MountMarker::for_current_process(&mount_point, Utc::now()).with_args(args.clone());
+        marker.write(dir.path()).unwrap();
+        let read = MountMarker::read(dir.path(), &mount_point).unwrap();
+        assert_eq!(read.args, args);
+        assert_eq!(read, marker);
+        assert!(marker.is_running());
+
+        MountMarker::remove(dir.path(), &mount_point);
+        assert_eq!(MountMarker::read(dir.path(), &mount_point), None);
+    }
+
+    #[test]
+    fn test_marker_without_args() {
+        // Written before markers recorded how the mount was made
+        let marker: MountMarker = serde_json::from_str(
+            r#"{"mount_point":"/tmp/feeds","pid":1,"process_start":null,"mounted_at":"2024-03-15T10:00:00Z"}"#,
+        ).unwrap();
+        assert_eq!(marker.args, MountArgs::default());
+    }
+
+    #[test]
+    fn test_symlinked_mount_point_shares_marker() {
+        let dir = tempfile::tempdir().unwrap();
+        let mount_point = dir.path().join("feeds");
+        fs::create_dir(&mount_point).unwrap();
+        let link = dir.path().join("rss");
+        std::os::unix::fs::symlink("feeds", &link).unwrap();
+        assert_eq!(normalize(&link), normalize(&mount_point));
+
+        let markers = dir.path().join("markers");
+        let marker = MountMarker::for_current_process(&link, Utc::now());
+        marker.write(&markers).unwrap();
+        assert_eq!(MountMarker::read(&markers, &mount_point), Some(marker));
+        assert_eq!(lock_path(&markers, &link), lock_path(&markers, &mount_point));
+
+        MountMarker::remove(&markers, &mount_point.canonicalize().unwrap());
+        assert_eq!(MountMarker::read(&markers, &link), None);
+    }
+
+    #[test]
+    fn test_marker_of_exited_process() {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        let marker = MountMarker {
+            pid: child.id(),
+            process_start: None,
+            ..MountMarker::for_current_process(Path::new("/tmp/feeds"), Utc::now())
+        };
+        assert!(!marker.is_running());
+
+        // A pid handed to a later process
+        if Path::new("/proc/self").exists() {
+            let reused = MountMarker {
+                process_start: process_start(std::process::id()).map(|start| start + 1),
+                ..MountMarker::for_current_process(Path::new("/tmp/feeds"), Utc::now())
+            };
+            assert!(!reused.is_running());
+        }
+    }
+}
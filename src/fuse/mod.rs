@@ -1,6 +1,12 @@
+pub mod content;
 pub mod filesystem;
 pub mod inode;
+pub mod marker;
+pub mod mounts;
+pub mod naming;
+pub mod notify;
 pub mod operations;
+pub mod stats;
 
 use fuser::{FileAttr, FileType};
 use libc::{ENOENT, ENOTDIR};
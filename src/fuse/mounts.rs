@@ -0,0 +1,203 @@
+//! Mount table lookups, unmounting and open-file detection through system
+//! calls and `/proc`, so none of it depends on `mount`, `lsof`, `fuser` or
+//! `kill` being installed.
+
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+/// One line of `/proc/self/mountinfo`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountEntry {
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+    pub source: String,
+}
+
+/// The mounts visible to this process, or `None` where `/proc` is unavailable
+pub fn mount_table() -> Option<Vec<MountEntry>> {
+    fs::read_to_string("/proc/self/mountinfo").ok().map(|mountinfo| parse_mountinfo(&mountinfo))
+}
+
+/// Entries of a `/proc/self/mountinfo` listing: the mount point is the
+/// fifth field, and file system type and source follow the `-` separator
+/// after the optional fields
+pub fn parse_mountinfo(mountinfo: &str) -> Vec<MountEntry> {
+    mountinfo.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = fields.nth(4)?;
+            let mut fields = fields.skip_while(|field| *field != "-").skip(1);
+            Some(MountEntry {
+                mount_point: unescape(mount_point),
+                fs_type: fields.next()?.to_string(),
+                source: unescape(fields.next()?).to_string_lossy().into_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Undo the kernel's octal escaping of whitespace and backslashes (`\040`)
+fn unescape(field: &str) -> PathBuf {
+    let mut bytes = Vec::with_capacity(field.len());
+    let mut rest = field.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let octal = tail.get(..3)
+            .filter(|digits| byte == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)))
+            .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok());
+        match octal {
+            Some(value) => {
+                bytes.push(value);
+                rest = &tail[3..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    PathBuf::from(std::ffi::OsStr::from_bytes(&bytes))
+}
+
+/// Whether `path` is a mount point. Without a mount table this compares
+/// its device with its parent's; a path that cannot be examined at all
+/// (as a disconnected FUSE mount) counts as mounted.
+pub fn is_mount_point(path: &Path) -> bool {
+    if let Some(table) = mount_table() {
+        return table.iter().any(|entry| entry.mount_point == path);
+    }
+    let parent = path.parent().unwrap_or(path);
+    match (fs::metadata(path), fs::metadata(parent)) {
+        (Ok(node), Ok(parent)) => node.dev() != parent.dev() || node.ino() == parent.ino(),
+        (Err(e), _) => e.raw_os_error() == Some(libc::ENOTCONN),
+        _ => false,
+    }
+}
+
+/// How hard `unmount` tries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnmountMode {
+    /// Fails while the file system is in use
+    Normal,
+    /// Aborts pending requests to the file system
+    Force,
+    /// Detaches the mount now; it goes away once no longer in use
+    Lazy,
+}
+
+/// Unmount `path` with `umount2(2)` (`unmount(2)` outside Linux). Regular
+/// users get `EPERM` for FUSE mounts, which only `fusermount` may remove.
+pub fn unmount(path: &Path, mode: UnmountMode) -> io::Result<()> {
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    #[cfg(target_os = "linux")]
+    let result = {
+        let flags = match mode {
+            UnmountMode::Normal => 0,
+            UnmountMode::Force => libc::MNT_FORCE,
+            UnmountMode::Lazy => libc::MNT_DETACH,
+        };
+        unsafe { libc::umount2(c_path.as_ptr(), flags) }
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let flags = match mode {
+            UnmountMode::Normal => 0,
+            UnmountMode::Force | UnmountMode::Lazy => libc::MNT_FORCE,
+        };
+        unsafe { libc::unmount(c_path.as_ptr(), flags) }
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// A process with a file, working directory or root below a mount point
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountUser {
+    pub pid: u32,
+    pub command: String,
+    /// What it holds there: `cwd`, `root`, `exe` or an open file's path
+    pub holds: PathBuf,
+}
+
+/// Processes using anything below `mount_point`, found by reading the
+/// links in `/proc/<pid>`. Processes of other users are only visible to
+/// root.
+pub fn mount_users(mount_point: &Path) -> Vec<MountUser> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    let own_pid = std::process::id();
+    entries.flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|pid| *pid != own_pid)
+        .filter_map(|pid| {
+            let proc_dir = PathBuf::from(format!("/proc/{}", pid));
+            let holds = ["cwd", "root", "exe"].into_iter()
+                .filter_map(|link| fs::read_link(proc_dir.join(link)).ok())
+                .chain(fs::read_dir(proc_dir.join("fd")).into_iter().flatten().flatten()
+                    .filter_map(|fd| fs::read_link(fd.path()).ok()))
+                .find(|target| target.starts_with(mount_point))?;
+            let command = fs::read_to_string(proc_dir.join("comm"))
+                .map(|comm| comm.trim_end().to_string())
+                .unwrap_or_default();
+            Some(MountUser { pid, command, holds })
+        })
+        .collect()
+}
+
+/// Ask a process to exit with `SIGTERM`
+pub fn terminate(pid: u32) -> io::Result<()> {
+    let pid = libc::pid_t::try_from(pid).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    if unsafe { libc::kill(pid, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let mountinfo = "22 1 0:21 / /proc rw,nosuid shared:12 - proc proc rw\n\
+            41 22 0:38 / /home/me/rss rw,nosuid,nodev shared:20 - fuse rss-fuse rw,user_id=1000\n\
+            42 22 0:39 / /tmp/my\\040feeds rw - fuse.rss-fuse rss-fuse rw\n\
+            43 22 0:40 / /mnt/remote rw master:3 - fuse.sshfs host:/ rw\n";
+        let entries = parse_mountinfo(mountinfo);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[1], MountEntry {
+            mount_point: PathBuf::from("/home/me/rss"),
+            fs_type: "fuse".to_string(),
+            source: "rss-fuse".to_string(),
+        });
+        assert_eq!(entries[2].mount_point, PathBuf::from("/tmp/my feeds"));
+        assert_eq!(entries[3].source, "host:/");
+    }
+
+    #[test]
+    fn test_mount_users_of_working_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .current_dir(dir.path())
+            .spawn()
+            .unwrap();
+        // /proc/<pid>/cwd shows the canonical path
+        let canonical = dir.path().canonicalize().unwrap();
+        let users = mount_users(&canonical);
+        let _ = child.kill();
+        let _ = child.wait();
+        if Path::new("/proc").is_dir() {
+            assert!(users.iter().any(|user| user.pid == child.id() && user.holds == canonical));
+        }
+    }
+}
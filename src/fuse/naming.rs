@@ -0,0 +1,385 @@
+//! Helpers for turning feed names and article titles into filesystem names
+
+use chrono::format::{Item, StrftimeItems};
+use unicode_segmentation::UnicodeSegmentation;
+use crate::error::{Error, Result};
+use crate::feed::Article;
+
+/// Longest file or directory name most filesystems accept, in bytes
+pub const MAX_NAME_BYTES: usize = 255;
+
+/// Length of the hash appended to truncated names (`~` + hex digits)
+const HASH_SUFFIX_HEX: usize = 8;
+
+/// Fit `stem` + `ext` within `max_bytes`.
+///
+/// Names that are too long are cut at a grapheme boundary and given a short
+/// hash of the full stem, so long titles sharing a prefix stay distinct:
+/// `A very long title~1a2b3c4d.md`.
+pub fn fit_name(stem: &str, ext: &str, max_bytes: usize) -> String {
+    if stem.len() + ext.len() <= max_bytes {
+        return format!("{}{}", stem, ext);
+    }
+
+    let hash = blake3::hash(stem.as_bytes()).to_hex();
+    let suffix = format!("~{}", &hash[..HASH_SUFFIX_HEX]);
+    let budget = max_bytes.saturating_sub(ext.len() + suffix.len());
+
+    let mut cut = String::new();
+    for grapheme in stem.graphemes(true) {
+        if cut.len() + grapheme.len() > budget {
+            break;
+        }
+        cut.push_str(grapheme);
+    }
+
+    format!("{}{}{}", cut.trim_end(), suffix, ext)
+}
+
+/// Like [`fit_name`], splitting a short extension (e.g. `.md`) off `name` first
+pub fn fit_filename(name: &str, max_bytes: usize) -> String {
+    let (stem, ext) = split_extension(name);
+    fit_name(stem, ext, max_bytes)
+}
+
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(dot) if dot > 0 && name.len() - dot <= 10 && !name[dot..].contains(' ') => name.split_at(dot),
+        _ => (name, ""),
+    }
+}
+
+/// Replace characters that are unsafe or awkward in file names with `-`
+pub fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect()
+}
+
+/// Add ` (n)` before the extension, for the `n`th file wanting the same name
+pub fn with_collision_suffix(name: &str, n: usize, max_bytes: usize) -> String {
+    let (stem, ext) = split_extension(name);
+    let suffix = format!(" ({}){}", n, ext);
+    fit_name(stem, &suffix, max_bytes)
+}
+
+/// Article file names built from a pattern such as
+/// `{published:%Y-%m-%d}-{title}.{ext}` or `{index:03}-{title}`.
+///
+/// Placeholders: `{title}`, `{author}`, `{feed}`, `{id}`, `{ext}`,
+/// `{published}` (optionally with a strftime format, default `%Y-%m-%d`) and
+/// `{index}` (1-based position in the listing, optionally zero-padded to a
+/// width). `{{` and `}}` are literal braces. Without `{ext}` the extension
+/// is appended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilenameTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TemplatePart {
+    Literal(String),
+    Title,
+    Author,
+    Feed,
+    Id,
+    Ext,
+    Published(String),
+    Index(usize),
+}
+
+impl FilenameTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let invalid = |reason: String| Error::Invalid(format!("Filename template '{}': {}", template, reason));
+
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(invalid("unmatched '}'".to_string())),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(invalid("unclosed '{'".to_string())),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Self::parse_placeholder(&placeholder).map_err(invalid)?);
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(literal));
+        }
+
+        if !parts.iter().any(|p| matches!(p, TemplatePart::Title | TemplatePart::Id | TemplatePart::Index(_))) {
+            return Err(invalid("needs {title}, {id} or {index} to tell articles apart".to_string()));
+        }
+        Ok(Self { parts })
+    }
+
+    fn parse_placeholder(placeholder: &str) -> std::result::Result<TemplatePart, String> {
+        let (name, spec) = match placeholder.split_once(':') {
+            Some((name, spec)) => (name, Some(spec)),
+            None => (placeholder, None),
+        };
+        let part = match (name, spec) {
+            ("title", None) => TemplatePart::Title,
+            ("author", None) => TemplatePart::Author,
+            ("feed", None) => TemplatePart::Feed,
+            ("id", None) => TemplatePart::Id,
+            ("ext", None) => TemplatePart::Ext,
+            ("published", format) => {
+                let format = format.unwrap_or("%Y-%m-%d");
+                if format.is_empty() || StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                    return Err(format!("invalid date format '{}'", format));
+                }
+                TemplatePart::Published(format.to_string())
+            }
+            ("index", None) => TemplatePart::Index(0),
+            ("index", Some(width)) => TemplatePart::Index(
+                width.parse().map_err(|_| format!("invalid index width '{}'", width))?,
+            ),
+            (name, Some(_)) if matches!(name, "title" | "author" | "feed" | "id" | "ext") => {
+                return Err(format!("{{{}}} takes no format", name));
+            }
+            (name, _) => return Err(format!("unknown placeholder {{{}}}", name)),
+        };
+        Ok(part)
+    }
+
+    /// File name for `article`, the `index`th entry of `feed_name`'s listing,
+    /// with extension `ext` (without the dot)
+    pub fn render(&self, article: &Article, feed_name: &str, index: usize, ext: &str) -> String {
+        let mut name = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(text) => name.push_str(text),
+                TemplatePart::Title => name.push_str(&fit_name(&article.title, "", TITLE_BYTES)),
+                TemplatePart::Author => name.push_str(article.author.as_deref().unwrap_or("unknown")),
+                TemplatePart::Feed => name.push_str(feed_name),
+                TemplatePart::Id => name.push_str(&article.id),
+                TemplatePart::Ext => name.push_str(ext),
+                TemplatePart::Published(format) => match article.published {
+                    Some(published) => name.push_str(&published.format(format).to_string()),
+                    None => name.push_str("undated"),
+                },
+                TemplatePart::Index(width) => name.push_str(&format!("{:0width$}", index, width = *width)),
+            }
+        }
+        if !self.parts.contains(&TemplatePart::Ext) {
+            name.push('.');
+            name.push_str(ext);
+        }
+        fit_filename(sanitize(&name).trim(), MAX_NAME_BYTES)
+    }
+}
+
+/// Titles are shortened within templates as they are in default names
+const TITLE_BYTES: usize = 100;
+
+/// Transliterate a name to ASCII for tools that handle non-Latin filenames
+/// poorly, e.g. "статьи" → "stati", "中文" → "Zhong Wen".
+///
+/// Characters without a transliteration are dropped; if nothing is left the
+/// original name is returned unchanged.
+pub fn transliterate(name: &str) -> String {
+    let ascii = deunicode::deunicode_with_tofu(name, "");
+    let unquoted: String = ascii.chars().filter(|c| !matches!(c, '\'' | '`')).collect();
+    let cleaned = sanitize(&unquoted);
+    let cleaned = cleaned.trim();
+
+    // Don't turn a visible name into a hidden one
+    let became_hidden = cleaned.starts_with('.') && !name.starts_with('.');
+    if cleaned.is_empty() || became_hidden {
+        name.to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Directory name for one component of a feed name, as presented in the mount.
+///
+/// Feed names are labels and may hold any text; their directories get a slug:
+/// characters unsafe in file names become `-`, invisible formatting
+/// characters (zero-width spaces, bidi overrides) are dropped and leading
+/// dots are stripped, so no feed turns into a hidden directory, `.` or `..`.
+/// A label with nothing left, e.g. `...`, is named after its hash. The label
+/// itself stays readable from the directory's `user.rss.feed` attribute.
+pub fn feed_dir_name(component: &str, transliterate_names: bool) -> String {
+    let name = if transliterate_names {
+        transliterate(component)
+    } else {
+        component.to_string()
+    };
+    let visible: String = sanitize(&name).chars().filter(|&c| !is_invisible(c)).collect();
+    let slug = visible.trim().trim_start_matches('.').trim_start();
+    if slug.is_empty() {
+        let hash = blake3::hash(component.as_bytes()).to_hex();
+        return format!("feed-{}", &hash[..HASH_SUFFIX_HEX]);
+    }
+    fit_name(slug, "", MAX_NAME_BYTES)
+}
+
+/// Zero-width and text direction formatting characters, which make names that
+/// look alike differ or display reversed
+fn is_invisible(c: char) -> bool {
+    matches!(c,
+        '\u{00AD}' | '\u{061C}' | '\u{180E}' | '\u{FEFF}'
+        | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{206F}'
+    )
+}
+
+/// Directory name for an article tag under `tags/`, or `None` for a tag
+/// that leaves nothing usable (empty, `.` or `..`)
+pub fn tag_dir_name(tag: &str) -> Option<String> {
+    let name = fit_name(sanitize(tag).trim(), "", MAX_NAME_BYTES);
+    (!name.is_empty() && name.chars().any(|c| c != '.')).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_name_short_names_unchanged() {
+        assert_eq!(fit_name("Title", ".md", MAX_NAME_BYTES), "Title.md");
+
+        let exact = "a".repeat(MAX_NAME_BYTES - 3);
+        assert_eq!(fit_name(&exact, ".md", MAX_NAME_BYTES), format!("{}.md", exact));
+    }
+
+    #[test]
+    fn test_fit_name_at_limit() {
+        let long = "a".repeat(MAX_NAME_BYTES - 2);
+        let fitted = fit_name(&long, ".md", MAX_NAME_BYTES);
+        assert!(fitted.len() <= MAX_NAME_BYTES);
+        assert!(fitted.ends_with(".md"));
+        assert!(fitted.contains('~'));
+    }
+
+    #[test]
+    fn test_fit_name_keeps_graphemes_whole() {
+        // "é" as e + combining acute (3 bytes), and 3-byte CJK characters
+        let stem = "e\u{301}".repeat(200) + &"字".repeat(200);
+        let fitted = fit_name(&stem, ".md", MAX_NAME_BYTES);
+        assert!(fitted.len() <= MAX_NAME_BYTES);
+
+        let kept = fitted.split('~').next().unwrap();
+        assert!(kept.graphemes(true).all(|g| g == "e\u{301}" || g == "字"));
+    }
+
+    #[test]
+    fn test_fit_name_distinguishes_shared_prefixes() {
+        let prefix = "Shared prefix ".repeat(30);
+        let a = fit_name(&format!("{}one", prefix), "", MAX_NAME_BYTES);
+        let b = fit_name(&format!("{}two", prefix), "", MAX_NAME_BYTES);
+        assert_ne!(a, b);
+        assert!(a.len() <= MAX_NAME_BYTES && b.len() <= MAX_NAME_BYTES);
+    }
+
+    #[test]
+    fn test_fit_filename_splits_extension() {
+        let name = format!("{}.error.txt", "x".repeat(300));
+        let fitted = fit_filename(&name, MAX_NAME_BYTES);
+        assert!(fitted.len() <= MAX_NAME_BYTES);
+        assert!(fitted.ends_with(".txt"));
+
+        // Dots inside titles aren't mistaken for extensions
+        assert_eq!(split_extension("Rust 1.75 released"), ("Rust 1.75 released", ""));
+    }
+
+    #[test]
+    fn test_tag_dir_name() {
+        assert_eq!(tag_dir_name(" Rust ").as_deref(), Some("Rust"));
+        assert_eq!(tag_dir_name("C/C++").as_deref(), Some("C-C++"));
+        assert_eq!(tag_dir_name(".NET").as_deref(), Some(".NET"));
+        assert_eq!(tag_dir_name(".."), None);
+        assert_eq!(tag_dir_name("  "), None);
+    }
+
+    fn article(title: &str, published: Option<&str>) -> Article {
+        let mut article = crate::feed::test_article("a", title, "feed");
+        article.published = published.map(|p| p.parse().unwrap());
+        article
+    }
+
+    #[test]
+    fn test_feed_dir_name() {
+        assert_eq!(feed_dir_name("Hacker News", false), "Hacker News");
+        assert_eq!(feed_dir_name("Новости", false), "Новости");
+        assert_eq!(feed_dir_name("Новости", true), "Novosti");
+        assert_eq!(feed_dir_name("a:b|c", false), "a-b-c");
+        assert_eq!(feed_dir_name(".hidden", false), "hidden");
+        assert_eq!(feed_dir_name(" ..  spaced ", false), "spaced");
+        assert_eq!(feed_dir_name("zero\u{200B}width", false), "zerowidth");
+        assert_eq!(feed_dir_name("\u{202E}txt.exe", false), "txt.exe");
+        assert_eq!(feed_dir_name("line\nbreak", false), "line-break");
+
+        // Nothing usable left
+        let hashed = feed_dir_name("...", false);
+        assert!(hashed.starts_with("feed-") && hashed.len() == 13, "{}", hashed);
+        assert_ne!(hashed, feed_dir_name("\u{200B}", false));
+    }
+
+    #[test]
+    fn test_filename_template() {
+        let dated = article("Rust: 2024/25", Some("2024-03-05T10:00:00Z"));
+
+        let template = FilenameTemplate::parse("{published:%Y-%m-%d}-{title}.{ext}").unwrap();
+        assert_eq!(template.render(&dated, "news", 1, "md"), "2024-03-05-Rust- 2024-25.md");
+        assert_eq!(template.render(&article("Draft", None), "news", 1, "md"), "undated-Draft.md");
+
+        let indexed = FilenameTemplate::parse("{index:03}-{title}").unwrap();
+        assert_eq!(indexed.render(&dated, "news", 7, "txt"), "007-Rust- 2024-25.txt");
+
+        let braces = FilenameTemplate::parse("{{{feed}}} {title}").unwrap();
+        assert_eq!(braces.render(&dated, "news", 1, "md"), "{news} Rust- 2024-25.md");
+    }
+
+    #[test]
+    fn test_filename_template_errors() {
+        for template in ["{title", "title}", "{nope}", "{index:x}", "{title:%Y}", "{published}.{ext}"] {
+            assert!(FilenameTemplate::parse(template).is_err(), "{} should be rejected", template);
+        }
+    }
+
+    #[test]
+    fn test_with_collision_suffix() {
+        assert_eq!(with_collision_suffix("Title.md", 2, MAX_NAME_BYTES), "Title (2).md");
+        assert_eq!(with_collision_suffix("Title", 3, MAX_NAME_BYTES), "Title (3)");
+    }
+
+    #[test]
+    fn test_transliterate() {
+        assert_eq!(transliterate("статьи"), "stati");
+        assert_eq!(transliterate("Zürich news"), "Zurich news");
+        assert_eq!(transliterate("中文"), "Zhong Wen");
+        assert_eq!(transliterate("plain-ascii.md"), "plain-ascii.md");
+    }
+
+    #[test]
+    fn test_transliterate_falls_back_to_original() {
+        // Private-use characters have no transliteration
+        assert_eq!(transliterate("\u{E000}"), "\u{E000}");
+    }
+}
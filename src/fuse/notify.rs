@@ -0,0 +1,59 @@
+use std::ffi::OsString;
+use std::sync::mpsc::{self, Receiver, Sender};
+use tracing::{debug, warn};
+
+/// Kernel cache entry that no longer matches the filesystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Invalidation {
+    /// The name `name` in directory `parent` may now resolve differently
+    Entry { parent: u64, name: OsString },
+    /// The attributes and contents of an inode changed
+    Inode(u64),
+}
+
+/// Tells the kernel to drop cached entries and attributes, so listings
+/// update as soon as a refresh changes them instead of when their TTL runs
+/// out. Notifications are queued and sent from a dedicated thread: sending
+/// one while the kernel waits on a reply from the same mount would deadlock.
+#[derive(Debug, Clone)]
+pub struct KernelNotifier {
+    sender: Sender<Invalidation>,
+}
+
+impl KernelNotifier {
+    /// A notifier whose invalidations are delivered to the returned receiver
+    pub fn new() -> (Self, Receiver<Invalidation>) {
+        let (sender, receiver) = mpsc::channel();
+        (Self { sender }, receiver)
+    }
+
+    /// Forward invalidations to the kernel through a mounted session's notifier
+    pub fn spawn(notifier: fuser::Notifier) -> Self {
+        let (this, receiver) = Self::new();
+        let spawned = std::thread::Builder::new()
+            .name("fuse-notify".to_string())
+            .spawn(move || {
+                for invalidation in receiver {
+                    let result = match &invalidation {
+                        Invalidation::Entry { parent, name } => notifier.inval_entry(*parent, name),
+                        Invalidation::Inode(ino) => notifier.inval_inode(*ino, 0, 0),
+                    };
+                    match result {
+                        Ok(()) => {},
+                        // The kernel never looked the entry up, so nothing was cached
+                        Err(e) if e.raw_os_error() == Some(libc::ENOENT) => {},
+                        Err(e) => debug!("Failed to send {:?}: {}", invalidation, e),
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            warn!("Failed to start kernel notification thread: {}", e);
+        }
+        this
+    }
+
+    pub fn invalidate(&self, invalidation: Invalidation) {
+        // Only fails once the session has ended, when there is nothing to invalidate
+        let _ = self.sender.send(invalidation);
+    }
+}
@@ -1,23 +1,111 @@
 use std::sync::Arc;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
 use tracing::{info, warn, error, debug};
 
 use crate::fuse::filesystem::RssFuseFilesystem;
+use crate::fuse::marker::{self, MountArgs, MountMarker};
+use crate::fuse::mounts::{self, MountEntry, UnmountMode};
+use crate::fuse::notify::KernelNotifier;
 use crate::error::{Error, Result};
 
+/// Source name our mounts carry in `/proc/mounts`
+const FS_NAME: &str = "rss-fuse";
+
+/// Unmount with a system call, falling back to the setuid `fusermount`
+/// helper when refused: regular users may not unmount FUSE file systems
+/// themselves
+fn unmount_with(mount_point_str: &str, mode: UnmountMode) -> std::io::Result<()> {
+    match mounts::unmount(Path::new(mount_point_str), mode) {
+        Err(e) if e.raw_os_error() == Some(libc::EPERM) => {
+            debug!("umount refused ({}), using fusermount", e);
+            let mut fusermount = Command::new("fusermount");
+            fusermount.arg("-u");
+            if mode != UnmountMode::Normal {
+                // fusermount cannot force; detaching is the closest it gets
+                fusermount.arg("-z");
+            }
+            let output = fusermount.arg(mount_point_str).output()?;
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(std::io::Error::new(std::io::ErrorKind::Other,
+                    String::from_utf8_lossy(&output.stderr).trim().to_string()))
+            }
+        }
+        result => result,
+    }
+}
+
+/// Mount points of running RSS-FUSE filesystems, as listed in
+/// `/proc/self/mountinfo`
+pub fn active_mounts() -> Vec<PathBuf> {
+    mounts::mount_table().map(|table| rss_fuse_mounts(&table)).unwrap_or_default()
+}
+
+/// The RSS-FUSE mount points in a mount table
+fn rss_fuse_mounts(table: &[MountEntry]) -> Vec<PathBuf> {
+    table.iter()
+        .filter(|entry| entry.source == FS_NAME && entry.fs_type.starts_with("fuse"))
+        .map(|entry| entry.mount_point.clone())
+        .collect()
+}
+
 /// FUSE operations manager for mounting and unmounting the filesystem
 pub struct FuseOperations {
     pub filesystem: Arc<RssFuseFilesystem>,
+    session: Mutex<Option<MountedSession>>,
+    /// Where mount markers are kept
+    markers_dir: PathBuf,
+    /// How the mount was requested, recorded in its marker
+    mount_args: MountArgs,
+}
+
+/// A FUSE session started by `mount`: the thread running its request loop
+/// and the handle that unmounts it
+struct MountedSession {
+    mount_point: PathBuf,
+    /// The marker written for the mount, removed once it ends
+    marker: MountMarker,
+    unmount: Box<dyn FnMut() -> std::io::Result<()> + Send>,
+    thread: std::thread::JoinHandle<std::io::Result<()>>,
 }
 
 impl FuseOperations {
     pub fn new() -> Self {
         Self {
             filesystem: Arc::new(RssFuseFilesystem::new()),
+            session: Mutex::new(None),
+            markers_dir: marker::markers_dir(),
+            mount_args: MountArgs::default(),
+        }
+    }
+
+    /// Create operations for a filesystem using the given `[fuse]` settings
+    pub fn with_config(config: &crate::config::FilesystemConfig) -> Self {
+        Self {
+            filesystem: Arc::new(RssFuseFilesystem::with_config(config)),
+            session: Mutex::new(None),
+            markers_dir: marker::markers_dir(),
+            mount_args: MountArgs::default(),
         }
     }
 
+    /// Keep mount markers in `dir` instead of the per-user default
+    pub fn with_markers_dir(mut self, dir: PathBuf) -> Self {
+        self.markers_dir = dir;
+        self
+    }
+
+    /// Record in the mount marker that the mount was made with `args`, for
+    /// `remount` to repeat
+    pub fn with_mount_args(mut self, args: MountArgs) -> Self {
+        self.mount_args = args;
+        self
+    }
+
     /// Mount the RSS-FUSE filesystem at the specified mount point
     pub fn mount(&self, mount_point: &Path, options: MountOptions) -> Result<()> {
         info!("Mounting RSS-FUSE at: {}", mount_point.display());
@@ -50,6 +138,11 @@ impl FuseOperations {
             fuse_options.push(format!("gid={}", gid));
         }
 
+        if options.read_only {
+            fuse_options.push("-o".to_string());
+            fuse_options.push("ro".to_string());
+        }
+
         // Set default permissions
         fuse_options.push("-o".to_string());
         fuse_options.push("default_permissions".to_string());
@@ -74,32 +167,91 @@ impl FuseOperations {
             mount_options.push(fuser::MountOption::RO);
         }
         mount_options.push(fuser::MountOption::DefaultPermissions);
+        mount_options.push(fuser::MountOption::FSName(FS_NAME.to_string()));
         
-        // Create a new thread for the FUSE session
-        let mount_point_clone = mount_point.to_path_buf();
-        
-        std::thread::spawn(move || {
-            info!("Starting FUSE session at {}", mount_point_clone.display());
-            
-            // Mount with fuser
-            match fuser::mount2(fs, &mount_point_clone, &mount_options) {
+        // Mount with fuser; the mount is in place once this returns
+        let mut session = fuser::Session::new(fs, mount_point, &mount_options).map_err(|e| {
+            error!("FUSE mount failed: {}", e);
+            Error::Fuse(format!("Failed to mount {}: {}", mount_point.display(), e))
+        })?;
+
+        // Refreshes invalidate the kernel's cached listings as they land
+        self.filesystem.set_notifier(KernelNotifier::spawn(session.notifier()));
+        let mut unmounter = session.unmount_callable();
+
+        // Serve requests on a thread of its own until unmounted
+        let session_mount_point = mount_point.to_path_buf();
+        let thread = std::thread::spawn(move || {
+            info!("Starting FUSE session at {}", session_mount_point.display());
+            let result = session.run();
+            match &result {
                 Ok(_) => {
                     info!("FUSE session ended normally");
                 },
                 Err(e) => {
-                    error!("FUSE mount failed: {}", e);
+                    error!("FUSE session failed: {}", e);
                 }
             }
+            result
+        });
+        // Lets the next mount clean up after us should this process die
+        // without unmounting
+        let marker = MountMarker::for_current_process(mount_point, self.filesystem.clock().now_utc())
+            .with_args(self.mount_args.clone());
+        if let Err(e) = marker.write(&self.markers_dir) {
+            warn!("Failed to write mount marker for {}: {}", mount_point.display(), e);
+        }
+        *self.session.lock() = Some(MountedSession {
+            // As listed in the mount table, to tell whether it is still mounted
+            mount_point: mount_point.canonicalize().unwrap_or_else(|_| mount_point.to_path_buf()),
+            marker,
+            unmount: Box::new(move || unmounter.unmount()),
+            thread,
         });
-        
-        // Give the mount a moment to establish (reduced for faster startup)
-        std::thread::sleep(std::time::Duration::from_millis(50));
         
         info!("Filesystem mounted successfully at {}", mount_point.display());
         
         Ok(())
     }
 
+    /// Unmount the filesystem this process mounted and wait for its session
+    /// to end. The request being served (if any) is finished first; a busy
+    /// mount is detached, and after `timeout` the session is given up on.
+    pub fn shutdown(&self, timeout: Duration) -> Result<()> {
+        let Some(mut session) = self.session.lock().take() else {
+            return Ok(());
+        };
+        info!("Shutting down FUSE session at {}", session.mount_point.display());
+
+        if let Err(e) = (session.unmount)() {
+            warn!("Failed to unmount {}: {}", session.mount_point.display(), e);
+        }
+        if mounts::is_mount_point(&session.mount_point) {
+            // Busy: detach it now, the kernel ends the session once the last
+            // file is closed
+            warn!("{} is busy, detaching it", session.mount_point.display());
+            if let Err(e) = unmount_with(&session.mount_point.to_string_lossy(), UnmountMode::Lazy) {
+                warn!("Failed to detach {}: {}", session.mount_point.display(), e);
+            }
+        }
+
+        let deadline = Instant::now() + timeout;
+        while !session.thread.is_finished() {
+            if Instant::now() >= deadline {
+                return Err(Error::Fuse(format!(
+                    "FUSE session at {} still running after {}s", session.mount_point.display(), timeout.as_secs()
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        MountMarker::remove(&self.markers_dir, &session.marker.mount_point);
+        match session.thread.join() {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(Error::Fuse(format!("FUSE session failed: {}", e))),
+            Err(_) => Err(Error::Fuse("FUSE session thread panicked".to_string())),
+        }
+    }
+
     /// Unmount the filesystem at the specified mount point
     pub fn unmount(&self, mount_point: &Path, force: bool) -> Result<()> {
         info!("Unmounting RSS-FUSE from: {}", mount_point.display());
@@ -194,129 +346,39 @@ impl FuseOperations {
     /// Try graceful unmount
     fn try_graceful_unmount(&self, mount_point_str: &str) -> Result<()> {
         debug!("Attempting graceful unmount of {}", mount_point_str);
-        
-        // Try fusermount first (Linux)
-        let output = Command::new("fusermount")
-            .args(["-u", mount_point_str])
-            .output()
-            .map_err(|e| Error::Fuse(format!("Failed to run fusermount: {}", e)))?;
-
-        if output.status.success() {
-            return Ok(());
-        }
-
-        // Try umount as fallback (macOS/BSD)
-        let output = Command::new("umount")
-            .arg(mount_point_str)
-            .output()
-            .map_err(|e| Error::Fuse(format!("Failed to run umount: {}", e)))?;
-
-        if output.status.success() {
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(Error::Fuse(format!("Graceful unmount failed: {}", stderr)))
-        }
+        unmount_with(mount_point_str, UnmountMode::Normal)
+            .map_err(|e| Error::Fuse(format!("Graceful unmount failed: {}", e)))
     }
 
     /// Try force unmount
     fn try_force_unmount(&self, mount_point_str: &str, _force: bool) -> Result<()> {
         debug!("Attempting force unmount of {}", mount_point_str);
-        
-        // Try fusermount with force flags (Linux)
-        let output = Command::new("fusermount")
-            .args(["-u", "-z", mount_point_str])
-            .output()
-            .map_err(|e| Error::Fuse(format!("Failed to run fusermount: {}", e)))?;
-
-        if output.status.success() {
-            return Ok(());
-        }
-
-        // Try umount with force flag (macOS/BSD)
-        let output = Command::new("umount")
-            .args(["-f", mount_point_str])
-            .output()
-            .map_err(|e| Error::Fuse(format!("Failed to run umount: {}", e)))?;
-
-        if output.status.success() {
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(Error::Fuse(format!("Force unmount failed: {}", stderr)))
-        }
+        unmount_with(mount_point_str, UnmountMode::Force)
+            .map_err(|e| Error::Fuse(format!("Force unmount failed: {}", e)))
     }
 
     /// Try lazy unmount (detach immediately, cleanup when no longer in use)
     fn try_lazy_unmount(&self, mount_point_str: &str) -> Result<()> {
         debug!("Attempting lazy unmount of {}", mount_point_str);
-        
-        // Linux: fusermount with -z (lazy) flag
-        let output = Command::new("fusermount")
-            .args(["-u", "-z", mount_point_str])
-            .output()
-            .map_err(|e| Error::Fuse(format!("Failed to run fusermount: {}", e)))?;
-
-        if output.status.success() {
-            return Ok(());
-        }
-
-        // Linux: umount with -l (lazy) flag
-        let output = Command::new("umount")
-            .args(["-l", mount_point_str])
-            .output()
-            .map_err(|e| Error::Fuse(format!("Failed to run umount: {}", e)))?;
-
-        if output.status.success() {
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(Error::Fuse(format!("Lazy unmount failed: {}", stderr)))
-        }
+        unmount_with(mount_point_str, UnmountMode::Lazy)
+            .map_err(|e| Error::Fuse(format!("Lazy unmount failed: {}", e)))
     }
 
     /// Check if mount point is busy
     fn is_mount_busy(&self, mount_point_str: &str) -> bool {
-        // Check with lsof if available
-        if let Ok(output) = Command::new("lsof")
-            .args(["+D", mount_point_str])
-            .output() {
-            !output.stdout.is_empty()
-        } else {
-            // Fallback: check with fuser if available
-            if let Ok(output) = Command::new("fuser")
-                .args(["-m", mount_point_str])
-                .output() {
-                !output.stdout.is_empty()
-            } else {
-                false
-            }
-        }
+        !mounts::mount_users(Path::new(mount_point_str)).is_empty()
     }
 
     /// Show what processes are using the mount point
     fn show_mount_usage(&self, mount_point_str: &str) {
         info!("Checking what's using mount point: {}", mount_point_str);
         
-        // Try lsof first
-        if let Ok(output) = Command::new("lsof")
-            .args(["+D", mount_point_str])
-            .output() {
-            if !output.stdout.is_empty() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                info!("Processes using mount point:\n{}", stdout);
-                return;
-            }
-        }
-
-        // Try fuser as fallback
-        if let Ok(output) = Command::new("fuser")
-            .args(["-v", mount_point_str])
-            .output() {
-            if !output.stdout.is_empty() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                info!("Processes using mount point:\n{}", stdout);
-            }
+        let users = mounts::mount_users(Path::new(mount_point_str));
+        if !users.is_empty() {
+            let listing: Vec<String> = users.iter()
+                .map(|user| format!("  {} ({}): {}", user.pid, user.command, user.holds.display()))
+                .collect();
+            info!("Processes using mount point:\n{}", listing.join("\n"));
         }
     }
 
@@ -324,34 +386,41 @@ impl FuseOperations {
     fn kill_mount_users(&self, mount_point_str: &str) -> Result<()> {
         warn!("Force flag enabled - attempting to kill processes using mount point");
         
-        // Try fuser -k (kill processes)
-        if let Ok(output) = Command::new("fuser")
-            .args(["-k", "-m", mount_point_str])
-            .output() {
-            if output.status.success() {
-                info!("Killed processes using mount point");
-                return Ok(());
-            }
-        }
-
-        // Manual approach with lsof + kill
-        if let Ok(output) = Command::new("lsof")
-            .args(["-t", "+D", mount_point_str])
-            .output() {
-            let pids = String::from_utf8_lossy(&output.stdout);
-            for pid_str in pids.lines() {
-                if let Ok(pid) = pid_str.trim().parse::<u32>() {
-                    warn!("Killing process {} using mount point", pid);
-                    let _ = Command::new("kill")
-                        .args(["-TERM", &pid.to_string()])
-                        .output();
-                }
+        for user in mounts::mount_users(Path::new(mount_point_str)) {
+            warn!("Killing process {} ({}) using mount point", user.pid, user.command);
+            if let Err(e) = mounts::terminate(user.pid) {
+                warn!("Failed to signal process {}: {}", user.pid, e);
             }
         }
 
         Ok(())
     }
 
+    /// Clean up after an earlier RSS-FUSE process that served `mount_point`
+    /// and exited without unmounting, as found from the marker it left.
+    /// Returns that marker when its process is gone; a mount there that is
+    /// not ours is left alone.
+    pub fn recover_crashed_mount(&self, mount_point: &Path) -> Result<Option<MountMarker>> {
+        let Some(marker) = MountMarker::read(&self.markers_dir, mount_point) else {
+            return Ok(None);
+        };
+        if marker.is_running() {
+            return Ok(None);
+        }
+        let ours = mounts::mount_table().map_or(true, |table| {
+            table.iter()
+                .rev()
+                .find(|entry| entry.mount_point == marker.mount_point)
+                .is_some_and(|entry| !rss_fuse_mounts(std::slice::from_ref(entry)).is_empty())
+        });
+        if ours && self.is_mounted(&marker.mount_point) {
+            info!("Process {} exited without unmounting {}", marker.pid, marker.mount_point.display());
+            self.cleanup_stale_mount(&marker.mount_point)?;
+        }
+        MountMarker::remove(&self.markers_dir, &marker.mount_point);
+        Ok(Some(marker))
+    }
+
     /// Check if a mount point is stale (appears mounted but not responsive)
     pub fn is_mount_stale(&self, mount_point: &Path) -> bool {
         if !self.is_mounted(mount_point) {
@@ -395,31 +464,7 @@ impl FuseOperations {
 
     /// Check if a path is currently mounted
     pub fn is_mounted(&self, mount_point: &Path) -> bool {
-        let mount_point_str = match mount_point.to_str() {
-            Some(s) => s,
-            None => return false,
-        };
-
-        // Check /proc/mounts on Linux (fast system call)
-        if let Ok(mounts) = std::fs::read_to_string("/proc/mounts") {
-            for line in mounts.lines() {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 2 && parts[1] == mount_point_str {
-                    return true;
-                }
-            }
-            // /proc/mounts was readable but mount point not found
-            return false;
-        }
-
-        // Only use mount command as fallback if /proc/mounts is unavailable
-        // (avoid redundant system call for performance)
-        if let Ok(output) = Command::new("mount").output() {
-            let mounts = String::from_utf8_lossy(&output.stdout);
-            return mounts.lines().any(|line| line.contains(mount_point_str));
-        }
-
-        false
+        mounts::is_mount_point(mount_point)
     }
 
     /// Get filesystem statistics
@@ -525,12 +570,27 @@ impl Default for FuseOperations {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Utc;
     use tempfile::TempDir;
 
     #[test]
     fn test_fuse_operations_creation() {
         let ops = FuseOperations::new();
-        assert_eq!(ops.filesystem.get_total_inodes(), 5); // root + meta structure (4 nodes: root, .rss-fuse, logs, cache, config.toml)
+        assert_eq!(ops.filesystem.get_total_inodes(), 7); // root + meta structure (.rss-fuse, logs, cache, config.toml, status.json, status.md)
+        // Nothing mounted, nothing to wait for
+        ops.shutdown(Duration::ZERO).unwrap();
+    }
+
+    #[test]
+    fn test_rss_fuse_mounts() {
+        let table = mounts::parse_mountinfo("22 1 0:21 / /proc rw,nosuid shared:12 - proc proc rw\n\
+            41 22 0:38 / /home/me/rss rw,nosuid,nodev shared:20 - fuse rss-fuse rw\n\
+            42 22 0:39 / /tmp/my\\040feeds rw - fuse.rss-fuse rss-fuse rw\n\
+            43 22 0:40 / /mnt/remote rw - fuse.sshfs sshfs#host rw\n");
+        assert_eq!(rss_fuse_mounts(&table), vec![
+            PathBuf::from("/home/me/rss"),
+            PathBuf::from("/tmp/my feeds"),
+        ]);
     }
 
     #[test]
@@ -599,7 +659,7 @@ mod tests {
         let ops = FuseOperations::new();
         let stats = ops.get_stats();
         
-        assert_eq!(stats.total_inodes, 5); // root + meta structure
+        assert_eq!(stats.total_inodes, 7); // root + meta structure
         assert_eq!(stats.feeds_count, 0);
     }
 
@@ -611,4 +671,28 @@ mod tests {
         // Should return false for unmounted directory
         assert!(!ops.is_mounted(temp_dir.path()));
     }
+
+    #[test]
+    fn test_recover_crashed_mount() {
+        let temp_dir = TempDir::new().unwrap();
+        let markers_dir = temp_dir.path().join("markers");
+        let ops = FuseOperations::new().with_markers_dir(markers_dir.clone());
+        let mount_point = temp_dir.path().join("feeds");
+        assert_eq!(ops.recover_crashed_mount(&mount_point).unwrap(), None);
+
+        let mut child = Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        let crashed = MountMarker { pid: child.id(), process_start: None, ..MountMarker::for_current_process(&mount_point, Utc::now()) };
+        crashed.write(&markers_dir).unwrap();
+
+        // Not mounted any more, so only the marker is left to forget
+        assert_eq!(ops.recover_crashed_mount(&mount_point).unwrap(), Some(crashed));
+        assert_eq!(MountMarker::read(&markers_dir, &mount_point), None);
+
+        // A marker of a running process is kept
+        let running = MountMarker::for_current_process(&mount_point, Utc::now());
+        running.write(&markers_dir).unwrap();
+        assert_eq!(ops.recover_crashed_mount(&mount_point).unwrap(), None);
+        assert_eq!(MountMarker::read(&markers_dir, &mount_point), Some(running));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,156 @@
+//! Call counts and latency histograms of the FUSE callbacks, with a warning
+//! for any call slower than the configured threshold
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Upper bounds of the latency histogram buckets, in milliseconds. Calls
+/// slower than the last bound only count towards the total.
+pub const LATENCY_BUCKETS_MS: [f64; 10] = [0.1, 0.5, 1.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0];
+
+/// Per-operation statistics, shared by every clone of a filesystem
+#[derive(Debug, Default)]
+pub struct OpStats {
+    ops: Mutex<BTreeMap<&'static str, OpLatency>>,
+    slow_threshold_ms: AtomicU64, // 0 disables slow-operation warnings
+}
+
+#[derive(Debug, Clone, Default)]
+struct OpLatency {
+    count: u64,
+    total_us: u64,
+    max_us: u64,
+    buckets: [u64; LATENCY_BUCKETS_MS.len()], // calls within each bound, not cumulative
+}
+
+/// One operation's figures, as reported in `status.json` and by `status`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpReport {
+    pub op: String,
+    pub count: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+    /// Calls finishing within each of `LATENCY_BUCKETS_MS`, cumulative
+    pub buckets: Vec<u64>,
+}
+
+impl OpReport {
+    pub fn avg_ms(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.total_ms / self.count as f64 }
+    }
+
+    /// Bucket bound at or below which a fraction `q` of the calls finished;
+    /// `None` when they ran past the largest bucket
+    pub fn quantile_ms(&self, q: f64) -> Option<f64> {
+        let wanted = (self.count as f64 * q).ceil() as u64;
+        self.buckets.iter()
+            .position(|&within| within >= wanted)
+            .map(|i| LATENCY_BUCKETS_MS[i])
+    }
+
+    /// `quantile_ms` for display, e.g. `≤5ms`
+    pub fn quantile_label(&self, q: f64) -> String {
+        match self.quantile_ms(q) {
+            Some(bound) => format!("≤{}ms", bound),
+            None => format!(">{}ms", LATENCY_BUCKETS_MS[LATENCY_BUCKETS_MS.len() - 1]),
+        }
+    }
+}
+
+impl OpStats {
+    /// Warn about calls taking longer than `threshold`; zero turns it off
+    pub fn set_slow_threshold(&self, threshold: Duration) {
+        self.slow_threshold_ms.store(threshold.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Time a call to `op` on inode `ino` until the returned guard drops
+    pub fn time(&self, op: &'static str, ino: u64) -> OpTimer<'_> {
+        OpTimer { stats: self, op, ino, started: Instant::now() }
+    }
+
+    pub fn record(&self, op: &'static str, ino: u64, elapsed: Duration) {
+        let us = elapsed.as_micros() as u64;
+        {
+            let mut ops = self.ops.lock();
+            let latency = ops.entry(op).or_default();
+            latency.count += 1;
+            latency.total_us += us;
+            latency.max_us = latency.max_us.max(us);
+            if let Some(i) = LATENCY_BUCKETS_MS.iter().position(|&bound| us as f64 <= bound * 1000.0) {
+                latency.buckets[i] += 1;
+            }
+        }
+
+        let threshold = self.slow_threshold_ms.load(Ordering::Relaxed);
+        if threshold > 0 && elapsed >= Duration::from_millis(threshold) {
+            warn!("Slow FUSE {} on inode {}: {}ms", op, ino, elapsed.as_millis());
+        }
+    }
+
+    /// Figures for every operation called so far, by name
+    pub fn report(&self) -> Vec<OpReport> {
+        self.ops.lock().iter()
+            .map(|(op, latency)| OpReport {
+                op: op.to_string(),
+                count: latency.count,
+                total_ms: latency.total_us as f64 / 1000.0,
+                max_ms: latency.max_us as f64 / 1000.0,
+                buckets: latency.buckets.iter()
+                    .scan(0, |within, n| { *within += n; Some(*within) })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+/// Records the time since it was created when dropped
+pub struct OpTimer<'a> {
+    stats: &'a OpStats,
+    op: &'static str,
+    ino: u64,
+    started: Instant,
+}
+
+impl Drop for OpTimer<'_> {
+    fn drop(&mut self) {
+        self.stats.record(self.op, self.ino, self.started.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_op_stats() {
+        let stats = OpStats::default();
+        stats.record("lookup", 1, Duration::from_micros(50));
+        stats.record("lookup", 1, Duration::from_micros(800));
+        stats.record("lookup", 2, Duration::from_millis(30));
+        stats.record("read", 9, Duration::from_secs(6));
+        drop(stats.time("readdir", 1));
+
+        let report = stats.report();
+        let ops: Vec<&str> = report.iter().map(|r| r.op.as_str()).collect();
+        assert_eq!(ops, ["lookup", "read", "readdir"]);
+
+        let lookup = &report[0];
+        assert_eq!(lookup.count, 3);
+        assert_eq!(lookup.max_ms, 30.0);
+        assert_eq!(lookup.buckets, [1, 1, 2, 2, 2, 3, 3, 3, 3, 3]);
+        assert_eq!(lookup.quantile_ms(0.5), Some(1.0));
+        assert_eq!(lookup.quantile_ms(0.99), Some(50.0));
+        assert!((lookup.avg_ms() - 10.283).abs() < 0.001);
+
+        // Past the largest bucket
+        assert_eq!(report[1].buckets.last(), Some(&0));
+        assert_eq!(report[1].quantile_ms(0.5), None);
+        assert_eq!(report[1].quantile_label(0.5), ">5000ms");
+        assert_eq!(lookup.quantile_label(0.5), "≤1ms");
+        assert_eq!(report[2].count, 1);
+    }
+}
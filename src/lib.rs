@@ -5,7 +5,13 @@ pub mod fuse;
 pub mod storage;
 pub mod content;
 pub mod config;
+pub mod config_check;
 pub mod file_manager;
+pub mod limits;
+pub mod lock;
+pub mod profile;
+pub mod clock;
+pub mod sync;
 
 pub use config::Config;
 pub use error::{Error, Result};
\ No newline at end of file
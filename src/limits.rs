@@ -0,0 +1,163 @@
+//! Resource budgeting: compare current usage against configured soft/hard limits
+
+use std::fmt;
+
+use crate::config::{Config, ResourceLimits};
+use crate::error::{Error, Result};
+use crate::storage::persistent_cache::{PersistentCache, PersistentCacheConfig};
+
+/// Current resource usage
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResourceUsage {
+    pub feeds: usize,
+    pub total_articles: usize,
+    pub cache_bytes: u64,
+}
+
+impl ResourceUsage {
+    /// Measure usage from the configuration and the on-disk feed cache
+    pub fn measure(config: &Config) -> Self {
+        let mut usage = Self {
+            feeds: config.feeds.len(),
+            ..Self::default()
+        };
+
+        let cache_config = PersistentCacheConfig::default();
+        if !cache_config.cache_dir.exists() {
+            return usage;
+        }
+
+        if let Ok(cache) = PersistentCache::new(cache_config) {
+            usage.cache_bytes = cache.size_bytes();
+            if let Ok(Some(data)) = cache.load() {
+                usage.total_articles = data.feeds.values().map(|entry| entry.data.articles.len()).sum();
+            }
+        }
+
+        usage
+    }
+}
+
+/// A limit that current usage is above
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitExceeded {
+    pub resource: &'static str,
+    pub current: u64,
+    pub limit: u64,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} (limit {})", self.resource, self.current, self.limit)
+    }
+}
+
+const MB: u64 = 1024 * 1024;
+
+/// List every limit that `usage` is above
+pub fn check(limits: &ResourceLimits, usage: &ResourceUsage) -> Vec<LimitExceeded> {
+    let mut exceeded = Vec::new();
+
+    if let Some(limit) = limits.max_feeds {
+        if usage.feeds > limit {
+            exceeded.push(LimitExceeded { resource: "feeds", current: usage.feeds as u64, limit: limit as u64 });
+        }
+    }
+
+    if let Some(limit) = limits.max_total_articles {
+        if usage.total_articles > limit {
+            exceeded.push(LimitExceeded {
+                resource: "articles",
+                current: usage.total_articles as u64,
+                limit: limit as u64,
+            });
+        }
+    }
+
+    if let Some(limit) = limits.max_cache_mb {
+        if usage.cache_bytes > limit * MB {
+            exceeded.push(LimitExceeded { resource: "cache MB", current: usage.cache_bytes / MB, limit });
+        }
+    }
+
+    exceeded
+}
+
+/// Refuse adding `new_feeds` feeds if that would break a hard limit
+pub fn ensure_can_add_feeds(limits: &ResourceLimits, usage: &ResourceUsage, new_feeds: usize) -> Result<()> {
+    if let Some(limit) = limits.max_feeds {
+        if usage.feeds + new_feeds > limit {
+            return Err(Error::ResourceExhausted(format!(
+                "Hard limit of {} feeds reached ({} configured). Remove feeds or raise [limits.hard] max_feeds",
+                limit, usage.feeds
+            )));
+        }
+    }
+
+    if let Some(limit) = limits.max_total_articles {
+        if usage.total_articles >= limit {
+            return Err(Error::ResourceExhausted(format!(
+                "Hard limit of {} cached articles reached ({} cached). Raise [limits.hard] max_total_articles",
+                limit, usage.total_articles
+            )));
+        }
+    }
+
+    if let Some(limit) = limits.max_cache_mb {
+        if usage.cache_bytes >= limit * MB {
+            return Err(Error::ResourceExhausted(format!(
+                "Hard limit of {} MB cache reached. Clear the cache or raise [limits.hard] max_cache_mb",
+                limit
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a warning line for each exceeded soft limit
+pub fn print_soft_limit_warnings(config: &Config, usage: &ResourceUsage) {
+    for exceeded in check(&config.limits.soft, usage) {
+        println!("⚠️  Soft limit exceeded: {}", exceeded);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(feeds: usize, total_articles: usize, cache_mb: u64) -> ResourceUsage {
+        ResourceUsage { feeds, total_articles, cache_bytes: cache_mb * MB }
+    }
+
+    #[test]
+    fn test_check_reports_exceeded_limits() {
+        let limits = ResourceLimits {
+            max_feeds: Some(10),
+            max_total_articles: Some(1000),
+            max_cache_mb: Some(50),
+        };
+
+        assert!(check(&limits, &usage(10, 1000, 50)).is_empty());
+
+        let exceeded = check(&limits, &usage(11, 2000, 51));
+        assert_eq!(exceeded.len(), 3);
+        assert_eq!(exceeded[0].to_string(), "feeds: 11 (limit 10)");
+    }
+
+    #[test]
+    fn test_unset_limits_never_trigger() {
+        assert!(check(&ResourceLimits::default(), &usage(10_000, 1_000_000, 10_000)).is_empty());
+        assert!(ensure_can_add_feeds(&ResourceLimits::default(), &usage(10_000, 0, 0), 1).is_ok());
+    }
+
+    #[test]
+    fn test_hard_limit_refuses_new_feeds() {
+        let limits = ResourceLimits { max_feeds: Some(3), ..ResourceLimits::default() };
+
+        assert!(ensure_can_add_feeds(&limits, &usage(2, 0, 0), 1).is_ok());
+        let err = ensure_can_add_feeds(&limits, &usage(3, 0, 0), 1).unwrap_err();
+        assert!(matches!(err, Error::ResourceExhausted(_)));
+        assert!(ensure_can_add_feeds(&limits, &usage(1, 0, 0), 3).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Advisory locks that keep two rss-fuse processes from using the same
+//! cache or mount point at once. Locks are `flock(2)` locks on a file that
+//! names the holder, so they go away with the process however it ends.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Seek, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+/// Name of the lock file in the cache directory
+const CACHE_LOCK: &str = "instance.lock";
+
+/// The lock file guarding the cache in `cache_dir`
+pub fn cache_lock_path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join(CACHE_LOCK)
+}
+
+/// The process holding a lock, as recorded in its file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    /// What it holds the lock for, e.g. `mount at /home/me/rss`
+    pub purpose: String,
+    pub since: DateTime<Utc>,
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rss-fuse pid {} ({}, since {})", self.pid, self.purpose, self.since.format("%Y-%m-%d %H:%M:%S UTC"))
+    }
+}
+
+/// An exclusive lock, held until dropped
+#[derive(Debug)]
+pub struct InstanceLock {
+    path: PathBuf,
+    _file: File,
+}
+
+impl InstanceLock {
+    /// Take the lock at `path` and record this process as its holder, or
+    /// `None` if another process (or another lock in this one) holds it
+    pub fn try_acquire(path: &Path, purpose: &str) -> io::Result<Option<Self>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Not truncated before locking: the file names the current holder
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+            let e = io::Error::last_os_error();
+            return match e.raw_os_error() {
+                Some(libc::EWOULDBLOCK) => Ok(None),
+                _ => Err(e),
+            };
+        }
+
+        let holder = LockHolder {
+            pid: std::process::id(),
+            purpose: purpose.to_string(),
+            since: Utc::now(),
+        };
+        file.set_len(0)?;
+        file.rewind()?;
+        serde_json::to_writer(&mut file, &holder)?;
+        file.flush()?;
+        Ok(Some(Self { path: path.to_path_buf(), _file: file }))
+    }
+
+    /// Who holds the lock at `path`, if its file says
+    pub fn holder(path: &Path) -> Option<LockHolder> {
+        serde_json::from_slice(&fs::read(path).ok()?).ok()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = cache_lock_path(dir.path());
+
+        let lock = InstanceLock::try_acquire(&path, "mount at /tmp/feeds").unwrap().unwrap();
+        let holder = InstanceLock::holder(&path).unwrap();
+        assert_eq!(holder.pid, std::process::id());
+        assert_eq!(holder.purpose, "mount at /tmp/feeds");
+        assert!(holder.to_string().starts_with(&format!("rss-fuse pid {} (mount at /tmp/feeds", holder.pid)));
+
+        // Locks on separately opened files exclude each other even within
+        // one process
+        assert!(InstanceLock::try_acquire(&path, "prune").unwrap().is_none());
+        assert_eq!(InstanceLock::holder(&path), Some(holder));
+
+        drop(lock);
+        let lock = InstanceLock::try_acquire(&path, "prune").unwrap().unwrap();
+        assert_eq!(InstanceLock::holder(&path).unwrap().purpose, "prune");
+        assert_eq!(lock.path(), path);
+    }
+}
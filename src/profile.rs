@@ -0,0 +1,69 @@
+//! Profiles: independent feed sets (say `work` and `personal`), each with its
+//! own config, cache, logs and control socket so their mounts can run side
+//! by side. A profile `work` lives in `rss-fuse-work` directories next to the
+//! usual `rss-fuse` ones, which remain the default profile's.
+
+use std::sync::OnceLock;
+
+use crate::error::{Error, Result};
+
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+/// Choose the profile for this process; call once, before any paths are used
+pub fn select(name: Option<String>) -> Result<()> {
+    if let Some(name) = &name {
+        validate(name)?;
+    }
+    PROFILE.set(name).map_err(|_| Error::InvalidState("A profile was already selected".to_string()))
+}
+
+/// The selected profile, `None` for the default one
+pub fn current() -> Option<&'static str> {
+    PROFILE.get().and_then(|name| name.as_deref())
+}
+
+/// Name of the config, cache and data directories of the selected profile
+pub fn dir_name() -> String {
+    dir_name_for(current())
+}
+
+fn dir_name_for(profile: Option<&str>) -> String {
+    match profile {
+        Some(name) => format!("rss-fuse-{}", name),
+        None => "rss-fuse".to_string(),
+    }
+}
+
+/// Profile names end up in directory and socket names
+pub fn validate(name: &str) -> Result<()> {
+    let valid = !name.is_empty() && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(Error::Invalid(format!(
+            "Profile name '{}' must be 1-64 letters, digits, '-' or '_'", name
+        )));
+    }
+    Ok(())
+}
+
+/// `--profile <name>` to repeat in commands printed for the user
+pub fn cli_flag() -> String {
+    current().map(|name| format!(" --profile {}", name)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_names() {
+        assert_eq!(dir_name_for(None), "rss-fuse");
+        assert_eq!(dir_name_for(Some("work")), "rss-fuse-work");
+
+        assert!(validate("work").is_ok());
+        assert!(validate("home_2").is_ok());
+        for bad in ["", "../work", "a/b", ".hidden", "with space", &"x".repeat(65)] {
+            assert!(validate(bad).is_err(), "{:?}", bad);
+        }
+    }
+}
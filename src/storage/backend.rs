@@ -0,0 +1,99 @@
+//! Choosing a storage implementation from `StorageConfig::connection_string`.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::storage::file_storage::FileStorage;
+use crate::storage::traits::{MemoryStorage, Storage, StorageConfig};
+
+/// Backend named by a connection string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// `memory://`: nothing outlives the process
+    Memory,
+    /// `sqlite://path`: an SQLite database file
+    Sqlite(PathBuf),
+    /// `dir:///path`: one JSON file per feed in a directory
+    Directory(PathBuf),
+}
+
+impl StorageBackend {
+    pub fn parse(connection_string: &str) -> Result<Self> {
+        let connection_string = connection_string.trim();
+        let Some((scheme, rest)) = connection_string.split_once("://") else {
+            return Err(Error::Config(format!(
+                "Storage '{}' is not a connection string like memory://, sqlite://path or dir:///path",
+                connection_string
+            )));
+        };
+        let path = |kind: &str| {
+            if rest.is_empty() {
+                Err(Error::Config(format!("Storage '{}' names no {}", connection_string, kind)))
+            } else {
+                Ok(PathBuf::from(rest))
+            }
+        };
+        match scheme.to_ascii_lowercase().as_str() {
+            "memory" if rest.is_empty() => Ok(Self::Memory),
+            "memory" => Err(Error::Config(format!("Storage '{}' takes no path", connection_string))),
+            "sqlite" => path("database file").map(Self::Sqlite),
+            "dir" => path("directory").map(Self::Directory),
+            other => Err(Error::Config(format!(
+                "Unknown storage backend '{}'; use memory://, sqlite://path or dir:///path", other
+            ))),
+        }
+    }
+
+    /// Construct the backend's storage with `config`'s retention limits
+    pub fn open(&self, config: StorageConfig) -> Result<Arc<dyn Storage>> {
+        match self {
+            Self::Memory => Ok(Arc::new(MemoryStorage::new(config))),
+            Self::Directory(dir) => Ok(Arc::new(FileStorage::open(dir, config)?)),
+            Self::Sqlite(path) => Err(Error::Config(format!(
+                "Cannot open '{}': this build has no SQLite support; use memory:// or dir:///path",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// Storage for `config.connection_string`
+pub fn open_storage(config: &StorageConfig) -> Result<Arc<dyn Storage>> {
+    StorageBackend::parse(&config.connection_string)?.open(config.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(StorageBackend::parse("memory://").unwrap(), StorageBackend::Memory);
+        assert_eq!(StorageBackend::parse("sqlite://rss_fuse.db").unwrap(),
+                   StorageBackend::Sqlite(PathBuf::from("rss_fuse.db")));
+        assert_eq!(StorageBackend::parse("dir:///var/lib/rss-fuse").unwrap(),
+                   StorageBackend::Directory(PathBuf::from("/var/lib/rss-fuse")));
+        assert!(StorageBackend::parse("dir://").is_err());
+        assert!(StorageBackend::parse("memory:///tmp").is_err());
+        assert!(StorageBackend::parse("postgres://localhost").is_err());
+        assert!(StorageBackend::parse("/var/lib/rss-fuse").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_open_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("store");
+        let config = StorageConfig {
+            connection_string: format!("dir://{}", dir.display()),
+            ..StorageConfig::default()
+        };
+        let storage = open_storage(&config).unwrap();
+        assert!(matches!(storage.health_check().await.unwrap(), crate::storage::HealthStatus::Healthy));
+        assert!(dir.is_dir());
+
+        let sqlite = StorageConfig { connection_string: "sqlite://x.db".to_string(), ..StorageConfig::default() };
+        assert!(open_storage(&sqlite).is_err());
+    }
+}
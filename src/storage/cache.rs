@@ -1,14 +1,34 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use lru::LruCache;
 use std::num::NonZeroUsize;
 
+use crate::clock::{system_clock, SharedClock};
 use crate::feed::{Feed, Article};
+use crate::feed::fetcher::CacheValidators;
 use crate::error::{Error, Result};
 use crate::storage::persistent_cache::{PersistentCache, PersistentCacheConfig};
 
+/// How often feeds changed since the last flush are appended to the
+/// journal, which bounds what a crash loses
+const JOURNAL_FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often changed feeds are written to their files and the journal emptied
+const SAVE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Journal size past which a flush writes the feed files right away
+const JOURNAL_CHECKPOINT_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Feed entries with the cached articles they list and their HTTP
+/// validators, as written to disk
+type Snapshot = (
+    HashMap<String, CacheEntry<Feed>>,
+    HashMap<String, CacheEntry<Arc<Article>>>,
+    HashMap<String, CacheValidators>,
+);
+
 /// Cache entry with expiration tracking
 #[derive(Debug, Clone)]
 pub struct CacheEntry<T> {
@@ -21,7 +41,11 @@ pub struct CacheEntry<T> {
 
 impl<T> CacheEntry<T> {
     pub fn new(data: T, ttl: Duration) -> Self {
-        let now = SystemTime::now();
+        Self::new_at(data, ttl, SystemTime::now())
+    }
+
+    /// Create an entry as if inserted at `now`
+    pub fn new_at(data: T, ttl: Duration, now: SystemTime) -> Self {
         Self {
             data,
             created_at: now,
@@ -32,17 +56,29 @@ impl<T> CacheEntry<T> {
     }
 
     pub fn is_expired(&self) -> bool {
-        SystemTime::now() > self.expires_at
+        self.is_expired_at(SystemTime::now())
+    }
+
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        now > self.expires_at
     }
 
     pub fn access(&mut self) -> &T {
+        self.access_at(SystemTime::now())
+    }
+
+    pub fn access_at(&mut self, now: SystemTime) -> &T {
         self.access_count += 1;
-        self.last_accessed = SystemTime::now();
+        self.last_accessed = now;
         &self.data
     }
 
     pub fn age(&self) -> Duration {
-        SystemTime::now().duration_since(self.created_at)
+        self.age_at(SystemTime::now())
+    }
+
+    pub fn age_at(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.created_at)
             .unwrap_or_default()
     }
 }
@@ -111,10 +147,16 @@ pub struct ArticleCache {
     cache: Arc<RwLock<LruCache<String, CacheEntry<Arc<Article>>>>>,
     stats: Arc<RwLock<CacheStats>>,
     config: CacheConfig,
+    clock: SharedClock,
 }
 
 impl ArticleCache {
     pub fn new(config: CacheConfig) -> Self {
+        Self::with_clock(config, system_clock())
+    }
+
+    /// Create a cache that reads the current time from `clock`
+    pub fn with_clock(config: CacheConfig, clock: SharedClock) -> Self {
         let capacity = NonZeroUsize::new(config.max_entries)
             .unwrap_or(NonZeroUsize::new(1000).unwrap());
         
@@ -122,6 +164,7 @@ impl ArticleCache {
             cache: Arc::new(RwLock::new(LruCache::new(capacity))),
             stats: Arc::new(RwLock::new(CacheStats::default())),
             config,
+            clock,
         }
     }
 
@@ -137,8 +180,9 @@ impl ArticleCache {
         let mut cache = self.cache.write();
         let mut stats = self.stats.write();
 
+        let now = self.clock.now();
         if let Some(entry) = cache.get_mut(article_id) {
-            if entry.is_expired() {
+            if entry.is_expired_at(now) {
                 cache.pop(article_id);
                 stats.record_expiration();
                 stats.record_miss();
@@ -147,7 +191,7 @@ impl ArticleCache {
             }
 
             stats.record_hit();
-            Some(Arc::clone(entry.access()))
+            Some(Arc::clone(entry.access_at(now)))
         } else {
             stats.record_miss();
             None
@@ -156,7 +200,7 @@ impl ArticleCache {
 
     /// Put an article into cache
     pub fn put(&self, article_id: String, article: Arc<Article>) -> Result<()> {
-        let entry = CacheEntry::new(article, self.config.default_ttl);
+        let entry = CacheEntry::new_at(article, self.config.default_ttl, self.clock.now());
         let mut cache = self.cache.write();
         let mut stats = self.stats.write();
 
@@ -170,7 +214,7 @@ impl ArticleCache {
 
     /// Put an article with custom TTL
     pub fn put_with_ttl(&self, article_id: String, article: Arc<Article>, ttl: Duration) -> Result<()> {
-        let entry = CacheEntry::new(article, ttl);
+        let entry = CacheEntry::new_at(article, ttl, self.clock.now());
         let mut cache = self.cache.write();
         let mut stats = self.stats.write();
 
@@ -206,10 +250,11 @@ impl ArticleCache {
         let mut cache = self.cache.write();
         let mut stats = self.stats.write();
         let mut expired_keys = Vec::new();
+        let now = self.clock.now();
 
         // Find expired keys
         for (key, entry) in cache.iter() {
-            if entry.is_expired() {
+            if entry.is_expired_at(now) {
                 expired_keys.push(key.clone());
             }
         }
@@ -262,16 +307,27 @@ impl ArticleCache {
 #[derive(Clone)]
 pub struct FeedCache {
     feeds: Arc<RwLock<HashMap<String, CacheEntry<Feed>>>>,
+    changed: Arc<RwLock<HashSet<String>>>, // put, removed or expired since the last save
+    unjournaled: Arc<RwLock<HashSet<String>>>, // the same, since the last journal flush
     stats: Arc<RwLock<CacheStats>>,
     config: CacheConfig,
+    clock: SharedClock,
 }
 
 impl FeedCache {
     pub fn new(config: CacheConfig) -> Self {
+        Self::with_clock(config, system_clock())
+    }
+
+    /// Create a cache that reads the current time from `clock`
+    pub fn with_clock(config: CacheConfig, clock: SharedClock) -> Self {
         Self {
             feeds: Arc::new(RwLock::new(HashMap::new())),
+            changed: Arc::new(RwLock::new(HashSet::new())),
+            unjournaled: Arc::new(RwLock::new(HashSet::new())),
             stats: Arc::new(RwLock::new(CacheStats::default())),
             config,
+            clock,
         }
     }
 
@@ -280,9 +336,11 @@ impl FeedCache {
         let mut feeds = self.feeds.write();
         let mut stats = self.stats.write();
 
+        let now = self.clock.now();
         if let Some(entry) = feeds.get_mut(feed_name) {
-            if entry.is_expired() {
+            if entry.is_expired_at(now) {
                 feeds.remove(feed_name);
+                self.touch(feed_name);
                 stats.record_expiration();
                 stats.record_miss();
                 stats.total_entries = feeds.len();
@@ -290,7 +348,7 @@ impl FeedCache {
             }
 
             stats.record_hit();
-            Some(entry.access().clone())
+            Some(entry.access_at(now).clone())
         } else {
             stats.record_miss();
             None
@@ -299,10 +357,11 @@ impl FeedCache {
 
     /// Put a feed into cache
     pub fn put(&self, feed_name: String, feed: Feed) -> Result<()> {
-        let entry = CacheEntry::new(feed, self.config.default_ttl);
+        let entry = CacheEntry::new_at(feed, self.config.default_ttl, self.clock.now());
         let mut feeds = self.feeds.write();
         let mut stats = self.stats.write();
 
+        self.touch(&feed_name);
         feeds.insert(feed_name, entry);
         stats.total_entries = feeds.len();
         Ok(())
@@ -314,6 +373,9 @@ impl FeedCache {
         let mut stats = self.stats.write();
 
         let result = feeds.remove(feed_name).map(|entry| entry.data);
+        if result.is_some() {
+            self.touch(feed_name);
+        }
         stats.total_entries = feeds.len();
         result
     }
@@ -323,19 +385,49 @@ impl FeedCache {
         let mut feeds = self.feeds.write();
         let mut stats = self.stats.write();
         
-        feeds.clear();
+        for (name, _) in feeds.drain() {
+            self.touch(&name);
+        }
         stats.total_entries = 0;
     }
 
+    /// Names of the feeds put, removed or expired since the last call, whose
+    /// persistent cache files are out of date
+    pub fn take_changed(&self) -> HashSet<String> {
+        std::mem::take(&mut *self.changed.write())
+    }
+
+    /// Count feeds as changed again, e.g. after saving them failed
+    pub fn mark_changed(&self, feed_names: impl IntoIterator<Item = String>) {
+        self.changed.write().extend(feed_names);
+    }
+
+    /// Names of the feeds changed since the last call, whose entries the
+    /// journal doesn't have yet
+    pub fn take_unjournaled(&self) -> HashSet<String> {
+        std::mem::take(&mut *self.unjournaled.write())
+    }
+
+    /// Count feeds as not journaled again, e.g. after appending them failed
+    pub fn mark_unjournaled(&self, feed_names: impl IntoIterator<Item = String>) {
+        self.unjournaled.write().extend(feed_names);
+    }
+
+    fn touch(&self, feed_name: &str) {
+        self.changed.write().insert(feed_name.to_string());
+        self.unjournaled.write().insert(feed_name.to_string());
+    }
+
     /// Clean up expired feeds
     pub fn cleanup_expired(&self) -> usize {
         let mut feeds = self.feeds.write();
         let mut stats = self.stats.write();
         let mut expired_keys = Vec::new();
+        let now = self.clock.now();
 
         // Find expired keys
         for (key, entry) in feeds.iter() {
-            if entry.is_expired() {
+            if entry.is_expired_at(now) {
                 expired_keys.push(key.clone());
             }
         }
@@ -344,6 +436,7 @@ impl FeedCache {
         let count = expired_keys.len();
         for key in expired_keys {
             feeds.remove(&key);
+            self.touch(&key);
             stats.record_expiration();
         }
 
@@ -379,10 +472,18 @@ pub struct CacheManager {
     pub articles: ArticleCache,
     pub feeds: FeedCache,
     persistent_cache: Option<Arc<PersistentCache>>,
+    validators: Arc<RwLock<HashMap<String, CacheValidators>>>, // feed name -> saved with the feed's entry
+    flush_lock: Arc<Mutex<()>>, // keeps a journal flush from interleaving with a save
+    clock: SharedClock,
 }
 
 impl CacheManager {
     pub fn new(config: CacheConfig) -> Self {
+        Self::with_clock(config, system_clock())
+    }
+
+    /// Create a cache manager whose caches share the given clock
+    pub fn with_clock(config: CacheConfig, clock: SharedClock) -> Self {
         let article_config = CacheConfig {
             max_entries: config.max_entries,
             ..config.clone()
@@ -394,9 +495,12 @@ impl CacheManager {
         };
 
         Self {
-            articles: ArticleCache::new(article_config),
-            feeds: FeedCache::new(feed_config),
+            articles: ArticleCache::with_clock(article_config, Arc::clone(&clock)),
+            feeds: FeedCache::with_clock(feed_config, Arc::clone(&clock)),
             persistent_cache: None,
+            validators: Arc::new(RwLock::new(HashMap::new())),
+            flush_lock: Arc::new(Mutex::new(())),
+            clock,
         }
     }
 
@@ -418,6 +522,9 @@ impl CacheManager {
             articles: ArticleCache::new(article_config),
             feeds: FeedCache::new(feed_config),
             persistent_cache: Some(Arc::new(persistent_cache)),
+            validators: Arc::new(RwLock::new(HashMap::new())),
+            flush_lock: Arc::new(Mutex::new(())),
+            clock: system_clock(),
         };
 
         // Load existing cache from disk
@@ -433,10 +540,12 @@ impl CacheManager {
                 tracing::info!("Loading persistent cache: {} feeds, {} articles", 
                               cache_data.feeds.len(), cache_data.articles.len());
 
+                let now = self.clock.now();
+
                 // Load feeds into cache
                 for (feed_name, entry_data) in cache_data.feeds {
                     let cache_entry: CacheEntry<Feed> = entry_data.into();
-                    if !cache_entry.is_expired() {
+                    if !cache_entry.is_expired_at(now) {
                         let _ = self.feeds.put(feed_name, cache_entry.data);
                     }
                 }
@@ -444,11 +553,29 @@ impl CacheManager {
                 // Load articles into cache
                 for (article_id, entry_data) in cache_data.articles {
                     let cache_entry: CacheEntry<Article> = entry_data.into();
-                    if !cache_entry.is_expired() {
+                    if !cache_entry.is_expired_at(now) {
                         let _ = self.articles.put(article_id, Arc::new(cache_entry.data));
                     }
                 }
 
+                // Validators only hold for feeds whose content came back
+                let feeds = self.feeds.feeds.read();
+                self.validators.write().extend(
+                    cache_data.validators.into_iter().filter(|(name, _)| feeds.contains_key(name))
+                );
+                drop(feeds);
+
+                // What was just loaded is what is on disk, though feeds
+                // replayed from the journal are yet to reach their files
+                self.feeds.take_changed();
+                self.feeds.take_unjournaled();
+                if !cache_data.journaled.is_empty() {
+                    tracing::info!("Replayed {} feeds from the cache journal", cache_data.journaled.len());
+                    self.feeds.mark_changed(cache_data.journaled);
+                    if let Err(e) = self.save_to_disk() {
+                        tracing::warn!("Failed to save feeds replayed from the cache journal: {}", e);
+                    }
+                }
                 tracing::info!("Loaded persistent cache successfully");
             } else {
                 tracing::debug!("No persistent cache found or cache expired");
@@ -457,28 +584,25 @@ impl CacheManager {
         Ok(())
     }
 
-    /// Save cache data to disk
+    /// Save the feeds changed since the last save to disk, with the cached
+    /// articles they list, and empty the journal; other feeds' files are
+    /// left as they are
     pub fn save_to_disk(&self) -> Result<()> {
         if let Some(ref persistent_cache) = self.persistent_cache {
-            // Get current cache contents
-            let feeds = {
-                let feeds = self.feeds.feeds.read();
-                feeds.clone()
-            };
-
-            let articles = {
-                let articles = self.articles.cache.read();
-                // Convert LruCache to HashMap for persistence
-                let mut article_map = HashMap::new();
-                for (k, v) in articles.iter() {
-                    article_map.insert(k.clone(), v.clone());
-                }
-                article_map
-            };
+            let _flushing = self.flush_lock.lock();
+            let changed = self.feeds.take_changed();
+            if changed.is_empty() {
+                tracing::debug!("No cached feeds changed since the last save");
+                return persistent_cache.clear_journal();
+            }
 
-            tracing::info!("Saving cache to disk: {} feeds, {} articles", 
-                         feeds.len(), articles.len());
-            persistent_cache.save(&feeds, &articles)?;
+            let (feeds, articles, validators) = self.snapshot(&changed);
+            tracing::info!("Saving cache to disk: {} changed feeds", changed.len());
+            if let Err(e) = persistent_cache.save_changed(&feeds, &articles, &validators, &changed) {
+                self.feeds.mark_changed(changed);
+                return Err(e);
+            }
+            persistent_cache.clear_journal()?;
             tracing::info!("Cache saved successfully to: {}", 
                          persistent_cache.cache_path().display());
         } else {
@@ -487,15 +611,108 @@ impl CacheManager {
         Ok(())
     }
 
-    /// Enable automatic cache persistence
+    /// `save_to_disk` on a blocking thread, so the runtime never waits on
+    /// the disk
+    pub async fn save_to_disk_async(&self) -> Result<()> {
+        self.run_blocking(Self::save_to_disk).await
+    }
+
+    async fn run_blocking(&self, task: fn(&Self) -> Result<()>) -> Result<()> {
+        let manager = self.clone();
+        tokio::task::spawn_blocking(move || task(&manager)).await
+            .map_err(|e| Error::Storage(format!("Cache save task failed: {}", e)))?
+    }
+
+    /// Append the feeds changed since the last flush to the journal, in
+    /// one write. Once the journal has grown large the feeds' files are
+    /// saved instead.
+    pub fn flush_journal(&self) -> Result<()> {
+        let Some(ref persistent_cache) = self.persistent_cache else {
+            return Ok(());
+        };
+        let journal_bytes = {
+            let _flushing = self.flush_lock.lock();
+            let unjournaled = self.feeds.take_unjournaled();
+            if unjournaled.is_empty() {
+                return Ok(());
+            }
+            let (feeds, articles, validators) = self.snapshot(&unjournaled);
+            match persistent_cache.append_journal(&feeds, &articles, &validators, &unjournaled) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    self.feeds.mark_unjournaled(unjournaled);
+                    return Err(e);
+                }
+            }
+        };
+        if journal_bytes > JOURNAL_CHECKPOINT_BYTES {
+            self.save_to_disk()?;
+        }
+        Ok(())
+    }
+
+    /// Current entries of the named feeds, with the cached articles they
+    /// list and their validators
+    fn snapshot(&self, names: &HashSet<String>) -> Snapshot {
+        let feeds: HashMap<String, CacheEntry<Feed>> = {
+            let feeds = self.feeds.feeds.read();
+            names.iter()
+                .filter_map(|name| feeds.get(name).map(|entry| (name.clone(), entry.clone())))
+                .collect()
+        };
+
+        let articles = {
+            let articles = self.articles.cache.read();
+            feeds.values()
+                .flat_map(|entry| &entry.data.articles)
+                .filter_map(|article| articles.peek(&article.id).map(|cached| (article.id.clone(), cached.clone())))
+                .collect()
+        };
+
+        let validators = {
+            let validators = self.validators.read();
+            feeds.keys()
+                .filter_map(|name| validators.get(name).map(|v| (name.clone(), v.clone())))
+                .collect()
+        };
+        (feeds, articles, validators)
+    }
+
+    /// HTTP validators of the fetch behind a feed's cached entry
+    pub fn validators(&self, feed_name: &str) -> CacheValidators {
+        self.validators.read().get(feed_name).cloned().unwrap_or_default()
+    }
+
+    /// Remember the validators of a feed's latest fetch, or forget them for
+    /// `None`, and save them with the feed's entry
+    pub fn set_validators(&self, feed_name: &str, validators: Option<CacheValidators>) {
+        let changed = {
+            let mut all = self.validators.write();
+            match validators {
+                Some(validators) => all.insert(feed_name.to_string(), validators.clone()) != Some(validators),
+                None => all.remove(feed_name).is_some(),
+            }
+        };
+        if changed {
+            self.feeds.touch(feed_name);
+        }
+    }
+
+    /// Enable automatic cache persistence: changed feeds are journaled
+    /// every few seconds and saved to their files every five minutes, on
+    /// blocking threads so the disk doesn't hold up other tasks
     pub fn enable_auto_save(&self) {
         if self.persistent_cache.is_some() {
             let manager = self.clone();
             tokio::spawn(async move {
-                let mut interval = tokio::time::interval(Duration::from_secs(300)); // Save every 5 minutes
+                let mut flush = tokio::time::interval(JOURNAL_FLUSH_INTERVAL);
+                let mut save = tokio::time::interval(SAVE_INTERVAL);
                 loop {
-                    interval.tick().await;
-                    if let Err(e) = manager.save_to_disk() {
+                    let task: fn(&Self) -> Result<()> = tokio::select! {
+                        _ = flush.tick() => Self::flush_journal,
+                        _ = save.tick() => Self::save_to_disk,
+                    };
+                    if let Err(e) = manager.run_blocking(task).await {
                         tracing::warn!("Failed to auto-save cache: {}", e);
                     }
                 }
@@ -503,6 +720,11 @@ impl CacheManager {
         }
     }
 
+    /// Clock used for expiry decisions
+    pub fn clock(&self) -> SharedClock {
+        Arc::clone(&self.clock)
+    }
+
     /// Cleanup expired entries in both caches
     pub fn cleanup_expired(&self) -> (usize, usize) {
         let article_expired = self.articles.cleanup_expired();
@@ -537,6 +759,7 @@ impl Default for CacheManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
     use crate::feed::{ParsedArticle, FeedStatus};
     use chrono::Utc;
 
@@ -550,6 +773,7 @@ mod tests {
             published: Some(Utc::now()),
             guid: Some(id.to_string()),
             categories: vec!["test".to_string()],
+            enclosures: Vec::new(),
         };
         Arc::new(Article::new(parsed, "test-feed"))
     }
@@ -614,18 +838,20 @@ mod tests {
     #[test]
     fn test_cache_expiration() {
         let config = CacheConfig {
-            default_ttl: Duration::from_millis(10), // Very short TTL for testing
+            default_ttl: Duration::from_secs(60),
             ..Default::default()
         };
-        let cache = ArticleCache::new(config);
+        let clock = MockClock::default();
+        let cache = ArticleCache::with_clock(config, clock.shared());
         
         cache.put("test".to_string(), create_test_article("test")).unwrap();
         
-        // Should be available immediately
+        // Should be available immediately and right up to the deadline
+        assert!(cache.get("test").is_some());
+        clock.advance(Duration::from_secs(60));
         assert!(cache.get("test").is_some());
         
-        // Wait for expiration
-        std::thread::sleep(Duration::from_millis(20));
+        clock.advance(Duration::from_secs(1));
         
         // Should be expired now
         assert!(cache.get("test").is_none());
@@ -664,6 +890,116 @@ mod tests {
         assert_eq!(feed_expired, 0);
     }
 
+    #[test]
+    fn test_save_to_disk_writes_changed_feeds() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let persistent_config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let manager = CacheManager::with_persistence(CacheConfig::default(), persistent_config.clone()).unwrap();
+        let feed_files = || std::fs::read_dir(temp_dir.path().join("feeds")).unwrap().count();
+
+        manager.feeds.put("a".to_string(), create_test_feed("a")).unwrap();
+        manager.feeds.put("b".to_string(), create_test_feed("b")).unwrap();
+        manager.save_to_disk().unwrap();
+        assert_eq!(feed_files(), 2);
+        assert!(manager.feeds.take_changed().is_empty());
+
+        // Only the removed feed's file is touched
+        manager.feeds.remove("b");
+        manager.feeds.remove("missing");
+        assert_eq!(manager.feeds.take_changed(), HashSet::from(["b".to_string()]));
+        manager.feeds.mark_changed(["b".to_string()]);
+        manager.save_to_disk().unwrap();
+        assert_eq!(feed_files(), 1);
+
+        // A new manager loads without counting anything as changed
+        let reloaded = CacheManager::with_persistence(CacheConfig::default(), persistent_config).unwrap();
+        assert!(reloaded.feeds.get("a").is_some());
+        assert!(reloaded.feeds.take_changed().is_empty());
+    }
+
+    #[test]
+    fn test_journal_flush() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let persistent_config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let manager = CacheManager::with_persistence(CacheConfig::default(), persistent_config.clone()).unwrap();
+        let journal = temp_dir.path().join("journal.log");
+
+        // Flushing journals the changed feeds without writing their files
+        manager.feeds.put("a".to_string(), create_test_feed("a")).unwrap();
+        manager.flush_journal().unwrap();
+        assert!(journal.exists());
+        assert_eq!(std::fs::read_dir(temp_dir.path().join("feeds")).unwrap().count(), 0);
+        assert!(manager.feeds.take_unjournaled().is_empty());
+
+        // Without a save, e.g. after a crash, the journal is replayed and
+        // the replayed feeds saved
+        let reloaded = CacheManager::with_persistence(CacheConfig::default(), persistent_config.clone()).unwrap();
+        assert!(reloaded.feeds.get("a").is_some());
+        assert!(!journal.exists());
+        assert_eq!(std::fs::read_dir(temp_dir.path().join("feeds")).unwrap().count(), 1);
+
+        // A save empties the journal
+        manager.feeds.remove("a");
+        manager.flush_journal().unwrap();
+        manager.save_to_disk().unwrap();
+        assert!(!journal.exists());
+        let reloaded = CacheManager::with_persistence(CacheConfig::default(), persistent_config).unwrap();
+        assert!(reloaded.feeds.get("a").is_none());
+    }
+
+    #[test]
+    fn test_validators_persist() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let persistent_config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let manager = CacheManager::with_persistence(CacheConfig::default(), persistent_config.clone()).unwrap();
+        let validators = CacheValidators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+        };
+
+        manager.feeds.put("a".to_string(), create_test_feed("a")).unwrap();
+        manager.save_to_disk().unwrap();
+        // New validators alone are enough to rewrite the feed's file
+        manager.set_validators("a", Some(validators.clone()));
+        manager.set_validators("orphan", Some(validators.clone()));
+        manager.save_to_disk().unwrap();
+        let reloaded = CacheManager::with_persistence(CacheConfig::default(), persistent_config.clone()).unwrap();
+        assert_eq!(reloaded.validators("a"), validators);
+        assert_eq!(reloaded.validators("orphan"), CacheValidators::default());
+
+        // They are replayed from the journal too, and dropped with the feed
+        manager.set_validators("a", None);
+        manager.flush_journal().unwrap();
+        let reloaded = CacheManager::with_persistence(CacheConfig::default(), persistent_config).unwrap();
+        assert!(reloaded.feeds.get("a").is_some());
+        assert_eq!(reloaded.validators("a"), CacheValidators::default());
+    }
+
+    #[tokio::test]
+    async fn test_save_to_disk_async() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let persistent_config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let manager = CacheManager::with_persistence(CacheConfig::default(), persistent_config.clone()).unwrap();
+
+        manager.feeds.put("a".to_string(), create_test_feed("a")).unwrap();
+        manager.save_to_disk_async().await.unwrap();
+        assert!(manager.feeds.take_changed().is_empty());
+        let reloaded = CacheManager::with_persistence(CacheConfig::default(), persistent_config).unwrap();
+        assert!(reloaded.feeds.get("a").is_some());
+    }
+
     #[test]
     fn test_cache_entry_access_tracking() {
         let article = create_test_article("test");
@@ -695,17 +1031,17 @@ mod tests {
     #[test]
     fn test_cache_cleanup_expired() {
         let config = CacheConfig {
-            default_ttl: Duration::from_millis(10),
+            default_ttl: Duration::from_secs(60),
             ..Default::default()
         };
-        let cache = ArticleCache::new(config);
+        let clock = MockClock::default();
+        let cache = ArticleCache::with_clock(config, clock.shared());
         
         // Add some articles
         cache.put("article1".to_string(), create_test_article("1")).unwrap();
         cache.put("article2".to_string(), create_test_article("2")).unwrap();
         
-        // Wait for expiration
-        std::thread::sleep(Duration::from_millis(20));
+        clock.advance(Duration::from_secs(120));
         
         // Add a fresh article
         cache.put("article3".to_string(), create_test_article("3")).unwrap();
@@ -716,4 +1052,24 @@ mod tests {
         assert_eq!(cache.len(), 1);
         assert!(cache.get("article3").is_some());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cache_manager_shares_clock() {
+        let config = CacheConfig {
+            default_ttl: Duration::from_secs(3600),
+            ..Default::default()
+        };
+        let clock = MockClock::default();
+        let manager = CacheManager::with_clock(config, clock.shared());
+
+        manager.articles.put("a".to_string(), create_test_article("a")).unwrap();
+        manager.feeds.put("f".to_string(), create_test_feed("f")).unwrap();
+
+        clock.advance(Duration::from_secs(3599));
+        assert_eq!(manager.cleanup_expired(), (0, 0));
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(manager.cleanup_expired(), (1, 1));
+        assert!(manager.feeds.get("f").is_none());
+    }
+}
@@ -0,0 +1,240 @@
+//! Article bodies kept on disk instead of in memory while mounted. Bodies
+//! over a size threshold are written to a file named after their hash and
+//! the article keeps only the hash (`content_ref`, `description_ref`);
+//! readers get the body back with [`ContentStore::restore`].
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use parking_lot::{RwLock, RwLockReadGuard};
+use tracing::{debug, warn};
+
+use crate::error::{Error, Result};
+use crate::feed::Article;
+
+/// Directory of article bodies, one file per distinct body
+#[derive(Debug)]
+pub struct ContentStore {
+    dir: PathBuf,
+    threshold: usize,
+    prune_lock: RwLock<()>, // held shared from spilling until the article is reachable
+}
+
+impl ContentStore {
+    /// Keep bodies longer than `threshold` bytes in `dir`, which is removed
+    /// again when the store is dropped
+    pub fn open<P: AsRef<Path>>(dir: P, threshold: usize) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| Error::Storage(format!(
+            "Failed to create content directory '{}': {}", dir.display(), e
+        )))?;
+        Ok(Self { dir, threshold, prune_lock: RwLock::new(()) })
+    }
+
+    /// Store for this process under `parent`, as `content-<pid>`. Stores
+    /// left behind by processes that are gone (e.g. after a crash) are
+    /// removed.
+    pub fn for_process<P: AsRef<Path>>(parent: P, threshold: usize) -> Result<Self> {
+        let parent = parent.as_ref();
+        if let Ok(entries) = fs::read_dir(parent) {
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let Some(pid) = name.to_str().and_then(|name| name.strip_prefix("content-")) else {
+                    continue;
+                };
+                if pid.parse::<u32>().is_ok() && !process_exists(pid) {
+                    debug!("Removing content directory of exited process {}", pid);
+                    let _ = fs::remove_dir_all(entry.path());
+                }
+            }
+        }
+        Self::open(parent.join(format!("content-{}", std::process::id())), threshold)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Move the article's content and description to disk if they are over
+    /// the threshold. Returns whether anything moved.
+    pub fn spill(&self, article: &mut Article) -> Result<bool> {
+        let mut spilled = false;
+        if article.content.as_ref().is_some_and(|body| body.len() > self.threshold) {
+            article.content_ref = Some(self.put(article.content.as_deref().unwrap_or_default())?);
+            article.content = None;
+            spilled = true;
+        }
+        if article.description.as_ref().is_some_and(|body| body.len() > self.threshold) {
+            article.description_ref = Some(self.put(article.description.as_deref().unwrap_or_default())?);
+            article.description = None;
+            spilled = true;
+        }
+        Ok(spilled)
+    }
+
+    /// The article with its spilled bodies read back. A body that cannot be
+    /// read is left out, as if the feed had not sent one.
+    pub fn restore<'a>(&self, article: &'a Article) -> Cow<'a, Article> {
+        if article.content_ref.is_none() && article.description_ref.is_none() {
+            return Cow::Borrowed(article);
+        }
+        let mut restored = article.clone();
+        if let Some(hash) = restored.content_ref.take() {
+            restored.content = self.get(&hash, &article.id);
+        }
+        if let Some(hash) = restored.description_ref.take() {
+            restored.description = self.get(&hash, &article.id);
+        }
+        Cow::Owned(restored)
+    }
+
+    /// Hold while spilled articles are on their way to where `prune` looks
+    /// for them, so their bodies are not removed in between
+    pub fn spill_guard(&self) -> RwLockReadGuard<'_, ()> {
+        self.prune_lock.read()
+    }
+
+    /// Remove every body whose hash is not in the set `live` returns.
+    /// Returns how many were removed.
+    pub fn prune(&self, live: impl FnOnce() -> HashSet<String>) -> usize {
+        let _lock = self.prune_lock.write();
+        let live = live();
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return 0;
+        };
+        entries.flatten()
+            .filter(|entry| entry.file_name().to_str().is_some_and(|name| is_hash(name) && !live.contains(name)))
+            .filter(|entry| fs::remove_file(entry.path()).is_ok())
+            .count()
+    }
+
+    /// Write a body (unless an identical one is already stored) and return
+    /// its hash
+    fn put(&self, body: &str) -> Result<String> {
+        let hash = blake3::hash(body.as_bytes()).to_hex().to_string();
+        let path = self.dir.join(&hash);
+        if path.exists() {
+            return Ok(hash);
+        }
+
+        let storage_error = |e: std::io::Error| Error::Storage(format!(
+            "Failed to write article body '{}': {}", path.display(), e
+        ));
+        let mut temp_file = tempfile::Builder::new()
+            .prefix("body.")
+            .suffix(".tmp")
+            .tempfile_in(&self.dir)
+            .map_err(storage_error)?;
+        temp_file.write_all(body.as_bytes()).map_err(storage_error)?;
+        temp_file.persist(&path).map_err(|e| storage_error(e.error))?;
+        Ok(hash)
+    }
+
+    fn get(&self, hash: &str, article_id: &str) -> Option<String> {
+        match fs::read_to_string(self.dir.join(hash)) {
+            Ok(body) => Some(body),
+            Err(e) => {
+                warn!("Failed to read stored body {} of {}: {}", hash, article_id, e);
+                None
+            }
+        }
+    }
+}
+
+impl Drop for ContentStore {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            debug!("Failed to remove content directory {}: {}", self.dir.display(), e);
+        }
+    }
+}
+
+fn is_hash(name: &str) -> bool {
+    name.len() == 64 && name.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Whether a process is running; assumed so where `/proc` is unavailable
+fn process_exists(pid: &str) -> bool {
+    let proc = Path::new("/proc");
+    !proc.is_dir() || proc.join(pid).exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+    use crate::feed::test_article;
+
+    fn article(content: &str, description: &str) -> Article {
+        let mut article = test_article("long", "Long read", "test-feed");
+        article.description = Some(description.to_string());
+        article.content = Some(content.to_string());
+        article.published = Some(Utc::now());
+        article
+    }
+
+    #[test]
+    fn test_spill_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = ContentStore::open(temp_dir.path().join("content"), 16).unwrap();
+
+        let body = "<p>A body well over sixteen bytes</p>";
+        let mut spilled = article(body, "Short");
+        assert!(store.spill(&mut spilled).unwrap());
+        assert_eq!(spilled.content, None);
+        assert_eq!(spilled.description.as_deref(), Some("Short"));
+        assert!(spilled.description_ref.is_none());
+        let hash = spilled.content_ref.clone().unwrap();
+        assert!(store.path().join(&hash).exists());
+
+        let restored = store.restore(&spilled);
+        assert_eq!(restored.content.as_deref(), Some(body));
+        assert!(restored.content_ref.is_none());
+
+        // Short bodies stay in memory and need no copy to read
+        let mut short = article("Short", "Short");
+        assert!(!store.spill(&mut short).unwrap());
+        assert!(matches!(store.restore(&short), Cow::Borrowed(_)));
+
+        // Identical bodies share one file
+        let mut again = article(body, "Short");
+        store.spill(&mut again).unwrap();
+        assert_eq!(again.content_ref.as_deref(), Some(hash.as_str()));
+    }
+
+    #[test]
+    fn test_prune_and_drop() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("content");
+        let store = ContentStore::open(&dir, 4).unwrap();
+
+        let mut kept = article("kept body", "kept description");
+        let mut dropped = article("dropped body", "");
+        store.spill(&mut kept).unwrap();
+        store.spill(&mut dropped).unwrap();
+
+        let live: HashSet<String> = kept.content_ref.iter().chain(&kept.description_ref).cloned().collect();
+        assert_eq!(store.prune(|| live), 1);
+        assert_eq!(store.restore(&kept).content.as_deref(), Some("kept body"));
+        assert_eq!(store.restore(&dropped).content, None);
+
+        drop(store);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_stale_process_directories_removed() {
+        let temp_dir = TempDir::new().unwrap();
+        // Pids are below 2^22 on Linux
+        let stale = temp_dir.path().join("content-4294967295");
+        fs::create_dir_all(&stale).unwrap();
+
+        let store = ContentStore::for_process(temp_dir.path(), 1024).unwrap();
+        assert!(!stale.exists() || !Path::new("/proc").is_dir());
+        assert!(store.path().ends_with(format!("content-{}", std::process::id())));
+    }
+}
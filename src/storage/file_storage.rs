@@ -0,0 +1,452 @@
+//! Flat-file archive: feeds and articles as plain files in a directory, so
+//! they outlive the process without a database and the archive can be
+//! grepped or tracked in git independently of the mount.
+//!
+//! ```text
+//! <dir>/feeds/<hash of feed name>.json         feed metadata and article IDs
+//! <dir>/articles/<ab>/<hash of article ID>.md   the article as Markdown
+//! <dir>/articles/<ab>/<hash of article ID>.json the article's record
+//! ```
+//!
+//! `<ab>` is the first two characters of the hash. The JSON record is what
+//! is loaded back; the Markdown file is for reading. Every file is written
+//! to a temporary file and renamed into place, articles before the feed
+//! that lists them, so a crash leaves the previous or the new state.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::content::ContentExtractor;
+use crate::error::{Error, Result};
+use crate::feed::{Article, Feed, FeedStatus};
+use crate::storage::traits::{CleanupStats, HealthStatus, MemoryStorage, Storage, StorageConfig, StorageStats};
+
+const FEEDS_DIR: &str = "feeds";
+const ARTICLES_DIR: &str = "articles";
+
+/// A feed's file: the feed without its articles, which it lists by ID
+#[derive(Debug, Serialize, Deserialize)]
+struct FeedRecord {
+    name: String,
+    url: String,
+    title: Option<String>,
+    description: Option<String>,
+    last_updated: Option<DateTime<Utc>>,
+    status: FeedStatus,
+    articles: Vec<String>,
+}
+
+impl FeedRecord {
+    fn new(feed: &Feed) -> Self {
+        Self {
+            name: feed.name.clone(),
+            url: feed.url.clone(),
+            title: feed.title.clone(),
+            description: feed.description.clone(),
+            last_updated: feed.last_updated,
+            status: feed.status.clone(),
+            articles: feed.articles.iter().map(|article| article.id.clone()).collect(),
+        }
+    }
+
+    fn into_feed(self, articles: Vec<Article>) -> Feed {
+        Feed {
+            name: self.name,
+            url: self.url,
+            title: self.title,
+            description: self.description,
+            last_updated: self.last_updated,
+            articles,
+            status: self.status,
+        }
+    }
+}
+
+fn hash(key: &str) -> String {
+    blake3::hash(key.as_bytes()).to_hex().to_string()
+}
+
+/// Storage keeping feeds in memory and writing each change through to
+/// the archive in `dir`
+pub struct FileStorage {
+    archive: Arc<Archive>,
+    memory: MemoryStorage,
+}
+
+/// The archive's files, shared with the blocking threads writing them
+struct Archive {
+    dir: PathBuf,
+    extractor: ContentExtractor,
+}
+
+impl FileStorage {
+    /// Open the archive in `dir`, creating it if needed, and load the
+    /// feeds stored there. Files that cannot be read are skipped with a
+    /// warning.
+    pub fn open<P: AsRef<Path>>(dir: P, config: StorageConfig) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        for sub in [FEEDS_DIR, ARTICLES_DIR] {
+            fs::create_dir_all(dir.join(sub)).map_err(|e| Error::Storage(format!(
+                "Failed to create storage directory '{}': {}", dir.join(sub).display(), e
+            )))?;
+        }
+        let archive = Archive { dir, extractor: ContentExtractor::new()? };
+        let storage = Self { archive: Arc::new(archive), memory: MemoryStorage::new(config) };
+
+        for path in storage.archive.json_files(&storage.archive.dir.join(FEEDS_DIR))? {
+            let record = fs::read_to_string(&path).map_err(|e| e.to_string())
+                .and_then(|content| serde_json::from_str::<FeedRecord>(&content).map_err(|e| e.to_string()));
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    tracing::warn!("Skipping stored feed '{}': {}", path.display(), e);
+                    continue;
+                }
+            };
+            let articles = record.articles.iter()
+                .filter_map(|id| storage.archive.read_article(id))
+                .collect();
+            futures::executor::block_on(storage.memory.store_feed(&record.into_feed(articles)))?;
+        }
+        storage.migrate_feed_files()?;
+
+        tracing::debug!("Opened file storage at {}: {} feeds", storage.dir().display(), storage.memory.feeds_count());
+        Ok(storage)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.archive.dir
+    }
+
+    /// Move whole feeds stored directly in `dir`, as earlier releases did,
+    /// to the archive layout
+    fn migrate_feed_files(&self) -> Result<()> {
+        for path in self.archive.json_files(&self.archive.dir)? {
+            let Ok(feed) = fs::read_to_string(&path).map_err(|e| e.to_string())
+                .and_then(|content| serde_json::from_str::<Feed>(&content).map_err(|e| e.to_string())) else {
+                continue;
+            };
+            if futures::executor::block_on(self.memory.get_feed(&feed.name))?.is_none() {
+                self.archive.write_feed(&feed, None)?;
+                futures::executor::block_on(self.memory.store_feed(&feed))?;
+            }
+            let _ = fs::remove_file(&path);
+            tracing::info!("Moved stored feed '{}' to the archive layout", feed.name);
+        }
+        Ok(())
+    }
+
+    /// Run `task` against the archive on a blocking thread, so the runtime
+    /// never waits on the disk, or on this thread outside a runtime
+    async fn on_archive<T, F>(&self, task: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Archive) -> Result<T> + Send + 'static,
+    {
+        let archive = Arc::clone(&self.archive);
+        if tokio::runtime::Handle::try_current().is_err() {
+            return task(&archive);
+        }
+        tokio::task::spawn_blocking(move || task(&archive)).await
+            .map_err(|e| Error::Storage(format!("Storage task failed: {}", e)))?
+    }
+
+    /// Write `feed` over `previous`, as `Archive::write_feed` does, and
+    /// return it
+    async fn write_feed(&self, feed: Feed, previous: Option<Feed>) -> Result<Feed> {
+        self.on_archive(move |archive| archive.write_feed(&feed, previous.as_ref()).map(|()| feed)).await
+    }
+
+    /// The stored feed holding `article_id`, if any
+    async fn feed_of(&self, article_id: &str) -> Result<Option<Feed>> {
+        for name in self.memory.list_feeds().await? {
+            if let Some(feed) = self.memory.get_feed(&name).await? {
+                if feed.articles.iter().any(|article| article.id == article_id) {
+                    return Ok(Some(feed));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Archive {
+    fn json_files(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let entries = fs::read_dir(dir).map_err(|e| Error::Storage(format!(
+            "Failed to read storage directory '{}': {}", dir.display(), e
+        )))?;
+        Ok(entries.flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+            .collect())
+    }
+
+    fn feed_path(&self, name: &str) -> PathBuf {
+        self.dir.join(FEEDS_DIR).join(format!("{}.json", hash(name)))
+    }
+
+    /// Path of an article's files, without extension
+    fn article_path(&self, id: &str) -> PathBuf {
+        let hash = hash(id);
+        self.dir.join(ARTICLES_DIR).join(&hash[..2]).join(hash)
+    }
+
+    fn read_article(&self, id: &str) -> Option<Article> {
+        let path = self.article_path(id).with_extension("json");
+        let article = fs::read_to_string(&path).map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<Article>(&content).map_err(|e| e.to_string()));
+        article.map_err(|e| tracing::warn!("Skipping stored article '{}': {}", path.display(), e)).ok()
+    }
+
+    /// Write `contents` to a temporary file and rename it over `path`
+    fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+        let storage_error = |e: std::io::Error| Error::Storage(format!(
+            "Failed to write '{}': {}", path.display(), e
+        ));
+        let dir = path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(dir).map_err(storage_error)?;
+        let mut temp_file = tempfile::Builder::new()
+            .prefix(".write.")
+            .suffix(".tmp")
+            .tempfile_in(dir)
+            .map_err(storage_error)?;
+        temp_file.write_all(contents).map_err(storage_error)?;
+        temp_file.as_file().sync_all().map_err(storage_error)?;
+        temp_file.persist(path).map_err(|e| storage_error(e.error))?;
+        Ok(())
+    }
+
+    fn write_article(&self, feed_name: &str, article: &Article) -> Result<()> {
+        let path = self.article_path(&article.id);
+        let markdown = self.extractor.extract_article(article, feed_name)
+            .unwrap_or_else(|_| article.to_text());
+        Self::write_file(&path.with_extension("md"), markdown.as_bytes())?;
+        let record = serde_json::to_vec_pretty(article).map_err(Error::Serialization)?;
+        Self::write_file(&path.with_extension("json"), &record)
+    }
+
+    fn remove_article_files(&self, id: &str) {
+        let path = self.article_path(id);
+        for ext in ["md", "json"] {
+            let _ = fs::remove_file(path.with_extension(ext));
+        }
+    }
+
+    /// Write `feed`'s changed articles and then its record, and remove the
+    /// files of articles `previous` had that `feed` no longer lists
+    fn write_feed(&self, feed: &Feed, previous: Option<&Feed>) -> Result<()> {
+        let unchanged = |article: &Article| previous
+            .and_then(|previous| previous.articles.iter().find(|stored| stored.id == article.id))
+            .is_some_and(|stored| serde_json::to_value(stored).ok() == serde_json::to_value(article).ok());
+        for article in feed.articles.iter().filter(|article| !unchanged(article)) {
+            self.write_article(&feed.name, article)?;
+        }
+
+        let record = serde_json::to_vec_pretty(&FeedRecord::new(feed)).map_err(Error::Serialization)?;
+        Self::write_file(&self.feed_path(&feed.name), &record)?;
+
+        for article in previous.map_or(&[][..], |previous| previous.articles.as_slice()) {
+            if !feed.articles.iter().any(|kept| kept.id == article.id) {
+                self.remove_article_files(&article.id);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Storage for FileStorage {
+    async fn store_feed(&self, feed: &Feed) -> Result<()> {
+        let previous = self.memory.get_feed(&feed.name).await?;
+        self.write_feed(feed.clone(), previous).await?;
+        self.memory.store_feed(feed).await
+    }
+
+    async fn get_feed(&self, name: &str) -> Result<Option<Feed>> {
+        self.memory.get_feed(name).await
+    }
+
+    /// Articles of a feed that is not stored are only kept in memory
+    async fn store_article(&self, feed_name: &str, article: &Article) -> Result<()> {
+        self.store_articles(feed_name, std::slice::from_ref(article)).await
+    }
+
+    /// The feed's record is rewritten once for all the articles
+    async fn store_articles(&self, feed_name: &str, articles: &[Article]) -> Result<()> {
+        if let Some(previous) = self.memory.get_feed(feed_name).await? {
+            let mut feed = previous.clone();
+            for article in articles {
+                match feed.articles.iter_mut().find(|stored| stored.id == article.id) {
+                    Some(stored) => *stored = article.clone(),
+                    None => feed.articles.push(article.clone()),
+                }
+            }
+            let feed = self.write_feed(feed, Some(previous)).await?;
+            self.memory.store_feed(&feed).await?;
+        }
+        for article in articles {
+            self.memory.store_article(feed_name, article).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_article(&self, article_id: &str) -> Result<Option<Arc<Article>>> {
+        self.memory.get_article(article_id).await
+    }
+
+    async fn list_feeds(&self) -> Result<Vec<String>> {
+        self.memory.list_feeds().await
+    }
+
+    async fn list_articles(&self, feed_name: &str) -> Result<Vec<String>> {
+        self.memory.list_articles(feed_name).await
+    }
+
+    async fn remove_feed(&self, name: &str) -> Result<()> {
+        let feed = self.memory.get_feed(name).await?;
+        let path = self.archive.feed_path(name);
+        self.on_archive(move |archive| {
+            match fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(Error::Storage(format!("Failed to remove '{}': {}", path.display(), e)));
+                }
+                _ => {}
+            }
+            for article in feed.iter().flat_map(|feed| &feed.articles) {
+                archive.remove_article_files(&article.id);
+            }
+            Ok(())
+        }).await?;
+        self.memory.remove_feed(name).await
+    }
+
+    async fn remove_article(&self, article_id: &str) -> Result<()> {
+        if let Some(previous) = self.feed_of(article_id).await? {
+            let mut feed = previous.clone();
+            feed.articles.retain(|article| article.id != article_id);
+            let feed = self.write_feed(feed, Some(previous)).await?;
+            self.memory.store_feed(&feed).await?;
+        }
+        self.memory.remove_article(article_id).await
+    }
+
+    async fn get_stats(&self) -> Result<StorageStats> {
+        let mut stats = self.memory.get_stats().await?;
+        stats.storage_size_bytes = self.on_archive(|archive| Ok(walkdir::WalkDir::new(&archive.dir).into_iter()
+            .flatten()
+            .filter_map(|entry| entry.metadata().ok())
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len())
+            .sum())).await?;
+        stats.health_status = self.health_check().await?;
+        Ok(stats)
+    }
+
+    async fn cleanup(&self) -> Result<CleanupStats> {
+        let mut before = Vec::new();
+        for name in self.memory.list_feeds().await? {
+            before.extend(self.memory.get_feed(&name).await?);
+        }
+        let stats = self.memory.cleanup().await?;
+        if stats.articles_removed > 0 {
+            for previous in &before {
+                if let Some(feed) = self.memory.get_feed(&previous.name).await? {
+                    if feed.articles.len() != previous.articles.len() {
+                        self.write_feed(feed, Some(previous.clone())).await?;
+                    }
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    async fn health_check(&self) -> Result<HealthStatus> {
+        Ok(match fs::metadata(self.dir()) {
+            Ok(metadata) if metadata.is_dir() && !metadata.permissions().readonly() => HealthStatus::Healthy,
+            Ok(_) => HealthStatus::Critical(format!("'{}' is not a writable directory", self.dir().display())),
+            Err(e) => HealthStatus::Unavailable(format!("'{}': {}", self.dir().display(), e)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::test_article;
+    use tempfile::TempDir;
+
+    fn feed(name: &str, ids: &[&str]) -> Feed {
+        let articles = ids.iter().map(|id| {
+            let mut article = test_article(id, &format!("Article {}", id), name);
+            article.content = Some(format!("<p>Body of article {}</p>", id));
+            article
+        }).collect();
+        Feed {
+            name: name.to_string(),
+            url: format!("https://example.com/{}.xml", name),
+            title: None,
+            description: None,
+            last_updated: None,
+            articles,
+            status: FeedStatus::Active,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_feeds_survive_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::open(temp_dir.path(), StorageConfig::unlimited()).unwrap();
+        let news = feed("news", &["1", "2"]);
+        storage.store_feed(&news).await.unwrap();
+        storage.store_feed(&feed("blog", &["3"])).await.unwrap();
+        storage.remove_article(&news.articles[0].id).await.unwrap();
+        storage.remove_feed("blog").await.unwrap();
+
+        let reopened = FileStorage::open(temp_dir.path(), StorageConfig::unlimited()).unwrap();
+        assert_eq!(reopened.list_feeds().await.unwrap(), vec!["news".to_string()]);
+        let stored = reopened.get_feed("news").await.unwrap().unwrap();
+        assert_eq!(stored.articles.len(), 1);
+        assert_eq!(stored.articles[0].id, news.articles[1].id);
+        assert!(reopened.get_article(&news.articles[1].id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_articles_are_greppable_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::open(temp_dir.path(), StorageConfig::unlimited()).unwrap();
+        let news = feed("news", &["1", "2"]);
+        storage.store_feed(&news).await.unwrap();
+
+        let path = storage.archive.article_path(&news.articles[0].id);
+        assert!(path.starts_with(temp_dir.path().join(ARTICLES_DIR)));
+        assert!(fs::read_to_string(path.with_extension("md")).unwrap().contains("Body of article 1"));
+        let record: Article = serde_json::from_str(&fs::read_to_string(path.with_extension("json")).unwrap()).unwrap();
+        assert_eq!(record.id, news.articles[0].id);
+
+        // Articles dropped from the feed lose their files
+        storage.store_feed(&feed("news", &["2"])).await.unwrap();
+        assert!(!path.with_extension("md").exists());
+        assert!(!path.with_extension("json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_skips_unreadable_files_and_migrates_feed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join(FEEDS_DIR)).unwrap();
+        fs::write(temp_dir.path().join(FEEDS_DIR).join("broken.json"), "{").unwrap();
+        let old = feed("old", &["1"]);
+        let old_file = temp_dir.path().join(format!("{}.json", hash("old")));
+        fs::write(&old_file, serde_json::to_string(&old).unwrap()).unwrap();
+
+        let storage = FileStorage::open(temp_dir.path(), StorageConfig::unlimited()).unwrap();
+        assert_eq!(storage.list_feeds().await.unwrap(), vec!["old".to_string()]);
+        assert!(!old_file.exists());
+        assert!(storage.archive.feed_path("old").exists());
+    }
+}
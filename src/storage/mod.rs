@@ -2,14 +2,24 @@ pub mod cache;
 pub mod traits;
 pub mod repository;
 pub mod persistent_cache;
+pub mod read_state;
+pub mod content_store;
+pub mod state_file;
+pub mod file_storage;
+pub mod backend;
 
 pub use cache::{
     ArticleCache, FeedCache, CacheManager, CacheConfig, CacheStats, CacheEntry
 };
-pub use persistent_cache::{PersistentCache, PersistentCacheConfig};
+pub use persistent_cache::{Codec, Compression, PersistentCache, PersistentCacheConfig, PersistentCacheStats};
+pub use read_state::{ReadStateStore, StarredStore};
+pub use content_store::ContentStore;
+pub use state_file::StateFile;
+pub use file_storage::FileStorage;
+pub use backend::{StorageBackend, open_storage};
 pub use traits::{
-    Storage, Cache, FeedRepository, ArticleRepository,
+    Storage, Cache, FeedRepository, ArticleRepository, RefreshOutcome, FeedHealth,
     StorageStats, RepositoryStats, ArticleStats, ArticleQuery,
     StorageConfig, HealthStatus, CleanupStats, MemoryStorage
 };
-pub use repository::{Repository, RepositoryFactory};
\ No newline at end of file
+pub use repository::{Repository, RepositoryFactory, RepositoryMetrics};
\ No newline at end of file
@@ -1,13 +1,130 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
+use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use serde::{Deserialize, Serialize};
-use crate::feed::{Feed, Article};
+use serde_json::Value;
+use crate::clock::{system_clock, SharedClock};
+use crate::feed::{Article, Feed, IdStrategy};
+use crate::feed::fetcher::CacheValidators;
 use crate::error::{Error, Result};
 use crate::storage::cache::CacheEntry;
 
+/// Format version written by this build. Files in older formats are
+/// migrated when loaded; newer ones are set aside rather than overwritten.
+pub const CACHE_FORMAT_VERSION: u32 = 4;
+
+/// Last version keeping the whole cache in `feeds_cache.json`. Version 3
+/// writes a file per feed under `feeds/`, so a save only rewrites the feeds
+/// that changed.
+const SINGLE_FILE_VERSION: u32 = 2;
+
+/// First version with a file per feed. Version 4 compresses the JSON and
+/// keeps long article bodies under `bodies/`, once per distinct body.
+const PER_FEED_VERSION: u32 = 3;
+
+/// Start of the header line, `rss-fuse-cache v4 blake3:<hex> zstd`,
+/// preceding the body: JSON compressed with the named codec, whose
+/// checksum is given. Version 1 files are bare JSON; versions 2 and 3 have
+/// no codec.
+const HEADER_MAGIC: &str = "rss-fuse-cache";
+
+/// Length of the name hash naming each feed's file
+const FEED_FILE_HEX: usize = 16;
+
+/// Article fields whose values are stored under `bodies/` when long
+const BODY_FIELDS: [&str; 2] = ["content", "description"];
+
+/// Bodies shorter than this stay inline in the feed's file
+const BODY_MIN_BYTES: usize = 512;
+
+/// Append-only log of feed entries saved since the feed files were last
+/// written, one `<blake3 hex> <JSON record>` line each
+const JOURNAL_FILE: &str = "journal.log";
+
+/// Codec cache files are compressed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Codec {
+    #[default]
+    Zstd,
+    Lz4,
+    None,
+}
+
+impl Codec {
+    pub fn name(self) -> &'static str {
+        match self {
+            Codec::Zstd => "zstd",
+            Codec::Lz4 => "lz4",
+            Codec::None => "none",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Codec::Zstd, Codec::Lz4, Codec::None].into_iter().find(|codec| codec.name() == name)
+    }
+
+    fn compress(self, data: &[u8], level: i32) -> std::io::Result<Vec<u8>> {
+        match self {
+            Codec::Zstd => zstd::encode_all(data, level),
+            Codec::Lz4 => Ok(lz4_flex::compress_prepend_size(data)),
+            Codec::None => Ok(data.to_vec()),
+        }
+    }
+
+    fn decompress(self, data: &[u8]) -> std::result::Result<Vec<u8>, String> {
+        match self {
+            Codec::Zstd => zstd::decode_all(data).map_err(|e| e.to_string()),
+            Codec::Lz4 => lz4_flex::decompress_size_prepended(data).map_err(|e| e.to_string()),
+            Codec::None => Ok(data.to_vec()),
+        }
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Codec and level cache files are written with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compression {
+    pub codec: Codec,
+    pub level: i32,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self { codec: Codec::Zstd, level: Self::DEFAULT_ZSTD_LEVEL }
+    }
+}
+
+impl Compression {
+    pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+    /// `codec` at `level`, or at its default level. Only zstd has levels,
+    /// 1 (fastest) to 22 (smallest).
+    pub fn new(codec: Codec, level: Option<i32>) -> Result<Self> {
+        match (codec, level) {
+            (Codec::Zstd, Some(level)) if !(1..=22).contains(&level) => {
+                Err(Error::Config(format!("zstd compression level {} is not between 1 and 22", level)))
+            }
+            (Codec::Zstd, level) => Ok(Self { codec, level: level.unwrap_or(Self::DEFAULT_ZSTD_LEVEL) }),
+            (_, Some(_)) => Err(Error::Config(format!("{} compression has no levels", codec))),
+            (_, None) => Ok(Self { codec, level: 0 }),
+        }
+    }
+
+    pub fn none() -> Self {
+        Self { codec: Codec::None, level: 0 }
+    }
+}
+
 /// Serializable version of CacheEntry for disk storage
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializableCacheEntry<T> {
@@ -50,8 +167,12 @@ impl<T> From<SerializableCacheEntry<T>> for CacheEntry<T> {
 pub struct PersistentCacheData {
     pub feeds: HashMap<String, SerializableCacheEntry<Feed>>,
     pub articles: HashMap<String, SerializableCacheEntry<Article>>,
+    #[serde(default)]
+    pub validators: HashMap<String, CacheValidators>, // feed name -> HTTP validators of its last fetch
     pub cache_version: u32,
     pub saved_at: u64, // Unix timestamp
+    #[serde(skip)]
+    pub journaled: HashSet<String>, // feeds whose entry was replayed from the journal
 }
 
 impl Default for PersistentCacheData {
@@ -59,20 +180,233 @@ impl Default for PersistentCacheData {
         Self {
             feeds: HashMap::new(),
             articles: HashMap::new(),
-            cache_version: 1,
+            validators: HashMap::new(),
+            cache_version: CACHE_FORMAT_VERSION,
             saved_at: SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
                 .unwrap_or_default().as_secs(),
+            journaled: HashSet::new(),
         }
     }
 }
 
+/// One feed's file under `feeds/`: its cache entry, the cached articles
+/// it lists and the HTTP validators of the fetch that produced them
+#[derive(Debug, Serialize, Deserialize)]
+struct FeedCacheFile {
+    name: String,
+    feed: SerializableCacheEntry<Feed>,
+    articles: HashMap<String, SerializableCacheEntry<Article>>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    validators: CacheValidators,
+    saved_at: u64, // Unix timestamp
+}
+
+fn is_default(validators: &CacheValidators) -> bool {
+    *validators == CacheValidators::default()
+}
+
+/// One line of the journal: a feed's entry as its file would hold it, or
+/// `None` once the feed was removed or expired
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalRecord {
+    name: String,
+    file: Option<FeedCacheFile>,
+}
+
+/// Why a cache file's contents can't be used
+#[derive(Debug, Clone, PartialEq)]
+enum FormatError {
+    /// Truncated, failing its checksum or not parsing
+    Corrupt(String),
+    /// Written by a newer rss-fuse
+    Newer(u32),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Corrupt(reason) => write!(f, "is corrupt: {}", reason),
+            FormatError::Newer(version) => write!(
+                f, "has format version {}, newer than the supported {}", version, CACHE_FORMAT_VERSION
+            ),
+        }
+    }
+}
+
+/// Format version and JSON body of a cache file, decompressed and its
+/// checksum verified
+fn decode_header(content: &[u8]) -> std::result::Result<(u32, String), FormatError> {
+    let corrupt = FormatError::Corrupt;
+
+    match content.strip_prefix(HEADER_MAGIC.as_bytes()) {
+        Some(rest) => {
+            let newline = rest.iter().position(|&b| b == b'\n')
+                .ok_or_else(|| corrupt("no body after the header".to_string()))?;
+            let (header, body) = (&rest[..newline], &rest[newline + 1..]);
+            let header = std::str::from_utf8(header).map_err(|_| corrupt("header is not text".to_string()))?;
+            let mut fields = header.split_whitespace();
+            let version = fields.next()
+                .and_then(|v| v.strip_prefix('v'))
+                .and_then(|v| v.parse::<u32>().ok())
+                .ok_or_else(|| corrupt(format!("bad header '{}{}'", HEADER_MAGIC, header)))?;
+            if version > CACHE_FORMAT_VERSION {
+                return Err(FormatError::Newer(version));
+            }
+            let checksum = fields.next().and_then(|c| c.strip_prefix("blake3:"))
+                .ok_or_else(|| corrupt("header has no checksum".to_string()))?;
+            let codec = match fields.next() {
+                Some(name) => Codec::from_name(name).ok_or_else(|| corrupt(format!("unknown codec '{}'", name)))?,
+                None => Codec::None,
+            };
+            let body = codec.decompress(body).map_err(|e| corrupt(format!("{} body: {}", codec, e)))?;
+            let body = String::from_utf8(body).map_err(|_| corrupt("body is not UTF-8".to_string()))?;
+            if blake3::hash(body.as_bytes()).to_hex().as_str() != checksum {
+                return Err(corrupt("checksum mismatch".to_string()));
+            }
+            Ok((version, body))
+        }
+        // Version 1: bare JSON, versioned inside
+        None => {
+            let content = std::str::from_utf8(content).map_err(|_| corrupt("not UTF-8".to_string()))?;
+            let value: serde_json::Value = serde_json::from_str(content).map_err(|e| corrupt(e.to_string()))?;
+            let version = value.get("cache_version").and_then(|v| v.as_u64()).unwrap_or(1) as u32;
+            if version > 1 {
+                return Err(corrupt(format!("version {} without a header", version)));
+            }
+            Ok((version, content.to_string()))
+        }
+    }
+}
+
+/// A `feeds_cache.json` written before the cache was split per feed, with
+/// the format version it was written in
+fn decode_single_file(content: &[u8]) -> std::result::Result<(PersistentCacheData, u32), FormatError> {
+    let (version, body) = decode_header(content)?;
+    if version > SINGLE_FILE_VERSION {
+        return Err(FormatError::Corrupt(format!("version {} keeps a file per feed", version)));
+    }
+    let value: serde_json::Value = serde_json::from_str(&body).map_err(|e| FormatError::Corrupt(e.to_string()))?;
+    let data = serde_json::from_value(migrate(value, version))
+        .map_err(|e| FormatError::Corrupt(e.to_string()))?;
+    Ok((data, version))
+}
+
+/// Upgrade a `feeds_cache.json` written in format `version` to the last
+/// single-file format, one version at a time
+fn migrate(mut value: serde_json::Value, version: u32) -> serde_json::Value {
+    for from in version..SINGLE_FILE_VERSION {
+        match from {
+            // Version 2 only added the header; the JSON is unchanged
+            1 => {}
+            _ => unreachable!("no migration from cache format {}", from),
+        }
+    }
+    value["cache_version"] = SINGLE_FILE_VERSION.into();
+    value
+}
+
+/// JSON of a feed's file, with references to stored bodies in place of
+/// long `content` and `description` values
+fn decode_feed_json(content: &[u8]) -> std::result::Result<Value, FormatError> {
+    let (version, body) = decode_header(content)?;
+    if version < PER_FEED_VERSION {
+        return Err(FormatError::Corrupt(format!("version {} predates per-feed files", version)));
+    }
+    serde_json::from_str(&body).map_err(|e| FormatError::Corrupt(e.to_string()))
+}
+
+/// Call `f` on the value of every `content` and `description` field in `value`
+fn visit_bodies<E>(value: &mut Value, f: &mut impl FnMut(&mut Value) -> std::result::Result<(), E>)
+    -> std::result::Result<(), E> {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if BODY_FIELDS.contains(&key.as_str()) {
+                    f(field)?;
+                } else {
+                    visit_bodies(field, f)?;
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                visit_bodies(item, f)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Hash of the stored body a field refers to, `{"blake3": "<hex>"}`
+fn body_ref(field: &Value) -> Option<&str> {
+    field.get("blake3").and_then(Value::as_str)
+}
+
+/// Files of `dir` that hold a feed's cache: `<name hash>.json`, leaving out
+/// temporary and set-aside files
+fn feed_files(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir).into_iter().flatten().flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "json")
+                && path.file_stem().and_then(|stem| stem.to_str()).is_some_and(|stem| {
+                    stem.len() == FEED_FILE_HEX && stem.bytes().all(|b| b.is_ascii_hexdigit())
+                })
+        })
+        .collect()
+}
+
+/// Stored bodies in `dir`, named after their hash
+fn body_files(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir).into_iter().flatten().flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| {
+            name.len() == 64 && name.bytes().all(|b| b.is_ascii_hexdigit())
+        }))
+        .collect()
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// What the cache files hold, as reported by `rss-fuse cache stats`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PersistentCacheStats {
+    /// Size of the feed files and stored bodies
+    pub file_bytes: u64,
+    /// What the cache would take uncompressed, each body stored in every
+    /// article holding it
+    pub raw_bytes: u64,
+    /// Distinct article bodies stored once under `bodies/`
+    pub bodies: usize,
+    /// Article fields referring to a stored body
+    pub body_refs: usize,
+    pub feeds: usize,
+    pub articles: usize,
+    /// Entries past their expiry, dropped on the next load
+    pub expired: usize,
+    /// Feed files that are corrupt or from a newer version, set aside on the
+    /// next load
+    pub unreadable: usize,
+    pub saved_at: Option<SystemTime>,
+}
+
+impl PersistentCacheStats {
+    /// How many times smaller compression and shared bodies make the cache
+    pub fn compression_ratio(&self) -> Option<f64> {
+        (self.file_bytes > 0 && self.raw_bytes > 0).then(|| self.raw_bytes as f64 / self.file_bytes as f64)
+    }
+}
+
 /// Configuration for persistent cache
 #[derive(Debug, Clone)]
 pub struct PersistentCacheConfig {
     pub cache_dir: PathBuf,
     pub max_age_days: u64,
     pub max_size_mb: u64,
-    pub enable_compression: bool,
+    pub compression: Compression,
 }
 
 impl Default for PersistentCacheConfig {
@@ -80,170 +414,586 @@ impl Default for PersistentCacheConfig {
         Self {
             cache_dir: dirs::cache_dir()
                 .unwrap_or_else(|| PathBuf::from("."))
-                .join("rss-fuse"),
+                .join(crate::profile::dir_name()),
             max_age_days: 7, // Keep cache for 1 week
             max_size_mb: 100,
-            enable_compression: true,
+            compression: Compression::default(),
         }
     }
 }
 
-/// Persistent cache manager that saves/loads cache to/from disk
+/// Persistent cache manager that saves/loads cache to/from disk.
+///
+/// Each feed is kept in its own file under `feeds/`, with the cached
+/// articles it lists, so saving a few refreshed feeds doesn't rewrite the
+/// rest. Articles that no cached feed lists are not persisted. Long article
+/// bodies are kept under `bodies/`, named after their hash, so a body
+/// several feeds carry is stored once. Entries appended to the journal in
+/// between are replayed over the feed files when loading.
 pub struct PersistentCache {
     config: PersistentCacheConfig,
-    cache_file: PathBuf,
+    feeds_dir: PathBuf,
+    bodies_dir: PathBuf,
+    journal_path: PathBuf,
+    single_file: PathBuf, // feeds_cache.json of format versions 1 and 2
+    clock: SharedClock,
 }
 
 impl PersistentCache {
     pub fn new(config: PersistentCacheConfig) -> Result<Self> {
+        Self::with_clock(config, system_clock())
+    }
+
+    /// Create a persistent cache that judges entry and file age by `clock`
+    pub fn with_clock(config: PersistentCacheConfig, clock: SharedClock) -> Result<Self> {
+        let feeds_dir = config.cache_dir.join("feeds");
+
         // Ensure cache directory exists
-        if !config.cache_dir.exists() {
-            fs::create_dir_all(&config.cache_dir)
+        if !feeds_dir.exists() {
+            fs::create_dir_all(&feeds_dir)
                 .map_err(|e| Error::Storage(format!(
-                    "Failed to create cache directory '{}': {}", 
-                    config.cache_dir.display(), e
+                    "Failed to create cache directory '{}': {}",
+                    feeds_dir.display(), e
                 )))?;
         }
 
-        let cache_file = config.cache_dir.join("feeds_cache.json");
-
-        Ok(Self {
+        let cache = Self {
+            single_file: config.cache_dir.join("feeds_cache.json"),
+            bodies_dir: config.cache_dir.join("bodies"),
+            journal_path: config.cache_dir.join(JOURNAL_FILE),
+            feeds_dir,
             config,
-            cache_file,
-        })
+            clock,
+        };
+        if let Err(e) = cache.split_single_file() {
+            tracing::warn!("Failed to upgrade the cache in '{}': {}", cache.config.cache_dir.display(), e);
+        }
+        Ok(cache)
+    }
+
+    /// Move the feeds of a `feeds_cache.json` from before version 3 into
+    /// per-feed files
+    fn split_single_file(&self) -> Result<()> {
+        let Some(content) = read_file(&self.single_file)? else {
+            return Ok(());
+        };
+        let (cache_data, version) = match decode_single_file(&content) {
+            Ok(decoded) => decoded,
+            Err(e) => return self.set_aside(&self.single_file, &e),
+        };
+
+        tracing::info!("Upgrading cache file from format version {} to {}", version, CACHE_FORMAT_VERSION);
+        let mut articles = cache_data.articles;
+        for (name, feed) in cache_data.feeds {
+            let listed = feed.data.articles.iter()
+                .filter_map(|article| articles.remove_entry(&article.id))
+                .collect();
+            let validators = cache_data.validators.get(&name).cloned().unwrap_or_default();
+            self.write_feed_file(&FeedCacheFile { name, feed, articles: listed, validators, saved_at: cache_data.saved_at })?;
+        }
+        fs::remove_file(&self.single_file)
+            .map_err(|e| Error::Storage(format!(
+                "Failed to remove upgraded cache file '{}': {}", self.single_file.display(), e
+            )))
     }
 
-    /// Load cache data from disk
+    /// Load cache data from disk. Feed files that are corrupt or from a
+    /// newer version are moved aside, e.g. to `<hash>.corrupt.json`, and
+    /// skipped. Journal records replace the files of the feeds they name.
     pub fn load(&self) -> Result<Option<PersistentCacheData>> {
-        if !self.cache_file.exists() {
-            tracing::debug!("Cache file does not exist: {}", self.cache_file.display());
+        let now = unix_secs(self.clock.now());
+        let max_age_seconds = self.config.max_age_days * 24 * 60 * 60;
+
+        let mut files = HashMap::new();
+        for path in feed_files(&self.feeds_dir) {
+            if let Some(file) = self.load_feed_file(&path)? {
+                files.insert(file.name.clone(), file);
+            }
+        }
+        let mut journaled = HashSet::new();
+        for record in self.read_journal()? {
+            match record.file {
+                Some(file) => files.insert(record.name.clone(), file),
+                None => files.remove(&record.name),
+            };
+            journaled.insert(record.name);
+        }
+
+        let mut loaded: Option<PersistentCacheData> = None;
+        for file in files.into_values() {
+            // Check if the feed's file is too old
+            let file_age = now.saturating_sub(file.saved_at);
+            if file_age > max_age_seconds {
+                tracing::info!("Cached feed {} is too old ({} days), ignoring", file.name, file_age / (24 * 60 * 60));
+                continue;
+            }
+
+            // Filter out expired entries
+            let cache_data = loaded.get_or_insert_with(|| PersistentCacheData { saved_at: 0, ..Default::default() });
+            cache_data.saved_at = cache_data.saved_at.max(file.saved_at);
+            if file.feed.expires_at > now {
+                if file.validators != CacheValidators::default() {
+                    cache_data.validators.insert(file.name.clone(), file.validators);
+                }
+                cache_data.feeds.insert(file.name, file.feed);
+            }
+            cache_data.articles.extend(file.articles.into_iter().filter(|(_, entry)| entry.expires_at > now));
+        }
+        // Feeds the journal removed still have their files until the next save
+        if !journaled.is_empty() {
+            loaded.get_or_insert_with(|| PersistentCacheData { saved_at: 0, ..Default::default() }).journaled = journaled;
+        }
+
+        if let Some(cache_data) = &loaded {
+            tracing::info!("Loaded cache: {} feeds, {} articles",
+                          cache_data.feeds.len(), cache_data.articles.len());
+        }
+        Ok(loaded)
+    }
+
+    /// A feed's file, or `None` if it has none or it was unusable and set aside
+    fn load_feed_file(&self, path: &Path) -> Result<Option<FeedCacheFile>> {
+        let Some(content) = read_file(path)? else {
             return Ok(None);
+        };
+        match self.decode_feed_file(&content) {
+            Ok(file) => Ok(Some(file)),
+            Err(e) => {
+                self.set_aside(path, &e)?;
+                Ok(None)
+            }
         }
+    }
 
-        let file_content = fs::read_to_string(&self.cache_file)
+    /// Keep an unusable cache file for inspection or for the newer rss-fuse
+    /// that wrote it: `<name>.corrupt.json`, `<name>.v4.json`
+    fn set_aside(&self, path: &Path, error: &FormatError) -> Result<()> {
+        let tag = match error {
+            FormatError::Corrupt(_) => "corrupt".to_string(),
+            FormatError::Newer(version) => format!("v{}", version),
+        };
+        let aside = path.with_extension(format!("{}.json", tag));
+        tracing::warn!("Cache file '{}' {}; moving it to '{}'", path.display(), error, aside.display());
+        fs::rename(path, &aside)
             .map_err(|e| Error::Storage(format!(
-                "Failed to read cache file '{}': {}", 
-                self.cache_file.display(), e
-            )))?;
+                "Failed to move cache file '{}' aside: {}", path.display(), e
+            )))
+    }
 
-        let cache_data: PersistentCacheData = serde_json::from_str(&file_content)
-            .map_err(|e| Error::Serialization(e))?;
+    /// Size and entry counts of the cache files
+    pub fn stats(&self) -> Result<PersistentCacheStats> {
+        let now = unix_secs(self.clock.now());
+        let mut stats = PersistentCacheStats {
+            file_bytes: self.size_bytes(),
+            ..PersistentCacheStats::default()
+        };
 
-        // Check if cache is too old
-        let cache_age = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs() - cache_data.saved_at;
+        let mut bodies = HashSet::new();
+        for path in feed_files(&self.feeds_dir) {
+            let Some(content) = read_file(&path)? else {
+                continue;
+            };
+            // Count stored bodies by their recorded size rather than reading them
+            let mut raw_bytes = 0;
+            let mut refs = Vec::new();
+            let file = decode_feed_json(&content).and_then(|mut value| {
+                raw_bytes = serde_json::to_string_pretty(&value).map_or(0, |json| json.len() as u64);
+                visit_bodies(&mut value, &mut |field| {
+                    if let Some(hash) = body_ref(field) {
+                        raw_bytes += self.body_size(hash)
+                            .ok_or_else(|| FormatError::Corrupt(format!("body {} is missing", hash)))?;
+                        refs.push(hash.to_string());
+                        *field = Value::Null;
+                    }
+                    Ok(())
+                })?;
+                serde_json::from_value::<FeedCacheFile>(value).map_err(|e| FormatError::Corrupt(e.to_string()))
+            });
+            let Ok(file) = file else {
+                stats.unreadable += 1;
+                continue;
+            };
+            stats.raw_bytes += raw_bytes;
+            stats.body_refs += refs.len();
+            bodies.extend(refs);
+            stats.feeds += 1;
+            stats.articles += file.articles.len();
+            stats.expired += usize::from(file.feed.expires_at <= now)
+                + file.articles.values().filter(|e| e.expires_at <= now).count();
+            let saved_at = SystemTime::UNIX_EPOCH + Duration::from_secs(file.saved_at);
+            stats.saved_at = stats.saved_at.max(Some(saved_at));
+        }
+        stats.bodies = bodies.len();
+        Ok(stats)
+    }
 
-        let max_age_seconds = self.config.max_age_days * 24 * 60 * 60;
-        if cache_age > max_age_seconds {
-            tracing::info!("Cache file is too old ({} days), ignoring", cache_age / (24 * 60 * 60));
-            return Ok(None);
+    /// Delete the cache files, including set-aside and half-written
+    /// temporary ones. Returns the number of bytes removed.
+    pub fn clear(&self) -> Result<u64> {
+        let mut removed = 0;
+        let temp_files = fs::read_dir(&self.config.cache_dir).into_iter().flatten().flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "tmp"));
+        let feed_files = [&self.feeds_dir, &self.bodies_dir].into_iter()
+            .flat_map(|dir| fs::read_dir(dir).into_iter().flatten().flatten())
+            .map(|entry| entry.path());
+        for path in [self.single_file.clone(), self.journal_path.clone()].into_iter().chain(temp_files).chain(feed_files) {
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            fs::remove_file(&path)
+                .map_err(|e| Error::Storage(format!(
+                    "Failed to remove cache file '{}': {}", path.display(), e
+                )))?;
+            removed += metadata.len();
+        }
+        Ok(removed)
+    }
+
+    /// Save cache data to disk, replacing every feed's file and removing
+    /// those of feeds no longer in `feeds`
+    pub fn save(&self, feeds: &HashMap<String, CacheEntry<Feed>>,
+                articles: &HashMap<String, CacheEntry<Arc<Article>>>,
+                validators: &HashMap<String, CacheValidators>) -> Result<()> {
+        let names: HashSet<String> = feeds.keys().cloned().collect();
+        let (_, live_bodies) = self.write_changed(feeds, articles, validators, &names)?;
+
+        let kept: HashSet<PathBuf> = names.iter().map(|name| self.feed_file_path(name)).collect();
+        for path in feed_files(&self.feeds_dir) {
+            if !kept.contains(&path) {
+                remove_file(&path)?;
+            }
+        }
+        // Every feed's file was just written, so these are all the bodies in use
+        for path in body_files(&self.bodies_dir) {
+            if path.file_name().and_then(|name| name.to_str()).is_some_and(|name| !live_bodies.contains(name)) {
+                remove_file(&path)?;
+            }
         }
+        Ok(())
+    }
 
-        // Filter out expired entries
-        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap_or_default().as_secs();
+    /// Rewrite the files of the `changed` feeds only: from their entry in
+    /// `feeds`, or removed if they have none or it expired. Other feeds'
+    /// files are left alone. Returns the number of files written.
+    pub fn save_changed(&self, feeds: &HashMap<String, CacheEntry<Feed>>,
+                        articles: &HashMap<String, CacheEntry<Arc<Article>>>,
+                        validators: &HashMap<String, CacheValidators>,
+                        changed: &HashSet<String>) -> Result<usize> {
+        Ok(self.write_changed(feeds, articles, validators, changed)?.0)
+    }
 
-        let mut filtered_data = cache_data;
-        filtered_data.feeds.retain(|_, entry| entry.expires_at > now);
-        filtered_data.articles.retain(|_, entry| entry.expires_at > now);
+    /// Write the files of the `changed` feeds as `save_changed` does.
+    /// Returns the number of files written and the bodies they refer to.
+    fn write_changed(&self, feeds: &HashMap<String, CacheEntry<Feed>>,
+                     articles: &HashMap<String, CacheEntry<Arc<Article>>>,
+                     validators: &HashMap<String, CacheValidators>,
+                     changed: &HashSet<String>) -> Result<(usize, HashSet<String>)> {
+        let now = self.clock.now();
+        let mut written = 0;
+        let mut bodies = HashSet::new();
 
-        tracing::info!("Loaded cache: {} feeds, {} articles", 
-                      filtered_data.feeds.len(), filtered_data.articles.len());
+        for name in changed {
+            let Some(file) = feed_cache_file(name, feeds, articles, validators, now) else {
+                remove_file(&self.feed_file_path(name))?;
+                continue;
+            };
+            bodies.extend(self.write_feed_file(&file)?);
+            written += 1;
+        }
 
-        Ok(Some(filtered_data))
+        tracing::info!("Saved cache: {} of {} changed feeds written to {}",
+                      written, changed.len(), self.feeds_dir.display());
+        Ok((written, bodies))
     }
 
-    /// Save cache data to disk
-    pub fn save(&self, feeds: &HashMap<String, CacheEntry<Feed>>, 
-                articles: &HashMap<String, CacheEntry<Arc<Article>>>) -> Result<()> {
-        
-        // Convert to serializable format
-        let feed_entries: HashMap<String, SerializableCacheEntry<Feed>> = feeds
-            .iter()
-            .filter(|(_, entry)| !entry.is_expired())
-            .map(|(k, v)| (k.clone(), v.clone().into()))
-            .collect();
+    /// Append the entries of the `changed` feeds to the journal, as
+    /// `save_changed` would write them, and sync it: one write however many
+    /// feeds changed. `load` replays the journal until `clear_journal`.
+    /// Returns the journal's size in bytes.
+    pub fn append_journal(&self, feeds: &HashMap<String, CacheEntry<Feed>>,
+                          articles: &HashMap<String, CacheEntry<Arc<Article>>>,
+                          validators: &HashMap<String, CacheValidators>,
+                          changed: &HashSet<String>) -> Result<u64> {
+        let now = self.clock.now();
+        let mut lines = String::new();
+        for name in changed {
+            let record = JournalRecord { name: name.clone(), file: feed_cache_file(name, feeds, articles, validators, now) };
+            let json = serde_json::to_string(&record).map_err(Error::Serialization)?;
+            lines.push_str(&format!("{} {}\n", blake3::hash(json.as_bytes()).to_hex(), json));
+        }
 
-        let article_entries: HashMap<String, SerializableCacheEntry<Article>> = articles
-            .iter()
-            .filter(|(_, entry)| !entry.is_expired())
-            .map(|(k, v)| (k.clone(), SerializableCacheEntry {
-                data: (*v.data).clone(), // Dereference Arc<Article>
-                created_at: v.created_at.duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default().as_secs(),
-                expires_at: v.expires_at.duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default().as_secs(),
-                access_count: v.access_count,
-                last_accessed: v.last_accessed.duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default().as_secs(),
-            }))
-            .collect();
+        let storage_error = |e: std::io::Error| Error::Storage(format!(
+            "Failed to write cache journal '{}': {}", self.journal_path.display(), e
+        ));
+        let mut journal = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.journal_path)
+            .map_err(storage_error)?;
+        journal.write_all(lines.as_bytes()).map_err(storage_error)?;
+        journal.sync_data().map_err(storage_error)?;
+        tracing::debug!("Journaled {} changed feeds to {}", changed.len(), self.journal_path.display());
+        Ok(journal.metadata().map_err(storage_error)?.len())
+    }
 
-        let cache_data = PersistentCacheData {
-            feeds: feed_entries,
-            articles: article_entries,
-            cache_version: 1,
-            saved_at: SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap_or_default().as_secs(),
+    /// Empty the journal, once the feed files hold what it records
+    pub fn clear_journal(&self) -> Result<()> {
+        remove_file(&self.journal_path)
+    }
+
+    /// The journal's records, oldest first. Lines failing their checksum,
+    /// such as one a crash cut short, are skipped.
+    fn read_journal(&self) -> Result<Vec<JournalRecord>> {
+        let Some(content) = read_file(&self.journal_path)? else {
+            return Ok(Vec::new());
         };
+        let mut records = Vec::new();
+        for (number, line) in content.split(|&b| b == b'\n').enumerate().filter(|(_, line)| !line.is_empty()) {
+            let record = std::str::from_utf8(line).ok()
+                .and_then(|line| line.split_once(' '))
+                .filter(|(checksum, json)| blake3::hash(json.as_bytes()).to_hex().as_str() == *checksum)
+                .and_then(|(_, json)| serde_json::from_str(json).ok());
+            match record {
+                Some(record) => records.push(record),
+                None => tracing::warn!("Skipping line {} of cache journal '{}': it is incomplete or corrupt",
+                                       number + 1, self.journal_path.display()),
+            }
+        }
+        Ok(records)
+    }
 
-        // Serialize to JSON
-        let json_content = serde_json::to_string_pretty(&cache_data)
-            .map_err(|e| Error::Serialization(e))?;
+    /// Move a feed's cached entry and articles from `old` to `new`. Article
+    /// IDs that embed the feed name are recomputed under `strategy`; the
+    /// changed `(old, new)` IDs are returned so read state can follow.
+    pub fn rename_feed(&self, old: &str, new: &str, strategy: IdStrategy) -> Result<Vec<(String, String)>> {
+        let Some(mut file) = self.load_feed_file(&self.feed_file_path(old))? else {
+            return Ok(Vec::new());
+        };
 
-        // Write to temporary file first, then rename (atomic operation)
-        let temp_file = self.cache_file.with_extension("tmp");
-        fs::write(&temp_file, json_content)
-            .map_err(|e| Error::Storage(format!(
-                "Failed to write cache to '{}': {}", 
-                temp_file.display(), e
-            )))?;
+        let mut renamed = Vec::new();
+        file.name = new.to_string();
+        file.feed.data.name = new.to_string();
+        for article in &mut file.feed.data.articles {
+            let id = article.id_for(new, strategy);
+            if id != article.id {
+                renamed.push((std::mem::replace(&mut article.id, id), article.id.clone()));
+            }
+        }
+        for (old_id, new_id) in &renamed {
+            if let Some(mut cached) = file.articles.remove(old_id) {
+                cached.data.id = new_id.clone();
+                file.articles.insert(new_id.clone(), cached);
+            }
+        }
+
+        self.write_feed_file(&file)?;
+        remove_file(&self.feed_file_path(old))?;
+        Ok(renamed)
+    }
+
+    /// Point a feed's cached entry at a new URL, dropping the HTTP
+    /// validators of the old one. Returns whether it was cached.
+    pub fn set_feed_url(&self, name: &str, url: &str) -> Result<bool> {
+        let Some(mut file) = self.load_feed_file(&self.feed_file_path(name))? else {
+            return Ok(false);
+        };
+        file.feed.data.url = url.to_string();
+        file.validators = CacheValidators::default();
+        self.write_feed_file(&file)?;
+        Ok(true)
+    }
 
-        fs::rename(&temp_file, &self.cache_file)
+    /// Set the `read` flag of a feed's cached articles in `ids`. Returns how
+    /// many changed.
+    pub fn set_read_flags(&self, name: &str, ids: &HashSet<&str>, read: bool) -> Result<usize> {
+        let Some(mut file) = self.load_feed_file(&self.feed_file_path(name))? else {
+            return Ok(0);
+        };
+        let mut changed = 0;
+        for article in file.feed.data.articles.iter_mut().filter(|a| ids.contains(a.id.as_str())) {
+            if article.read != read {
+                article.read = read;
+                changed += 1;
+            }
+        }
+        for (_, cached) in file.articles.iter_mut().filter(|(id, _)| ids.contains(id.as_str())) {
+            cached.data.read = read;
+        }
+        if changed > 0 {
+            self.write_feed_file(&file)?;
+        }
+        Ok(changed)
+    }
+
+    /// File holding a feed's cache, named after a hash of the feed name as
+    /// feed names may contain `/` and any other character
+    fn feed_file_path(&self, name: &str) -> PathBuf {
+        let hash = blake3::hash(name.as_bytes()).to_hex();
+        self.feeds_dir.join(format!("{}.json", &hash[..FEED_FILE_HEX]))
+    }
+
+    /// Write a feed's file, moving long bodies to `bodies/`. Returns the
+    /// hashes of the bodies it refers to.
+    fn write_feed_file(&self, file: &FeedCacheFile) -> Result<HashSet<String>> {
+        // Serialize to JSON, with stored bodies referred to by hash
+        let mut value = serde_json::to_value(file).map_err(Error::Serialization)?;
+        let mut bodies = HashSet::new();
+        visit_bodies(&mut value, &mut |field| {
+            let Value::String(body) = &*field else {
+                return Ok(());
+            };
+            if body.len() < BODY_MIN_BYTES {
+                return Ok(());
+            }
+            let hash = self.put_body(body)?;
+            *field = serde_json::json!({ "blake3": hash });
+            bodies.insert(hash);
+            Ok::<(), Error>(())
+        })?;
+        let json_content = serde_json::to_string_pretty(&value)
+            .map_err(Error::Serialization)?;
+        let compression = self.config.compression;
+        let header = format!(
+            "{} v{} blake3:{} {}\n",
+            HEADER_MAGIC, CACHE_FORMAT_VERSION, blake3::hash(json_content.as_bytes()).to_hex(), compression.codec
+        );
+        let path = self.feed_file_path(&file.name);
+
+        // Write and sync a temporary file first, then rename it over the
+        // feed's file, so a crash leaves either the old or the new cache
+        let storage_error = |e: std::io::Error| Error::Storage(format!(
+            "Failed to write cache to '{}': {}", path.display(), e
+        ));
+        let mut temp_file = tempfile::Builder::new()
+            .prefix("feed.")
+            .suffix(".tmp")
+            .tempfile_in(&self.feeds_dir)
+            .map_err(storage_error)?;
+        let body = compression.codec.compress(json_content.as_bytes(), compression.level).map_err(storage_error)?;
+        temp_file.write_all(header.as_bytes()).map_err(storage_error)?;
+        temp_file.write_all(&body).map_err(storage_error)?;
+        temp_file.as_file().sync_all().map_err(storage_error)?;
+
+        temp_file.persist(&path)
             .map_err(|e| Error::Storage(format!(
-                "Failed to rename cache file '{}' to '{}': {}", 
-                temp_file.display(), self.cache_file.display(), e
+                "Failed to rename cache file '{}' to '{}': {}",
+                e.file.path().display(), path.display(), e.error
             )))?;
+        // Make the rename itself durable
+        if let Ok(dir) = fs::File::open(&self.feeds_dir) {
+            let _ = dir.sync_all();
+        }
 
-        tracing::info!("Saved cache: {} feeds, {} articles to {}", 
-                      cache_data.feeds.len(), cache_data.articles.len(),
-                      self.cache_file.display());
+        tracing::debug!("Saved cache of {}: {} articles to {}",
+                       file.name, file.articles.len(), path.display());
 
-        Ok(())
+        Ok(bodies)
     }
 
-    /// Check current cache file size
-    pub fn cache_size_mb(&self) -> f64 {
-        if let Ok(metadata) = fs::metadata(&self.cache_file) {
-            metadata.len() as f64 / (1024.0 * 1024.0)
-        } else {
-            0.0
+    /// A feed's file with its stored bodies read back
+    fn decode_feed_file(&self, content: &[u8]) -> std::result::Result<FeedCacheFile, FormatError> {
+        let mut value = decode_feed_json(content)?;
+        visit_bodies(&mut value, &mut |field| {
+            if let Some(hash) = body_ref(field) {
+                *field = Value::String(self.get_body(hash)?);
+            }
+            Ok(())
+        })?;
+        serde_json::from_value(value).map_err(|e| FormatError::Corrupt(e.to_string()))
+    }
+
+    fn body_path(&self, hash: &str) -> PathBuf {
+        self.bodies_dir.join(hash)
+    }
+
+    /// Store a body unless an identical one is stored already, as a
+    /// `<codec> <size>` line followed by the compressed body. Returns its hash.
+    fn put_body(&self, body: &str) -> Result<String> {
+        let hash = blake3::hash(body.as_bytes()).to_hex().to_string();
+        let path = self.body_path(&hash);
+        if path.exists() {
+            return Ok(hash);
+        }
+
+        let storage_error = |e: std::io::Error| Error::Storage(format!(
+            "Failed to write article body '{}': {}", path.display(), e
+        ));
+        fs::create_dir_all(&self.bodies_dir).map_err(storage_error)?;
+        let compression = self.config.compression;
+        let compressed = compression.codec.compress(body.as_bytes(), compression.level).map_err(storage_error)?;
+        let mut temp_file = tempfile::Builder::new()
+            .prefix("body.")
+            .suffix(".tmp")
+            .tempfile_in(&self.bodies_dir)
+            .map_err(storage_error)?;
+        temp_file.write_all(format!("{} {}\n", compression.codec, body.len()).as_bytes()).map_err(storage_error)?;
+        temp_file.write_all(&compressed).map_err(storage_error)?;
+        temp_file.as_file().sync_all().map_err(storage_error)?;
+        temp_file.persist(&path).map_err(|e| storage_error(e.error))?;
+        Ok(hash)
+    }
+
+    /// A stored body, checked against its hash
+    fn get_body(&self, hash: &str) -> std::result::Result<String, FormatError> {
+        let corrupt = |reason: String| FormatError::Corrupt(format!("body {}: {}", hash, reason));
+        let content = fs::read(self.body_path(hash)).map_err(|e| corrupt(e.to_string()))?;
+        let newline = content.iter().position(|&b| b == b'\n').ok_or_else(|| corrupt("no header".to_string()))?;
+        let codec = std::str::from_utf8(&content[..newline]).ok()
+            .and_then(|header| header.split_whitespace().next())
+            .and_then(Codec::from_name)
+            .ok_or_else(|| corrupt("bad header".to_string()))?;
+        let body = codec.decompress(&content[newline + 1..]).map_err(corrupt)?;
+        let body = String::from_utf8(body).map_err(|_| corrupt("not UTF-8".to_string()))?;
+        if blake3::hash(body.as_bytes()).to_hex().as_str() != hash {
+            return Err(corrupt("checksum mismatch".to_string()));
         }
+        Ok(body)
+    }
+
+    /// Uncompressed size of a stored body, from its header
+    fn body_size(&self, hash: &str) -> Option<u64> {
+        let mut header = String::new();
+        BufReader::new(fs::File::open(self.body_path(hash)).ok()?).read_line(&mut header).ok()?;
+        header.split_whitespace().nth(1)?.parse().ok()
+    }
+
+    /// Size of all cache files, in bytes
+    pub fn size_bytes(&self) -> u64 {
+        let feed_files = [&self.feeds_dir, &self.bodies_dir].into_iter()
+            .flat_map(|dir| fs::read_dir(dir).into_iter().flatten().flatten())
+            .map(|entry| entry.path());
+        [self.single_file.clone(), self.journal_path.clone()].into_iter().chain(feed_files)
+            .filter_map(|path| fs::metadata(path).ok())
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    /// Check current cache size
+    pub fn cache_size_mb(&self) -> f64 {
+        self.size_bytes() as f64 / (1024.0 * 1024.0)
     }
 
     /// Clean up old cache files and check size limits
     pub fn cleanup(&self) -> Result<()> {
-        // Check file size
+        // Check total size
         if self.cache_size_mb() > self.config.max_size_mb as f64 {
-            tracing::warn!("Cache file size ({:.1} MB) exceeds limit ({} MB), removing cache",
+            tracing::warn!("Cache size ({:.1} MB) exceeds limit ({} MB), removing cache",
                           self.cache_size_mb(), self.config.max_size_mb);
-            if self.cache_file.exists() {
-                fs::remove_file(&self.cache_file)
-                    .map_err(|e| Error::Storage(format!(
-                        "Failed to remove oversized cache file '{}': {}", 
-                        self.cache_file.display(), e
-                    )))?
+            for path in feed_files(&self.feeds_dir).into_iter().chain(body_files(&self.bodies_dir)) {
+                remove_file(&path)?;
             }
         }
 
         // Clean up temporary files
-        if let Ok(entries) = fs::read_dir(&self.config.cache_dir) {
-            for entry in entries.flatten() {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.ends_with(".tmp") {
-                        let _ = fs::remove_file(entry.path());
+        for dir in [&self.config.cache_dir, &self.feeds_dir, &self.bodies_dir] {
+            if let Ok(entries) = fs::read_dir(dir) {
+                for entry in entries.flatten() {
+                    if let Some(name) = entry.file_name().to_str() {
+                        if name.ends_with(".tmp") {
+                            let _ = fs::remove_file(entry.path());
+                        }
                     }
                 }
             }
@@ -252,9 +1002,66 @@ impl PersistentCache {
         Ok(())
     }
 
-    /// Get cache file path for debugging
+    /// Directory of the per-feed cache files
     pub fn cache_path(&self) -> &Path {
-        &self.cache_file
+        &self.feeds_dir
+    }
+
+    /// Directory holding the cache files
+    pub fn cache_dir(&self) -> &Path {
+        &self.config.cache_dir
+    }
+}
+
+/// What a feed's file holds for `name`: its entry in `feeds` with the
+/// cached articles it lists, or `None` if it has none or it expired
+fn feed_cache_file(name: &str, feeds: &HashMap<String, CacheEntry<Feed>>,
+                   articles: &HashMap<String, CacheEntry<Arc<Article>>>,
+                   validators: &HashMap<String, CacheValidators>, now: SystemTime) -> Option<FeedCacheFile> {
+    let entry = feeds.get(name).filter(|entry| !entry.is_expired_at(now))?;
+
+    // Convert to serializable format
+    let listed = entry.data.articles.iter()
+        .filter_map(|article| articles.get(&article.id).map(|cached| (&article.id, cached)))
+        .filter(|(_, cached)| !cached.is_expired_at(now))
+        .map(|(id, v)| (id.clone(), SerializableCacheEntry {
+            data: (*v.data).clone(), // Dereference Arc<Article>
+            created_at: unix_secs(v.created_at),
+            expires_at: unix_secs(v.expires_at),
+            access_count: v.access_count,
+            last_accessed: unix_secs(v.last_accessed),
+        }))
+        .collect();
+
+    Some(FeedCacheFile {
+        name: name.to_string(),
+        feed: entry.clone().into(),
+        articles: listed,
+        validators: validators.get(name).cloned().unwrap_or_default(),
+        saved_at: unix_secs(now),
+    })
+}
+
+fn read_file(path: &Path) -> Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::debug!("Cache file does not exist: {}", path.display());
+            Ok(None)
+        }
+        Err(e) => Err(Error::Storage(format!(
+            "Failed to read cache file '{}': {}", path.display(), e
+        ))),
+    }
+}
+
+/// Remove a cache file if it exists
+fn remove_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::Storage(format!(
+            "Failed to remove cache file '{}': {}", path.display(), e
+        ))),
+        _ => Ok(()),
     }
 }
 
@@ -262,6 +1069,7 @@ impl PersistentCache {
 mod tests {
     use super::*;
     use tempfile::TempDir;
+    use crate::clock::{Clock, MockClock};
     use crate::feed::{Article, ParsedArticle};
     use chrono::Utc;
 
@@ -275,6 +1083,7 @@ mod tests {
             published: Some(Utc::now()),
             guid: Some(format!("guid-{}", title.to_lowercase())),
             categories: vec!["test".to_string()],
+            enclosures: Vec::new(),
         };
         Article::new(parsed, "test-feed")
     }
@@ -313,12 +1122,15 @@ mod tests {
         let feed_entry = CacheEntry::new(test_feed, Duration::from_secs(3600));
         feeds.insert("tech-news".to_string(), feed_entry);
 
-        let test_article = create_test_article("Test Article");
-        let article_entry = CacheEntry::new(Arc::new(test_article), Duration::from_secs(3600));
-        articles.insert("test-id".to_string(), article_entry);
+        // Articles are saved with the feed listing them
+        let listed = feeds["tech-news"].data.articles[0].clone();
+        let article_entry = CacheEntry::new(Arc::new(listed.clone()), Duration::from_secs(3600));
+        articles.insert(listed.id.clone(), article_entry);
+        let unlisted = CacheEntry::new(Arc::new(create_test_article("Unlisted")), Duration::from_secs(3600));
+        articles.insert("unlisted-id".to_string(), unlisted);
 
         // Save cache
-        cache.save(&feeds, &articles).unwrap();
+        cache.save(&feeds, &articles, &HashMap::new()).unwrap();
 
         // Load cache
         let loaded_data = cache.load().unwrap().unwrap();
@@ -326,34 +1138,375 @@ mod tests {
         assert_eq!(loaded_data.feeds.len(), 1);
         assert_eq!(loaded_data.articles.len(), 1);
         assert!(loaded_data.feeds.contains_key("tech-news"));
-        assert!(loaded_data.articles.contains_key("test-id"));
+        assert!(loaded_data.articles.contains_key(&listed.id));
     }
 
     #[test]
-    fn test_cache_expiration() {
+    fn test_save_changed() {
         let temp_dir = TempDir::new().unwrap();
         let config = PersistentCacheConfig {
             cache_dir: temp_dir.path().to_path_buf(),
-            max_age_days: 0, // Expire immediately
             ..Default::default()
         };
+        let cache = PersistentCache::new(config).unwrap();
+
+        let mut feeds: HashMap<String, CacheEntry<Feed>> = ["a", "b", "c"].iter()
+            .map(|name| (name.to_string(), CacheEntry::new(create_test_feed(name, 1), Duration::from_secs(3600))))
+            .collect();
+        cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+        assert_eq!(feed_files(cache.cache_path()).len(), 3);
+        let untouched = fs::read(cache.feed_file_path("b")).unwrap();
+
+        // Only the changed feeds' files are written or removed
+        feeds.get_mut("a").unwrap().data.title = Some("Renamed".to_string());
+        feeds.remove("c");
+        let changed = HashSet::from(["a".to_string(), "c".to_string()]);
+        feeds.get_mut("b").unwrap().data.title = Some("Not saved".to_string());
+        assert_eq!(cache.save_changed(&feeds, &HashMap::new(), &HashMap::new(), &changed).unwrap(), 1);
+
+        assert_eq!(feed_files(cache.cache_path()).len(), 2);
+        assert_eq!(fs::read(cache.feed_file_path("b")).unwrap(), untouched);
+        let loaded = cache.load().unwrap().unwrap();
+        assert_eq!(loaded.feeds["a"].data.title.as_deref(), Some("Renamed"));
+        assert_eq!(loaded.feeds["b"].data.title.as_deref(), Some("Test Feed b"));
+        assert!(!loaded.feeds.contains_key("c"));
 
+        // A full save drops files of feeds it doesn't hold
+        feeds.remove("b");
+        cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+        assert_eq!(feed_files(cache.cache_path()), [cache.feed_file_path("a")]);
+    }
+
+    #[test]
+    fn test_journal_replay() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
         let cache = PersistentCache::new(config).unwrap();
 
+        let mut feeds: HashMap<String, CacheEntry<Feed>> = ["a", "b"].iter()
+            .map(|name| (name.to_string(), CacheEntry::new(create_test_feed(name, 1), Duration::from_secs(3600))))
+            .collect();
+        cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+        let saved = fs::read(cache.feed_file_path("a")).unwrap();
+
+        // Journaled entries win over the files, which are left alone
+        feeds.get_mut("a").unwrap().data.title = Some("Journaled".to_string());
+        feeds.remove("b");
+        feeds.insert("c".to_string(), CacheEntry::new(create_test_feed("c", 1), Duration::from_secs(3600)));
+        let changed = HashSet::from(["a".to_string(), "b".to_string(), "c".to_string()]);
+        cache.append_journal(&feeds, &HashMap::new(), &HashMap::new(), &changed).unwrap();
+        // A crash in the middle of an append leaves a partial line
+        let mut journal = fs::OpenOptions::new().append(true).open(&cache.journal_path).unwrap();
+        journal.write_all(b"0123 {\"name\":\"a\",\"fi").unwrap();
+        assert_eq!(fs::read(cache.feed_file_path("a")).unwrap(), saved);
+
+        let loaded = cache.load().unwrap().unwrap();
+        assert_eq!(loaded.feeds["a"].data.title.as_deref(), Some("Journaled"));
+        assert!(!loaded.feeds.contains_key("b"));
+        assert!(loaded.feeds.contains_key("c"));
+        assert_eq!(loaded.journaled, changed);
+
+        // Once the files are written the journal is no longer needed
+        cache.save_changed(&feeds, &HashMap::new(), &HashMap::new(), &loaded.journaled).unwrap();
+        cache.clear_journal().unwrap();
+        assert!(!cache.journal_path.exists());
+        let loaded = cache.load().unwrap().unwrap();
+        assert_eq!(loaded.feeds["a"].data.title.as_deref(), Some("Journaled"));
+        assert_eq!(loaded.feeds.len(), 2);
+        assert!(loaded.journaled.is_empty());
+    }
+
+    #[test]
+    fn test_single_file_cache_split() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        // Version 1 wrote the whole cache as bare JSON
+        let feed = create_test_feed("tech/news", 2);
+        let article = feed.articles[1].clone();
+        let legacy = PersistentCacheData {
+            feeds: HashMap::from([("tech/news".to_string(), CacheEntry::new(feed, Duration::from_secs(3600)).into())]),
+            articles: HashMap::from([
+                (article.id.clone(), CacheEntry::new(article.clone(), Duration::from_secs(3600)).into()),
+                ("unlisted".to_string(), CacheEntry::new(create_test_article("Unlisted"), Duration::from_secs(3600)).into()),
+            ]),
+            cache_version: 1,
+            ..Default::default()
+        };
+        let single_file = temp_dir.path().join("feeds_cache.json");
+        fs::write(&single_file, serde_json::to_string_pretty(&legacy).unwrap()).unwrap();
+
+        let cache = PersistentCache::new(config.clone()).unwrap();
+        assert!(!single_file.exists());
+        let upgraded = fs::read(cache.feed_file_path("tech/news")).unwrap();
+        assert!(upgraded.starts_with(b"rss-fuse-cache v4 blake3:"));
+
+        let loaded = cache.load().unwrap().unwrap();
+        assert_eq!(loaded.feeds["tech/news"].data.articles.len(), 2);
+        assert_eq!(loaded.articles.keys().collect::<Vec<_>>(), [&article.id]);
+        assert_eq!(loaded.cache_version, CACHE_FORMAT_VERSION);
+
+        // Version 2 added a header
+        cache.clear().unwrap();
+        let json = serde_json::to_string_pretty(&PersistentCacheData { cache_version: 2, ..legacy }).unwrap();
+        fs::write(&single_file, format!("rss-fuse-cache v2 blake3:{}\n{}", blake3::hash(json.as_bytes()).to_hex(), json)).unwrap();
+        let cache = PersistentCache::new(config).unwrap();
+        assert_eq!(cache.load().unwrap().unwrap().feeds.len(), 1);
+    }
+
+    #[test]
+    fn test_unusable_cache_set_aside() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            compression: Compression::none(), // editable as text
+            ..Default::default()
+        };
+        let cache = PersistentCache::new(config).unwrap();
+        let feeds = HashMap::from([(
+            "tech-news".to_string(),
+            CacheEntry::new(create_test_feed("tech-news", 1), Duration::from_secs(3600)),
+        )]);
+        cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+        let path = cache.feed_file_path("tech-news");
+        let saved = fs::read_to_string(&path).unwrap();
+
+        // A body that no longer matches its checksum
+        fs::write(&path, saved.replace("tech-news", "tech-newz")).unwrap();
+        assert_eq!(cache.stats().unwrap().unreadable, 1);
+        assert!(cache.load().unwrap().is_none());
+        assert!(!path.exists());
+        assert!(path.with_extension("corrupt.json").exists());
+
+        // A newer format is kept for the rss-fuse that wrote it
+        fs::write(&path, saved.replacen("v4", "v5", 1)).unwrap();
+        assert!(cache.load().unwrap().is_none());
+        assert_eq!(fs::read_to_string(path.with_extension("v5.json")).unwrap(), saved.replacen("v4", "v5", 1));
+
+        fs::write(&path, &saved[..saved.len() / 2]).unwrap();
+        assert!(cache.load().unwrap().is_none());
+        cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+        assert_eq!(cache.load().unwrap().unwrap().feeds.len(), 1);
+        assert_eq!(cache.stats().unwrap().feeds, 1);
+    }
+
+    #[test]
+    fn test_codecs_and_shared_bodies() {
+        for codec in [Codec::Zstd, Codec::Lz4, Codec::None] {
+            let temp_dir = TempDir::new().unwrap();
+            let config = PersistentCacheConfig {
+                cache_dir: temp_dir.path().to_path_buf(),
+                compression: Compression::new(codec, None).unwrap(),
+                ..Default::default()
+            };
+            let cache = PersistentCache::new(config).unwrap();
+
+            // The same long story in two feeds
+            let body = "<p>A long article body that several feeds carry.</p>".repeat(50);
+            let feeds: HashMap<String, CacheEntry<Feed>> = ["a", "b"].iter().map(|name| {
+                let mut feed = create_test_feed(name, 1);
+                feed.articles[0].content = Some(body.clone());
+                (name.to_string(), CacheEntry::new(feed, Duration::from_secs(3600)))
+            }).collect();
+            cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+
+            let header = fs::read(cache.feed_file_path("a")).unwrap();
+            assert!(header.starts_with(b"rss-fuse-cache v4 blake3:"));
+            assert!(header.split(|&b| b == b'\n').next().unwrap().ends_with(codec.name().as_bytes()));
+            assert_eq!(body_files(&temp_dir.path().join("bodies")).len(), 1);
+
+            let loaded = cache.load().unwrap().unwrap();
+            assert_eq!(loaded.feeds["b"].data.articles[0].content.as_deref(), Some(body.as_str()));
+
+            let stats = cache.stats().unwrap();
+            assert_eq!((stats.bodies, stats.body_refs), (1, 2));
+            assert!(stats.raw_bytes > 2 * body.len() as u64);
+            if codec != Codec::None {
+                assert!(stats.compression_ratio().unwrap() > 1.0);
+            }
+
+            // Bodies no feed refers to go with the next full save
+            cache.save(&HashMap::new(), &HashMap::new(), &HashMap::new()).unwrap();
+            assert!(body_files(&temp_dir.path().join("bodies")).is_empty());
+        }
+
+        assert!(Compression::new(Codec::Zstd, Some(19)).is_ok());
+        assert!(Compression::new(Codec::Zstd, Some(23)).is_err());
+        assert!(Compression::new(Codec::Lz4, Some(3)).is_err());
+    }
+
+    #[test]
+    fn test_missing_body_sets_feed_aside() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let cache = PersistentCache::new(config).unwrap();
+        let mut feed = create_test_feed("tech-news", 1);
+        feed.articles[0].content = Some("x".repeat(BODY_MIN_BYTES));
+        let feeds = HashMap::from([("tech-news".to_string(), CacheEntry::new(feed, Duration::from_secs(3600)))]);
+        cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+
+        for path in body_files(&temp_dir.path().join("bodies")) {
+            fs::remove_file(path).unwrap();
+        }
+        assert_eq!(cache.stats().unwrap().unreadable, 1);
+        assert!(cache.load().unwrap().is_none());
+        assert!(cache.feed_file_path("tech-news").with_extension("corrupt.json").exists());
+    }
+
+    #[test]
+    fn test_rename_feed() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let cache = PersistentCache::new(config).unwrap();
+
+        // One article keeps its GUID as ID; the other's is a hash prefixed with the feed name
+        let mut feed = create_test_feed("old", 2);
+        feed.articles[1].guid = None;
+        feed.articles[1].id = feed.articles[1].id_for("old", IdStrategy::Guid);
+        let hashed_id = feed.articles[1].id.clone();
+        let articles = HashMap::from([(
+            hashed_id.clone(),
+            CacheEntry::new(Arc::new(feed.articles[1].clone()), Duration::from_secs(3600)),
+        )]);
+        let feeds = HashMap::from([("old".to_string(), CacheEntry::new(feed, Duration::from_secs(3600)))]);
+        let validators = CacheValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+        cache.save(&feeds, &articles, &HashMap::from([("old".to_string(), validators.clone())])).unwrap();
+
+        let renamed = cache.rename_feed("old", "new", IdStrategy::Guid).unwrap();
+        assert_eq!(renamed.len(), 1);
+        assert_eq!(renamed[0].0, hashed_id);
+        assert!(renamed[0].1.starts_with("new:"));
+
+        let loaded = cache.load().unwrap().unwrap();
+        assert!(!loaded.feeds.contains_key("old"));
+        let entry = &loaded.feeds["new"];
+        assert_eq!(entry.data.name, "new");
+        assert_eq!(entry.data.articles[0].id, "guid-article 0");
+        assert_eq!(loaded.articles[&renamed[0].1].data.id, renamed[0].1);
+        assert_eq!(loaded.validators["new"], validators);
+
+        // The old URL's validators mean nothing to the new one
+        assert!(cache.set_feed_url("new", "https://example.com/moved.rss").unwrap());
+        assert!(!cache.set_feed_url("old", "https://example.com/moved.rss").unwrap());
+        let loaded = cache.load().unwrap().unwrap();
+        assert_eq!(loaded.feeds["new"].data.url, "https://example.com/moved.rss");
+        assert!(!loaded.validators.contains_key("new"));
+    }
+
+    #[test]
+    fn test_set_read_flags() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let cache = PersistentCache::new(config).unwrap();
+        let mut feed = create_test_feed("news", 2);
+        feed.articles[0].read = true;
+        let id = feed.articles[0].id.clone();
+        let feeds = HashMap::from([("news".to_string(), CacheEntry::new(feed, Duration::from_secs(3600)))]);
+        cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+
+        let ids = HashSet::from([id.as_str()]);
+        assert_eq!(cache.set_read_flags("news", &ids, false).unwrap(), 1);
+        assert!(!cache.load().unwrap().unwrap().feeds["news"].data.articles[0].read);
+        assert_eq!(cache.set_read_flags("news", &ids, false).unwrap(), 0);
+        assert_eq!(cache.set_read_flags("news", &ids, true).unwrap(), 1);
+        assert!(cache.load().unwrap().unwrap().feeds["news"].data.articles[0].read);
+        assert_eq!(cache.set_read_flags("gone", &ids, true).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_cache_expiration() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        let clock = MockClock::default();
+        let cache = PersistentCache::with_clock(config, clock.shared()).unwrap();
+
         // Create test data with short TTL
         let mut feeds = HashMap::new();
         let test_feed = create_test_feed("tech-news", 1);
-        let feed_entry = CacheEntry::new(test_feed, Duration::from_secs(1));
+        let feed_entry = CacheEntry::new_at(test_feed, Duration::from_secs(1), clock.now());
         feeds.insert("tech-news".to_string(), feed_entry);
 
         // Save cache
-        cache.save(&feeds, &HashMap::new()).unwrap();
+        cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+        assert_eq!(cache.load().unwrap().unwrap().feeds.len(), 1);
 
-        // Sleep to ensure expiration
-        std::thread::sleep(Duration::from_secs(2));
+        clock.advance(Duration::from_secs(2));
 
-        // Load cache - should be empty due to expiration
-        let loaded_data = cache.load().unwrap();
-        assert!(loaded_data.is_none() || loaded_data.unwrap().feeds.is_empty());
+        // Load cache - entry TTL has passed
+        let loaded_data = cache.load().unwrap().unwrap();
+        assert!(loaded_data.feeds.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_stats_and_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let clock = MockClock::default();
+        let cache = PersistentCache::with_clock(config, clock.shared()).unwrap();
+        assert_eq!(cache.stats().unwrap(), PersistentCacheStats::default());
+
+        let feeds = HashMap::from([
+            ("a".to_string(), CacheEntry::new_at(create_test_feed("a", 2), Duration::from_secs(1), clock.now())),
+            ("b".to_string(), CacheEntry::new_at(create_test_feed("b", 1), Duration::from_secs(3600), clock.now())),
+        ]);
+        cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+        fs::write(temp_dir.path().join("feeds_cache.tmp"), "partial").unwrap();
+        clock.advance(Duration::from_secs(2));
+
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.feeds, stats.articles, stats.expired), (2, 0, 1));
+        assert!(stats.file_bytes > 0);
+        assert!(stats.saved_at.is_some());
+
+        assert_eq!(cache.clear().unwrap(), stats.file_bytes + "partial".len() as u64);
+        assert!(cache.load().unwrap().is_none());
+        assert_eq!(cache.clear().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_cache_file_max_age() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PersistentCacheConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            max_age_days: 7,
+            ..Default::default()
+        };
+
+        let clock = MockClock::default();
+        let cache = PersistentCache::with_clock(config, clock.shared()).unwrap();
+
+        let mut feeds = HashMap::new();
+        let feed_entry = CacheEntry::new_at(create_test_feed("tech-news", 1), Duration::from_secs(30 * 86_400), clock.now());
+        feeds.insert("tech-news".to_string(), feed_entry);
+        cache.save(&feeds, &HashMap::new(), &HashMap::new()).unwrap();
+
+        clock.advance(Duration::from_secs(7 * 86_400));
+        assert!(cache.load().unwrap().is_some());
+
+        // Whole file is discarded once it is older than max_age_days
+        clock.advance(Duration::from_secs(1));
+        assert!(cache.load().unwrap().is_none());
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use parking_lot::RwLock;
+use crate::error::{Error, Result};
+
+/// Set of article IDs the user has marked as read, optionally persisted to a
+/// JSON file so the state survives remounts and cache expiry
+#[derive(Debug, Default)]
+pub struct ReadStateStore {
+    ids: IdSet,
+}
+
+impl ReadStateStore {
+    /// Store that lives only as long as the process
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Open (or start) a store backed by `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self { ids: IdSet::open(path.as_ref(), "read state")? })
+    }
+
+    pub fn is_read(&self, article_id: &str) -> bool {
+        self.ids.contains(article_id)
+    }
+
+    /// Mark an article as read. Returns `false` if it already was.
+    pub fn mark_read(&self, article_id: &str) -> Result<bool> {
+        Ok(self.ids.set_many([article_id], true)? > 0)
+    }
+
+    /// Mark an article as unread. Returns `false` if it was not read.
+    pub fn mark_unread(&self, article_id: &str) -> Result<bool> {
+        Ok(self.ids.set_many([article_id], false)? > 0)
+    }
+
+    /// Set the read flag of several articles, saving once. Returns how many
+    /// actually changed.
+    pub fn set_read_many<'a, I>(&self, article_ids: I, read: bool) -> Result<usize>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.ids.set_many(article_ids, read)
+    }
+
+    /// Carry read flags over to new IDs, e.g. after a feed is renamed.
+    /// Returns how many read articles were moved.
+    pub fn rename_ids(&self, renames: &[(String, String)]) -> Result<usize> {
+        self.ids.rename(renames)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.len() == 0
+    }
+}
+
+/// Set of article IDs the user has starred, kept in a file of its own and
+/// persisted the same way as read state
+#[derive(Debug, Default)]
+pub struct StarredStore {
+    ids: IdSet,
+}
+
+impl StarredStore {
+    /// Store that lives only as long as the process
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Open (or start) a store backed by `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self { ids: IdSet::open(path.as_ref(), "starred")? })
+    }
+
+    pub fn is_starred(&self, article_id: &str) -> bool {
+        self.ids.contains(article_id)
+    }
+
+    /// Star or unstar several articles, saving once. Returns how many
+    /// actually changed.
+    pub fn set_starred_many<'a, I>(&self, article_ids: I, starred: bool) -> Result<usize>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.ids.set_many(article_ids, starred)
+    }
+
+    /// Carry stars over to new IDs, e.g. after a feed is renamed. Returns
+    /// how many starred articles were moved.
+    pub fn rename_ids(&self, renames: &[(String, String)]) -> Result<usize> {
+        self.ids.rename(renames)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.len() == 0
+    }
+}
+
+/// A set of article IDs saved as a sorted JSON array whenever it changes
+#[derive(Debug, Default)]
+struct IdSet {
+    path: Option<PathBuf>,
+    /// What the set records, for error messages
+    what: &'static str,
+    ids: RwLock<HashSet<String>>,
+}
+
+impl IdSet {
+    fn open(path: &Path, what: &'static str) -> Result<Self> {
+        let ids = if path.exists() {
+            let content = fs::read_to_string(path)
+                .map_err(|e| Error::Storage(format!(
+                    "Failed to read {} file '{}': {}", what, path.display(), e
+                )))?;
+            serde_json::from_str(&content).map_err(Error::Serialization)?
+        } else {
+            HashSet::new()
+        };
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            what,
+            ids: RwLock::new(ids),
+        })
+    }
+
+    fn contains(&self, article_id: &str) -> bool {
+        self.ids.read().contains(article_id)
+    }
+
+    fn len(&self) -> usize {
+        self.ids.read().len()
+    }
+
+    /// Add (or remove) several IDs, saving once. Returns how many changed.
+    fn set_many<'a, I>(&self, article_ids: I, present: bool) -> Result<usize>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let changed = {
+            let mut set = self.ids.write();
+            article_ids.into_iter()
+                .filter(|id| if present { set.insert(id.to_string()) } else { set.remove(*id) })
+                .count()
+        };
+        if changed > 0 {
+            self.save()?;
+        }
+        Ok(changed)
+    }
+
+    fn rename(&self, renames: &[(String, String)]) -> Result<usize> {
+        let moved = {
+            let mut set = self.ids.write();
+            renames.iter()
+                .filter(|(old, new)| set.remove(old) && set.insert(new.clone()))
+                .count()
+        };
+        if moved > 0 {
+            self.save()?;
+        }
+        Ok(moved)
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        let json = {
+            let ids = self.ids.read();
+            let mut ids: Vec<&String> = ids.iter().collect();
+            ids.sort();
+            serde_json::to_string_pretty(&ids).map_err(Error::Serialization)?
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write to temporary file first, then rename (atomic operation)
+        let temp_file = path.with_extension("tmp");
+        fs::write(&temp_file, json)
+            .map_err(|e| Error::Storage(format!(
+                "Failed to write {} to '{}': {}", self.what, temp_file.display(), e
+            )))?;
+        fs::rename(&temp_file, path)
+            .map_err(|e| Error::Storage(format!(
+                "Failed to rename {} file '{}': {}", self.what, temp_file.display(), e
+            )))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_state_persists() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("read_state.json");
+
+        let store = ReadStateStore::open(&path).unwrap();
+        assert!(store.mark_read("a").unwrap());
+        assert!(!store.mark_read("a").unwrap());
+        assert!(store.mark_read("b").unwrap());
+        assert!(store.mark_unread("b").unwrap());
+
+        let reopened = ReadStateStore::open(&path).unwrap();
+        assert!(reopened.is_read("a"));
+        assert!(!reopened.is_read("b"));
+        assert_eq!(reopened.len(), 1);
+    }
+
+    #[test]
+    fn test_set_read_many() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("read_state.json");
+
+        let store = ReadStateStore::open(&path).unwrap();
+        store.mark_read("a").unwrap();
+        assert_eq!(store.set_read_many(["a", "b", "c"], true).unwrap(), 2);
+        assert_eq!(store.set_read_many(["c", "d"], false).unwrap(), 1);
+
+        let reopened = ReadStateStore::open(&path).unwrap();
+        assert!(reopened.is_read("a") && reopened.is_read("b"));
+        assert!(!reopened.is_read("c") && !reopened.is_read("d"));
+    }
+
+    #[test]
+    fn test_rename_ids() {
+        let store = ReadStateStore::in_memory();
+        store.mark_read("old:a").unwrap();
+        let renames = [
+            ("old:a".to_string(), "new:a".to_string()),
+            ("old:b".to_string(), "new:b".to_string()),
+        ];
+        assert_eq!(store.rename_ids(&renames).unwrap(), 1);
+        assert!(store.is_read("new:a") && !store.is_read("old:a"));
+        assert!(!store.is_read("new:b"));
+    }
+
+    #[test]
+    fn test_starred_store_persists() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("starred.json");
+
+        let store = StarredStore::open(&path).unwrap();
+        assert_eq!(store.set_starred_many(["a", "b"], true).unwrap(), 2);
+        assert_eq!(store.set_starred_many(["b"], false).unwrap(), 1);
+
+        let reopened = StarredStore::open(&path).unwrap();
+        assert!(reopened.is_starred("a"));
+        assert!(!reopened.is_starred("b"));
+        assert_eq!(reopened.len(), 1);
+    }
+}
@@ -1,35 +1,79 @@
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
-use crate::feed::{Feed, Article};
-use crate::feed::fetcher::FeedFetcher;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::feed::{Feed, FeedResult, FeedStatus, Article, IdStrategy, ParsedFeed, StatusChange};
+use crate::feed::filter::ArticleFilter;
+use crate::feed::fetcher::{CacheValidators, FeedFetcher, FeedLimits, FetchOutcome, NetworkOptions, RetryPolicy};
+use crate::feed::http_cache::HttpCache;
+use crate::feed::notifications::Notifier;
+use crate::feed::rate_limit::RateLimiter;
+use crate::feed::urls::UrlNormalizer;
+use crate::clock::SharedClock;
 use crate::storage::cache::{CacheManager, CacheConfig};
+use crate::storage::backend::open_storage;
 use crate::storage::persistent_cache::PersistentCacheConfig;
+use crate::storage::read_state::{ReadStateStore, StarredStore};
 use crate::storage::traits::{
-    Storage, FeedRepository, ArticleRepository, RepositoryStats, 
-    ArticleQuery, ArticleStats, MemoryStorage, StorageConfig
+    Storage, FeedRepository, ArticleRepository, RefreshOutcome, FeedHealth, RepositoryStats, 
+    ArticleQuery, ArticleStats, CleanupStats, MemoryStorage, StorageConfig, article_size
 };
 use crate::error::{Error, Result};
 
+/// Consecutive failures after which a feed is reported as errored
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// Refresh intervals of failing feeds grow up to this multiple
+const MAX_BACKOFF_FACTOR: u32 = 32;
+
+/// Status changes remembered per feed
+const STATUS_HISTORY_LEN: usize = 20;
+
 /// Combined repository implementation with caching and storage
 #[derive(Clone)]
 pub struct Repository {
     storage: Arc<dyn Storage>,
     cache: CacheManager,
     fetcher: FeedFetcher,
+    feed_fetchers: Arc<parking_lot::RwLock<HashMap<String, FeedFetcher>>>, // feeds with their own network settings
     metrics: Arc<parking_lot::RwLock<RepositoryMetrics>>,
+    id_strategies: Arc<parking_lot::RwLock<HashMap<String, IdStrategy>>>,
+    article_limits: Arc<parking_lot::RwLock<HashMap<String, usize>>>,
+    filters: Arc<parking_lot::RwLock<HashMap<String, Arc<ArticleFilter>>>>,
+    health: Arc<parking_lot::RwLock<HashMap<String, FeedHealth>>>,
+    failure_threshold: u32,
+    raw_feeds: Arc<parking_lot::RwLock<HashMap<String, Arc<[u8]>>>>, // latest body of feeds fetched without an HTTP cache
+    read_state: Arc<ReadStateStore>,
+    starred: Arc<StarredStore>,
+    refresh_results: Arc<parking_lot::RwLock<HashMap<String, FeedResult>>>,
+    feed_icons: Arc<parking_lot::RwLock<HashMap<String, String>>>, // feed name -> icon URL
+    statuses: Arc<parking_lot::RwLock<HashMap<String, StatusRecord>>>,
+    disabled: Arc<parking_lot::RwLock<HashSet<String>>>, // feeds that are not fetched
+    retention: StorageConfig,
+    notifier: Option<Arc<Notifier>>,
+    url_normalizer: Arc<UrlNormalizer>,
+}
+
+/// A feed's current status and its recent lasting ones, oldest first
+#[derive(Debug, Clone, Default)]
+struct StatusRecord {
+    current: FeedStatus,
+    history: VecDeque<StatusChange>,
 }
 
-#[derive(Debug, Default)]
-struct RepositoryMetrics {
-    cache_hits: u64,
-    cache_misses: u64,
-    storage_reads: u64,
-    storage_writes: u64,
-    feed_refreshes: u64,
-    total_response_time_ms: u64,
-    operation_count: u64,
+/// Running totals since the repository was created
+#[derive(Debug, Clone, Default)]
+pub struct RepositoryMetrics {
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub storage_reads: u64,
+    pub storage_writes: u64,
+    pub feed_refreshes: u64,
+    pub total_response_time_ms: u64,
+    pub operation_count: u64,
 }
 
 impl Repository {
@@ -38,7 +82,23 @@ impl Repository {
             storage,
             cache: CacheManager::new(cache_config),
             fetcher: FeedFetcher::new(),
+            feed_fetchers: Arc::new(parking_lot::RwLock::new(HashMap::new())),
             metrics: Arc::new(parking_lot::RwLock::new(RepositoryMetrics::default())),
+            id_strategies: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            article_limits: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            filters: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            health: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            raw_feeds: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            read_state: Arc::new(ReadStateStore::in_memory()),
+            starred: Arc::new(StarredStore::in_memory()),
+            refresh_results: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            feed_icons: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            statuses: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            disabled: Arc::new(parking_lot::RwLock::new(HashSet::new())),
+            retention: StorageConfig::unlimited(),
+            notifier: None,
+            url_normalizer: Arc::new(UrlNormalizer::default()),
         }
     }
 
@@ -51,7 +111,23 @@ impl Repository {
             storage,
             cache,
             fetcher: FeedFetcher::new(),
+            feed_fetchers: Arc::new(parking_lot::RwLock::new(HashMap::new())),
             metrics: Arc::new(parking_lot::RwLock::new(RepositoryMetrics::default())),
+            id_strategies: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            article_limits: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            filters: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            health: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            raw_feeds: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            read_state: Arc::new(ReadStateStore::in_memory()),
+            starred: Arc::new(StarredStore::in_memory()),
+            refresh_results: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            feed_icons: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            statuses: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            disabled: Arc::new(parking_lot::RwLock::new(HashSet::new())),
+            retention: StorageConfig::unlimited(),
+            notifier: None,
+            url_normalizer: Arc::new(UrlNormalizer::default()),
         };
 
         // Enable auto-save for persistent cache
@@ -60,9 +136,427 @@ impl Repository {
         Ok(repo)
     }
 
-    /// Save cache to disk manually
-    pub fn save_cache(&self) -> Result<()> {
-        self.cache.save_to_disk()
+    /// Save cache to disk manually, on a blocking thread
+    pub async fn save_cache(&self) -> Result<()> {
+        self.cache.save_to_disk_async().await
+    }
+
+    /// Set the article ID strategy used when refreshing `feed_name`
+    pub fn set_id_strategy(&self, feed_name: &str, strategy: IdStrategy) {
+        self.id_strategies.write().insert(feed_name.to_string(), strategy);
+    }
+
+    pub fn id_strategy(&self, feed_name: &str) -> IdStrategy {
+        self.id_strategies.read().get(feed_name).copied().unwrap_or_default()
+    }
+
+    /// Keep at most `max_articles` (the newest) when refreshing `feed_name`
+    pub fn set_max_articles(&self, feed_name: &str, max_articles: usize) {
+        self.article_limits.write().insert(feed_name.to_string(), max_articles);
+    }
+
+    pub fn max_articles(&self, feed_name: &str) -> Option<usize> {
+        self.article_limits.read().get(feed_name).copied()
+    }
+
+    /// Run articles fetched for `feed_name` through `filter` before they
+    /// are stored
+    pub fn set_filter(&self, feed_name: &str, filter: ArticleFilter) {
+        let mut filters = self.filters.write();
+        if filter.is_empty() {
+            filters.remove(feed_name);
+        } else {
+            filters.insert(feed_name.to_string(), Arc::new(filter));
+        }
+    }
+
+    fn filter(&self, feed_name: &str) -> Option<Arc<ArticleFilter>> {
+        self.filters.read().get(feed_name).cloned()
+    }
+
+    /// Retry transient fetch failures according to `policy`
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.fetcher = self.fetcher.with_retry_policy(policy);
+        self
+    }
+
+    /// Rewrite article links with `normalizer` before IDs are derived
+    pub fn with_url_normalizer(mut self, normalizer: UrlNormalizer) -> Self {
+        self.url_normalizer = Arc::new(normalizer);
+        self
+    }
+
+    pub fn url_normalizer(&self) -> &UrlNormalizer {
+        &self.url_normalizer
+    }
+
+    /// Clock the repository's timestamps and expiry follow
+    pub fn clock(&self) -> SharedClock {
+        self.cache.clock()
+    }
+
+    /// Reject feed responses and feeds larger than `limits`
+    pub fn with_feed_limits(mut self, limits: FeedLimits) -> Self {
+        self.fetcher = self.fetcher.with_limits(limits);
+        self
+    }
+
+    /// Reach feeds through the proxy and with the certificates in `network`
+    pub fn with_network(mut self, network: NetworkOptions) -> Result<Self> {
+        self.fetcher = self.fetcher.with_network(network)?;
+        Ok(self)
+    }
+
+    /// Throttle feed fetches per host with `limiter`, which feeds with their
+    /// own network settings share
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.fetcher = self.fetcher.with_rate_limiter(limiter);
+        self
+    }
+
+    /// Fetch `feed_name` with its own network settings, or with the shared
+    /// ones again when `network` is `None`
+    pub fn set_network(&self, feed_name: &str, network: Option<NetworkOptions>) -> Result<()> {
+        match network {
+            Some(network) if network != *self.fetcher.network() => {
+                let fetcher = self.fetcher.clone().with_network(network)?;
+                self.feed_fetchers.write().insert(feed_name.to_string(), fetcher);
+            }
+            _ => {
+                self.feed_fetchers.write().remove(feed_name);
+            }
+        }
+        Ok(())
+    }
+
+    fn fetcher_for(&self, feed_name: &str) -> FeedFetcher {
+        self.feed_fetchers.read().get(feed_name).cloned().unwrap_or_else(|| self.fetcher.clone())
+    }
+
+    /// Report a feed as errored only after this many consecutive failures
+    pub fn with_failure_threshold(mut self, threshold: u32) -> Self {
+        self.failure_threshold = threshold.max(1);
+        self
+    }
+
+    /// Enforce the article count, age and size limits of `config` on
+    /// refresh and in [`Repository::prune`]. Per-feed article limits set
+    /// with `set_max_articles` take precedence.
+    pub fn with_retention(mut self, config: StorageConfig) -> Self {
+        self.retention = config;
+        self
+    }
+
+    /// Limits applied by [`Repository::prune`]
+    pub fn retention(&self) -> &StorageConfig {
+        &self.retention
+    }
+
+    /// Drop the articles of `feed_name` that the retention limits no longer
+    /// allow, returning them
+    fn retain_articles(&self, feed_name: &str, articles: &mut Vec<Article>) -> Vec<Article> {
+        let mut retention = self.retention.clone();
+        if let Some(limit) = self.max_articles(feed_name) {
+            retention.max_articles_per_feed = Some(limit);
+        }
+        retention.retain_articles(articles, self.cache.clock().now_utc())
+    }
+
+    /// Forget articles the retention limits removed from their feed
+    async fn discard_articles(&self, removed: &[Article], stats: &mut CleanupStats) -> Result<()> {
+        for article in removed {
+            self.cache.articles.remove(&article.id);
+            self.storage.remove_article(&article.id).await?;
+            stats.articles_removed += 1;
+            stats.bytes_freed += article_size(article);
+        }
+        Ok(())
+    }
+
+    /// Apply the retention limits to every stored feed, then let the storage
+    /// backend clean up after itself. Returns what was removed; the pruned
+    /// feeds are saved to disk.
+    pub async fn prune(&self) -> Result<CleanupStats> {
+        let start = Instant::now();
+        let mut stats = CleanupStats::default();
+
+        let mut names = self.cache.feeds.feed_names();
+        names.extend(self.storage.list_feeds().await?);
+        names.sort();
+        names.dedup();
+        let mut feeds = Vec::new();
+        for name in &names {
+            feeds.extend(self.get_feed_from_cache_or_storage(name).await?);
+        }
+        let mut removed = Vec::new();
+        for feed in &mut feeds {
+            for article in &mut feed.articles {
+                article.starred |= self.starred.is_starred(&article.id);
+            }
+            removed.extend(self.retain_articles(&feed.name, &mut feed.articles));
+        }
+        removed.extend(self.retention.trim_to_size(feeds.iter_mut()));
+
+        if !removed.is_empty() {
+            self.discard_articles(&removed, &mut stats).await?;
+            for feed in feeds {
+                self.store_feed_in_cache_and_storage(feed).await?;
+            }
+            self.save_cache().await?;
+        }
+
+        stats.merge(&self.storage.cleanup().await?);
+        stats.duration_ms = start.elapsed().as_millis() as u64;
+        tracing::info!("Pruned {} articles ({} bytes)", stats.articles_removed, stats.bytes_freed);
+        Ok(stats)
+    }
+
+    pub fn feed_health(&self, feed_name: &str) -> FeedHealth {
+        self.health.read().get(feed_name).cloned().unwrap_or_default()
+    }
+
+    /// Delay before the next scheduled refresh: `base`, doubled for every
+    /// consecutive failure (up to a cap) so flapping feeds are polled less
+    pub fn refresh_delay(&self, feed_name: &str, base: Duration) -> Duration {
+        let failures = self.feed_health(feed_name).consecutive_failures.min(MAX_BACKOFF_FACTOR.ilog2());
+        base * 2u32.pow(failures)
+    }
+
+    /// Current status of a feed, `Updating` while a fetch runs. `None`
+    /// until the feed is first fetched, enabled or disabled.
+    pub fn feed_status(&self, feed_name: &str) -> Option<FeedStatus> {
+        self.statuses.read().get(feed_name).map(|record| record.current.clone())
+    }
+
+    /// The feed's recent status changes, oldest first
+    pub fn status_history(&self, feed_name: &str) -> Vec<StatusChange> {
+        self.statuses.read().get(feed_name)
+            .map(|record| record.history.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Move a feed to `status`. Lasting statuses are added to its history
+    /// when they differ from the last one there.
+    fn transition(&self, feed_name: &str, status: FeedStatus) {
+        let mut statuses = self.statuses.write();
+        let record = statuses.entry(feed_name.to_string()).or_default();
+        record.current = status.clone();
+        if status == FeedStatus::Updating || record.history.back().is_some_and(|change| change.status == status) {
+            return;
+        }
+        tracing::debug!("Feed {} is now {:?}", feed_name, status);
+        if record.history.len() == STATUS_HISTORY_LEN {
+            record.history.pop_front();
+        }
+        record.history.push_back(StatusChange { status, at: self.cache.clock().now_utc() });
+    }
+
+    /// Leave `Updating` for the status the feed had before the fetch
+    fn restore_status(&self, feed_name: &str) {
+        if let Some(record) = self.statuses.write().get_mut(feed_name) {
+            record.current = record.history.back().map(|change| change.status.clone()).unwrap_or_default();
+        }
+    }
+
+    /// Stop or resume fetching a feed. A disabled feed keeps its cached
+    /// articles; refreshes leave it untouched.
+    pub fn set_enabled(&self, feed_name: &str, enabled: bool) {
+        let changed = if enabled {
+            self.disabled.write().remove(feed_name)
+        } else {
+            self.disabled.write().insert(feed_name.to_string())
+        };
+        if changed {
+            self.transition(feed_name, if enabled { FeedStatus::Active } else { FeedStatus::Disabled });
+        }
+    }
+
+    pub fn is_enabled(&self, feed_name: &str) -> bool {
+        !self.disabled.read().contains(feed_name)
+    }
+
+    async fn record_refresh_success(&self, feed_name: &str) -> Result<()> {
+        self.transition(feed_name, FeedStatus::Active);
+        let recovered = {
+            let mut health = self.health.write();
+            let entry = health.entry(feed_name.to_string()).or_default();
+            let recovered = entry.consecutive_failures >= self.failure_threshold;
+            entry.consecutive_failures = 0;
+            entry.last_success = Some(self.cache.clock().now_utc());
+            recovered
+        };
+        if recovered {
+            self.set_feed_status(feed_name, FeedStatus::Active).await?;
+        }
+        Ok(())
+    }
+
+    async fn record_refresh_failure(&self, feed_name: &str, error: &Error) -> Result<()> {
+        let failures = {
+            let mut health = self.health.write();
+            let entry = health.entry(feed_name.to_string()).or_default();
+            entry.consecutive_failures += 1;
+            entry.last_error = Some(error.to_string());
+            entry.last_failure = Some(self.cache.clock().now_utc());
+            entry.consecutive_failures
+        };
+        if failures >= self.failure_threshold {
+            self.transition(feed_name, FeedStatus::Error(error.to_string()));
+        } else {
+            self.restore_status(feed_name);
+        }
+        if failures == self.failure_threshold {
+            tracing::warn!("Feed {} failed {} times in a row: {}", feed_name, failures, error);
+            self.set_feed_status(feed_name, FeedStatus::Error(error.to_string())).await?;
+        }
+        Ok(())
+    }
+
+    /// Update the status of a stored feed, if there is one
+    async fn set_feed_status(&self, feed_name: &str, status: FeedStatus) -> Result<()> {
+        if let Some(mut feed) = self.get_feed_from_cache_or_storage(feed_name).await? {
+            feed.status = status;
+            self.store_feed_in_cache_and_storage(feed).await?;
+        }
+        Ok(())
+    }
+
+    /// The document most recently downloaded for a feed. Feeds fetched
+    /// through an HTTP response cache are read back from it; only the
+    /// latest body of each other feed is kept in memory.
+    pub fn raw_feed(&self, feed_name: &str, url: &str) -> Option<Arc<[u8]>> {
+        if let Some(raw) = self.raw_feeds.read().get(feed_name) {
+            return Some(Arc::clone(raw));
+        }
+        self.fetcher_for(feed_name).cached_body(url).map(Arc::from)
+    }
+
+    /// Fetch through the fetcher, keeping the feed's health record current
+    async fn fetch_tracked(&self, name: &str, url: &str, validators: &CacheValidators) -> Result<FetchOutcome> {
+        if !self.is_enabled(name) {
+            return Err(Error::InvalidState(format!("Feed {} is disabled", name)));
+        }
+        self.transition(name, FeedStatus::Updating);
+        let start = Instant::now();
+        let fetcher = self.fetcher_for(name);
+        let result = fetcher.fetch_feed_conditional(url, validators).await;
+        self.health.write().entry(name.to_string()).or_default().last_fetch_duration = Some(start.elapsed());
+
+        match result {
+            Ok(outcome) => {
+                if let FetchOutcome::Modified { ref raw, .. } = outcome {
+                    if fetcher.has_http_cache() {
+                        self.raw_feeds.write().remove(name);
+                    } else {
+                        self.raw_feeds.write().insert(name.to_string(), Arc::from(raw.as_slice()));
+                    }
+                }
+                self.record_refresh_success(name).await?;
+                Ok(outcome)
+            }
+            Err(e) => {
+                let e = Error::HttpError(format!("Failed to refresh feed {}: {}", name, e));
+                self.refresh_results.write().insert(name.to_string(), FeedResult {
+                    feed_name: name.to_string(),
+                    success: false,
+                    error: Some(e.to_string()),
+                    articles_added: 0,
+                    articles_updated: 0,
+                    articles_removed: 0,
+                });
+                self.record_refresh_failure(name, &e).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Fetch feeds through an on-disk HTTP response cache
+    pub fn with_http_cache(mut self, cache: HttpCache) -> Self {
+        self.fetcher = self.fetcher.with_http_cache(cache);
+        self
+    }
+
+    /// Announce the articles refreshes add through `notifier`
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    pub fn notifier(&self) -> Option<&Arc<Notifier>> {
+        self.notifier.as_ref()
+    }
+
+    /// Use `store` to remember which articles have been read
+    pub fn with_read_state(mut self, store: Arc<ReadStateStore>) -> Self {
+        self.read_state = store;
+        self
+    }
+
+    pub fn is_article_read(&self, article_id: &str) -> bool {
+        self.read_state.is_read(article_id)
+    }
+
+    /// Mark an article as read, persisting the flag and updating cached copies
+    pub fn mark_article_read(&self, feed_name: &str, article_id: &str) -> Result<()> {
+        self.read_state.mark_read(article_id)?;
+        self.set_cached_read_flag(feed_name, article_id, true)
+    }
+
+    /// Mark an article as unread again
+    pub fn mark_article_unread(&self, feed_name: &str, article_id: &str) -> Result<()> {
+        self.read_state.mark_unread(article_id)?;
+        self.set_cached_read_flag(feed_name, article_id, false)
+    }
+
+    /// Use `store` to remember which articles are starred
+    pub fn with_starred(mut self, store: Arc<StarredStore>) -> Self {
+        self.starred = store;
+        self
+    }
+
+    pub fn is_article_starred(&self, article_id: &str) -> bool {
+        self.starred.is_starred(article_id)
+    }
+
+    /// Star or unstar an article. Returns `false` if it already was.
+    pub fn set_article_starred(&self, feed_name: &str, article_id: &str, starred: bool) -> Result<bool> {
+        let changed = self.starred.set_starred_many([article_id], starred)? > 0;
+        self.update_cached_article(feed_name, article_id, |article| article.starred = starred)?;
+        Ok(changed)
+    }
+
+    fn set_cached_read_flag(&self, feed_name: &str, article_id: &str, read: bool) -> Result<()> {
+        self.update_cached_article(feed_name, article_id, |article| article.read = read)
+    }
+
+    /// Apply `update` to the cached copies of an article
+    fn update_cached_article(&self, feed_name: &str, article_id: &str, update: impl Fn(&mut Article)) -> Result<()> {
+        if let Some(article) = self.cache.articles.get(article_id) {
+            let mut article = (*article).clone();
+            update(&mut article);
+            self.cache.articles.put(article_id.to_string(), Arc::new(article))?;
+        }
+        if let Some(mut feed) = self.cache.feeds.get(feed_name) {
+            if let Some(article) = feed.articles.iter_mut().find(|a| a.id == article_id) {
+                update(article);
+                self.cache.feeds.put(feed_name.to_string(), feed)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// HTTP validators recorded from the last successful fetch of
+    /// `feed_name`, kept with its cache entry across restarts
+    pub fn validators(&self, feed_name: &str) -> CacheValidators {
+        self.cache.validators(feed_name)
+    }
+
+    /// Create repository whose cache expiry follows `clock`
+    pub fn with_clock(storage: Arc<dyn Storage>, cache_config: CacheConfig, clock: SharedClock) -> Self {
+        Self {
+            cache: CacheManager::with_clock(cache_config, clock),
+            ..Self::new(storage, CacheConfig::default())
+        }
     }
 
     pub fn with_memory_storage() -> Self {
@@ -74,6 +568,163 @@ impl Repository {
         Self::new(storage, CacheConfig::default())
     }
 
+    /// Merge freshly fetched articles into the previously stored copy of a
+    /// feed. Known articles keep their state (read flag, cached content) and
+    /// take the fetched fields; an entry repeated within one fetch is kept
+    /// once. Articles that dropped out of the source stay, as long as the
+    /// feed URL is unchanged, until the article limit trims them. Old
+    /// articles are re-keyed under the current strategy and with normalized
+    /// links, so state survives a change of `id_strategy` or of the
+    /// stripped parameters. Updated articles are stamped with `now`.
+    fn merge_articles(name: &str, url: &str, previous: Option<&Feed>, fresh: Vec<Article>,
+                      strategy: IdStrategy, normalizer: &UrlNormalizer, now: DateTime<Utc>) -> (Vec<Article>, FeedResult) {
+        let mut known: HashMap<String, Article> = HashMap::new();
+        let mut known_order = Vec::new();
+        if let Some(previous) = previous {
+            for old in &previous.articles {
+                let mut old = old.clone();
+                let id = current_id(&old, &previous.name, strategy, normalizer);
+                old.link = normalizer.normalize(&old.link);
+                if let std::collections::hash_map::Entry::Vacant(entry) = known.entry(id.clone()) {
+                    entry.insert(old);
+                    known_order.push(id);
+                }
+            }
+        }
+
+        let mut result = FeedResult {
+            feed_name: name.to_string(),
+            success: true,
+            error: None,
+            articles_added: 0,
+            articles_updated: 0,
+            articles_removed: 0,
+        };
+        let mut seen = std::collections::HashSet::new();
+        let mut merged = Vec::with_capacity(fresh.len());
+        for article in fresh {
+            if !seen.insert(article.id.clone()) {
+                continue;
+            }
+            match known.remove(&article.id) {
+                Some(mut old) => {
+                    if old.merge_from(article, now) {
+                        result.articles_updated += 1;
+                    }
+                    merged.push(old);
+                }
+                None => {
+                    result.articles_added += 1;
+                    merged.push(article);
+                }
+            }
+        }
+
+        if previous.is_some_and(|feed| feed.url == url) {
+            for id in known_order {
+                if let Some(mut old) = known.remove(&id) {
+                    old.id = id;
+                    merged.push(old);
+                }
+            }
+        }
+        (merged, result)
+    }
+
+    /// Outcome of the most recent refresh of `name`
+    pub fn last_refresh_result(&self, name: &str) -> Option<FeedResult> {
+        self.refresh_results.read().get(name).cloned()
+    }
+
+    /// Publication date of the newest cached article of `name` that was
+    /// read: a hint of how recently the feed was read, if at all
+    pub fn newest_read(&self, name: &str) -> Option<DateTime<Utc>> {
+        let feed = self.cache.feeds.get(name)?;
+        feed.articles.iter()
+            .filter(|article| article.read || self.read_state.is_read(&article.id))
+            .filter_map(|article| article.published)
+            .max()
+    }
+
+    /// Where the icon of `name` was found at its last refresh
+    pub fn feed_icon_url(&self, name: &str) -> Option<String> {
+        self.feed_icons.read().get(name).cloned()
+    }
+
+    /// Convert a freshly fetched feed, carry over article state from
+    /// `previous`, store it and remember its HTTP validators
+    async fn apply_refresh(&self, name: &str, url: &str, parsed_feed: ParsedFeed,
+                           validators: CacheValidators, previous: Option<Feed>) -> Result<(Feed, FeedResult)> {
+        // Convert to Feed object
+        let strategy = self.id_strategy(name);
+        let filter = self.filter(name);
+        if let Some(icon) = icon_url(&parsed_feed, url) {
+            self.feed_icons.write().insert(name.to_string(), icon);
+        }
+        let mut fresh: Vec<Article> = parsed_feed.articles.into_iter()
+            .map(|mut a| {
+                a.link = self.url_normalizer.normalize(&a.link);
+                Article::with_id_strategy(a, name, strategy)
+            })
+            .collect();
+        if let Some(filter) = &filter {
+            fresh = filter.apply(fresh);
+        }
+        let (articles, mut result) = Self::merge_articles(
+            name, url, previous.as_ref(), fresh, strategy, &self.url_normalizer, self.cache.clock().now_utc(),
+        );
+        
+        let mut feed = Feed {
+            name: name.to_string(),
+            url: url.to_string(),
+            title: Some(parsed_feed.title),
+            description: parsed_feed.description,
+            last_updated: parsed_feed.last_build_date,
+            articles,
+            status: crate::feed::FeedStatus::Active,
+        };
+        
+        for article in &mut feed.articles {
+            article.read |= self.read_state.is_read(&article.id);
+            article.starred |= self.starred.is_starred(&article.id);
+        }
+        let mut removed = Vec::new();
+        if let Some(filter) = &filter {
+            // Articles kept from earlier refreshes that a rule now drops
+            let (dropped, kept) = feed.articles.into_iter().partition(|article| filter.drops(article));
+            feed.articles = kept;
+            removed = dropped;
+        }
+        removed.extend(self.retain_articles(name, &mut feed.articles));
+        result.articles_removed = removed.len();
+        
+        // A feed's first fetch is not news
+        if let (Some(notifier), Some(previous)) = (&self.notifier, &previous) {
+            if result.articles_added > 0 {
+                let known: HashSet<String> = previous.articles.iter()
+                    .map(|article| current_id(article, &previous.name, strategy, &self.url_normalizer))
+                    .collect();
+                notifier.articles_added(&feed, feed.articles.iter().filter(|article| !known.contains(&article.id)));
+            }
+        }
+        tracing::debug!("Refreshed {}: {} new, {} updated, {} removed articles",
+                        name, result.articles_added, result.articles_updated, result.articles_removed);
+        self.refresh_results.write().insert(name.to_string(), result.clone());
+        
+        // Store the refreshed feed
+        self.discard_articles(&removed, &mut CleanupStats::default()).await?;
+        self.cache.set_validators(name, Some(validators));
+        self.store_feed_in_cache_and_storage(feed.clone()).await?;
+        // The cache's auto-save journals the refreshed feed within seconds
+        
+        Ok((feed, result))
+    }
+
+    /// Snapshot of the counters behind `get_stats`
+    pub fn metrics(&self) -> RepositoryMetrics {
+        self.metrics.read().clone()
+    }
+
     fn record_operation_time(&self, duration: Duration) {
         let mut metrics = self.metrics.write();
         metrics.total_response_time_ms += duration.as_millis() as u64;
@@ -168,6 +819,8 @@ impl FeedRepository for Repository {
         
         // Remove from cache
         self.cache.feeds.remove(name);
+        self.cache.set_validators(name, None);
+        self.raw_feeds.write().remove(name);
         
         // Remove articles from cache
         let article_ids = self.storage.list_articles(name).await?;
@@ -196,39 +849,53 @@ impl FeedRepository for Repository {
         self.get_feed_from_cache_or_storage(name).await
     }
 
-    async fn refresh_feed(&self, name: &str, url: &str) -> Result<Feed> {
+    async fn refresh_feed(&self, name: &str, url: &str) -> Result<(Feed, FeedResult)> {
         let start = Instant::now();
         self.record_feed_refresh();
         
         // Fetch fresh feed data
-        let parsed_feed = self.fetcher.fetch_feed(url).await
-            .map_err(|e| Error::HttpError(format!("Failed to refresh feed {}: {}", name, e)))?;
-        
-        // Convert to Feed object
-        let feed = Feed {
-            name: name.to_string(),
-            url: url.to_string(),
-            title: Some(parsed_feed.title),
-            description: parsed_feed.description,
-            last_updated: parsed_feed.last_build_date,
-            articles: parsed_feed.articles.into_iter()
-                .map(|a| Article::new(a, name))
-                .collect(),
-            status: crate::feed::FeedStatus::Active,
+        let outcome = self.fetch_tracked(name, url, &CacheValidators::default()).await?;
+        let FetchOutcome::Modified { feed: parsed_feed, validators, .. } = outcome else {
+            return Err(Error::HttpError(format!("Failed to refresh feed {}: unexpected 304", name)));
         };
         
-        // Store the refreshed feed
-        self.store_feed_in_cache_and_storage(feed.clone()).await?;
+        let previous = self.get_feed_from_cache_or_storage(name).await?;
+        let refreshed = self.apply_refresh(name, url, parsed_feed, validators, previous).await?;
         
-        // Save to disk immediately after refresh
-        if let Err(e) = self.save_cache() {
-            tracing::warn!("Failed to save cache after feed refresh: {}", e);
-        } else {
-            tracing::debug!("Cache saved to disk after refreshing feed: {}", name);
+        self.record_operation_time(start.elapsed());
+        Ok(refreshed)
+    }
+
+    async fn refresh_feed_if_modified(&self, name: &str, url: &str) -> Result<RefreshOutcome> {
+        if !self.is_enabled(name) {
+            tracing::debug!("Feed {} is disabled, keeping current content", name);
+            return Ok(RefreshOutcome::NotModified);
         }
+        let start = Instant::now();
+        self.record_feed_refresh();
+        
+        // Only trust validators if we still hold the content they describe
+        let previous = self.get_feed_from_cache_or_storage(name).await?;
+        let validators = match previous {
+            Some(ref feed) if feed.url == url => self.validators(name),
+            _ => CacheValidators::default(),
+        };
+        
+        let outcome = self.fetch_tracked(name, url, &validators).await?;
+        
+        let result = match outcome {
+            FetchOutcome::NotModified => {
+                tracing::debug!("Feed {} not modified since last fetch", name);
+                RefreshOutcome::NotModified
+            }
+            FetchOutcome::Modified { feed, validators, .. } => {
+                let (feed, result) = self.apply_refresh(name, url, feed, validators, previous).await?;
+                RefreshOutcome::Updated(feed, result)
+            }
+        };
         
         self.record_operation_time(start.elapsed());
-        Ok(feed)
+        Ok(result)
     }
 
     /// Load feed with cache-first strategy: return cached content immediately,
@@ -251,13 +918,13 @@ impl FeedRepository for Repository {
     }
 
     /// Refresh feed in background and update cache/storage
-    async fn refresh_feed_background(&self, name: &str, url: &str) -> Result<Option<Feed>> {
+    async fn refresh_feed_background(&self, name: &str, url: &str) -> Result<Option<RefreshOutcome>> {
         let start = Instant::now();
         
-        match self.refresh_feed(name, url).await {
-            Ok(feed) => {
+        match self.refresh_feed_if_modified(name, url).await {
+            Ok(outcome) => {
                 self.record_operation_time(start.elapsed());
-                Ok(Some(feed))
+                Ok(Some(outcome))
             }
             Err(e) => {
                 // Log error but don't fail - cached content is still valid
@@ -344,11 +1011,9 @@ impl ArticleRepository for Repository {
             let _ = self.cache.articles.put(article.id.clone(), Arc::new(article.clone()));
         }
         
-        // Store all articles
-        for article in &articles {
-            self.record_storage_write();
-            self.storage.store_article(feed_name, article).await?;
-        }
+        // Store all articles in one batch
+        self.record_storage_write();
+        self.storage.store_articles(feed_name, &articles).await?;
         
         self.record_operation_time(start.elapsed());
         Ok(())
@@ -412,6 +1077,18 @@ impl ArticleRepository for Repository {
                     }
                 }
                 
+                if let Some(text) = &query.text {
+                    let haystack = [Some(&article.title), article.description.as_ref(), article.content.as_ref()]
+                        .into_iter()
+                        .flatten()
+                        .map(|field| field.to_lowercase())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    if !text.split_whitespace().all(|term| haystack.contains(&term.to_lowercase())) {
+                        matches = false;
+                    }
+                }
+                
                 if let Some(author_filter) = &query.author {
                     if article.author.as_ref().map_or(true, |a| a != author_filter) {
                         matches = false;
@@ -540,6 +1217,28 @@ impl ArticleRepository for Repository {
     }
 }
 
+/// The ID a fetch would give a stored article now: keyed by `strategy` on
+/// its link as `normalizer` rewrites it today
+fn current_id(article: &Article, feed_name: &str, strategy: IdStrategy, normalizer: &UrlNormalizer) -> String {
+    let link = normalizer.normalize(&article.link);
+    if link == article.link {
+        return article.id_for(feed_name, strategy);
+    }
+    Article { link, ..article.clone() }.id_for(feed_name, strategy)
+}
+
+/// The icon a feed declares, resolved against its site, or the site's
+/// `/favicon.ico` when it declares none
+fn icon_url(feed: &ParsedFeed, feed_url: &str) -> Option<String> {
+    let site = feed.link.as_deref()
+        .and_then(|link| url::Url::parse(link).ok())
+        .or_else(|| url::Url::parse(feed_url).ok())?;
+    if !matches!(site.scheme(), "http" | "https") {
+        return None;
+    }
+    site.join(feed.image.as_deref().unwrap_or("/favicon.ico")).ok().map(String::from)
+}
+
 /// Repository factory for easy creation with different backends
 pub struct RepositoryFactory;
 
@@ -548,23 +1247,25 @@ impl RepositoryFactory {
         Repository::with_memory_storage()
     }
     
-    pub fn with_config(storage_config: StorageConfig, cache_config: CacheConfig) -> Repository {
-        let storage = Arc::new(MemoryStorage::new(storage_config));
-        Repository::new(storage, cache_config)
+    /// Create repository on the backend named by `storage_config.connection_string`
+    pub fn with_config(storage_config: StorageConfig, cache_config: CacheConfig) -> Result<Repository> {
+        let storage = open_storage(&storage_config)?;
+        Ok(Repository::new(storage, cache_config).with_retention(storage_config))
     }
 
     /// Create repository with persistent cache
     pub fn with_persistent_cache(storage_config: StorageConfig, cache_config: CacheConfig,
                                 persistent_config: PersistentCacheConfig) -> Result<Repository> {
-        let storage = Arc::new(MemoryStorage::new(storage_config));
-        Repository::with_persistent_cache(storage, cache_config, persistent_config)
+        let storage = open_storage(&storage_config)?;
+        Ok(Repository::with_persistent_cache(storage, cache_config, persistent_config)?
+            .with_retention(storage_config))
     }
     
     pub async fn create_with_cleanup_task(
         storage_config: StorageConfig,
         cache_config: CacheConfig,
-    ) -> Repository {
-        let repo = Self::with_config(storage_config, cache_config);
+    ) -> Result<Repository> {
+        let repo = Self::with_config(storage_config, cache_config)?;
         
         // Start background cleanup task
         let cache_manager = repo.cache.clone();
@@ -584,7 +1285,7 @@ impl RepositoryFactory {
             }
         });
         
-        repo
+        Ok(repo)
     }
 }
 
@@ -604,6 +1305,7 @@ mod tests {
             published: Some(Utc::now()),
             guid: Some(id.to_string()),
             categories: vec!["test".to_string()],
+            enclosures: Vec::new(),
         };
         Article::new(parsed, feed_name)
     }
@@ -621,6 +1323,425 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_migrate_article_state_across_id_strategies() {
+        let mut previous = create_test_feed("test-feed");
+        previous.articles[0].read = true;
+
+        // Same entry, re-fetched under a different strategy
+        let mut fresh = vec![previous.articles[0].clone()];
+        fresh[0].read = false;
+        fresh[0].id = fresh[0].id_for("test-feed", IdStrategy::Link);
+
+        let (merged, _) = Repository::merge_articles("test-feed", &previous.url, Some(&previous), fresh, IdStrategy::Link, &UrlNormalizer::default(), Utc::now());
+        assert_eq!(merged.len(), 1);
+        assert!(merged[0].read);
+    }
+
+    #[test]
+    fn test_tracking_parameters_keep_article_identity() {
+        let mut previous = create_test_feed("test-feed");
+        previous.articles[0].link = "https://example.com/1?utm_source=rss".to_string();
+        previous.articles[0].read = true;
+
+        // The same story fetched again with different tracking junk
+        let normalizer = UrlNormalizer::default();
+        let mut fresh = previous.articles[0].clone();
+        fresh.link = normalizer.normalize("https://Example.com/1/?utm_campaign=x&fbclid=y");
+        fresh.read = false;
+        fresh.id = fresh.id_for("test-feed", IdStrategy::Link);
+
+        let (merged, result) = Repository::merge_articles("test-feed", &previous.url, Some(&previous), vec![fresh], IdStrategy::Link, &normalizer, Utc::now());
+        assert_eq!(merged.len(), 1);
+        assert_eq!((result.articles_added, result.articles_updated), (0, 0));
+        assert!(merged[0].read);
+        assert_eq!(merged[0].link, "https://example.com/1");
+    }
+
+    #[test]
+    fn test_merge_articles() {
+        let mut previous = create_test_feed("test-feed");
+        previous.articles.push(create_test_article("2", "test-feed"));
+        previous.articles[0].read = true;
+        let url = previous.url.clone();
+
+        let mut edited = create_test_article("1", "test-feed");
+        edited.title = "Edited title".to_string();
+        edited.content = None;
+        let fresh = vec![
+            edited.clone(),
+            create_test_article("3", "test-feed"),
+            edited, // republished under the same GUID
+        ];
+
+        let now = Utc::now() - chrono::Duration::days(3);
+        let (merged, result) = Repository::merge_articles("test-feed", &url, Some(&previous), fresh.clone(), IdStrategy::Guid, &UrlNormalizer::default(), now);
+        assert_eq!((result.articles_added, result.articles_updated), (1, 1));
+        let ids: Vec<&str> = merged.iter().map(|a| a.id.as_str()).collect();
+        // Article 2 dropped out of the source but is kept
+        assert_eq!(ids, ["1", "3", "2"]);
+        assert_eq!(merged[0].title, "Edited title");
+        assert!(merged[0].read);
+        assert_eq!(merged[0].updated, Some(now));
+        assert_eq!(merged[0].content.as_deref(), Some("Test content"));
+
+        // Merging the same content again changes nothing
+        let current = Feed { articles: merged, ..previous.clone() };
+        let (_, result) = Repository::merge_articles("test-feed", &url, Some(&current), fresh.clone(), IdStrategy::Guid, &UrlNormalizer::default(), now);
+        assert_eq!((result.articles_added, result.articles_updated), (0, 0));
+
+        // A new source URL starts over
+        let (merged, _) = Repository::merge_articles("test-feed", "https://other.example/rss", Some(&current), fresh, IdStrategy::Guid, &UrlNormalizer::default(), now);
+        assert_eq!(merged.len(), 2);
+    }
+
+    #[test]
+    fn test_current_id_normalizes_links() {
+        let normalizer = UrlNormalizer::default();
+        let mut stored = create_test_article("1", "f");
+        stored.guid = None;
+        stored.link = "https://example.com/a?utm_source=rss".to_string();
+        let mut fetched = stored.clone();
+        fetched.link = normalizer.normalize(&stored.link);
+
+        // Stored before tracking parameters were stripped, it still matches
+        // what a fetch gives it now, so it is not announced as new
+        let fetched_id = fetched.id_for("f", IdStrategy::Link);
+        assert_ne!(stored.id_for("f", IdStrategy::Link), fetched_id);
+        assert_eq!(current_id(&stored, "f", IdStrategy::Link, &normalizer), fetched_id);
+        assert_eq!(current_id(&fetched, "f", IdStrategy::Link, &normalizer), fetched_id);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_skips_unchanged_feed() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <link>https://example.com</link><description>d</description>
+            <item><title>A</title><link>https://example.com/a</link></item>
+            </channel></rss>"#;
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .and(header("if-none-match", "\"abc\""))
+            .respond_with(ResponseTemplate::new(304))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss).insert_header("etag", "\"abc\""))
+            .mount(&mock_server)
+            .await;
+
+        let repo = RepositoryFactory::memory();
+        let url = format!("{}/feed.xml", mock_server.uri());
+
+        let first = repo.refresh_feed_if_modified("t", &url).await.unwrap();
+        assert!(matches!(first, RefreshOutcome::Updated(ref feed, _) if feed.articles.len() == 1));
+        assert_eq!(repo.validators("t").etag.as_deref(), Some("\"abc\""));
+        assert_eq!(repo.raw_feed("t", &url).as_deref(), Some(rss.as_bytes()));
+
+        let second = repo.refresh_feed_if_modified("t", &url).await.unwrap();
+        assert!(matches!(second, RefreshOutcome::NotModified));
+        assert_eq!(repo.get_feed("t").await.unwrap().unwrap().articles.len(), 1);
+
+        // Without cached content the validators are not sent
+        repo.delete_feed("t").await.unwrap();
+        assert!(repo.raw_feed("t", &url).is_none());
+        let third = repo.refresh_feed_if_modified("t", &url).await.unwrap();
+        assert!(matches!(third, RefreshOutcome::Updated(_, ref result) if result.articles_added == 1));
+
+        // With an HTTP cache the body is read back from disk, not kept around
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cached = RepositoryFactory::memory().with_http_cache(crate::feed::http_cache::HttpCache::new(temp_dir.path()));
+        cached.refresh_feed_if_modified("t", &url).await.unwrap();
+        assert!(cached.raw_feeds.read().is_empty());
+        assert_eq!(cached.raw_feed("t", &url).as_deref(), Some(rss.as_bytes()));
+    }
+
+    #[test]
+    fn test_icon_url() {
+        let feed = |link: Option<&str>, image: Option<&str>| ParsedFeed {
+            title: "T".to_string(),
+            description: None,
+            link: link.map(String::from),
+            image: image.map(String::from),
+            last_build_date: None,
+            articles: Vec::new(),
+        };
+        let url = "https://feeds.example.com/rss.xml";
+        assert_eq!(icon_url(&feed(Some("https://blog.example.com/posts/"), None), url).as_deref(),
+                   Some("https://blog.example.com/favicon.ico"));
+        assert_eq!(icon_url(&feed(None, None), url).as_deref(), Some("https://feeds.example.com/favicon.ico"));
+        assert_eq!(icon_url(&feed(Some("https://blog.example.com/"), Some("img/icon.png")), url).as_deref(),
+                   Some("https://blog.example.com/img/icon.png"));
+        assert_eq!(icon_url(&feed(None, Some("https://cdn.example.com/i.png")), url).as_deref(),
+                   Some("https://cdn.example.com/i.png"));
+        assert_eq!(icon_url(&feed(None, None), "file:///tmp/feed.xml"), None);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_applies_max_articles() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <item><title>Old</title><link>https://example.com/old</link><pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate></item>
+            <item><title>New</title><link>https://example.com/new</link><pubDate>Wed, 03 Jan 2024 00:00:00 GMT</pubDate></item>
+            <item><title>Mid</title><link>https://example.com/mid</link><pubDate>Tue, 02 Jan 2024 00:00:00 GMT</pubDate></item>
+            </channel></rss>"#;
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss))
+            .mount(&mock_server)
+            .await;
+
+        let repo = RepositoryFactory::memory();
+        let url = format!("{}/feed.xml", mock_server.uri());
+        assert_eq!(repo.refresh_feed("all", &url).await.unwrap().0.articles.len(), 3);
+
+        repo.set_max_articles("capped", 2);
+        let (feed, result) = repo.refresh_feed("capped", &url).await.unwrap();
+        let titles: Vec<&str> = feed.articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["New", "Mid"]);
+        assert_eq!((result.articles_added, result.articles_removed), (3, 1));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_applies_filters() {
+        use crate::feed::filter::{FilterAction, FilterRule};
+        use regex::Regex;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <item><title>Sponsored: VPN deal</title><link>https://example.com/ad</link></item>
+            <item><title>Release notes</title><link>https://example.com/release</link></item>
+            </channel></rss>"#;
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss))
+            .mount(&mock_server)
+            .await;
+
+        let repo = RepositoryFactory::memory();
+        let url = format!("{}/feed.xml", mock_server.uri());
+        assert_eq!(repo.refresh_feed("news", &url).await.unwrap().0.articles.len(), 2);
+
+        let rule = |pattern: &str, action| FilterRule {
+            title: Some(Regex::new(pattern).unwrap()), content: None, author: None, tags: None, action,
+        };
+        repo.set_filter("news", ArticleFilter::new(vec![
+            rule("^Sponsored", FilterAction::Drop),
+            rule("(?i)release", FilterAction::Folder("releases".to_string())),
+        ]));
+
+        // Articles stored before the rule was added are dropped too
+        let (feed, result) = repo.refresh_feed("news", &url).await.unwrap();
+        assert_eq!(feed.articles.len(), 1);
+        assert_eq!(result.articles_removed, 1);
+        assert_eq!(repo.last_refresh_result("news"), Some(result));
+        assert_eq!(feed.articles[0].folder.as_deref(), Some("releases"));
+
+        repo.set_filter("news", ArticleFilter::default());
+        let (feed, result) = repo.refresh_feed("news", &url).await.unwrap();
+        assert_eq!(feed.articles.len(), 2);
+        assert_eq!((result.articles_added, result.articles_removed), (1, 0));
+        assert!(feed.articles.iter().all(|article| article.folder.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_starred_articles() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <item><title>A</title><link>https://example.com/a</link></item>
+            </channel></rss>"#;
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = Arc::new(StarredStore::open(temp_dir.path().join("starred.json")).unwrap());
+        let repo = RepositoryFactory::memory().with_starred(Arc::clone(&store));
+        let url = format!("{}/feed.xml", mock_server.uri());
+        let id = repo.refresh_feed("news", &url).await.unwrap().0.articles[0].id.clone();
+
+        assert!(repo.set_article_starred("news", &id, true).unwrap());
+        assert!(!repo.set_article_starred("news", &id, true).unwrap());
+        assert!(repo.get_feed("news").await.unwrap().unwrap().articles[0].starred);
+
+        // Kept across refreshes and in the store's file
+        assert!(repo.refresh_feed("news", &url).await.unwrap().0.articles[0].starred);
+        assert!(StarredStore::open(temp_dir.path().join("starred.json")).unwrap().is_starred(&id));
+
+        repo.set_article_starred("news", &id, false).unwrap();
+        assert!(!repo.is_article_starred(&id));
+    }
+
+    #[tokio::test]
+    async fn test_per_feed_proxy() {
+        use crate::feed::fetcher::ProxyMode;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Hidden</title>
+            <item><title>A</title><link>https://example.com/a</link></item>
+            </channel></rss>"#;
+        let proxy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss))
+            .mount(&proxy)
+            .await;
+
+        let repo = RepositoryFactory::memory();
+        let network = NetworkOptions { proxy: ProxyMode::Url(proxy.uri()), ..NetworkOptions::default() };
+        repo.set_network("hidden", Some(network)).unwrap();
+        let (feed, _) = repo.refresh_feed("hidden", "http://feeds.example/rss").await.unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Hidden"));
+        assert_eq!(proxy.received_requests().await.unwrap().len(), 1);
+
+        // Without the override the feed host is resolved directly, and fails
+        repo.set_network("hidden", None).unwrap();
+        assert!(repo.refresh_feed("hidden", "http://feeds.example/rss").await.is_err());
+        assert_eq!(proxy.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retention_on_refresh_and_prune() {
+        use crate::clock::MockClock;
+        use chrono::TimeZone;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <item><title>Old</title><link>https://example.com/old</link><pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate></item>
+            <item><title>New</title><link>https://example.com/new</link><pubDate>Wed, 03 Jan 2024 00:00:00 GMT</pubDate></item>
+            <item><title>Mid</title><link>https://example.com/mid</link><pubDate>Tue, 02 Jan 2024 00:00:00 GMT</pubDate></item>
+            </channel></rss>"#;
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss))
+            .mount(&mock_server)
+            .await;
+
+        let clock = MockClock::at(chrono::Utc.with_ymd_and_hms(2024, 1, 10, 0, 0, 0).unwrap());
+        let retention = StorageConfig { max_article_age_days: Some(8), ..StorageConfig::unlimited() };
+        let repo = Repository::with_clock(Arc::new(MemoryStorage::new(StorageConfig::unlimited())), CacheConfig::default(), Arc::new(clock.clone()))
+            .with_retention(retention);
+        let url = format!("{}/feed.xml", mock_server.uri());
+        let (feed, result) = repo.refresh_feed("news", &url).await.unwrap();
+        let titles: Vec<&str> = feed.articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["New", "Mid"]);
+        assert_eq!((result.articles_added, result.articles_removed), (3, 1));
+
+        // A day later "Mid" is past the limit too
+        clock.advance(Duration::from_secs(24 * 60 * 60));
+        let stats = repo.prune().await.unwrap();
+        assert_eq!(stats.articles_removed, 1);
+        let feed = repo.get_feed("news").await.unwrap().unwrap();
+        assert_eq!(feed.articles.len(), 1);
+        assert_eq!(feed.articles[0].title, "New");
+        assert_eq!(repo.prune().await.unwrap().articles_removed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_failure_tracking_and_backoff() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <item><title>A</title><link>https://example.com/a</link></item>
+            </channel></rss>"#;
+        let mock_server = MockServer::start().await;
+        let serve = |status: u16| Mock::given(method("GET")).respond_with(ResponseTemplate::new(status).set_body_string(rss));
+        serve(200).mount(&mock_server).await;
+
+        let repo = RepositoryFactory::memory().with_failure_threshold(2);
+        let url = format!("{}/feed.xml", mock_server.uri());
+        let base = Duration::from_secs(60);
+        repo.refresh_feed("t", &url).await.unwrap();
+        assert!(repo.feed_health("t").last_success.is_some());
+
+        mock_server.reset().await;
+        serve(500).mount(&mock_server).await;
+        assert!(repo.refresh_feed_if_modified("t", &url).await.is_err());
+        assert_eq!(repo.feed_health("t").consecutive_failures, 1);
+        assert!(matches!(repo.get_feed("t").await.unwrap().unwrap().status, FeedStatus::Active));
+        assert_eq!(repo.refresh_delay("t", base), base * 2);
+
+        assert!(repo.refresh_feed_if_modified("t", &url).await.is_err());
+        let health = repo.feed_health("t");
+        assert_eq!(health.consecutive_failures, 2);
+        assert!(health.last_error.unwrap().contains("500"));
+        assert!(matches!(repo.get_feed("t").await.unwrap().unwrap().status, FeedStatus::Error(_)));
+        assert_eq!(repo.refresh_delay("t", base), base * 4);
+
+        // Backoff is capped
+        for _ in 0..10 {
+            let _ = repo.refresh_feed_if_modified("t", &url).await;
+        }
+        assert_eq!(repo.refresh_delay("t", base), base * MAX_BACKOFF_FACTOR);
+
+        mock_server.reset().await;
+        serve(200).mount(&mock_server).await;
+        repo.refresh_feed_if_modified("t", &url).await.unwrap();
+        assert_eq!(repo.feed_health("t").consecutive_failures, 0);
+        assert!(matches!(repo.get_feed("t").await.unwrap().unwrap().status, FeedStatus::Active));
+        assert_eq!(repo.refresh_delay("t", base), base);
+
+        // Failures below the threshold and repeated errors leave no trace
+        let history: Vec<FeedStatus> = repo.status_history("t").into_iter().map(|change| change.status).collect();
+        assert!(matches!(history[..], [FeedStatus::Active, FeedStatus::Error(_), FeedStatus::Active]));
+        assert_eq!(repo.feed_status("t"), Some(FeedStatus::Active));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_feed_is_not_fetched() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>T</title>
+            <item><title>A</title><link>https://example.com/a</link></item>
+            </channel></rss>"#;
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(rss))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let repo = RepositoryFactory::memory();
+        let url = format!("{}/feed.xml", mock_server.uri());
+        assert_eq!(repo.feed_status("t"), None);
+        repo.refresh_feed("t", &url).await.unwrap();
+
+        repo.set_enabled("t", false);
+        assert_eq!(repo.feed_status("t"), Some(FeedStatus::Disabled));
+        assert!(matches!(repo.refresh_feed_if_modified("t", &url).await, Ok(RefreshOutcome::NotModified)));
+        assert!(matches!(repo.refresh_feed("t", &url).await, Err(Error::InvalidState(_))));
+        assert_eq!(repo.get_feed("t").await.unwrap().unwrap().articles.len(), 1);
+
+        repo.set_enabled("t", true);
+        repo.set_enabled("t", true);
+        repo.refresh_feed("t", &url).await.unwrap();
+        let history: Vec<FeedStatus> = repo.status_history("t").into_iter().map(|change| change.status).collect();
+        assert_eq!(history, [FeedStatus::Active, FeedStatus::Disabled, FeedStatus::Active]);
+    }
+
+    #[test]
+    fn test_id_strategy_defaults_to_guid() {
+        let repo = RepositoryFactory::memory();
+        assert_eq!(repo.id_strategy("any"), IdStrategy::Guid);
+
+        repo.set_id_strategy("any", IdStrategy::TitleDate);
+        assert_eq!(repo.id_strategy("any"), IdStrategy::TitleDate);
+    }
+
     #[tokio::test]
     async fn test_repository_feed_operations() {
         let repo = RepositoryFactory::memory();
@@ -699,6 +1820,18 @@ mod tests {
         };
         let results = repo.search_articles(&query).await.unwrap();
         assert_eq!(results.len(), 1);
+
+        // Free text: every term must match title, description or content
+        let query = ArticleQuery {
+            text: Some("article DESCRIPTION".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(repo.search_articles(&query).await.unwrap().len(), 1);
+        let query = ArticleQuery {
+            text: Some("article missing".to_string()),
+            ..Default::default()
+        };
+        assert!(repo.search_articles(&query).await.unwrap().is_empty());
     }
 
     #[tokio::test]
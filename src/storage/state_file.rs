@@ -0,0 +1,181 @@
+//! Portable read and star state: a JSON file naming articles by GUID and
+//! link rather than by internal ID, so the state can be backed up, kept
+//! with dotfiles or carried to another machine whose IDs differ.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::feed::urls::UrlNormalizer;
+use crate::feed::Article;
+use crate::storage::{ReadStateStore, StarredStore};
+
+/// Version written to and accepted from state files
+pub const STATE_FILE_VERSION: u32 = 1;
+
+/// Contents of a state file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateFile {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub articles: Vec<ArticleState>,
+}
+
+/// Read and star flags of one article
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleState {
+    /// Feed the article was in when exported; informational only
+    pub feed: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub read: bool,
+    #[serde(default)]
+    pub starred: bool,
+}
+
+/// Flags of a state file applied to known articles
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MatchedState {
+    /// Article ID with its read and starred flags
+    pub articles: Vec<(String, bool, bool)>,
+    /// Entries of the file no article matched
+    pub unmatched: usize,
+}
+
+impl StateFile {
+    /// State of the `(feed name, article)` pairs that are read or starred,
+    /// by their flags or by the stores
+    pub fn collect<'a>(articles: impl IntoIterator<Item = (&'a str, &'a Article)>,
+                       read: &ReadStateStore, starred: &StarredStore) -> Self {
+        let articles = articles.into_iter()
+            .map(|(feed, article)| ArticleState {
+                feed: feed.to_string(),
+                guid: article.guid.clone(),
+                url: (!article.link.is_empty()).then(|| article.link.clone()),
+                read: article.read || read.is_read(&article.id),
+                starred: article.starred || starred.is_starred(&article.id),
+            })
+            .filter(|state| (state.read || state.starred) && (state.guid.is_some() || state.url.is_some()))
+            .collect();
+        Self { version: STATE_FILE_VERSION, exported_at: Utc::now(), articles }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| Error::Storage(format!(
+            "Failed to read state file '{}': {}", path.display(), e
+        )))?;
+        let file: Self = serde_json::from_str(&content).map_err(|e| Error::Invalid(format!(
+            "'{}' is not a state file: {}", path.display(), e
+        )))?;
+        if file.version != STATE_FILE_VERSION {
+            return Err(Error::Invalid(format!(
+                "State file '{}' has version {}; this release reads version {}",
+                path.display(), file.version, STATE_FILE_VERSION
+            )));
+        }
+        Ok(file)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(Error::Serialization)?;
+        fs::write(path.as_ref(), json + "\n").map_err(|e| Error::Storage(format!(
+            "Failed to write state file '{}': {}", path.as_ref().display(), e
+        )))
+    }
+
+    /// The flags for each of `articles` an entry names, by GUID or else by
+    /// link as `normalizer` reduces it, in any feed
+    pub fn match_articles<'a>(&self, articles: impl IntoIterator<Item = &'a Article>, normalizer: &UrlNormalizer) -> MatchedState {
+        let mut by_guid = HashMap::new();
+        let mut by_url = HashMap::new();
+        for (index, entry) in self.articles.iter().enumerate() {
+            if let Some(guid) = &entry.guid {
+                by_guid.entry(guid.as_str()).or_insert(index);
+            }
+            if let Some(url) = entry.url.as_deref().and_then(|url| normalizer.match_key(url)) {
+                by_url.entry(url).or_insert(index);
+            }
+        }
+
+        let mut matched = MatchedState::default();
+        let mut used = HashSet::new();
+        for article in articles {
+            let index = article.guid.as_deref().and_then(|guid| by_guid.get(guid))
+                .or_else(|| normalizer.match_key(&article.link).and_then(|url| by_url.get(&url)));
+            if let Some(&index) = index {
+                let entry = &self.articles[index];
+                matched.articles.push((article.id.clone(), entry.read, entry.starred));
+                used.insert(index);
+            }
+        }
+        matched.unmatched = self.articles.len() - used.len();
+        matched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::{test_article, IdStrategy};
+    use tempfile::TempDir;
+
+    fn article(guid: Option<&str>, link: &str) -> Article {
+        let mut article = test_article(guid.unwrap_or_default(), "Title", "feed");
+        article.link = link.to_string();
+        if guid.is_none() {
+            article.guid = None;
+            article.id = article.id_for("feed", IdStrategy::Guid);
+        }
+        article
+    }
+
+    #[test]
+    fn test_round_trip_and_match() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.json");
+
+        let read = ReadStateStore::in_memory();
+        let starred = StarredStore::in_memory();
+        let by_guid = article(Some("guid-1"), "https://example.com/1");
+        let by_link = article(None, "https://example.com/2");
+        let untouched = article(Some("guid-3"), "https://example.com/3");
+        read.mark_read(&by_guid.id).unwrap();
+        starred.set_starred_many([by_link.id.as_str()], true).unwrap();
+
+        let file = StateFile::collect([("feed", &by_guid), ("feed", &by_link), ("feed", &untouched)], &read, &starred);
+        assert_eq!(file.articles.len(), 2);
+        file.save(&path).unwrap();
+        let loaded = StateFile::load(&path).unwrap();
+        assert_eq!(loaded, file);
+
+        // Another machine: other IDs, a link with tracking junk
+        let mut elsewhere = article(Some("guid-1"), "https://example.com/moved");
+        elsewhere.id = "other:1".to_string();
+        let mut relinked = article(None, "https://www.example.com/2/?utm_source=rss");
+        relinked.id = "other:2".to_string();
+        let normalizer = UrlNormalizer::default();
+        let matched = loaded.match_articles([&elsewhere, &relinked], &normalizer);
+        assert_eq!(matched.articles, vec![
+            ("other:1".to_string(), true, false),
+            ("other:2".to_string(), false, true),
+        ]);
+        assert_eq!(matched.unmatched, 0);
+        assert_eq!(loaded.match_articles([&untouched], &normalizer).unmatched, 2);
+    }
+
+    #[test]
+    fn test_rejects_unknown_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.json");
+        fs::write(&path, r#"{"version": 2, "exported_at": "2024-01-01T00:00:00Z", "articles": []}"#).unwrap();
+        assert!(matches!(StateFile::load(&path), Err(Error::Invalid(_))));
+    }
+}
@@ -1,7 +1,9 @@
 use std::sync::Arc;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-use crate::feed::{Feed, Article};
+use crate::feed::{Feed, FeedResult, Article};
 use crate::error::Result;
 
 /// Storage trait for persisting feeds and articles
@@ -16,6 +18,15 @@ pub trait Storage: Send + Sync {
     /// Store an individual article
     async fn store_article(&self, feed_name: &str, article: &Article) -> Result<()>;
     
+    /// Store several articles of one feed, in as few writes as the backend
+    /// allows
+    async fn store_articles(&self, feed_name: &str, articles: &[Article]) -> Result<()> {
+        for article in articles {
+            self.store_article(feed_name, article).await?;
+        }
+        Ok(())
+    }
+    
     /// Retrieve an article by ID
     async fn get_article(&self, article_id: &str) -> Result<Option<Arc<Article>>>;
     
@@ -96,14 +107,19 @@ pub trait FeedRepository: Send + Sync {
     /// Get feed with its articles
     async fn get_feed_with_articles(&self, name: &str) -> Result<Option<Feed>>;
     
-    /// Refresh feed from source and update storage
-    async fn refresh_feed(&self, name: &str, url: &str) -> Result<Feed>;
+    /// Refresh feed from source and update storage, reporting which
+    /// articles the refresh added, updated and removed
+    async fn refresh_feed(&self, name: &str, url: &str) -> Result<(Feed, FeedResult)>;
     
     /// Load feed with cache-first strategy (returns immediately from cache)
     async fn load_feed_cache_first(&self, name: &str, url: &str) -> Result<Option<Feed>>;
     
+    /// Refresh feed using HTTP validators from the previous fetch; unchanged
+    /// feeds are reported as `NotModified` and leave storage untouched
+    async fn refresh_feed_if_modified(&self, name: &str, url: &str) -> Result<RefreshOutcome>;
+    
     /// Refresh feed in background and update cache/storage
-    async fn refresh_feed_background(&self, name: &str, url: &str) -> Result<Option<Feed>>;
+    async fn refresh_feed_background(&self, name: &str, url: &str) -> Result<Option<RefreshOutcome>>;
     
     /// Get repository statistics
     async fn get_stats(&self) -> Result<RepositoryStats>;
@@ -137,6 +153,28 @@ pub trait ArticleRepository: Send + Sync {
     async fn get_stats(&self) -> Result<ArticleStats>;
 }
 
+/// Result of a conditional feed refresh
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)] // short-lived, moved straight into the mount
+pub enum RefreshOutcome {
+    /// Feed content was fetched and stored
+    Updated(Feed, FeedResult),
+    /// Source answered 304; the cached feed is still current
+    NotModified,
+}
+
+/// Refresh track record of a single feed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedHealth {
+    /// Failed refreshes since the last success
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub last_success: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_failure: Option<chrono::DateTime<chrono::Utc>>,
+    /// How long the most recent fetch took, successful or not
+    pub last_fetch_duration: Option<std::time::Duration>,
+}
+
 /// Storage statistics
 #[derive(Debug, Clone)]
 pub struct StorageStats {
@@ -151,7 +189,7 @@ pub struct StorageStats {
 pub use crate::storage::cache::CacheStats;
 
 /// Cleanup operation statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct CleanupStats {
     pub feeds_removed: usize,
     pub articles_removed: usize,
@@ -159,6 +197,16 @@ pub struct CleanupStats {
     pub duration_ms: u64,
 }
 
+impl CleanupStats {
+    /// Add the results of another cleanup pass
+    pub fn merge(&mut self, other: &CleanupStats) {
+        self.feeds_removed += other.feeds_removed;
+        self.articles_removed += other.articles_removed;
+        self.bytes_freed += other.bytes_freed;
+        self.duration_ms += other.duration_ms;
+    }
+}
+
 /// Health status of storage system
 #[derive(Debug, Clone, PartialEq)]
 pub enum HealthStatus {
@@ -193,6 +241,9 @@ pub struct ArticleQuery {
     pub feed_name: Option<String>,
     pub title_contains: Option<String>,
     pub content_contains: Option<String>,
+    /// Whitespace-separated terms that must all appear somewhere in the
+    /// title, description or content (case-insensitive)
+    pub text: Option<String>,
     pub author: Option<String>,
     pub tags: Vec<String>,
     pub date_from: Option<chrono::DateTime<chrono::Utc>>,
@@ -207,6 +258,7 @@ impl Default for ArticleQuery {
             feed_name: None,
             title_contains: None,
             content_contains: None,
+            text: None,
             author: None,
             tags: Vec::new(),
             date_from: None,
@@ -250,12 +302,111 @@ impl Default for StorageConfig {
             max_storage_size_mb: Some(500),
             cleanup_interval_hours: 24,
             enable_compression: true,
-            connection_string: "sqlite://rss_fuse.db".to_string(),
+            connection_string: "memory://".to_string(),
             connection_pool_size: Some(10),
         }
     }
 }
 
+impl StorageConfig {
+    /// Configuration that keeps every article
+    pub fn unlimited() -> Self {
+        Self {
+            max_articles_per_feed: None,
+            max_article_age_days: None,
+            max_storage_size_mb: None,
+            ..Self::default()
+        }
+    }
+
+    /// Remove the articles of one feed that are older than
+    /// `max_article_age_days` or beyond the newest `max_articles_per_feed`,
+    /// returning them. Articles without any date are never too old.
+    pub fn retain_articles(&self, articles: &mut Vec<Article>, now: DateTime<Utc>) -> Vec<Article> {
+        // Starred articles are kept whatever their age and don't count
+        // towards the limit
+        let mut removed = Vec::new();
+        if let Some(days) = self.max_article_age_days {
+            let cutoff = now - chrono::Duration::days(days.into());
+            let (kept, expired) = std::mem::take(articles).into_iter()
+                .partition(|a| a.starred || article_date(a).map_or(true, |date| date >= cutoff));
+            *articles = kept;
+            removed = expired;
+        }
+        if let Some(limit) = self.max_articles_per_feed {
+            if articles.iter().filter(|a| !a.starred).count() > limit {
+                // Newest first; undated articles sort last
+                articles.sort_by_key(|a| std::cmp::Reverse(a.published));
+                let mut unstarred = 0;
+                let (kept, dropped) = std::mem::take(articles).into_iter().partition(|a| {
+                    unstarred += usize::from(!a.starred);
+                    a.starred || unstarred <= limit
+                });
+                *articles = kept;
+                removed.extend(dropped);
+            }
+        }
+        removed
+    }
+
+    /// Remove the oldest articles across `feeds` until their combined size
+    /// fits `max_storage_size_mb`, returning them. Starred articles stay.
+    pub fn trim_to_size<'a>(&self, feeds: impl IntoIterator<Item = &'a mut Feed>) -> Vec<Article> {
+        let Some(max_bytes) = self.max_storage_size_mb.map(|mb| mb * 1024 * 1024) else {
+            return Vec::new();
+        };
+        let mut feeds: Vec<&mut Feed> = feeds.into_iter().collect();
+        let mut total: u64 = feeds.iter().flat_map(|f| &f.articles).map(article_size).sum();
+        if total <= max_bytes {
+            return Vec::new();
+        }
+
+        // Undated articles go first, as nothing says they are recent. Sorted
+        // once; the total shrinks by each chosen article's size, and the
+        // chosen ones are taken out of each feed in a single pass.
+        let mut by_age: Vec<(Option<DateTime<Utc>>, usize, usize, u64)> = feeds.iter().enumerate()
+            .flat_map(|(index, feed)| feed.articles.iter().enumerate()
+                .filter(|(_, a)| !a.starred)
+                .map(move |(position, a)| (article_date(a), index, position, article_size(a))))
+            .collect();
+        by_age.sort_unstable();
+
+        let mut doomed: Vec<HashSet<usize>> = vec![HashSet::new(); feeds.len()];
+        for (_, index, position, size) in by_age {
+            if total <= max_bytes {
+                break;
+            }
+            total = total.saturating_sub(size);
+            doomed[index].insert(position);
+        }
+
+        let mut removed = Vec::new();
+        for (feed, doomed) in feeds.iter_mut().zip(doomed).filter(|(_, doomed)| !doomed.is_empty()) {
+            let (gone, kept) = std::mem::take(&mut feed.articles).into_iter()
+                .enumerate()
+                .partition::<Vec<_>, _>(|(position, _)| doomed.contains(position));
+            feed.articles = kept.into_iter().map(|(_, article)| article).collect();
+            removed.extend(gone.into_iter().map(|(_, article)| article));
+        }
+        removed
+    }
+}
+
+/// When an article appeared: its publication date, or when it was first
+/// fetched if the feed gave none
+fn article_date(article: &Article) -> Option<DateTime<Utc>> {
+    article.published.or(article.cached_at)
+}
+
+/// Approximate space an article takes up: the size of its text fields
+pub fn article_size(article: &Article) -> u64 {
+    let text = article.title.len()
+        + article.link.len()
+        + article.description.as_ref().map_or(0, String::len)
+        + article.content.as_ref().map_or(0, String::len);
+    text as u64
+}
+
 /// Memory-only storage implementation for testing and development
 pub struct MemoryStorage {
     feeds: Arc<parking_lot::RwLock<std::collections::HashMap<String, Feed>>>,
@@ -281,7 +432,7 @@ impl MemoryStorage {
     }
 }
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use parking_lot;
 
 #[async_trait]
@@ -382,13 +533,26 @@ impl Storage for MemoryStorage {
     }
 
     async fn cleanup(&self) -> Result<CleanupStats> {
-        // Memory storage doesn't need cleanup, but we can provide stats
-        Ok(CleanupStats {
-            feeds_removed: 0,
-            articles_removed: 0,
-            bytes_freed: 0,
-            duration_ms: 0,
-        })
+        let start = std::time::Instant::now();
+        let mut stats = CleanupStats::default();
+        let now = Utc::now();
+
+        let mut feeds = self.feeds.write();
+        let mut removed = Vec::new();
+        for feed in feeds.values_mut() {
+            removed.extend(self.config.retain_articles(&mut feed.articles, now));
+        }
+        removed.extend(self.config.trim_to_size(feeds.values_mut()));
+
+        let mut articles = self.articles.write();
+        for article in &removed {
+            articles.remove(&article.id);
+            stats.bytes_freed += article_size(article);
+        }
+        stats.articles_removed = removed.len();
+
+        stats.duration_ms = start.elapsed().as_millis() as u64;
+        Ok(stats)
     }
 
     async fn health_check(&self) -> Result<HealthStatus> {
@@ -418,6 +582,7 @@ mod tests {
             published: Some(Utc::now()),
             guid: Some(id.to_string()),
             categories: vec!["test".to_string()],
+            enclosures: Vec::new(),
         };
         Article::new(parsed, feed_name)
     }
@@ -435,6 +600,90 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_memory_storage_cleanup_enforces_retention() {
+        let storage = MemoryStorage::new(StorageConfig {
+            max_articles_per_feed: Some(2),
+            max_article_age_days: Some(30),
+            ..StorageConfig::unlimited()
+        });
+        let mut feed = create_test_feed("news");
+        feed.articles = (0..4).map(|i| {
+            let mut article = create_test_article(&i.to_string(), "news");
+            article.published = Some(Utc::now() - chrono::Duration::days(i * 20));
+            article
+        }).collect();
+        storage.store_feed(&feed).await.unwrap();
+
+        // Two articles are too old; of the rest, both fit the count limit
+        let stats = storage.cleanup().await.unwrap();
+        assert_eq!(stats.articles_removed, 2);
+        assert!(stats.bytes_freed > 0);
+        assert_eq!(storage.articles_count(), 2);
+        assert_eq!(storage.get_feed("news").await.unwrap().unwrap().articles.len(), 2);
+        assert_eq!(storage.cleanup().await.unwrap().articles_removed, 0);
+    }
+
+    #[test]
+    fn test_trim_to_size_drops_oldest() {
+        let config = StorageConfig { max_storage_size_mb: Some(1), ..StorageConfig::unlimited() };
+        let mut feeds: Vec<Feed> = ["a", "b"].iter().map(|name| create_test_feed(name)).collect();
+        for (i, feed) in feeds.iter_mut().enumerate() {
+            feed.articles[0].content = Some("x".repeat(600 * 1024));
+            feed.articles[0].published = Some(Utc::now() - chrono::Duration::days(i as i64));
+        }
+
+        let removed = config.trim_to_size(feeds.iter_mut());
+        assert_eq!(removed.len(), 1);
+        assert!(feeds[1].articles.is_empty());
+        assert_eq!(feeds[0].articles.len(), 1);
+        assert!(config.trim_to_size(feeds.iter_mut()).is_empty());
+
+        // Several from one feed go at once, oldest first, and the rest keep
+        // their order
+        let mut feed = create_test_feed("c");
+        feed.articles = (0..5).map(|i| {
+            let mut article = create_test_article(&i.to_string(), "c");
+            article.content = Some("x".repeat(300 * 1024));
+            article.published = Some(Utc::now() - chrono::Duration::days(i));
+            article
+        }).collect();
+        let removed = config.trim_to_size(std::iter::once(&mut feed));
+        let titles = |articles: &[Article]| articles.iter().map(|a| a.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(&removed), ["Test Article 3", "Test Article 4"]);
+        assert_eq!(titles(&feed.articles), ["Test Article 0", "Test Article 1", "Test Article 2"]);
+    }
+
+    #[test]
+    fn test_retention_keeps_starred_articles() {
+        let now = Utc::now();
+        let config = StorageConfig {
+            max_articles_per_feed: Some(1),
+            max_article_age_days: Some(30),
+            ..StorageConfig::unlimited()
+        };
+        let mut articles: Vec<Article> = (0..4).map(|i| {
+            let mut article = create_test_article(&i.to_string(), "news");
+            article.published = Some(now - chrono::Duration::days(i * 20));
+            article.starred = i == 1 || i == 3;
+            article
+        }).collect();
+
+        // The old starred article outlives the age limit, and neither
+        // starred one takes the place of the newest
+        let removed = config.retain_articles(&mut articles, now);
+        let titles = |articles: &[Article]| articles.iter().map(|a| a.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(&removed), ["Test Article 2"]);
+        assert_eq!(titles(&articles), ["Test Article 0", "Test Article 1", "Test Article 3"]);
+
+        let config = StorageConfig { max_storage_size_mb: Some(1), ..StorageConfig::unlimited() };
+        let mut feeds = [create_test_feed("a")];
+        feeds[0].articles[0].content = Some("x".repeat(2 * 1024 * 1024));
+        feeds[0].articles[0].starred = true;
+        assert!(config.trim_to_size(feeds.iter_mut()).is_empty());
+        assert_eq!(feeds[0].articles.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_memory_storage_basic_operations() {
         let storage = MemoryStorage::default();
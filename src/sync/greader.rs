@@ -0,0 +1,374 @@
+//! Client for the Google Reader API as served by FreshRSS, Miniflux, The Old
+//! Reader and others: `ClientLogin` for a token, then JSON under
+//! `/reader/api/0`
+
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::error::{Error, Result};
+
+/// Stream of every item in the account's subscriptions
+pub const READING_LIST: &str = "user/-/state/com.google/reading-list";
+
+/// Tag the reader puts on items that have been read
+pub const READ_TAG: &str = "user/-/state/com.google/read";
+
+/// Tag the reader puts on starred items
+pub const STARRED_TAG: &str = "user/-/state/com.google/starred";
+
+/// Items per `stream/contents` page, the most servers allow
+const PAGE_SIZE: usize = 1000;
+
+/// `edit-tag` calls carry at most this many item IDs each
+const EDIT_BATCH: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct GReaderClient {
+    http: Client,
+    endpoint: String,
+    auth: String,
+}
+
+/// A feed the account is subscribed to
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Subscription {
+    /// `feed/<url>`
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    /// The feed URL; some servers only put it in `id`
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<Category>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Category {
+    pub id: String,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl Subscription {
+    pub fn feed_url(&self) -> &str {
+        self.url.as_deref().unwrap_or_else(|| self.id.strip_prefix("feed/").unwrap_or(&self.id))
+    }
+
+    /// The first folder (label) the feed is filed under
+    pub fn folder(&self) -> Option<&str> {
+        self.categories.iter()
+            .find_map(|category| category.label.as_deref()
+                .or_else(|| category.id.rsplit_once("/label/").map(|(_, label)| label)))
+            .filter(|label| !label.is_empty())
+    }
+}
+
+/// An entry as the reader knows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteItem {
+    /// `tag:google.com,2005:reader/item/<hex>`, used to edit its tags
+    pub id: String,
+    /// Links to the article, canonical first
+    pub links: Vec<String>,
+    pub read: bool,
+    pub starred: bool,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionList {
+    subscriptions: Vec<Subscription>,
+}
+
+#[derive(Deserialize)]
+struct StreamContents {
+    #[serde(default)]
+    items: Vec<StreamItem>,
+    #[serde(default)]
+    continuation: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StreamItem {
+    id: String,
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    canonical: Vec<Link>,
+    #[serde(default)]
+    alternate: Vec<Link>,
+}
+
+#[derive(Deserialize)]
+struct Link {
+    href: String,
+}
+
+impl GReaderClient {
+    /// Log in to the API at `endpoint` (e.g. `https://rss.example.com/api/greader.php`
+    /// for FreshRSS)
+    pub async fn login(http: Client, endpoint: &str, username: &str, password: &str) -> Result<Self> {
+        let endpoint = endpoint.trim_end_matches('/').to_string();
+        let response = http.post(format!("{}/accounts/ClientLogin", endpoint))
+            .form(&[("Email", username), ("Passwd", password)])
+            .send()
+            .await
+            .map_err(|e| Error::HttpError(format!("Sync login failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(Error::PermissionDenied(format!(
+                "Sync login to {} rejected (HTTP {})", endpoint, response.status().as_u16()
+            )));
+        }
+
+        let body = response.text().await
+            .map_err(|e| Error::HttpError(format!("Sync login failed: {}", e)))?;
+        let auth = body.lines()
+            .find_map(|line| line.strip_prefix("Auth="))
+            .ok_or_else(|| Error::HttpError("Sync login response has no Auth token".to_string()))?
+            .trim()
+            .to_string();
+        Ok(Self { http, endpoint, auth })
+    }
+
+    pub async fn subscriptions(&self) -> Result<Vec<Subscription>> {
+        let list: SubscriptionList = self.get_json("subscription/list", &[]).await?;
+        Ok(list.subscriptions)
+    }
+
+    /// The newest `limit` items of `stream` (e.g. [`READING_LIST`])
+    pub async fn items(&self, stream: &str, limit: usize) -> Result<Vec<RemoteItem>> {
+        let mut items = Vec::new();
+        let mut continuation = None;
+        while items.len() < limit {
+            let page_size = (limit - items.len()).min(PAGE_SIZE).to_string();
+            let mut query = vec![("n", page_size)];
+            if let Some(token) = continuation.take() {
+                query.push(("c", token));
+            }
+            let page: StreamContents = self.get_json(&format!("stream/contents/{}", stream), &query).await?;
+            let done = page.items.is_empty();
+            items.extend(page.items.into_iter().map(|item| RemoteItem {
+                read: has_state(&item.categories, "read"),
+                starred: has_state(&item.categories, "starred"),
+                links: item.canonical.into_iter().chain(item.alternate).map(|link| link.href).collect(),
+                id: item.id,
+            }));
+            match page.continuation {
+                Some(token) if !done => continuation = Some(token),
+                _ => break,
+            }
+        }
+        items.truncate(limit);
+        Ok(items)
+    }
+
+    /// Tag the items with `ids` as read
+    pub async fn mark_read(&self, ids: &[String]) -> Result<()> {
+        self.add_tag(ids, READ_TAG).await
+    }
+
+    /// Tag the items with `ids` as starred
+    pub async fn star(&self, ids: &[String]) -> Result<()> {
+        self.add_tag(ids, STARRED_TAG).await
+    }
+
+    async fn add_tag(&self, ids: &[String], tag: &str) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let token = self.edit_token().await?;
+        for batch in ids.chunks(EDIT_BATCH) {
+            let mut form: Vec<(&str, &str)> = batch.iter().map(|id| ("i", id.as_str())).collect();
+            form.push(("a", tag));
+            form.push(("T", &token));
+            let response = self.http.post(self.api_url("edit-tag"))
+                .header("Authorization", self.auth_header())
+                .form(&form)
+                .send()
+                .await
+                .map_err(|e| Error::HttpError(format!("Sync edit-tag failed: {}", e)))?;
+            if !response.status().is_success() {
+                return Err(Error::HttpError(format!("Sync edit-tag failed: HTTP {}", response.status().as_u16())));
+            }
+        }
+        debug!("Tagged {} items {} on {}", ids.len(), tag, self.endpoint);
+        Ok(())
+    }
+
+    /// Short-lived token that write calls must carry
+    async fn edit_token(&self) -> Result<String> {
+        let response = self.http.get(self.api_url("token"))
+            .header("Authorization", self.auth_header())
+            .send()
+            .await
+            .map_err(|e| Error::HttpError(format!("Sync token request failed: {}", e)))?;
+        let token = response.error_for_status()
+            .map_err(|e| Error::HttpError(format!("Sync token request failed: {}", e)))?
+            .text()
+            .await
+            .map_err(|e| Error::HttpError(format!("Sync token request failed: {}", e)))?;
+        Ok(token.trim().to_string())
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str, query: &[(&str, String)]) -> Result<T> {
+        let response = self.http.get(self.api_url(path))
+            .header("Authorization", self.auth_header())
+            .query(&[("output", "json")])
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| Error::HttpError(format!("Sync request {} failed: {}", path, e)))?;
+        if !response.status().is_success() {
+            return Err(Error::HttpError(format!("Sync request {} failed: HTTP {}", path, response.status().as_u16())));
+        }
+        response.json().await
+            .map_err(|e| Error::HttpError(format!("Sync response {} unreadable: {}", path, e)))
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{}/reader/api/0/{}", self.endpoint, path)
+    }
+
+    fn auth_header(&self) -> String {
+        format!("GoogleLogin auth={}", self.auth)
+    }
+}
+
+/// Whether `categories` hold the `user/<id>/state/com.google/<state>` tag,
+/// with the user given as `-` or by number
+fn has_state(categories: &[String], state: &str) -> bool {
+    let suffix = format!("/state/com.google/{}", state);
+    categories.iter().any(|category| category.starts_with("user/") && category.ends_with(&suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_string_contains, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn logged_in(server: &MockServer) -> GReaderClient {
+        Mock::given(method("POST"))
+            .and(path("/accounts/ClientLogin"))
+            .and(body_string_contains("Email=alice"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("SID=x\nLSID=x\nAuth=alice/0123abcd\n"))
+            .mount(server)
+            .await;
+        GReaderClient::login(Client::new(), &format!("{}/", server.uri()), "alice", "secret").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_login_rejected() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("Error=BadAuthentication"))
+            .mount(&server)
+            .await;
+
+        let result = GReaderClient::login(Client::new(), &server.uri(), "alice", "wrong").await;
+        assert!(matches!(result, Err(Error::PermissionDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_and_items() {
+        let server = MockServer::start().await;
+        let client = logged_in(&server).await;
+
+        Mock::given(method("GET"))
+            .and(path("/reader/api/0/subscription/list"))
+            .and(header("Authorization", "GoogleLogin auth=alice/0123abcd"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"subscriptions": [
+                {"id": "feed/https://blog.rust-lang.org/feed.xml", "title": "Rust Blog",
+                 "categories": [{"id": "user/-/label/Tech", "label": "Tech"}]},
+                {"id": "feed/2", "title": "Other", "url": "https://other.example/rss"}
+            ]}"#))
+            .mount(&server)
+            .await;
+
+        let subscriptions = client.subscriptions().await.unwrap();
+        assert_eq!(subscriptions[0].feed_url(), "https://blog.rust-lang.org/feed.xml");
+        assert_eq!(subscriptions[0].folder(), Some("Tech"));
+        assert_eq!(subscriptions[1].feed_url(), "https://other.example/rss");
+        assert_eq!(subscriptions[1].folder(), None);
+
+        let stream = format!("/reader/api/0/stream/contents/{}", READING_LIST);
+        Mock::given(method("GET"))
+            .and(path(stream.as_str()))
+            .and(query_param("c", "page2"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"items": [
+                {"id": "tag:3", "alternate": [{"href": "https://example.com/c"}]}
+            ]}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(stream.as_str()))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"continuation": "page2", "items": [
+                {"id": "tag:1", "categories": ["user/1234/state/com.google/read"],
+                 "canonical": [{"href": "https://example.com/a"}]},
+                {"id": "tag:2", "categories": ["user/-/label/Tech", "user/-/state/com.google/starred"],
+                 "alternate": [{"href": "https://example.com/b"}]}
+            ]}"#))
+            .mount(&server)
+            .await;
+
+        let items = client.items(READING_LIST, 10).await.unwrap();
+        let summary: Vec<(&str, bool)> = items.iter().map(|item| (item.id.as_str(), item.read)).collect();
+        assert_eq!(summary, [("tag:1", true), ("tag:2", false), ("tag:3", false)]);
+        let starred: Vec<bool> = items.iter().map(|item| item.starred).collect();
+        assert_eq!(starred, [false, true, false]);
+        assert_eq!(items[0].links, ["https://example.com/a"]);
+
+        assert_eq!(client.items(READING_LIST, 2).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mark_read() {
+        let server = MockServer::start().await;
+        let client = logged_in(&server).await;
+
+        Mock::given(method("GET"))
+            .and(path("/reader/api/0/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("edit-token\n"))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/reader/api/0/edit-tag"))
+            .and(body_string_contains("T=edit-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let ids: Vec<String> = (0..150).map(|n| format!("tag:{}", n)).collect();
+        client.mark_read(&ids).await.unwrap();
+        client.mark_read(&[]).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let edit = requests.iter().find(|request| request.url.path().ends_with("edit-tag")).unwrap();
+        let body = String::from_utf8_lossy(&edit.body);
+        assert!(body.contains("a=user%2F-%2Fstate%2Fcom.google%2Fread"));
+        assert_eq!(body.matches("i=").count(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_star() {
+        let server = MockServer::start().await;
+        let client = logged_in(&server).await;
+
+        Mock::given(method("GET"))
+            .and(path("/reader/api/0/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("edit-token\n"))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/reader/api/0/edit-tag"))
+            .and(body_string_contains("a=user%2F-%2Fstate%2Fcom.google%2Fstarred"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        client.star(&["tag:1".to_string()]).await.unwrap();
+    }
+}
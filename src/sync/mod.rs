@@ -0,0 +1,272 @@
+//! Mirroring a hosted reader (FreshRSS, Miniflux, The Old Reader) through
+//! the Google Reader API: its subscriptions become feeds, and an article read
+//! or starred on either side ends up read or starred on both. Both are
+//! sticky; marking an article unread or unstarring it again is not synced.
+
+pub mod greader;
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::config::{Config, FeedEntry, NetworkConfig, SyncConfig};
+use crate::error::Result;
+use crate::feed::fetcher::FeedFetcher;
+use crate::feed::urls::UrlNormalizer;
+use crate::feed::Article;
+
+pub use greader::{GReaderClient, RemoteItem, Subscription};
+
+/// An article held locally, as far as syncing is concerned
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalArticle {
+    pub feed: String,
+    pub id: String,
+    pub link: String,
+    pub read: bool,
+    pub starred: bool,
+}
+
+impl LocalArticle {
+    pub fn new(feed: &str, article: &Article, read: bool, starred: bool) -> Self {
+        Self {
+            feed: feed.to_string(),
+            id: article.id.clone(),
+            link: article.link.clone(),
+            read,
+            starred,
+        }
+    }
+}
+
+/// What reconciling read and starred state calls for
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncPlan {
+    /// Articles (feed name, article ID) read on the server but not here
+    pub mark_local: Vec<(String, String)>,
+    /// Server item IDs read here but not there
+    pub mark_remote: Vec<String>,
+    /// Articles (feed name, article ID) starred on the server but not here
+    pub star_local: Vec<(String, String)>,
+    /// Server item IDs starred here but not there
+    pub star_remote: Vec<String>,
+}
+
+/// Outcome of one sync, as reported by `rss-fuse sync`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub feeds_added: Vec<String>,
+    pub marked_read_locally: usize,
+    pub marked_read_remotely: usize,
+    #[serde(default)]
+    pub starred_locally: usize,
+    #[serde(default)]
+    pub starred_remotely: usize,
+}
+
+impl SyncReport {
+    /// Count what `plan` changed on each side
+    pub fn record(&mut self, plan: &SyncPlan) {
+        self.marked_read_locally = plan.mark_local.len();
+        self.marked_read_remotely = plan.mark_remote.len();
+        self.starred_locally = plan.star_local.len();
+        self.starred_remotely = plan.star_remote.len();
+    }
+}
+
+/// Log in with the `[sync]` settings, through the configured proxy
+pub async fn connect(sync: &SyncConfig, network: &NetworkConfig) -> Result<GReaderClient> {
+    let http = FeedFetcher::build_client(&network.options()?)?;
+    GReaderClient::login(http, &sync.endpoint, &sync.username, &sync.password()?).await
+}
+
+/// Add the account's subscriptions missing from the config file at
+/// `config_file`, saving it if any were. Returns the new feed names.
+pub async fn import_subscriptions(client: &GReaderClient, config_file: &Path) -> Result<Vec<String>> {
+    let subscriptions = client.subscriptions().await?;
+    let mut config = Config::load(config_file)?;
+    let added = new_feeds(&config, &subscriptions);
+    if added.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    for (name, entry) in &added {
+        info!("Subscribing to {} as {}", entry.url(), name);
+        config.feeds.insert(name.clone(), entry.clone());
+    }
+    config.validate()?;
+    config.save(config_file)?;
+    Ok(added.into_iter().map(|(name, _)| name).collect())
+}
+
+/// Feeds for subscriptions whose URL the config lacks, named after their
+/// titles and grouped by the folder they are filed under
+pub fn new_feeds(config: &Config, subscriptions: &[Subscription]) -> Vec<(String, FeedEntry)> {
+    let mut known_urls: HashSet<&str> = config.feeds.values().map(FeedEntry::url).collect();
+    let mut taken: HashSet<String> = config.feeds.keys().cloned().collect();
+    let mut added = Vec::new();
+
+    for subscription in subscriptions {
+        let url = subscription.feed_url();
+        let Ok(parsed) = url::Url::parse(url) else {
+            continue;
+        };
+        if !known_urls.insert(url) {
+            continue;
+        }
+
+        let stem = Some(slug(&subscription.title))
+            .filter(|stem| !stem.is_empty())
+            .or_else(|| parsed.host_str().map(slug))
+            .unwrap_or_else(|| "feed".to_string());
+        let base = match subscription.folder().map(slug).filter(|folder| !folder.is_empty()) {
+            Some(folder) => format!("{}/{}", folder, stem),
+            None => stem,
+        };
+        let name = (1..)
+            .map(|n| if n == 1 { base.clone() } else { format!("{}-{}", base, n) })
+            .find(|name| !taken.contains(name) && config.check_new_feed_name(name).is_ok())
+            .expect("some suffix is free");
+
+        taken.insert(name.clone());
+        added.push((name, FeedEntry::Url(url.to_string())));
+    }
+    added
+}
+
+/// Fetch the newest `limit` items from the server, mark the ones read or
+/// starred here the same there, and return the whole plan; the caller
+/// applies `mark_local` and `star_local` to its own state. Links are matched
+/// as `normalizer` reduces them.
+pub async fn sync_state(client: &GReaderClient, limit: usize, local: &[LocalArticle], normalizer: &UrlNormalizer) -> Result<SyncPlan> {
+    let remote = client.items(greader::READING_LIST, limit).await?;
+    let plan = reconcile(local, &remote, normalizer);
+    client.mark_read(&plan.mark_remote).await?;
+    client.star(&plan.star_remote).await?;
+    Ok(plan)
+}
+
+/// Match articles to items by link and work out which side needs marking
+pub fn reconcile(local: &[LocalArticle], remote: &[RemoteItem], normalizer: &UrlNormalizer) -> SyncPlan {
+    let by_link: HashMap<String, &LocalArticle> = local.iter()
+        .filter_map(|article| Some((normalizer.match_key(&article.link)?, article)))
+        .collect();
+
+    let mut plan = SyncPlan::default();
+    for item in remote {
+        let article = item.links.iter()
+            .filter_map(|link| normalizer.match_key(link))
+            .find_map(|key| by_link.get(&key));
+        let Some(article) = article else {
+            continue;
+        };
+        let local_id = || (article.feed.clone(), article.id.clone());
+        match (item.read, article.read) {
+            (true, false) => plan.mark_local.push(local_id()),
+            (false, true) => plan.mark_remote.push(item.id.clone()),
+            _ => {}
+        }
+        match (item.starred, article.starred) {
+            (true, false) => plan.star_local.push(local_id()),
+            (false, true) => plan.star_remote.push(item.id.clone()),
+            _ => {}
+        }
+    }
+    plan
+}
+
+/// Lowercase words joined by `-`, e.g. `This Week in Rust` → `this-week-in-rust`
+fn slug(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::greader::Category;
+
+    fn subscription(title: &str, url: &str, folder: Option<&str>) -> Subscription {
+        Subscription {
+            id: format!("feed/{}", url),
+            title: title.to_string(),
+            url: None,
+            categories: folder.map(|label| Category {
+                id: format!("user/-/label/{}", label),
+                label: None,
+            }).into_iter().collect(),
+        }
+    }
+
+    fn local(id: &str, link: &str, read: bool) -> LocalArticle {
+        LocalArticle { feed: "f".to_string(), id: id.to_string(), link: link.to_string(), read, starred: false }
+    }
+
+    fn remote(id: &str, link: &str, read: bool) -> RemoteItem {
+        RemoteItem { id: id.to_string(), links: vec![link.to_string()], read, starred: false }
+    }
+
+    #[test]
+    fn test_new_feeds() {
+        let mut config = Config::default();
+        config.feeds.insert("rust".to_string(), "https://blog.rust-lang.org/feed.xml".to_string().into());
+
+        let added = new_feeds(&config, &[
+            subscription("Rust Blog", "https://blog.rust-lang.org/feed.xml", None),
+            subscription("This Week in Rust!", "https://this-week-in-rust.org/rss.xml", Some("Tech News")),
+            subscription("Rust", "https://other.example/rss", None),
+            subscription("", "https://untitled.example/atom", None),
+            subscription("Broken", "not a url", None),
+        ]);
+
+        let names: Vec<&str> = added.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["tech-news/this-week-in-rust", "rust-2", "untitled-example"]);
+        assert_eq!(added[1].1.url(), "https://other.example/rss");
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let local = [
+            local("a", "https://example.com/a", true),
+            local("b", "https://example.com/b/", false),
+            local("c", "https://example.com/c", true),
+            local("d", "https://example.com/d", false),
+        ];
+        let remote = [
+            remote("tag:1", "https://example.com/a", false),
+            remote("tag:2", "https://example.com/b", true),
+            remote("tag:3", "https://example.com/c", true),
+            remote("tag:4", "https://example.com/d", false),
+            remote("tag:5", "https://example.com/elsewhere", true),
+        ];
+
+        let plan = reconcile(&local, &remote, &UrlNormalizer::default());
+        assert_eq!(plan.mark_remote, ["tag:1"]);
+        assert_eq!(plan.mark_local, [("f".to_string(), "b".to_string())]);
+        assert!(plan.star_local.is_empty() && plan.star_remote.is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_stars() {
+        let local = [
+            LocalArticle { starred: true, ..local("a", "https://example.com/a", true) },
+            local("b", "https://www.example.com/b?utm_source=rss", true),
+            LocalArticle { starred: true, ..local("c", "https://example.com/c", true) },
+        ];
+        let remote = [
+            remote("tag:1", "https://example.com/a", true),
+            RemoteItem { starred: true, ..remote("tag:2", "http://example.com/b/", true) },
+            RemoteItem { starred: true, ..remote("tag:3", "https://example.com/c", true) },
+        ];
+
+        let plan = reconcile(&local, &remote, &UrlNormalizer::default());
+        assert_eq!(plan.star_remote, ["tag:1"]);
+        assert_eq!(plan.star_local, [("f".to_string(), "b".to_string())]);
+        assert!(plan.mark_local.is_empty() && plan.mark_remote.is_empty());
+    }
+}
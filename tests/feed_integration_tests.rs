@@ -1,4 +1,4 @@
-use rss_fuse::feed::{parser::FeedParser, fetcher::FeedFetcher, Article, ParsedArticle};
+use rss_fuse::feed::{parser::FeedParser, fetcher::FeedFetcher, Article, IdStrategy, ParsedArticle};
 use std::collections::HashMap;
 use std::io::Cursor;
 use std::time::Duration;
@@ -241,6 +241,7 @@ async fn test_article_id_generation_and_deduplication() {
         published: None,
         guid: Some("unique-guid-123".to_string()),
         categories: vec![],
+        enclosures: Vec::new(),
     };
     
     let article1 = Article::new(article_with_guid.clone(), "test-feed");
@@ -266,6 +267,39 @@ async fn test_article_id_generation_and_deduplication() {
     assert_eq!(article2.id, article3.id);
 }
 
+#[test]
+fn test_article_id_strategies() {
+    let parsed = ParsedArticle {
+        title: "Recycled".to_string(),
+        link: "https://example.com/a?utm=1".to_string(),
+        description: Some("Body".to_string()),
+        content: None,
+        author: None,
+        published: Some(chrono::Utc::now()),
+        guid: Some("recycled-guid".to_string()),
+        categories: vec![],
+        enclosures: Vec::new(),
+    };
+
+    let by_guid = Article::with_id_strategy(parsed.clone(), "feed", IdStrategy::Guid);
+    let by_link = Article::with_id_strategy(parsed.clone(), "feed", IdStrategy::Link);
+    let by_title_date = Article::with_id_strategy(parsed.clone(), "feed", IdStrategy::TitleDate);
+    let by_content = Article::with_id_strategy(parsed.clone(), "feed", IdStrategy::ContentHash);
+
+    assert_eq!(by_guid.id, "recycled-guid");
+    assert!(by_link.id.starts_with("feed:"));
+    assert_ne!(by_link.id, by_title_date.id);
+    assert_ne!(by_title_date.id, by_content.id);
+
+    // Link changes on every fetch: content-hash stays stable, link doesn't
+    let relinked = ParsedArticle { link: "https://example.com/a?utm=2".to_string(), ..parsed };
+    assert_eq!(Article::with_id_strategy(relinked.clone(), "feed", IdStrategy::ContentHash).id, by_content.id);
+    assert_ne!(Article::with_id_strategy(relinked, "feed", IdStrategy::Link).id, by_link.id);
+
+    // IDs can be recomputed from a stored article for migration
+    assert_eq!(by_guid.id_for("feed", IdStrategy::Link), by_link.id);
+}
+
 #[tokio::test]
 async fn test_feed_caching_headers() {
     let mock_server = MockServer::start().await;
@@ -95,7 +95,8 @@ async fn test_complete_rss_to_fuse_workflow() {
     // Step 5: Verify feed directories and articles
     for child in &root_children {
         if child.name == "tech-news" {
-            let articles = fuse_ops.filesystem.list_children(child.ino);
+            let articles: Vec<_> = fuse_ops.filesystem.list_children(child.ino)
+                .into_iter().filter(|n| n.is_file()).collect();
             assert_eq!(articles.len(), 3); // Tech news has 3 articles
             
             // Verify article content can be read
@@ -106,7 +107,8 @@ async fn test_complete_rss_to_fuse_workflow() {
                 assert!(content.len() > 100); // Should have substantial content
             }
         } else if child.name == "science-blog" {
-            let articles = fuse_ops.filesystem.list_children(child.ino);
+            let articles: Vec<_> = fuse_ops.filesystem.list_children(child.ino)
+                .into_iter().filter(|n| n.is_file()).collect();
             assert_eq!(articles.len(), 2); // Science blog has 2 articles
             
             // Verify article filenames are properly sanitized
@@ -251,7 +253,8 @@ async fn test_feed_lifecycle_with_fuse_updates() {
     let feed_node = fuse_ops.filesystem.get_node_by_name(1, "dynamic-feed").unwrap();
     assert!(feed_node.is_directory());
     
-    let articles = fuse_ops.filesystem.list_children(feed_node.ino);
+    let articles: Vec<_> = fuse_ops.filesystem.list_children(feed_node.ino)
+        .into_iter().filter(|n| n.is_file()).collect();
     assert_eq!(articles.len(), 3);
     
     // Test reading article content
@@ -303,12 +306,14 @@ article_content = true
     
     // Verify meta directory structure
     let meta_children = fuse_ops.filesystem.list_children(meta_node.ino);
-    assert_eq!(meta_children.len(), 3); // config.toml, logs, cache
+    assert_eq!(meta_children.len(), 5); // config.toml, logs, cache, status.json, status.md
     
     let child_names: Vec<String> = meta_children.iter().map(|n| n.name.clone()).collect();
     assert!(child_names.contains(&"config.toml".to_string()));
     assert!(child_names.contains(&"logs".to_string()));
     assert!(child_names.contains(&"cache".to_string()));
+    assert!(child_names.contains(&"status.json".to_string()));
+    assert!(child_names.contains(&"status.md".to_string()));
     
     println!("✅ Configuration integration test passed!");
 }
@@ -429,7 +434,8 @@ async fn test_memory_efficiency_with_large_feeds() {
     
     // Verify all articles are accessible
     let feed_node = fuse_ops.filesystem.get_node_by_name(1, "large-feed").unwrap();
-    let articles = fuse_ops.filesystem.list_children(feed_node.ino);
+    let articles: Vec<_> = fuse_ops.filesystem.list_children(feed_node.ino)
+        .into_iter().filter(|n| n.is_file()).collect();
     assert_eq!(articles.len(), 100);
     
     // Test random access to articles (filesystem should handle this efficiently)
@@ -444,7 +450,7 @@ async fn test_memory_efficiency_with_large_feeds() {
     
     let final_stats = fuse_ops.get_stats();
     assert_eq!(final_stats.feeds_count, 1);
-    assert_eq!(final_stats.total_inodes, initial_stats.total_inodes + 101); // +1 feed dir + 100 articles
+    assert_eq!(final_stats.total_inodes, initial_stats.total_inodes + 106); // +1 feed dir + 100 articles + .meta with 4 files
     
     println!("✅ Memory efficiency test passed!");
     println!("   Articles processed: 100");
@@ -494,6 +500,7 @@ fn create_test_feed_with_articles(name: &str, count: usize) -> rss_fuse::feed::F
             published: Some(chrono::Utc::now()),
             guid: Some(format!("{}-{}", name, i + 1)),
             categories: vec![name.to_string(), "test".to_string()],
+            enclosures: Vec::new(),
         };
         rss_fuse::feed::Article::new(parsed, name)
     }).collect();